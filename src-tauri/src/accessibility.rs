@@ -0,0 +1,67 @@
+// 辅助功能 (屏幕阅读器)
+// 嵌入的窗口变成主窗口的子窗口后，切换标签不再是切换顶层窗口，讲述人/NVDA 不会朗读切换到了哪个应用：
+// - 网页标签栏用 ARIA 标记为 tablist/tab (名称、选中状态、第几个)，WebView2 把它们转换为 UIA 的 TabItem
+// - 切换标签后对新标签的窗口发送焦点事件 (EVENT_OBJECT_FOCUS)，屏幕阅读器据此朗读窗口名称
+// - 原生标签栏 (native_tabs.rs) 是自绘的子窗口，把它的窗口名称设为当前标签，如 "标签栏: Slack (第 2 个，共 5 个)"，
+//   名称变化时系统会通知屏幕阅读器
+
+#[cfg(windows)]
+use std::sync::Mutex;
+
+#[cfg(windows)]
+use windows::{
+    core::HSTRING,
+    Win32::{
+        Foundation::HWND,
+        UI::Accessibility::NotifyWinEvent,
+        UI::WindowsAndMessaging::{SetWindowTextW, CHILDID_SELF, EVENT_OBJECT_FOCUS, OBJID_CLIENT},
+    },
+};
+
+// 原生标签栏上一次设置的名称，没有变化时不重复设置
+#[cfg(windows)]
+static STRIP_NAME: Mutex<String> = Mutex::new(String::new());
+
+/// 切换到标签后调用，让屏幕阅读器朗读新标签
+pub fn tab_activated(hwnd: isize) {
+    #[cfg(windows)]
+    unsafe {
+        NotifyWinEvent(EVENT_OBJECT_FOCUS, HWND(hwnd as *mut _), OBJID_CLIENT.0, CHILDID_SELF as i32);
+    }
+    #[cfg(not(windows))]
+    let _ = hwnd;
+}
+
+/// 更新原生标签栏窗口的名称 (在标签栏的线程中重绘时调用)
+#[cfg(windows)]
+pub fn name_strip(strip: HWND, tabs: &[(isize, String)], active: isize) {
+    let name = strip_name(tabs, active);
+    let mut last = STRIP_NAME.lock().unwrap();
+    if *last == name {
+        return;
+    }
+    unsafe {
+        let _ = SetWindowTextW(strip, &HSTRING::from(name.as_str()));
+    }
+    *last = name;
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn strip_name(tabs: &[(isize, String)], active: isize) -> String {
+    match tabs.iter().position(|(hwnd, _)| *hwnd == active) {
+        Some(i) => format!("标签栏: {} (第 {} 个，共 {} 个)", tabs[i].1, i + 1, tabs.len()),
+        None => format!("标签栏: 共 {} 个标签", tabs.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_name_describes_active_tab_position() {
+        let tabs = vec![(1, "Slack".to_string()), (2, "记事本".to_string())];
+        assert_eq!(strip_name(&tabs, 2), "标签栏: 记事本 (第 2 个，共 2 个)");
+        assert_eq!(strip_name(&tabs, 9), "标签栏: 共 2 个标签");
+    }
+}
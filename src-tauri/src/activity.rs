@@ -0,0 +1,207 @@
+// 使用记录 (每个嵌入应用用了多长时间)
+// 每隔 SAMPLE_INTERVAL 检查一次：主窗口在前台、最近 AWAY_SECS 内有输入时，当前显示的标签所属的应用 (EXE 路径) 算作在使用。
+// 连续使用同一个应用的时间合并成一段，切换到其他应用、离开 (主窗口不在前台或没有输入) 或一段超过 MAX_SPAN_SECS 时
+// 写入数据库的 activity 表 (见 storage.rs，超过 MAX_SPAN_SECS 时接着开始新的一段)，程序异常退出时最多丢失一段。
+// get_activity_report 按时间范围汇总各应用的使用时间 (包括还没有写入的当前一段)，超过 RETENTION_DAYS 的记录启动时删除
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info, warn};
+
+use crate::clock::now_secs;
+use crate::platform;
+use crate::window_manager::WindowManager;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+// 超过这么长时间没有输入视为离开
+const AWAY_SECS: u64 = 300;
+
+// 一段记录的最长时间
+const MAX_SPAN_SECS: u64 = 300;
+
+const RETENTION_DAYS: u64 = 365;
+
+// 正在进行的一段
+static CURRENT: Mutex<Option<Span>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Span {
+    app: String,
+    started_at: u64,
+    seconds: u64,
+    last_seen: u64,
+}
+
+/// 报告的时间范围 (Unix 秒，包含 from 不包含 to)
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ActivityRange {
+    pub from: u64,
+    pub to: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AppActivity {
+    pub app: String,
+    pub name: String,
+    pub seconds: u64,
+}
+
+/// 开始记录
+pub fn start(app: &AppHandle) {
+    if let Some(storage) = crate::storage::get() {
+        match storage.prune_activity(now_secs().saturating_sub(RETENTION_DAYS * 86400)) {
+            Ok(0) => {}
+            Ok(count) => info!(target: "focus", "已删除 {} 条过期的使用记录", count),
+            Err(e) => warn!(target: "focus", "{}", e),
+        }
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SAMPLE_INTERVAL);
+        // 锁屏和切换用户期间不算使用时间
+        let using = if crate::session_watch::is_active() { current_app(&app) } else { None };
+        let finished = advance(&mut CURRENT.lock().unwrap(), using.as_deref(), now_secs());
+        if let Some(span) = finished {
+            save(&span);
+        }
+    });
+}
+
+/// 各应用在 range 内的使用时间，按时间从多到少排列
+pub fn report(range: ActivityRange) -> Result<Vec<AppActivity>, String> {
+    if range.from >= range.to {
+        return Err("时间范围无效".to_string());
+    }
+    let mut totals = match crate::storage::get() {
+        Some(storage) => storage.activity_totals(range.from, range.to)?,
+        None => Vec::new(),
+    };
+    if let Some(span) = CURRENT.lock().unwrap().as_ref() {
+        if span.seconds > 0 && (range.from..range.to).contains(&span.started_at) {
+            add(&mut totals, &span.app, span.seconds);
+        }
+    }
+    Ok(summarize(totals))
+}
+
+// 当前在使用的应用：主窗口在前台且最近有输入时，当前标签的 EXE 路径
+fn current_app(app: &AppHandle) -> Option<String> {
+    let host = crate::get_main_window_hwnd(app.clone());
+    if host == 0 || platform::current().foreground_window() != host || crate::idle::host_idle_secs() >= AWAY_SECS {
+        return None;
+    }
+    let tab = crate::host_layout::active();
+    app.state::<WindowManager>().list().into_iter().find(|w| w.hwnd == tab)?.exe_path
+}
+
+// 记录一次检查的结果，返回结束的一段 (需要写入数据库)
+fn advance(current: &mut Option<Span>, using: Option<&str>, now: u64) -> Option<Span> {
+    if let (Some(span), Some(app)) = (current.as_mut(), using) {
+        let elapsed = now.saturating_sub(span.last_seen);
+        if span.app == app && elapsed <= SAMPLE_INTERVAL.as_secs() * 2 {
+            if span.seconds < MAX_SPAN_SECS {
+                span.seconds += elapsed;
+                span.last_seen = now;
+                return None;
+            }
+            // 这一段已满，这次检查经过的时间算进接着的新一段
+            let next = Span { app: app.to_string(), started_at: span.last_seen, seconds: elapsed, last_seen: now };
+            return current.replace(next);
+        }
+    }
+    let finished = current.take().filter(|span| span.seconds > 0);
+    *current = using.map(|app| Span { app: app.to_string(), started_at: now, seconds: 0, last_seen: now });
+    finished
+}
+
+fn save(span: &Span) {
+    debug!(target: "focus", "使用记录: {} {} 秒", span.app, span.seconds);
+    let Some(storage) = crate::storage::get() else { return };
+    if let Err(e) = storage.add_activity(&span.app, span.started_at, span.seconds) {
+        warn!(target: "focus", "{}", e);
+    }
+}
+
+fn add(totals: &mut Vec<(String, u64)>, app: &str, seconds: u64) {
+    match totals.iter_mut().find(|(a, _)| a.eq_ignore_ascii_case(app)) {
+        Some(entry) => entry.1 += seconds,
+        None => totals.push((app.to_string(), seconds)),
+    }
+}
+
+// 同一应用 (路径大小写不同) 合并，按时间从多到少排列
+fn summarize(totals: Vec<(String, u64)>) -> Vec<AppActivity> {
+    let mut merged = Vec::new();
+    for (app, seconds) in totals {
+        add(&mut merged, &app, seconds);
+    }
+    let mut report: Vec<AppActivity> = merged
+        .into_iter()
+        .map(|(app, seconds)| {
+            let name = Path::new(&app).file_stem().map_or_else(|| app.clone(), |s| s.to_string_lossy().into_owned());
+            AppActivity { app, name, seconds }
+        })
+        .collect();
+    report.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.name.cmp(&b.name)));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_end_on_switch_and_away() {
+        let mut current = None;
+        assert_eq!(advance(&mut current, Some("code.exe"), 100), None);
+        assert_eq!(advance(&mut current, Some("code.exe"), 105), None);
+        assert_eq!(advance(&mut current, Some("code.exe"), 110), None);
+        let code = advance(&mut current, Some("music.exe"), 115).unwrap();
+        assert_eq!((code.app.as_str(), code.started_at, code.seconds), ("code.exe", 100, 10));
+
+        // 离开后回来重新开始一段，没有累计时间的一段不写入
+        assert_eq!(advance(&mut current, None, 120), None);
+        assert_eq!(current, None);
+        assert_eq!(advance(&mut current, Some("music.exe"), 200), None);
+        assert_eq!(advance(&mut current, None, 205), None);
+
+        // 长时间没有检查 (如休眠) 时不把中间的时间算进去
+        advance(&mut current, Some("code.exe"), 300);
+        advance(&mut current, Some("code.exe"), 305);
+        let span = advance(&mut current, Some("code.exe"), 1000).unwrap();
+        assert_eq!(span.seconds, 5);
+        assert_eq!(current.as_ref().unwrap().started_at, 1000);
+    }
+
+    #[test]
+    fn full_spans_roll_over_without_losing_time() {
+        let mut current = None;
+        for now in (0..=MAX_SPAN_SECS).step_by(5) {
+            assert_eq!(advance(&mut current, Some("code.exe"), now), None);
+        }
+        let full = advance(&mut current, Some("code.exe"), MAX_SPAN_SECS + 5).unwrap();
+        assert_eq!((full.started_at, full.seconds), (0, MAX_SPAN_SECS));
+        let next = current.as_ref().unwrap();
+        assert_eq!((next.started_at, next.seconds, next.last_seen), (MAX_SPAN_SECS, 5, MAX_SPAN_SECS + 5));
+    }
+
+    #[test]
+    fn report_merges_paths_and_sorts_by_time() {
+        let report = summarize(vec![
+            ("C:/Apps/Music.exe".to_string(), 10),
+            ("C:/Apps/code.exe".to_string(), 60),
+            ("c:/apps/music.exe".to_string(), 5),
+        ]);
+        assert_eq!(
+            report,
+            vec![
+                AppActivity { app: "C:/Apps/code.exe".to_string(), name: "code".to_string(), seconds: 60 },
+                AppActivity { app: "C:/Apps/Music.exe".to_string(), name: "Music".to_string(), seconds: 15 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,235 @@
+// 后台代理 (设置 startup.background_agent)
+// 主界面没有运行时，自动嵌入的监视列表 (app_watch.rs) 和窗口规则 (rules.rs) 也要生效，开机后打开的应用同样能被收进来：
+// - 开启后在 HKCU\...\Run 中登记 "WindowHub.exe --agent"，登录时启动；主界面启动时也会补上没有运行的代理
+// - 代理不创建网页和窗口，只按同样的方式轮询新窗口；主界面在运行时 (命名互斥量 UI_MUTEX 存在) 什么也不做
+// - 发现要嵌入的窗口时记录到 %APPDATA%\WindowHub\agent_handoff.json 并启动主界面，
+//   主界面启动后取走这些窗口，按规则和监视列表嵌入 (app_watch::adopt)
+// 关闭设置后代理在下一轮轮询时退出
+
+use std::time::Duration;
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use std::collections::HashSet;
+
+#[cfg(windows)]
+use windows::{
+    core::{w, PCWSTR},
+    Win32::{
+        Foundation::{GetLastError, ERROR_ALREADY_EXISTS},
+        System::Registry::{RegDeleteKeyValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ},
+        System::Threading::{CreateMutexW, OpenMutexW, SYNCHRONIZATION_SYNCHRONIZE},
+    },
+};
+
+/// 以后台代理方式启动的命令行参数
+pub const AGENT_ARG: &str = "--agent";
+
+#[cfg_attr(not(windows), allow(dead_code))]
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[cfg(windows)]
+const UI_MUTEX: PCWSTR = w!("Local\\WindowHub.UI");
+#[cfg(windows)]
+const AGENT_MUTEX: PCWSTR = w!("Local\\WindowHub.Agent");
+#[cfg(windows)]
+const RUN_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+#[cfg(windows)]
+const RUN_VALUE: PCWSTR = w!("WindowHub Agent");
+
+/// 后台代理的主循环 (run() 发现 --agent 参数时调用，不启动 Tauri)
+#[cfg(windows)]
+pub fn run() {
+    crate::settings::load_from_file();
+    crate::rules::load_from_file();
+    crate::app_watch::load_from_file();
+    // 同时只运行一个代理，句柄保持到进程退出
+    if !unsafe { create_mutex(AGENT_MUTEX) } {
+        info!(target: "agent", "后台代理已在运行");
+        return;
+    }
+    info!(target: "agent", "后台代理已启动");
+
+    let platform = crate::platform::current();
+    let mut seen: Option<HashSet<isize>> = None;
+    let mut waiting: Vec<isize> = Vec::new();
+    let mut launched = false;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        // 设置和规则可能在主界面中修改过
+        let _ = crate::settings::reload_from_file();
+        crate::rules::load_from_file();
+        crate::app_watch::load_from_file();
+        if !crate::settings::get().startup.background_agent {
+            info!(target: "agent", "后台代理已关闭，退出");
+            return;
+        }
+        // 主界面自己处理新窗口，退出后重新记录已有的窗口
+        if is_ui_running() {
+            seen = None;
+            waiting.clear();
+            launched = false;
+            continue;
+        }
+
+        let current: Vec<isize> = platform.enumerate().iter().map(|w| w.hwnd).collect();
+        let Some(known) = seen.as_mut() else {
+            seen = Some(current.into_iter().collect());
+            continue;
+        };
+        let captured: Vec<isize> = crate::app_watch::settle(known, &mut waiting, &current)
+            .into_iter()
+            .filter(|hwnd| {
+                let exe_path = platform.process_path(*hwnd).unwrap_or_default();
+                crate::app_watch::is_watched(&exe_path) || crate::rules::wants_embed(platform, *hwnd)
+            })
+            .collect();
+        if captured.is_empty() {
+            continue;
+        }
+        info!(target: "agent", "发现要嵌入的窗口: {:?}", captured);
+        save_handoff(&captured);
+        if !launched {
+            launched = true;
+            launch_ui();
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn run() {
+    warn!(target: "agent", "后台代理仅支持 Windows");
+}
+
+/// 主界面启动时调用：登记主界面正在运行，并按设置登记/取消开机启动代理，需要时启动代理
+pub fn init() {
+    #[cfg(windows)]
+    unsafe {
+        create_mutex(UI_MUTEX);
+    }
+    apply();
+}
+
+/// 按设置登记或取消登录时启动代理 (启动和设置变化时调用)
+#[cfg(windows)]
+pub fn apply() {
+    let enabled = crate::settings::get().startup.background_agent;
+    unsafe { set_run_at_login(enabled) };
+    if enabled && !is_running(AGENT_MUTEX) {
+        spawn_self(&[AGENT_ARG]);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn apply() {}
+
+/// 取走代理记录的窗口 (主界面启动后)，逐个按规则和监视列表嵌入
+pub fn adopt_handoff() {
+    let captured = load_handoff();
+    if captured.is_empty() {
+        return;
+    }
+    if let Some(path) = handoff_path() {
+        let _ = std::fs::remove_file(path);
+    }
+    info!(target: "agent", "接管后台代理发现的 {} 个窗口", captured.len());
+    let platform = crate::platform::current();
+    for hwnd in captured {
+        if platform.is_window(hwnd) {
+            crate::app_watch::adopt(hwnd);
+        }
+    }
+}
+
+// 创建命名互斥量并保持到进程退出，已存在时返回 false
+#[cfg(windows)]
+unsafe fn create_mutex(name: PCWSTR) -> bool {
+    match CreateMutexW(None, false, name) {
+        Ok(_) => GetLastError() != ERROR_ALREADY_EXISTS,
+        Err(e) => {
+            warn!(target: "agent", "创建互斥量失败: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(windows)]
+fn is_running(name: PCWSTR) -> bool {
+    unsafe {
+        match OpenMutexW(SYNCHRONIZATION_SYNCHRONIZE, false, name) {
+            Ok(handle) => {
+                let _ = windows::Win32::Foundation::CloseHandle(handle);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(windows)]
+fn is_ui_running() -> bool {
+    is_running(UI_MUTEX)
+}
+
+#[cfg(windows)]
+unsafe fn set_run_at_login(enabled: bool) {
+    if !enabled {
+        let _ = RegDeleteKeyValueW(HKEY_CURRENT_USER, RUN_KEY, RUN_VALUE);
+        return;
+    }
+    let Ok(exe) = std::env::current_exe() else { return };
+    let portable = if crate::paths::is_portable() { format!(" {}", crate::paths::PORTABLE_ARG) } else { String::new() };
+    let command: Vec<u16> = format!("\"{}\" {}{}", exe.display(), AGENT_ARG, portable).encode_utf16().chain(Some(0)).collect();
+    let result = RegSetKeyValueW(
+        HKEY_CURRENT_USER,
+        RUN_KEY,
+        RUN_VALUE,
+        REG_SZ.0,
+        Some(command.as_ptr() as *const _),
+        (command.len() * 2) as u32,
+    );
+    if result.is_err() {
+        warn!(target: "agent", "登记开机启动失败: {:?}", result);
+    }
+}
+
+#[cfg(windows)]
+fn launch_ui() {
+    info!(target: "agent", "启动主界面");
+    spawn_self(&[]);
+}
+
+#[cfg(windows)]
+fn spawn_self(args: &[&str]) {
+    // 便携模式下子进程使用同一个数据目录
+    let portable = crate::paths::is_portable().then_some(crate::paths::PORTABLE_ARG);
+    let result = std::env::current_exe().and_then(|exe| std::process::Command::new(exe).args(args).args(portable).spawn());
+    if let Err(e) = result {
+        warn!(target: "agent", "启动 WindowHub 失败 {:?}: {}", args, e);
+    }
+}
+
+fn handoff_path() -> Option<std::path::PathBuf> {
+    crate::paths::data_dir().map(|dir| dir.join("agent_handoff.json"))
+}
+
+fn load_handoff() -> Vec<isize> {
+    handoff_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// 追加到已记录的窗口之后 (主界面启动前可能又出现了新窗口)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn save_handoff(captured: &[isize]) {
+    let Some(path) = handoff_path() else { return };
+    let mut all = load_handoff();
+    all.extend(captured.iter().filter(|hwnd| !all.contains(hwnd)).copied().collect::<Vec<_>>());
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&all) {
+        let _ = std::fs::write(path, json);
+    }
+}
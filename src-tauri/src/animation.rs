@@ -0,0 +1,125 @@
+// 嵌入和弹出时的移动动画 (设置 appearance.animate_transitions，默认关闭)
+// 嵌入：窗口从原来在桌面上的位置移动、缩放到它在内容区中的位置；弹出：从内容区中的位置移回原来的位置
+// 在窗口操作线程上 (window_ops.rs) 按帧 SetWindowPos，约 DURATION 完成，先快后慢 (ease-out)；
+// 调用方不等待，动画期间前端调整的布局在结束后由 relayout 重新应用
+// 系统关闭了动画 ("在 Windows 中显示动画"，见 system_prefs.rs) 时不播放
+
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::window_manager::WindowRect;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, POINT, RECT},
+    Graphics::Gdi::ScreenToClient,
+    UI::WindowsAndMessaging::{GetWindowRect, IsWindow, SetWindowPos, SWP_NOACTIVATE, SWP_NOZORDER},
+};
+
+#[cfg_attr(not(windows), allow(dead_code))]
+const DURATION: Duration = Duration::from_millis(200);
+#[cfg_attr(not(windows), allow(dead_code))]
+const FRAME: Duration = Duration::from_millis(15);
+
+// (x, y, width, height)
+type Frame = (i32, i32, i32, i32);
+
+/// 是否播放动画
+pub fn enabled() -> bool {
+    crate::settings::get().appearance.animate_transitions && !crate::system_prefs::get().reduced_motion
+}
+
+/// 嵌入后把窗口从原来的屏幕位置 original 移动到内容区中的 slot (主窗口客户区坐标)
+#[cfg(windows)]
+pub fn embed(app: &AppHandle, host: isize, hwnd: isize, original: WindowRect, slot: Frame) {
+    let mut origin = POINT { x: original.left, y: original.top };
+    unsafe {
+        let _ = ScreenToClient(HWND(host as *mut _), &mut origin);
+    }
+    let from = (origin.x, origin.y, original.width(), original.height());
+    let app = app.clone();
+    crate::window_ops::post("animate_embed", move || {
+        play(hwnd, from, slot);
+        crate::relayout(&app);
+    });
+}
+
+/// 释放后把窗口从弹出前的屏幕位置 from 移回原来的位置 original
+#[cfg(windows)]
+pub fn release(hwnd: isize, from: WindowRect, original: WindowRect) {
+    let from = (from.left, from.top, from.width(), from.height());
+    let to = (original.left, original.top, original.width(), original.height());
+    // 释放时窗口已经恢复到原来的位置，立即放回起点，不等操作线程
+    unsafe {
+        let _ = SetWindowPos(HWND(hwnd as *mut _), None, from.0, from.1, from.2, from.3, SWP_NOZORDER | SWP_NOACTIVATE);
+    }
+    crate::window_ops::post("animate_release", move || play(hwnd, from, to));
+}
+
+/// 窗口的屏幕位置
+#[cfg(windows)]
+pub fn screen_rect(hwnd: isize) -> Option<WindowRect> {
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(HWND(hwnd as *mut _), &mut rect) }.ok()?;
+    Some(rect.into())
+}
+
+#[cfg(not(windows))]
+pub fn embed(_app: &AppHandle, _host: isize, _hwnd: isize, _original: WindowRect, _slot: Frame) {}
+
+#[cfg(not(windows))]
+pub fn release(_hwnd: isize, _from: WindowRect, _original: WindowRect) {}
+
+#[cfg(not(windows))]
+pub fn screen_rect(_hwnd: isize) -> Option<WindowRect> {
+    None
+}
+
+#[cfg(windows)]
+fn play(hwnd: isize, from: Frame, to: Frame) {
+    let window = HWND(hwnd as *mut _);
+    let count = (DURATION.as_millis() / FRAME.as_millis()) as u32;
+    for (x, y, width, height) in frames(from, to, count) {
+        unsafe {
+            if !IsWindow(window).as_bool() {
+                return;
+            }
+            let _ = SetWindowPos(window, None, x, y, width, height, SWP_NOZORDER | SWP_NOACTIVATE);
+        }
+        std::thread::sleep(FRAME);
+    }
+}
+
+// 先快后慢
+#[cfg_attr(not(windows), allow(dead_code))]
+fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+// from 到 to 之间的 count 帧 (最后一帧为 to)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn frames(from: Frame, to: Frame, count: u32) -> Vec<Frame> {
+    let lerp = |a: i32, b: i32, t: f32| a + ((b - a) as f32 * t).round() as i32;
+    (1..=count.max(1))
+        .map(|i| {
+            let t = ease_out(i as f32 / count.max(1) as f32);
+            (lerp(from.0, to.0, t), lerp(from.1, to.1, t), lerp(from.2, to.2, t), lerp(from.3, to.3, t))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_ease_out_and_end_at_target() {
+        let frames = frames((0, 0, 100, 100), (300, 100, 500, 300), 10);
+        assert_eq!(frames.len(), 10);
+        assert_eq!(frames[9], (300, 100, 500, 300));
+        // 先快后慢：前半段走过的距离超过一半
+        assert!(frames[4].0 > 150);
+        assert!(frames.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    }
+}
@@ -0,0 +1,87 @@
+// Win32 调用跟踪 (调试嵌入问题)
+// 开启后 (set_api_trace，或启动时设置环境变量 WINDOWHUB_API_TRACE=1) 嵌入、释放、激活过程中的每个 Win32 调用
+// (函数名、参数、返回值、GetLastError) 记录到环形缓冲区，只保留最近 TRACE_LIMIT 条。
+// get_api_trace(hwnd) 取出某个窗口的调用序列，诊断信息中附带全部记录，反馈某个应用嵌入失败时能看到具体哪一步出错
+// 调用方见 platform/win32.rs 的 traced! 宏
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tracing::info;
+
+const TRACE_LIMIT: usize = 2000;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static CALLS: Mutex<VecDeque<ApiCall>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiCall {
+    pub time: u64, // Unix 毫秒
+    pub hwnd: isize,
+    pub function: &'static str,
+    pub args: String,
+    pub result: String,
+    pub last_error: u32,
+}
+
+/// 按环境变量决定启动时是否开启 (程序启动时调用)
+pub fn init() {
+    if std::env::var("WINDOWHUB_API_TRACE").is_ok_and(|v| v == "1") {
+        set(true);
+    }
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 开启或关闭跟踪，开启时清空之前的记录
+pub fn set(enabled: bool) {
+    if ENABLED.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    if enabled {
+        CALLS.lock().unwrap().clear();
+    }
+    info!(target: "embed", "Win32 调用跟踪: {}", if enabled { "开启" } else { "关闭" });
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn record(hwnd: isize, function: &'static str, args: String, result: String, last_error: u32) {
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    push(&mut CALLS.lock().unwrap(), ApiCall { time, hwnd, function, args, result, last_error });
+}
+
+fn push(calls: &mut VecDeque<ApiCall>, call: ApiCall) {
+    if calls.len() >= TRACE_LIMIT {
+        calls.pop_front();
+    }
+    calls.push_back(call);
+}
+
+/// 记录的调用 (从旧到新)，指定 hwnd 时只返回针对该窗口的
+pub fn calls(hwnd: Option<isize>) -> Vec<ApiCall> {
+    CALLS.lock().unwrap().iter().filter(|c| hwnd.is_none_or(|h| c.hwnd == h)).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_latest_calls() {
+        let mut calls = VecDeque::new();
+        for i in 0..TRACE_LIMIT + 5 {
+            let call = ApiCall { time: i as u64, hwnd: 1, function: "SetParent", args: String::new(), result: String::new(), last_error: 0 };
+            push(&mut calls, call);
+        }
+        assert_eq!(calls.len(), TRACE_LIMIT);
+        assert_eq!(calls.front().unwrap().time, 5);
+    }
+}
@@ -0,0 +1,212 @@
+// 已安装应用的索引 (应用搜索)
+// 扫描开始菜单要遍历数百个快捷方式，漫游配置文件在网络上时需要几秒，每次搜索都扫描会卡住搜索框。
+// 扫描结果连同各目录的修改时间保存在数据库中 (storage.rs)：
+// - 启动时在后台线程读取并检查，有目录的修改时间变化 (增删了快捷方式或子目录) 才重新扫描
+// - enumerate_installed_apps_cached 直接返回内存中的结果
+// - 之后监听开始菜单目录 (notify，Windows 上为 ReadDirectoryChangesW)，安装或卸载程序时重新扫描
+// 扫描结果有变化时发送 apps-changed，打开的搜索面板随之刷新
+// 网页应用 (web_apps.rs) 不在索引中，每次返回时合并
+
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+use crate::AppInfo;
+
+const STORAGE_KEY: &str = "app_index";
+
+// 扫描的目录深度 (与开始菜单的分组层数相当)
+#[cfg_attr(not(windows), allow(dead_code))]
+const MAX_DEPTH: usize = 3;
+
+// 安装程序会连续创建多个快捷方式，安静一段时间后再扫描
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AppIndex {
+    // 扫描过的目录和当时的修改时间 (UNIX 秒)
+    dirs: Vec<(String, u64)>,
+    apps: Vec<AppInfo>,
+}
+
+// 内存中的索引，后台检查完成前为数据库中的旧结果
+static INDEX: Mutex<Option<AppIndex>> = Mutex::new(None);
+
+/// 启动时在后台读取索引，目录有变化时重新扫描，然后监听开始菜单的变化
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let stored = crate::storage::get().and_then(|s| s.load::<AppIndex>(STORAGE_KEY));
+        let previous = stored.as_ref().map(|index| paths(&index.apps));
+        let fresh = stored.as_ref().is_some_and(|index| !is_stale(&index.dirs, mtime));
+        *INDEX.lock().unwrap() = stored;
+        if fresh {
+            info!(target: "launcher", "应用索引未变化，跳过扫描");
+        } else if previous != Some(paths(&rescan())) {
+            let _ = app.emit("apps-changed", ());
+        }
+        watch(&app);
+    });
+}
+
+// 监听开始菜单目录，快捷方式增删或改名时重新扫描
+fn watch(app: &AppHandle) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!(target: "launcher", "创建开始菜单监听失败: {}", e);
+            return;
+        }
+    };
+    let roots = roots();
+    for root in &roots {
+        if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+            warn!(target: "launcher", "监听开始菜单失败 {}: {}", root.display(), e);
+        }
+    }
+    if roots.is_empty() {
+        return;
+    }
+
+    let relevant = |event: notify::Result<notify::Event>| {
+        event.is_ok_and(|event| {
+            let renamed = matches!(event.kind, notify::EventKind::Modify(notify::event::ModifyKind::Name(_)));
+            (event.kind.is_create() || event.kind.is_remove() || renamed) && event.paths.iter().any(|p| affects_index(p))
+        })
+    };
+    while let Ok(event) = rx.recv() {
+        let mut changed = relevant(event);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            changed |= relevant(event);
+        }
+        if !changed {
+            continue;
+        }
+        let previous = INDEX.lock().unwrap().as_ref().map(|index| paths(&index.apps));
+        if previous != Some(paths(&rescan())) {
+            info!(target: "launcher", "开始菜单有变化，已更新应用索引");
+            let _ = app.emit("apps-changed", ());
+        }
+    }
+}
+
+/// 已安装的应用 (含网页应用)，refresh 为 true 或还没有索引时先扫描
+pub fn cached(refresh: bool) -> Vec<AppInfo> {
+    let apps = if refresh { None } else { INDEX.lock().unwrap().as_ref().map(|index| index.apps.clone()) };
+    with_web_apps(apps.unwrap_or_else(rescan))
+}
+
+/// 重新扫描开始菜单并保存索引，返回扫描到的应用 (不含网页应用)
+pub fn rescan() -> Vec<AppInfo> {
+    let started = Instant::now();
+    let index = scan();
+    info!(target: "launcher", "扫描开始菜单: {} 个应用，{} 个目录，用时 {:?}", index.apps.len(), index.dirs.len(), started.elapsed());
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save(STORAGE_KEY, &index) {
+            warn!(target: "launcher", "{}", e);
+        }
+    }
+    let apps = index.apps.clone();
+    *INDEX.lock().unwrap() = Some(index);
+    apps
+}
+
+/// 合并网页应用，按名称排序去重
+pub fn with_web_apps(mut apps: Vec<AppInfo>) -> Vec<AppInfo> {
+    apps.extend(crate::web_apps::list());
+    apps.sort_by_key(|a| a.name.to_lowercase());
+    apps.dedup_by(|a, b| a.name.to_lowercase() == b.name.to_lowercase());
+    apps
+}
+
+fn paths(apps: &[AppInfo]) -> Vec<String> {
+    apps.iter().map(|a| a.path.clone()).collect()
+}
+
+// 快捷方式、程序或目录 (整个分组被删除时只有目录的事件) 的变化
+fn affects_index(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => ext.eq_ignore_ascii_case("lnk") || ext.eq_ignore_ascii_case("exe"),
+        None => true,
+    }
+}
+
+// 有目录被删除或修改时间变化时需要重新扫描
+fn is_stale(dirs: &[(String, u64)], mtime: impl Fn(&str) -> Option<u64>) -> bool {
+    dirs.is_empty() || dirs.iter().any(|(dir, recorded)| mtime(dir) != Some(*recorded))
+}
+
+fn mtime(path: &str) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs())
+}
+
+// 当前用户和所有用户的开始菜单程序目录 (存在的)
+fn roots() -> Vec<PathBuf> {
+    ["APPDATA", "ProgramData"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|dir| PathBuf::from(dir).join("Microsoft\\Windows\\Start Menu\\Programs"))
+        .filter(|p| p.exists())
+        .collect()
+}
+
+#[cfg(windows)]
+fn scan() -> AppIndex {
+    use walkdir::WalkDir;
+
+    let mut index = AppIndex::default();
+    for root in roots() {
+        for entry in WalkDir::new(&root).max_depth(MAX_DEPTH).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            // 最深一层的目录不展开，它的变化不影响结果
+            if entry.file_type().is_dir() {
+                if entry.depth() < MAX_DEPTH {
+                    let dir = path.to_string_lossy().to_string();
+                    if let Some(modified) = mtime(&dir) {
+                        index.dirs.push((dir, modified));
+                    }
+                }
+                continue;
+            }
+            let is_app = path.extension().is_some_and(|ext| ext == "lnk" || ext == "exe");
+            let Some(name) = path.file_stem().map(|n| n.to_string_lossy().to_string()) else { continue };
+            // 过滤掉一些不需要的项目
+            if is_app && !name.contains("Uninstall") && !name.contains("卸载") {
+                index.apps.push(AppInfo { name, path: path.to_string_lossy().to_string(), args: Vec::new() });
+            }
+        }
+    }
+    index
+}
+
+#[cfg(not(windows))]
+fn scan() -> AppIndex {
+    AppIndex::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_directories_invalidate_the_index() {
+        let dirs = vec![("Programs".to_string(), 100), ("Programs\\Tools".to_string(), 200)];
+        assert!(!is_stale(&dirs, |dir| Some(if dir == "Programs" { 100 } else { 200 })));
+        // 增删了快捷方式
+        assert!(is_stale(&dirs, |dir| Some(if dir == "Programs" { 100 } else { 201 })));
+        // 目录被删除
+        assert!(is_stale(&dirs, |dir| (dir == "Programs").then_some(100)));
+        // 没有扫描过
+        assert!(is_stale(&[], |_| Some(0)));
+
+        assert!(affects_index(Path::new(r"C:\Programs\Tools\Editor.LNK")));
+        assert!(affects_index(Path::new(r"C:\Programs\Tools")));
+        assert!(!affects_index(Path::new(r"C:\Programs\desktop.ini")));
+    }
+}
@@ -0,0 +1,208 @@
+// 监视应用自动嵌入
+// 用户登记的程序 (完整 EXE 路径) 在任何地方打开新的顶层窗口时自动嵌入，
+// 让从外部启动的应用 (如点击 telegram:// 链接、其他程序调起) 也进入 WindowHub
+// 列表保存在 %APPDATA%\WindowHub\watched_apps.json
+// 后台线程定时比较窗口列表，新窗口连续两次出现才嵌入 (跳过一闪而过的启动画面)，
+// 不像主窗口的新窗口 (无边框的启动画面、工具窗口) 也不嵌入 (见 main_window.rs)，
+// 嵌入后显示主窗口并发送 watched-app-embedded (载荷为 WatchedAppEmbedded)
+// 新窗口同时交给窗口规则 (rules.rs) 处理，被规则嵌入的窗口不再按监视列表处理
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+
+use crate::window_manager::{EmbeddedWindow, WindowManager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+static WATCHED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchedAppEmbedded {
+    pub exe_path: String,
+    pub window: EmbeddedWindow,
+}
+
+/// 当前监视的程序
+pub fn list() -> Vec<String> {
+    WATCHED.lock().unwrap().clone()
+}
+
+/// 程序是否在监视列表中
+pub fn is_watched(exe_path: &str) -> bool {
+    WATCHED.lock().unwrap().iter().any(|p| p.eq_ignore_ascii_case(exe_path))
+}
+
+/// 添加监视的程序，返回新的列表
+pub fn watch(exe_path: &str) -> Result<Vec<String>, String> {
+    let exe_path = exe_path.trim();
+    if exe_path.is_empty() {
+        return Err("程序路径不能为空".to_string());
+    }
+    if is_watched(exe_path) {
+        return Err("该程序已在监视列表中".to_string());
+    }
+    let watched = {
+        let mut watched = WATCHED.lock().unwrap();
+        watched.push(exe_path.to_string());
+        watched.clone()
+    };
+    info!(target: "embed", "开始监视应用: {}", exe_path);
+    save_to_file(&watched);
+    Ok(watched)
+}
+
+/// 移除监视的程序，返回新的列表
+pub fn unwatch(exe_path: &str) -> Vec<String> {
+    let watched = {
+        let mut watched = WATCHED.lock().unwrap();
+        watched.retain(|p| !p.eq_ignore_ascii_case(exe_path));
+        watched.clone()
+    };
+    info!(target: "embed", "停止监视应用: {}", exe_path);
+    save_to_file(&watched);
+    watched
+}
+
+/// 启动监视线程 (setup 时调用一次)
+pub fn start(app: &AppHandle) {
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+    std::thread::spawn(watch_thread);
+}
+
+fn watch_thread() {
+    let platform = crate::platform::current();
+    // 已经处理过的窗口 (启动时存在的、已嵌入或跳过的)，为 None 时下一轮重新记录
+    let mut seen: Option<HashSet<isize>> = None;
+    // 上一轮新出现、等待确认的窗口
+    let mut waiting: Vec<isize> = Vec::new();
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if !crate::session_watch::is_active() {
+            continue;
+        }
+        // 没有监视的程序和窗口规则时不比较窗口列表，登记后只处理之后出现的窗口
+        if WATCHED.lock().unwrap().is_empty() && !crate::rules::has_rules() {
+            seen = None;
+            waiting.clear();
+            continue;
+        }
+        let current: Vec<isize> = platform.enumerate().iter().map(|w| w.hwnd).collect();
+        let Some(known) = seen.as_mut() else {
+            seen = Some(current.into_iter().collect());
+            continue;
+        };
+        for hwnd in settle(known, &mut waiting, &current) {
+            adopt(hwnd);
+        }
+    }
+}
+
+/// 处理一个新窗口：先交给窗口规则，没有被规则嵌入且程序在监视列表中时自动嵌入
+/// (后台代理发现的窗口在主界面启动后也由这里处理，见 agent.rs)
+pub fn adopt(hwnd: isize) {
+    let platform = crate::platform::current();
+    let Ok(exe_path) = platform.process_path(hwnd) else { return };
+    if crate::reattach::is_waiting_for(&exe_path) {
+        return;
+    }
+    if let Some(app) = APP.get() {
+        if crate::rules::apply(app, platform, hwnd) {
+            return;
+        }
+    }
+    if is_watched(&exe_path) {
+        // 启动画面不嵌入，稍后出现的主窗口是另一个新窗口
+        if !crate::main_window::is_main(hwnd) {
+            info!(target: "embed", "跳过监视应用的启动画面: {} (hwnd={})", exe_path, hwnd);
+            return;
+        }
+        capture(hwnd, exe_path);
+    }
+}
+
+/// 比较窗口列表：上一轮新出现且仍然存在的窗口确认为新窗口返回，本轮新出现的进入等待
+pub fn settle(seen: &mut HashSet<isize>, waiting: &mut Vec<isize>, current: &[isize]) -> Vec<isize> {
+    let settled: Vec<isize> = waiting.drain(..).filter(|h| current.contains(h)).collect();
+    seen.extend(&settled);
+    seen.retain(|h| current.contains(h));
+    waiting.extend(current.iter().copied().filter(|h| !seen.contains(h)));
+    settled
+}
+
+fn capture(hwnd: isize, exe_path: String) {
+    let Some(app) = APP.get() else { return };
+    let Ok(window) = crate::embed_tab(app, &app.state::<WindowManager>(), hwnd) else { return };
+
+    info!(target: "embed", "自动嵌入监视的应用: {} (hwnd={})", exe_path, hwnd);
+    if let Some(main) = app.get_webview_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+    }
+    let _ = app.emit("watched-app-embedded", WatchedAppEmbedded { exe_path, window });
+}
+
+fn save_to_file(watched: &[String]) {
+    // 安全模式下没有加载原来的文件，修改只在本次运行中有效
+    if crate::safe_mode::is_enabled() {
+        return;
+    }
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("watched_apps.json");
+
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        if let Ok(json) = serde_json::to_string_pretty(watched) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+/// 从文件加载，返回内容是否有变化
+pub fn load_from_file() -> bool {
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("watched_apps.json");
+
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            if let Ok(loaded) = serde_json::from_str::<Vec<String>>(&content) {
+                let mut watched = WATCHED.lock().unwrap();
+                if *watched == loaded {
+                    return false;
+                }
+                info!(target: "settings", "已加载 {} 个监视的应用", loaded.len());
+                *watched = loaded;
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_windows_must_survive_one_poll() {
+        let mut seen: HashSet<isize> = [1, 2].into_iter().collect();
+        let mut waiting = Vec::new();
+
+        // 3 (启动画面) 和 4 新出现，先等待
+        assert!(settle(&mut seen, &mut waiting, &[1, 2, 3, 4]).is_empty());
+        // 3 已关闭，4 确认；5 新出现
+        assert_eq!(settle(&mut seen, &mut waiting, &[1, 4, 5]), vec![4]);
+        assert_eq!(settle(&mut seen, &mut waiting, &[1, 4, 5]), vec![5]);
+        // 已确认的窗口不会再次返回
+        assert!(settle(&mut seen, &mut waiting, &[1, 4, 5]).is_empty());
+        assert!(!seen.contains(&2));
+    }
+}
@@ -0,0 +1,336 @@
+// 备份和恢复整个数据目录 (见 paths.rs)
+// backup_data(path) 把数据库、设置、工作区、规则等配置文件和日志打包成一个文件 (JSON，文件内容为 base64)：
+// - 记录备份格式版本、程序版本和数据库结构版本，每个文件带 SHA-256，恢复前全部校验
+// - 数据库用 VACUUM INTO 导出一致的副本，程序运行中也可以备份；诊断文件和网页应用的浏览器配置 (web_apps) 不备份
+// - 边读边写，同一时间只有一个文件在内存中；被其他程序锁定等无法读取的文件跳过，在结果的 skipped 中列出
+// restore_data(path) 校验后把备份放到数据目录中的 PENDING_FILE，下次启动时 (打开数据库和日志之前) 才替换：
+// 先解压到旁边的 <数据目录>.restore，全部写完后把原来的目录改名为 <数据目录>.before-restore，再把新目录改回数据目录的名称，
+// 任何一步失败都保留原来的数据；上一次恢复前的数据只保留一份
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use tracing::{info, warn};
+
+// 文件格式的版本，不兼容的修改时增加
+const VERSION: u32 = 1;
+const FORMAT: &str = "windowhub-backup";
+
+// 等待下次启动时恢复的备份
+const PENDING_FILE: &str = "pending_restore.whbackup";
+const DB_FILE: &str = "windowhub.db";
+// 不备份的文件和目录 (数据库由 VACUUM INTO 导出，不直接复制；web_apps 是浏览器的配置和缓存，可能有几百 MB；
+// webview 是便携模式下 WebView2 的缓存)
+const SKIPPED: [&str; 8] =
+    [DB_FILE, "windowhub.db-wal", "windowhub.db-shm", "windowhub.db-journal", PENDING_FILE, "diagnostics", "web_apps", "webview"];
+
+/// 备份的信息
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackupInfo {
+    pub version: u32,
+    pub app_version: String,
+    pub schema_version: usize, // 数据库结构版本
+    pub created_at: u64,       // Unix 秒
+    pub files: Vec<String>,    // 相对于数据目录的路径
+    pub skipped: Vec<String>,  // 备份时无法读取而跳过的文件
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Archive {
+    format: String,
+    version: u32,
+    app_version: String,
+    schema_version: usize,
+    created_at: u64,
+    files: Vec<ArchivedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchivedFile {
+    path: String, // 用 / 分隔
+    sha256: String,
+    content: String, // base64
+}
+
+impl Archive {
+    fn info(&self) -> BackupInfo {
+        BackupInfo {
+            version: self.version,
+            app_version: self.app_version.clone(),
+            schema_version: self.schema_version,
+            created_at: self.created_at,
+            files: self.files.iter().map(|f| f.path.clone()).collect(),
+            skipped: Vec::new(),
+        }
+    }
+}
+
+/// 备份数据目录到 path
+pub fn backup(path: &str) -> Result<BackupInfo, String> {
+    let dir = data_dir()?;
+    // 先写临时文件再改名，写到一半失败时不会留下不完整的备份
+    let temp = format!("{}.tmp", path);
+    let info = write_backup(&dir, Path::new(&temp)).and_then(|info| {
+        std::fs::rename(&temp, path).map_err(|e| format!("写入备份失败: {}", e))?;
+        Ok(info)
+    });
+    if info.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    let info = info?;
+    info!(target: "settings", "已备份数据目录到 {} ({} 个文件，跳过 {} 个)", path, info.files.len(), info.skipped.len());
+    Ok(info)
+}
+
+fn write_backup(dir: &Path, target: &Path) -> Result<BackupInfo, String> {
+    let file = std::fs::File::create(target).map_err(|e| format!("写入备份失败: {}", e))?;
+    let created_at = crate::clock::now_secs();
+    let mut writer = ArchiveWriter::new(BufWriter::new(file), created_at)?;
+    let mut skipped = Vec::new();
+    for (path, source) in collect(dir) {
+        // 日志文件正在写入，读到的是当时的内容；被其他程序独占的文件跳过
+        match std::fs::read(&source) {
+            Ok(content) => writer.add(path, &content)?,
+            Err(e) => {
+                warn!(target: "settings", "备份时跳过 {}: {}", path, e);
+                skipped.push(path);
+            }
+        }
+    }
+    if let Some(storage) = crate::storage::get() {
+        let copy = std::env::temp_dir().join(format!("windowhub-backup-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&copy);
+        let content = storage.export_to(&copy).and_then(|_| std::fs::read(&copy).map_err(|e| format!("读取数据库副本失败: {}", e)));
+        let _ = std::fs::remove_file(&copy);
+        writer.add(DB_FILE.to_string(), &content?)?;
+    }
+    let files = writer.finish()?;
+    Ok(BackupInfo {
+        version: VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: crate::storage::schema_version(),
+        created_at,
+        files,
+        skipped,
+    })
+}
+
+// 边读边写备份文件 (格式与 Archive 相同)，同一时间只有一个文件的内容在内存中
+struct ArchiveWriter<W: Write> {
+    out: W,
+    files: Vec<String>,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    fn new(mut out: W, created_at: u64) -> Result<Self, String> {
+        // files 是最后一个字段，去掉空数组的结尾后逐个写入文件
+        let header = serde_json::to_string(&archive(Vec::new(), created_at)).map_err(|e| e.to_string())?;
+        let header = header.strip_suffix("]}").ok_or("备份格式错误")?;
+        out.write_all(header.as_bytes()).map_err(|e| format!("写入备份失败: {}", e))?;
+        Ok(ArchiveWriter { out, files: Vec::new() })
+    }
+
+    fn add(&mut self, path: String, content: &[u8]) -> Result<(), String> {
+        if !self.files.is_empty() {
+            self.out.write_all(b",").map_err(|e| format!("写入备份失败: {}", e))?;
+        }
+        let file = ArchivedFile { path, sha256: sha256(content), content: base64::engine::general_purpose::STANDARD.encode(content) };
+        serde_json::to_writer(&mut self.out, &file).map_err(|e| format!("写入备份失败: {}", e))?;
+        self.files.push(file.path);
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<String>, String> {
+        self.out.write_all(b"]}").and_then(|_| self.out.flush()).map_err(|e| format!("写入备份失败: {}", e))?;
+        Ok(self.files)
+    }
+}
+
+/// 校验备份并安排在下次启动时恢复
+pub fn restore(path: &str) -> Result<BackupInfo, String> {
+    let archive = read(Path::new(path))?;
+    let dir = data_dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("无法创建数据目录: {}", e))?;
+    std::fs::copy(path, dir.join(PENDING_FILE)).map_err(|e| format!("保存备份失败: {}", e))?;
+    info!(target: "settings", "将在下次启动时恢复备份: {}", path);
+    Ok(archive.info())
+}
+
+/// 启动时 (打开数据库和日志之前) 恢复等待中的备份，没有时返回 None
+pub fn apply_pending() -> Option<Result<BackupInfo, String>> {
+    let dir = data_dir().ok()?;
+    dir.join(PENDING_FILE).exists().then(|| apply(&dir))
+}
+
+// 解压到旁边的目录后替换数据目录
+fn apply(dir: &Path) -> Result<BackupInfo, String> {
+    let pending = dir.join(PENDING_FILE);
+    let archive = read(&pending).inspect_err(|_| {
+        // 备份已经损坏，重试也不会成功
+        let _ = std::fs::remove_file(&pending);
+    })?;
+    let staging = dir.with_extension("restore");
+    let previous = dir.with_extension("before-restore");
+
+    let _ = std::fs::remove_dir_all(&staging);
+    if let Err(e) = extract(&archive, &staging) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    let _ = std::fs::remove_dir_all(&previous);
+    if let Err(e) = std::fs::rename(dir, &previous) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(format!("数据目录正在使用，下次启动时重试: {}", e));
+    }
+    if let Err(e) = std::fs::rename(&staging, dir) {
+        let _ = std::fs::rename(&previous, dir);
+        return Err(format!("替换数据目录失败，下次启动时重试: {}", e));
+    }
+    let _ = std::fs::remove_file(previous.join(PENDING_FILE));
+    Ok(archive.info())
+}
+
+fn extract(archive: &Archive, dir: &Path) -> Result<(), String> {
+    for file in &archive.files {
+        let target = dir.join(&file.path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("无法创建目录 {}: {}", parent.display(), e))?;
+        }
+        let content = base64::engine::general_purpose::STANDARD.decode(&file.content).map_err(|e| e.to_string())?;
+        std::fs::write(&target, content).map_err(|e| format!("写入 {} 失败: {}", file.path, e))?;
+    }
+    Ok(())
+}
+
+// 读取并校验备份
+fn read(path: &Path) -> Result<Archive, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取备份失败: {}", e))?;
+    let archive: Archive = serde_json::from_str(&content).map_err(|_| "不是 WindowHub 的备份文件".to_string())?;
+    verify(&archive)?;
+    Ok(archive)
+}
+
+fn verify(archive: &Archive) -> Result<(), String> {
+    if archive.format != FORMAT {
+        return Err("不是 WindowHub 的备份文件".to_string());
+    }
+    if archive.version > VERSION {
+        return Err(format!("备份来自更新的版本 ({})，请先升级 WindowHub", archive.app_version));
+    }
+    if archive.schema_version > crate::storage::schema_version() {
+        return Err(format!("备份的数据库版本 {} 比当前程序新，请先升级 WindowHub", archive.schema_version));
+    }
+    for file in &archive.files {
+        // 只能写到数据目录中
+        let relative = Path::new(&file.path);
+        if file.path.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(format!("备份中的路径无效: {}", file.path));
+        }
+        let content = base64::engine::general_purpose::STANDARD
+            .decode(&file.content)
+            .map_err(|_| format!("备份已损坏: {}", file.path))?;
+        if sha256(&content) != file.sha256 {
+            return Err(format!("备份已损坏: {} 校验失败", file.path));
+        }
+    }
+    Ok(())
+}
+
+fn archive(files: Vec<(String, Vec<u8>)>, created_at: u64) -> Archive {
+    Archive {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: crate::storage::schema_version(),
+        created_at,
+        files: files
+            .into_iter()
+            .map(|(path, content)| ArchivedFile {
+                path,
+                sha256: sha256(&content),
+                content: base64::engine::general_purpose::STANDARD.encode(content),
+            })
+            .collect(),
+    }
+}
+
+// 数据目录中要备份的文件 (相对路径, 完整路径)
+fn collect(dir: &Path) -> Vec<(String, PathBuf)> {
+    use walkdir::WalkDir;
+
+    let walker = WalkDir::new(dir).into_iter().filter_entry(|e| e.depth() != 1 || !e.file_name().to_str().is_some_and(|name| SKIPPED.contains(&name)));
+    walker
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(dir).ok()?;
+            let path = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            Some((path, entry.path().to_path_buf()))
+        })
+        .collect()
+}
+
+fn data_dir() -> Result<PathBuf, String> {
+    crate::paths::data_dir().ok_or_else(|| "无法获取配置目录".to_string())
+}
+
+fn sha256(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_tampered_and_escaping_files() {
+        let files = vec![("settings.json".to_string(), b"{}".to_vec()), ("logs/windowhub.log".to_string(), b"log".to_vec())];
+        let mut backup = archive(files, 100);
+        assert!(verify(&backup).is_ok());
+        assert_eq!(backup.info().files, vec!["settings.json", "logs/windowhub.log"]);
+
+        backup.files[0].content = base64::engine::general_purpose::STANDARD.encode(b"{\"x\":1}");
+        assert!(verify(&backup).unwrap_err().contains("settings.json"));
+
+        let escaping = archive(vec![("../evil.json".to_string(), Vec::new())], 100);
+        assert!(verify(&escaping).is_err());
+        let mut newer = archive(Vec::new(), 100);
+        newer.version = VERSION + 1;
+        assert!(verify(&newer).is_err());
+    }
+
+    #[test]
+    fn streamed_archive_matches_format() {
+        let mut out = Vec::new();
+        let mut writer = ArchiveWriter::new(&mut out, 100).unwrap();
+        writer.add("settings.json".to_string(), b"{}").unwrap();
+        writer.add("logs/a.log".to_string(), b"log").unwrap();
+        assert_eq!(writer.finish().unwrap(), vec!["settings.json", "logs/a.log"]);
+        let archive: Archive = serde_json::from_slice(&out).unwrap();
+        assert!(verify(&archive).is_ok());
+        assert_eq!(archive.created_at, 100);
+
+        let mut empty = Vec::new();
+        ArchiveWriter::new(&mut empty, 100).unwrap().finish().unwrap();
+        assert!(serde_json::from_slice::<Archive>(&empty).unwrap().files.is_empty());
+    }
+
+    #[test]
+    fn pending_backup_replaces_data_directory() {
+        let root = std::env::temp_dir().join(format!("windowhub-backup-test-{}", std::process::id()));
+        let dir = root.join("WindowHub");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("settings.json"), "old").unwrap();
+        let backup = archive(vec![("settings.json".to_string(), b"new".to_vec()), ("logs/a.log".to_string(), b"log".to_vec())], 100);
+        std::fs::write(dir.join(PENDING_FILE), serde_json::to_string(&backup).unwrap()).unwrap();
+
+        assert_eq!(apply(&dir).unwrap().files.len(), 2);
+        assert_eq!(std::fs::read_to_string(dir.join("settings.json")).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(dir.join("logs").join("a.log")).unwrap(), "log");
+        assert!(!dir.join(PENDING_FILE).exists());
+        assert_eq!(std::fs::read_to_string(root.join("WindowHub.before-restore").join("settings.json")).unwrap(), "old");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
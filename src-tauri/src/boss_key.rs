@@ -0,0 +1,122 @@
+// 老板键
+// 按下 shortcuts.boss_key 一次性隐藏所有内容：cloak 所有可见的标签、隐藏主窗口、静音嵌入应用的音频会话；
+// 再按一次 (或用切换窗口的快捷键、托盘菜单显示主窗口) 恢复到之前的样子：
+// 只恢复这里隐藏的标签和静音的会话 (原本就隐藏的标签、用户自己静音的会话保持不变)，主窗口原来可见时重新显示，
+// 原来在前台时重新获得焦点。展台模式下不可用
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use crate::platform::{self, Platform};
+use crate::window_manager::WindowManager;
+
+// 隐藏前的状态
+#[derive(Debug, Default)]
+struct Hidden {
+    host_visible: bool,
+    host_focused: bool,
+    tabs: Vec<(isize, bool)>, // 隐藏的标签，以及是否通过 cloak 隐藏 (否则为 SW_HIDE)
+    muted: Vec<String>,       // 静音的音频会话
+}
+
+static HIDDEN: Mutex<Option<Hidden>> = Mutex::new(None);
+
+/// 按下老板键：隐藏一切，已经隐藏时恢复
+pub fn toggle(app: &AppHandle) {
+    if restore(app) {
+        return;
+    }
+    if crate::kiosk::is_enabled() {
+        info!(target: "focus", "展台模式下不响应老板键");
+        return;
+    }
+    let Some(window) = app.get_webview_window("main") else { return };
+    let manager = app.state::<WindowManager>();
+    let apps: Vec<String> = manager.list().into_iter().filter_map(|w| w.exe_path).collect();
+    let mut hidden = conceal(platform::current(), &manager.hwnds(), &apps);
+    hidden.host_visible = window.is_visible().unwrap_or(false);
+    hidden.host_focused = window.is_focused().unwrap_or(false);
+    let _ = window.hide();
+    info!(target: "focus", "老板键: 隐藏 {} 个标签，静音 {} 个音频会话", hidden.tabs.len(), hidden.muted.len());
+    *HIDDEN.lock().unwrap() = Some(hidden);
+}
+
+/// 老板键生效中时恢复之前的状态并返回 true
+pub fn restore(app: &AppHandle) -> bool {
+    let Some(hidden) = HIDDEN.lock().unwrap().take() else { return false };
+    reveal(platform::current(), &hidden);
+    if let Some(window) = app.get_webview_window("main") {
+        if hidden.host_visible {
+            let _ = window.show();
+        }
+        if hidden.host_focused {
+            let _ = window.set_focus();
+        }
+    }
+    info!(target: "focus", "老板键: 已恢复");
+    true
+}
+
+// 隐藏可见的标签，静音属于 apps 的会话
+fn conceal(platform: &dyn Platform, tabs: &[isize], apps: &[String]) -> Hidden {
+    let mut hidden = Hidden::default();
+    for &tab in tabs.iter().filter(|tab| !crate::tab_view::is_hidden(**tab)) {
+        let cloaked = platform.set_cloaked(tab, true);
+        if cloaked || platform.set_visible(tab, false) {
+            hidden.tabs.push((tab, cloaked));
+        }
+    }
+    for session in platform.audio_sessions() {
+        if session.muted || !apps.iter().any(|app| app.eq_ignore_ascii_case(&session.exe_path)) {
+            continue;
+        }
+        if platform.set_audio_session_muted(&session.id, true) {
+            hidden.muted.push(session.id);
+        }
+    }
+    hidden
+}
+
+fn reveal(platform: &dyn Platform, hidden: &Hidden) {
+    for id in &hidden.muted {
+        platform.set_audio_session_muted(id, false);
+    }
+    for &(tab, cloaked) in &hidden.tabs {
+        if cloaked {
+            platform.set_cloaked(tab, false);
+        } else {
+            platform.set_visible(tab, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatform;
+
+    #[test]
+    fn restores_only_what_it_hid() {
+        let platform = MockPlatform::new();
+        let shown = platform.create_window("音乐", "Music", Some("C:/Apps/music.exe"));
+        let background = platform.create_window("文档", "Notepad", Some("C:/Windows/notepad.exe"));
+        crate::tab_view::hide(&platform, background);
+        platform.add_audio_session("music", "C:/Apps/music.exe", false);
+        platform.add_audio_session("music-muted-by-user", "C:/Apps/music.exe", true);
+
+        let hidden = conceal(&platform, &[shown, background], &["c:/apps/MUSIC.exe".to_string()]);
+        let muted = |id: &str| platform.audio_sessions().into_iter().find(|s| s.id == id).unwrap().muted;
+        assert!(platform.window(shown).unwrap().cloaked);
+        assert!(muted("music"));
+        assert_eq!(hidden.tabs, vec![(shown, true)]);
+
+        reveal(&platform, &hidden);
+        assert!(!platform.window(shown).unwrap().cloaked);
+        assert!(platform.window(background).unwrap().cloaked);
+        assert!(!muted("music"));
+        assert!(muted("music-muted-by-user"));
+
+        crate::tab_view::forget(background);
+    }
+}
@@ -0,0 +1,98 @@
+// 资源上限
+// 防止失控的自动嵌入规则或插件一次嵌入几十个窗口、开启大量截图流和钩子，拖垮桌面：
+// - 标签：WindowManager::embed 在修改窗口之前检查仍存在的嵌入窗口个数 (已关闭等待重新嵌入的不算)
+// - 截图流：标签录制开始前、远程标签的连接第一次查看窗口时占用一个名额 (acquire_stream)，录制结束或连接断开时归还
+// - 钩子：拾取窗口、取色、录制宏等按需安装的钩子在安装前检查 resources 中登记的钩子个数，
+//   常驻的事件钩子和空闲检测等不受限制，但计入用量
+// 上限在设置的 limits 中配置，超出时返回 LimitExceeded (参数为设置项名称和上限)
+// get_resource_budget 返回各项的上限和当前用量
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::window_manager::{EmbedError, EmbedErrorCode};
+
+static STREAMS: AtomicUsize = AtomicUsize::new(0);
+
+/// 一项资源的上限和当前用量
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Budget {
+    pub limit: usize,
+    pub used: usize,
+}
+
+/// get_resource_budget 的返回值
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceBudget {
+    pub tabs: Budget,
+    pub capture_streams: Budget,
+    pub hooks: Budget,
+}
+
+/// 占用的截图流名额，离开作用域时归还
+pub struct StreamSlot(());
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        STREAMS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 各项资源的上限和用量 (tabs 为仍存在的嵌入窗口个数)
+pub fn get(live_tabs: usize) -> ResourceBudget {
+    let limits = crate::settings::get().limits;
+    ResourceBudget {
+        tabs: Budget { limit: limits.max_tabs, used: live_tabs },
+        capture_streams: Budget { limit: limits.max_capture_streams, used: STREAMS.load(Ordering::SeqCst) },
+        hooks: Budget { limit: limits.max_hooks, used: hooks_in_use() },
+    }
+}
+
+/// 再嵌入一个窗口是否超出上限
+pub fn check_tabs(live_tabs: usize) -> Result<(), EmbedError> {
+    check("max_tabs", live_tabs, 1, crate::settings::get().limits.max_tabs)
+}
+
+/// 再安装 count 个钩子是否超出上限
+pub fn check_hooks(count: usize) -> Result<(), EmbedError> {
+    check("max_hooks", hooks_in_use(), count, crate::settings::get().limits.max_hooks)
+}
+
+/// 占用一个截图流名额，已满时返回错误
+pub fn acquire_stream() -> Result<StreamSlot, EmbedError> {
+    let limit = crate::settings::get().limits.max_capture_streams;
+    STREAMS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| (used < limit).then_some(used + 1))
+        .map(|_| StreamSlot(()))
+        .map_err(|_| exceeded("max_capture_streams", limit))
+}
+
+fn hooks_in_use() -> usize {
+    crate::resources::list().iter().filter(|r| r.kind.is_hook()).map(|r| r.count as usize).sum()
+}
+
+fn check(name: &str, used: usize, count: usize, limit: usize) -> Result<(), EmbedError> {
+    if used + count > limit {
+        return Err(exceeded(name, limit));
+    }
+    Ok(())
+}
+
+fn exceeded(name: &str, limit: usize) -> EmbedError {
+    let params = vec![name.to_string(), limit.to_string()];
+    EmbedError { code: EmbedErrorCode::LimitExceeded, message: crate::messages::zh(EmbedErrorCode::LimitExceeded.key(), &params), params }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_only_when_limit_would_be_exceeded() {
+        assert!(check("max_tabs", 29, 1, 30).is_ok());
+        let error = check("max_tabs", 30, 1, 30).unwrap_err();
+        assert_eq!(error.code, EmbedErrorCode::LimitExceeded);
+        assert_eq!(error.params, vec!["max_tabs".to_string(), "30".to_string()]);
+        assert!(check("max_hooks", 30, 2, 31).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+// 拾取模式 (类似 Spy++ 的窗口查找器)
+// start_capture_mode 后隐藏主窗口，鼠标移到哪个窗口就用边框高亮它，单击即嵌入：
+// - 低级鼠标钩子跟踪光标下的顶层窗口 (WindowFromPoint)，不能嵌入的窗口不高亮
+// - 高亮边框见 highlight 模块
+// - 左键单击嵌入，右键或 Esc 取消 (这些点击和按键被拦截，不会传给下面的窗口)
+// 结束后重新显示主窗口并发送 capture-mode-ended (载荷为嵌入的窗口句柄，取消时为 null)
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicIsize, AtomicU32};
+#[cfg(windows)]
+use tracing::warn;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM},
+    System::LibraryLoader::GetModuleHandleW,
+    System::Threading::GetCurrentThreadId,
+    UI::Input::KeyboardAndMouse::VK_ESCAPE,
+    UI::WindowsAndMessaging::*,
+};
+
+#[cfg(windows)]
+use crate::highlight;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+// 拾取线程 (结束时向其发送 WM_QUIT)
+#[cfg(windows)]
+static THREAD_ID: AtomicU32 = AtomicU32::new(0);
+// 单击确认的窗口，取消时为 0
+#[cfg(windows)]
+static PICKED: AtomicIsize = AtomicIsize::new(0);
+// 拦截了按下后，对应的松开也要拦截
+#[cfg(windows)]
+static SWALLOW_UP: AtomicBool = AtomicBool::new(false);
+
+/// 进入拾取模式
+pub fn start(app: &AppHandle) -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        if ACTIVE.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return Err("已在拾取窗口".to_string());
+        }
+        if let Err(e) = crate::budget::check_hooks(2) {
+            ACTIVE.store(false, Ordering::SeqCst);
+            return Err(e.into());
+        }
+        let _ = APP.set(app.clone());
+        info!(target: "embed", "进入拾取模式");
+        // 主窗口会挡住要拾取的窗口
+        if let Some(main) = app.get_webview_window("main") {
+            let _ = main.hide();
+        }
+        std::thread::spawn(|| unsafe { capture_thread() });
+        Ok(())
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (app, &APP, &ACTIVE);
+        Err("仅支持 Windows".to_string())
+    }
+}
+
+/// 退出拾取模式，不嵌入
+pub fn cancel() {
+    #[cfg(windows)]
+    {
+        let thread = THREAD_ID.load(Ordering::SeqCst);
+        if ACTIVE.load(Ordering::SeqCst) && thread != 0 {
+            PICKED.store(0, Ordering::SeqCst);
+            unsafe {
+                let _ = PostThreadMessageW(thread, WM_QUIT, WPARAM(0), LPARAM(0));
+            }
+        }
+    }
+}
+
+// 拾取结束：显示主窗口，嵌入选中的窗口
+#[cfg_attr(not(windows), allow(dead_code))]
+fn finish(picked: Option<isize>) {
+    let Some(app) = APP.get() else { return };
+    if let Some(main) = app.get_webview_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+    }
+    let embedded = picked.filter(|hwnd| crate::embed_tab(app, &app.state::<crate::window_manager::WindowManager>(), *hwnd).is_ok());
+    info!(target: "embed", "退出拾取模式: {:?}", embedded);
+    ACTIVE.store(false, Ordering::SeqCst);
+    let _ = app.emit("capture-mode-ended", embedded);
+}
+
+#[cfg(windows)]
+unsafe fn capture_thread() {
+    THREAD_ID.store(GetCurrentThreadId(), Ordering::SeqCst);
+    PICKED.store(0, Ordering::SeqCst);
+    SWALLOW_UP.store(false, Ordering::SeqCst);
+
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let hooks = [
+        SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), instance, 0),
+        SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), instance, 0),
+    ];
+    let hook_count = hooks.iter().filter(|h| h.is_ok()).count() as u32;
+    let resource = crate::resources::register(crate::resources::ResourceKind::LowLevelHook, "capture", hook_count, None, None);
+    if hooks.iter().all(|h| h.is_ok()) {
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    } else {
+        warn!(target: "embed", "安装拾取钩子失败");
+    }
+
+    for hook in hooks.into_iter().flatten() {
+        let _ = UnhookWindowsHookEx(hook);
+    }
+    crate::resources::unregister(resource);
+    highlight::show(0);
+    THREAD_ID.store(0, Ordering::SeqCst);
+
+    let picked = PICKED.load(Ordering::SeqCst);
+    finish((picked != 0).then_some(picked));
+}
+
+// 光标下可以嵌入的顶层窗口
+#[cfg(windows)]
+unsafe fn target_at(pt: POINT) -> isize {
+    let hwnd = GetAncestor(WindowFromPoint(pt), GA_ROOT);
+    if hwnd.is_invalid() {
+        return 0;
+    }
+    let raw = hwnd.0 as isize;
+    let platform = crate::platform::current();
+    if highlight::is_overlay(raw) || crate::window_manager::check_embeddable(platform, raw).is_err() {
+        return 0;
+    }
+    raw
+}
+
+#[cfg(windows)]
+unsafe fn end(picked: isize) {
+    PICKED.store(picked, Ordering::SeqCst);
+    SWALLOW_UP.store(true, Ordering::SeqCst);
+    PostQuitMessage(0);
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        match wparam.0 as u32 {
+            WM_MOUSEMOVE => {
+                highlight::show(target_at(info.pt));
+            }
+            WM_LBUTTONDOWN => {
+                let target = target_at(info.pt);
+                // 点在不能嵌入的窗口上 (桌面、任务栏等) 时继续拾取
+                if target != 0 {
+                    end(target);
+                }
+                return LRESULT(1);
+            }
+            WM_RBUTTONDOWN => {
+                end(0);
+                return LRESULT(1);
+            }
+            WM_LBUTTONUP | WM_RBUTTONUP if SWALLOW_UP.swap(false, Ordering::SeqCst) => return LRESULT(1),
+            WM_LBUTTONUP => return LRESULT(1),
+            _ => {}
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        if info.vkCode == VK_ESCAPE.0 as u32 {
+            if wparam.0 as u32 == WM_KEYDOWN {
+                end(0);
+            }
+            return LRESULT(1);
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
@@ -0,0 +1,176 @@
+// 嵌入窗口 (主窗口的子窗口) 之间的 Z 序
+// 分屏停靠、预览时多个标签互相重叠，后端需要明确控制谁在上面：
+// - set_child_zorder 按给定顺序 (从上到下) 一次性排列 (DeferWindowPos，中间状态不会显示出来)
+// - bring_child_forward 把标签在嵌入窗口中上移一层
+// 最近一次指定的顺序记在 EXPECTED 中，激活标签时把它移到最前。
+// 嵌入的应用自己调整 Z 序时 (win_events 收到其他进程产生的 EVENT_OBJECT_REORDER)，
+// 实际顺序与指定的不一致则发送 child-zorder-changed，由前端决定是否重新排列 (本进程的调整不会触发)
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+
+#[cfg(windows)]
+use tracing::info;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::HWND,
+    UI::WindowsAndMessaging::{
+        BeginDeferWindowPos, DeferWindowPos, EndDeferWindowPos, GetWindow, IsWindow, GW_CHILD, GW_HWNDNEXT, HWND_TOP, SWP_NOACTIVATE, SWP_NOMOVE,
+        SWP_NOSIZE,
+    },
+};
+
+// 最近一次指定的顺序 (从上到下)
+static EXPECTED: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct ZOrderChanged {
+    pub expected: Vec<isize>,
+    pub actual: Vec<isize>,
+}
+
+/// 按 ordering (从上到下) 排列嵌入窗口，返回排列后的实际顺序
+/// 不是嵌入窗口的句柄忽略，没有列出的嵌入窗口保持在它们下面
+pub fn set_order(host: isize, ordering: &[isize], embedded: &[isize]) -> Result<Vec<isize>, String> {
+    let ordering = normalize(ordering, embedded);
+    if ordering.is_empty() {
+        return Err("没有可排列的嵌入窗口".to_string());
+    }
+    apply(ordering.clone())?;
+    *EXPECTED.lock().unwrap() = ordering;
+    Ok(current(host, embedded))
+}
+
+/// 把标签在嵌入窗口中上移一层，返回排列后的实际顺序
+pub fn bring_forward(host: isize, hwnd: isize, embedded: &[isize]) -> Result<Vec<isize>, String> {
+    if !embedded.contains(&hwnd) {
+        return Err("窗口未嵌入".to_string());
+    }
+    let order = forward(&current(host, embedded), hwnd);
+    set_order(host, &order, embedded)
+}
+
+/// 标签被激活 (提到最前)
+pub fn raised(hwnd: isize) {
+    let mut expected = EXPECTED.lock().unwrap();
+    if let Some(i) = expected.iter().position(|&h| h == hwnd) {
+        let tab = expected.remove(i);
+        expected.insert(0, tab);
+    }
+}
+
+/// 标签已释放或关闭
+pub fn forget(hwnd: isize) {
+    EXPECTED.lock().unwrap().retain(|&h| h != hwnd);
+}
+
+/// 其他进程调整了主窗口子窗口的 Z 序 (win_events 中调用)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn reordered(app: &AppHandle, host: isize, embedded: &[isize]) {
+    let expected = EXPECTED.lock().unwrap().clone();
+    let actual = current(host, embedded);
+    if !violates(&expected, &actual) {
+        return;
+    }
+    warn!(target: "embed", "嵌入窗口的 Z 序被改变: 应为 {:?}，实际 {:?}", expected, actual);
+    crate::win_events::record_event("zorder-changed", host, actual.first().copied(), format!("{:?}", actual));
+    let _ = app.emit("child-zorder-changed", ZOrderChanged { expected, actual });
+}
+
+// 去掉不是嵌入窗口的和重复的句柄
+fn normalize(ordering: &[isize], embedded: &[isize]) -> Vec<isize> {
+    let mut result: Vec<isize> = Vec::new();
+    for &hwnd in ordering {
+        if embedded.contains(&hwnd) && !result.contains(&hwnd) {
+            result.push(hwnd);
+        }
+    }
+    result
+}
+
+// 把 hwnd 与它上面的一个交换 (已经在最上面时不变)
+fn forward(order: &[isize], hwnd: isize) -> Vec<isize> {
+    let mut order = order.to_vec();
+    if let Some(i) = order.iter().position(|&h| h == hwnd) {
+        if i > 0 {
+            order.swap(i, i - 1);
+        }
+    }
+    order
+}
+
+// 实际顺序中，指定过顺序的窗口之间的相对位置是否与指定的不同
+fn violates(expected: &[isize], actual: &[isize]) -> bool {
+    let present: Vec<isize> = actual.iter().copied().filter(|h| expected.contains(h)).collect();
+    let wanted: Vec<isize> = expected.iter().copied().filter(|h| actual.contains(h)).collect();
+    present != wanted
+}
+
+// 嵌入窗口当前从上到下的顺序
+#[cfg(windows)]
+fn current(host: isize, embedded: &[isize]) -> Vec<isize> {
+    let mut order = Vec::new();
+    unsafe {
+        let mut child = GetWindow(HWND(host as *mut _), GW_CHILD).unwrap_or_default();
+        while !child.is_invalid() {
+            let raw = child.0 as isize;
+            if embedded.contains(&raw) {
+                order.push(raw);
+            }
+            child = GetWindow(child, GW_HWNDNEXT).unwrap_or_default();
+        }
+    }
+    order
+}
+
+#[cfg(not(windows))]
+fn current(_host: isize, _embedded: &[isize]) -> Vec<isize> {
+    Vec::new()
+}
+
+// 在窗口操作线程上一次性排列 (目标无响应时超时返回)
+#[cfg(windows)]
+fn apply(ordering: Vec<isize>) -> Result<(), String> {
+    crate::window_ops::call("set_child_zorder", move || unsafe {
+        let windows: Vec<HWND> = ordering.iter().map(|&h| HWND(h as *mut _)).filter(|&h| IsWindow(h).as_bool()).collect();
+        let mut pos = BeginDeferWindowPos(windows.len() as i32).map_err(|e| format!("排列嵌入窗口失败: {}", e))?;
+        let mut after = HWND_TOP;
+        for &hwnd in &windows {
+            pos = DeferWindowPos(pos, hwnd, after, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE)
+                .map_err(|e| format!("排列嵌入窗口失败: {}", e))?;
+            after = hwnd;
+        }
+        EndDeferWindowPos(pos).map_err(|e| format!("排列嵌入窗口失败: {}", e))?;
+        info!(target: "embed", "嵌入窗口 Z 序: {:?}", ordering);
+        Ok(())
+    })?
+}
+
+#[cfg(not(windows))]
+fn apply(_ordering: Vec<isize>) -> Result<(), String> {
+    Err("仅支持 Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_one_step_forward() {
+        assert_eq!(forward(&[1, 2, 3], 3), vec![1, 3, 2]);
+        assert_eq!(forward(&[1, 2, 3], 1), vec![1, 2, 3]);
+        assert_eq!(normalize(&[3, 9, 3, 1], &[1, 2, 3]), vec![3, 1]);
+    }
+
+    #[test]
+    fn only_compares_ordered_windows() {
+        assert!(!violates(&[1, 2], &[1, 5, 2]));
+        assert!(violates(&[1, 2], &[2, 1]));
+        // 关闭的窗口不算
+        assert!(!violates(&[1, 2, 3], &[1, 3]));
+    }
+}
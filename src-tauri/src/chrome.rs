@@ -0,0 +1,177 @@
+// 自定义主窗口边框 (设置 appearance.custom_chrome)
+// 去掉系统标题栏，标签栏兼作标题栏，最小化/最大化/关闭按钮由网页显示；由后端子类化主窗口处理：
+// - WM_NCCALCSIZE: 客户区向上覆盖原标题栏，左右下保留系统的 (不可见) 调整大小边框，顶部保留 1 像素
+// - WM_NCHITTEST: 顶部边缘和两个上角返回 HTTOP/HTTOPLEFT/HTTOPRIGHT，可以拖动调整大小
+// 客户区被网页 (WebView2 的子窗口) 覆盖，标签栏上的鼠标消息到不了主窗口，所以：
+// - 在标签栏空白处按下时前端调用 begin_window_drag，后端把它转换成标题栏上的按下 (WM_NCLBUTTONDOWN + HTCAPTION)，
+//   由系统的移动循环处理，拖到屏幕边缘贴靠 (Aero Snap)、摇动最小化其他窗口都和系统标题栏一样
+// - 鼠标停在最大化按钮上时前端调用 show_snap_layouts，发送 Win+Z 打开 Windows 11 的贴靠布局
+// - 原生标签栏 (native_tabs.rs) 空白处按下时直接调用 begin_drag
+
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM},
+    UI::HiDpi::{GetDpiForWindow, GetSystemMetricsForDpi},
+    UI::Input::KeyboardAndMouse::{ReleaseCapture, SendInput, INPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP, VK_LWIN, VK_Z},
+    UI::Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+    UI::WindowsAndMessaging::*,
+};
+
+#[cfg(windows)]
+const SUBCLASS_ID: usize = 0x5748_4348; // "WHCH"
+
+// 主窗口，未开启时为 0
+static HOST: AtomicIsize = AtomicIsize::new(0);
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 标题栏按钮的操作
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowControl {
+    Minimize,
+    ToggleMaximize,
+    Close,
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// 按设置开启或关闭自定义边框 (启动和设置变化时调用)
+pub fn apply(app: &AppHandle) {
+    let enabled = crate::settings::get().appearance.custom_chrome;
+    if ENABLED.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    let Some(main) = app.get_webview_window("main") else { return };
+    #[cfg(windows)]
+    {
+        let Ok(hwnd) = main.hwnd() else { return };
+        let host = hwnd.0 as isize;
+        HOST.store(host, Ordering::SeqCst);
+        let _ = app.run_on_main_thread(move || unsafe {
+            let hwnd = HWND(host as *mut _);
+            if enabled {
+                let _ = SetWindowSubclass(hwnd, Some(chrome_subclass_proc), SUBCLASS_ID, 0);
+            } else {
+                let _ = RemoveWindowSubclass(hwnd, Some(chrome_subclass_proc), SUBCLASS_ID);
+            }
+            // 让系统按新的规则重新计算非客户区
+            let _ = SetWindowPos(hwnd, None, 0, 0, 0, 0, SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE);
+        });
+    }
+    #[cfg(not(windows))]
+    let _ = main;
+    info!(target: "settings", "自定义窗口边框: {}", if enabled { "开启" } else { "关闭" });
+}
+
+/// 开始拖动主窗口 (鼠标左键在标签栏空白处按下时)
+pub fn begin_drag(app: &AppHandle) {
+    let host = HOST.load(Ordering::SeqCst);
+    if host == 0 || !is_enabled() {
+        return;
+    }
+    #[cfg(windows)]
+    let _ = app.run_on_main_thread(move || unsafe {
+        let mut cursor = POINT::default();
+        let _ = GetCursorPos(&mut cursor);
+        let _ = ReleaseCapture();
+        let position = ((cursor.y as u16 as u32) << 16) | cursor.x as u16 as u32;
+        let _ = PostMessageW(HWND(host as *mut _), WM_NCLBUTTONDOWN, WPARAM(HTCAPTION as usize), LPARAM(position as isize));
+    });
+    #[cfg(not(windows))]
+    let _ = app;
+}
+
+/// 标题栏按钮：交给系统命令处理，关闭时和系统标题栏的关闭按钮一样走 CloseRequested
+pub fn control(action: WindowControl) -> Result<(), String> {
+    let host = HOST.load(Ordering::SeqCst);
+    if host == 0 || !is_enabled() {
+        return Err("自定义窗口边框未开启".to_string());
+    }
+    #[cfg(windows)]
+    unsafe {
+        let hwnd = HWND(host as *mut _);
+        let command = match action {
+            WindowControl::Minimize => SC_MINIMIZE,
+            WindowControl::ToggleMaximize if IsZoomed(hwnd).as_bool() => SC_RESTORE,
+            WindowControl::ToggleMaximize => SC_MAXIMIZE,
+            WindowControl::Close => SC_CLOSE,
+        };
+        PostMessageW(hwnd, WM_SYSCOMMAND, WPARAM(command as usize), LPARAM(0)).map_err(|e| format!("窗口操作失败: {}", e))?;
+    }
+    #[cfg(not(windows))]
+    let _ = action;
+    Ok(())
+}
+
+/// 打开 Windows 11 的贴靠布局 (Win+Z)，只在 WindowHub 是前台窗口时发送
+pub fn show_snap_layouts() {
+    let host = HOST.load(Ordering::SeqCst);
+    if host == 0 || !is_enabled() {
+        return;
+    }
+    #[cfg(windows)]
+    unsafe {
+        if GetForegroundWindow().0 as isize != host {
+            return;
+        }
+        let key = |vk: u16, flags| crate::keys::key_input(vk, 0, flags);
+        let inputs: [INPUT; 4] = [
+            key(VK_LWIN.0, KEYBD_EVENT_FLAGS(0)),
+            key(VK_Z.0, KEYBD_EVENT_FLAGS(0)),
+            key(VK_Z.0, KEYEVENTF_KEYUP),
+            key(VK_LWIN.0, KEYEVENTF_KEYUP),
+        ];
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn chrome_subclass_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM, _id: usize, _data: usize) -> LRESULT {
+    match msg {
+        WM_NCCALCSIZE if wparam.0 != 0 => {
+            let params = &mut *(lparam.0 as *mut NCCALCSIZE_PARAMS);
+            let top = params.rgrc[0].top;
+            let result = DefSubclassProc(hwnd, msg, wparam, lparam);
+            // 默认处理会留出标题栏，改回原来的顶部；最大化时窗口超出屏幕的部分 (边框宽度) 仍然要留出
+            params.rgrc[0].top = top + if IsZoomed(hwnd).as_bool() { frame_height(hwnd) } else { 1 };
+            result
+        }
+        WM_NCHITTEST => {
+            let result = DefSubclassProc(hwnd, msg, wparam, lparam);
+            if IsZoomed(hwnd).as_bool() || !matches!(result.0 as u32, HTCLIENT | HTCAPTION | HTTOP | HTNOWHERE) {
+                return result;
+            }
+            let x = (lparam.0 & 0xFFFF) as i16 as i32;
+            let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
+            let mut window = RECT::default();
+            let _ = GetWindowRect(hwnd, &mut window);
+            let frame = frame_height(hwnd);
+            if y >= window.top + frame {
+                return result;
+            }
+            let code = if x < window.left + frame * 2 {
+                HTTOPLEFT
+            } else if x >= window.right - frame * 2 {
+                HTTOPRIGHT
+            } else {
+                HTTOP
+            };
+            LRESULT(code as isize)
+        }
+        _ => DefSubclassProc(hwnd, msg, wparam, lparam),
+    }
+}
+
+// 调整大小边框的高度 (按窗口的 DPI)
+#[cfg(windows)]
+unsafe fn frame_height(hwnd: HWND) -> i32 {
+    let dpi = GetDpiForWindow(hwnd);
+    GetSystemMetricsForDpi(SM_CYFRAME, dpi) + GetSystemMetricsForDpi(SM_CXPADDEDBORDER, dpi)
+}
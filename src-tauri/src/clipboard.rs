@@ -0,0 +1,426 @@
+// 剪贴板历史
+// 可选功能 (settings.clipboard.enabled)：隐藏的消息窗口通过 AddClipboardFormatListener 接收剪贴板变化，
+// 只在 WindowHub 或其嵌入的应用处于前台时记录文本和图片，其他应用复制的内容 (如密码管理器) 不会被记录
+// 历史只保存在内存中，停用时清空
+// - get_clipboard_history: 最近的记录 (新的在前)
+// - paste_history_item(target_hwnd, id): 把记录放回剪贴板，激活标签并发送 Ctrl+V
+// 记录变化时发送 clipboard-history-changed
+
+use base64::Engine;
+use jpeg_encoder::{ColorType, Encoder};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicBool, AtomicIsize};
+#[cfg(windows)]
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::{GlobalFree, HANDLE, HGLOBAL, HWND, LPARAM, LRESULT, WPARAM},
+        System::DataExchange::{
+            AddClipboardFormatListener, CloseClipboard, EmptyClipboard, GetClipboardData, IsClipboardFormatAvailable,
+            OpenClipboard, RemoveClipboardFormatListener, SetClipboardData,
+        },
+        System::LibraryLoader::GetModuleHandleW,
+        System::Memory::{GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE},
+        UI::WindowsAndMessaging::*,
+    },
+};
+
+// 标准剪贴板格式，定义在 Win32_System_Ole 中，为两个常量不引入整个模块
+#[cfg(windows)]
+const CF_DIB: u32 = 8;
+#[cfg(windows)]
+const CF_UNICODETEXT: u32 = 13;
+
+// 单条记录的上限，超过的 (大段文本、大图) 不记录
+#[cfg(windows)]
+const MAX_ENTRY_BYTES: usize = 32 * 1024 * 1024;
+
+const PREVIEW_WIDTH: usize = 240;
+const PREVIEW_HEIGHT: usize = 150;
+const PREVIEW_QUALITY: u8 = 75;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+static HISTORY: Mutex<Vec<ClipboardEntry>> = Mutex::new(Vec::new());
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
+// 监听线程是否在运行，以及它的消息窗口 (停用时向其发送 WM_CLOSE)
+#[cfg(windows)]
+static RUNNING: AtomicBool = AtomicBool::new(false);
+#[cfg(windows)]
+static LISTENER: AtomicIsize = AtomicIsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum ClipboardKind {
+    Text,
+    Image,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardEntry {
+    pub id: u64,
+    pub time: u64, // Unix 毫秒
+    pub kind: ClipboardKind,
+    pub text: Option<String>,
+    pub preview: Option<String>, // 图片的 JPEG 缩略图 (data URL)
+    #[serde(skip)]
+    dib: Vec<u8>, // 图片的原始 DIB 数据，粘贴时放回剪贴板
+}
+
+impl ClipboardEntry {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn same_content(&self, other: &ClipboardEntry) -> bool {
+        self.kind == other.kind && self.text == other.text && self.dib == other.dib
+    }
+}
+
+/// 保存 AppHandle 并按设置启动监听 (setup 时调用一次)
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    apply();
+}
+
+/// 设置变化后启动/停止监听，并按新的条数上限裁剪历史
+pub fn apply() {
+    let settings = crate::settings::get().clipboard;
+    if settings.enabled {
+        start();
+        HISTORY.lock().unwrap().truncate(settings.max_entries);
+    } else {
+        stop();
+        HISTORY.lock().unwrap().clear();
+    }
+    notify_changed();
+}
+
+/// 最近的记录 (新的在前)
+pub fn history() -> Vec<ClipboardEntry> {
+    HISTORY.lock().unwrap().clone()
+}
+
+fn notify_changed() {
+    if let Some(app) = APP.get() {
+        let _ = app.emit("clipboard-history-changed", history());
+    }
+}
+
+// 相同内容再次复制时移到最前面 (包括粘贴历史记录时写回剪贴板)，超过上限时丢弃最旧的
+#[cfg_attr(not(windows), allow(dead_code))]
+fn push_entry(history: &mut Vec<ClipboardEntry>, entry: ClipboardEntry, max_entries: usize) {
+    history.retain(|e| !e.same_content(&entry));
+    history.insert(0, entry);
+    history.truncate(max_entries);
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn record(kind: ClipboardKind, text: Option<String>, dib: Vec<u8>) {
+    let preview = if kind == ClipboardKind::Image { dib_preview(&dib, PREVIEW_WIDTH, PREVIEW_HEIGHT) } else { None };
+    let entry = ClipboardEntry {
+        id: LAST_ID.fetch_add(1, Ordering::SeqCst) + 1,
+        time: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        kind,
+        text,
+        preview,
+        dib,
+    };
+    push_entry(&mut HISTORY.lock().unwrap(), entry, crate::settings::get().clipboard.max_entries);
+    notify_changed();
+}
+
+/// 剪贴板 DIB (BITMAPINFOHEADER + 像素) 缩小为 JPEG 预览，只支持未压缩的 24/32 位图片
+#[cfg_attr(not(windows), allow(dead_code))]
+fn dib_preview(dib: &[u8], max_width: usize, max_height: usize) -> Option<String> {
+    let u32_at = |i: usize| dib.get(i..i + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    let header_size = u32_at(0)? as usize;
+    let width = u32_at(4)? as i32;
+    let height = u32_at(8)? as i32;
+    let bit_count = dib.get(14..16).map(|b| u16::from_le_bytes([b[0], b[1]]))? as usize;
+    let compression = u32_at(16)?;
+    let colors_used = u32_at(32)? as usize;
+    // BI_RGB，或 32 位的 BI_BITFIELDS (标准 BGRA 掩码)
+    if !(bit_count == 24 || bit_count == 32) || !(compression == 0 || (compression == 3 && bit_count == 32)) {
+        return None;
+    }
+    if width <= 0 || height == 0 {
+        return None;
+    }
+    let (width, rows) = (width as usize, height.unsigned_abs() as usize);
+    let masks = if compression == 3 && header_size == 40 { 12 } else { 0 };
+    let offset = header_size + masks + colors_used * 4;
+    let stride = (width * bit_count).div_ceil(32) * 4;
+    let pixels = dib.get(offset..offset + stride * rows)?;
+
+    let scale = (max_width as f64 / width as f64).min(max_height as f64 / rows as f64).min(1.0);
+    let out_width = ((width as f64 * scale) as usize).max(1);
+    let out_height = ((rows as f64 * scale) as usize).max(1);
+    let bytes_per_pixel = bit_count / 8;
+    let mut bgra = Vec::with_capacity(out_width * out_height * 4);
+    for y in 0..out_height {
+        let src_y = y * rows / out_height;
+        // 高度为正时是自下而上存储
+        let row = if height > 0 { rows - 1 - src_y } else { src_y };
+        for x in 0..out_width {
+            let i = row * stride + (x * width / out_width) * bytes_per_pixel;
+            bgra.extend_from_slice(&[pixels[i], pixels[i + 1], pixels[i + 2], 255]);
+        }
+    }
+
+    let mut jpeg = Vec::new();
+    Encoder::new(&mut jpeg, PREVIEW_QUALITY)
+        .encode(&bgra, out_width as u16, out_height as u16, ColorType::Bgra)
+        .ok()?;
+    Some(format!("data:image/jpeg;base64,{}", base64::engine::general_purpose::STANDARD.encode(&jpeg)))
+}
+
+/// 把历史记录放回剪贴板
+pub fn restore(id: u64) -> Result<(), String> {
+    let entry = HISTORY.lock().unwrap().iter().find(|e| e.id == id).cloned().ok_or("剪贴板记录不存在")?;
+    #[cfg(windows)]
+    unsafe {
+        match entry.kind {
+            ClipboardKind::Text => {
+                let text = entry.text.unwrap_or_default();
+                let bytes: Vec<u8> = text.encode_utf16().chain([0]).flat_map(|u| u.to_le_bytes()).collect();
+                write_clipboard(CF_UNICODETEXT, &bytes)
+            }
+            ClipboardKind::Image => write_clipboard(CF_DIB, &entry.dib),
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = entry;
+        Err("仅支持 Windows".to_string())
+    }
+}
+
+// ============================================================
+// 监听线程 (Windows)
+// ============================================================
+
+#[cfg(windows)]
+fn start() {
+    if RUNNING.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+        std::thread::spawn(|| unsafe {
+            listener_thread();
+            RUNNING.store(false, Ordering::SeqCst);
+        });
+    }
+}
+
+#[cfg(windows)]
+fn stop() {
+    let hwnd = LISTENER.load(Ordering::SeqCst);
+    if hwnd != 0 {
+        unsafe {
+            let _ = PostMessageW(HWND(hwnd as *mut _), WM_CLOSE, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn start() {}
+
+#[cfg(not(windows))]
+fn stop() {}
+
+#[cfg(windows)]
+unsafe fn listener_thread() {
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let class_name = w!("WindowHubClipboard");
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(listener_wnd_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+    // 仅接收消息的窗口，不会出现在任何地方
+    let hwnd = match CreateWindowExW(WINDOW_EX_STYLE(0), class_name, w!(""), WINDOW_STYLE(0), 0, 0, 0, 0, HWND_MESSAGE, None, instance, None) {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+            warn!(target: "events", "创建剪贴板监听窗口失败: {:?}", e);
+            return;
+        }
+    };
+    if let Err(e) = AddClipboardFormatListener(hwnd) {
+        warn!(target: "events", "注册剪贴板监听失败: {:?}", e);
+        let _ = DestroyWindow(hwnd);
+        return;
+    }
+    LISTENER.store(hwnd.0 as isize, Ordering::SeqCst);
+    // 启动过程中设置已被关闭
+    if !crate::settings::get().clipboard.enabled {
+        let _ = PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+    }
+    info!(target: "events", "剪贴板历史已启用");
+
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+    }
+    info!(target: "events", "剪贴板历史已停用");
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn listener_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_CLIPBOARDUPDATE => {
+            on_clipboard_update(hwnd);
+            LRESULT(0)
+        }
+        WM_CLOSE => {
+            let _ = RemoveClipboardFormatListener(hwnd);
+            let _ = DestroyWindow(hwnd);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            LISTENER.store(0, Ordering::SeqCst);
+            PostQuitMessage(0);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+// 只记录 WindowHub 自身或嵌入应用 (含其对话框) 在前台时的复制
+#[cfg(windows)]
+unsafe fn in_scope() -> bool {
+    let foreground = GetForegroundWindow();
+    foreground == crate::win_events::host_hwnd() || crate::win_events::find_embedded_tab(foreground).is_some()
+}
+
+#[cfg(windows)]
+unsafe fn on_clipboard_update(owner: HWND) {
+    if !in_scope() {
+        return;
+    }
+    // 复制方可能还没关闭剪贴板，稍等重试
+    let opened = (0..5).any(|attempt| {
+        if attempt > 0 {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        OpenClipboard(owner).is_ok()
+    });
+    if !opened {
+        return;
+    }
+    let content = if IsClipboardFormatAvailable(CF_UNICODETEXT).is_ok() {
+        read_global(CF_UNICODETEXT).map(|bytes| {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .take_while(|u| *u != 0)
+                .collect();
+            (ClipboardKind::Text, Some(String::from_utf16_lossy(&units)), Vec::new())
+        })
+    } else if IsClipboardFormatAvailable(CF_DIB).is_ok() {
+        read_global(CF_DIB).map(|dib| (ClipboardKind::Image, None, dib))
+    } else {
+        None
+    };
+    let _ = CloseClipboard();
+
+    match content {
+        Some((ClipboardKind::Text, Some(text), _)) if text.trim().is_empty() => {}
+        Some((kind, text, dib)) => record(kind, text, dib),
+        None => {}
+    }
+}
+
+#[cfg(windows)]
+unsafe fn read_global(format: u32) -> Option<Vec<u8>> {
+    let handle = GetClipboardData(format).ok()?;
+    let global = HGLOBAL(handle.0);
+    let size = GlobalSize(global);
+    if size == 0 || size > MAX_ENTRY_BYTES {
+        return None;
+    }
+    let ptr = GlobalLock(global) as *const u8;
+    if ptr.is_null() {
+        return None;
+    }
+    let bytes = std::slice::from_raw_parts(ptr, size).to_vec();
+    let _ = GlobalUnlock(global);
+    Some(bytes)
+}
+
+#[cfg(windows)]
+unsafe fn write_clipboard(format: u32, bytes: &[u8]) -> Result<(), String> {
+    let owner = HWND(LISTENER.load(Ordering::SeqCst) as *mut _);
+    OpenClipboard(owner).map_err(|e| format!("无法打开剪贴板: {}", e))?;
+    let result = (|| {
+        EmptyClipboard().map_err(|e| format!("无法清空剪贴板: {}", e))?;
+        let global = GlobalAlloc(GMEM_MOVEABLE, bytes.len()).map_err(|e| format!("分配内存失败: {}", e))?;
+        let ptr = GlobalLock(global) as *mut u8;
+        if ptr.is_null() {
+            let _ = GlobalFree(global);
+            return Err("分配内存失败".to_string());
+        }
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+        let _ = GlobalUnlock(global);
+        // 成功后内存归剪贴板所有，失败时需要自己释放
+        if let Err(e) = SetClipboardData(format, HANDLE(global.0)) {
+            let _ = GlobalFree(global);
+            return Err(format!("写入剪贴板失败: {}", e));
+        }
+        Ok(())
+    })();
+    let _ = CloseClipboard();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_entry(id: u64, text: &str) -> ClipboardEntry {
+        ClipboardEntry { id, time: 0, kind: ClipboardKind::Text, text: Some(text.to_string()), preview: None, dib: Vec::new() }
+    }
+
+    #[test]
+    fn repeated_copy_moves_entry_to_front() {
+        let mut history = Vec::new();
+        for (id, text) in [(1, "a"), (2, "b"), (3, "c"), (4, "a")] {
+            push_entry(&mut history, text_entry(id, text), 3);
+        }
+        let ids: Vec<u64> = history.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![4, 3, 2]);
+
+        push_entry(&mut history, text_entry(5, "d"), 3);
+        assert_eq!(history.iter().map(|e| e.text.as_deref().unwrap()).collect::<Vec<_>>(), vec!["d", "a", "c"]);
+    }
+
+    #[test]
+    fn previews_uncompressed_dibs_only() {
+        // 2x2 24 位 BI_RGB，每行 6 字节像素 + 2 字节对齐
+        let mut dib = vec![0u8; 40];
+        dib[0] = 40;
+        dib[4] = 2;
+        dib[8] = 2;
+        dib[12] = 1;
+        dib[14] = 24;
+        dib.extend(std::iter::repeat(0x80).take(16));
+        assert!(dib_preview(&dib, 240, 150).unwrap().starts_with("data:image/jpeg;base64,"));
+
+        // 像素数据不完整
+        assert!(dib_preview(&dib[..50], 240, 150).is_none());
+
+        // 8 位调色板图片不支持
+        dib[14] = 8;
+        assert!(dib_preview(&dib, 240, 150).is_none());
+    }
+}
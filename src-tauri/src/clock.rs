@@ -0,0 +1,9 @@
+// 墙上时间 (Unix 时间戳)，保存到数据库或发给前端的时间都用它
+
+/// 当前的 Unix 时间 (秒)
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
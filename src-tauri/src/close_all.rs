@@ -0,0 +1,191 @@
+// 关闭或释放所有标签
+// 之前由前端逐个调用 request_close / detach_window，遇到一个卡住的应用后面的都停下来。
+// close_all_tabs(policy) 在后端依次处理每个标签 (固定的标签跳过)，按策略决定每个标签的处理方式：
+// - release：释放回桌面
+// - close：请求关闭 (同 request_close)，弹出保存提示的标签保留，拒绝关闭时按 force_if_refused 强制结束或保留
+// - force_kill：直接结束进程树
+// 策略可以按程序 (EXE 文件名) 指定，其他标签使用 default。
+// 作为长时间操作执行 (见 operations.rs)，每处理完一个标签发送 close-all-progress，结束时 operation-finished 的结果为 CloseAllReport
+// 释放、强制结束和清理都在阻塞线程上执行，每一步最多等 STEP_TIMEOUT，超时的标签记为失败，继续处理下一个
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::close_request::{self, CloseOutcome};
+use crate::lifecycle::ReleaseReason;
+use crate::operations::Progress;
+use crate::window_manager::{EmbeddedWindow, WindowManager};
+
+// 每一步的最长等待时间，超时后卡住的调用留在后台线程上
+const STEP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseAction {
+    Release,
+    #[default]
+    Close,
+    ForceKill,
+}
+
+/// 某个程序的标签的处理方式，如 { "app": "code.exe", "action": "release" }
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AppCloseAction {
+    pub app: String, // EXE 文件名 (不区分大小写)
+    pub action: CloseAction,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ClosePolicy {
+    pub default: CloseAction,
+    pub apps: Vec<AppCloseAction>,
+    pub force_if_refused: bool, // 请求关闭被拒绝 (没有弹出确认对话框) 时强制结束
+}
+
+impl ClosePolicy {
+    /// 标签的处理方式，固定的标签为 None
+    pub fn action_for(&self, window: &EmbeddedWindow) -> Option<CloseAction> {
+        if window.pinned {
+            return None;
+        }
+        let exe_name = window.exe_path.as_deref().map(|p| p.rsplit(['\\', '/']).next().unwrap_or(p));
+        let rule = exe_name.and_then(|name| self.apps.iter().find(|a| a.app.eq_ignore_ascii_case(name)));
+        Some(rule.map_or(self.default, |r| r.action))
+    }
+}
+
+/// 一个标签的处理结果，result 为 released / closed / killed / confirming / refused / failed
+#[derive(Debug, Clone, Serialize)]
+pub struct TabCloseResult {
+    pub hwnd: isize,
+    pub title: String,
+    pub action: CloseAction,
+    pub result: &'static str,
+    pub error: Option<String>,
+}
+
+/// close-all-progress 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseAllProgress {
+    pub op_id: u64,
+    pub done: usize,
+    pub total: usize,
+    pub tab: TabCloseResult,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseAllReport {
+    pub results: Vec<TabCloseResult>,
+    pub skipped_pinned: Vec<isize>,
+}
+
+/// 按策略依次处理所有标签
+pub async fn run(app: AppHandle, policy: ClosePolicy, progress: Progress) -> Result<CloseAllReport, String> {
+    let windows = app.state::<WindowManager>().list();
+    let (targets, pinned): (Vec<_>, Vec<_>) = windows.into_iter().partition(|w| !w.pinned);
+    let skipped_pinned = pinned.iter().map(|w| w.hwnd).collect();
+    let total = targets.len();
+    info!(target: "embed", "关闭所有标签: {} 个，跳过 {} 个固定的标签", total, pinned.len());
+
+    let mut results = Vec::new();
+    for (i, window) in targets.into_iter().enumerate() {
+        let Some(action) = policy.action_for(&window) else { continue };
+        let (result, error) = match handle(&app, &window, action, policy.force_if_refused).await {
+            Ok(result) => (result, None),
+            Err(e) => {
+                warn!(target: "embed", "关闭标签失败: hwnd={}, {}", window.hwnd, e);
+                ("failed", Some(e))
+            }
+        };
+        let tab = TabCloseResult { hwnd: window.hwnd, title: crate::privacy::title(window.title.clone()), action, result, error };
+        progress.emit("close-all-progress", |op_id| CloseAllProgress { op_id, done: i + 1, total, tab: tab.clone() });
+        results.push(tab);
+    }
+    Ok(CloseAllReport { results, skipped_pinned })
+}
+
+async fn handle(app: &AppHandle, window: &EmbeddedWindow, action: CloseAction, force_if_refused: bool) -> Result<&'static str, String> {
+    let platform = crate::platform::current();
+    let hwnd = window.hwnd;
+    // 处理前面的标签时应用自己关闭了
+    if !platform.is_window(hwnd) {
+        forget(app, hwnd).await?;
+        return Ok("closed");
+    }
+    match action {
+        CloseAction::Release => {
+            let app = app.clone();
+            blocking("释放标签", move || {
+                let manager = app.state::<WindowManager>();
+                crate::release_tab(&app, &manager, hwnd, ReleaseReason::Detach).map_err(|e| e.message)
+            })
+            .await?;
+            Ok("released")
+        }
+        CloseAction::Close => match close_request::request(platform, hwnd).await {
+            CloseOutcome::Closed => {
+                forget(app, hwnd).await?;
+                Ok("closed")
+            }
+            CloseOutcome::Confirming { .. } => Ok("confirming"),
+            CloseOutcome::Refused if force_if_refused => kill(app, hwnd).await,
+            CloseOutcome::Refused => Ok("refused"),
+        },
+        CloseAction::ForceKill => kill(app, hwnd).await,
+    }
+}
+
+async fn kill(app: &AppHandle, hwnd: isize) -> Result<&'static str, String> {
+    let info = blocking("强制结束", move || {
+        let platform = crate::platform::current();
+        close_request::force_kill(platform, hwnd, platform.process_id(hwnd))
+    })
+    .await?;
+    warn!(target: "embed", "已强制结束进程: {} (pid={}, hwnd={})", info.exe_name, info.pid, hwnd);
+    forget(app, hwnd).await?;
+    Ok("killed")
+}
+
+// 窗口已经关闭，清理标签记录
+async fn forget(app: &AppHandle, hwnd: isize) -> Result<(), String> {
+    let app = app.clone();
+    blocking("清理标签", move || {
+        crate::forget_closed_tab(&app, hwnd);
+        Ok(())
+    })
+    .await
+}
+
+// 在阻塞线程上执行一步，最多等 STEP_TIMEOUT
+async fn blocking<T: Send + 'static>(step: &str, f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    let task = tauri::async_runtime::spawn_blocking(f);
+    match tokio::time::timeout(STEP_TIMEOUT, task).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(format!("{}中断: {}", step, e)),
+        Err(_) => Err(format!("{}超时 ({} 秒)", step, STEP_TIMEOUT.as_secs())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_action_by_app_and_skips_pinned() {
+        let policy: ClosePolicy = serde_json::from_str(r#"{"default":"release","apps":[{"app":"CODE.exe","action":"close"}]}"#).unwrap();
+        let mut window: EmbeddedWindow = serde_json::from_value(serde_json::json!({
+            "hwnd": 1, "title": "", "class_name": "", "pid": 0, "exe_path": r"C:\Apps\Code.exe",
+            "original_style": 0, "original_exstyle": 0, "original_rect": {"left": 0, "top": 0, "right": 0, "bottom": 0},
+            "original_owner": 0, "monitor": 0, "embedded_at": 0
+        }))
+        .unwrap();
+        assert_eq!(policy.action_for(&window), Some(CloseAction::Close));
+        window.exe_path = Some(r"C:\Apps\wt.exe".to_string());
+        assert_eq!(policy.action_for(&window), Some(CloseAction::Release));
+        window.pinned = true;
+        assert_eq!(policy.action_for(&window), None);
+    }
+}
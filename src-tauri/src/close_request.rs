@@ -0,0 +1,142 @@
+// 关闭嵌入窗口并判断结果
+// close_target_window 发出 WM_CLOSE 后直接移除标签，应用弹出"是否保存更改"时对话框没有了归属
+// request_close 先发出关闭请求，标签保持嵌入，在一段时间内观察：
+// - 窗口被销毁：已关闭
+// - 同一进程出现了新的可见顶层窗口：应用弹出了确认对话框，等用户处理
+// - 超时后窗口仍在也没有对话框：应用拒绝关闭 (或在后台处理，如最小化到托盘)
+// 应用卡死、关闭请求无效时用 force_kill 结束整个进程树
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::platform::Platform;
+
+const TIMEOUT: Duration = Duration::from_secs(3);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 关闭请求的结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum CloseOutcome {
+    Closed,
+    /// 应用弹出了确认对话框 (通常是保存更改提示)，窗口仍然嵌入
+    Confirming { dialog: isize, title: String },
+    Refused,
+}
+
+/// 请求关闭窗口并等待结果 (最多 TIMEOUT)
+pub async fn request(platform: &dyn Platform, hwnd: isize) -> CloseOutcome {
+    let before = platform.process_windows(hwnd);
+    platform.close(hwnd);
+
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if let Some(outcome) = check(platform, hwnd, &before) {
+            return outcome;
+        }
+    }
+    CloseOutcome::Refused
+}
+
+// 一次观察，还不能判断时返回 None
+fn check(platform: &dyn Platform, hwnd: isize, before: &[isize]) -> Option<CloseOutcome> {
+    if !platform.is_window(hwnd) {
+        return Some(CloseOutcome::Closed);
+    }
+    platform
+        .process_windows(hwnd)
+        .into_iter()
+        .find(|h| !before.contains(h))
+        .map(|dialog| CloseOutcome::Confirming { dialog, title: crate::privacy::title(platform.title(dialog)) })
+}
+
+/// 窗口所属的进程，强制结束前给用户确认
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub exe_name: String,
+    pub exe_path: String,
+}
+
+/// 窗口所属的进程信息
+pub fn process_info(platform: &dyn Platform, hwnd: isize) -> Result<ProcessInfo, String> {
+    if !platform.is_window(hwnd) {
+        return Err("无效的窗口句柄".to_string());
+    }
+    if platform.is_own_window(hwnd) {
+        return Err("不能结束 WindowHub 自身".to_string());
+    }
+    let pid = platform.process_id(hwnd);
+    if pid == 0 {
+        return Err("无法获取进程 ID".to_string());
+    }
+    let exe_path = platform.process_path(hwnd).unwrap_or_default();
+    let exe_name = std::path::Path::new(&exe_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(ProcessInfo { pid, exe_name, exe_path })
+}
+
+/// 强制结束窗口所属的进程树，confirmed_pid 是用户确认时看到的进程，
+/// 与窗口当前所属进程不一致 (窗口已关闭、句柄被复用) 时拒绝
+pub fn force_kill(platform: &dyn Platform, hwnd: isize, confirmed_pid: u32) -> Result<ProcessInfo, String> {
+    let info = process_info(platform, hwnd)?;
+    if info.pid != confirmed_pid {
+        return Err("窗口所属的进程已变化，请重新确认".to_string());
+    }
+    crate::crash_watch::expect_exit(info.pid);
+    platform.kill_process_tree(info.pid)?;
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::{MockClose, MockPlatform};
+
+    fn close_once(platform: &MockPlatform, hwnd: isize) -> Option<CloseOutcome> {
+        let before = platform.process_windows(hwnd);
+        platform.close(hwnd);
+        check(platform, hwnd, &before)
+    }
+
+    #[test]
+    fn detects_how_the_app_reacted_to_close() {
+        let platform = MockPlatform::new();
+        // 同一进程已经打开的其他窗口不算确认对话框
+        let editor = platform.create_window("未命名 - 记事本", "Notepad", None);
+        let other = platform.create_window("另一个窗口", "Notepad", None);
+        assert!(platform.process_windows(editor).contains(&other));
+
+        platform.set_on_close(editor, MockClose::Ignore);
+        assert_eq!(close_once(&platform, editor), None);
+
+        platform.set_on_close(editor, MockClose::Prompt);
+        let Some(CloseOutcome::Confirming { dialog, title }) = close_once(&platform, editor) else {
+            panic!("应检测到确认对话框");
+        };
+        assert_ne!(dialog, other);
+        assert_eq!(title, "保存更改?");
+
+        platform.set_on_close(editor, MockClose::Exit);
+        assert_eq!(close_once(&platform, editor), Some(CloseOutcome::Closed));
+    }
+
+    #[test]
+    fn force_kill_requires_the_confirmed_pid() {
+        let platform = MockPlatform::new();
+        let hung = platform.create_window("未响应", "Notepad", Some("C:/Windows/notepad.exe"));
+        let own = platform.create_own_window("WindowHub");
+        assert!(process_info(&platform, own).is_err());
+
+        let info = process_info(&platform, hung).unwrap();
+        assert_eq!(info.exe_name, "notepad.exe");
+        assert!(force_kill(&platform, hung, info.pid + 1).is_err());
+        assert!(platform.is_window(hung));
+
+        assert_eq!(force_kill(&platform, hung, info.pid), Ok(info));
+        assert!(!platform.is_window(hung));
+    }
+}
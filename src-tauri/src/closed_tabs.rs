@@ -0,0 +1,169 @@
+// 最近关闭的标签 (类似浏览器的 Ctrl+Shift+T)
+// 关闭标签 (或标签的窗口自己关闭) 时记下如何重新启动它 (程序路径、启动参数) 和它在布局中的位置 (标签序号、停靠区域)，
+// 以及名称、分组、颜色、快捷键、自动重启；最多保留 MAX_CLOSED 个，只保存在内存中。
+// 弹出的标签窗口仍在桌面上，不记录
+// reopen_last_tab (快捷键 shortcuts.reopen_tab，默认 Ctrl+Shift+T) 重新启动最近关闭的一个并嵌入，
+// 恢复上面的属性后停靠到原来的区域，发送 tab-reopened (前端把标签移回原来的序号)；
+// 窗口其实没有关闭的 (应用弹出保存提示后取消了关闭) 跳过并丢弃，以免启动出第二个；
+// 启动失败时放回列表，可以再试。展台模式下不能重新打开。无法获取程序路径的标签不能重新启动，不记录
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::operations::Progress;
+use crate::pinned_apps::LaunchSpec;
+use crate::window_manager::{EmbeddedWindow, TabColor, WindowManager};
+use crate::zones::Zone;
+
+const MAX_CLOSED: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosedTab {
+    pub launch: LaunchSpec,
+    #[serde(serialize_with = "crate::privacy::serialize_title")]
+    pub title: String,
+    #[serde(serialize_with = "crate::privacy::serialize_label")]
+    pub label: Option<String>,
+    pub group: Option<String>,
+    pub color: Option<TabColor>,
+    pub hotkey: Option<String>,
+    pub auto_restart: bool,
+    pub position: usize, // 关闭前的标签序号
+    pub zone: Zone,
+    #[serde(skip)]
+    hwnd: isize, // 关闭前的窗口
+}
+
+/// tab-reopened 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct TabReopened {
+    pub window: EmbeddedWindow,
+    pub position: usize,
+}
+
+// 最近关闭的在最后
+static CLOSED: Mutex<Vec<ClosedTab>> = Mutex::new(Vec::new());
+
+/// 标签关闭时记录 (position 为关闭前的序号，zone 为停靠区域)
+pub fn record(window: &EmbeddedWindow, position: usize, zone: Zone) {
+    let Some(exe_path) = window.exe_path.clone() else { return };
+    let tab = ClosedTab {
+        launch: LaunchSpec { path: exe_path, args: window.args.clone(), exe: None },
+        title: window.title.clone(),
+        label: window.label.clone(),
+        group: window.group.clone(),
+        color: window.color,
+        hotkey: window.hotkey.clone(),
+        auto_restart: window.auto_restart,
+        position,
+        zone,
+        hwnd: window.hwnd,
+    };
+    push(&mut CLOSED.lock().unwrap(), tab);
+}
+
+/// 最近关闭的标签，最近的在前
+pub fn list() -> Vec<ClosedTab> {
+    CLOSED.lock().unwrap().iter().rev().cloned().collect()
+}
+
+/// 重新打开最近关闭的标签，没有可以重新打开的标签时返回 Err
+pub fn reopen_last(app: &AppHandle) -> Result<u64, String> {
+    crate::kiosk::ensure_unlocked("重新打开标签")?;
+    let platform = crate::platform::current();
+    let tab = take_last(&mut CLOSED.lock().unwrap(), |hwnd| platform.is_window(hwnd)).ok_or("没有最近关闭的标签")?;
+    let handle = app.clone();
+    Ok(crate::operations::start(app, "reopen_tab", true, |progress| async move {
+        let result = reopen(&handle, &tab, &progress).await;
+        if let Err(e) = &result {
+            warn!(target: "launcher", "重新打开标签失败: {} - {}", tab.launch.path, e);
+            push(&mut CLOSED.lock().unwrap(), tab);
+        }
+        result
+    }))
+}
+
+async fn reopen(app: &AppHandle, tab: &ClosedTab, progress: &Progress) -> Result<TabReopened, String> {
+    let hwnd = crate::launch_and_wait(&tab.launch.path, &tab.launch.args, progress).await?;
+    let manager = app.state::<WindowManager>();
+    let window = crate::embed_tab(app, &manager, hwnd)?;
+    manager.set_label(hwnd, tab.label.clone());
+    manager.set_group(hwnd, tab.group.clone());
+    manager.set_color(hwnd, tab.color);
+    manager.set_auto_restart(hwnd, tab.auto_restart);
+    if let Some(hotkey) = &tab.hotkey {
+        if let Err(e) = crate::tab_hotkeys::bind(app, &manager, window.tab_id, hotkey) {
+            warn!(target: "shortcuts", "恢复标签快捷键失败: {}", e);
+        }
+    }
+    if tab.zone != Zone::Full {
+        let changed = crate::zones::dock(hwnd, tab.zone);
+        crate::relayout(app);
+        let _ = app.emit("tab-zone-changed", changed);
+    }
+    info!(target: "launcher", "已重新打开标签: {} (hwnd={}, 序号 {})", tab.launch.path, hwnd, tab.position);
+    let reopened = TabReopened { window: manager.get(hwnd).unwrap_or(window), position: tab.position };
+    let _ = app.emit("tab-reopened", reopened.clone());
+    Ok(reopened)
+}
+
+// 取出最近关闭、窗口确实已经不存在的标签，窗口还在的丢弃
+fn take_last(closed: &mut Vec<ClosedTab>, exists: impl Fn(isize) -> bool) -> Option<ClosedTab> {
+    while let Some(tab) = closed.pop() {
+        if !exists(tab.hwnd) {
+            return Some(tab);
+        }
+        info!(target: "launcher", "标签的窗口仍然存在，不重新打开: {} (hwnd={})", tab.launch.path, tab.hwnd);
+    }
+    None
+}
+
+fn push(closed: &mut Vec<ClosedTab>, tab: ClosedTab) {
+    closed.push(tab);
+    if closed.len() > MAX_CLOSED {
+        closed.remove(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(path: &str) -> ClosedTab {
+        ClosedTab {
+            launch: LaunchSpec { path: path.to_string(), args: Vec::new(), exe: None },
+            title: String::new(),
+            label: None,
+            group: None,
+            color: None,
+            hotkey: None,
+            auto_restart: false,
+            position: 0,
+            zone: Zone::Full,
+            hwnd: 0,
+        }
+    }
+
+    #[test]
+    fn keeps_most_recent_tabs() {
+        let mut closed = Vec::new();
+        for i in 0..=MAX_CLOSED {
+            push(&mut closed, tab(&format!("{}.exe", i)));
+        }
+        assert_eq!(closed.len(), MAX_CLOSED);
+        assert_eq!(closed[0].launch.path, "1.exe");
+        assert_eq!(closed.last().unwrap().launch.path, format!("{}.exe", MAX_CLOSED));
+    }
+
+    #[test]
+    fn skips_tabs_whose_window_still_exists() {
+        let mut closed = vec![ClosedTab { hwnd: 1, ..tab("a.exe") }, ClosedTab { hwnd: 2, ..tab("b.exe") }, ClosedTab { hwnd: 3, ..tab("c.exe") }];
+        let taken = take_last(&mut closed, |hwnd| hwnd == 3).unwrap();
+        assert_eq!(taken.launch.path, "b.exe");
+        assert_eq!(closed.len(), 1);
+        assert!(take_last(&mut closed, |_| true).is_none());
+        assert!(closed.is_empty());
+    }
+}
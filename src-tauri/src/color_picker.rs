@@ -0,0 +1,208 @@
+// 取色器 (pick_color)
+// 进入取色模式后，屏幕上任意位置 (包括嵌入的子窗口) 都可以取色：
+// - 低级鼠标钩子跟踪光标，读取屏幕上光标处像素的颜色 (GetPixel 读的是合成后的画面，
+//   所以嵌入窗口、硬件加速的内容也能取到)
+// - 移动时发送 color-preview (载荷为 PickedColor)，前端显示放大镜和颜色
+// - 左键单击确认，右键或 Esc 取消 (这些点击和按键被拦截，不会传给下面的窗口)
+// 钩子回调中只记录光标位置，读取像素和发送事件在消息循环中进行，避免钩子超时
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicI32, AtomicU32};
+#[cfg(windows)]
+use tauri::Emitter;
+#[cfg(windows)]
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM},
+    Graphics::Gdi::{GetDC, GetPixel, ReleaseDC, CLR_INVALID},
+    System::LibraryLoader::GetModuleHandleW,
+    System::Threading::GetCurrentThreadId,
+    UI::Input::KeyboardAndMouse::VK_ESCAPE,
+    UI::WindowsAndMessaging::*,
+};
+
+// 光标移动后通知消息循环读取像素
+#[cfg(windows)]
+const WM_PREVIEW: u32 = WM_APP + 1;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+// 取色线程 (钩子和消息循环所在的线程)
+#[cfg(windows)]
+static THREAD_ID: AtomicU32 = AtomicU32::new(0);
+// 光标的最新位置，以及是否已经有一条 WM_PREVIEW 在排队
+#[cfg(windows)]
+static CURSOR: (AtomicI32, AtomicI32) = (AtomicI32::new(0), AtomicI32::new(0));
+#[cfg(windows)]
+static PREVIEW_PENDING: AtomicBool = AtomicBool::new(false);
+// 单击确认 (true) 还是取消
+#[cfg(windows)]
+static CONFIRMED: AtomicBool = AtomicBool::new(false);
+// 拦截了按下后，对应的松开也要拦截
+#[cfg(windows)]
+static SWALLOW_UP: AtomicBool = AtomicBool::new(false);
+
+/// 取到的颜色，x/y 为屏幕坐标 (物理像素)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PickedColor {
+    pub x: i32,
+    pub y: i32,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub hex: String,
+}
+
+impl PickedColor {
+    // GetPixel 返回的 COLORREF 为 0x00BBGGRR
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn from_colorref(x: i32, y: i32, color: u32) -> Self {
+        let (r, g, b) = (color as u8, (color >> 8) as u8, (color >> 16) as u8);
+        PickedColor { x, y, r, g, b, hex: format!("#{:02x}{:02x}{:02x}", r, g, b) }
+    }
+}
+
+/// 进入取色模式并等待结束 (阻塞调用线程)，返回单击处的颜色，取消时为 None
+pub fn pick(app: &AppHandle) -> Result<Option<PickedColor>, String> {
+    if ACTIVE.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+        return Err("已在取色".to_string());
+    }
+    if let Err(e) = crate::budget::check_hooks(2) {
+        ACTIVE.store(false, Ordering::SeqCst);
+        return Err(e.into());
+    }
+    #[cfg(windows)]
+    let result = unsafe { pick_thread(app) };
+    #[cfg(not(windows))]
+    let result = {
+        let _ = app;
+        Err("当前平台不支持取色".to_string())
+    };
+    ACTIVE.store(false, Ordering::SeqCst);
+    result
+}
+
+#[cfg(windows)]
+unsafe fn pick_thread(app: &AppHandle) -> Result<Option<PickedColor>, String> {
+    THREAD_ID.store(GetCurrentThreadId(), Ordering::SeqCst);
+    CONFIRMED.store(false, Ordering::SeqCst);
+    SWALLOW_UP.store(false, Ordering::SeqCst);
+    PREVIEW_PENDING.store(false, Ordering::SeqCst);
+    let mut pt = POINT::default();
+    let _ = GetCursorPos(&mut pt);
+    CURSOR.0.store(pt.x, Ordering::SeqCst);
+    CURSOR.1.store(pt.y, Ordering::SeqCst);
+    info!(target: "embed", "进入取色模式");
+
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let hooks = [
+        SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), instance, 0),
+        SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), instance, 0),
+    ];
+    let hook_count = hooks.iter().filter(|h| h.is_ok()).count() as u32;
+    let resource = crate::resources::register(crate::resources::ResourceKind::LowLevelHook, "color_picker", hook_count, None, None);
+    let installed = hooks.iter().all(|h| h.is_ok());
+    if installed {
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+            if msg.message == WM_PREVIEW {
+                PREVIEW_PENDING.store(false, Ordering::SeqCst);
+                if let Some(color) = color_at_cursor() {
+                    let _ = app.emit("color-preview", color);
+                }
+                continue;
+            }
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+    for hook in hooks.into_iter().flatten() {
+        let _ = UnhookWindowsHookEx(hook);
+    }
+    crate::resources::unregister(resource);
+    THREAD_ID.store(0, Ordering::SeqCst);
+
+    if !installed {
+        warn!(target: "embed", "安装取色钩子失败");
+        return Err("无法进入取色模式".to_string());
+    }
+    let picked = if CONFIRMED.load(Ordering::SeqCst) { color_at_cursor() } else { None };
+    info!(target: "embed", "退出取色模式: {:?}", picked.as_ref().map(|c| &c.hex));
+    Ok(picked)
+}
+
+// 屏幕上光标最新位置处的颜色
+#[cfg(windows)]
+unsafe fn color_at_cursor() -> Option<PickedColor> {
+    let (x, y) = (CURSOR.0.load(Ordering::SeqCst), CURSOR.1.load(Ordering::SeqCst));
+    let screen = GetDC(HWND::default());
+    let color = GetPixel(screen, x, y);
+    ReleaseDC(HWND::default(), screen);
+    (color.0 != CLR_INVALID).then(|| PickedColor::from_colorref(x, y, color.0))
+}
+
+#[cfg(windows)]
+unsafe fn end(confirmed: bool) {
+    CONFIRMED.store(confirmed, Ordering::SeqCst);
+    SWALLOW_UP.store(true, Ordering::SeqCst);
+    PostQuitMessage(0);
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        CURSOR.0.store(info.pt.x, Ordering::SeqCst);
+        CURSOR.1.store(info.pt.y, Ordering::SeqCst);
+        match wparam.0 as u32 {
+            WM_MOUSEMOVE => {
+                if !PREVIEW_PENDING.swap(true, Ordering::SeqCst) {
+                    let _ = PostThreadMessageW(THREAD_ID.load(Ordering::SeqCst), WM_PREVIEW, WPARAM(0), LPARAM(0));
+                }
+            }
+            WM_LBUTTONDOWN => {
+                end(true);
+                return LRESULT(1);
+            }
+            WM_RBUTTONDOWN => {
+                end(false);
+                return LRESULT(1);
+            }
+            WM_LBUTTONUP | WM_RBUTTONUP if SWALLOW_UP.swap(false, Ordering::SeqCst) => return LRESULT(1),
+            _ => {}
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        if info.vkCode == VK_ESCAPE.0 as u32 {
+            if wparam.0 as u32 == WM_KEYDOWN {
+                end(false);
+            }
+            return LRESULT(1);
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorref_is_bgr() {
+        let color = PickedColor::from_colorref(3, 4, 0x00_33_22_11);
+        assert_eq!((color.r, color.g, color.b), (0x11, 0x22, 0x33));
+        assert_eq!(color.hex, "#112233");
+    }
+}
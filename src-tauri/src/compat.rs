@@ -0,0 +1,162 @@
+// 应用兼容性数据库
+// 记录已知的嵌入问题 (按 EXE 文件名和窗口类名匹配)，选择窗口时提前提示用户，
+// 例如 "嵌入后语音频道的音频输出可能中断"
+// 内置条目在 compat.json 中随程序发布，用户可以在数据目录 (见 paths.rs) 的 compat.json 中补充，
+// exe 和 class 都相同的用户条目会替换内置条目
+// 条目还可以标记 poll_title，嵌入后由 title_watch 定时检查这类窗口的标题和图标
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+const BUNDLED: &str = include_str!("compat.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatLevel {
+    Info,        // 仅提示
+    Warning,     // 可以嵌入，但有已知问题
+    Unsupported, // 嵌入后无法正常使用
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompatEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exe: Option<String>, // EXE 文件名，支持 * 通配
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>, // 窗口类名，支持 * 通配
+    pub level: CompatLevel,
+    #[serde(default)]
+    pub issues: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workaround: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub poll_title: bool, // 标题变化时不可靠地发送 NAMECHANGE，由 title_watch 定时检查
+}
+
+impl CompatEntry {
+    fn same_key(&self, other: &CompatEntry) -> bool {
+        let eq = |a: &Option<String>, b: &Option<String>| match (a, b) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            (None, None) => true,
+            _ => false,
+        };
+        eq(&self.exe, &other.exe) && eq(&self.class, &other.class)
+    }
+
+    /// 给出的条件都匹配才算匹配，没有任何条件的条目不匹配
+    fn matches(&self, exe_path: Option<&str>, class_name: &str) -> bool {
+        if self.exe.is_none() && self.class.is_none() {
+            return false;
+        }
+        if let Some(exe) = self.exe.as_deref() {
+            let Some(path) = exe_path else { return false };
+            let name = path.rsplit(['\\', '/']).next().unwrap_or(path);
+            if !crate::rules::glob_match(exe, name) {
+                return false;
+            }
+        }
+        self.class.as_deref().is_none_or(|class| crate::rules::glob_match(class, class_name))
+    }
+}
+
+static USER_ENTRIES: Mutex<Vec<CompatEntry>> = Mutex::new(Vec::new());
+
+fn bundled() -> &'static [CompatEntry] {
+    static ENTRIES: OnceLock<Vec<CompatEntry>> = OnceLock::new();
+    ENTRIES.get_or_init(|| {
+        serde_json::from_str(BUNDLED).unwrap_or_else(|e| {
+            warn!(target: "embed", "内置兼容性数据库格式错误: {}", e);
+            Vec::new()
+        })
+    })
+}
+
+/// 合并内置条目和用户条目
+fn merge(bundled: &[CompatEntry], user: &[CompatEntry]) -> Vec<CompatEntry> {
+    bundled
+        .iter()
+        .filter(|entry| !user.iter().any(|u| u.same_key(entry)))
+        .chain(user)
+        .cloned()
+        .collect()
+}
+
+fn find(entries: &[CompatEntry], exe_path: Option<&str>, class_name: &str) -> Vec<CompatEntry> {
+    entries.iter().filter(|entry| entry.matches(exe_path, class_name)).cloned().collect()
+}
+
+/// 查找窗口的已知兼容性问题
+pub fn lookup(exe_path: Option<&str>, class_name: &str) -> Vec<CompatEntry> {
+    let user = USER_ENTRIES.lock().unwrap();
+    find(&merge(bundled(), &user), exe_path, class_name)
+}
+
+/// 窗口是否需要定时检查标题 (有匹配的条目标记了 poll_title)
+pub fn needs_title_polling(exe_path: Option<&str>, class_name: &str) -> bool {
+    lookup(exe_path, class_name).iter().any(|entry| entry.poll_title)
+}
+
+/// 从文件加载用户条目，返回内容是否有变化
+pub fn load_from_file() -> bool {
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("compat.json");
+
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            match serde_json::from_str::<Vec<CompatEntry>>(&content) {
+                Ok(loaded) => {
+                    let mut entries = USER_ENTRIES.lock().unwrap();
+                    if *entries == loaded {
+                        return false;
+                    }
+                    info!(target: "settings", "已加载 {} 个自定义兼容性条目", loaded.len());
+                    *entries = loaded;
+                    return true;
+                }
+                Err(e) => warn!(target: "settings", "compat.json 格式错误: {}", e),
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(exe: Option<&str>, class: Option<&str>, level: CompatLevel) -> CompatEntry {
+        CompatEntry {
+            exe: exe.map(String::from),
+            class: class.map(String::from),
+            level,
+            issues: Vec::new(),
+            workaround: None,
+            poll_title: false,
+        }
+    }
+
+    #[test]
+    fn user_entries_override_bundled_and_all_fields_must_match() {
+        assert!(!bundled().is_empty());
+
+        let bundled = vec![
+            entry(Some("discord.exe"), None, CompatLevel::Warning),
+            entry(None, Some("SunAwtFrame"), CompatLevel::Warning),
+        ];
+        let user = vec![
+            entry(Some("Discord.exe"), None, CompatLevel::Info),
+            entry(Some("idea*.exe"), Some("SunAwtFrame"), CompatLevel::Unsupported),
+        ];
+        let entries = merge(&bundled, &user);
+        assert_eq!(entries.len(), 3);
+
+        let discord = find(&entries, Some(r"C:\Users\me\AppData\Local\Discord\app-1.0\Discord.exe"), "Chrome_WidgetWin_1");
+        assert_eq!(discord.iter().map(|e| e.level).collect::<Vec<_>>(), vec![CompatLevel::Info]);
+
+        let idea = find(&entries, Some(r"C:\Program Files\JetBrains\bin\idea64.exe"), "SunAwtFrame");
+        assert_eq!(idea.len(), 2);
+        let other_java = find(&entries, Some(r"C:\java\javaw.exe"), "SunAwtFrame");
+        assert_eq!(other_java.iter().map(|e| e.level).collect::<Vec<_>>(), vec![CompatLevel::Warning]);
+        assert!(find(&entries, None, "Notepad").is_empty());
+    }
+}
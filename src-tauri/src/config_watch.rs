@@ -0,0 +1,131 @@
+// 配置文件热重载
+// 监听 %APPDATA%\WindowHub 下的配置文件，用户手动编辑后自动重新加载：
+// - settings.json -> 重新注册快捷键，发送 settings-changed
+// - focus_guard.json -> 刷新托盘菜单的勾选状态
+// - workspaces.json -> 工作区列表
+// - watched_apps.json -> 自动嵌入的应用列表
+// - rules.json -> 窗口规则
+// 有文件实际变化时发送 config-reloaded (载荷为变化的文件名)；安全模式下不重新加载设置、规则和监视列表
+
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+const WATCHED_FILES: [&str; 6] = [
+    "settings.json",
+    "focus_guard.json",
+    "workspaces.json",
+    "watched_apps.json",
+    "rules.json",
+    "compat.json",
+];
+
+// 编辑器保存时通常会连续触发多次事件 (写临时文件、重命名)，等待一段时间合并处理
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReloaded {
+    pub files: Vec<String>,
+}
+
+fn watched_name(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    WATCHED_FILES.iter().copied().find(|f| f.eq_ignore_ascii_case(name))
+}
+
+/// 启动监听线程 (setup 时调用一次)
+pub fn start(app: &AppHandle) {
+    let Some(dir) = crate::paths::data_dir() else { return };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        warn!(target: "settings", "无法创建配置目录: {}", e);
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || watch_thread(app, dir));
+}
+
+fn watch_thread(app: AppHandle, dir: PathBuf) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!(target: "settings", "创建配置文件监听失败: {}", e);
+            return;
+        }
+    };
+    // 监听目录而不是单个文件，编辑器"写临时文件再重命名"的保存方式也能收到
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        warn!(target: "settings", "监听配置目录失败: {}", e);
+        return;
+    }
+    info!(target: "settings", "配置文件监听已启动: {}", dir.display());
+
+    while let Ok(event) = rx.recv() {
+        let mut changed: Vec<&'static str> = Vec::new();
+        let mut collect = |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() {
+                    for name in event.paths.iter().filter_map(|p| watched_name(p)) {
+                        if !changed.contains(&name) {
+                            changed.push(name);
+                        }
+                    }
+                }
+            }
+        };
+        collect(event);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            collect(event);
+        }
+        if !changed.is_empty() {
+            reload(&app, &changed);
+        }
+    }
+}
+
+// 重新加载变化的配置文件，WindowHub 自己保存时内容与内存一致，不会重复触发
+fn reload(app: &AppHandle, files: &[&'static str]) {
+    let mut reloaded = Vec::new();
+    for file in files {
+        if crate::safe_mode::is_enabled() && crate::safe_mode::SKIPPED_FILES.contains(file) {
+            continue;
+        }
+        let changed = match *file {
+            "settings.json" => match crate::settings::reload_from_file() {
+                Ok(Some(previous)) => {
+                    crate::on_settings_changed(app, &previous);
+                    true
+                }
+                Ok(None) => false,
+                Err(e) => {
+                    warn!(target: "settings", "设置文件无效，保留当前设置: {}", e);
+                    false
+                }
+            },
+            "focus_guard.json" => {
+                let changed = crate::focus_guard::load_from_file();
+                if changed {
+                    crate::tray::refresh(app);
+                }
+                changed
+            }
+            "workspaces.json" => crate::load_workspaces_from_file(),
+            "watched_apps.json" => crate::app_watch::load_from_file(),
+            "rules.json" => crate::rules::load_from_file(),
+            "compat.json" => crate::compat::load_from_file(),
+            _ => false,
+        };
+        if changed {
+            reloaded.push(file.to_string());
+        }
+    }
+
+    if !reloaded.is_empty() {
+        info!(target: "settings", "配置已重新加载: {:?}", reloaded);
+        let _ = app.emit("config-reloaded", ConfigReloaded { files: reloaded });
+    }
+}
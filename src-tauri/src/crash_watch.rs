@@ -0,0 +1,197 @@
+// 嵌入应用的崩溃检测
+// 嵌入时为窗口所属的进程注册等待 (RegisterWaitForSingleObject)，进程退出后立即得到通知，不用等存活轮询：
+// - 退出码非 0 且标签仍在 (不是关闭标签、强制结束或重启引起的) 视为崩溃，发送 tab-crashed (载荷为 TabCrashed)
+// - 标签开启了自动重启 (set_tab_auto_restart，随工作区保存) 或处于展台模式时，用相同参数重新启动应用并嵌入到
+//   同一个标签 (reattach.rs)；RESTART_WINDOW 内最多自动重启 MAX_RESTARTS 次，避免启动即崩溃的应用反复重启
+// 正常退出 (退出码 0) 仍由前端的存活检查处理
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+#[cfg(windows)]
+use tauri::{Emitter, Manager};
+#[cfg(windows)]
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{CloseHandle, BOOLEAN, HANDLE},
+    System::Threading::{
+        GetExitCodeProcess, OpenProcess, RegisterWaitForSingleObject, UnregisterWait, INFINITE,
+        PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SYNCHRONIZE, WT_EXECUTEONLYONCE,
+    },
+};
+
+#[cfg(windows)]
+use crate::window_manager::{EmbeddedWindow, WindowManager};
+
+const MAX_RESTARTS: usize = 3;
+const RESTART_WINDOW: Duration = Duration::from_secs(300);
+
+// 进程退出后稍等再判断，让关闭标签、重启等主动操作先完成各自的记录
+#[cfg(windows)]
+const EXIT_GRACE: Duration = Duration::from_millis(500);
+
+#[cfg_attr(not(windows), allow(dead_code))]
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// 正在等待退出的进程：PID -> (进程句柄, 等待句柄)
+#[cfg(windows)]
+static WATCHES: Mutex<Vec<(u32, isize, isize)>> = Mutex::new(Vec::new());
+
+// 主动结束的进程 (强制结束、重启)，退出时不算崩溃
+#[cfg_attr(not(windows), allow(dead_code))]
+static EXPECTED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+// 每个标签最近自动重启的时间
+#[cfg_attr(not(windows), allow(dead_code))]
+static RESTARTS: Mutex<Vec<(u64, Instant)>> = Mutex::new(Vec::new());
+
+/// tab-crashed 事件的载荷
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct TabCrashed {
+    pub hwnd: isize,
+    pub tab_id: u64,
+    pub exe: Option<String>,
+    pub exit_code: u32,
+    pub restarting: bool, // 已开始自动重启，结果通过 window-reattached / reattach-failed 通知
+}
+
+/// 保存 AppHandle (setup 时调用一次)
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// 开始监视嵌入窗口所属的进程 (同一进程只监视一次)
+#[cfg(windows)]
+pub fn watch(window: &EmbeddedWindow) {
+    let pid = window.pid;
+    if pid == 0 {
+        return;
+    }
+    let mut watches = WATCHES.lock().unwrap();
+    if watches.iter().any(|(p, _, _)| *p == pid) {
+        return;
+    }
+    unsafe {
+        let Ok(process) = OpenProcess(PROCESS_SYNCHRONIZE | PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+            warn!(target: "lifecycle", "无法监视进程 {}: {}", pid, window.title);
+            return;
+        };
+        let mut wait = HANDLE::default();
+        let context = pid as usize as *const core::ffi::c_void;
+        if RegisterWaitForSingleObject(&mut wait, process, Some(on_exit), Some(context), INFINITE, WT_EXECUTEONLYONCE).is_err() {
+            let _ = CloseHandle(process);
+            return;
+        }
+        watches.push((pid, process.0 as isize, wait.0 as isize));
+    }
+}
+
+#[cfg(not(windows))]
+pub fn watch(_window: &crate::window_manager::EmbeddedWindow) {}
+
+/// 即将主动结束的进程，退出时不算崩溃
+pub fn expect_exit(pid: u32) {
+    EXPECTED.lock().unwrap().push(pid);
+}
+
+// 线程池回调，不在这里做耗时操作
+#[cfg(windows)]
+unsafe extern "system" fn on_exit(context: *mut core::ffi::c_void, _timed_out: BOOLEAN) {
+    let pid = context as usize as u32;
+    std::thread::spawn(move || handle_exit(pid));
+}
+
+#[cfg(windows)]
+fn handle_exit(pid: u32) {
+    let watch = {
+        let mut watches = WATCHES.lock().unwrap();
+        let index = watches.iter().position(|(p, _, _)| *p == pid);
+        index.map(|i| watches.remove(i))
+    };
+    let Some((_, process, wait)) = watch else { return };
+    let mut exit_code = 0;
+    unsafe {
+        let process = HANDLE(process as *mut _);
+        let _ = GetExitCodeProcess(process, &mut exit_code);
+        let _ = UnregisterWait(HANDLE(wait as *mut _));
+        let _ = CloseHandle(process);
+    }
+
+    std::thread::sleep(EXIT_GRACE);
+    let expected = {
+        let mut expected = EXPECTED.lock().unwrap();
+        let index = expected.iter().position(|p| *p == pid);
+        index.map(|i| expected.remove(i)).is_some()
+    };
+    if expected || exit_code == 0 {
+        return;
+    }
+    let Some(app) = APP.get() else { return };
+    let platform = crate::platform::current();
+    let crashed: Vec<EmbeddedWindow> = app
+        .state::<WindowManager>()
+        .list()
+        .into_iter()
+        .filter(|w| w.pid == pid && !platform.is_window(w.hwnd))
+        .collect();
+    for window in crashed {
+        crashed_tab(app, &window, exit_code);
+    }
+}
+
+#[cfg(windows)]
+fn crashed_tab(app: &AppHandle, window: &EmbeddedWindow, exit_code: u32) {
+    warn!(target: "lifecycle", "标签应用崩溃: {} (退出码 0x{:X})", window.title, exit_code);
+    let mut restarting = false;
+    if (window.auto_restart || crate::kiosk::is_enabled()) && allow_restart(window.tab_id, Instant::now()) {
+        match crate::reattach::start(app, window.tab_id, true) {
+            Ok(()) => {
+                info!(target: "lifecycle", "自动重启标签 {}: {}", window.tab_id, window.title);
+                restarting = true;
+            }
+            Err(e) => warn!(target: "lifecycle", "自动重启失败: {} - {}", window.title, e),
+        }
+    }
+    if !restarting {
+        let _ = crate::tab_state::transition(window.tab_id, window.hwnd, crate::tab_state::TabState::Waiting);
+    }
+    let _ = app.emit(
+        "tab-crashed",
+        TabCrashed { hwnd: window.hwnd, tab_id: window.tab_id, exe: window.exe_path.clone(), exit_code, restarting },
+    );
+}
+
+// 记录一次自动重启，RESTART_WINDOW 内已经重启过 MAX_RESTARTS 次时不再重启
+#[cfg_attr(not(windows), allow(dead_code))]
+fn allow_restart(tab_id: u64, now: Instant) -> bool {
+    let mut restarts = RESTARTS.lock().unwrap();
+    restarts.retain(|(_, t)| now.duration_since(*t) < RESTART_WINDOW);
+    if restarts.iter().filter(|(id, _)| *id == tab_id).count() >= MAX_RESTARTS {
+        return false;
+    }
+    restarts.push((tab_id, now));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_restarts_of_a_crash_looping_tab() {
+        let start = Instant::now();
+        for _ in 0..MAX_RESTARTS {
+            assert!(allow_restart(7, start));
+        }
+        assert!(!allow_restart(7, start + Duration::from_secs(10)));
+        // 其他标签不受影响
+        assert!(allow_restart(8, start));
+        // 最早的几次过期后可以再次重启
+        assert!(allow_restart(7, start + RESTART_WINDOW));
+    }
+}
@@ -0,0 +1,125 @@
+// 诊断信息导出
+// 把嵌入窗口表、最近的系统事件、快捷键注册结果、显示器/DPI 布局、嵌入失败统计和版本号打包成一个 JSON 文件，
+// 用户反馈"卡住了"之类的问题时附上即可，文件保存在 %APPDATA%\WindowHub\diagnostics\
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tracing::info;
+
+use crate::win_events::EventRecord;
+use crate::window_manager::{EmbeddedWindowStatus, WindowManager};
+
+// 附带的日志行数
+const LOG_TAIL_LINES: usize = 200;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutRegistration {
+    pub shortcut: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub dpi: u32,
+    pub primary: bool,
+    pub current: bool, // 主窗口所在的显示器
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub created_at: u64, // Unix 秒
+    pub embedded_windows: Vec<EmbeddedWindowStatus>,
+    pub recent_events: Vec<EventRecord>,
+    pub shortcuts: Vec<ShortcutRegistration>,
+    pub monitors: Vec<MonitorInfo>,
+    pub recent_logs: Vec<String>,
+    pub embed_failures: Vec<crate::failure_stats::FailureStat>,
+    pub api_trace: Vec<crate::api_trace::ApiCall>, // 开启了 Win32 调用跟踪时的记录
+    pub self_metrics: crate::self_metrics::SelfMetrics,
+}
+
+static SHORTCUTS: Mutex<Vec<ShortcutRegistration>> = Mutex::new(Vec::new());
+
+/// 记录快捷键注册结果 (同一快捷键重复注册时以最后一次为准)
+pub fn record_shortcut(shortcut: &str, result: Result<(), String>) {
+    let mut shortcuts = SHORTCUTS.lock().unwrap();
+    shortcuts.retain(|s| s.shortcut != shortcut);
+    shortcuts.push(ShortcutRegistration {
+        shortcut: shortcut.to_string(),
+        ok: result.is_ok(),
+        error: result.err(),
+    });
+}
+
+fn monitors(app: &AppHandle) -> Vec<MonitorInfo> {
+    use tauri::Manager;
+
+    let window = app.get_webview_window("main");
+    let primary = app.primary_monitor().ok().flatten();
+    let current = window.and_then(|w| w.current_monitor().ok().flatten());
+    let same = |a: &tauri::Monitor, b: &Option<tauri::Monitor>| b.as_ref().is_some_and(|b| b.name() == a.name() && b.position() == a.position());
+
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| MonitorInfo {
+            name: m.name().cloned(),
+            x: m.position().x,
+            y: m.position().y,
+            width: m.size().width,
+            height: m.size().height,
+            scale_factor: m.scale_factor(),
+            dpi: (m.scale_factor() * 96.0).round() as u32,
+            primary: same(m, &primary),
+            current: same(m, &current),
+        })
+        .collect()
+}
+
+/// 收集当前的诊断信息
+pub fn collect(app: &AppHandle, manager: &WindowManager) -> Diagnostics {
+    Diagnostics {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        embedded_windows: manager.statuses(),
+        recent_events: crate::win_events::recent_events(),
+        shortcuts: SHORTCUTS.lock().unwrap().clone(),
+        monitors: monitors(app),
+        recent_logs: crate::logging::recent_logs(LOG_TAIL_LINES).unwrap_or_default(),
+        embed_failures: crate::failure_stats::list(),
+        api_trace: crate::api_trace::calls(None),
+        self_metrics: crate::self_metrics::collect(),
+    }
+}
+
+/// 写入诊断文件，返回文件路径
+pub fn export(app: &AppHandle, manager: &WindowManager) -> Result<PathBuf, String> {
+    let config_dir = crate::paths::data_dir().ok_or("无法获取配置目录")?;
+    let dir = config_dir.join("diagnostics");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("无法创建诊断目录: {}", e))?;
+
+    let diagnostics = collect(app, manager);
+    let path = dir.join(format!("diagnostics-{}.json", diagnostics.created_at));
+    let json = serde_json::to_string_pretty(&diagnostics).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("写入诊断文件失败: {}", e))?;
+
+    info!("诊断信息已导出: {}", path.display());
+    Ok(path)
+}
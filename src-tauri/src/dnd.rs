@@ -0,0 +1,103 @@
+// 免打扰
+// 开启后：静音嵌入应用的声音、不再提示标签关注 (任务栏闪烁、对话框)、阻止嵌入应用抢占前台
+// 可以手动开关，也可以设置在工作区上：恢复该工作区时开启，恢复其他工作区时关闭
+// 系统的专注助手没有公开的 API，这里只处理嵌入的应用
+// 状态变化时发送 do-not-disturb-changed (载荷为是否开启)
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+
+use crate::platform::{self, Platform};
+use crate::window_manager::WindowManager;
+
+// 开启期间定期重新静音：应用第一次发声时才会创建音频会话
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 由免打扰静音的音频会话，关闭时只恢复这些 (用户自己静音的保持不变)
+static MUTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// 开启或关闭免打扰
+pub fn set(app: &AppHandle, enabled: bool) {
+    if ENABLED.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    info!(target: "focus", "免打扰: {}", if enabled { "开启" } else { "关闭" });
+    let platform = platform::current();
+    if enabled {
+        mute(platform, &embedded_apps(app));
+        let app = app.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(RESCAN_INTERVAL);
+            if !is_enabled() {
+                break;
+            }
+            mute(platform::current(), &embedded_apps(&app));
+        });
+    } else {
+        unmute(platform);
+    }
+    let _ = app.emit("do-not-disturb-changed", enabled);
+}
+
+/// 新嵌入了标签 (免打扰期间立即静音)
+pub fn on_embedded(app: &AppHandle) {
+    if is_enabled() {
+        mute(platform::current(), &embedded_apps(app));
+    }
+}
+
+fn embedded_apps(app: &AppHandle) -> Vec<String> {
+    app.state::<WindowManager>().list().into_iter().filter_map(|w| w.exe_path).collect()
+}
+
+// 静音属于 apps 的会话
+fn mute(platform: &dyn Platform, apps: &[String]) {
+    let mut muted = MUTED.lock().unwrap();
+    for session in platform.audio_sessions() {
+        if session.muted || !apps.iter().any(|app| app.eq_ignore_ascii_case(&session.exe_path)) {
+            continue;
+        }
+        if platform.set_audio_session_muted(&session.id, true) {
+            muted.push(session.id);
+        }
+    }
+}
+
+fn unmute(platform: &dyn Platform) {
+    for id in MUTED.lock().unwrap().drain(..) {
+        platform.set_audio_session_muted(&id, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatform;
+
+    #[test]
+    fn restores_only_sessions_it_muted() {
+        let platform = MockPlatform::new();
+        let chat = "C:/Program Files/Chat/chat.exe";
+        platform.add_audio_session("chat-main", chat, false);
+        platform.add_audio_session("chat-muted-by-user", chat, true);
+        platform.add_audio_session("music", "C:/Program Files/Music/music.exe", false);
+
+        mute(&platform, &["c:/program files/chat/CHAT.EXE".to_string()]);
+        let muted = |id: &str| platform.audio_sessions().into_iter().find(|s| s.id == id).unwrap().muted;
+        assert!(muted("chat-main"));
+        assert!(!muted("music"));
+
+        unmute(&platform);
+        assert!(!muted("chat-main"));
+        assert!(muted("chat-muted-by-user"));
+    }
+}
@@ -0,0 +1,45 @@
+// 旧程序的 DPI 兼容
+// 不支持 DPI 感知的旧程序按 96 DPI 绘制，由系统按位图放大，嵌入后和旁边清晰的应用放在一起显得很模糊。
+// 枚举窗口时报告每个窗口的 DPI 感知方式 (WindowInfo.dpi_awareness)，
+// launch_app_ex 可以带上 DPI 覆盖启动应用，相当于属性对话框 "兼容性 > 更改高 DPI 设置" 中的选项：
+// 通过 __COMPAT_LAYER 环境变量只对这次启动的进程生效，不写注册表。
+// 应用已经在运行时 (单实例程序把启动转交给已有进程) 不起作用，需要先关闭它
+
+use serde::Deserialize;
+
+/// 高 DPI 缩放由谁执行
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DpiOverride {
+    Application,    // 应用自己缩放 (按实际 DPI 绘制，界面可能偏小但清晰)
+    System,         // 系统位图放大
+    SystemEnhanced, // 系统增强 (GDI 绘制的文字按实际 DPI 渲染)
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl DpiOverride {
+    /// 对应的兼容层名称
+    pub fn compat_layer(self) -> &'static str {
+        match self {
+            DpiOverride::Application => "HIGHDPIAWARE",
+            DpiOverride::System => "DPIUNAWARE",
+            DpiOverride::SystemEnhanced => "GDIDPISCALING DPIUNAWARE",
+        }
+    }
+}
+
+/// launch_app_ex 的启动选项
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LaunchOptions {
+    #[serde(default)]
+    pub dpi_override: Option<DpiOverride>,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl LaunchOptions {
+    /// 启动进程时设置的 __COMPAT_LAYER，没有需要的兼容选项时为 None
+    pub fn compat_layer(&self) -> Option<&'static str> {
+        self.dpi_override.map(DpiOverride::compat_layer)
+    }
+}
@@ -0,0 +1,108 @@
+// 嵌入前的风险评估
+// can_embed_window 先做 check_embeddable 的硬性检查 (自身、危险窗口、禁止列表、之前无响应)，
+// 通过后再看窗口是否可疑，可疑时返回 needs_confirmation 和原因，由前端询问用户后再嵌入：
+// - 窗口太小：通常是托盘程序的隐藏窗口、启动画面或消息窗口，嵌入后标签里什么也看不到
+// - 无法读取所属进程的路径：以管理员或其他用户身份运行的程序，嵌入后往往收不到输入，也无法重新启动
+// - 所属进程刚启动几秒：可能还在显示启动画面，稍后会换成真正的主窗口
+// 只是提示，嵌入命令本身不检查，用户确认后照常嵌入
+
+use serde::Serialize;
+
+use crate::platform::Platform;
+
+// 宽或高小于这个值的窗口视为太小
+const MIN_WIDTH: i32 = 120;
+const MIN_HEIGHT: i32 = 80;
+
+// 进程启动不到这么多秒视为刚启动
+const NEW_PROCESS_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskKind {
+    TinyWindow,
+    NoProcessAccess,
+    NewProcess,
+}
+
+impl RiskKind {
+    /// 消息目录中的键 (见 messages.rs)
+    pub fn key(self) -> &'static str {
+        match self {
+            RiskKind::TinyWindow => "risk.tiny_window",
+            RiskKind::NoProcessAccess => "risk.no_process_access",
+            RiskKind::NewProcess => "risk.new_process",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RiskReason {
+    pub kind: RiskKind,
+    pub params: Vec<String>,
+    pub message: String, // 按界面语言生成
+}
+
+impl RiskReason {
+    fn new(kind: RiskKind, params: Vec<String>) -> Self {
+        RiskReason { kind, message: crate::messages::text(kind.key(), &params), params }
+    }
+}
+
+/// can_embed_window 的结果 (不能嵌入时命令返回错误)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum EmbedCheck {
+    Allowed,
+    NeedsConfirmation { reasons: Vec<RiskReason> },
+}
+
+/// 评估可以嵌入的窗口是否需要用户确认
+pub fn assess(platform: &dyn Platform, hwnd: isize) -> EmbedCheck {
+    let size = platform.window_size(hwnd);
+    let path_readable = platform.process_path(hwnd).is_ok();
+    let pid = platform.process_id(hwnd);
+    let age = (pid != 0).then(|| platform.process_age_secs(pid)).flatten();
+    let reasons = reasons(size, path_readable, age);
+    if reasons.is_empty() {
+        EmbedCheck::Allowed
+    } else {
+        EmbedCheck::NeedsConfirmation { reasons }
+    }
+}
+
+fn reasons(size: Option<(i32, i32)>, path_readable: bool, process_age_secs: Option<u64>) -> Vec<RiskReason> {
+    let mut reasons = Vec::new();
+    if let Some((width, height)) = size.filter(|&(w, h)| w < MIN_WIDTH || h < MIN_HEIGHT) {
+        reasons.push(RiskReason::new(RiskKind::TinyWindow, vec![width.to_string(), height.to_string()]));
+    }
+    if !path_readable {
+        reasons.push(RiskReason::new(RiskKind::NoProcessAccess, Vec::new()));
+    }
+    if let Some(age) = process_age_secs.filter(|&age| age < NEW_PROCESS_SECS) {
+        reasons.push(RiskReason::new(RiskKind::NewProcess, vec![age.to_string()]));
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(reasons: &[RiskReason]) -> Vec<RiskKind> {
+        reasons.iter().map(|r| r.kind).collect()
+    }
+
+    #[test]
+    fn normal_window_needs_no_confirmation() {
+        assert!(reasons(Some((800, 600)), true, Some(3600)).is_empty());
+        assert!(reasons(None, true, None).is_empty());
+    }
+
+    #[test]
+    fn lists_every_risk_found() {
+        let found = reasons(Some((300, 40)), false, Some(2));
+        assert_eq!(kinds(&found), vec![RiskKind::TinyWindow, RiskKind::NoProcessAccess, RiskKind::NewProcess]);
+        assert_eq!(found[0].params, vec!["300".to_string(), "40".to_string()]);
+    }
+}
@@ -0,0 +1,147 @@
+// 嵌入/释放失败统计
+// 在本地按 (操作, 程序, 窗口类名, 错误代码, 显示方式) 累计失败次数，不上传任何数据，
+// 用户和维护者可以据此看出哪些应用最常失败，补充到兼容性数据库 (compat.json)
+// 统计保存在数据库中 (storage.rs)
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::platform::{self, EmbedMode};
+use crate::window_manager::{EmbedError, EmbedErrorCode};
+
+// 最多保留的条目数，超出时丢弃最久没有再失败的
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureOp {
+    Embed,
+    Release,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureStat {
+    pub op: FailureOp,
+    pub exe: Option<String>, // EXE 文件名 (不含路径，避免记录用户目录)
+    pub class_name: String,
+    pub code: EmbedErrorCode,
+    pub strategy: EmbedMode,
+    pub count: u32,
+    pub last_message: String,
+    pub last_at: u64, // Unix 秒
+}
+
+static STATS: Mutex<Vec<FailureStat>> = Mutex::new(Vec::new());
+
+/// 记录一次失败 (窗口已经不存在时程序和类名可能为空)
+pub fn record(op: FailureOp, hwnd: isize, error: &EmbedError) {
+    // 重复释放、选择了过期的窗口、超出资源上限不是应用的问题
+    if matches!(error.code, EmbedErrorCode::NotEmbedded | EmbedErrorCode::StaleHandle | EmbedErrorCode::LimitExceeded) {
+        return;
+    }
+    let platform = platform::current();
+    let exe = platform
+        .process_path(hwnd)
+        .ok()
+        .map(|path| path.rsplit(['\\', '/']).next().unwrap_or(&path).to_string());
+    let class_name = platform.class_name(hwnd);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let stats = {
+        let mut stats = STATS.lock().unwrap();
+        add(&mut stats, op, exe, class_name, error, platform.embed_mode(), now);
+        stats.clone()
+    };
+    save(&stats);
+}
+
+fn add(stats: &mut Vec<FailureStat>, op: FailureOp, exe: Option<String>, class_name: String, error: &EmbedError, strategy: EmbedMode, now: u64) {
+    let same = |s: &FailureStat| {
+        s.op == op
+            && s.code == error.code
+            && s.strategy == strategy
+            && s.class_name == class_name
+            && s.exe.as_deref().map(str::to_lowercase) == exe.as_deref().map(str::to_lowercase)
+    };
+    if let Some(stat) = stats.iter_mut().find(|s| same(s)) {
+        stat.count += 1;
+        stat.last_message = error.message.clone();
+        stat.last_at = now;
+        return;
+    }
+    if stats.len() >= MAX_ENTRIES {
+        if let Some(oldest) = stats.iter().enumerate().min_by_key(|(_, s)| s.last_at).map(|(i, _)| i) {
+            stats.remove(oldest);
+        }
+    }
+    stats.push(FailureStat {
+        op,
+        exe,
+        class_name,
+        code: error.code,
+        strategy,
+        count: 1,
+        last_message: error.message.clone(),
+        last_at: now,
+    });
+}
+
+/// 失败统计，次数多的在前
+pub fn list() -> Vec<FailureStat> {
+    let mut stats = STATS.lock().unwrap().clone();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then(b.last_at.cmp(&a.last_at)));
+    stats
+}
+
+pub fn clear() {
+    STATS.lock().unwrap().clear();
+    save(&[]);
+    info!(target: "embed", "已清空嵌入失败统计");
+}
+
+fn save(stats: &[FailureStat]) {
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save("embed_failures", stats) {
+            warn!(target: "embed", "{}", e);
+        }
+    }
+}
+
+/// 从数据库加载 (setup 时调用)
+pub fn load() {
+    if let Some(loaded) = crate::storage::get().and_then(|s| s.load::<Vec<FailureStat>>("embed_failures")) {
+        *STATS.lock().unwrap() = loaded;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_are_counted_per_app_and_code() {
+        let mut stats = Vec::new();
+        let timeout = EmbedError::new(EmbedErrorCode::Timeout);
+        let failed = EmbedError::with_param(EmbedErrorCode::Failed, "SetParent 失败");
+        let exe = |name: &str| Some(name.to_string());
+
+        add(&mut stats, FailureOp::Embed, exe("Zoom.exe"), "ZPContentViewWndClass".into(), &timeout, EmbedMode::Reparent, 10);
+        add(&mut stats, FailureOp::Embed, exe("zoom.exe"), "ZPContentViewWndClass".into(), &timeout, EmbedMode::Reparent, 20);
+        add(&mut stats, FailureOp::Embed, exe("Zoom.exe"), "ZPContentViewWndClass".into(), &failed, EmbedMode::Reparent, 30);
+        add(&mut stats, FailureOp::Release, exe("Zoom.exe"), "ZPContentViewWndClass".into(), &failed, EmbedMode::Reparent, 40);
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].count, 2);
+        assert_eq!(stats[0].last_at, 20);
+
+        for i in 0..MAX_ENTRIES as u64 {
+            add(&mut stats, FailureOp::Embed, None, format!("Class{}", i), &failed, EmbedMode::Reparent, 100 + i);
+        }
+        assert_eq!(stats.len(), MAX_ENTRIES);
+        assert!(stats.iter().all(|s| s.last_at >= 100));
+    }
+}
@@ -0,0 +1,424 @@
+// 内置文件浏览
+// 资源管理器 (CabinetWClass) 与系统外壳共享进程，嵌入后容易拖垮整个桌面，
+// 这里提供一个最小的文件管理后端，前端可以用它实现不依赖资源管理器的文件标签：
+// - list_directory: 列出目录内容 (文件夹在前，按名称排序)，每一项带图标类别
+// - open_path: 用默认程序打开文件
+// - file_operations: 复制/移动/重命名/删除/新建文件夹，逐项报告结果
+// - watch_directory / unwatch_directory: 目录内容变化时发送 directory-changed (载荷为目录路径)
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_opener::OpenerExt;
+use tracing::{info, warn};
+
+// 连续的文件变化 (解压、批量复制) 合并后再通知前端
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 图标类别，前端据此显示对应的图标
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileIcon {
+    Folder,
+    Image,
+    Video,
+    Audio,
+    Archive,
+    Document,
+    Code,
+    Executable,
+    Shortcut,
+    File,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirEntry {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,     // 文件大小 (字节)，文件夹为 0
+    pub modified: u64, // 修改时间 (Unix 秒)
+    pub hidden: bool,
+    pub icon: FileIcon,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryListing {
+    pub path: String,
+    pub parent: Option<String>,
+    pub entries: Vec<DirEntry>,
+}
+
+/// 文件操作，sources / paths 可以包含多项
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FileOperation {
+    Copy { sources: Vec<String>, target_dir: String },
+    Move { sources: Vec<String>, target_dir: String },
+    Rename { path: String, new_name: String },
+    Delete { paths: Vec<String> }, // Windows 上移到回收站
+    CreateFolder { parent: String, name: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileOperationError {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FileOperationResult {
+    pub created: Vec<String>, // 新产生的路径 (复制/移动的目标、重命名后的路径、新文件夹)
+    pub errors: Vec<FileOperationError>,
+}
+
+fn icon_for(path: &Path, is_dir: bool) -> FileIcon {
+    if is_dir {
+        return FileIcon::Folder;
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "ico" | "tif" | "tiff" | "heic" => FileIcon::Image,
+        "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "webm" | "m4v" => FileIcon::Video,
+        "mp3" | "wav" | "flac" | "aac" | "ogg" | "m4a" | "wma" => FileIcon::Audio,
+        "zip" | "rar" | "7z" | "tar" | "gz" | "bz2" | "xz" | "cab" | "iso" => FileIcon::Archive,
+        "txt" | "md" | "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "csv" | "rtf" | "odt" => FileIcon::Document,
+        "rs" | "js" | "ts" | "py" | "c" | "cpp" | "h" | "cs" | "java" | "go" | "json" | "toml" | "yaml" | "yml" | "html"
+        | "css" | "xml" | "sh" | "ps1" | "bat" | "cmd" => FileIcon::Code,
+        "exe" | "msi" | "com" | "app" => FileIcon::Executable,
+        "lnk" | "url" => FileIcon::Shortcut,
+        _ => FileIcon::File,
+    }
+}
+
+#[cfg(windows)]
+fn is_hidden(name: &str, metadata: &std::fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    name.starts_with('.') || metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+}
+
+#[cfg(not(windows))]
+fn is_hidden(name: &str, _metadata: &std::fs::Metadata) -> bool {
+    name.starts_with('.')
+}
+
+// 路径为空时从用户目录开始
+fn resolve(path: &str) -> Result<PathBuf, String> {
+    if !path.trim().is_empty() {
+        return Ok(PathBuf::from(path));
+    }
+    std::env::var("USERPROFILE")
+        .or_else(|_| std::env::var("HOME"))
+        .map(PathBuf::from)
+        .map_err(|_| "无法获取用户目录".to_string())
+}
+
+/// 列出目录内容，show_hidden 为 false 时跳过隐藏文件
+pub fn list_directory(path: &str, show_hidden: bool) -> Result<DirectoryListing, String> {
+    let dir = resolve(path)?;
+    let read = std::fs::read_dir(&dir).map_err(|e| format!("无法读取目录 {}: {}", dir.display(), e))?;
+
+    let mut entries: Vec<DirEntry> = read
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // 跟随符号链接，链接到文件夹的也当作文件夹
+            let metadata = std::fs::metadata(entry.path()).or_else(|_| entry.metadata()).ok()?;
+            let hidden = is_hidden(&name, &metadata);
+            if hidden && !show_hidden {
+                return None;
+            }
+            let is_dir = metadata.is_dir();
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            Some(DirEntry {
+                icon: icon_for(&entry.path(), is_dir),
+                path: entry.path().to_string_lossy().to_string(),
+                name,
+                is_dir,
+                size: if is_dir { 0 } else { metadata.len() },
+                modified,
+                hidden,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+
+    Ok(DirectoryListing {
+        path: dir.to_string_lossy().to_string(),
+        parent: dir.parent().map(|p| p.to_string_lossy().to_string()),
+        entries,
+    })
+}
+
+/// 用默认程序打开文件 (文件夹由前端在文件标签内浏览，不交给资源管理器)
+pub fn open_path(app: &AppHandle, path: &str) -> Result<(), String> {
+    let target = Path::new(path);
+    if !target.exists() {
+        return Err(format!("文件不存在: {}", path));
+    }
+    if target.is_dir() {
+        return Err("文件夹请在文件标签中浏览".to_string());
+    }
+    info!(target: "launcher", "打开文件: {}", path);
+    app.opener().open_path(path, None::<&str>).map_err(|e| format!("打开失败: {}", e))
+}
+
+// 目标文件夹中已有同名项时依次尝试 "名称 (2).扩展名"、"名称 (3).扩展名"...
+fn unique_target(dir: &Path, name: &str, exists: impl Fn(&Path) -> bool) -> PathBuf {
+    let candidate = dir.join(name);
+    if !exists(&candidate) {
+        return candidate;
+    }
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    let ext = path.extension().and_then(|e| e.to_str());
+    (2..)
+        .map(|n| match ext {
+            Some(ext) if !stem.is_empty() => dir.join(format!("{} ({}).{}", stem, n, ext)),
+            _ => dir.join(format!("{} ({})", name, n)),
+        })
+        .find(|p| !exists(p))
+        .unwrap()
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from.is_dir() {
+        std::fs::create_dir(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(from, to).map(|_| ())
+    }
+}
+
+fn remove_recursive(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+// 移到回收站 (可以撤销)，不弹出确认和进度对话框
+#[cfg(windows)]
+fn delete_path(path: &Path) -> Result<(), String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::{SHFileOperationW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, FO_DELETE, SHFILEOPSTRUCTW};
+
+    // pFrom 是以两个 \0 结尾的路径列表
+    let from: Vec<u16> = path.as_os_str().to_string_lossy().encode_utf16().chain([0, 0]).collect();
+    let mut op = SHFILEOPSTRUCTW {
+        wFunc: FO_DELETE,
+        pFrom: PCWSTR(from.as_ptr()),
+        fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT).0 as u16,
+        ..Default::default()
+    };
+    let code = unsafe { SHFileOperationW(&mut op) };
+    if code != 0 {
+        return Err(format!("删除失败 (错误码 {})", code));
+    }
+    if op.fAnyOperationsAborted.as_bool() {
+        return Err("删除已取消".to_string());
+    }
+    Ok(())
+}
+
+// 其他平台没有统一的回收站接口，直接删除
+#[cfg(not(windows))]
+fn delete_path(path: &Path) -> Result<(), String> {
+    remove_recursive(path).map_err(|e| format!("删除失败: {}", e))
+}
+
+fn transfer(source: &str, target_dir: &Path, remove_source: bool) -> Result<String, String> {
+    let from = Path::new(source);
+    let name = from.file_name().and_then(|n| n.to_str()).ok_or("无效的路径")?;
+    if target_dir.starts_with(from) {
+        return Err("不能复制或移动到自身的子文件夹中".to_string());
+    }
+    if remove_source && from.parent() == Some(target_dir) {
+        return Ok(source.to_string());
+    }
+    let to = unique_target(target_dir, name, |p| p.exists());
+    if remove_source {
+        // 跨磁盘时无法直接重命名，复制后再删除原文件
+        if std::fs::rename(from, &to).is_err() {
+            copy_recursive(from, &to).map_err(|e| format!("移动失败: {}", e))?;
+            remove_recursive(from).map_err(|e| format!("已复制，但删除原文件失败: {}", e))?;
+        }
+    } else {
+        copy_recursive(from, &to).map_err(|e| format!("复制失败: {}", e))?;
+    }
+    Ok(to.to_string_lossy().to_string())
+}
+
+fn rename(path: &str, new_name: &str) -> Result<String, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name.contains(['/', '\\']) || new_name == "." || new_name == ".." {
+        return Err("无效的名称".to_string());
+    }
+    let from = Path::new(path);
+    let to = from.parent().ok_or("无效的路径")?.join(new_name);
+    // 只改大小写时目标 "已存在" (不区分大小写的文件系统)，仍然允许
+    if to.exists() && !to.to_string_lossy().eq_ignore_ascii_case(path) {
+        return Err(format!("已存在同名的文件: {}", new_name));
+    }
+    std::fs::rename(from, &to).map_err(|e| format!("重命名失败: {}", e))?;
+    Ok(to.to_string_lossy().to_string())
+}
+
+fn create_folder(parent: &str, name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return Err("无效的名称".to_string());
+    }
+    let to = unique_target(Path::new(parent), name, |p| p.exists());
+    std::fs::create_dir(&to).map_err(|e| format!("新建文件夹失败: {}", e))?;
+    Ok(to.to_string_lossy().to_string())
+}
+
+/// 执行文件操作，单项失败不影响其余项
+pub fn file_operations(operation: FileOperation) -> FileOperationResult {
+    let mut result = FileOperationResult::default();
+    let mut record = |path: &str, outcome: Result<Option<String>, String>| match outcome {
+        Ok(created) => result.created.extend(created),
+        Err(message) => {
+            warn!(target: "files", "文件操作失败: {} - {}", path, message);
+            result.errors.push(FileOperationError { path: path.to_string(), message });
+        }
+    };
+
+    info!(target: "files", "文件操作: {:?}", operation);
+    match &operation {
+        FileOperation::Copy { sources, target_dir } | FileOperation::Move { sources, target_dir } => {
+            let remove_source = matches!(operation, FileOperation::Move { .. });
+            for source in sources {
+                record(source, transfer(source, Path::new(target_dir), remove_source).map(Some));
+            }
+        }
+        FileOperation::Rename { path, new_name } => record(path, rename(path, new_name).map(Some)),
+        FileOperation::Delete { paths } => {
+            for path in paths {
+                record(path, delete_path(Path::new(path)).map(|_| None));
+            }
+        }
+        FileOperation::CreateFolder { parent, name } => record(parent, create_folder(parent, name).map(Some)),
+    }
+    result
+}
+
+// ============================================================
+// 目录监听
+// ============================================================
+
+// 正在监听的目录 (同一目录被多个文件标签打开时只监听一次，按引用计数释放)
+static WATCHERS: Mutex<Vec<(String, usize, RecommendedWatcher)>> = Mutex::new(Vec::new());
+
+// 变化的目录交给合并线程，合并后再发送事件
+static CHANGES: OnceLock<mpsc::Sender<String>> = OnceLock::new();
+
+// 已发送、合并线程还没取出的变化数
+static PENDING_CHANGES: AtomicUsize = AtomicUsize::new(0);
+
+/// 积压的目录变化数
+pub fn pending_changes() -> usize {
+    PENDING_CHANGES.load(Ordering::Relaxed)
+}
+
+fn changes(app: &AppHandle) -> mpsc::Sender<String> {
+    CHANGES
+        .get_or_init(|| {
+            let (tx, rx) = mpsc::channel::<String>();
+            let app = app.clone();
+            std::thread::spawn(move || {
+                while let Ok(dir) = rx.recv() {
+                    PENDING_CHANGES.fetch_sub(1, Ordering::Relaxed);
+                    let mut changed = vec![dir];
+                    while let Ok(dir) = rx.recv_timeout(DEBOUNCE) {
+                        PENDING_CHANGES.fetch_sub(1, Ordering::Relaxed);
+                        if !changed.contains(&dir) {
+                            changed.push(dir);
+                        }
+                    }
+                    for dir in changed {
+                        let _ = app.emit("directory-changed", dir);
+                    }
+                }
+            });
+            tx
+        })
+        .clone()
+}
+
+/// 开始监听目录内容的变化
+pub fn watch_directory(app: &AppHandle, path: &str) -> Result<(), String> {
+    let mut watchers = WATCHERS.lock().unwrap();
+    if let Some(entry) = watchers.iter_mut().find(|(dir, _, _)| dir == path) {
+        entry.1 += 1;
+        return Ok(());
+    }
+
+    let tx = changes(app);
+    let dir = path.to_string();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|e| e.kind.is_create() || e.kind.is_modify() || e.kind.is_remove()) {
+            PENDING_CHANGES.fetch_add(1, Ordering::Relaxed);
+            if tx.send(dir.clone()).is_err() {
+                PENDING_CHANGES.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    })
+    .map_err(|e| format!("无法监听目录: {}", e))?;
+    watcher
+        .watch(Path::new(path), RecursiveMode::NonRecursive)
+        .map_err(|e| format!("无法监听目录 {}: {}", path, e))?;
+    watchers.push((path.to_string(), 1, watcher));
+    Ok(())
+}
+
+/// 停止监听目录
+pub fn unwatch_directory(path: &str) {
+    let mut watchers = WATCHERS.lock().unwrap();
+    if let Some(index) = watchers.iter().position(|(dir, _, _)| dir == path) {
+        watchers[index].1 -= 1;
+        if watchers[index].1 == 0 {
+            watchers.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icons_by_extension() {
+        assert_eq!(icon_for(Path::new("photo.JPG"), false), FileIcon::Image);
+        assert_eq!(icon_for(Path::new("setup.exe"), false), FileIcon::Executable);
+        assert_eq!(icon_for(Path::new("src.rs"), true), FileIcon::Folder);
+        assert_eq!(icon_for(Path::new("README"), false), FileIcon::File);
+    }
+
+    #[test]
+    fn colliding_names_get_numbered() {
+        let dir = Path::new("/data");
+        let taken = [PathBuf::from("/data/report.txt"), PathBuf::from("/data/report (2).txt"), PathBuf::from("/data/photos")];
+        let exists = |p: &Path| taken.iter().any(|t| t == p);
+        assert_eq!(unique_target(dir, "notes.txt", exists), PathBuf::from("/data/notes.txt"));
+        assert_eq!(unique_target(dir, "report.txt", exists), PathBuf::from("/data/report (3).txt"));
+        assert_eq!(unique_target(dir, "photos", exists), PathBuf::from("/data/photos (2)"));
+    }
+}
@@ -0,0 +1,184 @@
+// 文件搜索 (搜索框)
+// 两种提供者，由 settings.search.file_provider 选择：
+// - Everything: 通过 Everything SDK (Everything64.dll) 查询 Everything 的索引，几毫秒内搜索所有磁盘。
+//   需要 Everything 正在运行，DLL 放在程序目录、Everything 安装目录或 settings.search.everything_dll 指定的位置
+// - 内置: 遍历桌面和下载目录 (两层)
+// auto (默认) 时 Everything 可用就用 Everything，否则使用内置搜索
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::settings::FileSearchProvider;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileResult {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+}
+
+/// 搜索文件和文件夹，最多返回 limit 条
+pub fn search(query: &str, limit: usize) -> Vec<FileResult> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let settings = crate::settings::get().search;
+    match settings.file_provider {
+        FileSearchProvider::Builtin => builtin(query, limit),
+        FileSearchProvider::Everything => everything::search(&settings.everything_dll, query, limit).unwrap_or_else(|e| {
+            warn!(target: "files", "Everything 搜索失败: {}", e);
+            Vec::new()
+        }),
+        FileSearchProvider::Auto => {
+            everything::search(&settings.everything_dll, query, limit).unwrap_or_else(|_| builtin(query, limit))
+        }
+    }
+}
+
+fn file_result(path: &str, is_dir: bool) -> FileResult {
+    let name = path.trim_end_matches(['\\', '/']).rsplit(['\\', '/']).next().unwrap_or(path);
+    FileResult { name: name.to_string(), path: path.to_string(), is_dir }
+}
+
+// 在桌面和下载目录中按文件名查找
+#[cfg(windows)]
+fn builtin(query: &str, limit: usize) -> Vec<FileResult> {
+    use std::path::PathBuf;
+    use walkdir::WalkDir;
+
+    let Ok(profile) = std::env::var("USERPROFILE") else { return Vec::new() };
+    let query = query.to_lowercase();
+    let mut results = Vec::new();
+    for dir in [PathBuf::from(&profile).join("Desktop"), PathBuf::from(&profile).join("Downloads")] {
+        if !dir.exists() {
+            continue;
+        }
+        for entry in WalkDir::new(dir).max_depth(2).into_iter().filter_map(|e| e.ok()) {
+            if entry.depth() > 0 && entry.file_name().to_string_lossy().to_lowercase().contains(&query) {
+                results.push(file_result(&entry.path().to_string_lossy(), entry.file_type().is_dir()));
+                if results.len() >= limit {
+                    return results;
+                }
+            }
+        }
+    }
+    results
+}
+
+#[cfg(not(windows))]
+fn builtin(_query: &str, _limit: usize) -> Vec<FileResult> {
+    Vec::new()
+}
+
+#[cfg(windows)]
+mod everything {
+    use std::sync::Mutex;
+    use windows::core::{HSTRING, PCSTR, PCWSTR};
+    use windows::Win32::Foundation::{BOOL, HMODULE};
+    use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+
+    use super::{file_result, FileResult};
+
+    const DLL_NAME: &str = "Everything64.dll";
+
+    // Everything_GetLastError 的错误码
+    const EVERYTHING_ERROR_IPC: u32 = 2;
+
+    // SDK 的导出函数
+    struct Sdk {
+        set_search: unsafe extern "system" fn(PCWSTR),
+        set_max: unsafe extern "system" fn(u32),
+        query: unsafe extern "system" fn(BOOL) -> BOOL,
+        num_results: unsafe extern "system" fn() -> u32,
+        is_folder: unsafe extern "system" fn(u32) -> BOOL,
+        full_path: unsafe extern "system" fn(u32, *mut u16, u32) -> u32,
+        last_error: unsafe extern "system" fn() -> u32,
+    }
+
+    // 已加载的 SDK (配置的 DLL 路径, SDK)，路径变化时重新加载。SDK 的查询状态是全局的，查询期间一直持有锁
+    static SDK: Mutex<Option<(String, Option<Sdk>)>> = Mutex::new(None);
+
+    pub fn search(dll: &str, query: &str, limit: usize) -> Result<Vec<FileResult>, String> {
+        let mut sdk = SDK.lock().unwrap();
+        if sdk.as_ref().is_none_or(|(path, _)| path != dll) {
+            *sdk = Some((dll.to_string(), unsafe { load(dll) }));
+        }
+        let Some((_, Some(sdk))) = sdk.as_ref() else { return Err(format!("找不到 {}", DLL_NAME)) };
+
+        unsafe {
+            let query = HSTRING::from(query);
+            (sdk.set_search)(PCWSTR(query.as_ptr()));
+            (sdk.set_max)(limit as u32);
+            if !(sdk.query)(true.into()).as_bool() {
+                return Err(match (sdk.last_error)() {
+                    EVERYTHING_ERROR_IPC => "Everything 没有运行".to_string(),
+                    code => format!("查询失败 (错误码 {})", code),
+                });
+            }
+            let mut results = Vec::new();
+            let mut buffer = vec![0u16; 32768];
+            for i in 0..(sdk.num_results)().min(limit as u32) {
+                let len = (sdk.full_path)(i, buffer.as_mut_ptr(), buffer.len() as u32) as usize;
+                if len == 0 {
+                    continue;
+                }
+                let path = String::from_utf16_lossy(&buffer[..len.min(buffer.len())]);
+                results.push(file_result(&path, (sdk.is_folder)(i).as_bool()));
+            }
+            Ok(results)
+        }
+    }
+
+    // 依次尝试配置的路径、程序目录、Everything 安装目录和系统搜索路径
+    unsafe fn load(dll: &str) -> Option<Sdk> {
+        let mut candidates = Vec::new();
+        if !dll.trim().is_empty() {
+            candidates.push(dll.trim().to_string());
+        }
+        if let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(|p| p.to_path_buf())) {
+            candidates.push(dir.join(DLL_NAME).to_string_lossy().to_string());
+        }
+        if let Ok(dir) = std::env::var("ProgramFiles") {
+            candidates.push(std::path::Path::new(&dir).join("Everything").join(DLL_NAME).to_string_lossy().to_string());
+        }
+        candidates.push(DLL_NAME.to_string());
+
+        let module = candidates.iter().find_map(|path| LoadLibraryW(&HSTRING::from(path.as_str())).ok())?;
+        Some(Sdk {
+            set_search: std::mem::transmute(export(module, "Everything_SetSearchW\0")?),
+            set_max: std::mem::transmute(export(module, "Everything_SetMax\0")?),
+            query: std::mem::transmute(export(module, "Everything_QueryW\0")?),
+            num_results: std::mem::transmute(export(module, "Everything_GetNumResults\0")?),
+            is_folder: std::mem::transmute(export(module, "Everything_IsFolderResult\0")?),
+            full_path: std::mem::transmute(export(module, "Everything_GetResultFullPathNameW\0")?),
+            last_error: std::mem::transmute(export(module, "Everything_GetLastError\0")?),
+        })
+    }
+
+    unsafe fn export(module: HMODULE, name: &str) -> Option<unsafe extern "system" fn() -> isize> {
+        GetProcAddress(module, PCSTR(name.as_ptr()))
+    }
+}
+
+#[cfg(not(windows))]
+mod everything {
+    use super::FileResult;
+
+    pub fn search(_dll: &str, _query: &str, _limit: usize) -> Result<Vec<FileResult>, String> {
+        Err("仅支持 Windows".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_named_after_the_last_component() {
+        let file = file_result(r"C:\Users\me\Documents\报告.docx", false);
+        assert_eq!(file.name, "报告.docx");
+        assert_eq!(file_result(r"D:\Projects\", true).name, "Projects");
+        assert_eq!(file_result("C:", true).name, "C:");
+    }
+}
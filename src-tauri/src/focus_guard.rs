@@ -0,0 +1,192 @@
+// 焦点保护：阻止嵌入应用在用户使用其他程序时把前台抢回 WindowHub
+// 前台切换到主窗口/嵌入窗口，且最近没有用户输入时，视为被抢占，立即还原之前的前台窗口
+// 按应用 (EXE 路径) 单独关闭保护，配置保存在 %APPDATA%\WindowHub\focus_guard.json
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tracing::info;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+#[cfg(windows)]
+use crate::platform::win32::ThreadInputAttachment;
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::HWND,
+    UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+    UI::WindowsAndMessaging::*,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FocusGuardConfig {
+    pub enabled: bool,
+    pub exempt_apps: Vec<String>, // 允许抢占焦点的 EXE 路径
+}
+
+static CONFIG: Mutex<FocusGuardConfig> = Mutex::new(FocusGuardConfig { enabled: false, exempt_apps: Vec::new() });
+
+// 最近一个不属于 WindowHub 的前台窗口
+#[cfg(windows)]
+static LAST_EXTERNAL: AtomicIsize = AtomicIsize::new(0);
+
+// 距离最近一次键盘/鼠标输入多久以内的前台切换视为用户操作 (毫秒)
+#[cfg(windows)]
+const USER_INPUT_GRACE_MS: u32 = 500;
+
+pub fn config() -> FocusGuardConfig {
+    CONFIG.lock().unwrap().clone()
+}
+
+pub fn is_enabled() -> bool {
+    CONFIG.lock().unwrap().enabled
+}
+
+pub fn set_enabled(enabled: bool) {
+    let config = {
+        let mut config = CONFIG.lock().unwrap();
+        config.enabled = enabled;
+        config.clone()
+    };
+    info!(target: "focus", "焦点保护: {}", if enabled { "开启" } else { "关闭" });
+    save_to_file(&config);
+}
+
+/// 该标签是否受焦点保护 (未被单独关闭)
+pub fn is_tab_guarded(target_hwnd: isize) -> bool {
+    match crate::get_process_path(target_hwnd) {
+        Ok(path) => !CONFIG.lock().unwrap().exempt_apps.iter().any(|p| p.eq_ignore_ascii_case(&path)),
+        Err(_) => true,
+    }
+}
+
+/// 单独开启/关闭某个标签所属应用的焦点保护
+pub fn set_tab_guarded(target_hwnd: isize, guarded: bool) -> Result<(), String> {
+    let path = crate::get_process_path(target_hwnd)?;
+    let config = {
+        let mut config = CONFIG.lock().unwrap();
+        config.exempt_apps.retain(|p| !p.eq_ignore_ascii_case(&path));
+        if !guarded {
+            config.exempt_apps.push(path.clone());
+        }
+        config.clone()
+    };
+    info!(target: "focus", "{} 焦点保护: {}", path, if guarded { "开启" } else { "关闭" });
+    save_to_file(&config);
+    Ok(())
+}
+
+/// 忘记之前的前台窗口 (会话解锁后调用，之前记录的是锁屏界面)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn reset() {
+    #[cfg(windows)]
+    LAST_EXTERNAL.store(0, Ordering::SeqCst);
+}
+
+/// 前台窗口变化 (由 win_events 的 EVENT_SYSTEM_FOREGROUND 钩子调用)
+#[cfg(windows)]
+pub unsafe fn on_foreground_changed(hwnd: HWND, event_time: u32) {
+    // 锁屏和切换用户期间的前台变化不是嵌入应用造成的
+    if !crate::session_watch::is_active() {
+        return;
+    }
+    let host = crate::win_events::host_hwnd();
+    let raw = hwnd.0 as isize;
+
+    let tab = crate::win_events::find_embedded_tab(hwnd);
+    let is_ours = raw == host.0 as isize || tab.is_some() || crate::platform::win32::is_own_window(hwnd);
+    if !is_ours {
+        LAST_EXTERNAL.store(raw, Ordering::SeqCst);
+        return;
+    }
+
+    // 免打扰期间所有嵌入应用都不能抢占前台
+    let dnd = crate::dnd::is_enabled();
+    if !(is_enabled() || dnd) || is_user_initiated(event_time) {
+        return;
+    }
+
+    let previous = HWND(LAST_EXTERNAL.load(Ordering::SeqCst) as *mut _);
+    if previous.is_invalid() || !IsWindow(previous).as_bool() || IsIconic(previous).as_bool() || !IsWindowVisible(previous).as_bool() {
+        return;
+    }
+
+    // 主窗口被激活时，根据输入焦点判断是哪个嵌入窗口触发的
+    let tab = tab.or_else(|| {
+        let mut info = GUITHREADINFO { cbSize: std::mem::size_of::<GUITHREADINFO>() as u32, ..Default::default() };
+        if GetGUIThreadInfo(0, &mut info).is_ok() && !info.hwndFocus.is_invalid() {
+            crate::win_events::find_embedded_tab(info.hwndFocus)
+        } else {
+            None
+        }
+    });
+    if let Some(tab) = tab {
+        if !dnd && !is_tab_guarded(tab) {
+            return;
+        }
+    }
+
+    info!(target: "focus", "拦截焦点抢占: hwnd={}, tab={:?}, 还原到 {}", raw, tab, previous.0 as isize);
+    crate::win_events::record_event("focus-blocked", raw, tab, format!("restore={}", previous.0 as isize));
+    restore_foreground(hwnd, previous);
+
+    if let Some(tab) = tab {
+        crate::win_events::notify_attention(tab, "focus");
+    }
+}
+
+// 事件发生前不久有键盘/鼠标输入，说明是用户主动切换 (点击标签、快捷键、托盘菜单等)
+#[cfg(windows)]
+unsafe fn is_user_initiated(event_time: u32) -> bool {
+    let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+    if !GetLastInputInfo(&mut info).as_bool() {
+        return true;
+    }
+    event_time.wrapping_sub(info.dwTime) < USER_INPUT_GRACE_MS
+}
+
+#[cfg(windows)]
+unsafe fn restore_foreground(current: HWND, previous: HWND) {
+    let (current, previous) = (current.0 as isize, previous.0 as isize);
+    // 在窗口操作线程上切换 (见 window_ops.rs)，不阻塞事件回调
+    crate::window_ops::post("restore_foreground", move || unsafe {
+        // 前台可能属于嵌入应用的进程，需要临时挂接输入队列才能切换
+        let _attachment = ThreadInputAttachment::new(HWND(current as *mut _));
+        let _ = SetForegroundWindow(HWND(previous as *mut _));
+    });
+}
+
+// 持久化辅助函数
+fn save_to_file(config: &FocusGuardConfig) {
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("focus_guard.json");
+
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        if let Ok(json) = serde_json::to_string_pretty(config) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+/// 从文件加载配置，返回配置是否有变化
+pub fn load_from_file() -> bool {
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("focus_guard.json");
+
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            if let Ok(loaded) = serde_json::from_str::<FocusGuardConfig>(&content) {
+                let mut config = CONFIG.lock().unwrap();
+                if *config == loaded {
+                    return false;
+                }
+                info!(target: "focus", "焦点保护: {}, 例外应用 {} 个", loaded.enabled, loaded.exempt_apps.len());
+                *config = loaded;
+                return true;
+            }
+        }
+    }
+    false
+}
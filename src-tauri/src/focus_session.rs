@@ -0,0 +1,126 @@
+// 专注时段 (番茄钟)
+// start_focus_session(minutes, allowed_tabs) 开始一段专注时间：不在 allowed_tabs 中的标签锁定输入 (画面仍然显示，见 lock_tab_input)，
+// 期间新嵌入的标签同样锁定；这些标签的关注提示 (任务栏闪烁、对话框) 不再发送。
+// 每秒发送 focus-session-tick (载荷为 FocusSession)，结束时发送 focus-session-finished (载荷为是否到时结束，提前停止为 false)，
+// 只解除这里的锁定 (见 input_lock.rs，用户和演示模式的锁定保持不变)。同一时间只有一段，重新开始时先结束之前的一段
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+
+use crate::clock::now_secs;
+use crate::input_lock::{self, LockOwner};
+use crate::window_manager::WindowManager;
+
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+const MAX_MINUTES: u64 = 240;
+
+#[derive(Debug, Clone)]
+struct Session {
+    ends_at: u64,
+    allowed: Vec<isize>,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+// 每开始一段加一，旧的计时线程据此退出
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// get_focus_session 的返回值和 focus-session-tick 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusSession {
+    pub ends_at: u64,
+    pub remaining_secs: u64,
+    pub allowed_tabs: Vec<isize>,
+}
+
+/// 开始专注时段
+pub fn start(app: &AppHandle, minutes: u64, allowed: Vec<isize>) -> Result<FocusSession, String> {
+    if minutes == 0 || minutes > MAX_MINUTES {
+        return Err(format!("专注时长应在 1~{} 分钟之间", MAX_MINUTES));
+    }
+    stop(app, false);
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let ends_at = now_secs() + minutes * 60;
+    *SESSION.lock().unwrap() = Some(Session { ends_at, allowed: allowed.clone() });
+    lock_distractions(app);
+    info!(target: "focus", "开始专注 {} 分钟，允许 {} 个标签", minutes, allowed.len());
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TICK_INTERVAL);
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            break;
+        }
+        let Some(session) = current() else { break };
+        if session.remaining_secs == 0 {
+            stop(&app, true);
+            break;
+        }
+        lock_distractions(&app);
+        let _ = app.emit("focus-session-tick", session);
+    });
+    Ok(FocusSession { ends_at, remaining_secs: minutes * 60, allowed_tabs: allowed })
+}
+
+/// 结束专注时段，completed 表示是否到时结束；没有进行中的专注时段时返回 false
+pub fn stop(app: &AppHandle, completed: bool) -> bool {
+    if SESSION.lock().unwrap().take().is_none() {
+        return false;
+    }
+    let locked = input_lock::locked_by(LockOwner::FocusSession).len();
+    input_lock::unlock_all(app, LockOwner::FocusSession);
+    info!(target: "focus", "专注结束 ({})，解除锁定 {} 个标签", if completed { "到时" } else { "提前停止" }, locked);
+    let _ = app.emit("focus-session-finished", completed);
+    true
+}
+
+/// 进行中的专注时段
+pub fn current() -> Option<FocusSession> {
+    let session = SESSION.lock().unwrap();
+    let session = session.as_ref()?;
+    Some(FocusSession {
+        ends_at: session.ends_at,
+        remaining_secs: session.ends_at.saturating_sub(now_secs()),
+        allowed_tabs: session.allowed.clone(),
+    })
+}
+
+/// 专注期间不提示该标签的关注
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn suppresses(tab: isize) -> bool {
+    SESSION.lock().unwrap().as_ref().is_some_and(|s| !s.allowed.contains(&tab))
+}
+
+// 锁定不在允许列表中的标签 (已经由专注时段锁定的不再重复)
+fn lock_distractions(app: &AppHandle) {
+    let Some(allowed) = SESSION.lock().unwrap().as_ref().map(|s| s.allowed.clone()) else { return };
+    let tabs = app.state::<WindowManager>().hwnds();
+    for tab in to_lock(&tabs, &allowed) {
+        input_lock::lock(app, tab, LockOwner::FocusSession);
+    }
+    // 锁定期间专注时段结束了
+    if SESSION.lock().unwrap().is_none() {
+        input_lock::unlock_all(app, LockOwner::FocusSession);
+    }
+}
+
+fn to_lock(tabs: &[isize], allowed: &[isize]) -> Vec<isize> {
+    tabs.iter().filter(|tab| !allowed.contains(tab)).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locks_tabs_outside_the_allowed_list() {
+        let tabs = [1, 2, 3, 4];
+        assert_eq!(to_lock(&tabs, &[2]), vec![1, 3, 4]);
+        assert_eq!(to_lock(&tabs, &[1, 2, 4]), Vec::<isize>::new());
+    }
+}
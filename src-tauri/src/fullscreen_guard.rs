@@ -0,0 +1,92 @@
+// 独占全屏保护
+// 嵌入的游戏等窗口切换到独占全屏 (Direct3D exclusive fullscreen) 后，作为子窗口无法完成显示模式切换，整个桌面会卡住
+// 后台线程定时查询系统的用户通知状态 (SHQueryUserNotificationState)，处于 D3D 全屏时找出属于标签的全屏窗口：
+// - 前台窗口就是标签，或与标签属于同一进程 (应用另外打开了全屏窗口)
+// - 前台是主窗口且标签盖住了整个显示器 (子窗口自己切换成了全屏)
+// 找到后立即释放该标签，恢复成独立窗口让它正常全屏，并发送 exclusive-fullscreen-released 提示用户
+// 其他程序 (不是标签) 的全屏不处理
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::warn;
+
+use crate::lifecycle::ReleaseReason;
+use crate::window_manager::WindowManager;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExclusiveFullscreenReleased {
+    pub hwnd: isize,
+    pub title: String,
+    pub exe_path: Option<String>,
+}
+
+/// 启动检测线程 (setup 时调用一次)
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let platform = crate::platform::current();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            // 展台模式下不释放标签
+            if !crate::session_watch::is_active() || crate::kiosk::is_enabled() || !platform.exclusive_fullscreen_active() {
+                continue;
+            }
+            let manager = app.state::<WindowManager>();
+            let tabs: Vec<Tab> = manager
+                .list()
+                .iter()
+                .map(|w| Tab { hwnd: w.hwnd, pid: w.pid, covers_monitor: platform.covers_monitor(w.hwnd) })
+                .collect();
+            let foreground = platform.foreground_window();
+            let Some(tab) = culprit(&tabs, foreground, platform.process_id(foreground), platform.is_own_window(foreground)) else {
+                continue;
+            };
+            let Some(window) = manager.get(tab) else { continue };
+            warn!(target: "embed", "标签切换到了独占全屏，自动释放: hwnd={}, {}", tab, window.title);
+            let _ = crate::release_tab(&app, &manager, tab, ReleaseReason::Detach);
+            let _ = app.emit(
+                "exclusive-fullscreen-released",
+                ExclusiveFullscreenReleased { hwnd: tab, title: window.title, exe_path: window.exe_path },
+            );
+        }
+    });
+}
+
+struct Tab {
+    hwnd: isize,
+    pid: u32,
+    covers_monitor: bool,
+}
+
+// 处于 D3D 全屏时，全屏的是哪个标签 (foreground_own 为前台是否是 WindowHub 自己的窗口)
+fn culprit(tabs: &[Tab], foreground: isize, foreground_pid: u32, foreground_own: bool) -> Option<isize> {
+    if let Some(tab) = tabs.iter().find(|t| t.hwnd == foreground || (foreground_pid != 0 && t.pid == foreground_pid)) {
+        return Some(tab.hwnd);
+    }
+    if foreground_own {
+        return tabs.iter().find(|t| t.covers_monitor).map(|t| t.hwnd);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_tab_that_went_fullscreen() {
+        let tabs = [
+            Tab { hwnd: 0x91, pid: 10, covers_monitor: false },
+            Tab { hwnd: 0x92, pid: 20, covers_monitor: true },
+        ];
+        // 标签的进程打开了全屏窗口
+        assert_eq!(culprit(&tabs, 0x500, 10, false), Some(0x91));
+        // 前台是主窗口，标签盖住了显示器
+        assert_eq!(culprit(&tabs, 0x600, 1, true), Some(0x92));
+        // 其他程序的全屏
+        assert_eq!(culprit(&tabs, 0x700, 30, false), None);
+    }
+}
@@ -0,0 +1,244 @@
+// 鼠标手势 (设置 input.mouse_gestures / input.gestures)
+// 在主窗口 (包括嵌入的窗口) 中按住右键划动，按划动的方向序列触发操作，如向左划切换到上一个标签、
+// 先下后右关闭标签，和浏览器的鼠标手势一样
+// 低级鼠标钩子 (单独的线程，只在开启时运行)：
+// - 右键在主窗口中按下时拦截，开始记录方向；每移动 MIN_STROKE 像素按主要的移动方向记一笔，与上一笔相同时合并
+// - 松开右键时按记录的方向查找手势；没有移动时补发一次右键单击，应用的右键菜单照常弹出
+// 开启后嵌入应用中的右键拖动 (如游戏中转动视角) 会被当作手势
+
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+use tracing::info;
+
+use crate::mouse_hook::MouseHook;
+use crate::settings::Gesture;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicIsize, Ordering};
+#[cfg(windows)]
+use tauri::Manager;
+#[cfg(windows)]
+use tracing::debug;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{LPARAM, LRESULT, WPARAM},
+    UI::Input::KeyboardAndMouse::{SendInput, INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEINPUT, MOUSE_EVENT_FLAGS},
+    UI::WindowsAndMessaging::*,
+};
+
+#[cfg(windows)]
+use crate::settings::MouseAction;
+
+// 记一笔需要移动的距离 (物理像素)
+const MIN_STROKE: i32 = 24;
+
+// 一个手势最多的笔数，超出后不再记录 (松开时也不会匹配)
+const MAX_STROKES: usize = 8;
+
+// 钩子线程收到后补发右键单击 (不能在钩子回调中调用 SendInput)
+#[cfg(windows)]
+const WM_REPLAY_CLICK: u32 = WM_APP + 1;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static HOOK: MouseHook = MouseHook::new("gestures", "鼠标手势");
+
+// 当前的手势配置，钩子中不重复读取设置
+static GESTURES: Mutex<Vec<Gesture>> = Mutex::new(Vec::new());
+
+// 正在记录的手势，右键没有按下时为 None
+#[cfg(windows)]
+static TRACKING: Mutex<Option<Tracker>> = Mutex::new(None);
+
+// 主窗口
+#[cfg(windows)]
+static HOST: AtomicIsize = AtomicIsize::new(0);
+
+/// 按设置开启或关闭 (启动和设置变化时调用)
+pub fn apply(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let input = crate::settings::get().input;
+    let enabled = input.mouse_gestures && !input.gestures.is_empty() && crate::session_watch::is_active();
+    *GESTURES.lock().unwrap() = input.gestures;
+    if !HOOK.set_enabled(enabled) {
+        return;
+    }
+    info!(target: "shortcuts", "{}鼠标手势", if enabled { "开启" } else { "关闭" });
+    #[cfg(windows)]
+    if enabled {
+        if let Some(host) = app.get_webview_window("main").and_then(|w| w.hwnd().ok()) {
+            HOST.store(host.0 as isize, Ordering::SeqCst);
+        }
+        HOOK.spawn(Some(mouse_proc), on_message, || *TRACKING.lock().unwrap() = None);
+    }
+}
+
+/// 检查手势的写法 (设置校验时调用)
+pub fn validate(strokes: &str) -> Result<(), String> {
+    if strokes.is_empty() || strokes.chars().count() > MAX_STROKES {
+        return Err(format!("鼠标手势 \"{}\" 的笔数必须在 1 到 {} 之间", strokes, MAX_STROKES));
+    }
+    if let Some(c) = strokes.chars().find(|c| !"LRUD".contains(*c)) {
+        return Err(format!("鼠标手势 \"{}\" 中的方向 {} 无效，只能使用 L/R/U/D", strokes, c));
+    }
+    if strokes.as_bytes().windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(format!("鼠标手势 \"{}\" 中相邻的方向不能相同", strokes));
+    }
+    Ok(())
+}
+
+/// 按住右键后的移动轨迹
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq)]
+struct Tracker {
+    anchor: (i32, i32), // 上一笔结束的位置
+    strokes: String,
+    overflow: bool, // 超出了 MAX_STROKES
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl Tracker {
+    fn new(x: i32, y: i32) -> Self {
+        Tracker { anchor: (x, y), strokes: String::new(), overflow: false }
+    }
+
+    fn move_to(&mut self, x: i32, y: i32) {
+        let (dx, dy) = (x - self.anchor.0, y - self.anchor.1);
+        if dx.abs().max(dy.abs()) < MIN_STROKE {
+            return;
+        }
+        let direction = match (dx.abs() >= dy.abs(), dx > 0, dy > 0) {
+            (true, true, _) => 'R',
+            (true, false, _) => 'L',
+            (false, _, true) => 'D',
+            (false, _, false) => 'U',
+        };
+        self.anchor = (x, y);
+        if self.strokes.ends_with(direction) {
+            return;
+        }
+        if self.strokes.len() >= MAX_STROKES {
+            self.overflow = true;
+            return;
+        }
+        self.strokes.push(direction);
+    }
+
+    /// 是否移动过 (没有时松开右键算普通的右键单击)
+    fn moved(&self) -> bool {
+        !self.strokes.is_empty()
+    }
+
+    fn gesture(&self) -> Option<&str> {
+        (self.moved() && !self.overflow).then_some(self.strokes.as_str())
+    }
+}
+
+// 钩子线程收到的消息：补发右键单击
+#[cfg(windows)]
+fn on_message(msg: &MSG) -> bool {
+    if msg.message != WM_REPLAY_CLICK {
+        return false;
+    }
+    unsafe { replay_right_click() };
+    true
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code < 0 {
+        return CallNextHookEx(None, code, wparam, lparam);
+    }
+    let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+    // 补发的单击和其他程序模拟的输入不处理
+    let injected = info.flags & LLMHF_INJECTED != 0;
+    let (x, y) = (info.pt.x, info.pt.y);
+    match wparam.0 as u32 {
+        WM_RBUTTONDOWN if !injected && in_host(info.pt) => {
+            *TRACKING.lock().unwrap() = Some(Tracker::new(x, y));
+            return LRESULT(1);
+        }
+        WM_MOUSEMOVE => {
+            if let Some(tracker) = TRACKING.lock().unwrap().as_mut() {
+                tracker.move_to(x, y);
+            }
+        }
+        WM_RBUTTONUP if !injected => {
+            if let Some(tracker) = TRACKING.lock().unwrap().take() {
+                finish(&tracker);
+                return LRESULT(1);
+            }
+        }
+        _ => {}
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+// 光标下的窗口是主窗口或其中的标签
+#[cfg(windows)]
+unsafe fn in_host(pt: windows::Win32::Foundation::POINT) -> bool {
+    let host = HOST.load(Ordering::SeqCst);
+    host != 0 && GetAncestor(WindowFromPoint(pt), GA_ROOT).0 as isize == host
+}
+
+// 松开右键：触发匹配的手势，没有移动时补发右键单击
+#[cfg(windows)]
+unsafe fn finish(tracker: &Tracker) {
+    if !tracker.moved() {
+        HOOK.post(WM_REPLAY_CLICK);
+        return;
+    }
+    let Some(strokes) = tracker.gesture() else { return };
+    let action = GESTURES.lock().unwrap().iter().find(|g| g.strokes == strokes).map(|g| g.action);
+    let Some(action) = action else {
+        debug!(target: "shortcuts", "没有对应的鼠标手势: {}", strokes);
+        return;
+    };
+    let Some(app) = APP.get().cloned() else { return };
+    info!(target: "shortcuts", "鼠标手势 {}: {:?}", strokes, action);
+    std::thread::spawn(move || trigger(&app, action));
+}
+
+#[cfg(windows)]
+fn trigger(app: &AppHandle, action: MouseAction) {
+    crate::handle_shortcut_action(app, action.shortcut_action());
+}
+
+#[cfg(windows)]
+unsafe fn replay_right_click() {
+    let click = |flags: MOUSE_EVENT_FLAGS| INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 { mi: MOUSEINPUT { dwFlags: flags, ..Default::default() } },
+    };
+    let inputs = [click(MOUSEEVENTF_RIGHTDOWN), click(MOUSEEVENTF_RIGHTUP)];
+    SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(points: &[(i32, i32)]) -> Tracker {
+        let mut tracker = Tracker::new(100, 100);
+        for &(x, y) in points {
+            tracker.move_to(x, y);
+        }
+        tracker
+    }
+
+    #[test]
+    fn strokes_follow_the_dominant_direction() {
+        assert_eq!(track(&[(80, 105), (40, 95), (10, 100)]).gesture(), Some("L"));
+        assert_eq!(track(&[(105, 130), (100, 160), (130, 165), (170, 160)]).gesture(), Some("DR"));
+        assert_eq!(track(&[(110, 110)]).gesture(), None);
+        assert!(!track(&[(110, 110)]).moved());
+    }
+
+    #[test]
+    fn gesture_strings_are_validated() {
+        assert!(validate("DR").is_ok());
+        assert!(validate("").is_err());
+        assert!(validate("LL").is_err());
+        assert!(validate("DX").is_err());
+    }
+}
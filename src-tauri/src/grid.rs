@@ -0,0 +1,109 @@
+// 监控墙：网格排列的标签
+// embed_as_grid 把一组窗口嵌入后按数量自动排成网格 (2 个为 2x1，3~4 个为 2x2，5~6 个为 3x2，7~9 个为 3x3 ...)，
+// 格子之间留出相同的空隙。网格中的标签同时显示，切换到其中任意一个时整个网格一起显示；
+// 加入或移除 (释放、关闭、停靠到其他区域) 标签后其余标签重新排列
+// 网格和停靠区域 (zones.rs) 互斥，加入网格的标签取消停靠
+
+use std::sync::Mutex;
+
+// 格子之间的空隙 (物理像素)
+const GAP: i32 = 6;
+
+// 网格中的标签，按加入顺序从左到右、从上到下排列
+static MEMBERS: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+/// 网格中的标签
+pub fn members() -> Vec<isize> {
+    MEMBERS.lock().unwrap().clone()
+}
+
+pub fn contains(tab: isize) -> bool {
+    MEMBERS.lock().unwrap().contains(&tab)
+}
+
+/// 把标签加入网格 (已在网格中的保持原来的位置)，返回加入后的所有标签
+pub fn add(tabs: &[isize]) -> Vec<isize> {
+    let mut members = MEMBERS.lock().unwrap();
+    for &tab in tabs {
+        if !members.contains(&tab) {
+            members.push(tab);
+        }
+    }
+    members.clone()
+}
+
+/// 把标签移出网格 (释放、关闭或停靠到其他区域时)，返回标签是否在网格中
+pub fn remove(tab: isize) -> bool {
+    let mut members = MEMBERS.lock().unwrap();
+    let before = members.len();
+    members.retain(|h| *h != tab);
+    members.len() != before
+}
+
+/// n 个标签的网格列数和行数 (列数不少于行数)
+pub fn dimensions(n: usize) -> (usize, usize) {
+    if n == 0 {
+        return (0, 0);
+    }
+    let columns = (1..=n).find(|c| c * c >= n).unwrap_or(n);
+    (columns, n.div_ceil(columns))
+}
+
+/// 网格中的标签在内容区 area (x, y, 宽, 高) 中的位置和大小，不在网格中时为 None
+pub fn rect(tab: isize, area: (i32, i32, i32, i32)) -> Option<(i32, i32, i32, i32)> {
+    let members = MEMBERS.lock().unwrap();
+    let index = members.iter().position(|h| *h == tab)?;
+    Some(cell(index, members.len(), area))
+}
+
+// 第 index 个格子的位置：按列数/行数均分 (加上空隙后再分，余数分散到各格)，格子之间留出 GAP
+fn cell(index: usize, n: usize, area: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+    let (x, y, width, height) = area;
+    let (columns, rows) = dimensions(n);
+    let (column, row) = ((index % columns) as i32, (index / columns) as i32);
+    let span = |start: i32, len: i32, count: i32, i: i32| {
+        let from = start + i * (len + GAP) / count;
+        let to = start + (i + 1) * (len + GAP) / count - GAP;
+        (from, to - from)
+    };
+    let (x, width) = span(x, width, columns as i32, column);
+    let (y, height) = span(y, height, rows as i32, row);
+    (x, y, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_grows_with_the_number_of_tabs() {
+        assert_eq!(dimensions(1), (1, 1));
+        assert_eq!(dimensions(2), (2, 1));
+        assert_eq!(dimensions(4), (2, 2));
+        assert_eq!(dimensions(5), (3, 2));
+        assert_eq!(dimensions(9), (3, 3));
+        assert_eq!(dimensions(10), (4, 3));
+
+        // 2x2：格子之间留出相同的空隙，铺满内容区
+        let area = (0, 80, 1006, 806);
+        assert_eq!(cell(0, 4, area), (0, 80, 500, 400));
+        assert_eq!(cell(1, 4, area), (506, 80, 500, 400));
+        assert_eq!(cell(3, 4, area), (506, 486, 500, 400));
+    }
+
+    #[test]
+    fn removing_a_tab_rearranges_the_rest() {
+        let (a, b, c) = (0x71, 0x72, 0x73);
+        assert_eq!(add(&[a, b, c]), vec![a, b, c]);
+        let area = (0, 0, 1000, 1000);
+        assert_eq!(rect(c, area).unwrap().1, 503);
+
+        assert!(remove(b));
+        assert!(!remove(b));
+        // 剩下两个排成一行
+        assert_eq!(rect(c, area), Some((503, 0, 497, 1000)));
+
+        remove(a);
+        remove(c);
+    }
+}
@@ -0,0 +1,181 @@
+// 窗口高亮边框
+// 在外部窗口周围画一圈彩色边框，用于窗口选择器悬停预览和拾取模式
+// 边框是一个置顶、鼠标穿透的分层窗口，用窗口区域挖空中间只留四条边
+// 边框窗口属于一个单独的线程 (第一次使用时创建)，其他线程通过线程消息移动或隐藏它，
+// 调用方 (包括低级鼠标钩子) 不会被阻塞
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+#[cfg(windows)]
+use std::sync::OnceLock;
+#[cfg(windows)]
+use tracing::warn;
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM},
+        Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS},
+        Graphics::Gdi::{CombineRgn, CreateRectRgn, CreateSolidBrush, DeleteObject, SetWindowRgn, RGN_DIFF},
+        System::LibraryLoader::GetModuleHandleW,
+        System::Threading::GetCurrentThreadId,
+        UI::WindowsAndMessaging::*,
+    },
+};
+
+// 边框宽度和颜色 (与前端的强调色一致，COLORREF 为 0x00BBGGRR)
+#[cfg(windows)]
+const BORDER: i32 = 4;
+#[cfg(windows)]
+const BORDER_COLOR: COLORREF = COLORREF(0x00FA_B489);
+
+// 线程消息：wparam 为要高亮的窗口，0 表示隐藏
+#[cfg(windows)]
+const WM_HIGHLIGHT: u32 = WM_APP + 1;
+
+// 边框线程 ID 和边框窗口
+#[cfg(windows)]
+static THREAD: OnceLock<u32> = OnceLock::new();
+#[cfg(windows)]
+static OVERLAY: AtomicIsize = AtomicIsize::new(0);
+
+// 当前高亮的窗口
+static CURRENT: AtomicIsize = AtomicIsize::new(0);
+
+/// 高亮窗口，target 为 0 时隐藏边框
+pub fn show(target: isize) {
+    if CURRENT.swap(target, Ordering::SeqCst) == target {
+        return;
+    }
+    #[cfg(windows)]
+    unsafe {
+        let _ = PostThreadMessageW(thread(), WM_HIGHLIGHT, WPARAM(target as usize), LPARAM(0));
+    }
+}
+
+/// 取消高亮 (只在 target 仍是当前高亮的窗口时隐藏，避免把后来的高亮清掉)
+pub fn hide(target: isize) {
+    if CURRENT.load(Ordering::SeqCst) == target {
+        show(0);
+    }
+}
+
+/// 高亮边框窗口本身 (WindowFromPoint 等需要跳过它)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn is_overlay(hwnd: isize) -> bool {
+    #[cfg(windows)]
+    {
+        hwnd != 0 && hwnd == OVERLAY.load(Ordering::SeqCst)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = hwnd;
+        false
+    }
+}
+
+// 边框线程 (第一次调用时启动，等线程消息队列建立后返回)
+#[cfg(windows)]
+fn thread() -> u32 {
+    *THREAD.get_or_init(|| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || unsafe {
+            let overlay = create_overlay();
+            // 确保线程消息队列已建立，之后发送的消息不会丢失
+            let mut msg = MSG::default();
+            let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+            let _ = tx.send(GetCurrentThreadId());
+
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+                if msg.hwnd.is_invalid() && msg.message == WM_HIGHLIGHT {
+                    if let Some(overlay) = overlay {
+                        place(overlay, msg.wParam.0 as isize);
+                    }
+                    continue;
+                }
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+        rx.recv().unwrap_or(0)
+    })
+}
+
+#[cfg(windows)]
+unsafe fn create_overlay() -> Option<HWND> {
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let class_name = w!("WindowHubHighlight");
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(overlay_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        hbrBackground: CreateSolidBrush(BORDER_COLOR),
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+    let overlay = CreateWindowExW(
+        WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOOLWINDOW | WS_EX_TOPMOST | WS_EX_NOACTIVATE,
+        class_name,
+        w!(""),
+        WS_POPUP,
+        0,
+        0,
+        0,
+        0,
+        HWND::default(),
+        None,
+        instance,
+        None,
+    );
+    match overlay {
+        Ok(overlay) => {
+            let _ = SetLayeredWindowAttributes(overlay, COLORREF(0), 255, LWA_ALPHA);
+            OVERLAY.store(overlay.0 as isize, Ordering::SeqCst);
+            Some(overlay)
+        }
+        Err(e) => {
+            warn!(target: "embed", "创建高亮边框窗口失败: {:?}", e);
+            None
+        }
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn overlay_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+// 把边框移到目标窗口外圈，目标为 0 或已关闭时隐藏
+#[cfg(windows)]
+unsafe fn place(overlay: HWND, target: isize) {
+    let hwnd = HWND(target as *mut _);
+    // 可见边框 (不含 Win10 以上的透明阴影区域)
+    let mut rect = RECT::default();
+    let size = std::mem::size_of::<RECT>() as u32;
+    let found = target != 0
+        && IsWindow(hwnd).as_bool()
+        && (DwmGetWindowAttribute(hwnd, DWMWA_EXTENDED_FRAME_BOUNDS, &mut rect as *mut _ as *mut _, size).is_ok()
+            || GetWindowRect(hwnd, &mut rect).is_ok());
+    if !found {
+        let _ = ShowWindow(overlay, SW_HIDE);
+        return;
+    }
+    let width = rect.right - rect.left + BORDER * 2;
+    let height = rect.bottom - rect.top + BORDER * 2;
+    let outer = CreateRectRgn(0, 0, width, height);
+    let inner = CreateRectRgn(BORDER, BORDER, width - BORDER, height - BORDER);
+    CombineRgn(outer, outer, inner, RGN_DIFF);
+    let _ = DeleteObject(inner);
+    // 区域交给系统管理，不需要删除
+    SetWindowRgn(overlay, outer, true);
+    let _ = SetWindowPos(
+        overlay,
+        HWND_TOPMOST,
+        rect.left - BORDER,
+        rect.top - BORDER,
+        width,
+        height,
+        SWP_NOACTIVATE | SWP_SHOWWINDOW,
+    );
+}
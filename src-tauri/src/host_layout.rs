@@ -0,0 +1,129 @@
+// 嵌入窗口跟随主窗口调整大小
+// 之前每次 resize 都由前端计算内容区位置再调用 update_window_rect，拖动边框时每帧一次 IPC 往返，嵌入窗口明显落后于主窗口
+// 前端在布局变化时上报内容区到主窗口客户区四边的距离 (物理像素)，
+// 主窗口 Resized 事件中直接在后端算出内容区，移动当前标签 (经 resize 调度线程合并)
+// 停靠的标签 (zones.rs) 放到内容区中对应的区域，当前标签停靠时所有停靠的标签一起移动
+// 监控墙网格 (grid.rs) 中的标签放到各自的格子，当前标签在网格中时整个网格一起移动
+// 临时最大化的标签占满整个客户区 (盖住标签栏)
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+
+use crate::zones::{self, Zone};
+use crate::{grid, resize, zoom};
+
+/// 内容区到主窗口客户区四边的距离 (物理像素)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Insets {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+static LAYOUT: Mutex<Option<Insets>> = Mutex::new(None);
+
+// 当前显示的标签，主窗口调整大小时只移动它
+static ACTIVE_TAB: AtomicIsize = AtomicIsize::new(0);
+
+// 主窗口客户区大小 (最近一次 Resized)，放置最大化的标签时使用
+static HOST_SIZE: Mutex<(i32, i32)> = Mutex::new((0, 0));
+
+pub fn set_layout(insets: Insets) {
+    *LAYOUT.lock().unwrap() = Some(insets);
+}
+
+/// 前端上报的内容区边距 (还没有上报时为 None)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn insets() -> Option<Insets> {
+    *LAYOUT.lock().unwrap()
+}
+
+/// 记录当前显示的标签 (activate_window 时调用)，返回之前的标签
+pub fn set_active(tab: isize) -> isize {
+    ACTIVE_TAB.swap(tab, Ordering::SeqCst)
+}
+
+/// 当前显示的标签
+pub fn active() -> isize {
+    ACTIVE_TAB.load(Ordering::SeqCst)
+}
+
+/// 标签被释放或关闭 (是当前标签时清空，主窗口调整大小时不再移动它)
+pub fn forget(tab: isize) {
+    let _ = ACTIVE_TAB.compare_exchange(tab, 0, Ordering::SeqCst, Ordering::SeqCst);
+}
+
+/// 把标签放到主窗口客户区的指定位置 (x, y, width, height 为内容区，停靠的标签放到其中对应的区域)
+/// 缩放的标签按 1/scale 布局，由放大镜覆盖窗口放大
+pub fn place(tab: isize, x: i32, y: i32, width: i32, height: i32) {
+    let (x, y, width, height) = slot(tab, (x, y, width, height));
+    resize::schedule(tab, x, y, width, height);
+}
+
+/// 内容区为 area 时标签的最终位置和大小 (客户区坐标)
+pub fn slot(tab: isize, area: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+    let host = *HOST_SIZE.lock().unwrap();
+    let (x, y, width, height) = if zones::maximized() == Some(tab) && host.0 > 0 && host.1 > 0 {
+        (0, 0, host.0, host.1)
+    } else if let Some(cell) = grid::rect(tab, area) {
+        cell
+    } else {
+        zones::rect(zones::zone_of(tab), area, zones::split())
+    };
+    let scale = zoom::scale_of(tab);
+    let width = (width as f32 / scale).round() as i32;
+    let height = (height as f32 / scale).round() as i32;
+    (x, y, width, height)
+}
+
+/// 主窗口客户区大小变化 (WindowEvent::Resized)
+pub fn host_resized(width: u32, height: u32) {
+    *HOST_SIZE.lock().unwrap() = (width as i32, height as i32);
+    let tab = ACTIVE_TAB.load(Ordering::SeqCst);
+    let Some(insets) = *LAYOUT.lock().unwrap() else { return };
+    if tab == 0 {
+        return;
+    }
+    let Some((x, y, w, h)) = content_rect(insets, width as i32, height as i32) else { return };
+    let tabs = if zones::maximized() == Some(tab) {
+        vec![tab]
+    } else if grid::contains(tab) {
+        grid::members()
+    } else if zones::zone_of(tab) == Zone::Full {
+        vec![tab]
+    } else {
+        zones::docked()
+    };
+    for tab in tabs {
+        place(tab, x, y, w, h);
+    }
+}
+
+/// 客户区大小为 width x height 时内容区的位置和大小 (还没有上报布局时为 None)
+pub fn content_area(width: u32, height: u32) -> Option<(i32, i32, i32, i32)> {
+    let insets = (*LAYOUT.lock().unwrap())?;
+    content_rect(insets, width as i32, height as i32)
+}
+
+// 客户区大小为 width x height 时内容区的位置和大小，最小化等内容区为空时返回 None
+fn content_rect(insets: Insets, width: i32, height: i32) -> Option<(i32, i32, i32, i32)> {
+    let w = width - insets.left - insets.right;
+    let h = height - insets.top - insets.bottom;
+    (w > 0 && h > 0).then_some((insets.left, insets.top, w, h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_rect_follows_host_size() {
+        let insets = Insets { left: 0, top: 80, right: 0, bottom: 24 };
+        assert_eq!(content_rect(insets, 1920, 1080), Some((0, 80, 1920, 976)));
+        assert_eq!(content_rect(insets, 800, 600), Some((0, 80, 800, 496)));
+        // 最小化时客户区为 0
+        assert_eq!(content_rect(insets, 0, 0), None);
+    }
+}
@@ -0,0 +1,166 @@
+// 主窗口的位置和大小
+// 退出和关闭 (隐藏) 主窗口时记录所在显示器、还原后的位置和大小、是否最大化 (GetWindowPlacement)，
+// 下次启动时恢复，而不是每次都从默认大小开始
+// 恢复前检查位置是否还在屏幕上：显示器拔掉或分辨率变小后，窗口标题栏露出的部分太少时，
+// 移到原来的显示器 (仍然存在时) 或主显示器上，并缩小到工作区以内
+// 保存在数据库中 (storage.rs)，展台模式下由 kiosk.rs 控制窗口，不保存也不恢复
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use crate::monitors::{self, Monitor};
+use crate::window_manager::WindowRect;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, RECT},
+    Graphics::Gdi::{MonitorFromWindow, MONITOR_DEFAULTTONEAREST},
+    UI::WindowsAndMessaging::{GetWindowPlacement, SetWindowPlacement, SW_SHOWMAXIMIZED, SW_SHOWNORMAL, WINDOWPLACEMENT},
+};
+
+const STORAGE_KEY: &str = "host_placement";
+
+// 标题栏至少要露出这么多 (物理像素) 才能用鼠标拖回来
+const MIN_VISIBLE: i32 = 48;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostPlacement {
+    pub monitor: String,  // 显示器设备名，如 \\.\DISPLAY2
+    pub rect: WindowRect, // 还原后的位置和大小
+    pub maximized: bool,
+}
+
+/// 记录主窗口当前的位置 (关闭主窗口和退出时调用)
+pub fn save(app: &AppHandle) {
+    if crate::kiosk::is_enabled() {
+        return;
+    }
+    let Some(placement) = current(app) else { return };
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save(STORAGE_KEY, &placement) {
+            warn!(target: "settings", "保存主窗口位置失败: {}", e);
+        }
+    }
+}
+
+/// 恢复上次的位置 (setup 时调用)
+pub fn restore(app: &AppHandle) {
+    if crate::kiosk::is_enabled() {
+        return;
+    }
+    let Some(saved) = crate::storage::get().and_then(|s| s.load::<HostPlacement>(STORAGE_KEY)) else { return };
+    let monitors = monitors::list();
+    let Some(rect) = clamp(&saved, &monitors) else { return };
+    info!(target: "settings", "恢复主窗口位置: {:?} 最大化={}", rect, saved.maximized);
+    apply(app, rect, saved.maximized);
+}
+
+/// 让窗口回到屏幕上：标题栏露出足够多时保持不变，否则移到原来的显示器或主显示器上
+/// 没有任何显示器时返回 None
+fn clamp(saved: &HostPlacement, monitors: &[Monitor]) -> Option<WindowRect> {
+    let rect = saved.rect;
+    let title = WindowRect { bottom: rect.top + MIN_VISIBLE, ..rect };
+    let visible = monitors.iter().any(|m| {
+        let width = title.right.min(m.work.right) - title.left.max(m.work.left);
+        let height = title.bottom.min(m.work.bottom) - title.top.max(m.work.top);
+        width >= MIN_VISIBLE && height >= MIN_VISIBLE
+    });
+    if visible && rect.top >= monitors.iter().map(|m| m.work.top).min()? {
+        return Some(rect);
+    }
+    let target = monitors
+        .iter()
+        .find(|m| m.name.eq_ignore_ascii_case(&saved.monitor))
+        .or_else(|| monitors.iter().find(|m| m.primary))
+        .or(monitors.first())?
+        .work;
+    let width = rect.width().min(target.width());
+    let height = rect.height().min(target.height());
+    let left = rect.left.clamp(target.left, target.right - width);
+    let top = rect.top.clamp(target.top, target.bottom - height);
+    Some(WindowRect { left, top, right: left + width, bottom: top + height })
+}
+
+#[cfg(windows)]
+fn host(app: &AppHandle) -> Option<HWND> {
+    use tauri::Manager;
+    let hwnd = app.get_webview_window("main")?.hwnd().ok()?;
+    Some(HWND(hwnd.0))
+}
+
+// rcNormalPosition 是工作区坐标，与屏幕坐标只差任务栏在主显示器上/左侧时的宽度，检查是否在屏幕上时忽略这点差别
+#[cfg(windows)]
+fn current(app: &AppHandle) -> Option<HostPlacement> {
+    let hwnd = host(app)?;
+    unsafe {
+        let mut placement = WINDOWPLACEMENT { length: std::mem::size_of::<WINDOWPLACEMENT>() as u32, ..Default::default() };
+        GetWindowPlacement(hwnd, &mut placement).ok()?;
+        Some(HostPlacement {
+            monitor: monitors::name(MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST)).unwrap_or_default(),
+            rect: WindowRect::from(placement.rcNormalPosition),
+            maximized: placement.showCmd == SW_SHOWMAXIMIZED.0 as u32,
+        })
+    }
+}
+
+#[cfg(not(windows))]
+fn current(_app: &AppHandle) -> Option<HostPlacement> {
+    None
+}
+
+#[cfg(windows)]
+fn apply(app: &AppHandle, rect: WindowRect, maximized: bool) {
+    let Some(hwnd) = host(app) else { return };
+    let placement = WINDOWPLACEMENT {
+        length: std::mem::size_of::<WINDOWPLACEMENT>() as u32,
+        showCmd: if maximized { SW_SHOWMAXIMIZED.0 as u32 } else { SW_SHOWNORMAL.0 as u32 },
+        rcNormalPosition: RECT { left: rect.left, top: rect.top, right: rect.right, bottom: rect.bottom },
+        ..Default::default()
+    };
+    if let Err(e) = unsafe { SetWindowPlacement(hwnd, &placement) } {
+        warn!(target: "settings", "恢复主窗口位置失败: {}", e);
+    }
+}
+
+#[cfg(not(windows))]
+fn apply(_app: &AppHandle, _rect: WindowRect, _maximized: bool) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> WindowRect {
+        WindowRect { left, top, right, bottom }
+    }
+
+    fn monitors() -> Vec<Monitor> {
+        vec![
+            Monitor { name: r"\\.\DISPLAY1".to_string(), bounds: rect(0, 0, 1920, 1080), work: rect(0, 0, 1920, 1040), dpi: 96, primary: true },
+            Monitor { name: r"\\.\DISPLAY2".to_string(), bounds: rect(1920, 0, 3840, 1080), work: rect(1920, 0, 3840, 1040), dpi: 96, primary: false },
+        ]
+    }
+
+    fn saved(monitor: &str, rect: WindowRect) -> HostPlacement {
+        HostPlacement { monitor: monitor.to_string(), rect, maximized: false }
+    }
+
+    #[test]
+    fn visible_placement_is_kept() {
+        let placement = saved(r"\\.\DISPLAY2", rect(1800, 100, 2600, 700));
+        assert_eq!(clamp(&placement, &monitors()), Some(placement.rect));
+    }
+
+    #[test]
+    fn placement_on_removed_monitor_moves_to_primary() {
+        let placement = saved(r"\\.\DISPLAY3", rect(4000, 100, 4800, 700));
+        assert_eq!(clamp(&placement, &monitors()), Some(rect(1120, 100, 1920, 700)));
+    }
+
+    #[test]
+    fn oversized_placement_shrinks_to_work_area() {
+        let placement = saved(r"\\.\DISPLAY2", rect(1900, -500, 4500, 1500));
+        assert_eq!(clamp(&placement, &monitors()), Some(rect(1920, 0, 3840, 1040)));
+        assert_eq!(clamp(&placement, &[]), None);
+    }
+}
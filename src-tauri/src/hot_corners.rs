@@ -0,0 +1,234 @@
+// 热角 (设置 input.hot_corners)
+// 鼠标推到屏幕的角落或边缘并停留一会儿 (input.hot_corner_delay_ms) 时触发操作，如右上角显示/隐藏主窗口、
+// 下边缘打开搜索，作为快捷键之外的另一种唤出方式
+// 低级鼠标钩子 (单独的线程，只在配置了热角时运行) 跟踪光标：
+// - 只算显示器的外边缘，光标经过两个显示器相接的边时不触发
+// - 按住鼠标键 (拖动窗口、选择文本) 时不触发
+// - 触发后光标离开该位置才能再次触发
+// 前台是独占全屏的程序 (游戏、演示) 时不触发
+
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+use tracing::info;
+
+use crate::mouse_hook::MouseHook;
+use crate::settings::{HotCorner, ScreenEdge};
+
+#[cfg(windows)]
+use crate::settings::HotCornerAction;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+#[cfg(windows)]
+use tauri::Manager;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{LPARAM, LRESULT, POINT, WPARAM},
+    Graphics::Gdi::{GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTONULL},
+    UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_LBUTTON, VK_MBUTTON, VK_RBUTTON},
+    UI::WindowsAndMessaging::*,
+};
+
+// 角落的大小 (物理像素)：光标在一条边上且离另一条边不到这么远时算角落
+const CORNER_SIZE: i32 = 8;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static HOOK: MouseHook = MouseHook::new("hot_corners", "热角鼠标");
+
+// 当前的热角配置和停留时间，钩子中不重复读取设置
+static CONFIG: Mutex<(Vec<HotCorner>, u64)> = Mutex::new((Vec::new(), 0));
+
+// 光标当前所在的热角，以及是否已经触发过
+#[cfg(windows)]
+static CURRENT: Mutex<Option<ScreenEdge>> = Mutex::new(None);
+#[cfg(windows)]
+static FIRED: AtomicBool = AtomicBool::new(false);
+
+// 停留计时器
+#[cfg(windows)]
+static TIMER: AtomicUsize = AtomicUsize::new(0);
+#[cfg(windows)]
+static TIMER_RESOURCE: AtomicU64 = AtomicU64::new(0);
+
+/// 按设置开启或关闭 (启动和设置变化时调用)
+pub fn apply(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let input = crate::settings::get().input;
+    let enabled = !input.hot_corners.is_empty() && crate::session_watch::is_active();
+    *CONFIG.lock().unwrap() = (input.hot_corners, input.hot_corner_delay_ms);
+    if !HOOK.set_enabled(enabled) {
+        return;
+    }
+    info!(target: "shortcuts", "{}热角", if enabled { "开启" } else { "关闭" });
+    #[cfg(windows)]
+    if enabled {
+        HOOK.spawn(Some(mouse_proc), on_message, on_exit);
+    }
+}
+
+/// 光标所在的显示器边缘或角落
+/// monitor 为显示器的矩形 (左, 上, 右, 下)，open 为左、上、右、下各边在光标处是否是外边缘 (外面没有其他显示器)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn edge_at(x: i32, y: i32, monitor: (i32, i32, i32, i32), open: [bool; 4]) -> Option<ScreenEdge> {
+    let (left, top, right, bottom) = monitor;
+    let at_left = open[0] && x <= left;
+    let at_top = open[1] && y <= top;
+    let at_right = open[2] && x >= right - 1;
+    let at_bottom = open[3] && y >= bottom - 1;
+    let near_left = x < left + CORNER_SIZE;
+    let near_top = y < top + CORNER_SIZE;
+    let near_right = x >= right - CORNER_SIZE;
+    let near_bottom = y >= bottom - CORNER_SIZE;
+
+    if (at_top && near_left) || (at_left && near_top) {
+        Some(ScreenEdge::TopLeft)
+    } else if (at_top && near_right) || (at_right && near_top) {
+        Some(ScreenEdge::TopRight)
+    } else if (at_bottom && near_left) || (at_left && near_bottom) {
+        Some(ScreenEdge::BottomLeft)
+    } else if (at_bottom && near_right) || (at_right && near_bottom) {
+        Some(ScreenEdge::BottomRight)
+    } else if at_top {
+        Some(ScreenEdge::Top)
+    } else if at_bottom {
+        Some(ScreenEdge::Bottom)
+    } else if at_left {
+        Some(ScreenEdge::Left)
+    } else if at_right {
+        Some(ScreenEdge::Right)
+    } else {
+        None
+    }
+}
+
+// 钩子线程收到的消息：停留计时器 (线程计时器的 WM_TIMER 没有窗口可以分发)
+#[cfg(windows)]
+fn on_message(msg: &MSG) -> bool {
+    if msg.message != WM_TIMER {
+        return false;
+    }
+    unsafe { on_dwell() };
+    true
+}
+
+#[cfg(windows)]
+fn on_exit() {
+    unsafe { reset_dwell() };
+    *CURRENT.lock().unwrap() = None;
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 && wparam.0 as u32 == WM_MOUSEMOVE {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        on_move(info.pt);
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+// 光标移动：进入新的热角时开始计时，离开时取消
+#[cfg(windows)]
+unsafe fn on_move(pt: POINT) {
+    let buttons_down = [VK_LBUTTON, VK_RBUTTON, VK_MBUTTON].iter().any(|vk| GetAsyncKeyState(vk.0 as i32) < 0);
+    let (corners, delay_ms) = CONFIG.lock().unwrap().clone();
+    let edge = if buttons_down { None } else { edge_of(pt) }.filter(|edge| corners.iter().any(|c| c.edge == *edge));
+
+    let mut current = CURRENT.lock().unwrap();
+    if *current == edge {
+        return;
+    }
+    *current = edge;
+    FIRED.store(false, Ordering::SeqCst);
+    reset_dwell();
+    if edge.is_some() {
+        let delay = (delay_ms as u32).max(USER_TIMER_MINIMUM);
+        TIMER.store(SetTimer(None, 0, delay, None), Ordering::SeqCst);
+        let resource = crate::resources::register(crate::resources::ResourceKind::Timer, "hot_corners", 1, None, None);
+        TIMER_RESOURCE.store(resource, Ordering::SeqCst);
+    }
+}
+
+// 光标所在显示器的边缘或角落
+#[cfg(windows)]
+unsafe fn edge_of(pt: POINT) -> Option<ScreenEdge> {
+    let monitor = MonitorFromPoint(pt, MONITOR_DEFAULTTONEAREST);
+    let mut info = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+    if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+        return None;
+    }
+    let rect = info.rcMonitor;
+    let outside = |x: i32, y: i32| MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONULL).is_invalid();
+    let open = [
+        outside(rect.left - 1, pt.y),
+        outside(pt.x, rect.top - 1),
+        outside(rect.right, pt.y),
+        outside(pt.x, rect.bottom),
+    ];
+    edge_at(pt.x, pt.y, (rect.left, rect.top, rect.right, rect.bottom), open)
+}
+
+#[cfg(windows)]
+unsafe fn reset_dwell() {
+    let timer = TIMER.swap(0, Ordering::SeqCst);
+    if timer != 0 {
+        let _ = KillTimer(None, timer);
+        crate::resources::unregister(TIMER_RESOURCE.swap(0, Ordering::SeqCst));
+    }
+}
+
+// 停留时间到：触发光标所在热角的操作 (每次进入只触发一次)
+#[cfg(windows)]
+unsafe fn on_dwell() {
+    reset_dwell();
+    let Some(edge) = *CURRENT.lock().unwrap() else { return };
+    if FIRED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let Some(action) = CONFIG.lock().unwrap().0.iter().find(|c| c.edge == edge).map(|c| c.action) else { return };
+    if crate::platform::current().exclusive_fullscreen_active() {
+        return;
+    }
+    let Some(app) = APP.get().cloned() else { return };
+    info!(target: "shortcuts", "热角 {:?}: {:?}", edge, action);
+    // 操作可能较慢 (如嵌入窗口)，不能阻塞钩子线程
+    std::thread::spawn(move || trigger(&app, action));
+}
+
+#[cfg(windows)]
+fn trigger(app: &AppHandle, action: HotCornerAction) {
+    // 搜索和切换标签需要看到主窗口
+    if matches!(action, HotCornerAction::Search | HotCornerAction::NextTab | HotCornerAction::PrevTab) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+    crate::handle_shortcut_action(app, action.shortcut_action());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: (i32, i32, i32, i32) = (0, 0, 1920, 1080);
+
+    #[test]
+    fn corners_and_edges_on_a_single_monitor() {
+        let open = [true; 4];
+        assert_eq!(edge_at(1919, 0, MONITOR, open), Some(ScreenEdge::TopRight));
+        assert_eq!(edge_at(1915, 0, MONITOR, open), Some(ScreenEdge::TopRight));
+        assert_eq!(edge_at(0, 1079, MONITOR, open), Some(ScreenEdge::BottomLeft));
+        assert_eq!(edge_at(960, 1079, MONITOR, open), Some(ScreenEdge::Bottom));
+        assert_eq!(edge_at(0, 500, MONITOR, open), Some(ScreenEdge::Left));
+        assert_eq!(edge_at(960, 500, MONITOR, open), None);
+    }
+
+    #[test]
+    fn shared_edge_between_monitors_is_not_a_trigger() {
+        // 右边接着另一个显示器
+        let open = [true, true, false, true];
+        assert_eq!(edge_at(1919, 500, MONITOR, open), None);
+        assert_eq!(edge_at(1919, 0, MONITOR, open), Some(ScreenEdge::TopRight));
+    }
+}
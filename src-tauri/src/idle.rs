@@ -0,0 +1,236 @@
+// 标签的空闲时间
+// 记录每个标签最后一次收到用户输入的时间，前端据此把长时间没有使用的标签变暗
+// 低级鼠标/键盘钩子 (单独的线程) 只统计主窗口在前台时的输入：
+// - 鼠标：光标下的窗口所属的标签
+// - 键盘：有输入焦点的窗口所属的标签
+// 标签是主窗口的直接子窗口，输入落在标签内部的子窗口上时向上查找
+// 切换到标签也算一次使用；从未收到输入的标签从嵌入时开始计算
+// 同时记录整个主窗口最后一次收到输入的时间 (光标在主窗口或标签上、主窗口在前台时按键)，
+// 设置了 input.idle_hide_minutes 时，主窗口超过这么长时间没有输入就隐藏到托盘，用切换窗口的快捷键 (Alt+Space) 再显示
+// 输入钩子只有 Windows 上有，其他平台不知道用户是否在使用，不做空闲隐藏，主窗口也不算空闲
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::clock::now_secs;
+
+#[cfg(windows)]
+use std::sync::atomic::AtomicIsize;
+#[cfg(windows)]
+use std::time::Duration;
+#[cfg(windows)]
+use tauri::Manager;
+#[cfg(windows)]
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+    System::LibraryLoader::GetModuleHandleW,
+    UI::WindowsAndMessaging::*,
+};
+
+// 主窗口
+#[cfg(windows)]
+static HOST: AtomicIsize = AtomicIsize::new(0);
+
+// 标签最后一次收到输入的时间 (Unix 秒)
+static LAST_INPUT: Mutex<Vec<(isize, u64)>> = Mutex::new(Vec::new());
+
+// 主窗口最后一次收到输入的时间 (Unix 秒)
+static HOST_INPUT: AtomicU64 = AtomicU64::new(0);
+
+// 检查是否需要空闲隐藏的间隔
+#[cfg(windows)]
+const AUTO_HIDE_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TabIdle {
+    pub hwnd: isize,
+    pub idle_secs: u64,
+}
+
+/// 开始统计主窗口 host 中的输入
+pub fn start(host: isize) {
+    #[cfg(windows)]
+    {
+        if host == 0 || HOST.swap(host, Ordering::SeqCst) != 0 {
+            return;
+        }
+        std::thread::spawn(|| unsafe { hook_thread() });
+    }
+    #[cfg(not(windows))]
+    let _ = host;
+}
+
+/// 标签收到了输入或被切换到
+pub fn touch(tab: isize) {
+    let now = now_secs();
+    let mut last = LAST_INPUT.lock().unwrap();
+    match last.iter_mut().find(|(h, _)| *h == tab) {
+        Some(entry) => entry.1 = now,
+        None => last.push((tab, now)),
+    }
+}
+
+/// 主窗口空闲超过 input.idle_hide_minutes 时隐藏到托盘 (只在 Windows 上)
+#[cfg(not(windows))]
+pub fn start_auto_hide(_app: &AppHandle) {}
+
+/// 主窗口空闲超过 input.idle_hide_minutes 时隐藏到托盘 (只在 Windows 上)
+#[cfg(windows)]
+pub fn start_auto_hide(app: &AppHandle) {
+    HOST_INPUT.store(now_secs(), Ordering::SeqCst);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut was_visible = true;
+        loop {
+            std::thread::sleep(AUTO_HIDE_INTERVAL);
+            // 锁屏期间不算空闲，解锁后重新计算
+            if !crate::session_watch::is_active() {
+                HOST_INPUT.store(now_secs(), Ordering::SeqCst);
+                continue;
+            }
+            let Some(window) = app.get_webview_window("main") else { continue };
+            let visible = window.is_visible().unwrap_or(false);
+            // 重新显示后从显示时开始计算
+            if visible && !was_visible {
+                HOST_INPUT.store(now_secs(), Ordering::SeqCst);
+            }
+            was_visible = visible;
+            let minutes = crate::settings::get().input.idle_hide_minutes;
+            if !visible || crate::kiosk::is_enabled() || !should_hide(HOST_INPUT.load(Ordering::SeqCst), minutes, now_secs()) {
+                continue;
+            }
+            info!(target: "tray", "主窗口 {} 分钟没有输入，隐藏到托盘", minutes);
+            let _ = window.hide();
+            was_visible = false;
+        }
+    });
+}
+
+/// 主窗口多长时间没有收到输入 (秒)，没有输入钩子的平台总是 0
+pub fn host_idle_secs() -> u64 {
+    if cfg!(windows) {
+        now_secs().saturating_sub(HOST_INPUT.load(Ordering::SeqCst))
+    } else {
+        0
+    }
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn should_hide(last_input: u64, minutes: u64, now: u64) -> bool {
+    minutes > 0 && now.saturating_sub(last_input) >= minutes * 60
+}
+
+/// 标签已释放或关闭
+pub fn forget(tab: isize) {
+    LAST_INPUT.lock().unwrap().retain(|(h, _)| *h != tab);
+}
+
+/// 各标签的空闲时间，tabs 为 (句柄, 嵌入时间)
+pub fn idle_times(tabs: &[(isize, u64)]) -> Vec<TabIdle> {
+    idle_times_at(&LAST_INPUT.lock().unwrap(), tabs, now_secs())
+}
+
+fn idle_times_at(last_input: &[(isize, u64)], tabs: &[(isize, u64)], now: u64) -> Vec<TabIdle> {
+    tabs.iter()
+        .map(|&(hwnd, embedded_at)| {
+            let last = last_input.iter().find(|(h, _)| *h == hwnd).map_or(embedded_at, |(_, t)| *t);
+            TabIdle { hwnd, idle_secs: now.saturating_sub(last) }
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+unsafe fn hook_thread() {
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let hooks = [
+        SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), instance, 0),
+        SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), instance, 0),
+    ];
+    let hook_count = hooks.iter().filter(|h| h.is_ok()).count() as u32;
+    let resource = crate::resources::register(crate::resources::ResourceKind::LowLevelHook, "idle", hook_count, None, None);
+    if hooks.iter().all(|h| h.is_ok()) {
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    } else {
+        warn!(target: "embed", "安装输入钩子失败，无法统计标签空闲时间");
+    }
+    for hook in hooks.into_iter().flatten() {
+        let _ = UnhookWindowsHookEx(hook);
+    }
+    crate::resources::unregister(resource);
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        let hwnd = WindowFromPoint(info.pt);
+        if GetAncestor(hwnd, GA_ROOT).0 as isize == HOST.load(Ordering::SeqCst) {
+            HOST_INPUT.store(now_secs(), Ordering::SeqCst);
+        }
+        touch_tab_of(hwnd);
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let mut gui = GUITHREADINFO { cbSize: std::mem::size_of::<GUITHREADINFO>() as u32, ..Default::default() };
+        if GetGUIThreadInfo(0, &mut gui).is_ok() {
+            touch_tab_of(gui.hwndFocus);
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+// 记录 hwnd 所属的标签 (主窗口不在前台时忽略)
+#[cfg(windows)]
+unsafe fn touch_tab_of(hwnd: HWND) {
+    let host = HOST.load(Ordering::SeqCst);
+    if GetForegroundWindow().0 as isize != host {
+        return;
+    }
+    HOST_INPUT.store(now_secs(), Ordering::SeqCst);
+    let mut current = hwnd;
+    while !current.is_invalid() {
+        let parent = GetAncestor(current, GA_PARENT);
+        if parent.0 as isize == host {
+            touch(current.0 as isize);
+            return;
+        }
+        current = parent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untouched_tabs_are_idle_since_embedding() {
+        let last_input = [(0x20, 1_000)];
+        let tabs = [(0x20, 100), (0x30, 500)];
+        assert_eq!(
+            idle_times_at(&last_input, &tabs, 4_600),
+            vec![TabIdle { hwnd: 0x20, idle_secs: 3_600 }, TabIdle { hwnd: 0x30, idle_secs: 4_100 }]
+        );
+    }
+
+    #[test]
+    fn host_hides_after_the_configured_idle_minutes() {
+        assert!(!should_hide(1_000, 0, 100_000));
+        assert!(!should_hide(1_000, 5, 1_299));
+        assert!(should_hide(1_000, 5, 1_300));
+        // 时钟回拨时不隐藏
+        assert!(!should_hide(2_000, 5, 1_000));
+    }
+}
@@ -0,0 +1,183 @@
+// 从窗口选择器中学习要忽略的窗口
+// 输入法、悬浮工具条、更新程序之类的窗口偶尔会漏过过滤出现在选择器里，用户每次都把它们划掉：
+// - dismiss_window(hwnd) 记录一次划掉 (按窗口类名 + 程序文件名)，同一种窗口被划掉 LEARN_AFTER 次后自动忽略，
+//   同时发送 window-pattern-ignored (载荷为 IgnorePattern)，前端提示并可以用 unignore_window_pattern 撤销
+// - ignore_window_pattern 直接忽略一种窗口 (类名和程序文件名至少指定一个，都指定时都要相同)
+// 忽略的窗口不再出现在窗口列表中 (window_list.rs)；list_ignored_windows / unignore_window_pattern 查看和移除
+// 列表和划掉的次数保存在数据库中 (storage.rs)
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+const STORAGE_KEY: &str = "ignored_windows";
+
+// 被划掉几次后自动忽略
+const LEARN_AFTER: u32 = 3;
+
+/// 忽略的窗口：类名完全相同 (区分大小写)，程序文件名不区分大小写
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IgnorePattern {
+    #[serde(default)]
+    pub class_name: Option<String>,
+    #[serde(default)]
+    pub exe: Option<String>,
+}
+
+impl IgnorePattern {
+    fn matches(&self, class_name: &str, exe_name: Option<&str>) -> bool {
+        let class_ok = self.class_name.as_deref().is_none_or(|c| c == class_name);
+        let exe_ok = self.exe.as_deref().is_none_or(|e| exe_name.is_some_and(|n| n.eq_ignore_ascii_case(e)));
+        class_ok && exe_ok
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct IgnoreList {
+    patterns: Vec<IgnorePattern>,
+    dismissals: Vec<(IgnorePattern, u32)>, // 还没有忽略的窗口被划掉的次数
+}
+
+static LIST: Mutex<IgnoreList> = Mutex::new(IgnoreList { patterns: Vec::new(), dismissals: Vec::new() });
+
+/// 窗口是否被忽略 (exe_path 为窗口所属程序的路径)
+pub fn is_ignored(class_name: &str, exe_path: Option<&str>) -> bool {
+    let exe_name = exe_path.map(file_name);
+    LIST.lock().unwrap().patterns.iter().any(|p| p.matches(class_name, exe_name))
+}
+
+/// 用户在选择器中划掉了窗口，返回这种窗口是否因此开始被忽略
+pub fn dismiss(app: &AppHandle, class_name: &str, exe_path: Option<&str>) -> bool {
+    let pattern = IgnorePattern {
+        class_name: Some(class_name.to_string()).filter(|c| !c.is_empty()),
+        exe: exe_path.map(|p| file_name(p).to_string()),
+    };
+    if pattern.class_name.is_none() && pattern.exe.is_none() {
+        return false;
+    }
+    let (learned, list) = {
+        let mut list = LIST.lock().unwrap();
+        let learned = record_dismissal(&mut list, pattern.clone());
+        (learned, list.clone())
+    };
+    save(&list);
+    if learned {
+        info!(target: "embed", "窗口多次被划掉，之后忽略: {:?}", pattern);
+        let _ = app.emit("window-pattern-ignored", &pattern);
+    }
+    learned
+}
+
+/// 忽略一种窗口，返回新的列表
+pub fn ignore(pattern: IgnorePattern) -> Result<Vec<IgnorePattern>, String> {
+    let pattern = normalize(pattern).ok_or("窗口类名和程序文件名至少指定一个")?;
+    let list = {
+        let mut list = LIST.lock().unwrap();
+        list.dismissals.retain(|(p, _)| *p != pattern);
+        if !list.patterns.contains(&pattern) {
+            list.patterns.push(pattern.clone());
+        }
+        list.clone()
+    };
+    info!(target: "embed", "忽略窗口: {:?}", pattern);
+    save(&list);
+    Ok(list.patterns)
+}
+
+/// 取消忽略，返回新的列表
+pub fn unignore(pattern: IgnorePattern) -> Vec<IgnorePattern> {
+    let pattern = normalize(pattern);
+    let list = {
+        let mut list = LIST.lock().unwrap();
+        list.patterns.retain(|p| Some(p) != pattern.as_ref());
+        list.clone()
+    };
+    info!(target: "embed", "取消忽略窗口: {:?}", pattern);
+    save(&list);
+    list.patterns
+}
+
+pub fn list() -> Vec<IgnorePattern> {
+    LIST.lock().unwrap().patterns.clone()
+}
+
+/// 从数据库加载 (setup 时调用)
+pub fn load() {
+    let Some(loaded) = crate::storage::get().and_then(|s| s.load::<IgnoreList>(STORAGE_KEY)) else { return };
+    info!(target: "settings", "已加载 {} 个忽略的窗口", loaded.patterns.len());
+    *LIST.lock().unwrap() = loaded;
+}
+
+// 记录一次划掉，达到 LEARN_AFTER 次时移到忽略列表，返回是否移动
+fn record_dismissal(list: &mut IgnoreList, pattern: IgnorePattern) -> bool {
+    if list.patterns.contains(&pattern) {
+        return false;
+    }
+    let count = match list.dismissals.iter_mut().find(|(p, _)| *p == pattern) {
+        Some((_, count)) => {
+            *count += 1;
+            *count
+        }
+        None => {
+            list.dismissals.push((pattern.clone(), 1));
+            1
+        }
+    };
+    if count < LEARN_AFTER {
+        return false;
+    }
+    list.dismissals.retain(|(p, _)| *p != pattern);
+    list.patterns.push(pattern);
+    true
+}
+
+// 去掉空白，程序路径只保留文件名；都为空时为 None
+fn normalize(pattern: IgnorePattern) -> Option<IgnorePattern> {
+    let class_name = pattern.class_name.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    let exe = pattern.exe.map(|e| file_name(e.trim()).to_string()).filter(|e| !e.is_empty());
+    (class_name.is_some() || exe.is_some()).then_some(IgnorePattern { class_name, exe })
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+fn save(list: &IgnoreList) {
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save(STORAGE_KEY, list) {
+            warn!(target: "embed", "{}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(class_name: Option<&str>, exe: Option<&str>) -> IgnorePattern {
+        IgnorePattern { class_name: class_name.map(str::to_string), exe: exe.map(str::to_string) }
+    }
+
+    #[test]
+    fn learns_after_repeated_dismissals() {
+        let mut list = IgnoreList::default();
+        let ime = pattern(Some("IME"), Some("ctfmon.exe"));
+        assert!(!record_dismissal(&mut list, ime.clone()));
+        assert!(!record_dismissal(&mut list, ime.clone()));
+        assert!(record_dismissal(&mut list, ime.clone()));
+        assert_eq!(list.patterns, vec![ime]);
+        assert!(list.dismissals.is_empty());
+    }
+
+    #[test]
+    fn pattern_matches_class_and_exe_name() {
+        let by_exe = pattern(None, Some("Updater.exe"));
+        assert!(by_exe.matches("AnyClass", Some("updater.EXE")));
+        assert!(!by_exe.matches("AnyClass", None));
+        let both = pattern(Some("IME"), Some("ctfmon.exe"));
+        assert!(!both.matches("IME", Some("notepad.exe")));
+        assert_eq!(normalize(pattern(Some(" "), Some(r"C:\Windows\ctfmon.exe"))), Some(pattern(None, Some("ctfmon.exe"))));
+        assert_eq!(normalize(pattern(Some(""), None)), None);
+    }
+}
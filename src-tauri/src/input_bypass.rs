@@ -0,0 +1,176 @@
+// 低延迟输入模式 (设置 input.low_latency)
+// 网页 (WebView2) 铺满主窗口客户区，嵌入窗口叠在它上面；鼠标在内容区移动时网页仍然参与命中测试，
+// 在嵌入的编辑器、游戏中能感到延迟。开启后：
+// - 网页的宿主窗口 (WRY_WEBVIEW) 设置窗口区域 (SetWindowRgn)，挖掉正在显示的嵌入窗口所在的矩形，
+//   这些位置的命中测试直接落到嵌入窗口上，网页完全不参与
+// - 网页的宿主窗口放到子窗口 Z 序的最下面
+// 标签显示/隐藏时立即重新计算 (tab_view.rs)，移动和调整大小是异步进行的，由主线程的定时器跟随；
+// 关闭时恢复完整的区域
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+use tracing::info;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicU64, AtomicUsize};
+#[cfg(windows)]
+use tauri::Manager;
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::{HWND, RECT},
+        Graphics::Gdi::{CombineRgn, CreateRectRgn, DeleteObject, SetWindowRgn, HRGN, RGN_DIFF},
+        UI::WindowsAndMessaging::*,
+    },
+};
+
+#[cfg(windows)]
+use crate::window_manager::WindowManager;
+
+// 跟随嵌入窗口移动的间隔 (毫秒)
+#[cfg(windows)]
+const POLL_MS: u32 = 100;
+
+// 左, 上, 右, 下
+type Rect = (i32, i32, i32, i32);
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 当前设置的区域：宿主窗口大小和挖掉的矩形 (宿主窗口坐标)，没有变化时不重新设置
+#[cfg_attr(not(windows), allow(dead_code))]
+static APPLIED: Mutex<Option<((i32, i32), Vec<Rect>)>> = Mutex::new(None);
+
+#[cfg(windows)]
+static TIMER: AtomicUsize = AtomicUsize::new(0);
+#[cfg(windows)]
+static TIMER_RESOURCE: AtomicU64 = AtomicU64::new(0);
+
+/// 按设置开启或关闭 (启动和设置变化时调用)
+pub fn apply(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let enabled = crate::settings::get().input.low_latency;
+    if ENABLED.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    info!(target: "settings", "{}低延迟输入模式", if enabled { "开启" } else { "关闭" });
+    #[cfg(windows)]
+    let _ = app.run_on_main_thread(move || unsafe {
+        if enabled {
+            TIMER.store(SetTimer(None, 0, POLL_MS, Some(timer_proc)), Ordering::SeqCst);
+            let resource = crate::resources::register(crate::resources::ResourceKind::Timer, "input_bypass", 1, None, None);
+            TIMER_RESOURCE.store(resource, Ordering::SeqCst);
+        } else {
+            let _ = KillTimer(None, TIMER.swap(0, Ordering::SeqCst));
+            crate::resources::unregister(TIMER_RESOURCE.swap(0, Ordering::SeqCst));
+        }
+        update_region();
+    });
+}
+
+/// 显示的标签变化后立即重新计算 (任意线程)
+pub fn refresh() {
+    if !ENABLED.load(Ordering::SeqCst) {
+        return;
+    }
+    #[cfg(windows)]
+    if let Some(app) = APP.get() {
+        let _ = app.run_on_main_thread(|| unsafe { update_region() });
+    }
+}
+
+// 宿主窗口 container 中要挖掉的矩形：嵌入窗口的矩形 (屏幕坐标) 裁剪到宿主窗口内并换算成宿主窗口坐标，去掉空的
+#[cfg_attr(not(windows), allow(dead_code))]
+fn holes(container: Rect, windows: &[Rect]) -> Vec<Rect> {
+    let (left, top, right, bottom) = container;
+    windows
+        .iter()
+        .map(|&(l, t, r, b)| (l.max(left) - left, t.max(top) - top, r.min(right) - left, b.min(bottom) - top))
+        .filter(|&(l, t, r, b)| l < r && t < b)
+        .collect()
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn timer_proc(_hwnd: HWND, _msg: u32, _id: usize, _time: u32) {
+    update_region();
+}
+
+// 按正在显示的嵌入窗口重新设置网页宿主窗口的区域 (主线程)
+#[cfg(windows)]
+unsafe fn update_region() {
+    let Some(app) = APP.get() else { return };
+    let Some(host) = app.get_webview_window("main").and_then(|w| w.hwnd().ok()) else { return };
+    let Ok(container) = FindWindowExW(HWND(host.0), None, w!("WRY_WEBVIEW"), None) else { return };
+    let mut bounds = RECT::default();
+    if GetWindowRect(container, &mut bounds).is_err() {
+        return;
+    }
+    let size = (bounds.right - bounds.left, bounds.bottom - bounds.top);
+
+    let holes = if ENABLED.load(Ordering::SeqCst) {
+        let shown: Vec<Rect> = app
+            .state::<WindowManager>()
+            .hwnds()
+            .into_iter()
+            .filter(|tab| !crate::tab_view::is_hidden(*tab))
+            .filter_map(|tab| {
+                let hwnd = HWND(tab as *mut _);
+                let mut rect = RECT::default();
+                (IsWindowVisible(hwnd).as_bool() && GetWindowRect(hwnd, &mut rect).is_ok())
+                    .then_some((rect.left, rect.top, rect.right, rect.bottom))
+            })
+            .collect();
+        holes((bounds.left, bounds.top, bounds.right, bounds.bottom), &shown)
+    } else {
+        Vec::new()
+    };
+
+    let mut applied = APPLIED.lock().unwrap();
+    let unchanged = match applied.as_ref() {
+        Some((applied_size, applied_holes)) => *applied_size == size && *applied_holes == holes,
+        None => holes.is_empty(),
+    };
+    if unchanged {
+        return;
+    }
+
+    if holes.is_empty() {
+        let _ = SetWindowRgn(container, HRGN::default(), true);
+        *applied = None;
+        return;
+    }
+    let region = CreateRectRgn(0, 0, size.0, size.1);
+    for &(left, top, right, bottom) in &holes {
+        let hole = CreateRectRgn(left, top, right, bottom);
+        CombineRgn(region, region, hole, RGN_DIFF);
+        let _ = DeleteObject(hole);
+    }
+    // 设置成功后区域归系统所有，不能再删除
+    if SetWindowRgn(container, region, true) == 0 {
+        let _ = DeleteObject(region);
+        return;
+    }
+    let _ = SetWindowPos(container, HWND_BOTTOM, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE);
+    *applied = Some((size, holes));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holes_are_clipped_to_the_webview() {
+        let container = (100, 50, 1100, 850);
+        let windows = [
+            // 内容区，超出网页下边缘的部分裁掉
+            (100, 130, 1100, 900),
+            // 完全在网页外 (主窗口最小化时的位置)
+            (-32000, -32000, -31000, -31500),
+        ];
+        assert_eq!(holes(container, &windows), vec![(0, 80, 1000, 800)]);
+        assert!(holes(container, &[]).is_empty());
+    }
+}
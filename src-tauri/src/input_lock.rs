@@ -0,0 +1,101 @@
+// 标签输入锁定的持有者
+// 锁定输入的有三方：用户 (lock_tab_input)、演示模式 (presentation.rs) 和专注时段 (focus_session.rs)，
+// 它们共用 EmbeddedWindow.input_locked 这一个标志。每一方只登记/撤销自己的锁定，
+// 第一个持有者锁定时禁用窗口输入，最后一个持有者解除时才恢复，结束演示不会解开专注时段还需要锁定的标签。
+// 禁用/启用窗口要等目标处理消息，只在登记表的锁之外调用
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::platform;
+use crate::window_manager::{TabInputLocked, WindowManager};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockOwner {
+    User,
+    Presentation,
+    FocusSession,
+}
+
+impl LockOwner {
+    fn label(self) -> &'static str {
+        match self {
+            LockOwner::User => "用户",
+            LockOwner::Presentation => "演示模式",
+            LockOwner::FocusSession => "专注时段",
+        }
+    }
+}
+
+// (标签, 持有者)
+static OWNERS: Mutex<Vec<(isize, LockOwner)>> = Mutex::new(Vec::new());
+
+/// owner 锁定标签的输入，返回标签是否处于锁定状态 (当前平台不支持时为 false)
+pub fn lock(app: &AppHandle, tab: isize, owner: LockOwner) -> bool {
+    let first = {
+        let mut owners = OWNERS.lock().unwrap();
+        if owners.contains(&(tab, owner)) {
+            return true;
+        }
+        let first = !owners.iter().any(|&(h, _)| h == tab);
+        owners.push((tab, owner));
+        first
+    };
+    if !first {
+        return true;
+    }
+    if !platform::current().set_input_enabled(tab, false) {
+        OWNERS.lock().unwrap().retain(|&entry| entry != (tab, owner));
+        return false;
+    }
+    changed(app, tab, true);
+    true
+}
+
+/// owner 解除对标签的锁定，其他持有者仍在锁定时返回它们
+pub fn unlock(app: &AppHandle, tab: isize, owner: LockOwner) -> Vec<LockOwner> {
+    let (held, remaining) = {
+        let mut owners = OWNERS.lock().unwrap();
+        let held = owners.contains(&(tab, owner));
+        owners.retain(|&entry| entry != (tab, owner));
+        let remaining: Vec<LockOwner> = owners.iter().filter(|(h, _)| *h == tab).map(|(_, o)| *o).collect();
+        (held, remaining)
+    };
+    // 没有登记持有者但标志仍为锁定 (如更早的版本锁定的) 时同样恢复
+    let flagged = app.state::<WindowManager>().get(tab).is_some_and(|w| w.input_locked);
+    if remaining.is_empty() && (held || flagged) {
+        platform::current().set_input_enabled(tab, true);
+        changed(app, tab, false);
+    }
+    remaining
+}
+
+/// owner 解除它锁定的所有标签
+pub fn unlock_all(app: &AppHandle, owner: LockOwner) {
+    let tabs: Vec<isize> = OWNERS.lock().unwrap().iter().filter(|(_, o)| *o == owner).map(|(h, _)| *h).collect();
+    for tab in tabs {
+        unlock(app, tab, owner);
+    }
+}
+
+/// owner 锁定的标签
+pub fn locked_by(owner: LockOwner) -> Vec<isize> {
+    OWNERS.lock().unwrap().iter().filter(|(_, o)| *o == owner).map(|(h, _)| *h).collect()
+}
+
+/// 用户解除锁定被其他持有者挡住时的错误
+pub fn held_error(remaining: &[LockOwner]) -> String {
+    let labels: Vec<&str> = remaining.iter().map(|o| o.label()).collect();
+    format!("标签的输入由{}锁定，请先退出", labels.join("、"))
+}
+
+/// 标签已释放或关闭
+pub fn forget(tab: isize) {
+    OWNERS.lock().unwrap().retain(|(h, _)| *h != tab);
+}
+
+fn changed(app: &AppHandle, tab: isize, locked: bool) {
+    if app.state::<WindowManager>().set_input_locked(tab, locked).is_some() {
+        let _ = app.emit("tab-input-locked", TabInputLocked { hwnd: tab, locked });
+    }
+}
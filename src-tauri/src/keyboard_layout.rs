@@ -0,0 +1,226 @@
+// 键盘布局与快捷键
+// 全局快捷键按虚拟键注册 (global-hotkey 把键名换成固定的虚拟键)。字母和数字的虚拟键在各种布局中都跟随键帽上的字符，
+// Ctrl+W、Alt+1 在 AZERTY、Dvorak 上按键帽生效；标点键 (VK_OEM_*) 却随布局变化：
+// "Ctrl+ù"、"Ctrl+ö" 无法解析，"Ctrl+/" 在德语布局上注册到了 "-" 键上。
+// 快捷键统一由 parse 解析：键是单个标点或其他非字母数字的字符时，按当前布局 (VkKeyScanExW) 找到产生该字符的键，
+// 换成注册时使用同一个虚拟键的键名，需要 Shift 才能输入的字符加上 Shift
+// 布局随前台窗口和 Win+空格 切换，WM_INPUTLANGCHANGE 只发给获得焦点的窗口，所以这里定时检查前台线程的布局，
+// 变化后如果有快捷键依赖布局，重新注册全局快捷键
+
+use std::str::FromStr;
+#[cfg(windows)]
+use std::sync::atomic::{AtomicIsize, Ordering};
+#[cfg(windows)]
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::Shortcut;
+
+// 检查布局变化的间隔
+#[cfg(windows)]
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// 当前布局 (HKL)，0 表示尚未检测
+#[cfg(windows)]
+static LAYOUT: AtomicIsize = AtomicIsize::new(0);
+
+// 标点键的虚拟键和对应的键名 (global-hotkey 注册时使用的映射)
+const OEM_KEYS: [(u16, &str); 11] = [
+    (0xBA, "Semicolon"),
+    (0xBB, "Equal"),
+    (0xBC, "Comma"),
+    (0xBD, "Minus"),
+    (0xBE, "Period"),
+    (0xBF, "Slash"),
+    (0xC0, "Backquote"),
+    (0xDB, "BracketLeft"),
+    (0xDC, "Backslash"),
+    (0xDD, "BracketRight"),
+    (0xDE, "Quote"),
+];
+
+// 媒体键的简写 (小键盘 Numpad0~9、F13~F24 和 MediaPlayPause 等完整键名直接由 Shortcut 解析)
+const ALIASES: [(&str, &str); 6] = [
+    ("PlayPause", "MediaPlayPause"),
+    ("NextTrack", "MediaTrackNext"),
+    ("PrevTrack", "MediaTrackPrevious"),
+    ("PreviousTrack", "MediaTrackPrevious"),
+    ("StopMedia", "MediaStop"),
+    ("Mute", "AudioVolumeMute"),
+];
+
+/// 按当前键盘布局解析快捷键
+pub fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    let normalized = normalize(&expand_alias(accelerator), resolve_char);
+    Shortcut::from_str(&normalized).map_err(|e| e.to_string())
+}
+
+/// 快捷键的结果是否随布局变化
+pub fn is_layout_dependent(accelerator: &str) -> bool {
+    let (_, key) = split(accelerator);
+    let mut chars = key.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if !c.is_ascii_alphanumeric())
+}
+
+/// 定时检查前台线程的键盘布局，变化后重新注册依赖布局的快捷键
+#[cfg(windows)]
+pub fn start(app: &AppHandle) {
+    LAYOUT.store(foreground_layout(), Ordering::SeqCst);
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if !crate::session_watch::is_active() {
+            continue;
+        }
+        let layout = foreground_layout();
+        if layout == 0 || LAYOUT.swap(layout, Ordering::SeqCst) == layout {
+            continue;
+        }
+        if uses_layout(&app) {
+            tracing::info!(target: "shortcuts", "键盘布局已切换，重新注册快捷键: {:#x}", layout);
+            crate::apply_shortcuts(&app);
+        }
+    });
+}
+
+#[cfg(not(windows))]
+pub fn start(_app: &AppHandle) {}
+
+// 设置中的快捷键或标签快捷键是否有依赖布局的
+#[cfg(windows)]
+fn uses_layout(app: &AppHandle) -> bool {
+    use tauri::Manager;
+
+    let tabs = app.state::<crate::window_manager::WindowManager>().list();
+    crate::settings::shortcuts().bindings().iter().any(|(accelerator, _)| is_layout_dependent(accelerator))
+        || tabs.iter().filter_map(|w| w.hotkey.as_deref()).any(is_layout_dependent)
+}
+
+// 修饰键部分和键 ("Ctrl++" 的键为 "+")
+fn split(accelerator: &str) -> (&str, &str) {
+    let accelerator = accelerator.trim();
+    if let Some(modifiers) = accelerator.strip_suffix("++") {
+        return (modifiers, "+");
+    }
+    accelerator.rsplit_once('+').unwrap_or(("", accelerator))
+}
+
+// 把媒体键的简写换成完整键名
+fn expand_alias(accelerator: &str) -> String {
+    let (modifiers, key) = split(accelerator);
+    match ALIASES.iter().find(|(alias, _)| alias.eq_ignore_ascii_case(key.trim())) {
+        Some((_, name)) => join(modifiers, false, name),
+        None => accelerator.trim().to_string(),
+    }
+}
+
+// 把键换成当前布局中产生该字符的键的键名，无法换算时保持原样
+fn normalize(accelerator: &str, resolve: impl Fn(char) -> Option<(u16, bool)>) -> String {
+    if !is_layout_dependent(accelerator) {
+        return accelerator.trim().to_string();
+    }
+    let (modifiers, key) = split(accelerator);
+    let Some((vk, shift)) = key.chars().next().and_then(resolve) else { return accelerator.trim().to_string() };
+    let Some((_, name)) = OEM_KEYS.iter().find(|(oem, _)| *oem == vk) else {
+        return match vk {
+            0x30..=0x39 | 0x41..=0x5A => join(modifiers, shift, &(vk as u8 as char).to_string()),
+            _ => accelerator.trim().to_string(),
+        };
+    };
+    join(modifiers, shift, name)
+}
+
+fn join(modifiers: &str, shift: bool, key: &str) -> String {
+    let mut parts: Vec<&str> = modifiers.split('+').map(str::trim).filter(|m| !m.is_empty()).collect();
+    if shift && !parts.iter().any(|m| m.eq_ignore_ascii_case("shift")) {
+        parts.push("Shift");
+    }
+    parts.push(key);
+    parts.join("+")
+}
+
+// 当前布局中输入字符 c 的虚拟键和是否需要 Shift (需要 AltGr 等其他修饰键的字符无法作为快捷键)
+#[cfg(windows)]
+fn resolve_char(c: char) -> Option<(u16, bool)> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{VkKeyScanExW, HKL};
+
+    let mut utf16 = [0u16; 2];
+    let [unit] = c.encode_utf16(&mut utf16) else { return None };
+    let layout = match LAYOUT.load(Ordering::SeqCst) {
+        0 => foreground_layout(),
+        layout => layout,
+    };
+    let scan = unsafe { VkKeyScanExW(*unit, HKL(layout as *mut _)) };
+    if scan == -1 {
+        return None;
+    }
+    let (vk, state) = ((scan & 0xFF) as u16, (scan >> 8) & 0xFF);
+    match state {
+        0 => Some((vk, false)),
+        1 => Some((vk, true)),
+        _ => None,
+    }
+}
+
+#[cfg(not(windows))]
+fn resolve_char(_c: char) -> Option<(u16, bool)> {
+    None
+}
+
+#[cfg(windows)]
+fn foreground_layout() -> isize {
+    use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyboardLayout;
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+        GetKeyboardLayout(thread).0 as isize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 法语 AZERTY 布局的部分按键
+    fn azerty(c: char) -> Option<(u16, bool)> {
+        match c {
+            'ù' => Some((0xC0, false)),
+            ',' => Some((0xBC, false)),
+            '?' => Some((0xBC, true)),
+            '!' => Some((0xDF, false)),
+            '&' => Some((0x31, false)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn punctuation_follows_the_layout() {
+        assert_eq!(normalize("Ctrl+ù", azerty), "Ctrl+Backquote");
+        assert_eq!(normalize("Ctrl+,", azerty), "Ctrl+Comma");
+        assert_eq!(normalize("Ctrl+?", azerty), "Ctrl+Shift+Comma");
+        assert_eq!(normalize("Ctrl+Shift+?", azerty), "Ctrl+Shift+Comma");
+        assert_eq!(normalize("Alt+&", azerty), "Alt+1");
+        // 没有对应键名的虚拟键和布局中不存在的字符保持原样
+        assert_eq!(normalize("Ctrl+!", azerty), "Ctrl+!");
+        assert_eq!(normalize("Ctrl++", azerty), "Ctrl++");
+        // 字母和数字本来就跟随键帽
+        assert_eq!(normalize(" Ctrl+W ", azerty), "Ctrl+W");
+        assert_eq!(normalize("Alt+1", azerty), "Alt+1");
+
+        assert!(is_layout_dependent("Ctrl+/"));
+        assert!(!is_layout_dependent("Ctrl+Numpad1"));
+        assert!(is_layout_dependent("Ctrl++"));
+        assert!(!is_layout_dependent("Ctrl+Shift+T"));
+        assert!(!is_layout_dependent("Alt+F4"));
+    }
+
+    #[test]
+    fn media_key_aliases_expand() {
+        assert_eq!(expand_alias("playpause"), "MediaPlayPause");
+        assert_eq!(expand_alias("Ctrl+Shift+NextTrack"), "Ctrl+Shift+MediaTrackNext");
+        assert_eq!(expand_alias("Alt+F13"), "Alt+F13");
+        assert!(parse("PrevTrack").is_ok());
+        assert!(parse("Ctrl+Numpad7").is_ok());
+        assert!(parse("Shift+F24").is_ok());
+    }
+}
@@ -0,0 +1,188 @@
+// 键盘输入转发：向嵌入窗口模拟按键 (粘贴到标签、简单宏)
+// 按键序列格式：
+// - 普通文本原样输入 (按 Unicode 字符发送，不受键盘布局和输入法影响)
+// - {键名} 发送单个按键，如 {enter} {tab} {f5}
+// - {修饰键+键名} 发送组合键，如 {ctrl+v} {ctrl+shift+t} {alt+f4}
+// - {{ 输入一个字面量 {
+// 例: "hello{enter}"、"{ctrl+a}{ctrl+v}"
+
+#[cfg(windows)]
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, VIRTUAL_KEY,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyStep {
+    Text(String),
+    Chord(Vec<u16>), // 虚拟键码，最后一个为主键，前面为修饰键
+}
+
+// 修饰键: Shift / Ctrl / Alt / 左右 Win
+#[cfg(windows)]
+const MODIFIER_KEYS: [u16; 5] = [0x10, 0x11, 0x12, 0x5B, 0x5C];
+
+// 需要 KEYEVENTF_EXTENDEDKEY 的键 (方向键、导航键区、Win、小键盘除号、音量和媒体键)，否则会被当成小键盘按键
+#[cfg(windows)]
+fn is_extended_key(vk: u16) -> bool {
+    matches!(vk, 0x21..=0x28 | 0x2D | 0x2E | 0x5B | 0x5C | 0x6F | 0xAD..=0xB3)
+}
+
+// 键名 -> 虚拟键码
+fn vk_from_name(name: &str) -> Option<u16> {
+    let vk = match name {
+        "ctrl" | "control" => 0x11,
+        "shift" => 0x10,
+        "alt" => 0x12,
+        "win" | "meta" => 0x5B,
+        "enter" | "return" => 0x0D,
+        "tab" => 0x09,
+        "esc" | "escape" => 0x1B,
+        "space" => 0x20,
+        "backspace" | "bs" => 0x08,
+        "delete" | "del" => 0x2E,
+        "insert" | "ins" => 0x2D,
+        "home" => 0x24,
+        "end" => 0x23,
+        "pageup" | "pgup" => 0x21,
+        "pagedown" | "pgdn" => 0x22,
+        "left" => 0x25,
+        "up" => 0x26,
+        "right" => 0x27,
+        "down" => 0x28,
+        "numpadadd" | "numadd" | "numpadplus" | "numplus" => 0x6B,
+        "numpadsubtract" | "numsubtract" => 0x6D,
+        "numpadmultiply" | "nummultiply" => 0x6A,
+        "numpaddivide" | "numdivide" => 0x6F,
+        "numpaddecimal" | "numdecimal" => 0x6E,
+        "volumemute" | "audiovolumemute" => 0xAD,
+        "volumedown" | "audiovolumedown" => 0xAE,
+        "volumeup" | "audiovolumeup" => 0xAF,
+        "mediatracknext" => 0xB0,
+        "mediatrackprev" | "mediatrackprevious" => 0xB1,
+        "mediastop" => 0xB2,
+        "mediaplaypause" => 0xB3,
+        _ => {
+            let chars: Vec<char> = name.chars().collect();
+            if chars.len() == 1 && chars[0].is_ascii_alphanumeric() {
+                // 'A'-'Z' / '0'-'9' 的虚拟键码就是对应的大写 ASCII
+                return Some(chars[0].to_ascii_uppercase() as u16);
+            }
+            // 小键盘数字
+            if let Some(digit) = name.strip_prefix("numpad").or_else(|| name.strip_prefix("num")) {
+                let n: u16 = digit.parse().ok()?;
+                return (n <= 9).then_some(0x60 + n);
+            }
+            // F1 ~ F24
+            let n: u16 = name.strip_prefix('f')?.parse().ok()?;
+            if (1..=24).contains(&n) {
+                return Some(0x70 + n - 1);
+            }
+            return None;
+        }
+    };
+    Some(vk)
+}
+
+/// 解析按键序列
+pub fn parse_sequence(sequence: &str) -> Result<Vec<KeyStep>, String> {
+    let mut steps = Vec::new();
+    let mut text = String::new();
+    let mut chars = sequence.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            text.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            text.push('{');
+            continue;
+        }
+
+        let mut spec = String::new();
+        loop {
+            match chars.next() {
+                Some('}') => break,
+                Some(c) => spec.push(c),
+                None => return Err(format!("按键序列缺少 '}}': {{{}", spec)),
+            }
+        }
+
+        let mut keys = Vec::new();
+        for name in spec.split('+') {
+            let name = name.trim().to_lowercase();
+            let vk = vk_from_name(&name).ok_or_else(|| format!("未知按键: {}", name))?;
+            keys.push(vk);
+        }
+
+        if !text.is_empty() {
+            steps.push(KeyStep::Text(std::mem::take(&mut text)));
+        }
+        steps.push(KeyStep::Chord(keys));
+    }
+
+    if !text.is_empty() {
+        steps.push(KeyStep::Text(text));
+    }
+    Ok(steps)
+}
+
+#[cfg(windows)]
+pub fn key_input(vk: u16, scan: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT { wVk: VIRTUAL_KEY(vk), wScan: scan, dwFlags: flags, time: 0, dwExtraInfo: 0 },
+        },
+    }
+}
+
+/// 把按键序列转换成 SendInput 事件 (修饰键先释放，避免用户按住的快捷键修饰键污染输入)
+#[cfg(windows)]
+pub unsafe fn build_inputs(steps: &[KeyStep]) -> Vec<INPUT> {
+    let mut inputs = Vec::new();
+
+    for vk in MODIFIER_KEYS {
+        if GetAsyncKeyState(vk as i32) as u16 & 0x8000 != 0 {
+            inputs.push(key_input(vk, 0, KEYEVENTF_KEYUP));
+        }
+    }
+
+    for step in steps {
+        match step {
+            KeyStep::Text(text) => {
+                for unit in text.encode_utf16() {
+                    inputs.push(key_input(0, unit, KEYEVENTF_UNICODE));
+                    inputs.push(key_input(0, unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP));
+                }
+            }
+            KeyStep::Chord(keys) => {
+                let flags = |vk: u16| if is_extended_key(vk) { KEYEVENTF_EXTENDEDKEY } else { KEYBD_EVENT_FLAGS(0) };
+                for vk in keys {
+                    inputs.push(key_input(*vk, 0, flags(*vk)));
+                }
+                for vk in keys.iter().rev() {
+                    inputs.push(key_input(*vk, 0, flags(*vk) | KEYEVENTF_KEYUP));
+                }
+            }
+        }
+    }
+    inputs
+}
+
+/// 发送按键，返回实际注入的事件数
+#[cfg(windows)]
+pub unsafe fn send(steps: &[KeyStep]) -> Result<u32, String> {
+    let inputs = build_inputs(steps);
+    if inputs.is_empty() {
+        return Ok(0);
+    }
+    let sent = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    if sent as usize != inputs.len() {
+        // 常见原因：目标窗口以管理员权限运行 (UIPI 拦截)
+        return Err(format!("按键注入被拦截: {}/{}", sent, inputs.len()));
+    }
+    Ok(sent)
+}
@@ -0,0 +1,232 @@
+// 展台模式 (锁定)
+// 把 WindowHub 当作车间看板、监控大屏的外壳：
+// - 主窗口无边框全屏，不能退出全屏、不能关闭主窗口
+// - 不能关闭或弹出标签 (所有释放标签的路径都经过 release_tab 的检查)，托盘菜单中的对应项禁用
+// - 退出 WindowHub (托盘菜单、快捷键) 和退出展台模式需要输入 PIN；连续输错 FREE_ATTEMPTS 次后
+//   暂时不接受输入，等待时间按错误次数加倍 (最长 MAX_LOCKOUT)
+// - 嵌入的应用退出后自动重新启动并嵌入到原来的标签
+// 状态和 PIN 的加盐摘要保存在数据目录 (见 paths.rs) 的 kiosk.json，重启后仍处于展台模式
+// 状态变化时发送 kiosk-mode-changed (载荷为是否开启)，需要输入 PIN 时发送 kiosk-pin-requested
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+// 不计入等待的错误次数，之后每次输错等待时间加倍
+const FREE_ATTEMPTS: u32 = 3;
+const FIRST_LOCKOUT: Duration = Duration::from_secs(30);
+const MAX_LOCKOUT: Duration = Duration::from_secs(15 * 60);
+
+// PIN 只有几位数字，摘要反复计算多次，拿到 kiosk.json 后也不能很快试出 PIN
+const PIN_ROUNDS: u32 = 100_000;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct KioskState {
+    enabled: bool,
+    pin_salt: String,
+    pin_hash: String,
+    // 旧版本保存的明文 PIN，读取时转换为摘要
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pin: String,
+}
+
+static STATE: Mutex<KioskState> =
+    Mutex::new(KioskState { enabled: false, pin_salt: String::new(), pin_hash: String::new(), pin: String::new() });
+
+// 连续输错的次数和在此之前不接受输入的时间
+static ATTEMPTS: Mutex<(u32, Option<Instant>)> = Mutex::new((0, None));
+
+pub fn is_enabled() -> bool {
+    STATE.lock().unwrap().enabled
+}
+
+/// 展台模式下不允许的操作返回错误 (action 为操作名称，如 "关闭标签")
+pub fn ensure_unlocked(action: &str) -> Result<(), String> {
+    if is_enabled() {
+        return Err(format!("展台模式下不能{}", action));
+    }
+    Ok(())
+}
+
+/// 开启展台模式，pin 为之后退出时需要输入的 PIN (4~12 位数字)
+pub fn enter(app: &AppHandle, pin: &str) -> Result<(), String> {
+    if !is_valid_pin(pin) {
+        return Err("PIN 必须是 4~12 位数字".to_string());
+    }
+    *ATTEMPTS.lock().unwrap() = (0, None);
+    {
+        let mut state = STATE.lock().unwrap();
+        if state.enabled {
+            return Ok(());
+        }
+        *state = hashed(pin)?;
+        save_to_file(&state);
+    }
+    info!(target: "settings", "进入展台模式");
+    apply(app, true);
+    Ok(())
+}
+
+/// 输入 PIN 退出展台模式
+pub fn exit(app: &AppHandle, pin: &str) -> Result<(), String> {
+    verify(pin)?;
+    {
+        let mut state = STATE.lock().unwrap();
+        state.enabled = false;
+        save_to_file(&state);
+    }
+    info!(target: "settings", "退出展台模式");
+    apply(app, false);
+    Ok(())
+}
+
+/// 校验 PIN (不在展台模式时总是通过)
+pub fn verify(pin: &str) -> Result<(), String> {
+    let (salt, expected) = {
+        let state = STATE.lock().unwrap();
+        if !state.enabled {
+            return Ok(());
+        }
+        (state.pin_salt.clone(), state.pin_hash.clone())
+    };
+    let mut attempts = ATTEMPTS.lock().unwrap();
+    let now = Instant::now();
+    if let Some(until) = attempts.1.filter(|until| *until > now) {
+        return Err(format!("PIN 错误次数过多，请 {} 秒后再试", (until - now).as_secs() + 1));
+    }
+    if crate::remote_tabs::constant_eq(&hash_pin(&salt, pin), &expected) {
+        *attempts = (0, None);
+        return Ok(());
+    }
+    attempts.0 += 1;
+    attempts.1 = lockout(attempts.0).map(|wait| now + wait);
+    warn!(target: "settings", "展台模式 PIN 错误 ({} 次)", attempts.0);
+    Err("PIN 错误".to_string())
+}
+
+// 连续输错 failures 次后需要等待的时间
+fn lockout(failures: u32) -> Option<Duration> {
+    let over = failures.checked_sub(FREE_ATTEMPTS)?;
+    Some(FIRST_LOCKOUT.saturating_mul(1 << over.min(16)).min(MAX_LOCKOUT))
+}
+
+// 开启展台模式的状态，PIN 加随机盐后保存摘要
+fn hashed(pin: &str) -> Result<KioskState, String> {
+    let mut salt = [0u8; 16];
+    getrandom::getrandom(&mut salt).map_err(|e| format!("无法获取随机数: {}", e))?;
+    let salt: String = salt.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(KioskState { enabled: true, pin_hash: hash_pin(&salt, pin), pin_salt: salt, pin: String::new() })
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    let mut digest = Sha256::digest(format!("{}:{}", salt, pin).as_bytes());
+    for _ in 1..PIN_ROUNDS {
+        digest = Sha256::digest(digest);
+    }
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 需要输入 PIN 才能执行的操作 (action 如 "quit")：显示主窗口并请前端输入 PIN
+pub fn request_pin(app: &AppHandle, action: &str) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    let _ = app.emit("kiosk-pin-requested", action);
+}
+
+/// 启动时恢复展台模式 (主窗口创建后调用)
+pub fn restore(app: &AppHandle) {
+    if is_enabled() {
+        info!(target: "settings", "恢复展台模式");
+        apply(app, true);
+    }
+}
+
+fn apply(app: &AppHandle, enabled: bool) {
+    if let Some(main) = app.get_webview_window("main") {
+        if main.is_fullscreen().unwrap_or(false) != enabled {
+            let _ = main.set_fullscreen(enabled);
+            let _ = app.emit("host-fullscreen-changed", enabled);
+        }
+        if enabled {
+            let _ = main.show();
+        }
+    }
+    crate::tray::refresh(app);
+    let _ = app.emit("kiosk-mode-changed", enabled);
+}
+
+fn is_valid_pin(pin: &str) -> bool {
+    (4..=12).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit())
+}
+
+fn save_to_file(state: &KioskState) {
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("kiosk.json");
+
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        if let Ok(json) = serde_json::to_string_pretty(state) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+pub fn load_from_file() {
+    let Some(config_dir) = crate::paths::data_dir() else { return };
+    let path = config_dir.join("kiosk.json");
+    let Ok(content) = std::fs::read_to_string(path) else { return };
+    match serde_json::from_str::<KioskState>(&content) {
+        // 旧版本的明文 PIN：转换为摘要后重新保存
+        Ok(state) if !state.pin.is_empty() && is_valid_pin(&state.pin) => match hashed(&state.pin) {
+            Ok(migrated) => {
+                let migrated = KioskState { enabled: state.enabled, ..migrated };
+                save_to_file(&migrated);
+                *STATE.lock().unwrap() = migrated;
+            }
+            Err(e) => warn!(target: "settings", "转换 kiosk.json 中的 PIN 失败: {}", e),
+        },
+        // 没有有效 PIN 的展台模式无法退出，忽略
+        Ok(state) if state.enabled && state.pin_hash.is_empty() => warn!(target: "settings", "kiosk.json 中的 PIN 无效，不进入展台模式"),
+        Ok(state) => *STATE.lock().unwrap() = state,
+        Err(e) => warn!(target: "settings", "读取 kiosk.json 失败: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_are_short_digit_strings() {
+        assert!(is_valid_pin("2468"));
+        assert!(is_valid_pin("123456789012"));
+        assert!(!is_valid_pin("123"));
+        assert!(!is_valid_pin("12a4"));
+        assert!(!is_valid_pin("1234567890123"));
+        // 不在展台模式时不需要 PIN
+        assert_eq!(verify(""), Ok(()));
+        assert_eq!(ensure_unlocked("关闭标签"), Ok(()));
+    }
+
+    #[test]
+    fn pin_is_salted_and_throttled() {
+        let state = hashed("2468").unwrap();
+        assert!(state.pin.is_empty());
+        assert_eq!(hash_pin(&state.pin_salt, "2468"), state.pin_hash);
+        assert_ne!(hash_pin(&state.pin_salt, "2469"), state.pin_hash);
+        assert_ne!(hashed("2468").unwrap().pin_hash, state.pin_hash);
+
+        assert_eq!(lockout(FREE_ATTEMPTS - 1), None);
+        assert_eq!(lockout(FREE_ATTEMPTS), Some(FIRST_LOCKOUT));
+        assert_eq!(lockout(FREE_ATTEMPTS + 1), Some(FIRST_LOCKOUT * 2));
+        assert_eq!(lockout(100), Some(MAX_LOCKOUT));
+    }
+}
@@ -0,0 +1,287 @@
+// 从 PowerToys 导入布局 (import_layouts)
+// - FancyZones 的自定义布局 (custom-layouts.json)：每个布局转换为一个只有分隔比例的工作区 "FancyZones - 名称"，
+//   恢复这个工作区时按布局调整停靠区域的分隔条。停靠区域是 2x2 网格，超过两列/两行或者自由布局 (canvas)
+//   只能取最靠近中间的分隔线，结果中 approximated 为 true
+// - PowerToys 工作区 (workspaces.json)：每个工作区转换为同名工作区，应用按 EXE 路径启动
+// 不指定文件时从 PowerToys 的默认位置读取这两个文件；按 JSON 内容判断是哪种文件
+// 已有同名工作区时跳过，不覆盖用户自己的工作区；导入后保存并发送 config-reloaded
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+use crate::config_watch::ConfigReloaded;
+use crate::zones::SplitRatios;
+use crate::Workspace;
+
+// 两条分隔线相差不到这个比例时当作同一条 (FancyZones 的区域之间有间距)
+const SAME_LINE: f32 = 0.02;
+
+/// 导入的一项
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportedLayout {
+    pub name: String, // 导入后的工作区名称
+    pub source: ImportSource,
+    pub split: SplitRatios,
+    pub apps: usize,
+    pub approximated: bool, // 布局无法用 2x2 网格精确表示
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    FancyZones,
+    PowerToysWorkspaces,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub imported: Vec<ImportedLayout>,
+    pub skipped: Vec<String>, // 跳过的项和原因
+}
+
+#[derive(Deserialize)]
+struct FancyZonesFile {
+    #[serde(rename = "custom-layouts")]
+    layouts: Vec<FancyZonesLayout>,
+}
+
+#[derive(Deserialize)]
+struct FancyZonesLayout {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    info: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct GridInfo {
+    rows: usize,
+    columns: usize,
+    rows_percentage: Vec<u32>,
+    columns_percentage: Vec<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CanvasInfo {
+    ref_width: i32,
+    ref_height: i32,
+    zones: Vec<CanvasZone>,
+}
+
+#[derive(Deserialize)]
+struct CanvasZone {
+    #[serde(rename = "X")]
+    x: i32,
+    #[serde(rename = "Y")]
+    y: i32,
+}
+
+#[derive(Deserialize)]
+struct WorkspacesFile {
+    workspaces: Vec<PowerToysWorkspace>,
+}
+
+#[derive(Deserialize)]
+struct PowerToysWorkspace {
+    name: String,
+    #[serde(default)]
+    applications: Vec<PowerToysApp>,
+}
+
+#[derive(Deserialize)]
+struct PowerToysApp {
+    #[serde(rename = "application-path", default)]
+    path: String,
+}
+
+/// 导入 path (为空时导入 PowerToys 默认位置的文件)，返回导入和跳过的项
+pub fn import(app: &AppHandle, path: Option<&str>) -> Result<ImportReport, String> {
+    let files: Vec<PathBuf> = match path {
+        Some(path) => vec![PathBuf::from(path)],
+        None => default_files(),
+    };
+    if files.is_empty() {
+        return Err("没有找到 PowerToys 的布局文件".to_string());
+    }
+    let mut layouts = Vec::new();
+    let mut report = ImportReport::default();
+    for file in &files {
+        let content = std::fs::read_to_string(file).map_err(|e| format!("读取 {} 失败: {}", file.display(), e))?;
+        let (parsed, skipped) = parse(&content).map_err(|e| format!("{}: {}", file.display(), e))?;
+        layouts.extend(parsed);
+        report.skipped.extend(skipped);
+    }
+
+    {
+        let mut workspaces = crate::WORKSPACES.lock().unwrap();
+        for (layout, apps) in layouts {
+            if workspaces.iter().any(|w| w.name == layout.name) {
+                report.skipped.push(format!("{}: 已有同名工作区", layout.name));
+                continue;
+            }
+            workspaces.push(Workspace {
+                name: layout.name.clone(),
+                apps,
+                tabs: Vec::new(),
+                do_not_disturb: false,
+                split: layout.split,
+                media_target: None,
+                shortcuts: None,
+            });
+            report.imported.push(layout);
+        }
+        if !report.imported.is_empty() {
+            crate::save_workspaces_to_file(&workspaces);
+        }
+    }
+    info!(target: "workspace", "从 PowerToys 导入了 {} 个布局，跳过 {} 个", report.imported.len(), report.skipped.len());
+    if !report.imported.is_empty() {
+        let _ = app.emit("config-reloaded", ConfigReloaded { files: vec!["workspaces.json".to_string()] });
+    }
+    Ok(report)
+}
+
+// PowerToys 默认位置的布局文件 (存在的)
+fn default_files() -> Vec<PathBuf> {
+    let Ok(local) = std::env::var("LOCALAPPDATA") else { return Vec::new() };
+    let dir = PathBuf::from(local).join("Microsoft").join("PowerToys");
+    [dir.join("FancyZones").join("custom-layouts.json"), dir.join("Workspaces").join("workspaces.json")]
+        .into_iter()
+        .filter(|p| p.exists())
+        .collect()
+}
+
+// 按内容判断文件类型，返回 (布局和应用列表, 跳过的项)
+fn parse(content: &str) -> Result<(Vec<(ImportedLayout, Vec<String>)>, Vec<String>), String> {
+    if let Ok(file) = serde_json::from_str::<FancyZonesFile>(content) {
+        let mut layouts = Vec::new();
+        let mut skipped = Vec::new();
+        for layout in file.layouts {
+            match fancy_zones_split(&layout) {
+                Ok((split, approximated)) => layouts.push((
+                    ImportedLayout {
+                        name: format!("FancyZones - {}", layout.name),
+                        source: ImportSource::FancyZones,
+                        split,
+                        apps: 0,
+                        approximated,
+                    },
+                    Vec::new(),
+                )),
+                Err(e) => skipped.push(format!("{}: {}", layout.name, e)),
+            }
+        }
+        return Ok((layouts, skipped));
+    }
+    if let Ok(file) = serde_json::from_str::<WorkspacesFile>(content) {
+        let layouts = file
+            .workspaces
+            .into_iter()
+            .map(|workspace| {
+                let mut apps: Vec<String> = Vec::new();
+                for app in workspace.applications {
+                    if !app.path.is_empty() && !apps.iter().any(|a| a.eq_ignore_ascii_case(&app.path)) {
+                        apps.push(app.path);
+                    }
+                }
+                let layout = ImportedLayout {
+                    name: workspace.name,
+                    source: ImportSource::PowerToysWorkspaces,
+                    split: SplitRatios::default(),
+                    apps: apps.len(),
+                    approximated: false,
+                };
+                (layout, apps)
+            })
+            .collect();
+        return Ok((layouts, Vec::new()));
+    }
+    Err("不是 FancyZones 或 PowerToys 工作区的文件".to_string())
+}
+
+// FancyZones 布局的分隔比例，以及是否只是近似
+fn fancy_zones_split(layout: &FancyZonesLayout) -> Result<(SplitRatios, bool), String> {
+    match layout.kind.as_str() {
+        "grid" => {
+            let grid: GridInfo = serde_json::from_value(layout.info.clone()).map_err(|e| format!("格式错误: {}", e))?;
+            let columns = first_share(&grid.columns_percentage, grid.columns)?;
+            let rows = first_share(&grid.rows_percentage, grid.rows)?;
+            let split = SplitRatios { columns: columns.unwrap_or(0.5), rows: rows.unwrap_or(0.5) };
+            Ok((split.clamped(), grid.columns > 2 || grid.rows > 2))
+        }
+        "canvas" => {
+            let canvas: CanvasInfo = serde_json::from_value(layout.info.clone()).map_err(|e| format!("格式错误: {}", e))?;
+            if canvas.ref_width <= 0 || canvas.ref_height <= 0 {
+                return Err("格式错误: 参考尺寸无效".to_string());
+            }
+            let (columns, more_columns) = middle_line(canvas.zones.iter().map(|z| z.x as f32 / canvas.ref_width as f32));
+            let (rows, more_rows) = middle_line(canvas.zones.iter().map(|z| z.y as f32 / canvas.ref_height as f32));
+            let split = SplitRatios { columns: columns.unwrap_or(0.5), rows: rows.unwrap_or(0.5) };
+            Ok((split.clamped(), more_columns || more_rows))
+        }
+        // 预设布局 (focus、columns 等) 不保存在自定义布局中
+        other => Err(format!("不支持的布局类型 {}", other)),
+    }
+}
+
+// 网格第一列 (行) 占的比例，只有一列时为 None
+fn first_share(percentages: &[u32], count: usize) -> Result<Option<f32>, String> {
+    let total: u32 = percentages.iter().sum();
+    if count == 0 || percentages.len() != count || total == 0 {
+        return Err("格式错误: 行列比例无效".to_string());
+    }
+    Ok((count > 1).then(|| percentages[0] as f32 / total as f32))
+}
+
+// 区域左 (上) 边形成的分隔线中最靠近中间的一条，以及是否还有其他分隔线
+fn middle_line(edges: impl Iterator<Item = f32>) -> (Option<f32>, bool) {
+    let mut lines: Vec<f32> = Vec::new();
+    for edge in edges.filter(|e| *e > SAME_LINE && *e < 1.0 - SAME_LINE) {
+        if !lines.iter().any(|l| (l - edge).abs() < SAME_LINE) {
+            lines.push(edge);
+        }
+    }
+    let middle = lines.iter().copied().min_by(|a, b| (a - 0.5).abs().total_cmp(&(b - 0.5).abs()));
+    (middle, lines.len() > 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_fancy_zones_layouts() {
+        let content = r#"{ "custom-layouts": [
+            { "name": "主副屏", "type": "grid", "info": { "rows": 1, "columns": 2, "rows-percentage": [10000],
+              "columns-percentage": [6667, 3333], "cell-child-map": [[0, 1]] } },
+            { "name": "三列", "type": "grid", "info": { "rows": 1, "columns": 3, "rows-percentage": [10000],
+              "columns-percentage": [2500, 5000, 2500] } },
+            { "name": "自由", "type": "canvas", "info": { "ref-width": 2000, "ref-height": 1000, "zones": [
+              { "X": 0, "Y": 0, "width": 790, "height": 1000 }, { "X": 810, "Y": 0, "width": 1190, "height": 300 },
+              { "X": 810, "Y": 310, "width": 1190, "height": 690 } ] } },
+            { "name": "坏的", "type": "grid", "info": { "rows": 2, "columns": 1, "rows-percentage": [10000], "columns-percentage": [10000] } }
+        ] }"#;
+        let (layouts, skipped) = parse(content).unwrap();
+        let splits: Vec<(&str, SplitRatios, bool)> = layouts.iter().map(|(l, _)| (l.name.as_str(), l.split, l.approximated)).collect();
+        assert_eq!(splits[0], ("FancyZones - 主副屏", SplitRatios { columns: 0.6667, rows: 0.5 }, false));
+        assert_eq!(splits[1], ("FancyZones - 三列", SplitRatios { columns: 0.25, rows: 0.5 }, true));
+        assert_eq!(splits[2], ("FancyZones - 自由", SplitRatios { columns: 0.405, rows: 0.31 }, false));
+        assert_eq!(skipped.len(), 1);
+    }
+
+    #[test]
+    fn converts_power_toys_workspaces() {
+        let content = r#"{ "workspaces": [ { "id": "{1}", "name": "写代码", "applications": [
+            { "application": "Code", "application-path": "C:\\Apps\\Code.exe" },
+            { "application": "Code", "application-path": "c:\\apps\\code.exe" },
+            { "application": "Terminal", "application-path": "C:\\Apps\\wt.exe" } ] } ] }"#;
+        let (layouts, _) = parse(content).unwrap();
+        assert_eq!(layouts[0].0.name, "写代码");
+        assert_eq!(layouts[0].1, vec![r"C:\Apps\Code.exe", r"C:\Apps\wt.exe"]);
+        assert!(parse(r#"{ "settings": {} }"#).is_err());
+    }
+}
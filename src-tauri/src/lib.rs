@@ -2,719 +2,2099 @@
 // 修复：深度输入焦点, Z序切换, 安全关闭, 全局快捷键
 // 新增：防止卡死的安全措施
 
+mod accessibility;
+mod activity;
+mod agent;
+mod animation;
+mod api_trace;
+mod app_index;
+mod app_watch;
+mod backup;
+mod boss_key;
+mod budget;
+mod capture;
+mod child_order;
+mod chrome;
+mod clipboard;
+mod clock;
+mod close_all;
+mod close_request;
+mod closed_tabs;
+mod color_picker;
+mod compat;
+mod config_watch;
+mod crash_watch;
+mod diagnostics;
+mod dnd;
+mod dpi_compat;
+mod embed_risk;
+mod failure_stats;
+mod file_browser;
+mod file_search;
+mod focus_guard;
+mod focus_session;
+mod fullscreen_guard;
+mod gestures;
+mod grid;
+mod highlight;
+mod host_layout;
+mod host_placement;
+mod hot_corners;
+mod idle;
+mod ignored_windows;
+mod input_bypass;
+mod input_lock;
+mod keyboard_layout;
+mod keys;
+mod kiosk;
+mod layout_import;
+mod lifecycle;
+mod logging;
+mod macros;
+mod main_window;
+mod media_keys;
+mod messages;
+mod monitors;
+mod mouse_buttons;
+mod mouse_hook;
+mod native_tabs;
+mod notifications;
+mod ocr;
+mod operations;
+mod palette;
+mod passthrough;
+mod paths;
+mod permissions;
+mod pinned_apps;
+mod pinned_tabs;
+mod platform;
+mod plugins;
+mod presentation;
+mod privacy;
+mod problem_classes;
+mod profile;
+mod rdp;
+mod reattach;
+mod remote_tabs;
+mod resize;
+mod resources;
+mod rules;
+mod safe_mode;
+mod scheduler;
+mod scripts;
+mod self_metrics;
+mod session_watch;
+mod settings;
+mod shell_restart;
+mod snapshots;
+mod storage;
+mod stress;
+mod switcher;
+mod system_prefs;
+mod tab_hotkeys;
+mod tab_labels;
+mod tab_numbers;
+mod tab_overlay;
+mod tab_recording;
+mod tab_state;
+mod tab_suspend;
+mod tab_view;
+mod test_window;
+mod theme;
+mod thumbnails;
+mod title_watch;
+mod toast;
+mod transition;
+mod tray;
+mod ui_state;
+mod updater;
+mod web_apps;
+mod win_events;
+mod window_details;
+mod window_groups;
+mod window_hints;
+mod window_identity;
+mod window_list;
+mod window_manager;
+mod window_ops;
+mod window_query;
+mod window_spy;
+mod zoom;
+mod zones;
+
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{
-    menu::{Menu, MenuItem},
     tray::{TrayIconBuilder, TrayIconEvent, MouseButton},
-    AppHandle, Manager, Emitter, WindowEvent,
+    AppHandle, Manager, Emitter, RunEvent, State, WindowEvent,
 };
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use tracing::{debug, info, warn};
 
 #[cfg(windows)]
 use windows::Win32::{
     Foundation::{BOOL, HWND, LPARAM, WPARAM, RECT, TRUE, POINT},
-    Graphics::Gdi::{InvalidateRect, ClientToScreen, ScreenToClient, RedrawWindow, RDW_ERASE, RDW_INVALIDATE, RDW_FRAME, RDW_ALLCHILDREN, RDW_UPDATENOW, RDW_INTERNALPAINT},
-    UI::Input::KeyboardAndMouse::{GetAsyncKeyState, SetFocus, SetActiveWindow},
+    Graphics::Gdi::{ClientToScreen, ScreenToClient},
     UI::WindowsAndMessaging::*,
-    System::Threading::{GetCurrentProcessId, GetCurrentThreadId, AttachThreadInput, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
 };
 
 #[cfg(windows)]
-use windows::Win32::System::ProcessStatus::K32GetModuleFileNameExW;
+use windows::Win32::{
+    Foundation::GlobalFree,
+    System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GHND},
+    UI::Shell::DROPFILES,
+};
+
+use lifecycle::ReleaseReason;
+use platform::WindowInfo;
+use window_manager::{EmbedError, EmbedErrorCode, TabColor, TabTags, WindowManager};
 
-static ORIGINAL_STYLES: Mutex<Vec<(isize, i32, i32, RECT)>> = Mutex::new(Vec::new());
+// 正在预览的标签: (预览的窗口, 结束预览时恢复到顶层的窗口)
+static PEEK: Mutex<Option<(isize, isize)>> = Mutex::new(None);
 
 // ============================================================
 // 工作区 (Workspace) 数据结构
 // ============================================================
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Workspace {
     pub name: String,
     pub apps: Vec<String>, // EXE 路径列表
+    #[serde(default)]
+    pub tabs: Vec<WorkspaceTab>, // 设置了分组或颜色的标签
+    #[serde(default)]
+    pub do_not_disturb: bool, // 恢复此工作区时开启免打扰
+    #[serde(default)]
+    pub split: zones::SplitRatios, // 停靠区域的分隔比例
+    #[serde(default)]
+    pub media_target: Option<String>, // 接收媒体键的应用 (EXE 路径)
+    #[serde(default)]
+    pub shortcuts: Option<settings::ShortcutSettings>, // 此工作区自己的快捷键 (为空时使用设置中的快捷键)
+}
+
+/// 当前生效的快捷键及其来源工作区 (workspace 为空表示使用设置中的快捷键)
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveShortcuts {
+    pub workspace: Option<String>,
+    pub shortcuts: settings::ShortcutSettings,
+}
+
+/// 工作区中标签的分组、颜色、快捷键和自动重启，恢复时嵌入的窗口按 EXE 路径重新应用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceTab {
+    pub exe_path: String,
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub color: Option<TabColor>,
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    #[serde(default)]
+    pub auto_restart: bool,
+    #[serde(default)]
+    pub preserve_transparency: bool,
 }
 
 static WORKSPACES: Mutex<Vec<Workspace>> = Mutex::new(Vec::new());
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WindowInfo {
-    pub hwnd: isize,
-    pub title: String,
-    pub class_name: String,
-    pub width: i32,
-    pub height: i32,
+// 最近保存或恢复的工作区，调整分隔比例时保存到这个工作区
+static CURRENT_WORKSPACE: Mutex<Option<String>> = Mutex::new(None);
+
+// 正在恢复的工作区中还没有对应窗口的标签
+static PENDING_TABS: Mutex<Vec<WorkspaceTab>> = Mutex::new(Vec::new());
+
+#[tauri::command]
+async fn enumerate_windows() -> Vec<WindowInfo> {
+    window_list::current().await
 }
 
-#[cfg(windows)]
-fn get_current_pid() -> u32 {
-    unsafe { GetCurrentProcessId() }
+/// 可以嵌入的窗口按程序分组 (名称、图标、窗口数)，选择器显示为 "程序 → 窗口" 树
+#[tauri::command]
+async fn enumerate_windows_grouped() -> Vec<window_groups::ProcessGroup> {
+    window_groups::enumerate().await
 }
-#[cfg(not(windows))]
-fn get_current_pid() -> u32 { 0 }
 
-#[cfg(windows)]
-fn is_self_window(hwnd: HWND) -> bool {
-    unsafe {
-        let mut pid = 0;
-        GetWindowThreadProcessId(hwnd, Some(&mut pid));
-        pid == get_current_pid()
-    }
+/// 窗口选择器中的静态预览 (JPEG data URL，宽高不超过 max_size，默认 240)，短时间内重复请求返回缓存
+#[tauri::command]
+async fn get_window_thumbnail(hwnd: isize, max_size: Option<i32>) -> Result<String, String> {
+    thumbnails::get(hwnd, max_size).await
 }
 
-// 辅助：获取类名
-#[cfg(windows)]
-unsafe fn get_class_name(hwnd: HWND) -> String {
-    let mut class_buf = [0u16; 256];
-    let class_len = GetClassNameW(hwnd, &mut class_buf);
-    String::from_utf16_lossy(&class_buf[..class_len as usize])
+// 窗口列表的增量 (since_token 为上次返回的 token，0 表示取完整列表)
+#[tauri::command]
+async fn enumerate_windows_delta(since_token: u64) -> window_list::WindowDelta {
+    window_list::delta(since_token).await
 }
 
-// 辅助：检查是否是危险窗口（可能导致卡死）
-#[cfg(windows)]
-fn is_dangerous_window(class_name: &str) -> bool {
-    // 这些窗口类型嵌入后可能导致系统不稳定
-    let dangerous = [
-        // "CabinetWClass",        // 文件资源管理器 (已允许)
-        // "ExplorerWClass",       // 文件资源管理器变体 (已允许)
-        "Progman",              // 桌面
-        "WorkerW",              // 桌面工作区
-        "Shell_TrayWnd",        // 任务栏
-        "Shell_SecondaryTrayWnd", // 副屏任务栏
-        "TaskManagerWindow",    // 任务管理器
-        "Windows.UI.Core.CoreWindow", // UWP 应用
-    ];
-    dangerous.iter().any(|d| class_name.contains(d))
-}
-
-#[tauri::command]
-fn enumerate_windows() -> Vec<WindowInfo> {
-    #[cfg(windows)]
-    {
-        let mut windows: Vec<WindowInfo> = Vec::new();
-        unsafe {
-            let _ = EnumWindows(
-                Some(enum_window_callback),
-                LPARAM(&mut windows as *mut Vec<WindowInfo> as isize),
-            );
-        }
-        windows
-    }
-    #[cfg(not(windows))]
-    Vec::new()
+// 窗口的详细属性 (样式、所有者、位置状态、DPI、cloak、权限、界面框架)，用于排查嵌入问题
+#[tauri::command]
+fn get_window_details(manager: State<'_, WindowManager>, hwnd: isize) -> Result<window_details::WindowDetails, String> {
+    window_details::get(hwnd, manager.get(hwnd).is_some())
 }
 
-#[cfg(windows)]
-unsafe extern "system" fn enum_window_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
-    let windows = &mut *(lparam.0 as *mut Vec<WindowInfo>);
-    if !IsWindowVisible(hwnd).as_bool() { return TRUE; }
-    if is_self_window(hwnd) { return TRUE; }
-    let title = get_window_title_inner(hwnd);
-    if title.is_empty() || title.contains("WindowHub") { return TRUE; }
-    
-    let class_name = get_class_name(hwnd);
-    let excluded = ["Progman", "Shell_TrayWnd", "Shell_SecondaryTrayWnd", 
-                    "Windows.UI.Core.CoreWindow", "ApplicationFrameWindow",
-                    "WorkerW", "TaskManagerWindow"];
-    if excluded.contains(&class_name.as_str()) { return TRUE; }
-    
-    let mut rect = RECT::default();
-    if GetWindowRect(hwnd, &mut rect).is_ok() {
-        let width = rect.right - rect.left;
-        let height = rect.bottom - rect.top;
-        if width > 100 && height > 100 {
-            windows.push(WindowInfo { hwnd: hwnd.0 as isize, title, class_name, width, height });
-        }
-    }
-    TRUE
+// 窗口及其所有子窗口的层级 (类名、样式、位置、焦点所在的子窗口)
+#[tauri::command]
+fn get_window_tree(hwnd: isize) -> Result<window_spy::WindowTree, String> {
+    window_spy::tree(hwnd)
 }
 
-#[cfg(windows)]
-unsafe fn get_window_title_inner(hwnd: HWND) -> String {
-    let len = GetWindowTextLengthW(hwnd);
-    if len == 0 { return String::new(); }
-    let mut buf = vec![0u16; (len + 1) as usize];
-    GetWindowTextW(hwnd, &mut buf);
-    String::from_utf16_lossy(&buf[..len as usize])
+// 屏幕坐标处最深的子窗口，以及从顶层窗口到它的路径
+#[tauri::command]
+fn inspect_point(x: i32, y: i32) -> Result<window_spy::PointInfo, String> {
+    window_spy::inspect(x, y)
 }
 
+// 窗口的已知兼容性问题 (内置数据库和用户的 compat.json)，选择窗口时提前提示
 #[tauri::command]
-fn embed_window(app: AppHandle, target_hwnd: isize) -> Result<bool, String> {
-    #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        if is_self_window(hwnd) { return Err("不能嵌入自身".to_string()); }
+fn get_compatibility(hwnd: isize) -> Vec<compat::CompatEntry> {
+    let platform = platform::current();
+    let exe_path = platform.process_path(hwnd).ok();
+    compat::lookup(exe_path.as_deref(), &platform.class_name(hwnd))
+}
 
-        // 检查是否是危险窗口
-        let class_name = get_class_name(hwnd);
-        if is_dangerous_window(&class_name) {
-            return Err(format!("不支持嵌入此类型窗口: {}", class_name));
-        }
+// 按标题正则、类名、程序和进程 ID 查找顶层窗口和标签
+#[tauri::command]
+fn find_windows(manager: State<'_, WindowManager>, query: window_query::WindowQuery) -> Result<Vec<window_query::WindowMatch>, String> {
+    window_query::find(platform::current(), &manager.list(), &query)
+}
 
-        let main_window = app.get_webview_window("main").ok_or("无法获取主窗口")?;
-        let parent_hwnd_raw = main_window.hwnd().map_err(|e| e.to_string())?;
-        let parent = HWND(parent_hwnd_raw.0 as *mut _);
-        
-        let parent_style = GetWindowLongW(parent, GWL_STYLE);
-        if (parent_style as u32 & WS_CLIPCHILDREN.0) == 0 {
-             SetWindowLongW(parent, GWL_STYLE, parent_style | WS_CLIPCHILDREN.0 as i32);
-        }
+// 按粘贴的句柄 (十六进制)、完整标题或 "exe:程序名" 嵌入窗口，返回嵌入的标签
+#[tauri::command]
+fn embed_by_reference(app: AppHandle, manager: State<'_, WindowManager>, reference: String) -> Result<window_manager::EmbeddedWindow, String> {
+    let hwnd = window_query::resolve(platform::current(), &reference)?;
+    info!(target: "embed", "按引用嵌入窗口: hwnd={}", hwnd);
+    Ok(embed_tab(&app, &manager, hwnd)?)
+}
 
-        let original_style = GetWindowLongW(hwnd, GWL_STYLE);
-        let original_exstyle = GetWindowLongW(hwnd, GWL_EXSTYLE);
-        let mut original_rect = RECT::default();
-        GetWindowRect(hwnd, &mut original_rect);
-        
-        {
-            let mut styles = ORIGINAL_STYLES.lock().unwrap();
-            if !styles.iter().any(|(h, _, _, _)| *h == target_hwnd) {
-                styles.push((target_hwnd, original_style, original_exstyle, original_rect));
-            }
+#[tauri::command]
+fn embed_window(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize, token: Option<String>) -> Result<bool, String> {
+    // 从窗口列表选择时带上 token，窗口关闭后句柄被其他窗口重新使用时不会嵌入错误的窗口
+    if let Some(token) = token {
+        window_identity::check(platform::current(), target_hwnd, &token).inspect_err(|e| lifecycle::embed_failed(target_hwnd, e))?;
+    }
+    embed_tab(&app, &manager, target_hwnd)?;
+    Ok(true)
+}
+
+// 嵌入窗口并广播 window-embedded，窗口选择器、快捷键、自动嵌入等入口共用
+// 窗口已经嵌入时什么也不做，返回已有的记录
+fn embed_tab(app: &AppHandle, manager: &WindowManager, target_hwnd: isize) -> Result<window_manager::EmbeddedWindow, EmbedError> {
+    let _lock = manager.lock_hwnd(target_hwnd);
+    if let Some(existing) = manager.get(target_hwnd) {
+        debug!(target: "embed", "窗口已嵌入，忽略: hwnd={}", target_hwnd);
+        return Ok(existing);
+    }
+    let platform = platform::current();
+    let host = get_main_window_hwnd(app.clone());
+    let result = if host == 0 {
+        Err(EmbedError::new(EmbedErrorCode::NoHost))
+    } else {
+        // 修改样式和父窗口的中间状态不显示，调整到内容区大小后再显示
+        transition::begin(platform, target_hwnd);
+        manager.embed(platform, host, target_hwnd)
+    };
+    let window = match result {
+        Ok(window) => window,
+        Err(e) => {
+            transition::end(platform, target_hwnd);
+            lifecycle::embed_failed(target_hwnd, &e);
+            return Err(e);
         }
-        
-        let new_style = (original_style as u32 
-            & !(WS_CAPTION.0 | WS_THICKFRAME.0 | WS_MINIMIZEBOX.0 | WS_MAXIMIZEBOX.0 | WS_SYSMENU.0 | WS_POPUP.0 | WS_BORDER.0 | WS_DLGFRAME.0))
-            | WS_CHILD.0 | WS_VISIBLE.0 | WS_CLIPSIBLINGS.0;
+    };
+    let window = apply_workspace_tab(app, manager, window);
+    if window.preserve_transparency {
+        let _ = apply_transparency(manager, target_hwnd, true);
+    }
+    rdp::on_embedded(window.hwnd, &window.class_name);
+    crash_watch::watch(&window);
+    lifecycle::embedded(&window);
+    dnd::on_embedded(app);
+    presentation::on_embedded(app);
 
-        SetWindowLongW(hwnd, GWL_STYLE, new_style as i32);
-        SetParent(hwnd, parent);
-        
-        SetWindowPos(hwnd, HWND_TOP, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_FRAMECHANGED | SWP_SHOWWINDOW);
+    // 镜像模式下激活会把原窗口带到 WindowHub 前面，只在点击镜像画面时激活
+    if platform.embed_mode() == platform::EmbedMode::Reparent {
+        tab_view::show(platform, &manager.hwnds(), target_hwnd);
+        let _ = activate_window(target_hwnd, None);
 
-        let _ = activate_window(target_hwnd);
-        
-        println!("嵌入窗口成功: hwnd={}, class={}", target_hwnd, class_name);
-        
         // 强制重绘，修复黑屏问题
         let _ = force_repaint(target_hwnd);
-        
-        Ok(true)
-    }
-    #[cfg(not(windows))]
-    Err("仅支持 Windows".to_string())
-}
-
-#[tauri::command]
-fn release_window(target_hwnd: isize) -> Result<bool, String> {
-    #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        
-        // 安全地断开线程连接
-        let id_current = GetCurrentThreadId();
-        let id_target = GetWindowThreadProcessId(hwnd, None);
-        if id_current != id_target {
-            let _ = AttachThreadInput(id_current, id_target, false);
-        }
 
-        let _ = SetParent(hwnd, HWND(0 as _)); 
-        
-        let styles = ORIGINAL_STYLES.lock().unwrap();
-        if let Some((_, original_style, original_exstyle, rect)) = styles.iter().find(|(h, _, _, _)| *h == target_hwnd) {
-            SetWindowLongW(hwnd, GWL_STYLE, *original_style);
-            SetWindowLongW(hwnd, GWL_EXSTYLE, *original_exstyle);
-            let width = rect.right - rect.left;
-            let height = rect.bottom - rect.top;
-            SetWindowPos(hwnd, HWND_TOP, rect.left, rect.top, width, height, SWP_FRAMECHANGED | SWP_SHOWWINDOW);
-        } else {
-            let default_style = WS_OVERLAPPEDWINDOW.0 | WS_VISIBLE.0;
-            SetWindowLongW(hwnd, GWL_STYLE, default_style as i32);
-            SetWindowPos(hwnd, HWND_TOP, 100, 100, 800, 600, SWP_FRAMECHANGED | SWP_SHOWWINDOW);
+        // 从桌面上的位置移动到标签位置 (动画期间窗口需要可见，提前结束过渡)
+        if animation::enabled() {
+            if let Some(area) = content_area(app) {
+                transition::end(platform, target_hwnd);
+                animation::embed(app, host, target_hwnd, window.original_rect, host_layout::slot(target_hwnd, area));
+            }
         }
-        
-        ShowWindow(hwnd, SW_RESTORE);
-        SetForegroundWindow(hwnd);
-        Ok(true)
     }
-    #[cfg(not(windows))]
-    Err("仅支持 Windows".to_string())
+
+    tray::refresh(app);
+
+    Ok(window)
 }
 
-/// 释放所有嵌入窗口 (用于程序退出时的同步清理)
-#[cfg(windows)]
-fn release_all_embedded_windows() {
-    unsafe {
-        let styles = ORIGINAL_STYLES.lock().unwrap();
-        println!("[清理] 释放 {} 个嵌入窗口", styles.len());
-        
-        for (target_hwnd, original_style, original_exstyle, rect) in styles.iter() {
-            let hwnd = HWND(*target_hwnd as *mut _);
-            
-            if !IsWindow(hwnd).as_bool() {
-                continue;
-            }
-            
-            // 断开线程连接
-            let id_current = GetCurrentThreadId();
-            let id_target = GetWindowThreadProcessId(hwnd, None);
-            if id_current != id_target {
-                let _ = AttachThreadInput(id_current, id_target, false);
-            }
-            
-            // 移除父窗口关系
-            let _ = SetParent(hwnd, HWND(0 as _));
-            
-            // 恢复原始样式
-            SetWindowLongW(hwnd, GWL_STYLE, *original_style);
-            SetWindowLongW(hwnd, GWL_EXSTYLE, *original_exstyle);
-            
-            // 恢复原始位置和大小
-            let width = rect.right - rect.left;
-            let height = rect.bottom - rect.top;
-            SetWindowPos(hwnd, HWND_TOP, rect.left, rect.top, width, height, SWP_FRAMECHANGED | SWP_SHOWWINDOW);
-            
-            ShowWindow(hwnd, SW_RESTORE);
-        }
+// 快捷键：把当前前台窗口嵌入 WindowHub，成功后显示主窗口并发送 foreground-embedded (载荷为 WindowInfo)
+fn embed_foreground(app: &AppHandle) {
+    let platform = platform::current();
+    let hwnd = platform.foreground_window();
+    // 前台是 WindowHub 自己 (或其嵌入的标签) 时不处理
+    if hwnd == 0 || platform.is_own_window(hwnd) {
+        return;
     }
+    info!(target: "shortcuts", "嵌入前台窗口: hwnd={}", hwnd);
+    let manager = app.state::<WindowManager>();
+    let Ok(window) = embed_tab(app, &manager, hwnd) else { return };
+
+    if let Some(main) = app.get_webview_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+    }
+    let info = WindowInfo {
+        hwnd: window.hwnd,
+        title: window.title.clone(),
+        class_name: window.class_name.clone(),
+        width: window.original_rect.width(),
+        height: window.original_rect.height(),
+        hint: None,
+        ordinal: 0,
+        token: window_identity::token(platform, window.hwnd),
+        dpi_awareness: platform.dpi_awareness(window.hwnd),
+    };
+    let _ = app.emit("foreground-embedded", info);
 }
 
+/// 进入拾取模式：隐藏主窗口，单击要嵌入的窗口 (右键或 Esc 取消)，结束时发送 capture-mode-ended
 #[tauri::command]
-fn update_window_rect(target_hwnd: isize, x: i32, y: i32, width: i32, height: i32) -> Result<bool, String> {
-    #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        
-        // 检查窗口是否还有效
-        if !IsWindow(hwnd).as_bool() {
-            return Ok(false);
-        }
-        
-        let mut rect = RECT::default();
-        if GetWindowRect(hwnd, &mut rect).is_ok() {
-             let parent = GetParent(hwnd);
-             if parent.is_ok() {
-                  let mut pt_tl = POINT { x: rect.left, y: rect.top };
-                  ScreenToClient(parent.unwrap(), &mut pt_tl);
-                  
-                  let current_w = rect.right - rect.left;
-                  let current_h = rect.bottom - rect.top;
-                  
-                  if (pt_tl.x - x).abs() <= 1 && (pt_tl.y - y).abs() <= 1 && 
-                     (current_w - width).abs() <= 1 && (current_h - height).abs() <= 1 {
-                      return Ok(true);
-                  }
-             }
-        }
+fn start_capture_mode(app: AppHandle) -> Result<(), String> {
+    capture::start(&app)
+}
 
-        SetWindowPos(hwnd, HWND::default(), x, y, width, height, SWP_NOZORDER | SWP_NOACTIVATE | SWP_SHOWWINDOW);
-        Ok(true)
-    }
-    #[cfg(not(windows))]
-    Err("仅支持 Windows".to_string())
+#[tauri::command]
+fn cancel_capture_mode() {
+    capture::cancel();
 }
 
+/// 取色：单击屏幕上任意位置 (包括嵌入的窗口) 返回该处的颜色，右键或 Esc 取消时返回 null，移动时发送 color-preview
+#[tauri::command]
+async fn pick_color(app: AppHandle) -> Result<Option<color_picker::PickedColor>, String> {
+    tauri::async_runtime::spawn_blocking(move || color_picker::pick(&app))
+        .await
+        .map_err(|e| format!("取色中断: {}", e))?
+}
 
+// 释放时 SetParent 等调用要等目标处理消息，目标卡住时会一直阻塞，不放在主线程上执行，以免其他命令都被卡住
+#[tauri::command]
+async fn release_window(app: AppHandle, target_hwnd: isize) -> Result<bool, String> {
+    kiosk::ensure_unlocked("弹出标签")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app.state::<WindowManager>();
+        release_tab(&app, &manager, target_hwnd, ReleaseReason::Detach).map_err(String::from)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(true)
+}
 
+/// 所有显示器的范围、工作区、DPI、设备名和是否为主显示器，release_to_monitor 等按这个顺序的序号指定显示器
 #[tauri::command]
-fn activate_window(target_hwnd: isize) -> Result<bool, String> {
-    #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        
-        // 检查窗口是否有效
-        if !IsWindow(hwnd).as_bool() {
-            return Ok(false);
-        }
-        
-        let id_current = GetCurrentThreadId();
-        let id_target = GetWindowThreadProcessId(hwnd, None);
-        
-        // 只在不同线程时才 Attach，避免死锁
-        let attached = if id_current != id_target {
-            AttachThreadInput(id_current, id_target, true).as_bool()
-        } else {
-            false
-        };
-        
-        // 尝试强制前台
-        if IsIconic(hwnd).as_bool() {
-            ShowWindow(hwnd, SW_RESTORE);
-        }
-        
-        // 关键修复：使用 SetForegroundWindow 确保焦点
-        SetForegroundWindow(hwnd);
-        BringWindowToTop(hwnd);
-        
-        SetWindowPos(hwnd, HWND_TOP, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_SHOWWINDOW);
-        
-        // 关键修复：发送 WM_NCACTIVATE 欺骗窗口它已被激活 (对 Chrome/VSCode/Electron 应用至关重要)
-        let _ = PostMessageW(hwnd, WM_NCACTIVATE, WPARAM(1), LPARAM(0));
-        
-        // 新增修复：发送 WM_ACTIVATE (WA_ACTIVE=1) 欺骗客户区它已被激活 (针对飞书/Electron/游戏)
-        let _ = PostMessageW(hwnd, WM_ACTIVATE, WPARAM(1), LPARAM(0));
-        
-        // 关键修复：不仅 SetFocus，还要 SetActiveWindow，确保输入法上下文被激活
-        let _ = SetActiveWindow(hwnd);
-        SetFocus(hwnd);
-        
-        // 再次强制重绘，确保激活状态正确显示
-        let _ = RedrawWindow(hwnd, None, None, RDW_INVALIDATE | RDW_UPDATENOW | RDW_ALLCHILDREN);
-        
-        // 紧急回滚：永久连接导致了所有输入法失效 (死锁或队列冲突)。
-        // 恢复断开逻辑，但适当延长到 200ms 以给 TSF 更多初始化时间。
-        if attached {
-            std::thread::spawn(move || {
-                std::thread::sleep(std::time::Duration::from_millis(200));
-                unsafe { let _ = AttachThreadInput(id_current, id_target, false); }
-            });
-        }
-        
-        Ok(true)
-    }
-    #[cfg(not(windows))]
-    Err("仅支持 Windows".to_string())
+fn enumerate_monitors() -> Vec<monitors::Monitor> {
+    monitors::list()
 }
 
+/// 弹出标签并放到第 monitor_index 个显示器上 (enumerate_monitors 的顺序)，maximize 时最大化，否则保持大小放在中间
 #[tauri::command]
-fn close_target_window(target_hwnd: isize) -> Result<bool, String> {
-    #[cfg(windows)]
-    unsafe {
-        let _ = release_window(target_hwnd);
-        let hwnd = HWND(target_hwnd as *mut _);
-        let _ = PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
-        Ok(true)
+async fn release_to_monitor(app: AppHandle, target_hwnd: isize, monitor_index: usize, maximize: bool) -> Result<bool, String> {
+    kiosk::ensure_unlocked("弹出标签")?;
+    let monitor = monitors::list().into_iter().nth(monitor_index).ok_or("显示器不存在")?;
+    // 同 release_window，释放和摆放都不在主线程上执行
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app.state::<WindowManager>();
+        release_tab(&app, &manager, target_hwnd, ReleaseReason::Detach)?;
+        // 在窗口操作线程上执行，排在弹出动画之后
+        monitors::place(target_hwnd, &monitor, maximize)?;
+        info!(target: "embed", "弹出到显示器 {} ({}): hwnd={}, 最大化={}", monitor_index, monitor.name, target_hwnd, maximize);
+        Ok::<(), String>(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(true)
+}
+
+// 释放窗口并清理标签的各种记录，窗口没有被嵌入时返回 NotEmbedded
+fn release_tab(app: &AppHandle, manager: &WindowManager, target_hwnd: isize, reason: ReleaseReason) -> Result<(), EmbedError> {
+    // 命令、脚本、全部关闭和独占全屏检测都经过这里，展台模式下一律不释放
+    kiosk::ensure_unlocked(if reason == ReleaseReason::Detach { "弹出标签" } else { "关闭标签" })?;
+    let platform = platform::current();
+    let _lock = manager.lock_hwnd(target_hwnd);
+    // 弹出当前显示的标签时，从标签位置移回原来的位置
+    let animate_from = (reason == ReleaseReason::Detach && host_layout::active() == target_hwnd && animation::enabled())
+        .then(|| animation::screen_rect(target_hwnd))
+        .flatten();
+    let position = manager.hwnds().iter().position(|&h| h == target_hwnd).unwrap_or(0);
+    let saved = manager
+        .release(platform, target_hwnd)
+        .inspect_err(|e| failure_stats::record(failure_stats::FailureOp::Release, target_hwnd, e))?;
+    // 弹出的窗口还在桌面上，不需要重新打开
+    if reason == ReleaseReason::Close {
+        closed_tabs::record(&saved, position, zones::zone_of(target_hwnd));
     }
-    #[cfg(not(windows))]
-    Err("仅支持 Windows".to_string())
+    if reason == ReleaseReason::Detach {
+        pinned_tabs::forget(&saved);
+    }
+    forget_tab_state(app, target_hwnd, Some(&saved));
+    platform.raise(target_hwnd);
+    if let Some(from) = animate_from {
+        animation::release(target_hwnd, from, saved.original_rect);
+    }
+
+    tray::refresh(app);
+    lifecycle::released(target_hwnd, reason, Some(saved));
+    Ok(())
 }
 
-#[tauri::command]
-fn is_window_valid(target_hwnd: isize) -> bool {
-    #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        IsWindow(hwnd).as_bool()
+// 清理各模块中与标签有关的状态，释放和关闭标签共用，新模块的清理加在这里
+fn forget_tab_state(app: &AppHandle, target_hwnd: isize, saved: Option<&window_manager::EmbeddedWindow>) {
+    tab_hotkeys::forget(app, saved);
+    tab_view::forget(target_hwnd);
+    privacy::refresh_capture();
+    tab_overlay::forget(target_hwnd);
+    zones::forget(target_hwnd);
+    presentation::forget(app, target_hwnd);
+    input_lock::forget(target_hwnd);
+    tab_suspend::resume(target_hwnd);
+    host_layout::forget(target_hwnd);
+    child_order::forget(target_hwnd);
+    if grid::remove(target_hwnd) {
+        grid_changed(app);
     }
-    #[cfg(not(windows))]
-    false
+    idle::forget(target_hwnd);
+    rdp::forget(target_hwnd);
+    zoom::clear(app, target_hwnd);
+    resources::release_tab(target_hwnd);
 }
 
 #[tauri::command]
-fn is_cursor_in_client_area(app: AppHandle, top_offset: i32) -> bool {
-    #[cfg(windows)]
-    unsafe {
-        let main_window = match app.get_webview_window("main") {
-            Some(w) => w,
-            None => return false,
-        };
-        let hwnd_raw = match main_window.hwnd() {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-        let hwnd = HWND(hwnd_raw.0 as *mut _);
+fn update_window_rect(target_hwnd: isize, x: i32, y: i32, width: i32, height: i32) -> Result<bool, String> {
+    // 由调度线程按帧合并后应用
+    host_layout::place(target_hwnd, x, y, width, height);
+    Ok(platform::current().is_window(target_hwnd))
+}
 
-        // 修复 Bug: 如果窗口被隐藏或最小化，直接返回 false，防止"幽灵嵌入"
-        if !IsWindowVisible(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
-            return false;
-        }
+// 内容区到主窗口客户区四边的距离，主窗口调整大小时后端据此直接移动当前标签
+// 前端只移动当前标签，有停靠的标签时由后端按新的内容区一起移动
+#[tauri::command]
+fn set_embed_layout(app: AppHandle, insets: host_layout::Insets) {
+    host_layout::set_layout(insets);
+    relayout_docked(&app);
+}
 
-        let mut cursor_point = POINT::default();
-        GetCursorPos(&mut cursor_point);
+/// 原生标签栏的位置：网页中原来标签区域的位置 (客户区物理像素)，scale 为 devicePixelRatio
+#[tauri::command]
+fn place_native_tab_strip(x: i32, y: i32, width: i32, height: i32, scale: f32) {
+    native_tabs::place(x, y, width, height, scale);
+}
 
-        let mut client_point = POINT { x: 0, y: 0 };
-        ClientToScreen(hwnd, &mut client_point);
-        
-        let mut client_rect = RECT::default();
-        GetClientRect(hwnd, &mut client_rect);
+// 停靠区域的位置变化后 (布局或分隔比例) 重新放置停靠的标签和网格中的标签
+fn relayout_docked(app: &AppHandle) {
+    if !zones::docked().is_empty() || !grid::members().is_empty() {
+        relayout(app);
+    }
+}
 
-        let screen_left = client_point.x;
-        let screen_top = client_point.y;
-        let screen_right = screen_left + client_rect.right;
-        let screen_bottom = screen_top + client_rect.bottom;
+// 按主窗口当前大小重新放置当前标签 (及与它一起显示的停靠标签)
+fn relayout(app: &AppHandle) {
+    if let Some(size) = app.get_webview_window("main").and_then(|w| w.inner_size().ok()) {
+        host_layout::host_resized(size.width, size.height);
+    }
+}
 
-        let effective_top = screen_top + top_offset;
+// 主窗口当前的内容区 (客户区坐标，物理像素)，还没有上报布局时为 None
+fn content_area(app: &AppHandle) -> Option<(i32, i32, i32, i32)> {
+    let size = app.get_webview_window("main")?.inner_size().ok()?;
+    host_layout::content_area(size.width, size.height)
+}
 
-        cursor_point.x >= screen_left && cursor_point.x <= screen_right &&
-        cursor_point.y >= effective_top && cursor_point.y <= screen_bottom
-    }
-    #[cfg(not(windows))]
-    false
+/// 拖动标签或外部窗口时光标处的停靠区域，光标不在内容区内时为 None
+#[tauri::command]
+fn get_snap_zone(app: AppHandle) -> Option<zones::ZoneHint> {
+    let main = app.get_webview_window("main")?;
+    let cursor = main.cursor_position().ok()?;
+    let origin = main.inner_position().ok()?;
+    zones::hint_at(content_area(&app)?, zones::split(), cursor.x as i32 - origin.x, cursor.y as i32 - origin.y)
 }
 
+/// 把标签停靠到内容区的区域 (full 为取消停靠) 并切换到它，
+/// 被挤出区域的标签改为不停靠，变化通过 tab-zone-changed 通知 (载荷为 TabZone 列表)
 #[tauri::command]
-fn get_foreground_window() -> isize {
-    #[cfg(windows)]
-    unsafe { GetForegroundWindow().0 as isize }
-    #[cfg(not(windows))]
-    0
+fn dock_tab(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize, zone: zones::Zone) -> Result<(), String> {
+    if manager.get(target_hwnd).is_none() {
+        return Err("标签不存在".to_string());
+    }
+    if grid::remove(target_hwnd) {
+        grid_changed(&app);
+    }
+    let changed = zones::dock(target_hwnd, zone);
+    show_tab(app.clone(), manager, target_hwnd)?;
+    relayout(&app);
+    let _ = app.emit("tab-zone-changed", changed);
+    Ok(())
 }
 
+/// 监控墙：嵌入一组窗口 (已嵌入的直接使用) 并按数量自动排成网格，同时显示，返回网格中的所有标签
+/// 再次调用时加入已有的网格；网格中的标签释放、关闭或停靠到其他区域后其余标签重新排列
+/// 变化通过 grid-changed 通知 (载荷为网格中的标签)
 #[tauri::command]
-fn get_main_window_hwnd(app: AppHandle) -> isize {
-    #[cfg(windows)]
-    {
-        if let Some(w) = app.get_webview_window("main") {
-            if let Ok(h) = w.hwnd() {
-                return h.0 as isize;
+fn embed_as_grid(app: AppHandle, manager: State<'_, WindowManager>, hwnds: Vec<isize>) -> Result<Vec<isize>, String> {
+    let mut tabs = Vec::new();
+    let mut undocked = Vec::new();
+    for hwnd in hwnds {
+        if manager.get(hwnd).is_none() {
+            if let Err(e) = embed_tab(&app, &manager, hwnd) {
+                warn!(target: "embed", "网格中的窗口嵌入失败 hwnd={}: {}", hwnd, e.message);
+                continue;
             }
         }
-        0
+        // 网格和停靠区域互斥
+        if zones::zone_of(hwnd) != zones::Zone::Full {
+            undocked.extend(zones::dock(hwnd, zones::Zone::Full));
+        }
+        tabs.push(hwnd);
     }
-    #[cfg(not(windows))]
-    0
+    let Some(&first) = tabs.first() else {
+        return Err("没有可以排列的窗口".to_string());
+    };
+    let members = grid::add(&tabs);
+    info!(target: "embed", "监控墙网格: {} 个标签 ({:?})", members.len(), grid::dimensions(members.len()));
+    show_tab(app.clone(), manager, first)?;
+    if !undocked.is_empty() {
+        let _ = app.emit("tab-zone-changed", undocked);
+    }
+    grid_changed(&app);
+    Ok(members)
 }
 
-#[tauri::command]
-fn get_window_title(target_hwnd: isize) -> String {
-    #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        get_window_title_inner(hwnd)
+// 网格中的标签变化：当前标签在网格中时立即重新排列，通知前端
+fn grid_changed(app: &AppHandle) {
+    if grid::contains(host_layout::active()) {
+        relayout(app);
     }
-    #[cfg(not(windows))]
-    String::new()
+    let _ = app.emit("grid-changed", grid::members());
 }
 
-#[tauri::command]
-fn is_mouse_left_down() -> bool {
-    #[cfg(windows)]
-    unsafe { (GetAsyncKeyState(0x01) as u16 & 0x8000) != 0 }
-    #[cfg(not(windows))]
-    false
+/// tab-fullscreen-changed 事件的载荷
+#[derive(Clone, Serialize)]
+struct TabFullscreen {
+    hwnd: isize,
+    fullscreen: bool,
 }
 
-// 检查窗口是否可以安全嵌入
+/// 临时最大化标签 (占满整个主窗口客户区，停靠在一起的其他标签隐藏)，再次调用恢复原来的布局
+/// 返回切换后是否最大化，变化通过 tab-fullscreen-changed 通知
 #[tauri::command]
-fn can_embed_window(target_hwnd: isize) -> Result<bool, String> {
-    #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        if is_self_window(hwnd) { 
-            return Err("不能嵌入自身".to_string()); 
-        }
-        let class_name = get_class_name(hwnd);
-        if is_dangerous_window(&class_name) {
-            return Err(format!("不支持嵌入此窗口类型: {}", class_name));
-        }
-        Ok(true)
+fn toggle_tab_fullscreen(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize) -> Result<bool, String> {
+    if manager.get(target_hwnd).is_none() {
+        return Err("标签不存在".to_string());
     }
-    #[cfg(not(windows))]
-    Ok(true)
+    let fullscreen = zones::toggle_maximized(target_hwnd);
+    show_tab(app.clone(), manager, target_hwnd)?;
+    relayout(&app);
+    info!(target: "embed", "标签 hwnd={} {}", target_hwnd, if fullscreen { "最大化" } else { "恢复布局" });
+    let _ = app.emit("tab-fullscreen-changed", TabFullscreen { hwnd: target_hwnd, fullscreen });
+    Ok(fullscreen)
 }
 
-// 隐藏嵌入窗口（搜索时使用）
+/// 主窗口切换为所在显示器上的无边框全屏 (再次调用恢复原来的大小和位置)，返回切换后是否全屏
+/// 嵌入的标签随 Resized 事件按新的客户区重新布局，变化通过 host-fullscreen-changed 通知
 #[tauri::command]
-fn hide_window(target_hwnd: isize) -> bool {
-    #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        ShowWindow(hwnd, SW_HIDE);
-        true
+fn toggle_host_fullscreen(app: AppHandle) -> Result<bool, String> {
+    let main = app.get_webview_window("main").ok_or("主窗口不存在")?;
+    let fullscreen = !main.is_fullscreen().map_err(|e| e.to_string())?;
+    if !fullscreen {
+        kiosk::ensure_unlocked("退出全屏")?;
     }
-    #[cfg(not(windows))]
+    main.set_fullscreen(fullscreen).map_err(|e| format!("切换全屏失败: {}", e))?;
+    info!(target: "embed", "主窗口{}", if fullscreen { "全屏" } else { "退出全屏" });
+    let _ = app.emit("host-fullscreen-changed", fullscreen);
+    Ok(fullscreen)
+}
+
+/// 演示模式：主窗口在指定显示器上无边框全屏，只显示并操作这个标签，不发送关注提示，阻止屏幕保护程序
+/// monitor 为 enumerate_monitors 中的序号，不指定时留在当前显示器；变化通过 presentation-mode-changed 通知
+#[tauri::command]
+fn enter_presentation_mode(app: AppHandle, tab: isize, monitor: Option<usize>) -> Result<presentation::PresentationMode, String> {
+    presentation::enter(&app, tab, monitor)
+}
+
+/// 退出演示模式，没有进入时返回 false
+#[tauri::command]
+fn exit_presentation_mode(app: AppHandle) -> bool {
+    presentation::exit(&app)
+}
+
+#[tauri::command]
+fn get_presentation_mode() -> Option<presentation::PresentationMode> {
+    presentation::current()
+}
+
+/// 开启展台模式 (全屏、不能关闭或弹出标签、退出需要 PIN、应用退出后自动重启)
+#[tauri::command]
+fn enter_kiosk_mode(app: AppHandle, pin: String) -> Result<(), String> {
+    kiosk::enter(&app, &pin)
+}
+
+#[tauri::command]
+fn exit_kiosk_mode(app: AppHandle, pin: String) -> Result<(), String> {
+    kiosk::exit(&app, &pin)
+}
+
+#[tauri::command]
+fn get_kiosk_mode() -> bool {
+    kiosk::is_enabled()
+}
+
+/// 展台模式下输入 PIN 退出 WindowHub
+#[tauri::command]
+fn kiosk_quit(app: AppHandle, pin: String) -> Result<(), String> {
+    kiosk::verify(&pin)?;
+    info!(target: "shortcuts", "退出应用 (展台模式)");
+    app.exit(0);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_split_ratios() -> zones::SplitRatios {
+    zones::split()
+}
+
+/// 拖动分隔条：调整停靠区域的分隔比例 (columns 为左列宽度、rows 为上行高度占内容区的比例)，
+/// 停靠的标签立即按新比例移动，比例保存到当前的工作区
+#[tauri::command]
+fn set_split_ratio(app: AppHandle, group: zones::SplitGroup, ratio: f32) -> Result<zones::SplitRatios, String> {
+    let ratios = zones::set_split_ratio(group, ratio)?;
+    relayout_docked(&app);
+    let current = CURRENT_WORKSPACE.lock().unwrap().clone();
+    if let Some(name) = current {
+        let mut workspaces = WORKSPACES.lock().unwrap();
+        if let Some(ws) = workspaces.iter_mut().find(|w| w.name == name) {
+            ws.split = ratios;
+            save_workspaces_to_file(&workspaces);
+        }
+    }
+    Ok(ratios)
+}
+
+/// 激活标签；options 可选：raise_only 只调整 Z 序 (标签悬停预览用，不切换当前标签、不抢焦点)，
+/// focus_only 只设置焦点，no_foreground_steal 其他程序在前台时不抢前台，retry_count 焦点没有落到窗口上时重试的次数
+#[tauri::command]
+fn activate_window(target_hwnd: isize, options: Option<platform::ActivateOptions>) -> Result<bool, String> {
+    let platform = platform::current();
+    if !platform.is_window(target_hwnd) {
+        return Ok(false);
+    }
+    let options = options.unwrap_or_default();
+    if options.raise_only {
+        let raised = platform.activate(target_hwnd, options);
+        if raised {
+            child_order::raised(target_hwnd);
+        }
+        return Ok(raised);
+    }
+    zoom::set_active(target_hwnd);
+    // 切换到其他标签：新标签调整到内容区大小之前先不显示
+    if host_layout::set_active(target_hwnd) != target_hwnd {
+        transition::begin(platform, target_hwnd);
+    }
+    let activated = platform.activate(target_hwnd, options);
+    if activated {
+        child_order::raised(target_hwnd);
+        lifecycle::activated(target_hwnd, platform.title(target_hwnd));
+    }
+    Ok(activated)
+}
+
+// 同 release_window，释放不在主线程上执行
+#[tauri::command]
+async fn close_target_window(app: AppHandle, target_hwnd: isize) -> Result<bool, String> {
+    kiosk::ensure_unlocked("关闭标签")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app.state::<WindowManager>();
+        release_tab(&app, &manager, target_hwnd, ReleaseReason::Close)?;
+        platform::current().close(target_hwnd);
+        Ok::<(), String>(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(true)
+}
+
+/// 请求关闭嵌入窗口并等待应用的反应，只有窗口确实关闭时才移除标签；
+/// 应用弹出保存提示或拒绝关闭时标签保留，由前端提示用户
+/// 返回操作 ID，结果 (CloseOutcome) 通过 operation-finished 送达
+#[tauri::command]
+fn request_close(app: AppHandle, target_hwnd: isize) -> Result<u64, String> {
+    kiosk::ensure_unlocked("关闭标签")?;
+    let handle = app.clone();
+    Ok(operations::start(&app, "request_close", false, |_| async move {
+        let outcome = close_request::request(platform::current(), target_hwnd).await;
+        info!(target: "embed", "关闭窗口 hwnd={}: {:?}", target_hwnd, outcome);
+        if outcome == close_request::CloseOutcome::Closed {
+            forget_closed_tab(&handle, target_hwnd);
+        }
+        Ok(outcome)
+    }))
+}
+
+/// 按策略关闭、释放或强制结束所有标签 (固定的标签除外)，在后端依次处理，一个应用卡住不影响其他标签
+/// 返回操作 ID，每处理完一个标签发送 close-all-progress，结果 (CloseAllReport) 通过 operation-finished 送达
+#[tauri::command]
+fn close_all_tabs(app: AppHandle, policy: Option<close_all::ClosePolicy>) -> Result<u64, String> {
+    kiosk::ensure_unlocked("关闭标签")?;
+    let handle = app.clone();
+    Ok(operations::start(&app, "close_all_tabs", false, |progress| close_all::run(handle, policy.unwrap_or_default(), progress)))
+}
+
+/// 强制结束嵌入窗口所属的进程树 (应用卡死、关闭请求无效时使用)
+/// 不带 pid 调用只返回进程信息 (PID、程序名、路径) 供前端确认；
+/// 确认后带上返回的 pid 再次调用才结束进程并移除标签
+#[tauri::command]
+async fn force_kill_window(app: AppHandle, target_hwnd: isize, pid: Option<u32>) -> Result<close_request::ProcessInfo, String> {
+    kiosk::ensure_unlocked("关闭标签")?;
+    // 结束进程后要等它退出，查询进程信息也可能被卡住的目标拖慢，不在主线程上执行
+    tauri::async_runtime::spawn_blocking(move || {
+        let platform = platform::current();
+        let Some(pid) = pid else {
+            return close_request::process_info(platform, target_hwnd);
+        };
+        let info = close_request::force_kill(platform, target_hwnd, pid)?;
+        warn!(target: "embed", "已强制结束进程: {} (pid={}, hwnd={})", info.exe_name, info.pid, target_hwnd);
+        forget_closed_tab(&app, target_hwnd);
+        Ok(info)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 重新启动并嵌入最近关闭的标签 (恢复名称、分组、颜色和停靠区域)，返回操作 ID
+/// 结果 (TabReopened) 通过 operation-finished 送达，同时发送 tab-reopened
+#[tauri::command]
+fn reopen_last_tab(app: AppHandle) -> Result<u64, String> {
+    closed_tabs::reopen_last(&app)
+}
+
+/// 最近关闭的标签，最近的在前
+#[tauri::command]
+fn list_closed_tabs() -> Vec<closed_tabs::ClosedTab> {
+    closed_tabs::list()
+}
+
+// 窗口已经关闭，清理标签记录 (不需要恢复窗口状态)
+fn forget_closed_tab(app: &AppHandle, target_hwnd: isize) {
+    let manager = app.state::<WindowManager>();
+    let _lock = manager.lock_hwnd(target_hwnd);
+    let position = manager.hwnds().iter().position(|&h| h == target_hwnd).unwrap_or(0);
+    let saved = manager.remove(target_hwnd);
+    if let Some(saved) = &saved {
+        closed_tabs::record(saved, position, zones::zone_of(target_hwnd));
+    }
+    forget_tab_state(app, target_hwnd, saved.as_ref());
+    tray::refresh(app);
+    lifecycle::released(target_hwnd, ReleaseReason::Close, saved);
+}
+
+#[tauri::command]
+fn is_window_valid(target_hwnd: isize) -> bool {
+    platform::current().is_window(target_hwnd)
+}
+
+#[tauri::command]
+fn is_cursor_in_client_area(app: AppHandle, top_offset: i32) -> bool {
+    #[cfg(windows)]
+    unsafe {
+        let main_window = match app.get_webview_window("main") {
+            Some(w) => w,
+            None => return false,
+        };
+        let hwnd_raw = match main_window.hwnd() {
+            Ok(h) => h,
+            Err(_) => return false,
+        };
+        let hwnd = HWND(hwnd_raw.0 as *mut _);
+
+        // 修复 Bug: 如果窗口被隐藏或最小化，直接返回 false，防止"幽灵嵌入"
+        if !IsWindowVisible(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+            return false;
+        }
+
+        let mut cursor_point = POINT::default();
+        GetCursorPos(&mut cursor_point);
+
+        let mut client_point = POINT { x: 0, y: 0 };
+        ClientToScreen(hwnd, &mut client_point);
+        
+        let mut client_rect = RECT::default();
+        GetClientRect(hwnd, &mut client_rect);
+
+        let screen_left = client_point.x;
+        let screen_top = client_point.y;
+        let screen_right = screen_left + client_rect.right;
+        let screen_bottom = screen_top + client_rect.bottom;
+
+        let effective_top = screen_top + top_offset;
+
+        cursor_point.x >= screen_left && cursor_point.x <= screen_right &&
+        cursor_point.y >= effective_top && cursor_point.y <= screen_bottom
+    }
+    #[cfg(not(windows))]
     false
 }
 
-// 显示嵌入窗口（搜索结束时使用）
 #[tauri::command]
-fn show_window(target_hwnd: isize) -> bool {
+fn get_foreground_window() -> isize {
+    platform::current().foreground_window()
+}
+
+#[tauri::command]
+fn get_main_window_hwnd(app: AppHandle) -> isize {
+    #[cfg(windows)]
+    {
+        if let Some(w) = app.get_webview_window("main") {
+            if let Ok(h) = w.hwnd() {
+                return h.0 as isize;
+            }
+        }
+        0
+    }
+    // macOS 只用作嵌入记录的 host，镜像模式不会改变窗口层级
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(w) = app.get_webview_window("main") {
+            if let Ok(h) = w.ns_window() {
+                return h as isize;
+            }
+        }
+        0
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    0
+}
+
+#[tauri::command]
+fn get_window_title(target_hwnd: isize) -> String {
+    privacy::title(platform::current().title(target_hwnd))
+}
+
+#[tauri::command]
+fn is_mouse_left_down() -> bool {
+    platform::current().is_mouse_left_down()
+}
+
+// 检查窗口是否可以安全嵌入，可疑的窗口返回 needs_confirmation 由用户确认 (见 embed_risk.rs)
+#[tauri::command]
+fn can_embed_window(target_hwnd: isize) -> Result<embed_risk::EmbedCheck, String> {
+    let platform = platform::current();
+    window_manager::check_embeddable(platform, target_hwnd)?;
+    Ok(embed_risk::assess(platform, target_hwnd))
+}
+
+// 嵌入时无响应、之后不再嵌入的窗口类型
+#[tauri::command]
+fn get_problem_classes() -> Vec<String> {
+    problem_classes::list()
+}
+
+// 允许再次嵌入该类型的窗口，返回新的列表
+#[tauri::command]
+fn forget_problem_class(class_name: String) -> Vec<String> {
+    problem_classes::forget(&class_name)
+}
+
+/// 用户在窗口选择器中划掉了窗口，同一种窗口多次被划掉后不再列出 (发送 window-pattern-ignored 供撤销)，返回这次是否开始忽略
+#[tauri::command]
+fn dismiss_window(app: AppHandle, hwnd: isize) -> bool {
+    let platform = platform::current();
+    let exe_path = platform.process_path(hwnd).ok();
+    ignored_windows::dismiss(&app, &platform.class_name(hwnd), exe_path.as_deref())
+}
+
+/// 不再列出一种窗口 (类名和/或程序文件名)，返回新的列表
+#[tauri::command]
+fn ignore_window_pattern(pattern: ignored_windows::IgnorePattern) -> Result<Vec<ignored_windows::IgnorePattern>, String> {
+    ignored_windows::ignore(pattern)
+}
+
+#[tauri::command]
+fn list_ignored_windows() -> Vec<ignored_windows::IgnorePattern> {
+    ignored_windows::list()
+}
+
+#[tauri::command]
+fn unignore_window_pattern(pattern: ignored_windows::IgnorePattern) -> Vec<ignored_windows::IgnorePattern> {
+    ignored_windows::unignore(pattern)
+}
+
+// 在外部窗口周围显示/隐藏高亮边框 (窗口选择器悬停时标出对应的窗口)
+#[tauri::command]
+fn highlight_window(target_hwnd: isize, enable: bool) {
+    if enable {
+        highlight::show(target_hwnd);
+    } else {
+        highlight::hide(target_hwnd);
+    }
+}
+
+// 列出后端记录的所有嵌入窗口：原始状态 + 当前标题/位置/未响应/挂起/隐藏等实时状态
+// 前端据此与自己的标签列表对账
+#[tauri::command]
+fn list_embedded_windows(manager: State<'_, WindowManager>) -> Vec<window_manager::EmbeddedWindowStatus> {
+    let mut statuses = manager.statuses();
+    // 固定的标签排在最前面 (稳定排序，保持其余顺序)
+    statuses.sort_by_key(|s| !s.window.pinned);
+    for status in &statuses {
+        tab_state::set_hung(status.window.hwnd, status.valid && status.hung);
+        tab_overlay::set_hung(status.window.hwnd, status.valid && status.hung);
+        // 应用关闭自己的模态对话框时会重新启用窗口，对账时再次锁定
+        if status.valid && status.window.input_locked {
+            platform::current().set_input_enabled(status.window.hwnd, false);
+        }
+    }
+    statuses
+}
+
+// 所有标签的生命周期状态 (前端据此与 tab-state-changed 重新同步)
+#[tauri::command]
+fn get_tab_states() -> Vec<tab_state::TabStateEntry> {
+    tab_state::list()
+}
+
+// 重建托盘菜单（列出当前嵌入的窗口）
+#[tauri::command]
+fn refresh_tray_menu(app: AppHandle) {
+    tray::refresh(&app);
+}
+
+// 设置托盘图标的关注状态（后台标签闪烁/请求关注时切换为红色角标）
+#[tauri::command]
+fn set_tray_attention(app: AppHandle, attention: bool) {
+    tray::set_attention(&app, attention);
+}
+
+// 获取焦点保护配置
+#[tauri::command]
+fn get_focus_guard() -> focus_guard::FocusGuardConfig {
+    focus_guard::config()
+}
+
+// 开启/关闭焦点保护（阻止嵌入应用在后台抢占前台焦点）
+#[tauri::command]
+fn set_focus_guard(app: AppHandle, enabled: bool) {
+    focus_guard::set_enabled(enabled);
+    tray::refresh(&app);
+}
+
+// 重命名标签，label 为空时恢复显示窗口标题
+// 名称按 EXE + 嵌入时的标题保存，重启后再次嵌入相同的窗口时恢复
+#[tauri::command]
+fn set_tab_label(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize, label: String) -> Result<(), String> {
+    let label = label.trim();
+    let label = (!label.is_empty()).then(|| label.to_string());
+    let previous = manager.set_label(target_hwnd, label.clone()).ok_or("窗口未嵌入")?;
+    tab_labels::set(previous.exe_path.as_deref(), &previous.title, previous.label.as_deref(), label.as_deref());
+
+    tray::refresh(&app);
+    let _ = app.emit("tab-label-changed", tab_labels::TabLabelChanged { hwnd: target_hwnd, label });
+    Ok(())
+}
+
+// 设置标签分组，group 为空时移出分组
+#[tauri::command]
+fn set_tab_group(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize, group: String) -> Result<(), String> {
+    let group = group.trim();
+    let group = (!group.is_empty()).then(|| group.to_string());
+    let window = manager.set_group(target_hwnd, group).ok_or("窗口未嵌入")?;
+    let _ = app.emit("tab-tags-changed", TabTags::from(&window));
+    Ok(())
+}
+
+// 设置标签颜色标记，color 为 None 时清除
+#[tauri::command]
+fn set_tab_color(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize, color: Option<TabColor>) -> Result<(), String> {
+    let window = manager.set_color(target_hwnd, color).ok_or("窗口未嵌入")?;
+    let _ = app.emit("tab-tags-changed", TabTags::from(&window));
+    Ok(())
+}
+
+/// 给标签绑定全局快捷键，按下后切换到该标签 (accelerator 为空时解除)
+#[tauri::command]
+fn bind_hotkey_to_tab(app: AppHandle, manager: State<'_, WindowManager>, accelerator: String, tab_id: u64) -> Result<(), String> {
+    tab_hotkeys::bind(&app, &manager, tab_id, &accelerator).map(|_| ())
+}
+
+/// 检查更新 (发布通道和更新地址见设置 updates)，有新版本时同时发送 update-available
+#[tauri::command]
+async fn check_for_updates(app: AppHandle) -> Result<Option<updater::UpdateInfo>, String> {
+    updater::check(&app).await
+}
+
+/// 下载并安装新版本，安装前释放所有嵌入窗口，完成后重启
+#[tauri::command]
+async fn install_update(app: AppHandle) -> Result<(), String> {
+    if kiosk::is_enabled() {
+        return Err("展台模式下不能安装更新".to_string());
+    }
+    updater::install(&app).await
+}
+
+/// 设置标签的应用崩溃后是否自动重新启动并嵌入 (随工作区保存)
+#[tauri::command]
+fn set_tab_auto_restart(manager: State<'_, WindowManager>, tab_id: u64, enabled: bool) -> Result<(), String> {
+    let window = manager.find_tab(tab_id).ok_or("标签不存在")?;
+    manager.set_auto_restart(window.hwnd, enabled);
+    info!(target: "lifecycle", "标签 {} 自动重启: {}", tab_id, enabled);
+    Ok(())
+}
+
+/// 锁定标签的输入：画面仍然实时显示，但鼠标和键盘不会到达应用 (只看不点的仪表盘等)
+#[tauri::command]
+fn lock_tab_input(app: AppHandle, manager: State<'_, WindowManager>, hwnd: isize, locked: bool) -> Result<(), String> {
+    manager.get(hwnd).ok_or("标签不存在")?;
+    if locked {
+        if !input_lock::lock(&app, hwnd, input_lock::LockOwner::User) {
+            return Err("当前平台不支持锁定标签的输入".to_string());
+        }
+    } else {
+        // 演示模式或专注时段还在锁定时保持锁定
+        let remaining = input_lock::unlock(&app, hwnd, input_lock::LockOwner::User);
+        if !remaining.is_empty() {
+            return Err(input_lock::held_error(&remaining));
+        }
+    }
+    info!(target: "embed", "标签 {} 锁定输入: {}", hwnd, locked);
+    Ok(())
+}
+
+/// 标签显示时是否排除在屏幕共享和截图之外 (显示期间整个主窗口都不出现在捕获中)
+#[tauri::command]
+fn set_tab_capture_excluded(app: AppHandle, manager: State<'_, WindowManager>, hwnd: isize, excluded: bool) -> Result<(), String> {
+    privacy::set_tab_excluded(&app, &manager, hwnd, excluded)
+}
+
+/// 嵌入期间保留标签的分层透明效果 (半透明便签等小工具)，关闭时去掉透明作为普通窗口显示
+/// 返回结果类型，PerPixel / Unsupported 表示透明效果可能无法正常显示，由前端提示用户
+#[tauri::command]
+fn set_tab_transparency(manager: State<'_, WindowManager>, hwnd: isize, preserve: bool) -> Result<platform::TransparencyResult, String> {
+    apply_transparency(&manager, hwnd, preserve)
+}
+
+// 设置标签的分层透明并记录结果，嵌入、重新嵌入 (重启标签、恢复工作区) 时按记录的选项再次应用
+// 修改样式要等目标处理消息，在窗口操作线程上执行
+pub(crate) fn apply_transparency(manager: &WindowManager, hwnd: isize, preserve: bool) -> Result<platform::TransparencyResult, String> {
+    let window = manager.get(hwnd).ok_or("标签不存在")?;
+    let result = window_ops::call("set_layered", move || platform::current().set_layered(hwnd, &window, preserve))?;
+    let preserved = matches!(result, platform::TransparencyResult::Preserved | platform::TransparencyResult::PerPixel);
+    manager.set_preserve_transparency(hwnd, preserve && preserved);
+    match result {
+        platform::TransparencyResult::PerPixel | platform::TransparencyResult::Unsupported => {
+            warn!(target: "embed", "标签 {} 的透明效果可能无法保留: {:?}", hwnd, result)
+        }
+        _ => info!(target: "embed", "标签 {} 保留透明: {} ({:?})", hwnd, preserve, result),
+    }
+    Ok(result)
+}
+
+/// 开始把标签的画面录制为 MP4 (只录这个标签，不录整个桌面)
+#[tauri::command]
+fn start_tab_recording(app: AppHandle, manager: State<'_, WindowManager>, hwnd: isize, path: String) -> Result<(), String> {
+    manager.get(hwnd).ok_or("标签不存在")?;
+    tab_recording::start(&app, hwnd, &path)
+}
+
+/// 结束录制，等待视频文件写完后返回
+#[tauri::command]
+async fn stop_tab_recording() -> Result<tab_recording::RecordingSummary, String> {
+    tauri::async_runtime::spawn_blocking(tab_recording::stop).await.map_err(|e| e.to_string())?
+}
+
+/// 识别嵌入窗口中的文字，region 为相对于窗口左上角的区域 (不传时识别整个窗口)
+#[tauri::command]
+async fn ocr_window(hwnd: isize, region: Option<window_manager::WindowRect>) -> Result<ocr::OcrText, String> {
+    tauri::async_runtime::spawn_blocking(move || ocr::recognize(hwnd, region))
+        .await
+        .map_err(|e| format!("文字识别中断: {}", e))?
+}
+
+/// 显示系统通知 (主窗口隐藏在托盘中时也能看到)，返回通知 ID，点击后发送 toast-activated
+#[tauri::command]
+fn notify(app: AppHandle, title: String, body: String, actions: Option<Vec<toast::ToastAction>>) -> Result<String, String> {
+    toast::notify(&app, &title, &body, &actions.unwrap_or_default())
+}
+
+/// 切换到第 index 个标签 (从 1 开始，不限于快捷键能直接切换的前 9 个)
+#[tauri::command]
+fn activate_tab_by_index(app: AppHandle, index: u32) -> Result<(), String> {
+    tab_numbers::activate(&app, index)
+}
+
+// 按下了标签的快捷键：显示主窗口并切换到该标签
+fn activate_tab_by_hotkey(app: &AppHandle, tab: isize) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    debug!(target: "shortcuts", "发送事件: tab-hotkey-pressed({})", tab);
+    let _ = app.emit("tab-hotkey-pressed", tab);
+}
+
+// 应用重启后重新嵌入：等待与标签身份匹配的新窗口并嵌入到同一位置，relaunch 时先重新启动应用
+// 展台模式下应用退出后总是重新启动
+// 结果通过 window-reattached / reattach-failed 事件通知
+#[tauri::command]
+fn reattach_window(app: AppHandle, tab_id: u64, relaunch: bool) -> Result<(), String> {
+    reattach::start(&app, tab_id, relaunch || kiosk::is_enabled())
+}
+
+// 重启标签中的应用 (关闭后重新启动并嵌入到同一位置)，force 时应用拒绝关闭则强制结束
+// 返回操作 ID，关闭的结果通过 operation-finished 送达，重新嵌入的结果同样通过 window-reattached / reattach-failed 事件通知
+#[tauri::command]
+fn restart_tab(app: AppHandle, target_hwnd: isize, force: bool) -> u64 {
+    let handle = app.clone();
+    operations::start(&app, "restart_tab", true, |_| async move { reattach::restart(&handle, target_hwnd, force).await })
+}
+
+// 监视应用：该程序之后打开的新窗口自动嵌入，返回新的监视列表
+#[tauri::command]
+fn watch_app(exe_path: String) -> Result<Vec<String>, String> {
+    app_watch::watch(&exe_path)
+}
+
+#[tauri::command]
+fn unwatch_app(exe_path: String) -> Vec<String> {
+    app_watch::unwatch(&exe_path)
+}
+
+#[tauri::command]
+fn get_watched_apps() -> Vec<String> {
+    app_watch::list()
+}
+
+/// 用窗口测试一条规则 (规则不需要保存)，返回是否匹配和条件中使用的窗口属性
+#[tauri::command]
+fn test_rule(rule: rules::Rule, target_hwnd: isize) -> Result<rules::RuleTest, String> {
+    rules::test(platform::current(), &rule, target_hwnd)
+}
+
+// 规则文件中匹配该窗口的规则名称
+#[tauri::command]
+fn list_matched_rules(target_hwnd: isize) -> Vec<String> {
+    rules::matched(platform::current(), target_hwnd)
+}
+
+// 标签按最近使用排序 (当前标签在前)
+#[tauri::command]
+fn get_tab_mru(manager: State<'_, WindowManager>) -> Vec<isize> {
+    lifecycle::mru(&manager.hwnds())
+}
+
+// 快速切换器当前的标签列表和选中项 (切换器页面加载时获取)，未打开时为 None
+#[tauri::command]
+fn get_switcher_state() -> Option<switcher::SwitcherState> {
+    switcher::state()
+}
+
+// 在快速切换器中点击标签
+#[tauri::command]
+fn switcher_activate(app: AppHandle, target_hwnd: isize) {
+    switcher::commit(&app, Some(target_hwnd));
+}
+
+// 关闭快速切换器，不切换标签
+#[tauri::command]
+fn switcher_cancel(app: AppHandle) {
+    switcher::cancel(&app);
+}
+
+// 单独开启/关闭某个标签所属应用的焦点保护
+#[tauri::command]
+fn set_tab_focus_guard(app: AppHandle, target_hwnd: isize, enabled: bool) -> Result<(), String> {
+    focus_guard::set_tab_guarded(target_hwnd, enabled)?;
+    tray::refresh(&app);
+    Ok(())
+}
+
+// 把拖放到主窗口的文件转发给嵌入窗口 (模拟 WM_DROPFILES)
+// x, y 为主窗口客户区坐标 (物理像素)，与 update_window_rect 一致
+// 注意：只对调用过 DragAcceptFiles 的窗口有效，纯 OLE 拖放 (IDropTarget) 的应用收不到
+#[tauri::command]
+fn forward_file_drop(target_hwnd: isize, paths: Vec<String>, x: i32, y: i32) -> Result<bool, String> {
     #[cfg(windows)]
     unsafe {
         let hwnd = HWND(target_hwnd as *mut _);
-        ShowWindow(hwnd, SW_SHOW);
-        true
+        if !IsWindow(hwnd).as_bool() {
+            return Err("无效的窗口句柄".to_string());
+        }
+        if paths.is_empty() {
+            return Ok(false);
+        }
+
+        // 主窗口客户区坐标 -> 屏幕坐标
+        let mut screen_pt = POINT { x, y };
+        if let Ok(parent) = GetParent(hwnd) {
+            let _ = ClientToScreen(parent, &mut screen_pt);
+        }
+
+        // 找到落点下接受文件拖放的控件，没有则发给嵌入窗口本身
+        let mut drop_target = hwnd;
+        let mut current = WindowFromPoint(screen_pt);
+        while !current.is_invalid() && (current == hwnd || IsChild(hwnd, current).as_bool()) {
+            let ex_style = platform::win32::WindowStyle::ex_style(current).get().unwrap_or(0);
+            if ex_style & WS_EX_ACCEPTFILES.0 != 0 {
+                drop_target = current;
+                break;
+            }
+            if current == hwnd { break; }
+            current = GetParent(current).unwrap_or_default();
+        }
+
+        let mut client_pt = screen_pt;
+        let _ = ScreenToClient(drop_target, &mut client_pt);
+
+        // DROPFILES 头 + 双 \0 结尾的 UTF-16 路径列表
+        let mut file_list: Vec<u16> = Vec::new();
+        for path in &paths {
+            file_list.extend(path.encode_utf16());
+            file_list.push(0);
+        }
+        file_list.push(0);
+
+        let header_size = std::mem::size_of::<DROPFILES>();
+        let total_size = header_size + file_list.len() * 2;
+        let hglobal = GlobalAlloc(GHND, total_size).map_err(|e| format!("分配内存失败: {}", e))?;
+        let ptr = GlobalLock(hglobal) as *mut u8;
+        if ptr.is_null() {
+            let _ = GlobalFree(hglobal);
+            return Err("锁定内存失败".to_string());
+        }
+        let header = DROPFILES {
+            pFiles: header_size as u32,
+            pt: client_pt,
+            fNC: BOOL(0),
+            fWide: TRUE,
+        };
+        std::ptr::write_unaligned(ptr as *mut DROPFILES, header);
+        std::ptr::copy_nonoverlapping(file_list.as_ptr() as *const u8, ptr.add(header_size), file_list.len() * 2);
+        let _ = GlobalUnlock(hglobal);
+
+        // 投递成功后由目标窗口调用 DragFinish 释放内存
+        if PostMessageW(drop_target, WM_DROPFILES, WPARAM(hglobal.0 as usize), LPARAM(0)).is_err() {
+            let _ = GlobalFree(hglobal);
+            return Err("投递 WM_DROPFILES 失败".to_string());
+        }
+
+        info!(target: "drop", "转发 {} 个文件到 hwnd={} ({}, {})", paths.len(), drop_target.0 as isize, client_pt.x, client_pt.y);
+        Ok(true)
     }
     #[cfg(not(windows))]
-    false
+    Err("仅支持 Windows".to_string())
+}
+
+/// 显示标签并隐藏其他标签，把焦点交给它
+#[tauri::command]
+fn show_tab(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize) -> Result<bool, String> {
+    if manager.get(target_hwnd).is_none() {
+        return Err("标签不存在".to_string());
+    }
+    presentation::ensure_allows(target_hwnd)?;
+    // 切换到其他标签时结束临时最大化，原标签回到内容区 (或停靠的区域)
+    if let Some(restored) = zones::end_maximize_except(target_hwnd) {
+        relayout(&app);
+        let _ = app.emit("tab-fullscreen-changed", TabFullscreen { hwnd: restored, fullscreen: false });
+    }
+    let platform = platform::current();
+    tab_suspend::resume(target_hwnd);
+    tab_view::show(platform, &manager.hwnds(), target_hwnd);
+    idle::touch(target_hwnd);
+    // 镜像模式下激活会把原窗口带到 WindowHub 前面
+    if platform.embed_mode() != platform::EmbedMode::Reparent {
+        return Ok(true);
+    }
+    let activated = activate_window(target_hwnd, None)?;
+    // 网格中的标签在切换走期间可能因为其他标签加入或移出而没有重新排列
+    if grid::contains(target_hwnd) {
+        relayout(&app);
+    }
+    Ok(activated)
+}
+
+/// 各标签距离最后一次输入的秒数 (前端把长时间未使用的标签变暗)
+#[tauri::command]
+fn get_tab_idle_times(manager: State<'_, WindowManager>) -> Vec<idle::TabIdle> {
+    let tabs: Vec<(isize, u64)> = manager.list().iter().map(|w| (w.hwnd, w.embedded_at)).collect();
+    idle::idle_times(&tabs)
+}
+
+/// 隐藏标签 (打开搜索、工作区等面板时)，焦点交给主窗口
+#[tauri::command]
+fn hide_tab(app: AppHandle, target_hwnd: isize) -> bool {
+    tab_view::hide(platform::current(), target_hwnd);
+    if let Some(main) = app.get_webview_window("main") {
+        let _ = main.set_focus();
+    }
+    true
+}
+
+// 隐藏嵌入窗口（搜索时使用）
+#[tauri::command]
+fn hide_window(target_hwnd: isize) -> bool {
+    platform::current().set_visible(target_hwnd, false)
+}
+
+// 显示嵌入窗口（搜索结束时使用）
+#[tauri::command]
+fn show_window(target_hwnd: isize) -> bool {
+    platform::current().set_visible(target_hwnd, true)
 }
 
 // 强制重绘窗口 (修复黑屏)
 #[tauri::command]
 fn force_repaint(target_hwnd: isize) -> bool {
+    platform::current().repaint(target_hwnd)
+}
+
+// 预览后台标签：临时把它提到子窗口顶层，不改变当前标签，也不抢焦点
+#[tauri::command]
+fn peek_window(manager: State<'_, WindowManager>, target_hwnd: isize) -> Result<bool, String> {
     #[cfg(windows)]
     unsafe {
         let hwnd = HWND(target_hwnd as *mut _);
-        if !IsWindow(hwnd).as_bool() { return false; }
-        
-        // 方法1: InvalidateRect
-        let _ = InvalidateRect(hwnd, None, true);
-        
-        // 方法2: RedrawWindow (更强力)
-        let flags = RDW_ERASE | RDW_FRAME | RDW_INVALIDATE | RDW_ALLCHILDREN | RDW_UPDATENOW | RDW_INTERNALPAINT;
-        let _ = RedrawWindow(hwnd, None, None, flags);
-        
-        true
+        if !IsWindow(hwnd).as_bool() {
+            return Ok(false);
+        }
+        let parent = GetParent(hwnd).map_err(|_| "窗口未嵌入".to_string())?;
+
+        // 找到当前显示在最上层的嵌入窗口 (即当前标签)
+        let embedded = manager.hwnds();
+        let mut top = GetWindow(parent, GW_CHILD).unwrap_or_default();
+        while !top.is_invalid() && !embedded.contains(&(top.0 as isize)) {
+            top = GetWindow(top, GW_HWNDNEXT).unwrap_or_default();
+        }
+
+        let mut peek = PEEK.lock().unwrap();
+        // 连续预览多个标签时，始终恢复到最初的当前标签
+        let restore_to = match *peek {
+            Some((_, restore_to)) => restore_to,
+            None => top.0 as isize,
+        };
+        if restore_to == target_hwnd {
+            return Ok(true);
+        }
+
+        // 后台标签可能还是旧尺寸，套用当前标签的位置和大小
+        let mut rect = RECT::default();
+        let restore_hwnd = HWND(restore_to as *mut _);
+        if IsWindow(restore_hwnd).as_bool() && GetWindowRect(restore_hwnd, &mut rect).is_ok() {
+            let mut top_left = POINT { x: rect.left, y: rect.top };
+            let _ = ScreenToClient(parent, &mut top_left);
+            let _ = SetWindowPos(hwnd, HWND_TOP, top_left.x, top_left.y, rect.right - rect.left, rect.bottom - rect.top, SWP_NOACTIVATE | SWP_SHOWWINDOW);
+        } else {
+            let _ = SetWindowPos(hwnd, HWND_TOP, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_SHOWWINDOW);
+        }
+        let _ = force_repaint(target_hwnd);
+
+        *peek = Some((target_hwnd, restore_to));
+        Ok(true)
     }
     #[cfg(not(windows))]
-    false
+    Err("仅支持 Windows".to_string())
+}
+
+// 结束预览，恢复当前标签到顶层
+#[tauri::command]
+fn end_peek() -> bool {
+    let Some((_, restore_to)) = PEEK.lock().unwrap().take() else { return false };
+    #[cfg(windows)]
+    unsafe {
+        let hwnd = HWND(restore_to as *mut _);
+        if IsWindow(hwnd).as_bool() {
+            let _ = SetWindowPos(hwnd, HWND_TOP, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_SHOWWINDOW);
+            let _ = force_repaint(restore_to);
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = restore_to;
+    true
 }
 
 
+/// 按 ordering (从上到下) 排列嵌入窗口的 Z 序，返回排列后的实际顺序
+#[tauri::command]
+fn set_child_zorder(app: AppHandle, manager: State<'_, WindowManager>, ordering: Vec<isize>) -> Result<Vec<isize>, String> {
+    child_order::set_order(get_main_window_hwnd(app), &ordering, &manager.hwnds())
+}
+
+/// 把嵌入窗口在其他嵌入窗口中上移一层，返回排列后的实际顺序
+#[tauri::command]
+fn bring_child_forward(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize) -> Result<Vec<isize>, String> {
+    child_order::bring_forward(get_main_window_hwnd(app), target_hwnd, &manager.hwnds())
+}
+
 // ============================================================
 // 新功能：枚举已安装应用 & 启动应用
 // ============================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AppInfo {
-    pub name: String,
-    pub path: String,  // .lnk 或 .exe 路径
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppInfo {
+    pub name: String,
+    pub path: String,  // .lnk 或 .exe 路径
+    #[serde(default)]
+    pub args: Vec<String>,  // 启动参数 (网页应用)
+}
+
+/// 重新扫描开始菜单 (搜索框使用 enumerate_installed_apps_cached)
+#[tauri::command]
+fn enumerate_installed_apps() -> Vec<AppInfo> {
+    app_index::with_web_apps(app_index::rescan())
+}
+
+/// 已安装的应用，直接返回索引中的结果，refresh 为 true 时重新扫描
+#[tauri::command]
+async fn enumerate_installed_apps_cached(refresh: bool) -> Result<Vec<AppInfo>, String> {
+    tauri::async_runtime::spawn_blocking(move || app_index::cached(refresh)).await.map_err(|e| e.to_string())
+}
+
+/// 启动应用，返回操作 ID，进度通过 launch-progress、新窗口的句柄通过 operation-finished 送达
+#[tauri::command]
+fn launch_app(app: AppHandle, path: String, args: Option<Vec<String>>) -> u64 {
+    operations::start(&app, "launch_app", true, |progress| async move {
+        launch_and_wait(&path, &args.unwrap_or_default(), &progress).await
+    })
+}
+
+/// 取消进行中的操作 (launch_app、request_close、restart_tab 返回的操作 ID)，操作已经结束时返回 false
+#[tauri::command]
+fn cancel_operation(app: AppHandle, op_id: u64) -> bool {
+    operations::cancel(&app, op_id)
+}
+
+#[tauri::command]
+fn list_operations() -> Vec<operations::OperationInfo> {
+    operations::list()
+}
+
+/// 把网址做成网页应用 (用浏览器的应用模式或指定配置打开)，保存后出现在应用搜索结果中
+#[tauri::command]
+fn create_web_app(url: String, browser: String, profile: Option<String>) -> Result<AppInfo, String> {
+    web_apps::create(&url, &browser, profile.as_deref())
+}
+
+#[tauri::command]
+fn remove_web_app(name: String) -> Vec<AppInfo> {
+    web_apps::remove(&name)
 }
 
+/// 在新的控制台窗口中执行搜索框的命令 ("> 命令")，返回操作 ID，同 launch_app
 #[tauri::command]
-fn enumerate_installed_apps() -> Vec<AppInfo> {
-    #[cfg(windows)]
-    {
-        use std::path::PathBuf;
-        let mut apps: Vec<AppInfo> = Vec::new();
-        
-        // 扫描开始菜单目录
-        let start_menu_paths = vec![
-            std::env::var("APPDATA").ok().map(|p| PathBuf::from(p).join("Microsoft\\Windows\\Start Menu\\Programs")),
-            std::env::var("ProgramData").ok().map(|p| PathBuf::from(p).join("Microsoft\\Windows\\Start Menu\\Programs")),
-        ];
-        
-        for path_opt in start_menu_paths {
-            if let Some(path) = path_opt {
-                if path.exists() {
-                    scan_shortcuts(&path, &mut apps);
-                }
-            }
+fn run_shell_command(app: AppHandle, command: String) -> u64 {
+    operations::start(&app, "launch_app", true, |progress| async move {
+        #[cfg(windows)]
+        let spawn = || {
+            palette::spawn_shell(&command)?;
+            info!(target: "launcher", "执行命令: {}", command);
+            Ok(())
+        };
+        #[cfg(not(windows))]
+        let spawn = || Err("仅支持 Windows".to_string());
+        wait_for_launch(&command, spawn, &progress).await
+    })
+}
+
+/// 按启动选项启动应用 (如覆盖旧程序的高 DPI 缩放)，返回操作 ID，同 launch_app
+#[tauri::command]
+fn launch_app_ex(app: AppHandle, path: String, args: Option<Vec<String>>, options: dpi_compat::LaunchOptions) -> u64 {
+    operations::start(&app, "launch_app", true, |progress| async move {
+        launch_and_wait_with(&path, &args.unwrap_or_default(), &options, &progress).await
+    })
+}
+
+// 启动 exe、lnk 或普通文件，不经过 cmd.exe (参数中的 & | 等会被 cmd 当作命令分隔符，可以注入命令)：
+// exe 直接创建进程，参数原样传给应用，兼容层通过环境变量 __COMPAT_LAYER 设置；
+// lnk 和普通文件交给 ShellExecuteW 用关联的程序打开 (兼容层只对 exe 生效)
+#[cfg(windows)]
+fn spawn_target(path: &str, args: &[String], compat_layer: Option<&str>) -> Result<(), String> {
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::UI::Shell::ShellExecuteW;
+
+    let is_exe = std::path::Path::new(path).extension().is_some_and(|e| e.eq_ignore_ascii_case("exe"));
+    if is_exe {
+        let mut command = std::process::Command::new(path);
+        command.args(args);
+        if let Some(layer) = compat_layer {
+            command.env("__COMPAT_LAYER", layer);
         }
-        
-        // 去重
-        apps.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-        apps.dedup_by(|a, b| a.name.to_lowercase() == b.name.to_lowercase());
-        
-        apps
+        return command.spawn().map(|_| ()).map_err(|e| e.to_string());
+    }
+    let parameters = args.iter().map(|arg| quote_arg(arg)).collect::<Vec<_>>().join(" ");
+    let result = unsafe { ShellExecuteW(HWND::default(), w!("open"), &HSTRING::from(path), &HSTRING::from(parameters), PCWSTR::null(), SW_SHOWNORMAL) };
+    // 返回值大于 32 表示成功，否则是错误码
+    let code = result.0 as isize;
+    if code > 32 {
+        Ok(())
+    } else {
+        Err(format!("无法打开文件 (错误码 {})", code))
     }
-    #[cfg(not(windows))]
-    Vec::new()
 }
 
-#[cfg(windows)]
-fn scan_shortcuts(dir: &std::path::Path, apps: &mut Vec<AppInfo>) {
-    use walkdir::WalkDir;
-    
-    for entry in WalkDir::new(dir).max_depth(3).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if let Some(ext) = path.extension() {
-            if ext == "lnk" || ext == "exe" {
-                if let Some(name) = path.file_stem() {
-                    let name_str = name.to_string_lossy().to_string();
-                    // 过滤掉一些不需要的项目
-                    if !name_str.contains("Uninstall") && !name_str.contains("卸载") {
-                        apps.push(AppInfo {
-                            name: name_str,
-                            path: path.to_string_lossy().to_string(),
-                        });
-                    }
-                }
+// 按 Windows 命令行的规则给参数加引号 (CommandLineToArgvW 能还原出原来的参数)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // 引号前的反斜杠加倍，引号本身转义
+                quoted.extend(std::iter::repeat('\\').take(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.extend(std::iter::repeat('\\').take(backslashes));
+                quoted.push(c);
+                backslashes = 0;
             }
         }
     }
+    // 结尾的反斜杠加倍，避免转义右引号
+    quoted.extend(std::iter::repeat('\\').take(backslashes * 2));
+    quoted.push('"');
+    quoted
 }
 
-#[tauri::command]
-async fn launch_app(path: String) -> Result<isize, String> {
+/// 启动应用 (exe、lnk 或普通文件)，等待并返回它打开的新窗口
+async fn launch_and_wait(path: &str, args: &[String], progress: &operations::Progress) -> Result<isize, String> {
+    launch_and_wait_with(path, args, &Default::default(), progress).await
+}
+
+async fn launch_and_wait_with(
+    path: &str,
+    args: &[String],
+    options: &dpi_compat::LaunchOptions,
+    progress: &operations::Progress,
+) -> Result<isize, String> {
+    // 支持 exe, lnk 以及普通文件(txt, ppt, etc)
+    #[cfg(windows)]
+    let spawn = || {
+        spawn_target(path, args, options.compat_layer())?;
+        info!(target: "launcher", "启动应用: {} {:?} {:?}", path, args, options.compat_layer());
+        Ok(())
+    };
+    #[cfg(not(windows))]
+    let spawn = || {
+        let _ = (args, options);
+        Err("仅支持 Windows".to_string())
+    };
+    wait_for_launch(path, spawn, progress).await
+}
+
+// 调用 spawn 启动进程，等待并返回它打开的新窗口；name 用于日志
+async fn wait_for_launch(name: &str, spawn: impl FnOnce() -> Result<(), String>, progress: &operations::Progress) -> Result<isize, String> {
     #[cfg(windows)]
     {
-        use std::process::Command;
         use std::time::Duration;
-        use std::os::windows::process::CommandExt; // 必须导入此 trait 才能使用 creation_flags
-        
+
         // 获取启动前的窗口列表
-        let before_windows: std::collections::HashSet<isize> = enumerate_windows()
-            .iter()
-            .map(|w| w.hwnd)
-            .collect();
+        let before = window_list::delta(0).await;
+        let before_windows: std::collections::HashSet<isize> = before.changed.iter().map(|w| w.hwnd).collect();
+        let mut token = before.token;
         
-        // 统一使用 start 命令启动，支持 exe, lnk 以及普通文件(txt, ppt, etc)
-        let result = Command::new("cmd")
-            .args(["/C", "start", "", &path])
-            .creation_flags(0x08000000) // CREATE_NO_WINDOW (防止闪烁黑框)
-            .spawn();
-        
-        if let Err(e) = result {
+        if let Err(e) = spawn() {
+            warn!(target: "launcher", "启动失败: {} - {}", name, e);
             return Err(format!("启动失败: {}", e));
         }
+        progress.stage("spawned");
         
         // 等待新窗口出现（最多等待 10 秒）
-        for _ in 0..100 {
+        // 先出现的是启动画面时继续等待同一进程的主窗口（最多 30 秒）
+        let platform = platform::current();
+        let mut splash: Option<(isize, u32)> = None;
+        progress.stage("waiting-for-window");
+        for i in 0..300 {
             tokio::time::sleep(Duration::from_millis(100)).await;
+            if splash.is_none() && i >= 100 {
+                break;
+            }
             
-            let current_windows = enumerate_windows();
-            for win in &current_windows {
-                if !before_windows.contains(&win.hwnd) {
+            // 只检查有变化的窗口 (启动画面本身也可能变成主窗口)
+            let delta = window_list::delta(token).await;
+            token = delta.token;
+            for win in &delta.changed {
+                if before_windows.contains(&win.hwnd) {
+                    continue;
+                }
+                let pid = platform.process_id(win.hwnd);
+                if splash.is_some_and(|(_, splash_pid)| splash_pid != pid) {
+                    continue;
+                }
+                if main_window::is_main(win.hwnd) {
                     // 找到新窗口！
+                    info!(target: "launcher", "检测到新窗口: hwnd={}, title={}", win.hwnd, win.title);
+                    progress.stage("found");
                     return Ok(win.hwnd);
                 }
+                if splash.is_none() {
+                    info!(target: "launcher", "检测到启动画面，等待主窗口: hwnd={}, title={}", win.hwnd, win.title);
+                    splash = Some((win.hwnd, pid));
+                }
             }
         }
         
+        // 一直没有出现主窗口时嵌入仍然存在的启动画面 (可能是判断有误的小窗口应用)
+        if let Some((hwnd, _)) = splash.filter(|(hwnd, _)| platform.is_window(*hwnd)) {
+            warn!(target: "launcher", "未检测到主窗口，使用最先出现的窗口: hwnd={}", hwnd);
+            progress.stage("found");
+            return Ok(hwnd);
+        }
+        warn!(target: "launcher", "未检测到新窗口: {}", name);
         Err("应用已启动，但未检测到新窗口".to_string())
     }
     #[cfg(not(windows))]
-    Err("仅支持 Windows".to_string())
+    {
+        let _ = (name, progress);
+        spawn().map(|_| 0)
+    }
 }
 
-// ============================================================
-// 工作区 (Workspace) 功能
-// ============================================================
+// 固定到编号的应用 (开启 shortcuts.pinned_slots 后 Alt+数字 切换或启动)
+#[tauri::command]
+fn get_pinned_apps() -> Vec<pinned_apps::PinnedApp> {
+    pinned_apps::list()
+}
 
-/// 通过窗口句柄获取进程的 EXE 路径
 #[tauri::command]
-fn get_process_path(target_hwnd: isize) -> Result<String, String> {
+fn pin_app_to_slot(n: u32, launch_spec: pinned_apps::LaunchSpec) -> Result<Vec<pinned_apps::PinnedApp>, String> {
+    pinned_apps::pin(n, launch_spec)
+}
+
+#[tauri::command]
+fn unpin_app_slot(n: u32) -> Vec<pinned_apps::PinnedApp> {
+    pinned_apps::unpin(n)
+}
+
+/// 固定标签：排在最前面，不能用 Ctrl+W 关闭，下次启动时恢复
+#[tauri::command]
+fn pin_tab(app: AppHandle, manager: State<'_, WindowManager>, hwnd: isize) -> Result<window_manager::EmbeddedWindow, String> {
+    pinned_tabs::set(&app, &manager, hwnd, true)
+}
+
+#[tauri::command]
+fn unpin_tab(app: AppHandle, manager: State<'_, WindowManager>, hwnd: isize) -> Result<window_manager::EmbeddedWindow, String> {
+    pinned_tabs::set(&app, &manager, hwnd, false)
+}
+
+// 设置标签缩放比例 (1.0 ~ 3.0，1.0 为取消缩放)
+#[tauri::command]
+fn set_tab_zoom(app: AppHandle, target_hwnd: isize, scale: f32) -> Result<(), String> {
+    zoom::set_scale(&app, target_hwnd, scale)
+}
+
+// 获取标签缩放比例
+#[tauri::command]
+fn get_tab_zoom(target_hwnd: isize) -> f32 {
+    zoom::scale_of(target_hwnd)
+}
+
+/// 向嵌入窗口发送按键序列 (格式见 keys.rs)，返回注入的事件数
+#[tauri::command]
+async fn send_keys(target_hwnd: isize, sequence: String) -> Result<u32, String> {
+    let steps = keys::parse_sequence(&sequence)?;
     #[cfg(windows)]
-    unsafe {
-        let hwnd = HWND(target_hwnd as *mut _);
-        if !IsWindow(hwnd).as_bool() {
+    {
+        if !activate_window(target_hwnd, None)? {
             return Err("无效的窗口句柄".to_string());
         }
-        
-        let mut pid: u32 = 0;
-        GetWindowThreadProcessId(hwnd, Some(&mut pid));
-        
-        if pid == 0 {
-            return Err("无法获取进程 ID".to_string());
-        }
-        
-        // 打开进程
-        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)
-            .map_err(|e| format!("无法打开进程: {}", e))?;
-        
-        // 获取路径
-        let mut buffer = [0u16; 1024];
-        let len = K32GetModuleFileNameExW(process, None, &mut buffer);
-        
-        if len == 0 {
-            return Err("无法获取进程路径".to_string());
+        // 等待焦点切换完成，否则前几个按键会落到主窗口
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let foreground = get_foreground_window();
+        let target = HWND(target_hwnd as *mut _);
+        let root = unsafe { GetAncestor(target, GA_ROOT) };
+        if foreground != target_hwnd && foreground != root.0 as isize {
+            return Err("目标窗口未能获得焦点".to_string());
         }
-        
-        let path = String::from_utf16_lossy(&buffer[..len as usize]);
-        Ok(path)
+
+        let sent = unsafe { keys::send(&steps)? };
+        info!(target: "keys", "发送按键到 hwnd={}: {} 个事件", target_hwnd, sent);
+        Ok(sent)
     }
     #[cfg(not(windows))]
-    Err("仅支持 Windows".to_string())
+    {
+        let _ = (target_hwnd, steps);
+        Err("仅支持 Windows".to_string())
+    }
 }
 
-/// 保存工作区
+// ============================================================
+// 按键宏
+// ============================================================
+
+/// 开始录制焦点在该标签中时的键盘输入
 #[tauri::command]
-fn save_workspace(name: String, hwnds: Vec<isize>) -> Result<(), String> {
-    let mut apps = Vec::new();
-    
-    for hwnd in hwnds {
-        if let Ok(path) = get_process_path(hwnd) {
-            if !apps.contains(&path) {
-                apps.push(path);
-            }
-        }
+fn start_recording_macro(app: AppHandle, manager: State<'_, WindowManager>, target_hwnd: isize) -> Result<(), String> {
+    let window = manager.get(target_hwnd).ok_or("标签不存在")?;
+    macros::start_recording(&app, target_hwnd, window.exe_path.as_deref())
+}
+
+/// 结束录制并保存宏
+#[tauri::command]
+fn stop_recording(app: AppHandle, name: Option<String>) -> Result<macros::Macro, String> {
+    macros::stop_recording(&app, name)
+}
+
+/// 在标签中回放宏，返回发送的按键数
+#[tauri::command]
+async fn play_macro(id: u64, target_hwnd: isize) -> Result<usize, String> {
+    tauri::async_runtime::spawn_blocking(move || macros::play(id, target_hwnd))
+        .await
+        .map_err(|e| format!("回放中断: {}", e))?
+}
+
+#[tauri::command]
+fn list_macros() -> Vec<macros::Macro> {
+    macros::list()
+}
+
+#[tauri::command]
+fn delete_macro(id: u64) -> Result<(), String> {
+    macros::delete(id)
+}
+
+// ============================================================
+// 剪贴板历史
+// ============================================================
+
+// 最近的剪贴板记录 (新的在前)，未启用时为空
+#[tauri::command]
+fn get_clipboard_history() -> Vec<clipboard::ClipboardEntry> {
+    clipboard::history()
+}
+
+/// 把剪贴板记录粘贴到嵌入窗口：放回剪贴板后激活标签并发送 Ctrl+V
+#[tauri::command]
+async fn paste_history_item(target_hwnd: isize, id: u64) -> Result<u32, String> {
+    clipboard::restore(id)?;
+    send_keys(target_hwnd, "{ctrl+v}".to_string()).await
+}
+
+// ============================================================
+// 日志
+// ============================================================
+
+/// 读取最近 n 行日志 (附到问题反馈中)
+#[tauri::command]
+fn get_recent_logs(n: usize) -> Result<Vec<String>, String> {
+    logging::recent_logs(n)
+}
+
+/// 设置日志级别 (off/error/warn/info/debug/trace)，指定 target 时只影响对应模块
+#[tauri::command]
+fn set_log_level(level: String, target: Option<String>) -> Result<(), String> {
+    logging::set_level(&level, target.as_deref())
+}
+
+// 获取应用设置
+#[tauri::command]
+fn get_settings() -> settings::Settings {
+    settings::get()
+}
+
+// 更新应用设置：校验后保存并通知前端
+#[tauri::command]
+fn update_settings(app: AppHandle, settings: settings::Settings) -> Result<settings::Settings, String> {
+    let previous = settings::update(settings)?;
+    Ok(on_settings_changed(&app, &previous))
+}
+
+// 主窗口的背景材质和圆角 (Windows 11)，保存到设置 appearance.backdrop / corners
+#[tauri::command]
+fn set_backdrop(app: AppHandle, kind: settings::Backdrop, corners: Option<settings::Corners>) -> Result<settings::Settings, String> {
+    let mut settings = settings::get();
+    settings.appearance.backdrop = kind;
+    if let Some(corners) = corners {
+        settings.appearance.corners = corners;
     }
-    
+    let previous = settings::update(settings)?;
+    Ok(on_settings_changed(&app, &previous))
+}
+
+// 自定义窗口边框 (appearance.custom_chrome)：在标签栏空白处按下时开始拖动主窗口
+#[tauri::command]
+fn begin_window_drag(app: AppHandle) {
+    chrome::begin_drag(&app);
+}
+
+// 自定义窗口边框的最小化/最大化/关闭按钮
+#[tauri::command]
+fn window_control(action: chrome::WindowControl) -> Result<(), String> {
+    chrome::control(action)
+}
+
+// 鼠标停在自定义边框的最大化按钮上时打开贴靠布局
+#[tauri::command]
+fn show_snap_layouts() {
+    chrome::show_snap_layouts();
+}
+
+// ============================================================
+// 远程标签 (实验性，见 remote_tabs.rs)
+// ============================================================
+
+/// 列出局域网中另一台电脑 (开启了 remote.enabled 的 WindowHub) 上可以查看的窗口
+#[tauri::command]
+async fn list_remote_windows(address: String, key: String, token: String) -> Result<Vec<remote_tabs::RemoteWindow>, String> {
+    tauri::async_runtime::spawn_blocking(move || remote_tabs::list_windows(&address, &key, &token))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 打开对方的窗口作为远程标签，画面通过 remote-tab-frame 事件发送 (令牌的权限见 permissions.rs)
+#[tauri::command]
+async fn open_remote_tab(app: AppHandle, address: String, key: String, hwnd: isize, token: String) -> Result<remote_tabs::RemoteTab, String> {
+    tauri::async_runtime::spawn_blocking(move || remote_tabs::open(&app, &address, &key, hwnd, &token))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 向远程标签转发点击或按键
+#[tauri::command]
+fn send_remote_input(id: u64, input: remote_tabs::RemoteInput) -> Result<(), String> {
+    remote_tabs::send_input(id, input)
+}
+
+#[tauri::command]
+fn close_remote_tab(id: u64) -> Result<(), String> {
+    remote_tabs::close(id)
+}
+
+#[tauri::command]
+fn list_remote_tabs() -> Vec<remote_tabs::RemoteTab> {
+    remote_tabs::list()
+}
+
+// ============================================================
+// 外部集成的令牌 (见 permissions.rs)
+// ============================================================
+
+/// 登记外部集成，返回的令牌只出现这一次
+#[tauri::command]
+fn create_api_client(name: String, capabilities: Vec<permissions::Capability>) -> Result<permissions::CreatedClient, String> {
+    permissions::create(&name, capabilities)
+}
+
+#[tauri::command]
+fn list_api_clients() -> Vec<permissions::ApiClient> {
+    permissions::list()
+}
+
+/// 作废令牌，返回是否存在
+#[tauri::command]
+fn revoke_token(id: u64) -> Result<bool, String> {
+    permissions::revoke(id)
+}
+
+/// 修改过权限的脚本和插件 (其余使用默认权限)
+#[tauri::command]
+fn list_capability_grants() -> Vec<permissions::Grant> {
+    permissions::grants()
+}
+
+#[tauri::command]
+fn set_capabilities(kind: permissions::GrantKind, name: String, capabilities: Vec<permissions::Capability>) -> Result<permissions::Grant, String> {
+    permissions::set_grant(kind, &name, capabilities)
+}
+
+// 设置变化后 (命令更新或配置文件被修改)：快捷键有变化时重新注册，并通知前端
+fn on_settings_changed(app: &AppHandle, previous: &settings::Settings) -> settings::Settings {
+    let current = settings::get();
+    if previous.shortcuts != current.shortcuts {
+        apply_shortcuts(app);
+    }
+    if previous.clipboard != current.clipboard {
+        clipboard::apply();
+    }
+    if previous.appearance != current.appearance {
+        native_tabs::apply(app);
+        // 原生标签栏自己处理中键
+        mouse_buttons::apply(app);
+        theme::apply_backdrop(app);
+        chrome::apply(app);
+    }
+    if previous.input != current.input {
+        input_bypass::apply(app);
+        hot_corners::apply(app);
+        gestures::apply(app);
+        mouse_buttons::apply(app);
+    }
+    if previous.startup.background_agent != current.startup.background_agent {
+        agent::apply();
+    }
+    if previous.remote != current.remote {
+        remote_tabs::apply();
+    }
+    let _ = app.emit("settings-changed", &current);
+    current
+}
+
+// ============================================================
+// 自动化脚本
+// ============================================================
+
+// 脚本目录中的脚本名称
+#[tauri::command]
+fn list_scripts() -> Vec<String> {
+    scripts::list()
+}
+
+/// 执行脚本，返回脚本最后一个表达式的值 (没有值时为空字符串)
+#[tauri::command]
+async fn run_script(app: AppHandle, name: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || scripts::run(&app, &name))
+        .await
+        .map_err(|e| format!("脚本执行中断: {}", e))?
+}
+
+/// 备份整个数据目录 (数据库、配置文件和日志) 到一个文件
+#[tauri::command]
+async fn backup_data(path: String) -> Result<backup::BackupInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || backup::backup(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 校验备份文件，下次启动时用它替换数据目录
+#[tauri::command]
+async fn restore_data(path: String) -> Result<backup::BackupInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || backup::restore(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 导出设置、快捷键、窗口规则、工作区和标签名称到一个 JSON 文件 (在其他电脑上导入)
+#[tauri::command]
+fn export_profile(path: String) -> Result<(), String> {
+    profile::export(&path)
+}
+
+/// 导入 export_profile 导出的文件，替换当前配置，返回变化的配置文件名
+#[tauri::command]
+fn import_profile(app: AppHandle, path: String) -> Result<Vec<String>, String> {
+    profile::import(&app, &path)
+}
+
+/// 从 PowerToys 导入 FancyZones 自定义布局和 PowerToys 工作区为工作区 (path 为空时读取 PowerToys 的默认位置)
+#[tauri::command]
+fn import_layouts(app: AppHandle, path: Option<String>) -> Result<layout_import::ImportReport, String> {
+    layout_import::import(&app, path.as_deref())
+}
+
+/// 系统主题 (深色/浅色) 和强调色，变化时发送 theme-changed
+#[tauri::command]
+fn get_system_theme() -> theme::SystemTheme {
+    theme::get()
+}
+
+/// 系统辅助功能设置 (高对比度、关闭动画、文本大小)，变化时发送 system-accessibility-changed
+#[tauri::command]
+fn get_system_accessibility_prefs() -> system_prefs::AccessibilityPrefs {
+    system_prefs::get()
+}
+
+/// 后端消息目录 (键和对应语言的文本)，前端据此翻译事件中的消息键
+#[tauri::command]
+fn get_messages(locale: String) -> Result<Vec<messages::MessageText>, String> {
+    messages::catalog(&locale)
+}
+
+/// 本地记录的嵌入/释放失败统计 (按程序、窗口类名和错误代码累计)，次数多的在前
+#[tauri::command]
+fn get_failure_stats() -> Vec<failure_stats::FailureStat> {
+    failure_stats::list()
+}
+
+#[tauri::command]
+fn clear_failure_stats() {
+    failure_stats::clear();
+}
+
+/// 压力测试 (QA 用，界面中没有入口)：反复嵌入和释放一个测试窗口 iterations 次，检查每一轮的状态和对象泄漏。
+/// behavior 可以让测试窗口绘制缓慢、未响应或拒绝关闭，验证超时和回滚。
+/// 只在 debug 构建或启用 stress-test 特性时可用
+#[tauri::command]
+async fn stress_test(app: AppHandle, iterations: u32, behavior: Option<test_window::Behavior>) -> Result<stress::StressReport, String> {
+    if !cfg!(any(debug_assertions, feature = "stress-test")) {
+        return Err("发布版本不提供压力测试 (构建时启用 stress-test 特性)".to_string());
+    }
+    let host = get_main_window_hwnd(app);
+    tauri::async_runtime::spawn_blocking(move || stress::run(host, iterations, behavior.unwrap_or_default()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 导出诊断信息 (嵌入窗口表、最近事件、快捷键、显示器布局等)，返回 JSON 文件路径
+#[tauri::command]
+fn export_diagnostics(app: AppHandle, manager: State<WindowManager>) -> Result<String, String> {
+    diagnostics::export(&app, &manager).map(|path| path.to_string_lossy().to_string())
+}
+
+// ============================================================
+// 工作区 (Workspace) 功能
+// ============================================================
+
+/// 通过窗口句柄获取进程的 EXE 路径
+#[tauri::command]
+fn get_process_path(target_hwnd: isize) -> Result<String, String> {
+    platform::current().process_path(target_hwnd)
+}
+
+/// 当前平台信息 (macOS 下标签页为镜像模式)
+#[tauri::command]
+fn get_platform_info() -> platform::PlatformInfo {
+    platform::info()
+}
+
+/// 保存工作区
+#[tauri::command]
+fn save_workspace(app: AppHandle, manager: State<'_, WindowManager>, name: String, hwnds: Vec<isize>) -> Result<(), String> {
+    let apps = workspace_apps(platform::current(), &hwnds);
     if apps.is_empty() {
         return Err("没有可保存的应用".to_string());
     }
+    let tabs = workspace_tabs(&manager, &hwnds);
     
     let mut workspaces = WORKSPACES.lock().unwrap();
     
     // 如果同名工作区已存在，更新它
-    if let Some(ws) = workspaces.iter_mut().find(|w| w.name == name) {
+    let split = zones::split();
+    let shortcuts = if let Some(ws) = workspaces.iter_mut().find(|w| w.name == name) {
         ws.apps = apps;
+        ws.tabs = tabs;
+        ws.split = split;
+        ws.shortcuts.clone()
     } else {
-        workspaces.push(Workspace { name, apps });
-    }
+        workspaces.push(Workspace { name: name.clone(), apps, tabs, do_not_disturb: false, split, media_target: None, shortcuts: None });
+        None
+    };
+    *CURRENT_WORKSPACE.lock().unwrap() = Some(name);
     
     // 持久化到文件
     save_workspaces_to_file(&workspaces);
-    
+    drop(workspaces);
+
+    // 保存的工作区成为当前工作区，快捷键换成它自己的 (没有设置时为设置中的快捷键)
+    if settings::set_workspace_shortcuts(shortcuts) {
+        apply_shortcuts(&app);
+    }
     Ok(())
 }
 
@@ -727,7 +2107,7 @@ fn get_workspaces() -> Vec<Workspace> {
 
 /// 删除工作区
 #[tauri::command]
-fn delete_workspace(name: String) -> Result<(), String> {
+fn delete_workspace(app: AppHandle, name: String) -> Result<(), String> {
     let mut workspaces = WORKSPACES.lock().unwrap();
     let len_before = workspaces.len();
     workspaces.retain(|w| w.name != name);
@@ -737,27 +2117,326 @@ fn delete_workspace(name: String) -> Result<(), String> {
     }
     
     save_workspaces_to_file(&workspaces);
+    drop(workspaces);
+
+    // 删除的是当前工作区时不再使用它的快捷键
+    let mut current = CURRENT_WORKSPACE.lock().unwrap();
+    if current.as_deref() == Some(name.as_str()) {
+        *current = None;
+        drop(current);
+        if settings::set_workspace_shortcuts(None) {
+            apply_shortcuts(&app);
+        }
+    }
     Ok(())
 }
 
-/// 恢复工作区 (返回需要启动的应用路径列表)
+/// 恢复工作区 (返回需要启动的应用路径列表)，同时按工作区设置开启或关闭免打扰、恢复分隔比例，
+/// 切换到工作区的快捷键和媒体键转发
 #[tauri::command]
-fn restore_workspace(name: String) -> Result<Vec<String>, String> {
+fn restore_workspace(app: AppHandle, name: String) -> Result<Vec<String>, String> {
     let workspaces = WORKSPACES.lock().unwrap();
     
     if let Some(ws) = workspaces.iter().find(|w| w.name == name) {
+        *PENDING_TABS.lock().unwrap() = ws.tabs.clone();
+        *CURRENT_WORKSPACE.lock().unwrap() = Some(name);
+        dnd::set(&app, ws.do_not_disturb);
+        let shortcuts_changed = settings::set_workspace_shortcuts(ws.shortcuts.clone());
+        if media_keys::set_target(ws.media_target.clone()) || shortcuts_changed {
+            info!(target: "shortcuts", "切换到工作区 {} 的快捷键", ws.name);
+            apply_shortcuts(&app);
+        }
+        if zones::split() != ws.split {
+            zones::set_split(ws.split);
+            relayout_docked(&app);
+            let _ = app.emit("split-ratios-changed", zones::split());
+        }
         Ok(ws.apps.clone())
     } else {
         Err("工作区不存在".to_string())
     }
 }
 
+/// 设置恢复工作区时是否开启免打扰
+#[tauri::command]
+fn set_workspace_do_not_disturb(name: String, enabled: bool) -> Result<(), String> {
+    let mut workspaces = WORKSPACES.lock().unwrap();
+    let ws = workspaces.iter_mut().find(|w| w.name == name).ok_or("工作区不存在")?;
+    ws.do_not_disturb = enabled;
+    save_workspaces_to_file(&workspaces);
+    Ok(())
+}
+
+/// 设置恢复工作区后接收媒体键的应用 (exe_path 为空时不转发)
+#[tauri::command]
+fn set_workspace_media_target(app: AppHandle, name: String, exe_path: Option<String>) -> Result<(), String> {
+    let mut workspaces = WORKSPACES.lock().unwrap();
+    let ws = workspaces.iter_mut().find(|w| w.name == name).ok_or("工作区不存在")?;
+    if exe_path.as_ref().is_some_and(|path| !ws.apps.iter().any(|a| a.eq_ignore_ascii_case(path))) {
+        return Err("应用不在工作区中".to_string());
+    }
+    ws.media_target = exe_path.clone();
+    save_workspaces_to_file(&workspaces);
+    drop(workspaces);
+    if CURRENT_WORKSPACE.lock().unwrap().as_deref() == Some(name.as_str()) && media_keys::set_target(exe_path) {
+        apply_shortcuts(&app);
+    }
+    Ok(())
+}
+
+/// 设置工作区自己的快捷键 (shortcuts 为空时恢复为使用设置中的快捷键)
+#[tauri::command]
+fn set_workspace_shortcuts(app: AppHandle, name: String, shortcuts: Option<settings::ShortcutSettings>) -> Result<(), String> {
+    if let Some(shortcuts) = &shortcuts {
+        settings::validate_shortcuts(shortcuts)?;
+    }
+    let mut workspaces = WORKSPACES.lock().unwrap();
+    let ws = workspaces.iter_mut().find(|w| w.name == name).ok_or("工作区不存在")?;
+    ws.shortcuts = shortcuts.clone();
+    save_workspaces_to_file(&workspaces);
+    drop(workspaces);
+    if CURRENT_WORKSPACE.lock().unwrap().as_deref() == Some(name.as_str()) && settings::set_workspace_shortcuts(shortcuts) {
+        apply_shortcuts(&app);
+    }
+    Ok(())
+}
+
+/// 当前生效的快捷键
+#[tauri::command]
+fn get_active_shortcuts() -> ActiveShortcuts {
+    let current = CURRENT_WORKSPACE.lock().unwrap().clone();
+    let workspace = current.filter(|name| WORKSPACES.lock().unwrap().iter().any(|w| w.name == *name && w.shortcuts.is_some()));
+    ActiveShortcuts { workspace, shortcuts: settings::shortcuts() }
+}
+
+/// 保存当前所有标签和分隔比例的快照
+#[tauri::command]
+fn snapshot_session(manager: State<'_, WindowManager>, label: String) -> Result<snapshots::SnapshotInfo, String> {
+    let label = label.trim();
+    let label = if label.is_empty() { "手动保存" } else { label };
+    snapshots::save(label, &snapshots::capture(&manager.list(), zones::split()))
+}
+
+/// 所有快照，从新到旧
+#[tauri::command]
+fn list_snapshots() -> Result<Vec<snapshots::SnapshotInfo>, String> {
+    snapshots::list()
+}
+
+/// 恢复快照：恢复分隔比例，已经打开的标签套用快照中的分组、颜色、快捷键和自动重启，
+/// 返回快照中当前没有嵌入、需要由前端启动的应用，启动后嵌入的窗口同样套用。恢复前先保存当前状态
+#[tauri::command]
+fn restore_snapshot(app: AppHandle, manager: State<'_, WindowManager>, id: u64) -> Result<Vec<AppInfo>, String> {
+    let snapshot = snapshots::load(id)?;
+    let windows = manager.list();
+    if !windows.is_empty() {
+        snapshots::save("恢复快照前", &snapshots::capture(&windows, zones::split()))?;
+    }
+    let (hwnds, open): (Vec<isize>, Vec<(String, Vec<String>)>) =
+        windows.into_iter().filter_map(|w| Some((w.hwnd, (w.exe_path?, w.args)))).unzip();
+    for (tab, found) in snapshot.tabs.iter().zip(snapshots::pair(&snapshot, &open)) {
+        if let Some(i) = found {
+            apply_snapshot_tab(&app, &manager, hwnds[i], tab);
+        }
+    }
+    let missing = snapshots::missing(&snapshot, &open);
+    *PENDING_TABS.lock().unwrap() = missing
+        .iter()
+        .map(|t| WorkspaceTab { exe_path: t.exe_path.clone(), group: t.group.clone(), color: t.color, hotkey: t.hotkey.clone(), auto_restart: t.auto_restart, preserve_transparency: false })
+        .collect();
+    if zones::split() != snapshot.split {
+        zones::set_split(snapshot.split);
+        relayout_docked(&app);
+        let _ = app.emit("split-ratios-changed", zones::split());
+    }
+    info!(target: "workspace", "恢复快照 {}: 需要启动 {} 个应用", id, missing.len());
+    Ok(missing
+        .into_iter()
+        .map(|t| AppInfo { name: t.title.clone(), path: t.exe_path.clone(), args: t.args.clone() })
+        .collect())
+}
+
+// 已经打开的标签套用快照中的属性
+fn apply_snapshot_tab(app: &AppHandle, manager: &WindowManager, hwnd: isize, tab: &snapshots::SnapshotTab) {
+    manager.set_group(hwnd, tab.group.clone());
+    manager.set_auto_restart(hwnd, tab.auto_restart);
+    let Some(window) = manager.set_color(hwnd, tab.color) else { return };
+    let _ = app.emit("tab-tags-changed", TabTags::from(&window));
+    if window.hotkey == tab.hotkey {
+        return;
+    }
+    // 空的快捷键解除绑定
+    if let Err(e) = tab_hotkeys::bind(app, manager, window.tab_id, tab.hotkey.as_deref().unwrap_or("")) {
+        warn!(target: "shortcuts", "恢复快照的标签快捷键失败: {}", e);
+    }
+}
+
+#[tauri::command]
+fn get_do_not_disturb() -> bool {
+    dnd::is_enabled()
+}
+
+/// 手动开启或关闭免打扰
+#[tauri::command]
+fn set_do_not_disturb(app: AppHandle, enabled: bool) {
+    dnd::set(&app, enabled);
+}
+
+#[tauri::command]
+fn get_privacy_mode() -> bool {
+    privacy::is_enabled()
+}
+
+/// 开启或关闭隐私模式 (隐藏窗口标题，主窗口不出现在屏幕共享和截图中)
+#[tauri::command]
+fn set_privacy_mode(app: AppHandle, enabled: bool) -> Result<(), String> {
+    privacy::set(&app, enabled)
+}
+
+/// 开启或关闭 Win32 调用跟踪 (调试嵌入问题)
+#[tauri::command]
+fn set_api_trace(enabled: bool) {
+    api_trace::set(enabled);
+}
+
+/// 跟踪记录的 Win32 调用 (从旧到新)，指定 hwnd 时只返回针对该窗口的
+#[tauri::command]
+fn get_api_trace(hwnd: Option<isize>) -> Vec<api_trace::ApiCall> {
+    api_trace::calls(hwnd)
+}
+
+/// WindowHub 自身的资源占用 (CPU、内存、句柄、钩子、队列积压) 和疑似泄漏的提示
+#[tauri::command]
+fn get_self_metrics() -> self_metrics::SelfMetrics {
+    self_metrics::collect()
+}
+
+/// 当前存在的钩子和定时器 (调试用)
+#[tauri::command]
+fn list_active_resources() -> Vec<resources::Resource> {
+    resources::list()
+}
+
+/// 标签、截图流和钩子的上限和当前用量
+#[tauri::command]
+fn get_resource_budget(manager: State<'_, WindowManager>) -> budget::ResourceBudget {
+    let platform = platform::current();
+    budget::get(manager.hwnds().into_iter().filter(|&hwnd| platform.is_window(hwnd)).count())
+}
+
+/// 各嵌入应用在时间范围内的使用时间
+#[tauri::command]
+fn get_activity_report(range: activity::ActivityRange) -> Result<Vec<activity::AppActivity>, String> {
+    activity::report(range)
+}
+
+/// 开始专注时段：allowed_tabs 以外的标签锁定输入、不提示关注，minutes 分钟后自动结束
+#[tauri::command]
+fn start_focus_session(app: AppHandle, minutes: u64, allowed_tabs: Vec<isize>) -> Result<focus_session::FocusSession, String> {
+    focus_session::start(&app, minutes, allowed_tabs)
+}
+
+/// 提前结束专注时段
+#[tauri::command]
+fn stop_focus_session(app: AppHandle) -> Result<(), String> {
+    if !focus_session::stop(&app, false) {
+        return Err("没有进行中的专注时段".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_focus_session() -> Option<focus_session::FocusSession> {
+    focus_session::current()
+}
+
+#[tauri::command]
+fn list_schedules() -> Vec<scheduler::Schedule> {
+    scheduler::list()
+}
+
+/// 添加定时任务：每天 (或 days 中的星期几) 的 time (HH:MM) 执行 action
+#[tauri::command]
+fn create_schedule(time: String, days: Vec<u8>, action: scheduler::ScheduledAction) -> Result<scheduler::Schedule, String> {
+    scheduler::create(time, days, action)
+}
+
+#[tauri::command]
+fn delete_schedule(id: u64) -> Result<(), String> {
+    scheduler::delete(id)
+}
+
+// 窗口对应的 EXE 路径 (按标签顺序去重，同一应用的多个窗口只启动一次)
+fn workspace_apps(platform: &dyn platform::Platform, hwnds: &[isize]) -> Vec<String> {
+    let mut apps = Vec::new();
+    for &hwnd in hwnds {
+        if let Ok(path) = platform.process_path(hwnd) {
+            if !apps.contains(&path) {
+                apps.push(path);
+            }
+        }
+    }
+    apps
+}
+
+// 标签的分组、颜色、快捷键、自动重启和保留透明 (按标签顺序，只保存设置过的标签)
+fn workspace_tabs(manager: &WindowManager, hwnds: &[isize]) -> Vec<WorkspaceTab> {
+    let windows = manager.list();
+    hwnds
+        .iter()
+        .filter_map(|hwnd| windows.iter().find(|w| w.hwnd == *hwnd))
+        .filter(|w| w.group.is_some() || w.color.is_some() || w.hotkey.is_some() || w.auto_restart || w.preserve_transparency)
+        .filter_map(|w| {
+            Some(WorkspaceTab {
+                exe_path: w.exe_path.clone()?,
+                group: w.group.clone(),
+                color: w.color,
+                hotkey: w.hotkey.clone(),
+                auto_restart: w.auto_restart,
+                preserve_transparency: w.preserve_transparency,
+            })
+        })
+        .collect()
+}
+
+// 恢复工作区时新嵌入的窗口取第一个同一应用的待恢复标签，应用其分组、颜色、快捷键、自动重启和保留透明
+fn apply_workspace_tab(app: &AppHandle, manager: &WindowManager, window: window_manager::EmbeddedWindow) -> window_manager::EmbeddedWindow {
+    let Some(exe_path) = window.exe_path.as_deref() else { return window };
+    let tab = {
+        let mut pending = PENDING_TABS.lock().unwrap();
+        let index = pending.iter().position(|t| t.exe_path.eq_ignore_ascii_case(exe_path));
+        index.map(|i| pending.remove(i))
+    };
+    let Some(tab) = tab else { return window };
+    manager.set_group(window.hwnd, tab.group);
+    manager.set_auto_restart(window.hwnd, tab.auto_restart);
+    manager.set_preserve_transparency(window.hwnd, tab.preserve_transparency);
+    let window = manager.set_color(window.hwnd, tab.color).unwrap_or(window);
+    let Some(hotkey) = tab.hotkey else { return window };
+    tab_hotkeys::bind(app, manager, window.tab_id, &hotkey).unwrap_or_else(|e| {
+        warn!(target: "shortcuts", "恢复工作区的标签快捷键失败: {}", e);
+        window
+    })
+}
+
+// 退出时自动保存快照 (没有标签时不保存)
+fn save_exit_snapshot(app: &AppHandle) {
+    if !settings::get().snapshots.on_exit {
+        return;
+    }
+    let windows = app.state::<WindowManager>().list();
+    if windows.is_empty() {
+        return;
+    }
+    if let Err(e) = snapshots::save("退出时", &snapshots::capture(&windows, zones::split())) {
+        warn!(target: "workspace", "退出时保存快照失败: {}", e);
+    }
+}
+
 // 持久化辅助函数
 fn save_workspaces_to_file(workspaces: &Vec<Workspace>) {
-    if let Ok(config_dir) = std::env::var("APPDATA") {
-        let path = std::path::Path::new(&config_dir)
-            .join("WindowHub")
-            .join("workspaces.json");
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("workspaces.json");
         
         if let Some(parent) = path.parent() {
             let _ = std::fs::create_dir_all(parent);
@@ -769,162 +2448,326 @@ fn save_workspaces_to_file(workspaces: &Vec<Workspace>) {
     }
 }
 
-fn load_workspaces_from_file() {
-    if let Ok(config_dir) = std::env::var("APPDATA") {
-        let path = std::path::Path::new(&config_dir)
-            .join("WindowHub")
-            .join("workspaces.json");
+// 返回工作区列表是否有变化
+fn load_workspaces_from_file() -> bool {
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("workspaces.json");
         
         if path.exists() {
             if let Ok(content) = std::fs::read_to_string(&path) {
                 if let Ok(loaded) = serde_json::from_str::<Vec<Workspace>>(&content) {
                     let mut workspaces = WORKSPACES.lock().unwrap();
+                    if *workspaces == loaded {
+                        return false;
+                    }
                     *workspaces = loaded;
-                    println!("[SETUP] 已加载 {} 个工作区", workspaces.len());
+                    info!(target: "workspace", "已加载 {} 个工作区", workspaces.len());
+                    return true;
                 }
             }
         }
     }
+    false
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileResult {
-    pub name: String,
-    pub path: String,
-    pub is_dir: bool,
+#[tauri::command]
+async fn search_files(query: String) -> Result<Vec<file_search::FileResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || file_search::search(&query, 20)).await.map_err(|e| e.to_string())
 }
 
+/// 搜索框的结果：计算结果、命令、系统操作、打开的窗口、应用和文件 (见 palette.rs)
 #[tauri::command]
-fn search_files(query: String) -> Vec<FileResult> {
-    #[cfg(windows)]
-    {
-         if query.trim().is_empty() { return Vec::new(); }
-         
-         use walkdir::WalkDir;
-         use std::path::PathBuf;
-         
-         let mut results = Vec::new();
-         let query_lower = query.to_lowercase();
-         
-         // 搜索路径: 桌面, 文档, 下载
-         let mut search_paths = Vec::new();
-         if let Ok(p) = std::env::var("USERPROFILE") {
-            let user_profile = PathBuf::from(p);
-            search_paths.push(user_profile.join("Desktop"));
-            search_paths.push(user_profile.join("Downloads"));
-         }
-         
-         for path in search_paths {
-             if !path.exists() { continue; }
-             
-             for entry in WalkDir::new(path).max_depth(2).into_iter().filter_map(|e| e.ok()) {
-                 let file_name = entry.file_name().to_string_lossy();
-                 if file_name.to_lowercase().contains(&query_lower) {
-                     results.push(FileResult {
-                         name: file_name.to_string(),
-                         path: entry.path().to_string_lossy().to_string(),
-                         is_dir: entry.file_type().is_dir(),
-                     });
-                     if results.len() >= 20 { break; }
-                 }
-             }
-             if results.len() >= 20 { break; }
-         }
-         
-         results
+async fn search_palette(manager: State<'_, WindowManager>, query: String) -> Result<Vec<palette::SearchResult>, String> {
+    let mut results = palette::quick_results(&query);
+    if !palette::searches_everything(&query) {
+        return Ok(results);
+    }
+    let platform = platform::current();
+    let windows: Vec<palette::OpenWindow> = if query.trim().is_empty() {
+        Vec::new()
+    } else {
+        window_list::current()
+            .await
+            .into_iter()
+            .filter(|w| manager.get(w.hwnd).is_none())
+            .map(|w| (w.hwnd, w.title, platform.process_path(w.hwnd).unwrap_or_default()))
+            .collect()
+    };
+    let file_query = query.clone();
+    let (apps, files, plugin_items) = tauri::async_runtime::spawn_blocking(move || {
+        let files = file_search::search(&file_query, palette::MAX_FILES);
+        (app_index::cached(false), files, plugins::search(&file_query))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    results.extend(palette::collect(&query, windows, apps, files, plugin_items));
+    Ok(results)
+}
+
+/// 执行插件的操作，插件要求打开程序或文件时返回它 (由前端启动并嵌入)
+#[tauri::command]
+async fn invoke_plugin_action(plugin: String, id: String) -> Result<Option<plugins::PluginOpen>, String> {
+    tauri::async_runtime::spawn_blocking(move || plugins::invoke(&plugin, &id)).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn list_plugins() -> Vec<plugins::PluginInfo> {
+    plugins::list()
+}
+
+/// 重新扫描插件目录 (%APPDATA%\WindowHub\plugins)
+#[tauri::command]
+fn reload_plugins() -> Vec<plugins::PluginInfo> {
+    plugins::load()
+}
+
+/// 执行搜索框中的系统操作 (锁定、睡眠、清空回收站)
+#[tauri::command]
+fn run_system_action(action: palette::SystemAction) -> Result<(), String> {
+    palette::run_system_action(action)
+}
+
+// ============================================================
+// 内置文件浏览 (代替嵌入资源管理器)
+// ============================================================
+
+// 列出目录内容，path 为空时为用户目录
+#[tauri::command]
+fn list_directory(path: String, show_hidden: Option<bool>) -> Result<file_browser::DirectoryListing, String> {
+    file_browser::list_directory(&path, show_hidden.unwrap_or(false))
+}
+
+// 用默认程序打开文件
+#[tauri::command]
+fn open_path(app: AppHandle, path: String) -> Result<(), String> {
+    file_browser::open_path(&app, &path)
+}
+
+#[tauri::command]
+fn file_operations(operation: file_browser::FileOperation) -> file_browser::FileOperationResult {
+    file_browser::file_operations(operation)
+}
+
+// 文件标签显示的目录变化时发送 directory-changed
+#[tauri::command]
+fn watch_directory(app: AppHandle, path: String) -> Result<(), String> {
+    file_browser::watch_directory(&app, &path)
+}
+
+#[tauri::command]
+fn unwatch_directory(path: String) {
+    file_browser::unwatch_directory(&path);
+}
+
+// 注册全局快捷键，结果写入日志并记录到诊断信息
+fn register_shortcut(app: &AppHandle, shortcut: &str) {
+    let result = keyboard_layout::parse(shortcut)
+        .and_then(|parsed| app.global_shortcut().register(parsed).map_err(|e| format!("{:?}", e)));
+    match &result {
+        Ok(_) => info!(target: "shortcuts", "注册成功: {}", shortcut),
+        Err(e) => warn!(target: "shortcuts", "注册失败: {} - {}", shortcut, e),
+    }
+    diagnostics::record_shortcut(shortcut, result);
+}
+
+// 按当前设置重新注册所有全局快捷键
+fn apply_shortcuts(app: &AppHandle) {
+    info!(target: "shortcuts", "开始注册全局快捷键...");
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        warn!(target: "shortcuts", "注销快捷键失败: {:?}", e);
+    }
+    for (accelerator, _) in settings::shortcuts().bindings() {
+        register_shortcut(app, &accelerator);
+    }
+    tab_hotkeys::register_all(app, &app.state::<WindowManager>());
+    media_keys::register_all(app);
+    info!(target: "shortcuts", "快捷键注册完成！");
+}
+
+fn handle_shortcut_action(app: &AppHandle, action: settings::ShortcutAction) {
+    use settings::ShortcutAction;
+
+    match action {
+        // 切换到指定标签 (或输入编号的一位)
+        ShortcutAction::SwitchTab(digit) => tab_numbers::on_digit(app, digit),
+        // 开始/取消输入标签编号
+        ShortcutAction::TabNumberChord => tab_numbers::on_chord(app),
+        // 关闭当前标签
+        ShortcutAction::CloseTab => {
+            // 固定的标签需要先取消固定才能关闭
+            let active = host_layout::active();
+            if app.state::<WindowManager>().get(active).is_some_and(|w| w.pinned) {
+                debug!(target: "shortcuts", "当前标签已固定，不关闭: hwnd={}", active);
+                return;
+            }
+            debug!(target: "shortcuts", "发送事件: close-current-tab");
+            let _ = app.emit("close-current-tab", ());
+        }
+        // 下一个/上一个标签，MRU 模式下按最近使用顺序 (载荷为目标标签)
+        ShortcutAction::NextTab | ShortcutAction::PrevTab => {
+            let forward = action == ShortcutAction::NextTab;
+            if settings::shortcuts().mru_cycling {
+                let order = lifecycle::mru(&window_manager::embedded_hwnds(app));
+                if let Some(target) = lifecycle::mru_target(&order, forward) {
+                    let event = if forward { "next-tab-mru" } else { "prev-tab-mru" };
+                    debug!(target: "shortcuts", "发送事件: {}({})", event, target);
+                    let _ = app.emit(event, target);
+                }
+            } else {
+                let event = if forward { "next-tab" } else { "prev-tab" };
+                debug!(target: "shortcuts", "发送事件: {}", event);
+                let _ = app.emit(event, ());
+            }
+        }
+        // 打开搜索
+        ShortcutAction::Search => {
+            debug!(target: "shortcuts", "发送事件: open-search");
+            let _ = app.emit("open-search", ());
+        }
+        // 弹出当前窗口 (Detach)
+        ShortcutAction::DetachTab => {
+            debug!(target: "shortcuts", "发送事件: detach-current-tab");
+            let _ = app.emit("detach-current-tab", ());
+        }
+        // 重启当前标签的应用
+        ShortcutAction::RestartTab => {
+            debug!(target: "shortcuts", "发送事件: restart-current-tab");
+            let _ = app.emit("restart-current-tab", ());
+        }
+        // 重新打开最近关闭的标签
+        ShortcutAction::ReopenTab => {
+            if let Err(e) = closed_tabs::reopen_last(app) {
+                debug!(target: "shortcuts", "{}", e);
+            }
+        }
+        // 退出应用
+        ShortcutAction::Quit => {
+            if kiosk::is_enabled() {
+                kiosk::request_pin(app, "quit");
+                return;
+            }
+            info!(target: "shortcuts", "退出应用");
+            app.exit(0);
+        }
+        // 显示/隐藏主窗口
+        ShortcutAction::ToggleWindow => {
+            // 老板键隐藏期间按切换窗口等于再按一次老板键
+            if boss_key::restore(app) {
+                return;
+            }
+            if let Some(window) = app.get_webview_window("main") {
+                // 展台模式下主窗口保持显示
+                if window.is_visible().unwrap_or(false) && !kiosk::is_enabled() {
+                    let _ = window.hide();
+                } else {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+        }
+        // 快速切换器 (打开或选中下一个)
+        ShortcutAction::QuickSwitcher => switcher::on_shortcut(app),
+        // 嵌入当前前台窗口
+        ShortcutAction::EmbedForeground => embed_foreground(app),
+        // 主窗口全屏 / 恢复
+        ShortcutAction::ToggleHostFullscreen => {
+            if let Err(e) = toggle_host_fullscreen(app.clone()) {
+                warn!(target: "shortcuts", "切换全屏失败: {}", e);
+            }
+        }
+        // 临时最大化当前标签 / 恢复布局
+        ShortcutAction::ToggleTabFullscreen => {
+            let tab = host_layout::active();
+            if tab != 0 {
+                if let Err(e) = toggle_tab_fullscreen(app.clone(), app.state(), tab) {
+                    warn!(target: "shortcuts", "最大化标签失败: {}", e);
+                }
+            }
+        }
+        // 老板键: 隐藏一切 / 恢复
+        ShortcutAction::BossKey => boss_key::toggle(app),
     }
-    #[cfg(not(windows))]
-    Vec::new()
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    paths::apply_webview_dir();
+    // 恢复备份要替换整个数据目录，在打开日志文件和数据库之前进行
+    let restored = backup::apply_pending();
+    logging::init();
+    if paths::is_portable() {
+        info!(target: "settings", "便携模式，数据目录: {:?}", paths::data_dir());
+    }
+    match restored {
+        Some(Ok(backup)) => info!(target: "settings", "已恢复备份 (创建于 {}，{} 个文件)", backup.created_at, backup.files.len()),
+        Some(Err(e)) => warn!(target: "settings", "恢复备份失败: {}", e),
+        None => {}
+    }
+    api_trace::init();
+    if std::env::args().any(|arg| arg == agent::AGENT_ARG) {
+        agent::run();
+        return;
+    }
+
     tauri::Builder::default()
+        .manage(WindowManager::default())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_global_shortcut::Builder::new().with_handler(|app, shortcut, event| {
             if event.state == ShortcutState::Pressed {
-                 let s = shortcut.to_string();
-                 println!("[HANDLER] 处理快捷键: {}", s);
-                 
-                 // Tauri v2 格式: alt+Digit1, control+KeyK, shift+control+Tab
-                 // 转换为小写进行匹配
-                 let s_lower = s.to_lowercase();
-                 
-                 // Alt+1~9: 切换到指定标签
-                 if s_lower.starts_with("alt+digit") {
-                     if let Some(c) = s_lower.chars().last() {
-                         if let Some(digit) = c.to_digit(10) {
-                             println!("[HANDLER] 发送事件: switch-tab({})", digit);
-                             let _ = app.emit("switch-tab", digit);
-                             return;
-                         }
-                     }
-                 }
-                 
-                 // Ctrl+W: 关闭当前标签
-                 if s_lower == "control+keyw" {
-                     println!("[HANDLER] 发送事件: close-current-tab");
-                     let _ = app.emit("close-current-tab", ());
-                     return;
-                 }
-                 
-                 // Ctrl+Tab: 下一个标签
-                 if s_lower == "control+tab" {
-                     println!("[HANDLER] 发送事件: next-tab");
-                     let _ = app.emit("next-tab", ());
-                     return;
-                 }
-                 
-                 // Ctrl+Shift+Tab: 上一个标签
-                 if s_lower == "shift+control+tab" || s_lower == "control+shift+tab" {
-                     println!("[HANDLER] 发送事件: prev-tab");
-                     let _ = app.emit("prev-tab", ());
-                     return;
-                 }
-                 
-                 // Ctrl+K: 打开搜索
-                 if s_lower == "control+keyk" {
-                     println!("[HANDLER] 发送事件: open-search");
-                     let _ = app.emit("open-search", ());
-                     return;
-                 }
-                 
-                 // Ctrl+D: 弹出当前窗口 (Detach)
-                 if s_lower == "control+keyd" {
-                     println!("[HANDLER] 发送事件: detach-current-tab");
-                     let _ = app.emit("detach-current-tab", ());
-                     return;
-                 }
-
-                 // Alt+Q: 退出应用
-                 if s_lower == "alt+keyq" {
-                     println!("[HANDLER] 退出应用");
-                     app.exit(0);
-                     return;
-                 }
-
-                 // Alt+Space: Toggle Window
-                 if s_lower == "alt+space" {
-                     if let Some(window) = app.get_webview_window("main") {
-                        if window.is_visible().unwrap_or(false) {
-                            let _ = window.hide();
-                        } else {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                     }
-                     return;
-                 }
-                 
-                 println!("[HANDLER] 未匹配的快捷键: {}", s);
+                debug!(target: "shortcuts", "处理快捷键: {}", shortcut);
+                if passthrough::intercept(app, shortcut) {
+                    return;
+                }
+                if let Some(tab) = tab_hotkeys::tab_for(&app.state::<WindowManager>().list(), shortcut) {
+                    activate_tab_by_hotkey(app, tab);
+                    return;
+                }
+                if media_keys::route(app, shortcut) {
+                    return;
+                }
+                match settings::action_for(shortcut) {
+                    Some(action) => handle_shortcut_action(app, action),
+                    None => warn!(target: "shortcuts", "未匹配的快捷键: {}", shortcut),
+                }
             }
         })
         .build())
         .invoke_handler(tauri::generate_handler![
             enumerate_windows,
+            enumerate_windows_grouped,
+            get_window_thumbnail,
+            enumerate_windows_delta,
+            find_windows,
+            get_window_details,
+            get_window_tree,
+            inspect_point,
+            get_compatibility,
+            embed_by_reference,
             embed_window,
+            start_capture_mode,
+            cancel_capture_mode,
+            pick_color,
             release_window,
+            release_to_monitor,
+            enumerate_monitors,
             update_window_rect,
+            set_embed_layout,
+            place_native_tab_strip,
+            get_snap_zone,
+            dock_tab,
+            toggle_tab_fullscreen,
+            embed_as_grid,
+            toggle_host_fullscreen,
+            enter_presentation_mode,
+            exit_presentation_mode,
+            get_presentation_mode,
+            enter_kiosk_mode,
+            exit_kiosk_mode,
+            get_kiosk_mode,
+            kiosk_quit,
+            get_split_ratios,
+            set_split_ratio,
             activate_window,
             get_foreground_window,
             get_window_title,
@@ -932,25 +2775,186 @@ pub fn run() {
             is_cursor_in_client_area,
             get_main_window_hwnd,
             close_target_window,
+            close_all_tabs,
+            reopen_last_tab,
+            list_closed_tabs,
+            request_close,
+            force_kill_window,
             is_window_valid,
             can_embed_window,
+            highlight_window,
+            get_problem_classes,
+            forget_problem_class,
+            dismiss_window,
+            ignore_window_pattern,
+            list_ignored_windows,
+            unignore_window_pattern,
+            show_tab,
+            hide_tab,
+            get_tab_idle_times,
             hide_window,
             show_window,
             enumerate_installed_apps,
+            enumerate_installed_apps_cached,
             launch_app,
+            launch_app_ex,
+            create_web_app,
+            remove_web_app,
+            cancel_operation,
+            list_operations,
             search_files,
+            search_palette,
+            run_system_action,
+            run_shell_command,
+            invoke_plugin_action,
+            list_plugins,
+            reload_plugins,
             force_repaint,
             get_process_path,
+            get_platform_info,
             save_workspace,
             get_workspaces,
             delete_workspace,
-            restore_workspace
+            restore_workspace,
+            set_workspace_do_not_disturb,
+            set_workspace_media_target,
+            set_workspace_shortcuts,
+            snapshot_session,
+            list_snapshots,
+            restore_snapshot,
+            get_active_shortcuts,
+            get_do_not_disturb,
+            set_do_not_disturb,
+            get_privacy_mode,
+            set_privacy_mode,
+            get_activity_report,
+            set_api_trace,
+            get_api_trace,
+            get_self_metrics,
+            list_active_resources,
+            get_resource_budget,
+            start_focus_session,
+            stop_focus_session,
+            get_focus_session,
+            list_schedules,
+            create_schedule,
+            delete_schedule,
+            set_tab_capture_excluded,
+            set_tab_transparency,
+            refresh_tray_menu,
+            set_tray_attention,
+            get_focus_guard,
+            set_focus_guard,
+            set_tab_focus_guard,
+            forward_file_drop,
+            send_keys,
+            start_recording_macro,
+            stop_recording,
+            play_macro,
+            list_macros,
+            delete_macro,
+            set_tab_zoom,
+            get_tab_zoom,
+            peek_window,
+            end_peek,
+            set_child_zorder,
+            bring_child_forward,
+            list_embedded_windows,
+            get_tab_states,
+            set_tab_label,
+            set_tab_group,
+            set_tab_color,
+            bind_hotkey_to_tab,
+            set_tab_auto_restart,
+            activate_tab_by_index,
+            notify,
+            check_for_updates,
+            install_update,
+            reattach_window,
+            restart_tab,
+            watch_app,
+            unwatch_app,
+            get_watched_apps,
+            test_rule,
+            list_matched_rules,
+            list_directory,
+            open_path,
+            file_operations,
+            watch_directory,
+            unwatch_directory,
+            get_clipboard_history,
+            paste_history_item,
+            get_tab_mru,
+            get_switcher_state,
+            switcher_activate,
+            switcher_cancel,
+            get_recent_logs,
+            set_log_level,
+            list_remote_windows,
+            open_remote_tab,
+            send_remote_input,
+            close_remote_tab,
+            list_remote_tabs,
+            create_api_client,
+            list_api_clients,
+            revoke_token,
+            list_capability_grants,
+            set_capabilities,
+            list_scripts,
+            run_script,
+            backup_data,
+            restore_data,
+            export_profile,
+            import_profile,
+            import_layouts,
+            export_diagnostics,
+            stress_test,
+            get_failure_stats,
+            get_messages,
+            get_system_accessibility_prefs,
+            get_system_theme,
+            set_backdrop,
+            begin_window_drag,
+            window_control,
+            show_snap_layouts,
+            get_pinned_apps,
+            pin_app_to_slot,
+            unpin_app_slot,
+            pin_tab,
+            unpin_tab,
+            lock_tab_input,
+            start_tab_recording,
+            stop_tab_recording,
+            ocr_window,
+            clear_failure_stats,
+            get_settings,
+            update_settings
         ])
         .on_window_event(|window, event| {
+            if let WindowEvent::Resized(size) = event {
+                if window.label() == "main" {
+                    host_layout::host_resized(size.width, size.height);
+                }
+                return;
+            }
             if let WindowEvent::CloseRequested { api, .. } = event {
+                // 快速切换器 (Alt+F4) 只隐藏，下次复用
+                if window.label() == switcher::WINDOW_LABEL {
+                    switcher::cancel(window.app_handle());
+                    api.prevent_close();
+                    return;
+                }
+
+                // 展台模式下不能关闭主窗口
+                if kiosk::is_enabled() {
+                    api.prevent_close();
+                    return;
+                }
+
                 // 关闭窗口时，同步释放所有嵌入窗口 (防止冻结)
-                #[cfg(windows)]
-                release_all_embedded_windows();
+                tab_suspend::resume_all();
+                window.state::<WindowManager>().release_all(platform::current());
+                host_placement::save(window.app_handle());
                 
                 window.hide().unwrap();
                 api.prevent_close();
@@ -959,109 +2963,266 @@ pub fn run() {
         .setup(|app| {
             #[cfg(desktop)]
             {
-                use tauri_plugin_global_shortcut::GlobalShortcutExt;
-                
-                // 加载已保存的工作区
-                load_workspaces_from_file();
-                
-                println!("[SETUP] 开始注册全局快捷键...");
-                
-                // Alt+1~9: 切换到指定标签
-                for i in 1..=9 {
-                    let shortcut = format!("Alt+{}", i);
-                    match app.global_shortcut().register(shortcut.as_str()) {
-                        Ok(_) => println!("[SETUP] ✅ 注册成功: {}", shortcut),
-                        Err(e) => println!("[SETUP] ❌ 注册失败: {} - {:?}", shortcut, e),
-                    }
-                }
-                
-                // Ctrl+W: 关闭当前标签
-                match app.global_shortcut().register("Ctrl+W") {
-                    Ok(_) => println!("[SETUP] ✅ 注册成功: Ctrl+W"),
-                    Err(e) => println!("[SETUP] ❌ 注册失败: Ctrl+W - {:?}", e),
-                }
-                
-                // Ctrl+Tab: 下一个标签
-                match app.global_shortcut().register("Ctrl+Tab") {
-                    Ok(_) => println!("[SETUP] ✅ 注册成功: Ctrl+Tab"),
-                    Err(e) => println!("[SETUP] ❌ 注册失败: Ctrl+Tab - {:?}", e),
-                }
-                
-                // Ctrl+Shift+Tab: 上一个标签
-                match app.global_shortcut().register("Ctrl+Shift+Tab") {
-                    Ok(_) => println!("[SETUP] ✅ 注册成功: Ctrl+Shift+Tab"),
-                    Err(e) => println!("[SETUP] ❌ 注册失败: Ctrl+Shift+Tab - {:?}", e),
-                }
-                
-                // Ctrl+K: 打开搜索
-                match app.global_shortcut().register("Ctrl+K") {
-                    Ok(_) => println!("[SETUP] ✅ 注册成功: Ctrl+K"),
-                    Err(e) => println!("[SETUP] ❌ 注册失败: Ctrl+K - {:?}", e),
-                }
+                resources::init();
 
-                // Ctrl+D: 弹出当前窗口
-                match app.global_shortcut().register("Ctrl+D") {
-                    Ok(_) => println!("[SETUP] ✅ 注册成功: Ctrl+D"),
-                    Err(e) => println!("[SETUP] ❌ 注册失败: Ctrl+D - {:?}", e),
-                }
+                // 程序自己维护的数据在数据库中，先打开数据库
+                storage::init(app.handle());
 
-                // Alt+Q: 退出应用
-                match app.global_shortcut().register("Alt+Q") {
-                    Ok(_) => println!("[SETUP] ✅ 注册成功: Alt+Q"),
-                    Err(e) => println!("[SETUP] ❌ 注册失败: Alt+Q - {:?}", e),
+                // 加载设置和已保存的工作区 (安全模式下使用默认设置，不加载规则和监视列表)
+                let safe_mode = safe_mode::detect();
+                if !safe_mode {
+                    settings::load_from_file();
                 }
-
-                // Alt+Space: Toggle
-                match app.global_shortcut().register("Alt+Space") {
-                    Ok(_) => println!("[SETUP] ✅ 注册成功: Alt+Space"),
-                    Err(e) => println!("[SETUP] ❌ 注册失败: Alt+Space - {:?}", e),
+                load_workspaces_from_file();
+                focus_guard::load_from_file();
+                tab_labels::load();
+                if !safe_mode {
+                    app_watch::load_from_file();
+                }
+                problem_classes::load();
+                ignored_windows::load();
+                compat::load_from_file();
+                pinned_apps::load();
+                pinned_tabs::load();
+                failure_stats::load();
+                if !safe_mode {
+                    rules::load_from_file();
+                    plugins::load();
                 }
+                kiosk::load_from_file();
+                macros::load_from_file();
                 
+                // 启动系统事件监听 (闪烁/对话框等关注请求)
+                lifecycle::init(app.handle());
+                privacy::init(app.handle());
+                crash_watch::init(app.handle());
+                tab_state::init(app.handle());
+                system_prefs::init(app.handle());
+                theme::init(app.handle(), get_main_window_hwnd(app.handle().clone()));
+                win_events::start(app.handle());
+                app_watch::start(app.handle());
+                app_index::start(app.handle());
+                keyboard_layout::start(app.handle());
+                title_watch::start(app.handle());
+                if !safe_mode {
+                    updater::check_on_startup(app.handle());
+                    // 主界面启动前后台代理发现的窗口
+                    agent::init();
+                    std::thread::spawn(agent::adopt_handoff);
+                }
+                fullscreen_guard::start(app.handle());
+                clipboard::init(app.handle());
+                remote_tabs::init(app.handle());
+                idle::start(get_main_window_hwnd(app.handle().clone()));
+                idle::start_auto_hide(app.handle());
+                activity::start(app.handle());
+                scheduler::start(app.handle());
+                tab_overlay::init(get_main_window_hwnd(app.handle().clone()));
+                notifications::start(app.handle());
+                #[cfg(target_os = "macos")]
+                platform::mirror::init(app.handle());
+
+                // 监听配置文件，手动修改后无需重启
+                config_watch::start(app.handle());
                 
-                println!("[SETUP] 快捷键注册完成！");
+                apply_shortcuts(app.handle());
 
                 // --- 托盘图标设置 ---
-                let quit_i = MenuItem::with_id(app, "quit", "退出 WindowHub", true, None::<&str>)?;
-                let show_i = MenuItem::with_id(app, "show", "显示主窗口", true, None::<&str>)?;
-                let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
+                let menu = tray::build_menu(app.handle())?;
 
-                let _ = TrayIconBuilder::new()
+                let _ = TrayIconBuilder::with_id(tray::TRAY_ID)
                     .icon(app.default_window_icon().unwrap().clone())
                     .menu(&menu)
                     .on_menu_event(|app, event| {
                         match event.id.as_ref() {
                             "quit" => {
+                                if kiosk::is_enabled() {
+                                    kiosk::request_pin(app, "quit");
+                                    return;
+                                }
                                 app.exit(0);
                             }
                             "show" => {
+                                boss_key::restore(app);
                                 if let Some(window) = app.get_webview_window("main") {
                                     let _ = window.show();
                                     let _ = window.set_focus();
                                 }
                             }
-                            _ => {}
+                            "focus-guard" => {
+                                focus_guard::set_enabled(!focus_guard::is_enabled());
+                                tray::refresh(app);
+                            }
+                            "privacy-mode" => {
+                                if let Err(e) = privacy::set(app, !privacy::is_enabled()) {
+                                    warn!(target: "tray", "切换隐私模式失败: {}", e);
+                                }
+                            }
+                            id => {
+                                tray::handle_tab_menu_event(app, id);
+                            }
                         }
                     })
                     .on_tray_icon_event(|tray, event| {
                         if let TrayIconEvent::Click { button: MouseButton::Left, .. } = event {
                             let app = tray.app_handle();
+                            if boss_key::restore(app) {
+                                return;
+                            }
                             if let Some(window) = app.get_webview_window("main") {
-                                if window.is_visible().unwrap_or(false) {
+                                if window.is_visible().unwrap_or(false) && !kiosk::is_enabled() {
                                     let _ = window.hide();
                                 } else {
                                     let _ = window.show();
                                     let _ = window.set_focus();
+                                    // 用户已经看到主窗口，清除关注角标
+                                    tray::set_attention(app, false);
                                 }
                             }
                         }
                     })
                     .build(app);
+                
+                tray::update_icon(app.handle());
+
+                // 启动时只保留托盘图标 (展台模式下总是全屏显示)
+                if settings::get().startup.start_hidden && !kiosk::is_enabled() {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.hide();
+                    }
+                }
+                if safe_mode {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.set_title("WindowHub (安全模式)");
+                    }
+                }
+                host_placement::restore(app.handle());
+                kiosk::restore(app.handle());
+                native_tabs::apply(app.handle());
+                chrome::apply(app.handle());
+                input_bypass::apply(app.handle());
+                hot_corners::apply(app.handle());
+                gestures::apply(app.handle());
+                mouse_buttons::apply(app.handle());
+
+                // 重新打开上次固定的标签 (安全模式下不启动其他应用)
+                if !safe_mode {
+                    pinned_tabs::restore(app.handle());
+                }
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // 托盘菜单、快捷键等退出时主窗口可能还显示着，记录它的位置
+            if let RunEvent::ExitRequested { .. } = event {
+                host_placement::save(app);
+                save_exit_snapshot(app);
+            }
+            // 退出时让持有钩子和定时器的后台线程先退出
+            if let RunEvent::Exit = event {
+                resources::release_all();
+            }
+        });
 }
 
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use platform::mock::MockPlatform;
+    use platform::Platform;
+
+    #[test]
+    fn quote_arg_keeps_shell_characters_literal() {
+        assert_eq!(quote_arg("--app=https://a.test/?x=1&y=2|z"), "--app=https://a.test/?x=1&y=2|z");
+        assert_eq!(quote_arg(""), r#""""#);
+        assert_eq!(quote_arg(r"C:\My Files\"), r#""C:\My Files\\""#);
+        assert_eq!(quote_arg(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(quote_arg(r"C:\a\b"), r"C:\a\b");
+    }
+
+    #[test]
+    fn workspace_apps_dedupes_in_tab_order() {
+        let platform = MockPlatform::new();
+        let code = platform.create_window("main.rs", "Chrome_WidgetWin_1", Some(r"C:\Apps\Code.exe"));
+        let term = platform.create_window("PowerShell", "CASCADIA_HOSTING_WINDOW_CLASS", Some(r"C:\Apps\wt.exe"));
+        let code2 = platform.create_window("lib.rs", "Chrome_WidgetWin_1", Some(r"C:\Apps\Code.exe"));
+
+        let apps = workspace_apps(&platform, &[term, code, code2]);
+        assert_eq!(apps, vec![r"C:\Apps\wt.exe".to_string(), r"C:\Apps\Code.exe".to_string()]);
+    }
+
+    #[test]
+    fn workspace_apps_skips_closed_windows() {
+        let platform = MockPlatform::new();
+        let closed = platform.create_window("计算器", "CalcFrame", Some(r"C:\Windows\calc.exe"));
+        let notepad = platform.create_window("记事本", "Notepad", Some(r"C:\Windows\notepad.exe"));
+        platform.close(closed);
+
+        assert_eq!(workspace_apps(&platform, &[closed, notepad]), vec![r"C:\Windows\notepad.exe".to_string()]);
+    }
+
+    #[test]
+    fn workspaces_survive_save_and_load() {
+        let workspaces = vec![
+            Workspace {
+                name: "开发".to_string(),
+                apps: vec![r"C:\Apps\Code.exe".to_string(), r"C:\Apps\wt.exe".to_string()],
+                tabs: vec![WorkspaceTab { exe_path: r"C:\Apps\wt.exe".to_string(), group: Some("后端".to_string()), color: Some(TabColor::Green), hotkey: None, auto_restart: false, preserve_transparency: false }],
+                do_not_disturb: true,
+                split: zones::SplitRatios { columns: 0.7, rows: 0.5 },
+                media_target: None,
+                shortcuts: Some(settings::ShortcutSettings { switch_tab_modifier: "Ctrl+Alt".to_string(), ..Default::default() }),
+            },
+            Workspace {
+                name: "写作".to_string(),
+                apps: vec![r"C:\Windows\notepad.exe".to_string()],
+                tabs: Vec::new(),
+                do_not_disturb: false,
+                split: zones::SplitRatios::default(),
+                media_target: Some(r"C:\Windows\notepad.exe".to_string()),
+                shortcuts: None,
+            },
+        ];
+        let json = serde_json::to_string_pretty(&workspaces).unwrap();
+        let loaded: Vec<Workspace> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, workspaces);
+    }
+
+    #[test]
+    fn workspaces_without_tabs_still_load() {
+        let loaded: Vec<Workspace> = serde_json::from_str(r#"[{"name":"旧","apps":["C:\\Apps\\wt.exe"]}]"#).unwrap();
+        assert!(loaded[0].tabs.is_empty());
+        assert!(!loaded[0].do_not_disturb);
+        assert_eq!(loaded[0].split, zones::SplitRatios::default());
+        assert_eq!(loaded[0].media_target, None);
+        assert_eq!(loaded[0].shortcuts, None);
+    }
+
+    #[test]
+    fn workspace_tabs_keep_group_and_color_in_tab_order() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let build = platform.create_window("build", "CASCADIA_HOSTING_WINDOW_CLASS", Some(r"C:\Apps\wt.exe"));
+        let plain = platform.create_window("记事本", "Notepad", Some(r"C:\Windows\notepad.exe"));
+        let code = platform.create_window("main.rs", "Chrome_WidgetWin_1", Some(r"C:\Apps\Code.exe"));
+        for hwnd in [build, plain, code] {
+            manager.embed(&platform, 0x10, hwnd).unwrap();
+        }
+        manager.set_group(build, Some("后端".to_string()));
+        manager.set_color(code, Some(TabColor::Blue));
+
+        let tabs = workspace_tabs(&manager, &[code, plain, build]);
+        assert_eq!(
+            tabs,
+            vec![
+                WorkspaceTab { exe_path: r"C:\Apps\Code.exe".to_string(), group: None, color: Some(TabColor::Blue), hotkey: None, auto_restart: false, preserve_transparency: false },
+                WorkspaceTab { exe_path: r"C:\Apps\wt.exe".to_string(), group: Some("后端".to_string()), color: None, hotkey: None, auto_restart: false, preserve_transparency: false },
+            ]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+// 窗口生命周期事件
+// 嵌入/释放/激活时向前端广播结构化事件，标签栏、托盘菜单等多个视图据此保持同步，
+// 不再依赖各个命令的返回值：
+// - window-embedded: EmbeddedWindow (嵌入时记录的完整信息)
+// - window-released: WindowReleased
+// - window-activated: WindowActivated (激活的标签变化时才发送)
+// - embed-failed: EmbedFailed
+// 事件同时交给自动化脚本 (scripts.rs) 中对应的 on_<事件名> 函数
+// 同时记录标签的激活历史，用于按最近使用顺序切换 (快速切换器、MRU 模式的 Ctrl+Tab)
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+
+use crate::scripts;
+use crate::tab_state::{self, TabState};
+use crate::window_manager::{EmbedError, EmbedErrorCode, EmbeddedWindow};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// 上一次激活的标签，重复激活同一标签 (调整大小、补焦点) 不再发送事件
+static LAST_ACTIVATED: AtomicIsize = AtomicIsize::new(0);
+
+// 激活历史，最近的在前，释放的标签会被移除
+static RECENT: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+/// 释放的原因
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseReason {
+    Detach, // 弹出为独立窗口
+    Close,  // 关闭标签并请求窗口关闭
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowReleased {
+    pub hwnd: isize,
+    pub reason: ReleaseReason,
+    pub window: Option<EmbeddedWindow>, // 嵌入时的记录，未被记录的窗口为 None
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowActivated {
+    pub hwnd: isize,
+    pub previous: isize, // 之前激活的标签，没有时为 0
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbedFailed {
+    pub hwnd: isize,
+    pub code: EmbedErrorCode,
+    pub key: &'static str, // 消息目录中的键，前端可以用 get_messages 的目录翻译
+    pub params: Vec<String>,
+    pub message: String,
+}
+
+/// 保存 AppHandle (setup 时调用一次)，之后任何线程都可以发送事件
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+fn emit<S: Serialize + Clone>(event: &str, payload: S) {
+    if let Some(app) = APP.get() {
+        scripts::dispatch(app, event, &payload);
+        let _ = app.emit(event, payload);
+    }
+    crate::native_tabs::refresh();
+}
+
+pub fn embedded(window: &EmbeddedWindow) {
+    let _ = tab_state::transition(window.tab_id, window.hwnd, TabState::Embedded);
+    emit("window-embedded", window.clone());
+}
+
+pub fn released(hwnd: isize, reason: ReleaseReason, window: Option<EmbeddedWindow>) {
+    RECENT.lock().unwrap().retain(|h| *h != hwnd);
+    let _ = LAST_ACTIVATED.compare_exchange(hwnd, 0, Ordering::SeqCst, Ordering::SeqCst);
+    if let Some(window) = &window {
+        let state = match reason {
+            ReleaseReason::Detach => TabState::Released,
+            ReleaseReason::Close => TabState::Closed,
+        };
+        let _ = tab_state::transition(window.tab_id, hwnd, state);
+    }
+    emit("window-released", WindowReleased { hwnd, reason, window });
+}
+
+pub fn activated(hwnd: isize, title: String) {
+    {
+        let mut recent = RECENT.lock().unwrap();
+        recent.retain(|h| *h != hwnd);
+        recent.insert(0, hwnd);
+    }
+    let previous = LAST_ACTIVATED.swap(hwnd, Ordering::SeqCst);
+    if previous != hwnd {
+        crate::accessibility::tab_activated(hwnd);
+        emit("window-activated", WindowActivated { hwnd, previous, title });
+    }
+}
+
+/// 标签按最近使用排序 (当前标签在前)，从未激活过的按原顺序排在后面
+pub fn mru(tabs: &[isize]) -> Vec<isize> {
+    order_by_recent(tabs, &RECENT.lock().unwrap())
+}
+
+fn order_by_recent(tabs: &[isize], recent: &[isize]) -> Vec<isize> {
+    let mut ordered: Vec<isize> = recent.iter().copied().filter(|h| tabs.contains(h)).collect();
+    ordered.extend(tabs.iter().copied().filter(|h| !recent.contains(h)));
+    ordered
+}
+
+/// 按最近使用顺序切换的目标 (order 为 mru 的结果)：向前 (下一个标签) 为上一次使用的标签 (连续按下在最近两个标签间来回)，
+/// 向后 (上一个标签) 为最久未使用的标签 (连续按下依次轮换所有标签)，不足两个标签时为 None
+/// 还没有激活记录时 order 就是标签栏的顺序，向前为第二个标签，向后为最后一个标签
+pub fn mru_target(order: &[isize], forward: bool) -> Option<isize> {
+    if order.len() < 2 {
+        return None;
+    }
+    if forward {
+        order.get(1).copied()
+    } else {
+        order.last().copied()
+    }
+}
+
+pub fn embed_failed(hwnd: isize, error: &EmbedError) {
+    warn!(target: "embed", "嵌入窗口失败: hwnd={}, {:?}: {}", hwnd, error.code, error.message);
+    crate::failure_stats::record(crate::failure_stats::FailureOp::Embed, hwnd, error);
+    emit(
+        "embed-failed",
+        EmbedFailed {
+            hwnd,
+            code: error.code,
+            key: error.code.key(),
+            params: error.params.clone(),
+            message: crate::messages::text(error.code.key(), &error.params),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tabs_ordered_by_recent_use_then_original_order() {
+        let tabs = [1, 2, 3, 4];
+        assert_eq!(order_by_recent(&tabs, &[3, 9, 1]), vec![3, 1, 2, 4]);
+        assert_eq!(order_by_recent(&tabs, &[]), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn mru_cycling_flips_between_two_most_recent() {
+        assert_eq!(mru_target(&[3, 1, 2], true), Some(1));
+        assert_eq!(mru_target(&[3, 1, 2], false), Some(2));
+        assert_eq!(mru_target(&[3], true), None);
+        // 没有激活记录时按标签栏的顺序
+        let order = order_by_recent(&[1, 2, 3], &[]);
+        assert_eq!(mru_target(&order, true), Some(2));
+        assert_eq!(mru_target(&order, false), Some(3));
+    }
+}
@@ -0,0 +1,119 @@
+// 日志系统 (tracing)
+// 同时输出到控制台和 %APPDATA%\WindowHub\logs\windowhub.YYYY-MM-DD.log (按天滚动，保留 7 天)
+// 各模块使用独立的 target，可单独调整级别：
+// embed / shortcuts / launcher / workspace / tray / events / focus / zoom / keys / drop / settings / files / notify / scripts / agent / lifecycle
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Builder, Rotation};
+use tracing_subscriber::{filter::Targets, fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, Registry};
+
+pub const TARGETS: [&str; 16] = ["embed", "shortcuts", "launcher", "workspace", "tray", "events", "focus", "zoom", "keys", "drop", "settings", "files", "notify", "scripts", "agent", "lifecycle"];
+
+const LOG_FILE_PREFIX: &str = "windowhub";
+const MAX_LOG_FILES: usize = 7;
+
+// 后台写文件线程的守卫，drop 后会丢失未写入的日志，所以常驻
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+static RELOAD: OnceLock<reload::Handle<Targets, Registry>> = OnceLock::new();
+
+// 当前级别配置: (默认级别, 按模块覆盖的级别)
+static LEVELS: Mutex<(LevelFilter, Vec<(String, LevelFilter)>)> = Mutex::new((LevelFilter::INFO, Vec::new()));
+
+pub fn log_dir() -> Option<PathBuf> {
+    crate::paths::data_dir().map(|dir| dir.join("logs"))
+}
+
+fn build_filter(default: LevelFilter, overrides: &[(String, LevelFilter)]) -> Targets {
+    // 框架内部日志太多，只保留警告
+    let mut filter = Targets::new()
+        .with_default(default)
+        .with_target("tao", LevelFilter::WARN)
+        .with_target("wry", LevelFilter::WARN)
+        .with_target("tauri", LevelFilter::WARN);
+    for (target, level) in overrides {
+        filter = filter.with_target(target.clone(), *level);
+    }
+    filter
+}
+
+/// 初始化日志 (run() 开始时调用一次)
+pub fn init() {
+    let (filter, handle) = reload::Layer::new(build_filter(LevelFilter::INFO, &[]));
+    let console_layer = fmt::layer().with_target(true);
+
+    let file_layer = log_dir().and_then(|dir| {
+        let appender = Builder::new()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(&dir)
+            .map_err(|e| eprintln!("[LOG] 无法创建日志文件: {}", e))
+            .ok()?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        let _ = GUARD.set(guard);
+        Some(fmt::layer().with_ansi(false).with_target(true).with_writer(writer))
+    });
+
+    if tracing_subscriber::registry().with(filter).with(console_layer).with(file_layer).try_init().is_ok() {
+        let _ = RELOAD.set(handle);
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level).map_err(|_| format!("无效的日志级别: {} (可选 off/error/warn/info/debug/trace)", level))
+}
+
+/// 设置日志级别，target 为空时设置默认级别，否则只影响对应模块
+pub fn set_level(level: &str, target: Option<&str>) -> Result<(), String> {
+    let level = parse_level(level)?;
+    let filter = {
+        let mut levels = LEVELS.lock().unwrap();
+        match target {
+            None => levels.0 = level,
+            Some(target) => {
+                if !TARGETS.contains(&target) {
+                    return Err(format!("未知的日志模块: {}", target));
+                }
+                levels.1.retain(|(t, _)| t != target);
+                levels.1.push((target.to_string(), level));
+            }
+        }
+        build_filter(levels.0, &levels.1)
+    };
+
+    let handle = RELOAD.get().ok_or("日志系统未初始化")?;
+    handle.reload(filter).map_err(|e| e.to_string())?;
+    tracing::info!("日志级别已更新: {} -> {}", target.unwrap_or("默认"), level);
+    Ok(())
+}
+
+/// 读取最近 n 行日志 (从最新的日志文件往前读)
+pub fn recent_logs(n: usize) -> Result<Vec<String>, String> {
+    let dir = log_dir().ok_or("无法获取日志目录")?;
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("无法读取日志目录: {}", e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.file_name().and_then(|f| f.to_str()).is_some_and(|f| f.starts_with(LOG_FILE_PREFIX)))
+        .collect();
+    // 文件名带日期，按名称排序即按时间排序
+    files.sort();
+
+    let mut lines: Vec<String> = Vec::new();
+    for path in files.iter().rev() {
+        if lines.len() >= n {
+            break;
+        }
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+        let mut file_lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let take = (n - lines.len()).min(file_lines.len());
+        let mut tail = file_lines.split_off(file_lines.len() - take);
+        tail.append(&mut lines);
+        lines = tail;
+    }
+    Ok(lines)
+}
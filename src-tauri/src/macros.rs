@@ -0,0 +1,308 @@
+// 按键宏：录制在某个标签中的键盘输入，之后重新回放 (老旧工具中的重复操作)
+// - start_recording 在独立线程上安装低级键盘钩子，只记录焦点在目标标签中时的按键 (含按键间隔)，
+//   注入的按键 (回放、send_keys、快捷键透传) 不记录
+// - stop_recording 结束录制并保存到 %APPDATA%\WindowHub\macros.json
+// - play 先激活目标标签，再按原来的间隔用 SendInput 逐个发送；焦点离开标签时立即停止，
+//   结束时释放宏里按下但没有松开的键
+// 录制状态变化时发送 macro-recording-changed (载荷为 MacroRecording)
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use std::time::Duration;
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+    System::LibraryLoader::GetModuleHandleW,
+    System::Threading::GetCurrentThreadId,
+    UI::Input::KeyboardAndMouse::{SendInput, INPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP},
+    UI::WindowsAndMessaging::*,
+};
+
+// 单个宏最多记录的按键事件
+#[cfg(windows)]
+const MAX_KEYS: usize = 10_000;
+
+// 回放时两个按键之间最长等待的时间 (录制时停顿太久的部分压缩掉)
+#[cfg(windows)]
+const MAX_DELAY_MS: u32 = 2000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroKey {
+    pub vk: u16,
+    pub scan: u16,
+    pub up: bool,
+    pub extended: bool,
+    pub delay_ms: u32, // 距离上一个按键的时间
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Macro {
+    pub id: u64,
+    pub name: String,
+    pub exe: Option<String>, // 录制时标签的 EXE 文件名
+    pub created_at: u64,     // Unix 秒
+    pub keys: Vec<MacroKey>,
+}
+
+/// macro-recording-changed 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct MacroRecording {
+    pub recording: bool,
+    pub target: isize,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+struct Recording {
+    target: isize,
+    exe: Option<String>,
+    thread_id: u32, // 钩子线程，结束时向它发送 WM_QUIT
+    last: Instant,
+    keys: Vec<MacroKey>,
+}
+
+static MACROS: Mutex<Vec<Macro>> = Mutex::new(Vec::new());
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+pub fn list() -> Vec<Macro> {
+    MACROS.lock().unwrap().clone()
+}
+
+/// 开始录制在 target 标签中的按键 (exe 为标签应用的路径)
+pub fn start_recording(app: &AppHandle, target: isize, exe_path: Option<&str>) -> Result<(), String> {
+    if RECORDING.lock().unwrap().is_some() {
+        return Err("已经在录制宏".to_string());
+    }
+    crate::budget::check_hooks(1)?;
+    let exe = exe_path.and_then(|p| std::path::Path::new(p).file_name()).and_then(|f| f.to_str()).map(str::to_string);
+    let thread_id = start_hook()?;
+    *RECORDING.lock().unwrap() = Some(Recording { target, exe, thread_id, last: Instant::now(), keys: Vec::new() });
+    info!(target: "keys", "开始录制宏: hwnd={}", target);
+    crate::tab_overlay::set_recording(target, true);
+    let _ = app.emit("macro-recording-changed", MacroRecording { recording: true, target });
+    Ok(())
+}
+
+/// 结束录制并保存，name 为空时自动命名
+pub fn stop_recording(app: &AppHandle, name: Option<String>) -> Result<Macro, String> {
+    let recording = RECORDING.lock().unwrap().take().ok_or("没有正在录制的宏")?;
+    #[cfg(windows)]
+    unsafe {
+        let _ = PostThreadMessageW(recording.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+    }
+    crate::tab_overlay::set_recording(recording.target, false);
+    let _ = app.emit("macro-recording-changed", MacroRecording { recording: false, target: recording.target });
+    if recording.keys.is_empty() {
+        return Err("没有录制到按键".to_string());
+    }
+
+    let mut macros = MACROS.lock().unwrap();
+    let id = macros.iter().map(|m| m.id).max().unwrap_or(0) + 1;
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()).unwrap_or_else(|| format!("宏 {}", id));
+    let created_at = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let recorded = Macro { id, name, exe: recording.exe, created_at, keys: recording.keys };
+    info!(target: "keys", "宏已保存: {} ({} 个按键)", recorded.name, recorded.keys.len());
+    macros.push(recorded.clone());
+    save_to_file(&macros);
+    Ok(recorded)
+}
+
+pub fn delete(id: u64) -> Result<(), String> {
+    let mut macros = MACROS.lock().unwrap();
+    let before = macros.len();
+    macros.retain(|m| m.id != id);
+    if macros.len() == before {
+        return Err("宏不存在".to_string());
+    }
+    save_to_file(&macros);
+    Ok(())
+}
+
+/// 在 target 标签中回放宏 (阻塞到回放结束)，返回发送的按键数
+pub fn play(id: u64, target: isize) -> Result<usize, String> {
+    let recorded = MACROS.lock().unwrap().iter().find(|m| m.id == id).cloned().ok_or("宏不存在")?;
+    if RECORDING.lock().unwrap().as_ref().is_some_and(|r| r.target == target) {
+        return Err("正在录制该标签，不能回放".to_string());
+    }
+    #[cfg(windows)]
+    {
+        if !crate::activate_window(target, None)? {
+            return Err("无效的窗口句柄".to_string());
+        }
+        // 等待焦点切换完成，否则前几个按键会落到主窗口
+        std::thread::sleep(Duration::from_millis(50));
+
+        let mut sent = 0;
+        let mut result = Ok(());
+        for key in &recorded.keys {
+            std::thread::sleep(Duration::from_millis(key.delay_ms.min(MAX_DELAY_MS) as u64));
+            if crate::platform::win32::focused_tab(&[target]).is_none() {
+                result = Err("焦点离开了标签，已停止回放".to_string());
+                break;
+            }
+            if unsafe { send_key(key) } {
+                sent += 1;
+            } else {
+                // 常见原因：目标窗口以管理员权限运行 (UIPI 拦截)
+                result = Err("按键注入被拦截".to_string());
+                break;
+            }
+        }
+        // 不让修饰键等停留在按下状态
+        for vk in held_keys(&recorded.keys[..sent]) {
+            let release = MacroKey { vk, scan: 0, up: true, extended: false, delay_ms: 0 };
+            unsafe { send_key(&release) };
+        }
+        info!(target: "keys", "回放宏 {} 到 hwnd={}: {}/{} 个按键", recorded.name, target, sent, recorded.keys.len());
+        result.map(|_| sent)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (recorded, target);
+        Err("仅支持 Windows".to_string())
+    }
+}
+
+// 按下后没有松开的键
+#[cfg_attr(not(windows), allow(dead_code))]
+fn held_keys(keys: &[MacroKey]) -> Vec<u16> {
+    let mut held: Vec<u16> = Vec::new();
+    for key in keys {
+        if key.up {
+            held.retain(|vk| *vk != key.vk);
+        } else if !held.contains(&key.vk) {
+            held.push(key.vk);
+        }
+    }
+    held
+}
+
+#[cfg(windows)]
+unsafe fn send_key(key: &MacroKey) -> bool {
+    let mut flags = KEYBD_EVENT_FLAGS(0);
+    if key.extended {
+        flags |= KEYEVENTF_EXTENDEDKEY;
+    }
+    if key.up {
+        flags |= KEYEVENTF_KEYUP;
+    }
+    let input = crate::keys::key_input(key.vk, key.scan, flags);
+    SendInput(&[input], std::mem::size_of::<INPUT>() as i32) == 1
+}
+
+// 启动钩子线程，返回线程 ID
+#[cfg(windows)]
+fn start_hook() -> Result<u32, String> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || unsafe { hook_thread(sender) });
+    receiver.recv().map_err(|_| "录制线程启动失败".to_string())?
+}
+
+#[cfg(not(windows))]
+fn start_hook() -> Result<u32, String> {
+    Err("仅支持 Windows".to_string())
+}
+
+// 安装键盘钩子并运行消息循环，通过 sender 返回线程 ID (安装失败时返回错误)
+#[cfg(windows)]
+unsafe fn hook_thread(sender: std::sync::mpsc::Sender<Result<u32, String>>) {
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let hook = match SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), instance, 0) {
+        Ok(hook) => hook,
+        Err(e) => {
+            warn!(target: "keys", "安装键盘钩子失败，无法录制宏: {}", e);
+            let _ = sender.send(Err(format!("安装键盘钩子失败: {}", e)));
+            return;
+        }
+    };
+    let resource = crate::resources::register(crate::resources::ResourceKind::LowLevelHook, "macros", 1, None, None);
+    let _ = sender.send(Ok(GetCurrentThreadId()));
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+    }
+    let _ = UnhookWindowsHookEx(hook);
+    crate::resources::unregister(resource);
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        if !info.flags.contains(LLKHF_INJECTED) {
+            record(info, wparam.0 as u32 == WM_KEYUP || wparam.0 as u32 == WM_SYSKEYUP);
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+#[cfg(windows)]
+fn record(info: &KBDLLHOOKSTRUCT, up: bool) {
+    let mut recording = RECORDING.lock().unwrap();
+    let Some(recording) = recording.as_mut() else { return };
+    if recording.keys.len() >= MAX_KEYS || crate::platform::win32::focused_tab(&[recording.target]).is_none() {
+        return;
+    }
+    let now = Instant::now();
+    let delay_ms = now.duration_since(recording.last).as_millis().min(u32::MAX as u128) as u32;
+    recording.last = now;
+    recording.keys.push(MacroKey {
+        vk: info.vkCode as u16,
+        scan: info.scanCode as u16,
+        up,
+        extended: info.flags.contains(LLKHF_EXTENDED),
+        // 第一个按键不等待
+        delay_ms: if recording.keys.is_empty() { 0 } else { delay_ms },
+    });
+}
+
+fn save_to_file(macros: &[Macro]) {
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("macros.json");
+
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        if let Ok(json) = serde_json::to_string_pretty(macros) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+pub fn load_from_file() {
+    let Some(config_dir) = crate::paths::data_dir() else { return };
+    let path = config_dir.join("macros.json");
+    let Ok(content) = std::fs::read_to_string(path) else { return };
+    match serde_json::from_str::<Vec<Macro>>(&content) {
+        Ok(loaded) => {
+            info!(target: "settings", "已加载 {} 个按键宏", loaded.len());
+            *MACROS.lock().unwrap() = loaded;
+        }
+        Err(e) => warn!(target: "settings", "读取 macros.json 失败: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(vk: u16, up: bool) -> MacroKey {
+        MacroKey { vk, scan: 0, up, extended: false, delay_ms: 0 }
+    }
+
+    #[test]
+    fn keys_left_down_are_released_after_playback() {
+        // Ctrl 按下，A 按下松开，停在 Ctrl 还没松开 (录制在按住 Ctrl 时结束)
+        let keys = [key(0x11, false), key(0x41, false), key(0x41, true)];
+        assert_eq!(held_keys(&keys), vec![0x11]);
+        // 按键自动重复产生的多次按下只释放一次
+        let keys = [key(0x10, false), key(0x10, false), key(0x10, true)];
+        assert!(held_keys(&keys).is_empty());
+    }
+}
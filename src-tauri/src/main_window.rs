@@ -0,0 +1,79 @@
+// 判断新窗口是不是应用的主窗口
+// IDE、Electron 和 Java 应用启动时先显示启动画面 (无边框、不能调整大小、通常较小)，过几秒才出现真正的主窗口，
+// 直接嵌入第一个新窗口会抓到启动画面。主窗口的特征：有标题栏、能调整大小、足够大、不是工具窗口
+// launch_and_wait 遇到启动画面时继续等待同一进程的主窗口，监视应用 (app_watch.rs) 跳过启动画面
+
+// 窗口样式位 (与 Win32 的 WS_* / WS_EX_* 相同)
+const WS_CAPTION: u32 = 0x00C0_0000;
+const WS_THICKFRAME: u32 = 0x0004_0000;
+const WS_EX_TOOLWINDOW: u32 = 0x0000_0080;
+
+// 主窗口的最小尺寸 (物理像素)
+const MIN_WIDTH: i32 = 400;
+const MIN_HEIGHT: i32 = 300;
+
+/// 判断所需的窗口属性
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowShape {
+    pub style: u32,
+    pub ex_style: u32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// 窗口是否像应用的主窗口 (无法读取窗口属性时按主窗口处理)
+pub fn is_main(hwnd: isize) -> bool {
+    shape(hwnd).is_none_or(looks_like_main)
+}
+
+fn looks_like_main(shape: WindowShape) -> bool {
+    shape.style & WS_CAPTION == WS_CAPTION
+        && shape.style & WS_THICKFRAME != 0
+        && shape.ex_style & WS_EX_TOOLWINDOW == 0
+        && shape.width >= MIN_WIDTH
+        && shape.height >= MIN_HEIGHT
+}
+
+#[cfg(windows)]
+fn shape(hwnd: isize) -> Option<WindowShape> {
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::UI::WindowsAndMessaging::{GetWindowLongPtrW, GetWindowRect, IsWindow, GWL_EXSTYLE, GWL_STYLE};
+
+    let hwnd = HWND(hwnd as *mut _);
+    unsafe {
+        if !IsWindow(hwnd).as_bool() {
+            return None;
+        }
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).ok()?;
+        Some(WindowShape {
+            style: GetWindowLongPtrW(hwnd, GWL_STYLE) as u32,
+            ex_style: GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32,
+            width: rect.right - rect.left,
+            height: rect.bottom - rect.top,
+        })
+    }
+}
+
+#[cfg(not(windows))]
+fn shape(_hwnd: isize) -> Option<WindowShape> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splash_screens_are_not_main_windows() {
+        let main = WindowShape { style: 0x14CF_0000, ex_style: 0x100, width: 1280, height: 800 };
+        assert!(looks_like_main(main));
+        // 无边框启动画面
+        assert!(!looks_like_main(WindowShape { style: 0x9400_0000, ..main }));
+        // 有标题栏但不能调整大小
+        assert!(!looks_like_main(WindowShape { style: main.style & !WS_THICKFRAME, ..main }));
+        assert!(!looks_like_main(WindowShape { ex_style: WS_EX_TOOLWINDOW, ..main }));
+        assert!(!looks_like_main(WindowShape { width: 380, height: 240, ..main }));
+    }
+}
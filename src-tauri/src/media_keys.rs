@@ -0,0 +1,84 @@
+// 媒体键转发
+// 工作区可以指定一个媒体应用 (Workspace.media_target，EXE 路径)：恢复该工作区后接管播放/暂停、上一首、下一首和停止键，
+// 按下时以 WM_APPCOMMAND 发给嵌入的该应用的窗口 (如嵌入的音乐播放器)，不需要它在前台。
+// 没有这个应用的标签时照常把按键发给系统；设置或标签快捷键中已经使用的媒体键不接管
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Shortcut};
+use tracing::{debug, warn};
+
+use crate::window_manager::WindowManager;
+
+// 接管的媒体键: (键, 键名, APPCOMMAND_*)
+const MEDIA_KEYS: [(Code, &str, u32); 4] = [
+    (Code::MediaPlayPause, "MediaPlayPause", 14),
+    (Code::MediaTrackNext, "MediaTrackNext", 11),
+    (Code::MediaTrackPrevious, "MediaTrackPrevious", 12),
+    (Code::MediaStop, "MediaStop", 13),
+];
+
+// 当前工作区的媒体应用 (EXE 路径)
+static TARGET: Mutex<Option<String>> = Mutex::new(None);
+
+/// 设置接收媒体键的应用，返回是否有变化 (有变化时调用方重新注册快捷键)
+pub fn set_target(exe_path: Option<String>) -> bool {
+    let mut target = TARGET.lock().unwrap();
+    let changed = *target != exe_path;
+    *target = exe_path;
+    changed
+}
+
+/// 注册接管的媒体键 (全局快捷键整体重新注册后调用)
+pub fn register_all(app: &AppHandle) {
+    if TARGET.lock().unwrap().is_none() {
+        return;
+    }
+    let windows = app.state::<WindowManager>().list();
+    for (code, name, _) in MEDIA_KEYS {
+        let shortcut = Shortcut::new(None, code);
+        if crate::settings::action_for(&shortcut).is_some() || crate::tab_hotkeys::tab_for(&windows, &shortcut).is_some() {
+            continue;
+        }
+        if let Err(e) = app.global_shortcut().register(shortcut) {
+            warn!(target: "shortcuts", "注册媒体键失败: {} - {:?}", name, e);
+        }
+    }
+}
+
+/// 按下的是接管的媒体键时转发给媒体应用并返回 true
+pub fn route(app: &AppHandle, shortcut: &Shortcut) -> bool {
+    let Some((_, name, command)) = MEDIA_KEYS.iter().find(|(code, _, _)| Shortcut::new(None, *code) == *shortcut) else {
+        return false;
+    };
+    let Some(target) = TARGET.lock().unwrap().clone() else { return false };
+    let tab = app
+        .state::<WindowManager>()
+        .list()
+        .into_iter()
+        .find(|w| w.exe_path.as_deref().is_some_and(|p| p.eq_ignore_ascii_case(&target)));
+    match tab {
+        Some(tab) => {
+            debug!(target: "shortcuts", "媒体键发给标签: {} -> hwnd={}", name, tab.hwnd);
+            send_command(tab.hwnd, *command);
+        }
+        // 媒体应用不在标签中，按键交还给系统
+        None => crate::passthrough::forward(app, *shortcut, name.to_string()),
+    }
+    true
+}
+
+#[cfg(windows)]
+fn send_command(hwnd: isize, command: u32) {
+    use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_APPCOMMAND};
+
+    // lParam 高位为命令，设备为键盘 (FAPPCOMMAND_KEY = 0)
+    let lparam = (command << 16) as isize;
+    if let Err(e) = unsafe { PostMessageW(HWND(hwnd as *mut _), WM_APPCOMMAND, WPARAM(hwnd as usize), LPARAM(lparam)) } {
+        warn!(target: "shortcuts", "发送媒体命令失败: hwnd={} - {}", hwnd, e);
+    }
+}
+
+#[cfg(not(windows))]
+fn send_command(_hwnd: isize, _command: u32) {}
@@ -0,0 +1,106 @@
+// 后端消息目录
+// 返回给前端的错误和状态不直接写死中文，而是用键 + 参数表示 (如 embed.blocked + [类名])，
+// 按设置中的界面语言 (appearance.language) 生成文本；前端可以用 get_messages(locale) 取得整个目录自己翻译
+// 文本中的 {0}、{1} 按顺序替换为参数，缺少某种语言的文本时使用中文
+// 日志仍然只写中文
+
+use serde::Serialize;
+
+/// 支持的语言，顺序与 CATALOG 中每条文本的顺序一致，第一个是默认语言
+pub const LOCALES: [&str; 2] = ["zh-CN", "en-US"];
+
+const CATALOG: &[(&str, [&str; 2])] = &[
+    ("embed.no_host", ["无法获取主窗口", "Unable to get the WindowHub main window"]),
+    ("embed.invalid_window", ["无效的窗口句柄", "The window no longer exists"]),
+    ("embed.own_window", ["不能嵌入自身", "WindowHub cannot embed itself"]),
+    ("embed.dangerous", ["不支持嵌入此类型窗口: {0}", "This kind of window cannot be embedded: {0}"]),
+    ("embed.blocked", ["该窗口在禁止嵌入列表中: {0}", "This window is in the embed block list: {0}"]),
+    ("embed.problematic", ["此类型窗口之前嵌入时无响应: {0}", "This kind of window stopped responding the last time it was embedded: {0}"]),
+    ("embed.timeout", ["窗口无响应，嵌入超时", "The window stopped responding and embedding timed out"]),
+    ("embed.failed", ["{0}", "{0}"]),
+    ("embed.not_embedded", ["窗口没有被嵌入", "The window is not embedded"]),
+    ("embed.stale_handle", ["窗口已关闭，请刷新窗口列表后重新选择", "The window has closed; refresh the window list and pick it again"]),
+    ("embed.limit_exceeded", ["已达到资源上限 {0} = {1}，请关闭一些标签或在设置中调高上限", "Resource limit {0} = {1} reached; close some tabs or raise the limit in settings"]),
+    ("risk.tiny_window", ["窗口很小 ({0}x{1})，可能是隐藏窗口或启动画面", "The window is very small ({0}x{1}); it may be a hidden window or a splash screen"]),
+    ("risk.no_process_access", ["无法读取所属程序的路径，可能以管理员身份运行，嵌入后可能无法操作", "The owning program cannot be inspected; it may be running as administrator and might not accept input once embedded"]),
+    ("risk.new_process", ["所属程序 {0} 秒前才启动，可能还在显示启动画面", "The owning program started {0} seconds ago and may still be showing a splash screen"]),
+    ("settings.safe_mode", ["安全模式下不能修改设置，请正常启动后再修改", "Settings cannot be changed in safe mode; restart normally to change them"]),
+    ("settings.unknown_language", ["不支持的界面语言: {0}", "Unsupported language: {0}"]),
+    ("privacy.title", ["窗口", "Window"]),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageText {
+    pub key: &'static str,
+    pub text: &'static str,
+}
+
+/// 按语言代码找到支持的语言 (不区分大小写，"en"、"en-GB" 都对应 en-US)
+pub fn resolve(locale: &str) -> Option<usize> {
+    let locale = locale.to_ascii_lowercase();
+    let language = locale.split(['-', '_']).next().unwrap_or("");
+    LOCALES
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(&locale))
+        .or_else(|| LOCALES.iter().position(|l| l.split('-').next().is_some_and(|l| l.eq_ignore_ascii_case(language))))
+}
+
+/// 当前界面语言 (设置为空或不支持时使用中文)
+fn current() -> usize {
+    resolve(&crate::settings::get().appearance.language).unwrap_or(0)
+}
+
+fn render(locale: usize, key: &str, params: &[String]) -> String {
+    let Some((_, texts)) = CATALOG.iter().find(|(k, _)| *k == key) else {
+        return key.to_string();
+    };
+    let template = if texts[locale].is_empty() { texts[0] } else { texts[locale] };
+    params
+        .iter()
+        .enumerate()
+        .fold(template.to_string(), |text, (i, param)| text.replace(&format!("{{{}}}", i), param))
+}
+
+/// 用当前界面语言生成消息
+pub fn text(key: &str, params: &[String]) -> String {
+    render(current(), key, params)
+}
+
+/// 中文消息 (写日志、保存诊断信息时使用)
+pub fn zh(key: &str, params: &[String]) -> String {
+    render(0, key, params)
+}
+
+/// 某种语言的完整目录
+pub fn catalog(locale: &str) -> Result<Vec<MessageText>, String> {
+    let index = resolve(locale).ok_or_else(|| text("settings.unknown_language", &[locale.to_string()]))?;
+    Ok(CATALOG
+        .iter()
+        .map(|(key, texts)| MessageText { key, text: if texts[index].is_empty() { texts[0] } else { texts[index] } })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_locales_and_renders_params() {
+        assert_eq!(resolve("zh-CN"), Some(0));
+        assert_eq!(resolve("en"), Some(1));
+        assert_eq!(resolve("EN_gb"), Some(1));
+        assert_eq!(resolve("fr-FR"), None);
+
+        let params = ["Shell_TrayWnd".to_string()];
+        assert_eq!(render(0, "embed.dangerous", &params), "不支持嵌入此类型窗口: Shell_TrayWnd");
+        assert_eq!(render(1, "embed.dangerous", &params), "This kind of window cannot be embedded: Shell_TrayWnd");
+        assert_eq!(render(1, "no.such.key", &[]), "no.such.key");
+
+        // 每条消息在每种语言中的参数个数一致
+        for (key, texts) in CATALOG {
+            let count = |text: &str| (0..4).filter(|i| text.contains(&format!("{{{}}}", i))).count();
+            assert!(texts.iter().all(|t| count(t) == count(texts[0])), "{}", key);
+        }
+        assert_eq!(catalog("en-US").unwrap().len(), CATALOG.len());
+    }
+}
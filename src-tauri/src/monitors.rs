@@ -0,0 +1,123 @@
+// 显示器
+// 按 EnumDisplayMonitors 的顺序列出显示器 (enumerate_monitors)，前端按这个顺序的序号指定显示器，
+// 用于选择显示器、布局预设和弹出到显示器；DPI 为显示器的有效 DPI (GetDpiForMonitor)，96 为 100% 缩放
+// release_to_monitor 弹出标签时直接把窗口放到指定的显示器上：最大化，或者保持原来的大小放在工作区中间
+
+use serde::Serialize;
+
+use crate::window_manager::WindowRect;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{BOOL, HWND, LPARAM, RECT, TRUE},
+    Graphics::Gdi::{EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW},
+    UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI},
+    UI::WindowsAndMessaging::{
+        GetWindowRect, IsWindow, SetWindowPos, ShowWindow, MONITORINFOF_PRIMARY, SWP_NOACTIVATE, SWP_NOZORDER, SW_MAXIMIZE, SW_RESTORE,
+    },
+};
+
+/// 显示器 (屏幕坐标，物理像素)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Monitor {
+    pub name: String, // 显示器设备名，如 \\.\DISPLAY2
+    pub bounds: WindowRect,
+    pub work: WindowRect, // 工作区 (去掉任务栏)
+    pub dpi: u32,
+    pub primary: bool,
+}
+
+/// 把顶层窗口移到显示器上：maximize 时最大化，否则保持大小 (超出工作区时缩小) 放在工作区中间
+#[cfg(windows)]
+pub fn place(hwnd: isize, monitor: &Monitor, maximize: bool) -> Result<(), String> {
+    let work = monitor.work;
+    crate::window_ops::call("place_on_monitor", move || unsafe {
+        let window = HWND(hwnd as *mut _);
+        if !IsWindow(window).as_bool() {
+            return Err("窗口不存在".to_string());
+        }
+        // 最大化的窗口先还原，否则 SetWindowPos 只改变还原后的位置
+        let _ = ShowWindow(window, SW_RESTORE);
+        let mut rect = RECT::default();
+        GetWindowRect(window, &mut rect).map_err(|e| format!("获取窗口位置失败: {}", e))?;
+        let target = centered(rect.into(), work);
+        SetWindowPos(window, None, target.left, target.top, target.width(), target.height(), SWP_NOZORDER | SWP_NOACTIVATE)
+            .map_err(|e| format!("移动窗口失败: {}", e))?;
+        if maximize {
+            let _ = ShowWindow(window, SW_MAXIMIZE);
+        }
+        Ok(())
+    })?
+}
+
+#[cfg(not(windows))]
+pub fn place(_hwnd: isize, _monitor: &Monitor, _maximize: bool) -> Result<(), String> {
+    Err("仅支持 Windows".to_string())
+}
+
+// 保持 rect 的大小 (不超过工作区) 放在工作区中间
+#[cfg_attr(not(windows), allow(dead_code))]
+fn centered(rect: WindowRect, work: WindowRect) -> WindowRect {
+    let width = rect.width().min(work.width());
+    let height = rect.height().min(work.height());
+    let left = work.left + (work.width() - width) / 2;
+    let top = work.top + (work.height() - height) / 2;
+    WindowRect { left, top, right: left + width, bottom: top + height }
+}
+
+/// 显示器的设备名
+#[cfg(windows)]
+pub fn name(monitor: HMONITOR) -> Option<String> {
+    let mut info = MONITORINFOEXW::default();
+    info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+    unsafe { GetMonitorInfoW(monitor, &mut info as *mut _ as *mut MONITORINFO) }.as_bool().then(|| {
+        let len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+        String::from_utf16_lossy(&info.szDevice[..len])
+    })
+}
+
+/// 所有显示器
+#[cfg(windows)]
+pub fn list() -> Vec<Monitor> {
+    unsafe extern "system" fn collect(monitor: HMONITOR, _hdc: HDC, _rect: *mut RECT, data: LPARAM) -> BOOL {
+        let monitors = &mut *(data.0 as *mut Vec<Monitor>);
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if GetMonitorInfoW(monitor, &mut info as *mut _ as *mut MONITORINFO).as_bool() {
+            let (mut dpi, mut dpi_y) = (96, 96);
+            let _ = GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi, &mut dpi_y);
+            monitors.push(Monitor {
+                name: name(monitor).unwrap_or_default(),
+                bounds: WindowRect::from(info.monitorInfo.rcMonitor),
+                work: WindowRect::from(info.monitorInfo.rcWork),
+                dpi,
+                primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+            });
+        }
+        TRUE
+    }
+    let mut monitors: Vec<Monitor> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(HDC::default(), None, Some(collect), LPARAM(&mut monitors as *mut _ as isize));
+    }
+    monitors
+}
+
+#[cfg(not(windows))]
+pub fn list() -> Vec<Monitor> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centers_and_shrinks_to_work_area() {
+        let work = WindowRect { left: 1920, top: 0, right: 3840, bottom: 1040 };
+        let small = WindowRect { left: 100, top: 100, right: 900, bottom: 700 };
+        assert_eq!(centered(small, work), WindowRect { left: 2480, top: 220, right: 3280, bottom: 820 });
+        let large = WindowRect { left: 0, top: 0, right: 2560, bottom: 1440 };
+        assert_eq!(centered(large, work), work);
+    }
+}
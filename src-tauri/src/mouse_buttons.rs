@@ -0,0 +1,177 @@
+// 鼠标中键和侧键 (设置 input.mouse_buttons)
+// 主窗口在前台且指针在主窗口 (包括嵌入的窗口) 上时，由低级鼠标钩子拦截：
+// - 在标签栏上中键单击：如关闭指针下的标签。关闭/弹出/重启只作用于指针下的标签，由前端按坐标找到标签
+//   (tab-strip-middle-click，载荷为客户区坐标和操作)；其他操作与快捷键相同
+//   原生标签栏 (native_tabs.rs) 自己处理中键，开启时不拦截
+// - 侧键 X1/X2：可以设置为切换到上一个/下一个标签 (嵌入的浏览器等需要侧键后退/前进，默认不拦截)
+// 默认全部为 null，不安装钩子，在设置中配置了操作才拦截
+// 按下和松开都被拦截，应用不会收到半次点击；操作在松开时执行
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+use tracing::info;
+
+use crate::host_layout::Insets;
+use crate::mouse_hook::MouseHook;
+use crate::settings::{MouseAction, MouseButtonSettings};
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicIsize, Ordering};
+#[cfg(windows)]
+use tauri::{Emitter, Manager};
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM},
+    Graphics::Gdi::ScreenToClient,
+    UI::WindowsAndMessaging::*,
+};
+
+/// 中键单击标签栏时发给前端的事件载荷
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct TabStripClick {
+    pub x: i32, // 主窗口客户区坐标 (物理像素)
+    pub y: i32,
+    pub action: MouseAction,
+}
+
+/// 被拦截的按键
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Button {
+    Middle,
+    Back,
+    Forward,
+}
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static HOOK: MouseHook = MouseHook::new("mouse_buttons", "鼠标按键");
+
+// 当前的按键配置和是否使用原生标签栏，钩子中不重复读取设置
+static CONFIG: Mutex<Option<(MouseButtonSettings, bool)>> = Mutex::new(None);
+
+// 已拦截按下、等待松开的按键，以及按下时的位置 (客户区坐标) 和要执行的操作
+#[cfg(windows)]
+static PRESSED: Mutex<Option<(Button, (i32, i32), MouseAction)>> = Mutex::new(None);
+
+// 主窗口
+#[cfg(windows)]
+static HOST: AtomicIsize = AtomicIsize::new(0);
+
+/// 按设置开启或关闭 (启动和设置变化时调用)
+pub fn apply(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let settings = crate::settings::get();
+    let buttons = settings.input.mouse_buttons;
+    let enabled = (buttons.middle_click_tab.is_some() || buttons.back.is_some() || buttons.forward.is_some()) && crate::session_watch::is_active();
+    *CONFIG.lock().unwrap() = Some((buttons, settings.appearance.native_tab_strip));
+    if !HOOK.set_enabled(enabled) {
+        return;
+    }
+    info!(target: "shortcuts", "{}鼠标中键/侧键操作", if enabled { "开启" } else { "关闭" });
+    #[cfg(windows)]
+    if enabled {
+        if let Some(host) = app.get_webview_window("main").and_then(|w| w.hwnd().ok()) {
+            HOST.store(host.0 as isize, Ordering::SeqCst);
+        }
+        HOOK.spawn(Some(mouse_proc), |_| false, || *PRESSED.lock().unwrap() = None);
+    }
+}
+
+/// 按键对应的操作，中键只在标签栏 (客户区中内容区上方) 上有效；client 为指针的客户区坐标
+#[cfg_attr(not(windows), allow(dead_code))]
+fn action_for(button: Button, client: (i32, i32), insets: Option<Insets>, buttons: &MouseButtonSettings, native_strip: bool) -> Option<MouseAction> {
+    match button {
+        Button::Middle if native_strip => None,
+        Button::Middle => {
+            let insets = insets?;
+            (client.1 >= 0 && client.1 < insets.top).then_some(buttons.middle_click_tab?)
+        }
+        Button::Back => buttons.back,
+        Button::Forward => buttons.forward,
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code < 0 {
+        return CallNextHookEx(None, code, wparam, lparam);
+    }
+    let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+    let x_button = || if (info.mouseData >> 16) as u16 == XBUTTON1 { Button::Back } else { Button::Forward };
+    let (button, down) = match wparam.0 as u32 {
+        WM_MBUTTONDOWN => (Button::Middle, true),
+        WM_MBUTTONUP => (Button::Middle, false),
+        WM_XBUTTONDOWN => (x_button(), true),
+        WM_XBUTTONUP => (x_button(), false),
+        _ => return CallNextHookEx(None, code, wparam, lparam),
+    };
+    // 其他程序模拟的输入不处理
+    if info.flags & LLMHF_INJECTED != 0 {
+        return CallNextHookEx(None, code, wparam, lparam);
+    }
+
+    if down {
+        if let Some((client, action)) = target(button, info.pt) {
+            *PRESSED.lock().unwrap() = Some((button, client, action));
+            return LRESULT(1);
+        }
+    } else if let Some((_, client, action)) = PRESSED.lock().unwrap().take_if(|(pressed, ..)| *pressed == button) {
+        run(button, client, action);
+        return LRESULT(1);
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+// 主窗口在前台且指针在主窗口上时，按键对应的操作
+#[cfg(windows)]
+unsafe fn target(button: Button, pt: POINT) -> Option<((i32, i32), MouseAction)> {
+    let host = HOST.load(Ordering::SeqCst);
+    if host == 0 || GetForegroundWindow().0 as isize != host || GetAncestor(WindowFromPoint(pt), GA_ROOT).0 as isize != host {
+        return None;
+    }
+    let mut client = pt;
+    let _ = ScreenToClient(HWND(host as *mut _), &mut client);
+    let client = (client.x, client.y);
+    let (buttons, native_strip) = CONFIG.lock().unwrap().clone()?;
+    let action = action_for(button, client, crate::host_layout::insets(), &buttons, native_strip)?;
+    Some((client, action))
+}
+
+// 执行操作 (新线程，不阻塞钩子)
+#[cfg(windows)]
+fn run(button: Button, client: (i32, i32), action: MouseAction) {
+    let Some(app) = APP.get().cloned() else { return };
+    info!(target: "shortcuts", "鼠标按键 {:?}: {:?}", button, action);
+    std::thread::spawn(move || {
+        let on_tab = matches!(action, MouseAction::CloseTab | MouseAction::DetachTab | MouseAction::RestartTab);
+        if button == Button::Middle && on_tab {
+            let _ = app.emit("tab-strip-middle-click", TabStripClick { x: client.0, y: client.1, action });
+        } else {
+            crate::handle_shortcut_action(&app, action.shortcut_action());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn middle_click_only_acts_on_the_tab_strip() {
+        let buttons = MouseButtonSettings {
+            middle_click_tab: Some(MouseAction::CloseTab),
+            back: Some(MouseAction::PrevTab),
+            forward: Some(MouseAction::NextTab),
+        };
+        let insets = Some(Insets { left: 0, top: 40, right: 0, bottom: 0 });
+        assert_eq!(action_for(Button::Middle, (200, 20), insets, &buttons, false), Some(MouseAction::CloseTab));
+        assert_eq!(action_for(Button::Middle, (200, 300), insets, &buttons, false), None);
+        assert_eq!(action_for(Button::Middle, (200, 20), insets, &buttons, true), None);
+        assert_eq!(action_for(Button::Back, (200, 300), insets, &buttons, false), Some(MouseAction::PrevTab));
+        // 默认不拦截任何按键
+        assert_eq!(action_for(Button::Back, (200, 300), insets, &MouseButtonSettings::default(), false), None);
+    }
+}
@@ -0,0 +1,105 @@
+// 低级鼠标钩子线程 (鼠标按键、鼠标手势、热角共用)
+// 每个功能开启时在单独的线程上安装自己的 WH_MOUSE_LL 钩子并处理消息，关闭时向该线程发送 WM_QUIT
+// 开启和关闭可能在线程启动前后交错 (设置连续变化、锁屏解锁)：
+// - 线程先登记自己 (只有没有其他线程登记时才能登记)，再检查是否仍然开启，关闭后才启动的线程直接退出
+// - 关闭时取走登记的线程并通知它退出；线程退出时只清除自己的登记 (compare_exchange)，
+//   不会清掉关闭后又开启时登记的新线程
+// 同时最多有一个线程在处理消息，重复开启时多出来的线程登记失败后退出
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+#[cfg(windows)]
+use tracing::warn;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, WPARAM},
+    System::LibraryLoader::GetModuleHandleW,
+    System::Threading::GetCurrentThreadId,
+    UI::WindowsAndMessaging::*,
+};
+
+pub struct MouseHook {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    owner: &'static str, // 登记资源时的名称
+    #[cfg_attr(not(windows), allow(dead_code))]
+    label: &'static str, // 日志中的名称
+    enabled: AtomicBool,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    thread_id: AtomicU32,
+}
+
+impl MouseHook {
+    pub const fn new(owner: &'static str, label: &'static str) -> Self {
+        MouseHook { owner, label, enabled: AtomicBool::new(false), thread_id: AtomicU32::new(0) }
+    }
+
+    /// 开启或关闭，返回是否有变化；关闭时通知钩子线程退出，开启后由调用方 spawn 钩子线程
+    pub fn set_enabled(&self, enabled: bool) -> bool {
+        if self.enabled.swap(enabled, Ordering::SeqCst) == enabled {
+            return false;
+        }
+        #[cfg(windows)]
+        if !enabled {
+            let thread = self.thread_id.swap(0, Ordering::SeqCst);
+            if thread != 0 {
+                unsafe {
+                    let _ = PostThreadMessageW(thread, WM_QUIT, WPARAM(0), LPARAM(0));
+                }
+            }
+        }
+        true
+    }
+
+    /// 向钩子线程发送消息 (钩子回调中不能做的事交给线程的 on_message)
+    #[cfg(windows)]
+    pub fn post(&self, message: u32) {
+        let thread = self.thread_id.load(Ordering::SeqCst);
+        if thread != 0 {
+            unsafe {
+                let _ = PostThreadMessageW(thread, message, WPARAM(0), LPARAM(0));
+            }
+        }
+    }
+
+    /// 在新线程上安装钩子 proc 并处理消息，直到关闭
+    /// on_message 先看到线程收到的每条消息，返回 true 表示已处理；on_exit 在线程退出前清理功能自己的状态
+    #[cfg(windows)]
+    pub fn spawn(&'static self, proc: HOOKPROC, on_message: fn(&MSG) -> bool, on_exit: fn()) {
+        std::thread::spawn(move || unsafe { self.run(proc, on_message, on_exit) });
+    }
+
+    #[cfg(windows)]
+    unsafe fn run(&self, proc: HOOKPROC, on_message: fn(&MSG) -> bool, on_exit: fn()) {
+        // 先建立消息队列，登记后关闭时发送的 WM_QUIT 不会丢失
+        let mut msg = MSG::default();
+        let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+        let own = GetCurrentThreadId();
+        if self.thread_id.compare_exchange(0, own, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return;
+        }
+        // 线程启动前又关闭了时直接退出
+        if !self.enabled.load(Ordering::SeqCst) {
+            let _ = self.thread_id.compare_exchange(own, 0, Ordering::SeqCst, Ordering::SeqCst);
+            return;
+        }
+        let instance = GetModuleHandleW(None).unwrap_or_default();
+        match SetWindowsHookExW(WH_MOUSE_LL, proc, instance, 0) {
+            Ok(hook) => {
+                let resource = crate::resources::register(crate::resources::ResourceKind::LowLevelHook, self.owner, 1, None, None);
+                while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+                    if on_message(&msg) {
+                        continue;
+                    }
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+                let _ = UnhookWindowsHookEx(hook);
+                crate::resources::unregister(resource);
+            }
+            Err(e) => warn!(target: "shortcuts", "安装{}钩子失败: {}", self.label, e),
+        }
+        on_exit();
+        let _ = self.thread_id.compare_exchange(own, 0, Ordering::SeqCst, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,577 @@
+// 原生标签栏
+// 设置中开启 appearance.native_tab_strip 后，标签改由后端绘制在主窗口上的一个子窗口中
+// (GDI 画背景和应用图标，Direct2D/DirectWrite 画文字)，点击标签直接在后端切换，不经过网页，输入延迟最低；
+// 网页隐藏自己的标签，只负责搜索面板等其他界面：
+// - 子窗口盖在网页原来的标签区域上，位置由前端报告 (place)，右侧的按钮仍由网页显示
+// - 切换/关闭后发送和网页标签栏相同含义的事件，前端据此同步状态：
+//   tab-strip-activate (hwnd，后端已经切换)、tab-strip-close (hwnd，由前端走原来的关闭流程)
+// - 嵌入/释放/激活 (lifecycle.rs) 时重绘，标题和名称的变化每秒重绘一次
+// 子窗口在主线程创建，由主线程的消息循环处理
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+#[cfg(windows)]
+use std::cell::RefCell;
+#[cfg(windows)]
+use std::collections::HashMap;
+#[cfg(windows)]
+use tauri::Emitter;
+#[cfg(windows)]
+use tracing::info;
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM},
+        Graphics::Direct2D::Common::{D2D1_ALPHA_MODE_IGNORE, D2D1_COLOR_F, D2D1_PIXEL_FORMAT, D2D_RECT_F},
+        Graphics::Direct2D::*,
+        Graphics::DirectWrite::*,
+        Graphics::Dxgi::Common::DXGI_FORMAT_B8G8R8A8_UNORM,
+        Graphics::Gdi::{
+            BeginPaint, CreateSolidBrush, DeleteObject, EndPaint, FillRect, GetSysColor, InvalidateRect, COLOR_BTNFACE, COLOR_BTNTEXT, COLOR_HIGHLIGHT,
+            COLOR_HIGHLIGHTTEXT, COLOR_WINDOW, PAINTSTRUCT,
+        },
+        System::LibraryLoader::GetModuleHandleW,
+        UI::Input::KeyboardAndMouse::{TrackMouseEvent, TME_LEAVE, TRACKMOUSEEVENT},
+        UI::WindowsAndMessaging::*,
+    },
+};
+
+use crate::window_manager::WindowManager;
+
+// 以下尺寸为 96 DPI 下的像素，绘制时乘以缩放比例
+const TAB_MAX_WIDTH: i32 = 200;
+const TAB_GAP: i32 = 8;
+#[cfg(windows)]
+const PADDING: i32 = 10;
+#[cfg(windows)]
+const ICON_SIZE: i32 = 16;
+const CLOSE_WIDTH: i32 = 20;
+
+// 与网页标签栏相同的配色 (0xRRGGBB)，开启高对比度时改用系统颜色 (见 palette)
+#[cfg(windows)]
+const BACKGROUND: u32 = 0x181825;
+#[cfg(windows)]
+const TAB_COLOR: u32 = 0x313244;
+#[cfg(windows)]
+const HOVER_COLOR: u32 = 0x45475a;
+#[cfg(windows)]
+const ACTIVE_COLOR: u32 = 0x89b4fa;
+#[cfg(windows)]
+const TEXT_COLOR: u32 = 0xcdd6f4;
+#[cfg(windows)]
+const ACTIVE_TEXT_COLOR: u32 = 0x1e1e2e;
+
+// 在 Win32_UI_Controls 中定义，为它单独开启 feature 不值得
+#[cfg(windows)]
+const WM_MOUSELEAVE: u32 = 0x02A3;
+
+#[cfg(windows)]
+struct Palette {
+    background: u32,
+    tab: u32,
+    hover: u32,
+    active: u32,
+    text: u32,
+    active_text: u32,
+}
+
+#[cfg(windows)]
+fn palette() -> Palette {
+    if !crate::system_prefs::get().high_contrast {
+        return Palette {
+            background: BACKGROUND,
+            tab: TAB_COLOR,
+            hover: HOVER_COLOR,
+            active: ACTIVE_COLOR,
+            text: TEXT_COLOR,
+            active_text: ACTIVE_TEXT_COLOR,
+        };
+    }
+    // GetSysColor 返回 0x00BBGGRR
+    let system = |index| {
+        let bgr = unsafe { GetSysColor(index) };
+        ((bgr & 0xFF) << 16) | (bgr & 0xFF00) | ((bgr >> 16) & 0xFF)
+    };
+    Palette {
+        background: system(COLOR_WINDOW),
+        tab: system(COLOR_BTNFACE),
+        hover: system(COLOR_BTNFACE),
+        active: system(COLOR_HIGHLIGHT),
+        text: system(COLOR_BTNTEXT),
+        active_text: system(COLOR_HIGHLIGHTTEXT),
+    }
+}
+
+#[cfg(windows)]
+const REFRESH_TIMER_ID: usize = 1;
+#[cfg(windows)]
+const REFRESH_INTERVAL_MS: u32 = 1000;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// 标签栏子窗口，未创建时为 0
+static STRIP: AtomicIsize = AtomicIsize::new(0);
+
+// 前端报告的位置 (客户区物理像素) 和缩放比例，子窗口创建前收到的也保留
+static PLACEMENT: Mutex<Option<Placement>> = Mutex::new(None);
+
+// 鼠标所在的标签序号，没有时为 -1
+#[cfg_attr(not(windows), allow(dead_code))]
+static HOVER: AtomicIsize = AtomicIsize::new(-1);
+
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    scale: f32,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Hit {
+    Tab(usize),
+    Close(usize),
+}
+
+/// 按设置创建或销毁标签栏 (启动和设置变化时调用)
+pub fn apply(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let enabled = crate::settings::get().appearance.native_tab_strip;
+    #[cfg(windows)]
+    {
+        let handle = app.clone();
+        let _ = app.run_on_main_thread(move || unsafe {
+            if enabled {
+                create(&handle);
+            } else {
+                destroy();
+            }
+        });
+    }
+    #[cfg(not(windows))]
+    if enabled {
+        warn!(target: "settings", "原生标签栏只支持 Windows");
+    }
+}
+
+/// 前端报告的标签区域 (客户区物理像素，scale 为 devicePixelRatio)
+pub fn place(x: i32, y: i32, width: i32, height: i32, scale: f32) {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    *PLACEMENT.lock().unwrap() = Some(Placement { x, y, width, height, scale });
+    let strip = STRIP.load(Ordering::SeqCst);
+    if strip == 0 {
+        return;
+    }
+    #[cfg(windows)]
+    unsafe {
+        // 放到网页之上
+        let _ = SetWindowPos(HWND(strip as *mut _), HWND_TOP, x, y, width, height, SWP_NOACTIVATE | SWP_SHOWWINDOW);
+        let _ = InvalidateRect(HWND(strip as *mut _), None, false);
+    }
+}
+
+/// 标签变化后重绘 (任意线程)
+pub fn refresh() {
+    let strip = STRIP.load(Ordering::SeqCst);
+    if strip == 0 {
+        return;
+    }
+    #[cfg(windows)]
+    unsafe {
+        let _ = InvalidateRect(HWND(strip as *mut _), None, false);
+    }
+}
+
+// count 个标签在宽 width 的标签栏中的水平范围 (左, 右)：最宽 TAB_MAX_WIDTH，放不下时平均缩窄
+#[cfg_attr(not(windows), allow(dead_code))]
+fn layout(count: usize, width: i32, scale: f32) -> Vec<(i32, i32)> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let gap = (TAB_GAP as f32 * scale) as i32;
+    let max = (TAB_MAX_WIDTH as f32 * scale) as i32;
+    let fit = (width - gap * (count as i32 - 1)) / count as i32;
+    let tab = fit.min(max).max(1);
+    (0..count as i32).map(|i| (i * (tab + gap), i * (tab + gap) + tab)).collect()
+}
+
+// x 处是哪个标签 (closable 时标签右侧 CLOSE_WIDTH 为关闭按钮)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn hit_test(ranges: &[(i32, i32)], x: i32, scale: f32, closable: bool) -> Option<Hit> {
+    let index = ranges.iter().position(|(left, right)| (*left..*right).contains(&x))?;
+    let close = (CLOSE_WIDTH as f32 * scale) as i32;
+    if closable && x >= ranges[index].1 - close {
+        return Some(Hit::Close(index));
+    }
+    Some(Hit::Tab(index))
+}
+
+// 要显示的标签 (hwnd, 文字)：有自定义名称时显示名称，否则显示窗口当前标题
+#[cfg_attr(not(windows), allow(dead_code))]
+fn tabs() -> Vec<(isize, String)> {
+    let Some(app) = APP.get() else { return Vec::new() };
+    let platform = crate::platform::current();
+    app.state::<WindowManager>()
+        .list()
+        .into_iter()
+        .map(|w| {
+            let text = w.label.unwrap_or_else(|| platform.title(w.hwnd));
+            (w.hwnd, crate::privacy::title(text))
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+struct Renderer {
+    target: ID2D1DCRenderTarget,
+    format: IDWriteTextFormat,
+    scale: f32,
+    text_scale: f32, // 系统的文本缩放 (见 system_prefs.rs)
+}
+
+#[cfg(windows)]
+thread_local! {
+    // Direct2D 资源只在主线程使用，缩放比例变化或设备丢失后重新创建
+    static RENDERER: RefCell<Option<Renderer>> = const { RefCell::new(None) };
+    // 标签的应用图标 (HICON)，窗口图标由应用持有，不需要销毁
+    static ICONS: RefCell<HashMap<isize, isize>> = RefCell::new(HashMap::new());
+}
+
+#[cfg(windows)]
+unsafe fn create(app: &AppHandle) {
+    if STRIP.load(Ordering::SeqCst) != 0 {
+        return;
+    }
+    let Some(host) = app.get_webview_window("main").and_then(|w| w.hwnd().ok()) else {
+        warn!(target: "settings", "无法获取主窗口，不能创建原生标签栏");
+        return;
+    };
+
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let class_name = w!("WindowHubTabStrip");
+    let wc = WNDCLASSW {
+        style: CS_HREDRAW | CS_VREDRAW,
+        lpfnWndProc: Some(strip_wnd_proc),
+        hInstance: instance.into(),
+        hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+
+    let strip = match CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        class_name,
+        w!(""),
+        WS_CHILD | WS_CLIPSIBLINGS,
+        0, 0, 0, 0,
+        HWND(host.0),
+        None,
+        instance,
+        None,
+    ) {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+            warn!(target: "settings", "创建原生标签栏失败: {:?}", e);
+            return;
+        }
+    };
+    STRIP.store(strip.0 as isize, Ordering::SeqCst);
+    SetTimer(strip, REFRESH_TIMER_ID, REFRESH_INTERVAL_MS, None);
+    crate::resources::register(crate::resources::ResourceKind::Timer, "native_tabs", 1, None, Some(strip.0 as isize));
+    info!(target: "settings", "已开启原生标签栏");
+
+    let placement = *PLACEMENT.lock().unwrap();
+    if let Some(p) = placement {
+        place(p.x, p.y, p.width, p.height, p.scale);
+    }
+}
+
+#[cfg(windows)]
+unsafe fn destroy() {
+    let strip = STRIP.swap(0, Ordering::SeqCst);
+    if strip != 0 {
+        let _ = DestroyWindow(HWND(strip as *mut _));
+        RENDERER.with(|r| r.borrow_mut().take());
+        ICONS.with(|icons| icons.borrow_mut().clear());
+        info!(target: "settings", "已关闭原生标签栏");
+    }
+}
+
+#[cfg(windows)]
+fn scale() -> f32 {
+    PLACEMENT.lock().unwrap().map(|p| p.scale).unwrap_or(1.0)
+}
+
+#[cfg(windows)]
+unsafe fn client_width(strip: HWND) -> i32 {
+    let mut client = RECT::default();
+    let _ = GetClientRect(strip, &mut client);
+    client.right
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn strip_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            paint(hwnd);
+            LRESULT(0)
+        }
+        WM_ERASEBKGND => LRESULT(1),
+        WM_TIMER if wparam.0 == REFRESH_TIMER_ID => {
+            let _ = InvalidateRect(hwnd, None, false);
+            LRESULT(0)
+        }
+        WM_MOUSEMOVE => {
+            let mut track = TRACKMOUSEEVENT {
+                cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+                dwFlags: TME_LEAVE,
+                hwndTrack: hwnd,
+                dwHoverTime: 0,
+            };
+            let _ = TrackMouseEvent(&mut track);
+            let x = (lparam.0 & 0xFFFF) as i16 as i32;
+            let ranges = layout(tabs().len(), client_width(hwnd), scale());
+            let hover = ranges.iter().position(|(left, right)| (*left..*right).contains(&x)).map_or(-1, |i| i as isize);
+            if HOVER.swap(hover, Ordering::SeqCst) != hover {
+                let _ = InvalidateRect(hwnd, None, false);
+            }
+            LRESULT(0)
+        }
+        WM_MOUSELEAVE => {
+            if HOVER.swap(-1, Ordering::SeqCst) != -1 {
+                let _ = InvalidateRect(hwnd, None, false);
+            }
+            LRESULT(0)
+        }
+        WM_LBUTTONDOWN | WM_MBUTTONUP => {
+            click(hwnd, (lparam.0 & 0xFFFF) as i16 as i32, msg == WM_MBUTTONUP);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            let _ = KillTimer(hwnd, REFRESH_TIMER_ID);
+            crate::resources::unregister_window(hwnd.0 as isize);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+// 左键点击标签切换，点击关闭按钮或中键点击关闭，左键按在空白处拖动主窗口
+#[cfg(windows)]
+unsafe fn click(strip: HWND, x: i32, middle: bool) {
+    let Some(app) = APP.get() else { return };
+    let tabs = tabs();
+    let closable = !crate::kiosk::is_enabled();
+    let ranges = layout(tabs.len(), client_width(strip), scale());
+    let (index, close) = match hit_test(&ranges, x, scale(), closable) {
+        Some(Hit::Close(i)) => (i, true),
+        Some(Hit::Tab(i)) => (i, middle),
+        // 空白处按下拖动主窗口 (自定义边框时标签栏兼作标题栏)
+        None if !middle => return crate::chrome::begin_drag(app),
+        None => return,
+    };
+    let tab = tabs[index].0;
+    if close {
+        if closable {
+            let _ = app.emit("tab-strip-close", tab);
+        }
+        return;
+    }
+    // 激活嵌入窗口会同步等待其他进程，不在消息处理中进行
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = crate::show_tab(app.clone(), app.state(), tab) {
+            warn!(target: "embed", "原生标签栏切换标签失败: {}", e);
+            return;
+        }
+        let _ = app.emit("tab-strip-activate", tab);
+        refresh();
+    });
+}
+
+#[cfg(windows)]
+unsafe fn paint(strip: HWND) {
+    let mut ps = PAINTSTRUCT::default();
+    let hdc = BeginPaint(strip, &mut ps);
+    let mut client = RECT::default();
+    let _ = GetClientRect(strip, &mut client);
+
+    let scale = scale();
+    let tabs = tabs();
+    let ranges = layout(tabs.len(), client.right, scale);
+    let active = crate::host_layout::active();
+    let hover = HOVER.load(Ordering::SeqCst);
+    let closable = !crate::kiosk::is_enabled();
+    let palette = palette();
+    let text_scale = crate::system_prefs::get().text_scale;
+    crate::accessibility::name_strip(strip, &tabs, active);
+    let px = |v: i32| (v as f32 * scale) as i32;
+
+    fill(hdc, &client, palette.background);
+    let top = client.top + px(4);
+    let icon_size = px(ICON_SIZE);
+    for (i, ((tab, _), (left, right))) in tabs.iter().zip(&ranges).enumerate() {
+        let color = if *tab == active {
+            palette.active
+        } else if hover == i as isize {
+            palette.hover
+        } else {
+            palette.tab
+        };
+        fill(hdc, &RECT { left: *left, top, right: *right, bottom: client.bottom }, color);
+        if let Some(icon) = icon_of(*tab) {
+            let y = top + (client.bottom - top - icon_size) / 2;
+            let _ = DrawIconEx(hdc, left + px(PADDING), y, HICON(icon as *mut _), icon_size, icon_size, 0, None, DI_NORMAL);
+        }
+    }
+
+    let drawn = RENDERER.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        if cell.as_ref().map_or(true, |r| r.scale != scale || r.text_scale != text_scale) {
+            *cell = Renderer::new(scale, text_scale).map_err(|e| warn!(target: "settings", "创建 Direct2D 资源失败: {}", e)).ok();
+        }
+        let Some(renderer) = cell.as_ref() else { return Ok(()) };
+        renderer.draw(hdc, &client, &tabs, &ranges, top, active, closable, &palette)
+    });
+    if let Err(e) = drawn {
+        // 设备丢失等，下次重绘时重新创建
+        warn!(target: "settings", "绘制原生标签栏失败: {}", e);
+        RENDERER.with(|r| r.borrow_mut().take());
+    }
+
+    let _ = EndPaint(strip, &ps);
+}
+
+#[cfg(windows)]
+unsafe fn fill(hdc: windows::Win32::Graphics::Gdi::HDC, rect: &RECT, rgb: u32) {
+    let bgr = ((rgb & 0xFF) << 16) | (rgb & 0xFF00) | ((rgb >> 16) & 0xFF);
+    let brush = CreateSolidBrush(COLORREF(bgr));
+    FillRect(hdc, rect, brush);
+    let _ = DeleteObject(brush);
+}
+
+// 窗口的小图标 (见 Platform::icon_handle)，按标签缓存
+#[cfg(windows)]
+unsafe fn icon_of(tab: isize) -> Option<isize> {
+    if let Some(icon) = ICONS.with(|icons| icons.borrow().get(&tab).copied()) {
+        return (icon != 0).then_some(icon);
+    }
+    let icon = crate::platform::current().icon_handle(tab);
+    ICONS.with(|icons| {
+        let mut icons = icons.borrow_mut();
+        // 顺便清掉已关闭标签的缓存
+        icons.retain(|h, _| IsWindow(HWND(*h as *mut _)).as_bool());
+        icons.insert(tab, icon);
+    });
+    (icon != 0).then_some(icon)
+}
+
+#[cfg(windows)]
+impl Renderer {
+    unsafe fn new(scale: f32, text_scale: f32) -> windows::core::Result<Renderer> {
+        let factory: ID2D1Factory = D2D1CreateFactory(D2D1_FACTORY_TYPE_SINGLE_THREADED, None)?;
+        // DPI 固定为 96，坐标直接使用物理像素
+        let properties = D2D1_RENDER_TARGET_PROPERTIES {
+            r#type: D2D1_RENDER_TARGET_TYPE_DEFAULT,
+            pixelFormat: D2D1_PIXEL_FORMAT { format: DXGI_FORMAT_B8G8R8A8_UNORM, alphaMode: D2D1_ALPHA_MODE_IGNORE },
+            dpiX: 96.0,
+            dpiY: 96.0,
+            usage: D2D1_RENDER_TARGET_USAGE_NONE,
+            minLevel: D2D1_FEATURE_LEVEL_DEFAULT,
+        };
+        let target = factory.CreateDCRenderTarget(&properties)?;
+
+        let dwrite: IDWriteFactory = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?;
+        let format = dwrite.CreateTextFormat(
+            w!("Microsoft YaHei UI"),
+            None::<&IDWriteFontCollection>,
+            DWRITE_FONT_WEIGHT_NORMAL,
+            DWRITE_FONT_STYLE_NORMAL,
+            DWRITE_FONT_STRETCH_NORMAL,
+            13.0 * scale * text_scale,
+            w!("zh-cn"),
+        )?;
+        format.SetWordWrapping(DWRITE_WORD_WRAPPING_NO_WRAP)?;
+        format.SetParagraphAlignment(DWRITE_PARAGRAPH_ALIGNMENT_CENTER)?;
+        // 放不下的标题以省略号结尾
+        let ellipsis = dwrite.CreateEllipsisTrimmingSign(&format)?;
+        let trimming = DWRITE_TRIMMING { granularity: DWRITE_TRIMMING_GRANULARITY_CHARACTER, delimiter: 0, delimiterCount: 0 };
+        format.SetTrimming(&trimming, &ellipsis)?;
+        Ok(Renderer { target, format, scale, text_scale })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn draw(
+        &self,
+        hdc: windows::Win32::Graphics::Gdi::HDC,
+        client: &RECT,
+        tabs: &[(isize, String)],
+        ranges: &[(i32, i32)],
+        top: i32,
+        active: isize,
+        closable: bool,
+        palette: &Palette,
+    ) -> windows::core::Result<()> {
+        let px = |v: i32| v as f32 * self.scale;
+        self.target.BindDC(hdc, client)?;
+        self.target.BeginDraw();
+        let text = self.target.CreateSolidColorBrush(&color(palette.text), None)?;
+        let active_text = self.target.CreateSolidColorBrush(&color(palette.active_text), None)?;
+        for ((tab, title), (left, right)) in tabs.iter().zip(ranges) {
+            let brush = if *tab == active { &active_text } else { &text };
+            let close = if closable { px(CLOSE_WIDTH) } else { 0.0 };
+            let title_rect = D2D_RECT_F {
+                left: *left as f32 + px(PADDING + ICON_SIZE) + px(PADDING) / 2.0,
+                top: top as f32,
+                right: *right as f32 - close - px(PADDING) / 2.0,
+                bottom: client.bottom as f32,
+            };
+            let title: Vec<u16> = title.encode_utf16().collect();
+            self.target.DrawText(&title, &self.format, &title_rect, brush, D2D1_DRAW_TEXT_OPTIONS_CLIP, DWRITE_MEASURING_MODE_NATURAL);
+            if closable {
+                let close_rect = D2D_RECT_F { left: *right as f32 - close, top: top as f32, right: *right as f32, bottom: client.bottom as f32 };
+                let glyph: Vec<u16> = "✕".encode_utf16().collect();
+                self.target.DrawText(&glyph, &self.format, &close_rect, brush, D2D1_DRAW_TEXT_OPTIONS_CLIP, DWRITE_MEASURING_MODE_NATURAL);
+            }
+        }
+        self.target.EndDraw(None, None)
+    }
+}
+
+#[cfg(windows)]
+fn color(rgb: u32) -> D2D1_COLOR_F {
+    let channel = |shift: u32| ((rgb >> shift) & 0xFF) as f32 / 255.0;
+    D2D1_COLOR_F { r: channel(16), g: channel(8), b: channel(0), a: 1.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tabs_shrink_to_fit_and_close_button_is_on_the_right() {
+        // 放得下时按最大宽度排列
+        assert_eq!(layout(2, 1000, 1.0), vec![(0, 200), (208, 408)]);
+        // 放不下时平均缩窄
+        assert_eq!(layout(4, 424, 1.0), vec![(0, 100), (108, 208), (216, 316), (324, 424)]);
+        // 150% 缩放
+        assert_eq!(layout(1, 1000, 1.5), vec![(0, 300)]);
+
+        let ranges = layout(2, 1000, 1.0);
+        assert_eq!(hit_test(&ranges, 50, 1.0, true), Some(Hit::Tab(0)));
+        assert_eq!(hit_test(&ranges, 390, 1.0, true), Some(Hit::Close(1)));
+        // 展台模式下没有关闭按钮
+        assert_eq!(hit_test(&ranges, 390, 1.0, false), Some(Hit::Tab(1)));
+        // 标签之间的空隙
+        assert_eq!(hit_test(&ranges, 204, 1.0, true), None);
+    }
+}
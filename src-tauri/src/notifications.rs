@@ -0,0 +1,154 @@
+// 嵌入应用的通知
+// 嵌入应用发出的 Toast 通知只出现在通知中心，看不出来自哪个标签
+// 通过 UserNotificationListener 读取通知 (需要用户在系统设置中允许访问通知)，
+// 按应用的 AUMID / 显示名称与标签的 EXE 对应，发送 tab-notification 事件，前端显示未读标记
+// 通知变化事件只对打包应用可用，这里定期查询，比较通知 ID 找出新通知
+
+use serde::Serialize;
+use std::path::Path;
+
+#[cfg(windows)]
+use std::collections::HashSet;
+#[cfg(windows)]
+use std::time::Duration;
+#[cfg(windows)]
+use tauri::{AppHandle, Emitter, Manager};
+#[cfg(windows)]
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use windows::{
+    UI::Notifications::Management::{UserNotificationListener, UserNotificationListenerAccessStatus},
+    UI::Notifications::{KnownNotificationBindings, NotificationKinds, UserNotification},
+    Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED},
+};
+
+#[cfg(windows)]
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// tab-notification 事件的载荷
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TabNotification {
+    pub hwnd: isize,
+    pub title: String,
+    pub body: String,
+}
+
+/// 开始监听通知 (不允许访问通知时只记录日志)
+#[cfg(windows)]
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || unsafe {
+        let _ = RoInitialize(RO_INIT_MULTITHREADED);
+        if let Err(e) = watch(&app) {
+            warn!(target: "notify", "无法读取系统通知: {}", e);
+        }
+    });
+}
+
+#[cfg(not(windows))]
+pub fn start(_app: &tauri::AppHandle) {}
+
+#[cfg(windows)]
+fn watch(app: &AppHandle) -> windows::core::Result<()> {
+    let listener = UserNotificationListener::Current()?;
+    let status = listener.RequestAccessAsync()?.get()?;
+    if status != UserNotificationListenerAccessStatus::Allowed {
+        info!(target: "notify", "未获得通知访问权限，不显示标签通知");
+        return Ok(());
+    }
+
+    // 启动前已有的通知不算新通知
+    let mut seen: HashSet<u32> = HashSet::new();
+    let mut first = true;
+    loop {
+        let notifications = listener.GetNotificationsAsync(NotificationKinds::Toast)?.get()?;
+        let mut current = HashSet::new();
+        for notification in &notifications {
+            let Ok(id) = notification.Id() else { continue };
+            current.insert(id);
+            // 演示模式下不显示未读标记
+            if first || seen.contains(&id) || crate::presentation::is_active() {
+                continue;
+            }
+            if let Some(event) = to_tab_notification(app, &notification) {
+                info!(target: "notify", "标签通知: hwnd={}, {}", event.hwnd, event.title);
+                let _ = app.emit("tab-notification", event);
+            }
+        }
+        seen = current;
+        first = false;
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+// 找到通知对应的标签，不属于任何标签时返回 None
+#[cfg(windows)]
+fn to_tab_notification(app: &AppHandle, notification: &UserNotification) -> Option<TabNotification> {
+    let app_info = notification.AppInfo().ok()?;
+    let aumid = app_info.AppUserModelId().map(|s| s.to_string()).unwrap_or_default();
+    let display_name = app_info
+        .DisplayInfo()
+        .and_then(|d| d.DisplayName())
+        .map(|s| s.to_string())
+        .unwrap_or_default();
+
+    let tabs: Vec<(isize, String)> = app
+        .state::<crate::window_manager::WindowManager>()
+        .list()
+        .into_iter()
+        .filter_map(|w| Some((w.hwnd, w.exe_path?)))
+        .collect();
+    let hwnd = match_tab(&aumid, &display_name, &tabs)?;
+
+    // ToastGeneric 模板的第一行是标题，其余为正文
+    let texts: Vec<String> = notification
+        .Notification()
+        .and_then(|n| n.Visual())
+        .and_then(|v| v.GetBinding(&KnownNotificationBindings::ToastGeneric()?))
+        .and_then(|b| b.GetTextElements())
+        .map(|elements| elements.into_iter().filter_map(|t| t.Text().ok()).map(|t| t.to_string()).collect())
+        .unwrap_or_default();
+    let mut texts = texts.into_iter();
+    let title = texts.next().unwrap_or(display_name);
+    let body = texts.collect::<Vec<_>>().join("\n");
+    Some(TabNotification { hwnd, title, body })
+}
+
+/// 按 AUMID 和显示名称找到通知所属的标签，tabs 为 (句柄, EXE 路径)
+/// 未打包的应用 AUMID 通常是 EXE 路径或包含程序名 (如 "Microsoft.Office.OUTLOOK.EXE.15")
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn match_tab(aumid: &str, display_name: &str, tabs: &[(isize, String)]) -> Option<isize> {
+    let aumid = aumid.to_lowercase();
+    let display_name = display_name.to_lowercase();
+    // 完整路径优先，其次是程序名
+    tabs.iter()
+        .find(|(_, exe)| !aumid.is_empty() && exe.to_lowercase() == aumid)
+        .or_else(|| {
+            tabs.iter().find(|(_, exe)| {
+                let Some(stem) = Path::new(exe).file_stem().map(|s| s.to_string_lossy().to_lowercase()) else {
+                    return false;
+                };
+                !stem.is_empty() && (aumid.contains(&stem) || display_name == stem)
+            })
+        })
+        .map(|(hwnd, _)| *hwnd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_notifications_to_tabs_by_exe() {
+        let tabs = vec![
+            (0x10, "C:/Program Files/Microsoft Office/OUTLOOK.EXE".to_string()),
+            (0x20, "C:/Users/me/AppData/Local/Feishu/Feishu.exe".to_string()),
+        ];
+        assert_eq!(match_tab("Microsoft.Office.OUTLOOK.EXE.15", "Outlook", &tabs), Some(0x10));
+        assert_eq!(match_tab("C:/Users/me/AppData/Local/Feishu/Feishu.exe", "", &tabs), Some(0x20));
+        assert_eq!(match_tab("com.example.app", "Feishu", &tabs), Some(0x20));
+        assert_eq!(match_tab("Microsoft.WindowsStore_8wekyb3d8bbwe!App", "Microsoft Store", &tabs), None);
+    }
+}
@@ -0,0 +1,180 @@
+// 识别嵌入窗口中的文字 (ocr_window)
+// 用 PrintWindow 截取窗口画面 (子窗口也能截到)，可以只取其中一块区域，
+// 交给系统自带的 Windows.Media.Ocr 识别，返回整段文字和每行、每个词的位置。
+// 位置相对于窗口左上角 (不是屏幕坐标)，前端直接叠加在标签画面上高亮；
+// 识别语言跟随系统的用户语言，没有安装对应的 OCR 语言包时返回错误
+
+use serde::Serialize;
+
+use crate::window_manager::WindowRect;
+
+#[cfg(windows)]
+use tracing::info;
+
+#[cfg(windows)]
+use windows::{
+    Foundation::Rect,
+    Graphics::Imaging::{BitmapPixelFormat, SoftwareBitmap},
+    Media::Ocr::OcrEngine,
+    Storage::Streams::DataWriter,
+    Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED},
+};
+
+/// 识别出的一个词
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OcrWord {
+    pub text: String,
+    pub rect: WindowRect,
+}
+
+/// 识别出的一行，rect 为行内所有词的外接矩形
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OcrLine {
+    pub text: String,
+    pub rect: WindowRect,
+    pub words: Vec<OcrWord>,
+}
+
+/// ocr_window 的结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OcrText {
+    pub text: String,
+    pub lines: Vec<OcrLine>,
+}
+
+/// 识别窗口 (或窗口中 region 区域) 的文字，region 相对于窗口左上角
+pub fn recognize(hwnd: isize, region: Option<WindowRect>) -> Result<OcrText, String> {
+    let platform = crate::platform::current();
+    if !platform.is_window(hwnd) {
+        return Err("窗口不存在".to_string());
+    }
+    let (width, height, pixels) = platform.capture(hwnd).ok_or("无法截取窗口画面")?;
+    let area = clamp_region(width, height, region).ok_or("识别区域不在窗口内")?;
+    let pixels = crop(&pixels, width, area);
+    let mut result = run(area.width(), area.height(), &pixels)?;
+    for line in &mut result.lines {
+        line.rect = offset(line.rect, area.left, area.top);
+        for word in &mut line.words {
+            word.rect = offset(word.rect, area.left, area.top);
+        }
+    }
+    Ok(result)
+}
+
+// 把区域限制在窗口画面内，没有指定区域时为整个窗口，交集为空时为 None
+fn clamp_region(width: i32, height: i32, region: Option<WindowRect>) -> Option<WindowRect> {
+    let full = WindowRect { left: 0, top: 0, right: width, bottom: height };
+    let region = region.unwrap_or(full);
+    let area = WindowRect {
+        left: region.left.max(0),
+        top: region.top.max(0),
+        right: region.right.min(width),
+        bottom: region.bottom.min(height),
+    };
+    (area.width() > 0 && area.height() > 0).then_some(area)
+}
+
+// 从自上而下的 BGRA 画面中复制出一块区域
+fn crop(pixels: &[u8], width: i32, area: WindowRect) -> Vec<u8> {
+    let stride = width as usize * 4;
+    let row = area.width() as usize * 4;
+    let mut out = Vec::with_capacity(row * area.height() as usize);
+    for y in area.top..area.bottom {
+        let start = y as usize * stride + area.left as usize * 4;
+        out.extend_from_slice(&pixels[start..start + row]);
+    }
+    out
+}
+
+fn offset(rect: WindowRect, dx: i32, dy: i32) -> WindowRect {
+    WindowRect { left: rect.left + dx, top: rect.top + dy, right: rect.right + dx, bottom: rect.bottom + dy }
+}
+
+// 所有词的外接矩形
+#[cfg_attr(not(windows), allow(dead_code))]
+fn bounds(words: &[OcrWord]) -> WindowRect {
+    let mut iter = words.iter().map(|w| w.rect);
+    let Some(first) = iter.next() else { return WindowRect::default() };
+    iter.fold(first, |a, b| WindowRect {
+        left: a.left.min(b.left),
+        top: a.top.min(b.top),
+        right: a.right.max(b.right),
+        bottom: a.bottom.max(b.bottom),
+    })
+}
+
+#[cfg(windows)]
+fn run(width: i32, height: i32, pixels: &[u8]) -> Result<OcrText, String> {
+    unsafe {
+        let _ = RoInitialize(RO_INIT_MULTITHREADED);
+    }
+    let engine = OcrEngine::TryCreateFromUserProfileLanguages()
+        .map_err(|_| "没有可用的文字识别语言，请在系统设置中安装语言包".to_string())?;
+    let max = OcrEngine::MaxImageDimension().map_err(|e| e.to_string())? as i32;
+    if width > max || height > max {
+        return Err(format!("识别区域太大，宽高不能超过 {} 像素", max));
+    }
+
+    let bitmap = (|| {
+        let writer = DataWriter::new()?;
+        writer.WriteBytes(pixels)?;
+        SoftwareBitmap::CreateCopyFromBuffer(&writer.DetachBuffer()?, BitmapPixelFormat::Bgra8, width, height)
+    })()
+    .map_err(|e| format!("无法创建位图: {}", e))?;
+    let result = engine
+        .RecognizeAsync(&bitmap)
+        .and_then(|op| op.get())
+        .map_err(|e| format!("文字识别失败: {}", e))?;
+
+    let read = || -> windows::core::Result<OcrText> {
+        let mut lines = Vec::new();
+        let view = result.Lines()?;
+        for i in 0..view.Size()? {
+            let line = view.GetAt(i)?;
+            let word_view = line.Words()?;
+            let mut words = Vec::new();
+            for j in 0..word_view.Size()? {
+                let word = word_view.GetAt(j)?;
+                words.push(OcrWord { text: word.Text()?.to_string_lossy(), rect: to_rect(word.BoundingRect()?) });
+            }
+            lines.push(OcrLine { text: line.Text()?.to_string_lossy(), rect: bounds(&words), words });
+        }
+        Ok(OcrText { text: result.Text()?.to_string_lossy(), lines })
+    };
+    let text = read().map_err(|e| format!("文字识别失败: {}", e))?;
+    info!(target: "embed", "文字识别完成: {}x{}, {} 行", width, height, text.lines.len());
+    Ok(text)
+}
+
+#[cfg(not(windows))]
+fn run(_width: i32, _height: i32, _pixels: &[u8]) -> Result<OcrText, String> {
+    Err("当前平台不支持文字识别".to_string())
+}
+
+#[cfg(windows)]
+fn to_rect(rect: Rect) -> WindowRect {
+    WindowRect {
+        left: rect.X.floor() as i32,
+        top: rect.Y.floor() as i32,
+        right: (rect.X + rect.Width).ceil() as i32,
+        bottom: (rect.Y + rect.Height).ceil() as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_is_clamped_and_cropped() {
+        let rect = |left, top, right, bottom| WindowRect { left, top, right, bottom };
+        assert_eq!(clamp_region(4, 3, None), Some(rect(0, 0, 4, 3)));
+        assert_eq!(clamp_region(4, 3, Some(rect(-5, 1, 2, 10))), Some(rect(0, 1, 2, 3)));
+        assert_eq!(clamp_region(4, 3, Some(rect(5, 0, 9, 3))), None);
+
+        // 每个像素的 4 个字节都是它的序号
+        let pixels: Vec<u8> = (0..12u8).flat_map(|i| [i; 4]).collect();
+        let cropped = crop(&pixels, 4, rect(1, 1, 3, 3));
+        assert_eq!(cropped, [5, 5, 5, 5, 6, 6, 6, 6, 9, 9, 9, 9, 10, 10, 10, 10]);
+    }
+}
@@ -0,0 +1,135 @@
+// 长时间运行的操作 (启动应用、关闭请求、重启标签、关闭所有标签)
+// 这些命令要等待几秒到几十秒，命令本身立即返回操作 ID，结果通过 operation-finished 事件送达
+// (载荷为 OperationFinished，前端的 runOperation 把两者合成一个 Promise)：
+// - cancel_operation(op_id) 取消：等待中的操作不再继续，发送 status 为 cancelled 的 operation-finished
+// - 启动类操作 (launch_app、restart_tab、重新打开标签、启动固定的应用、恢复固定的标签、脚本的 launch) 排队依次执行：
+//   它们都按"新出现的窗口"找应用的窗口，同时进行会把一个应用的窗口嵌入到另一个操作中
+// 取消只是停止等待，已经发出的关闭请求、已经启动的进程不会撤回
+// 启动应用的进度通过 launch-progress 事件 (LaunchProgress) 送达，前端据此更新提示，等待期间可以随时取消
+
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+// 进行中的操作 (ID, 类型, 任务)
+static RUNNING: Mutex<Vec<(u64, &'static str, JoinHandle<()>)>> = Mutex::new(Vec::new());
+
+// 启动类操作的队列 (tokio 的 Mutex 按请求顺序获得)
+static LAUNCH_QUEUE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// 进行中的操作
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationInfo {
+    pub op_id: u64,
+    pub kind: &'static str,
+}
+
+/// operation-finished 事件的载荷，status 为 done / failed / cancelled
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationFinished {
+    pub op_id: u64,
+    pub kind: &'static str,
+    pub status: &'static str,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+/// launch-progress 事件的载荷，stage 为 spawned / waiting-for-window / found (之后的 embedding 阶段由前端在嵌入窗口时显示)
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchProgress {
+    pub op_id: u64,
+    pub stage: &'static str,
+}
+
+/// 报告操作的进度，不属于任何操作时不发送
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Clone, Default)]
+pub struct Progress {
+    target: Option<(AppHandle, u64)>,
+}
+
+impl Progress {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn stage(&self, stage: &'static str) {
+        if let Some((app, op_id)) = &self.target {
+            let _ = app.emit("launch-progress", LaunchProgress { op_id: *op_id, stage });
+        }
+    }
+
+    /// 发送其他类型操作的进度事件 (载荷由操作 ID 生成)
+    pub fn emit<T: Serialize + Clone>(&self, event: &str, payload: impl FnOnce(u64) -> T) {
+        if let Some((app, op_id)) = &self.target {
+            let _ = app.emit(event, payload(*op_id));
+        }
+    }
+}
+
+/// 在后台执行操作，返回操作 ID；queued 为 true 时与其他启动类操作排队依次执行
+/// task 收到报告该操作进度的 Progress
+pub fn start<T, F>(app: &AppHandle, kind: &'static str, queued: bool, task: impl FnOnce(Progress) -> F) -> u64
+where
+    T: Serialize,
+    F: Future<Output = Result<T, String>> + Send + 'static,
+{
+    let op_id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let task = task(Progress { target: Some((app.clone(), op_id)) });
+    let app = app.clone();
+    // 先登记再让任务运行，任务很快结束时也能找到自己的记录
+    let mut running = RUNNING.lock().unwrap();
+    let handle = tauri::async_runtime::spawn(async move {
+        let result = if queued {
+            let _turn = LAUNCH_QUEUE.lock().await;
+            task.await
+        } else {
+            task.await
+        };
+        let (status, result, error) = match result {
+            Ok(value) => ("done", serde_json::to_value(value).ok(), None),
+            Err(e) => ("failed", None, Some(e)),
+        };
+        finish(&app, OperationFinished { op_id, kind, status, result, error });
+    });
+    running.push((op_id, kind, handle));
+    op_id
+}
+
+/// 取消操作，操作已经结束时返回 false
+pub fn cancel(app: &AppHandle, op_id: u64) -> bool {
+    let entry = {
+        let mut running = RUNNING.lock().unwrap();
+        running.iter().position(|(id, ..)| *id == op_id).map(|i| running.remove(i))
+    };
+    let Some((_, kind, handle)) = entry else { return false };
+    handle.abort();
+    info!(target: "launcher", "取消操作: {} ({})", op_id, kind);
+    let _ = app.emit(
+        "operation-finished",
+        OperationFinished { op_id, kind, status: "cancelled", result: None, error: Some("操作已取消".to_string()) },
+    );
+    true
+}
+
+/// 进行中的操作
+pub fn list() -> Vec<OperationInfo> {
+    RUNNING.lock().unwrap().iter().map(|(op_id, kind, _)| OperationInfo { op_id: *op_id, kind }).collect()
+}
+
+// 操作结束：已经被取消 (记录已移除) 时不再发送结果
+fn finish(app: &AppHandle, finished: OperationFinished) {
+    let removed = {
+        let mut running = RUNNING.lock().unwrap();
+        let before = running.len();
+        running.retain(|(id, ..)| *id != finished.op_id);
+        running.len() != before
+    };
+    if removed {
+        let _ = app.emit("operation-finished", finished);
+    }
+}
@@ -0,0 +1,432 @@
+// 搜索框 (Ctrl+K) 的结果
+// 一次查询返回多类结果，kind 决定前端的操作：
+// - calculation: 算式的结果 ("2*(3+4)"、"sqrt(2)")，复制到剪贴板
+// - command: "> 命令" 在新的控制台窗口中执行并嵌入，path 为命令本身，调用 run_shell_command
+// - action: 系统操作 (锁定、睡眠、清空回收站)，调用 run_system_action
+// - window: 打开但还没有嵌入的窗口，直接嵌入
+// - app: 已安装的应用和网页应用 (app_index.rs)，启动并嵌入
+// - file: 文件和文件夹 (file_search.rs)，用关联的程序打开并嵌入
+// - plugin: 插件的结果 (plugins.rs)，调用 invoke_plugin_action
+// 前三类来自 PROVIDERS (Provider，只看查询本身，立即返回)，排在最前面；其他由 collect 合并
+// 没有输入时只列出应用，以 > 开头时只有命令
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::file_search::FileResult;
+use crate::plugins::PluginItem;
+use crate::AppInfo;
+
+// 每类结果的条数上限
+const MAX_WINDOWS: usize = 5;
+const MAX_APPS: usize = 10;
+pub const MAX_FILES: usize = 10;
+
+// 命令的前缀
+const COMMAND_PREFIX: char = '>';
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultKind {
+    Calculation,
+    Command,
+    Action,
+    Window,
+    App,
+    File,
+    Plugin,
+}
+
+/// path 随 kind 不同：计算结果为数值，系统操作为操作名 (SystemAction)，插件结果为插件给出的 ID，其他为启动或打开的路径
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub kind: ResultKind,
+    pub name: String,
+    pub path: String,
+    pub args: Vec<String>,
+    pub is_dir: bool,
+    pub hwnd: Option<isize>,
+    pub plugin: Option<String>,
+    pub detail: Option<String>, // 代替路径显示的说明
+}
+
+impl SearchResult {
+    fn new(kind: ResultKind, name: String, path: String) -> SearchResult {
+        SearchResult { kind, name, path, args: Vec::new(), is_dir: false, hwnd: None, plugin: None, detail: None }
+    }
+}
+
+/// 搜索框的结果来源，只根据查询文本给出结果
+pub trait Provider: Sync {
+    fn search(&self, query: &str) -> Vec<SearchResult>;
+}
+
+static PROVIDERS: [&dyn Provider; 3] = [&Calculator, &Shell, &SystemActions];
+
+/// 各 Provider 的结果
+pub fn quick_results(query: &str) -> Vec<SearchResult> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    PROVIDERS.iter().flat_map(|provider| provider.search(query)).collect()
+}
+
+/// 是否还需要查找窗口、应用和文件 (命令只执行，不搜索)
+pub fn searches_everything(query: &str) -> bool {
+    !query.trim_start().starts_with(COMMAND_PREFIX)
+}
+
+/// 系统操作 (run_system_action)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemAction {
+    Lock,
+    Sleep,
+    EmptyRecycleBin,
+}
+
+// 系统操作 (SystemAction 序列化后的名称)、显示的名称和搜索关键词
+const SYSTEM_ACTIONS: [(&str, &str, &[&str]); 3] = [
+    ("lock", "锁定计算机", &["lock", "锁定", "锁屏"]),
+    ("sleep", "睡眠", &["sleep", "睡眠", "休眠"]),
+    ("empty_recycle_bin", "清空回收站", &["empty recycle bin", "recycle bin", "回收站", "清空"]),
+];
+
+/// 执行系统操作
+pub fn run_system_action(action: SystemAction) -> Result<(), String> {
+    info!(target: "launcher", "执行系统操作: {:?}", action);
+    system::run(action)
+}
+
+// 算式
+struct Calculator;
+
+impl Provider for Calculator {
+    fn search(&self, query: &str) -> Vec<SearchResult> {
+        let expr = query.strip_prefix('=').unwrap_or(query).trim();
+        // 单独的数字不算算式
+        if expr.parse::<f64>().is_ok() {
+            return Vec::new();
+        }
+        match evaluate(expr) {
+            Some(value) => {
+                let value = format_number(value);
+                vec![SearchResult::new(ResultKind::Calculation, format!("{} = {}", expr, value), value)]
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+// "> 命令"：用 conhost 打开控制台 (不交给 Windows Terminal，终端的标签页无法单独嵌入)，/K 执行后保留窗口
+struct Shell;
+
+impl Provider for Shell {
+    fn search(&self, query: &str) -> Vec<SearchResult> {
+        let Some(command) = query.strip_prefix(COMMAND_PREFIX).map(str::trim).filter(|c| !c.is_empty()) else {
+            return Vec::new();
+        };
+        let mut result = SearchResult::new(ResultKind::Command, command.to_string(), command.to_string());
+        result.detail = Some("conhost.exe".to_string());
+        vec![result]
+    }
+}
+
+/// 控制台的命令行 (conhost.exe 之后的部分)
+/// 命令原样接在 /K 后面：作为单独的参数传递时其中的引号会按 MSVC 的规则转义为 \"，cmd.exe 不认识这种转义
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn shell_command_line(command: &str) -> String {
+    format!("cmd.exe /K {}", command)
+}
+
+/// 在新的控制台窗口中执行命令
+#[cfg(windows)]
+pub fn spawn_shell(command: &str) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+
+    std::process::Command::new("conhost.exe")
+        .raw_arg(shell_command_line(command))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+struct SystemActions;
+
+impl Provider for SystemActions {
+    fn search(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.to_lowercase();
+        if query.chars().count() < 2 {
+            return Vec::new();
+        }
+        SYSTEM_ACTIONS
+            .iter()
+            .filter(|(_, name, keywords)| name.contains(&query) || keywords.iter().any(|k| k.starts_with(&query)))
+            .map(|(id, name, _)| SearchResult::new(ResultKind::Action, name.to_string(), id.to_string()))
+            .collect()
+    }
+}
+
+// 算式求值：+ - * / % ^、括号、常数 pi / e 和常用函数，结果不是有限数时为 None
+fn evaluate(expr: &str) -> Option<f64> {
+    let mut parser = Parser { chars: expr.chars().filter(|c| !c.is_whitespace()).collect(), pos: 0 };
+    let value = parser.expr()?;
+    (parser.pos == parser.chars.len() && value.is_finite()).then_some(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let matched = self.peek() == Some(c);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expr(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') || self.eat('×') {
+                value *= self.unary()?;
+            } else if self.eat('/') || self.eat('÷') {
+                value /= self.unary()?;
+            } else if self.eat('%') {
+                value %= self.unary()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    // 负号的优先级低于乘方 (-2^2 = -4)
+    fn unary(&mut self) -> Option<f64> {
+        if self.eat('-') {
+            return Some(-self.unary()?);
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        let base = self.primary()?;
+        if self.eat('^') {
+            return Some(base.powf(self.unary()?));
+        }
+        Some(base)
+    }
+
+    fn primary(&mut self) -> Option<f64> {
+        if self.eat('(') {
+            let value = self.expr()?;
+            return self.eat(')').then_some(value);
+        }
+        let start = self.pos;
+        if self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+                self.pos += 1;
+            }
+            return self.chars[start..self.pos].iter().collect::<String>().parse().ok();
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect::<String>().to_lowercase();
+        match name.as_str() {
+            "pi" => return Some(std::f64::consts::PI),
+            "e" => return Some(std::f64::consts::E),
+            "" => return None,
+            _ => {}
+        }
+        if !self.eat('(') {
+            return None;
+        }
+        let arg = self.expr()?;
+        if !self.eat(')') {
+            return None;
+        }
+        Some(match name.as_str() {
+            "sqrt" => arg.sqrt(),
+            "abs" => arg.abs(),
+            "sin" => arg.sin(),
+            "cos" => arg.cos(),
+            "tan" => arg.tan(),
+            "ln" => arg.ln(),
+            "log" => arg.log10(),
+            "round" => arg.round(),
+            "floor" => arg.floor(),
+            "ceil" => arg.ceil(),
+            _ => return None,
+        })
+    }
+}
+
+// 整数按整数显示，其他最多保留 10 位小数
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    let text = format!("{:.10}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(windows)]
+mod system {
+    use super::SystemAction;
+    use windows::Win32::System::Power::SetSuspendState;
+    use windows::Win32::System::Shutdown::LockWorkStation;
+    use windows::Win32::UI::Shell::SHEmptyRecycleBinW;
+
+    pub fn run(action: SystemAction) -> Result<(), String> {
+        unsafe {
+            match action {
+                SystemAction::Lock => LockWorkStation().map_err(|e| format!("锁定失败: {}", e)),
+                SystemAction::Sleep => {
+                    if SetSuspendState(false, false, false).as_bool() {
+                        Ok(())
+                    } else {
+                        Err("进入睡眠失败".to_string())
+                    }
+                }
+                // 由系统询问是否确认
+                SystemAction::EmptyRecycleBin => {
+                    SHEmptyRecycleBinW(None, None, 0).map_err(|e| format!("清空回收站失败: {}", e))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod system {
+    use super::SystemAction;
+
+    pub fn run(_action: SystemAction) -> Result<(), String> {
+        Err("仅支持 Windows".to_string())
+    }
+}
+
+/// 打开的窗口 (句柄, 标题, 程序路径)
+pub type OpenWindow = (isize, String, String);
+
+/// 按窗口、应用、文件、插件的顺序合并结果
+pub fn collect(
+    query: &str,
+    windows: Vec<OpenWindow>,
+    apps: Vec<AppInfo>,
+    files: Vec<FileResult>,
+    plugins: Vec<PluginItem>,
+) -> Vec<SearchResult> {
+    let query = query.trim().to_lowercase();
+    let matches = |text: &str| text.to_lowercase().contains(&query);
+    let mut results = Vec::new();
+    if !query.is_empty() {
+        results.extend(windows.into_iter().filter(|(_, title, _)| matches(title)).take(MAX_WINDOWS).map(|(hwnd, title, path)| {
+            SearchResult { hwnd: Some(hwnd), ..SearchResult::new(ResultKind::Window, crate::privacy::title(title), path) }
+        }));
+    }
+    results.extend(
+        apps.into_iter()
+            .filter(|a| matches(&a.name))
+            .take(MAX_APPS)
+            .map(|a| SearchResult { args: a.args, ..SearchResult::new(ResultKind::App, a.name, a.path) }),
+    );
+    if !query.is_empty() {
+        results.extend(
+            files.into_iter()
+                .take(MAX_FILES)
+                .map(|f| SearchResult { is_dir: f.is_dir, ..SearchResult::new(ResultKind::File, f.name, f.path) }),
+        );
+    }
+    results.extend(plugins.into_iter().map(|item| SearchResult {
+        plugin: Some(item.plugin),
+        detail: item.subtitle,
+        ..SearchResult::new(ResultKind::Plugin, item.title, item.id)
+    }));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_grouped_by_kind() {
+        let windows = vec![(7, "报告.docx - Word".to_string(), r"C:\Office\WINWORD.EXE".to_string())];
+        let apps = || vec![
+            AppInfo { name: "Word".to_string(), path: r"C:\Start\Word.lnk".to_string(), args: Vec::new() },
+            AppInfo { name: "Excel".to_string(), path: r"C:\Start\Excel.lnk".to_string(), args: Vec::new() },
+        ];
+        let files = vec![FileResult { name: "word-notes".to_string(), path: r"C:\Docs\word-notes".to_string(), is_dir: true }];
+
+        let plugin = vec![PluginItem { plugin: "Docs".to_string(), id: "w1".to_string(), title: "Word 帮助".to_string(), subtitle: None }];
+        let results = collect("WORD", windows.clone(), apps(), files.clone(), plugin);
+        let kinds: Vec<ResultKind> = results.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, vec![ResultKind::Window, ResultKind::App, ResultKind::File, ResultKind::Plugin]);
+        assert_eq!(results[3].plugin.as_deref(), Some("Docs"));
+        assert_eq!(results[0].hwnd, Some(7));
+        assert!(results[2].is_dir);
+
+        // 没有输入时只列出应用
+        let results = collect(" ", windows, apps(), files, Vec::new());
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.kind == ResultKind::App));
+    }
+
+    #[test]
+    fn calculator_evaluates_expressions() {
+        assert_eq!(evaluate("2*(3+4)"), Some(14.0));
+        assert_eq!(evaluate("-2^2"), Some(-4.0));
+        assert_eq!(evaluate("2^3^2"), Some(512.0));
+        assert_eq!(evaluate("10 % 4 + 1.5"), Some(3.5));
+        assert_eq!(evaluate("sqrt(16) / 8"), Some(0.5));
+        assert_eq!(evaluate("1/0"), None);
+        assert_eq!(evaluate("2*(3"), None);
+        assert_eq!(evaluate("notepad"), None);
+        assert_eq!(format_number(1.0 / 3.0), "0.3333333333");
+        assert_eq!(format_number(6.0), "6");
+
+        let results = quick_results("=pi*2");
+        assert_eq!(results[0].kind, ResultKind::Calculation);
+        assert_eq!(results[0].path, "6.2831853072");
+        // 单独的数字不显示计算结果
+        assert!(quick_results("42").is_empty());
+    }
+
+    #[test]
+    fn commands_and_system_actions() {
+        let results = quick_results("> ping 1.1.1.1");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, "ping 1.1.1.1");
+        // 带引号的命令原样交给 cmd.exe
+        let quoted = r#"> findstr "a b" "C:\Program Files\log.txt""#;
+        let command = &quick_results(quoted)[0].path;
+        assert_eq!(shell_command_line(command), r#"cmd.exe /K findstr "a b" "C:\Program Files\log.txt""#);
+        assert!(!searches_everything(" > ping"));
+
+        let names: Vec<String> = quick_results("回收站").into_iter().map(|r| r.path).collect();
+        assert_eq!(names, vec!["empty_recycle_bin"]);
+        assert_eq!(quick_results("LOCK")[0].path, "lock");
+        assert!(quick_results("l").is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+// 快捷键透传
+// Ctrl+W 等全局快捷键会抢走嵌入的浏览器、编辑器自己的同名快捷键 (关闭网页标签、关闭文件)。
+// 设置 shortcuts.passthrough 中按应用 (EXE 文件名) 列出要透传的快捷键：快捷键触发时，
+// 如果输入焦点在这些应用的标签中，WindowHub 不处理，而是临时注销该快捷键，把同样的组合键发送给应用，
+// 稍后再注册回来
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tracing::{debug, warn};
+
+use crate::settings;
+use crate::window_manager::WindowManager;
+
+// 发送组合键后等待应用收到再重新注册，否则注入的按键会被自己的快捷键再次截获
+const REREGISTER_DELAY: Duration = Duration::from_millis(100);
+
+/// 快捷键是否应交给焦点所在的标签，是则转发给它并返回 true (WindowHub 不再处理)
+pub fn intercept(app: &AppHandle, shortcut: &Shortcut) -> bool {
+    let rules = settings::shortcuts().passthrough;
+    if rules.is_empty() {
+        return false;
+    }
+    let manager = app.state::<WindowManager>();
+    let Some(tab) = focused_tab(&manager.hwnds()) else { return false };
+    let exe_path = manager.get(tab).and_then(|w| w.exe_path);
+    if !rules.iter().any(|rule| rule.passes(exe_path.as_deref(), shortcut)) {
+        return false;
+    }
+    let Some(accelerator) = settings::accelerator_for(shortcut) else { return false };
+    debug!(target: "shortcuts", "快捷键透传给标签: {} -> hwnd={}", accelerator, tab);
+    forward(app, *shortcut, accelerator);
+    true
+}
+
+/// 注销快捷键后重新发送组合键，稍后再注册回来
+pub fn forward(app: &AppHandle, shortcut: Shortcut, accelerator: String) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = app.global_shortcut().unregister(shortcut) {
+            warn!(target: "shortcuts", "透传前注销快捷键失败: {} - {:?}", accelerator, e);
+            return;
+        }
+        #[cfg(windows)]
+        {
+            let sent = crate::keys::parse_sequence(&format!("{{{}}}", accelerator))
+                .and_then(|steps| unsafe { crate::keys::send(&steps) });
+            if let Err(e) = sent {
+                warn!(target: "shortcuts", "透传快捷键失败: {} - {}", accelerator, e);
+            }
+        }
+        std::thread::sleep(REREGISTER_DELAY);
+        crate::register_shortcut(&app, &accelerator);
+    });
+}
+
+#[cfg(windows)]
+fn focused_tab(tabs: &[isize]) -> Option<isize> {
+    crate::platform::win32::focused_tab(tabs)
+}
+
+#[cfg(not(windows))]
+fn focused_tab(_tabs: &[isize]) -> Option<isize> {
+    None
+}
@@ -0,0 +1,44 @@
+// 数据目录
+// 默认为 %APPDATA%\WindowHub；便携模式下为程序旁边的 data 目录，设置、工作区、数据库和日志都跟着程序走，
+// 可以从 U 盘运行，不在电脑上留下数据 (WebView2 的缓存也放到 data\webview 中)
+// 程序旁边有 portable.flag 文件，或者命令行带 --portable 时为便携模式；
+// 后台代理和自己启动的主界面带上 --portable，保证和当前进程使用同一个目录
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// 便携模式的命令行参数
+pub const PORTABLE_ARG: &str = "--portable";
+const PORTABLE_FLAG: &str = "portable.flag";
+
+// 便携模式下的数据目录 (普通模式为 None)，第一次使用时确定，之后不再变化
+static PORTABLE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// 数据目录，无法确定时为 None (此时数据只保存在内存中)
+pub fn data_dir() -> Option<PathBuf> {
+    match portable_dir() {
+        Some(dir) => Some(dir.clone()),
+        None => std::env::var("APPDATA").ok().map(|dir| PathBuf::from(dir).join("WindowHub")),
+    }
+}
+
+pub fn is_portable() -> bool {
+    portable_dir().is_some()
+}
+
+/// 便携模式下让 WebView2 把缓存写到数据目录 (run() 创建窗口之前调用)
+pub fn apply_webview_dir() {
+    if let Some(dir) = portable_dir() {
+        std::env::set_var("WEBVIEW2_USER_DATA_FOLDER", dir.join("webview"));
+    }
+}
+
+fn portable_dir() -> Option<&'static PathBuf> {
+    PORTABLE_DIR
+        .get_or_init(|| {
+            let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+            let portable = std::env::args().any(|arg| arg == PORTABLE_ARG) || exe_dir.join(PORTABLE_FLAG).exists();
+            portable.then(|| exe_dir.join("data"))
+        })
+        .as_ref()
+}
@@ -0,0 +1,319 @@
+// 外部集成的权限
+// 能操作窗口的外部代码都按权限 (Capability) 检查，每个调用方只能做允许的操作：
+// - enumerate: 列出窗口和标签 (只读)
+// - view: 获取窗口画面
+// - input: 向窗口发送点击和按键
+// - embed: 嵌入、释放、切换、摆放标签，启动应用
+// - kill: 关闭标签、重启标签、退出 WindowHub (会丢失窗口中的内容，只给信任的调用方)
+// 调用方有三类：
+// - 令牌 (远程标签的对方，之后的本地 API、深度链接同样接入)：create_api_client(name, capabilities) 生成，
+//   令牌只在这时返回一次，之后 list_api_clients 只显示末尾几位；revoke_token(id) 作废，已经建立的连接在下一次请求时失去权限
+//   ID 取自数据库中只增不减的计数，作废的 ID 不会分给之后登记的调用方 (否则作废前建立的连接会得到新调用方的权限)
+// - 脚本 (scripts.rs)：默认可以 enumerate、view 和 embed
+// - 搜索框插件 (plugins.rs)：默认没有任何权限，要求打开程序 (open) 需要 embed
+// 脚本和插件的权限按名称用 set_capabilities(kind, name, capabilities) 修改，list_capability_grants 列出修改过的
+// 令牌是系统安全随机数，数据库中保存 SHA-256(令牌)；认证时对方不发送令牌，
+// 而是以 SHA-256(令牌) 为密钥对 nonce 计算 HMAC (见 remote_tabs.rs)，网络上截获握手得不到令牌
+// 服务端必须持有 HMAC 的密钥才能验证，所以保存的摘要本身就能完成认证：
+// 拿到 windowhub.db 副本的人不需要令牌就能以这些调用方的身份连接，数据库要和令牌一样保密
+// (导出的备份同样包含摘要)；怀疑数据库泄露时作废全部令牌重新生成
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use tracing::info;
+
+use crate::clock::now_secs;
+
+const CLIENTS_KEY: &str = "api_clients";
+const GRANTS_KEY: &str = "capability_grants";
+// 下一个外部集成的 ID
+const NEXT_ID_KEY: &str = "api_client_next_id";
+
+// 列表中显示的令牌末尾字符数
+const TOKEN_HINT_LEN: usize = 4;
+
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    Enumerate,
+    View,
+    Input,
+    Embed,
+    Kill,
+}
+
+impl Capability {
+    fn name(self) -> &'static str {
+        match self {
+            Capability::Enumerate => "enumerate",
+            Capability::View => "view",
+            Capability::Input => "input",
+            Capability::Embed => "embed",
+            Capability::Kill => "kill",
+        }
+    }
+}
+
+/// 按名称授权的调用方
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantKind {
+    Script,
+    Plugin,
+}
+
+impl GrantKind {
+    // 没有修改过权限时的默认值
+    fn defaults(self) -> Vec<Capability> {
+        match self {
+            GrantKind::Script => vec![Capability::Enumerate, Capability::View, Capability::Embed],
+            GrantKind::Plugin => Vec::new(),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            GrantKind::Script => "脚本",
+            GrantKind::Plugin => "插件",
+        }
+    }
+}
+
+/// 脚本或插件的权限 (list_capability_grants 返回修改过的)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grant {
+    pub kind: GrantKind,
+    pub name: String,
+    pub capabilities: Vec<Capability>,
+}
+
+impl Grant {
+    /// 当前的权限，没有修改过时为默认值
+    pub fn of(kind: GrantKind, name: &str) -> Grant {
+        let stored = load_grants().into_iter().find(|g| g.kind == kind && g.name == name);
+        stored.unwrap_or_else(|| Grant { kind, name: name.to_string(), capabilities: kind.defaults() })
+    }
+
+    pub fn require(&self, capability: Capability) -> Result<(), String> {
+        if self.capabilities.contains(&capability) {
+            Ok(())
+        } else {
+            Err(format!("{} {} 没有权限: {}", self.kind.label(), self.name, capability.name()))
+        }
+    }
+}
+
+/// 登记的外部集成 (list_api_clients 返回，不含令牌)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiClient {
+    pub id: u64,
+    pub name: String,
+    pub capabilities: Vec<Capability>,
+    pub token_hint: String, // 令牌的末尾几位，用于辨认
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+}
+
+impl ApiClient {
+    pub fn allows(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
+
+/// create_api_client 的返回值，令牌只在这里出现一次
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedClient {
+    pub client: ApiClient,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredClient {
+    #[serde(flatten)]
+    client: ApiClient,
+    token_hash: String,
+}
+
+// 读写数据库时加锁，避免同时登记和作废时丢失修改
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn load() -> Vec<StoredClient> {
+    crate::storage::get().and_then(|s| s.load::<Vec<StoredClient>>(CLIENTS_KEY)).unwrap_or_default()
+}
+
+fn save(clients: &[StoredClient]) -> Result<(), String> {
+    crate::storage::get().ok_or("数据库未打开")?.save(CLIENTS_KEY, clients)
+}
+
+fn load_grants() -> Vec<Grant> {
+    crate::storage::get().and_then(|s| s.load::<Vec<Grant>>(GRANTS_KEY)).unwrap_or_default()
+}
+
+/// 数据库中保存的令牌摘要，也是认证时 HMAC 的密钥 (持有摘要就能通过认证，不能公开)
+pub fn token_hash(token: &str) -> String {
+    Sha256::digest(token.trim().as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 登记的外部集成
+pub fn list() -> Vec<ApiClient> {
+    load().into_iter().map(|stored| stored.client).collect()
+}
+
+/// 登记外部集成并生成令牌
+pub fn create(name: &str, capabilities: Vec<Capability>) -> Result<CreatedClient, String> {
+    let name = checked_name(name)?;
+    if capabilities.is_empty() {
+        return Err("至少需要允许一项操作".to_string());
+    }
+    let capabilities = unique(capabilities);
+    let _lock = LOCK.lock().unwrap();
+    let mut clients = load();
+    // 32 字节的系统安全随机数
+    let token = crate::remote_tabs::nonce()?;
+    let storage = crate::storage::get().ok_or("数据库未打开")?;
+    let id = next_id(storage.load::<u64>(NEXT_ID_KEY), &clients);
+    storage.save(NEXT_ID_KEY, &(id + 1))?;
+    let client = ApiClient {
+        id,
+        name: name.to_string(),
+        capabilities,
+        token_hint: token[token.len() - TOKEN_HINT_LEN..].to_string(),
+        created_at: now_secs(),
+        last_used_at: None,
+    };
+    clients.push(StoredClient { client: client.clone(), token_hash: token_hash(&token) });
+    save(&clients)?;
+    info!(target: "settings", "登记外部集成 {} ({}): {:?}", client.id, client.name, client.capabilities);
+    Ok(CreatedClient { client, token })
+}
+
+// 新的 ID：计数的值，没有计数 (之前的版本) 时接在现有的 ID 之后
+fn next_id(counter: Option<u64>, clients: &[StoredClient]) -> u64 {
+    let after_existing = clients.iter().map(|c| c.client.id).max().unwrap_or(0) + 1;
+    counter.unwrap_or(1).max(after_existing)
+}
+
+/// 作废令牌，返回是否存在
+pub fn revoke(id: u64) -> Result<bool, String> {
+    let _lock = LOCK.lock().unwrap();
+    let mut clients = load();
+    let before = clients.len();
+    clients.retain(|c| c.client.id != id);
+    if clients.len() == before {
+        return Ok(false);
+    }
+    save(&clients)?;
+    info!(target: "settings", "作废外部集成的令牌: {}", id);
+    Ok(true)
+}
+
+/// 按认证时的 proof 找到外部集成 (matches 用令牌摘要计算 proof 并比较)，并记录使用时间
+pub fn authenticate(matches: impl Fn(&str) -> bool) -> Option<ApiClient> {
+    let _lock = LOCK.lock().unwrap();
+    let mut clients = load();
+    let stored = clients.iter_mut().find(|c| matches(&c.token_hash))?;
+    stored.client.last_used_at = Some(now_secs());
+    let client = stored.client.clone();
+    let _ = save(&clients);
+    Some(client)
+}
+
+/// 检查外部集成当前是否仍有权限 (令牌作废或权限变化后立即生效)
+pub fn check(id: u64, capability: Capability) -> Result<(), String> {
+    let allowed = load().iter().any(|c| c.client.id == id && c.client.allows(capability));
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!("没有权限: {}", capability.name()))
+    }
+}
+
+/// 修改过权限的脚本和插件
+pub fn grants() -> Vec<Grant> {
+    load_grants()
+}
+
+/// 修改脚本或插件的权限
+pub fn set_grant(kind: GrantKind, name: &str, capabilities: Vec<Capability>) -> Result<Grant, String> {
+    let name = checked_name(name)?;
+    let grant = Grant { kind, name: name.to_string(), capabilities: unique(capabilities) };
+    let _lock = LOCK.lock().unwrap();
+    let mut grants = load_grants();
+    grants.retain(|g| !(g.kind == kind && g.name == name));
+    grants.push(grant.clone());
+    crate::storage::get().ok_or("数据库未打开")?.save(GRANTS_KEY, &grants)?;
+    info!(target: "settings", "{} {} 的权限: {:?}", kind.label(), name, grant.capabilities);
+    Ok(grant)
+}
+
+fn checked_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("名称不能为空，且不能超过 {} 个字符", MAX_NAME_LEN));
+    }
+    Ok(name)
+}
+
+fn unique(capabilities: Vec<Capability>) -> Vec<Capability> {
+    capabilities.into_iter().fold(Vec::new(), |mut unique, capability| {
+        if !unique.contains(&capability) {
+            unique.push(capability);
+        }
+        unique
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_not_listed() {
+        let stored = StoredClient {
+            client: ApiClient {
+                id: 1,
+                name: "dashboard".to_string(),
+                capabilities: vec![Capability::Enumerate],
+                token_hint: "beef".to_string(),
+                created_at: 0,
+                last_used_at: None,
+            },
+            token_hash: token_hash("dead-beef"),
+        };
+        let listed = serde_json::to_value(&stored.client).unwrap();
+        assert!(listed.get("token_hash").is_none());
+        assert_eq!(listed["capabilities"], serde_json::json!(["enumerate"]));
+        let saved: StoredClient = serde_json::from_value(serde_json::to_value(&stored).unwrap()).unwrap();
+        assert_eq!(saved, stored);
+        assert!(saved.client.allows(Capability::Enumerate));
+        assert!(!saved.client.allows(Capability::Input));
+        assert_ne!(saved.token_hash, "dead-beef");
+        assert_eq!(saved.token_hash.len(), 64);
+    }
+
+    #[test]
+    fn revoked_ids_are_not_reused() {
+        let stored = |id| StoredClient {
+            client: ApiClient { id, name: "c".to_string(), capabilities: vec![Capability::View], token_hint: String::new(), created_at: 0, last_used_at: None },
+            token_hash: String::new(),
+        };
+        // 作废了 ID 最大的 3 后，计数仍为 4
+        assert_eq!(next_id(Some(4), &[stored(1), stored(2)]), 4);
+        assert_eq!(next_id(None, &[stored(1), stored(2)]), 3);
+        assert_eq!(next_id(None, &[]), 1);
+    }
+
+    #[test]
+    fn plugins_start_without_capabilities() {
+        let script = Grant { kind: GrantKind::Script, name: "layout".to_string(), capabilities: GrantKind::Script.defaults() };
+        assert!(script.require(Capability::Embed).is_ok());
+        assert!(script.require(Capability::Kill).is_err());
+        let plugin = Grant { kind: GrantKind::Plugin, name: "GitHub".to_string(), capabilities: GrantKind::Plugin.defaults() };
+        assert_eq!(plugin.require(Capability::Embed).unwrap_err(), "插件 GitHub 没有权限: embed");
+        assert_eq!(unique(vec![Capability::Kill, Capability::Kill, Capability::View]), vec![Capability::Kill, Capability::View]);
+    }
+}
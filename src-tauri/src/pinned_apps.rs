@@ -0,0 +1,195 @@
+// 固定到编号的应用 (类似任务栏的 Win+数字)
+// 开启 shortcuts.pinned_slots 后，Alt+N 先看固定在第 N 个位置的应用：
+// - 已经嵌入时切换到它 (有多个窗口时切换到最近使用的)
+// - 还没有打开时启动并嵌入，然后切换过去
+// 没有固定应用的编号仍然按标签序号切换
+// 列表保存在数据库中 (storage.rs，旧版的 pinned_apps.json 首次打开数据库时导入)，由 pin_app_to_slot / unpin_app_slot 修改
+// 判断是否已嵌入按 EXE 文件名比较；从快捷方式 (.lnk) 启动时第一次嵌入后记下实际的 EXE
+// 启动作为长时间操作与其他启动类操作排队执行 (见 operations.rs)，可以用 cancel_operation 取消
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::operations::Progress;
+use crate::window_manager::WindowManager;
+
+const STORAGE_KEY: &str = "pinned_apps";
+
+// 可以固定的编号
+const SLOTS: std::ops::RangeInclusive<u32> = 1..=9;
+
+/// 如何启动固定的应用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchSpec {
+    pub path: String, // exe、lnk 或其他可以打开的文件
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub exe: Option<String>, // 判断是否已嵌入时比较的 EXE 文件名，为空时取 path 的文件名
+}
+
+impl LaunchSpec {
+    // 用于比较的 EXE 文件名 (path 不是 exe 且还没有记下实际的 EXE 时为 None)
+    fn exe_name(&self) -> Option<&str> {
+        if let Some(exe) = self.exe.as_deref().filter(|e| !e.is_empty()) {
+            return Some(exe);
+        }
+        let name = file_name(&self.path);
+        name.to_ascii_lowercase().ends_with(".exe").then_some(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinnedApp {
+    pub slot: u32,
+    pub launch: LaunchSpec,
+}
+
+static PINNED: Mutex<Vec<PinnedApp>> = Mutex::new(Vec::new());
+
+// 正在启动的编号，重复按下时不再启动
+static LAUNCHING: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+// 启动结束或被取消时移除正在启动的编号
+struct Launching(u32);
+
+impl Drop for Launching {
+    fn drop(&mut self) {
+        LAUNCHING.lock().unwrap().retain(|s| *s != self.0);
+    }
+}
+
+/// 从数据库加载 (setup 时调用)
+pub fn load() {
+    let Some(pinned) = crate::storage::get().and_then(|s| s.load::<Vec<PinnedApp>>(STORAGE_KEY)) else { return };
+    info!(target: "settings", "已加载 {} 个固定的应用", pinned.len());
+    *PINNED.lock().unwrap() = pinned;
+}
+
+pub fn list() -> Vec<PinnedApp> {
+    PINNED.lock().unwrap().clone()
+}
+
+/// 把应用固定到编号 slot (替换原来固定的应用)，返回新的列表
+pub fn pin(slot: u32, launch: LaunchSpec) -> Result<Vec<PinnedApp>, String> {
+    if !SLOTS.contains(&slot) {
+        return Err(format!("编号必须在 {} 到 {} 之间", SLOTS.start(), SLOTS.end()));
+    }
+    let launch = LaunchSpec { path: launch.path.trim().to_string(), ..launch };
+    if launch.path.is_empty() {
+        return Err("程序路径不能为空".to_string());
+    }
+    info!(target: "launcher", "固定应用到编号 {}: {}", slot, launch.path);
+    let pinned = {
+        let mut pinned = PINNED.lock().unwrap();
+        pinned.retain(|p| p.slot != slot);
+        pinned.push(PinnedApp { slot, launch });
+        pinned.sort_by_key(|p| p.slot);
+        pinned.clone()
+    };
+    save(&pinned);
+    Ok(pinned)
+}
+
+/// 取消固定，返回新的列表
+pub fn unpin(slot: u32) -> Vec<PinnedApp> {
+    let pinned = {
+        let mut pinned = PINNED.lock().unwrap();
+        pinned.retain(|p| p.slot != slot);
+        pinned.clone()
+    };
+    info!(target: "launcher", "取消固定编号 {}", slot);
+    save(&pinned);
+    pinned
+}
+
+/// 按下 Alt+slot：切换到或启动固定的应用，没有固定应用时返回 false (由调用方按标签序号切换)
+pub fn activate(app: &AppHandle, slot: u32) -> bool {
+    let Some(pinned) = PINNED.lock().unwrap().iter().find(|p| p.slot == slot).cloned() else { return false };
+
+    let manager = app.state::<WindowManager>();
+    let order = crate::lifecycle::mru(&manager.hwnds());
+    let exe = pinned.launch.exe_name();
+    let existing = order.into_iter().find(|hwnd| {
+        let path = manager.get(*hwnd).and_then(|w| w.exe_path);
+        exe.is_some_and(|exe| path.is_some_and(|path| file_name(&path).eq_ignore_ascii_case(exe)))
+    });
+    if let Some(hwnd) = existing {
+        let _ = app.emit("pinned-app-activate", hwnd);
+        return true;
+    }
+
+    {
+        let mut launching = LAUNCHING.lock().unwrap();
+        if launching.contains(&slot) {
+            return true;
+        }
+        launching.push(slot);
+    }
+    let launching = Launching(slot);
+    let handle = app.clone();
+    crate::operations::start(app, "launch_pinned_app", true, |progress| async move {
+        let _launching = launching;
+        let result = launch(&handle, &pinned, &progress).await;
+        if let Err(e) = &result {
+            warn!(target: "launcher", "启动固定的应用失败 (编号 {}): {}", slot, e);
+            let _ = handle.emit("pinned-app-failed", e.clone());
+        }
+        result
+    });
+    true
+}
+
+// 启动并嵌入固定的应用，然后切换过去
+async fn launch(app: &AppHandle, pinned: &PinnedApp, progress: &Progress) -> Result<(), String> {
+    let hwnd = crate::launch_and_wait(&pinned.launch.path, &pinned.launch.args, progress).await?;
+    let window = crate::embed_tab(app, &app.state::<WindowManager>(), hwnd)?;
+    if let Some(main) = app.get_webview_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+    }
+    let _ = app.emit("pinned-app-activate", window.hwnd);
+
+    // 从快捷方式等启动时记下实际的 EXE，下次按编号时可以找到已经打开的窗口
+    if pinned.launch.exe_name().is_none() {
+        if let Some(path) = window.exe_path {
+            let exe = file_name(&path).to_string();
+            let updated = {
+                let mut list = PINNED.lock().unwrap();
+                let Some(entry) = list.iter_mut().find(|p| p.slot == pinned.slot && p.launch.path == pinned.launch.path) else { return Ok(()) };
+                entry.launch.exe = Some(exe);
+                list.clone()
+            };
+            save(&updated);
+        }
+    }
+    Ok(())
+}
+
+// Windows 路径的文件名 (在其他平台上 Path 不按反斜杠分割)
+fn file_name(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+fn save(pinned: &[PinnedApp]) {
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save(STORAGE_KEY, pinned) {
+            warn!(target: "settings", "保存固定的应用失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exe_name_comes_from_path_or_recorded_exe() {
+        let spec = |path: &str, exe: Option<&str>| LaunchSpec { path: path.to_string(), args: Vec::new(), exe: exe.map(str::to_string) };
+        assert_eq!(spec(r"C:\Apps\Code.exe", None).exe_name(), Some("Code.exe"));
+        assert_eq!(spec(r"C:\Users\me\Desktop\Slack.lnk", None).exe_name(), None);
+        assert_eq!(spec(r"C:\Users\me\Desktop\Slack.lnk", Some("slack.exe")).exe_name(), Some("slack.exe"));
+    }
+}
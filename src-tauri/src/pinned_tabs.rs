@@ -0,0 +1,150 @@
+// 固定的标签 (pin_tab / unpin_tab)
+// 固定的标签排在标签列表的最前面 (list_embedded_windows)，Ctrl+W 以及同样关闭当前标签的手势、热角不会关闭它，
+// 需要先取消固定；弹出为独立窗口时自动取消固定
+// 固定的标签按应用 (EXE 路径和启动参数) 保存在数据库中 (storage.rs)，应用退出或崩溃后仍然保留，下次启动时恢复：
+// - 同一应用已经有没有嵌入的窗口时直接嵌入
+// - 否则用相同参数重新启动应用，嵌入新窗口
+// 恢复后重新固定，发送 tab-pinned。恢复作为长时间操作与其他启动类操作排队执行 (见 operations.rs)
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::window_manager::{EmbeddedWindow, WindowManager};
+
+const STORAGE_KEY: &str = "pinned_tabs";
+
+/// 保存的固定标签
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinnedTab {
+    pub exe_path: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// tab-pinned 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct TabPinned {
+    pub hwnd: isize,
+    pub pinned: bool,
+}
+
+static PINNED: Mutex<Vec<PinnedTab>> = Mutex::new(Vec::new());
+
+/// 从数据库加载 (setup 时调用)
+pub fn load() {
+    let Some(tabs) = crate::storage::get().and_then(|s| s.load::<Vec<PinnedTab>>(STORAGE_KEY)) else { return };
+    info!(target: "settings", "已加载 {} 个固定的标签", tabs.len());
+    *PINNED.lock().unwrap() = tabs;
+}
+
+/// 固定或取消固定标签，返回修改后的记录
+pub fn set(app: &AppHandle, manager: &WindowManager, hwnd: isize, pinned: bool) -> Result<EmbeddedWindow, String> {
+    let window = manager.get(hwnd).ok_or("标签不存在")?;
+    if window.pinned == pinned {
+        return Ok(window);
+    }
+    let window = manager.set_pinned(hwnd, pinned).ok_or("标签不存在")?;
+    if let Some(tab) = saved_tab(&window) {
+        update(|tabs| if pinned { tabs.push(tab) } else { remove_one(tabs, &tab) });
+    }
+    info!(target: "embed", "{}标签: {}", if pinned { "固定" } else { "取消固定" }, window.title);
+    let _ = app.emit("tab-pinned", TabPinned { hwnd, pinned });
+    Ok(window)
+}
+
+/// 固定的标签弹出为独立窗口后不再恢复 (release_tab 调用)
+pub fn forget(window: &EmbeddedWindow) {
+    if !window.pinned {
+        return;
+    }
+    if let Some(tab) = saved_tab(window) {
+        update(|tabs| remove_one(tabs, &tab));
+    }
+}
+
+/// 恢复上次固定的标签 (setup 时调用，在后台依次嵌入或启动)
+pub fn restore(app: &AppHandle) {
+    let tabs = PINNED.lock().unwrap().clone();
+    if tabs.is_empty() {
+        return;
+    }
+    let handle = app.clone();
+    crate::operations::start(app, "restore_pinned_tabs", true, |progress| async move {
+        let platform = crate::platform::current();
+        let manager = handle.state::<WindowManager>();
+        let mut open: Vec<(isize, String)> = crate::window_list::current()
+            .await
+            .iter()
+            .filter(|w| manager.get(w.hwnd).is_none())
+            .filter_map(|w| Some((w.hwnd, platform.process_path(w.hwnd).ok()?)))
+            .collect();
+        for tab in tabs {
+            let hwnd = match take_window(&mut open, &tab.exe_path) {
+                Some(hwnd) => hwnd,
+                None => match crate::launch_and_wait(&tab.exe_path, &tab.args, &progress).await {
+                    Ok(hwnd) => hwnd,
+                    Err(e) => {
+                        warn!(target: "launcher", "恢复固定的标签失败: {} - {}", tab.exe_path, e);
+                        continue;
+                    }
+                },
+            };
+            match crate::embed_tab(&handle, &manager, hwnd) {
+                Ok(window) => {
+                    manager.set_pinned(window.hwnd, true);
+                    info!(target: "launcher", "已恢复固定的标签: {}", tab.exe_path);
+                    let _ = handle.emit("tab-pinned", TabPinned { hwnd: window.hwnd, pinned: true });
+                }
+                Err(e) => warn!(target: "launcher", "嵌入固定的标签失败: {} - {}", tab.exe_path, String::from(e)),
+            }
+        }
+        Ok(())
+    });
+}
+
+// 标签对应的保存记录，没有 EXE 路径 (无法重新启动) 时为 None
+fn saved_tab(window: &EmbeddedWindow) -> Option<PinnedTab> {
+    Some(PinnedTab { exe_path: window.exe_path.clone()?, args: window.args.clone() })
+}
+
+// 取出第一个属于 exe_path 的窗口，同一应用的多个固定标签依次使用不同的窗口
+fn take_window(open: &mut Vec<(isize, String)>, exe_path: &str) -> Option<isize> {
+    let index = open.iter().position(|(_, path)| path.eq_ignore_ascii_case(exe_path))?;
+    Some(open.remove(index).0)
+}
+
+// 同一应用固定了多个标签时只移除一个
+fn remove_one(tabs: &mut Vec<PinnedTab>, tab: &PinnedTab) {
+    if let Some(index) = tabs.iter().position(|t| t == tab) {
+        tabs.remove(index);
+    }
+}
+
+fn update(f: impl FnOnce(&mut Vec<PinnedTab>)) {
+    let tabs = {
+        let mut tabs = PINNED.lock().unwrap();
+        f(&mut tabs);
+        tabs.clone()
+    };
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save(STORAGE_KEY, &tabs) {
+            warn!(target: "settings", "保存固定的标签失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_pinned_tab_takes_its_own_window() {
+        let mut open = vec![(1, r"C:\Apps\wt.exe".to_string()), (2, r"C:\Apps\Code.exe".to_string()), (3, r"C:\Apps\WT.EXE".to_string())];
+        assert_eq!(take_window(&mut open, r"C:\Apps\wt.exe"), Some(1));
+        assert_eq!(take_window(&mut open, r"C:\Apps\wt.exe"), Some(3));
+        assert_eq!(take_window(&mut open, r"C:\Apps\wt.exe"), None);
+        assert_eq!(open.len(), 1);
+    }
+}
@@ -0,0 +1,94 @@
+// Core Audio 音频会话 (Windows)
+// 每个发声的进程在每个输出设备上各有一个会话；Electron 等应用的声音来自子进程，
+// 所以会话按进程的 EXE 路径归属到应用，而不是窗口所属的进程 ID
+
+use tracing::warn;
+use windows::core::Interface;
+use windows::Win32::{
+    Foundation::BOOL,
+    Media::Audio::{
+        eRender, IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator, ISimpleAudioVolume,
+        MMDeviceEnumerator, DEVICE_STATE_ACTIVE,
+    },
+    System::Com::{CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED},
+};
+
+use super::win32::process_path_of;
+use super::AudioSession;
+
+/// 所有输出设备上的音频会话 (系统声音会话除外)
+pub fn sessions() -> Vec<AudioSession> {
+    let mut sessions = Vec::new();
+    with_sessions(|control| {
+        if let Some(session) = unsafe { describe(control) } {
+            sessions.push(session);
+        }
+        true
+    });
+    sessions
+}
+
+/// 静音或取消静音会话，会话已不存在时返回 false
+pub fn set_muted(id: &str, muted: bool) -> bool {
+    let mut found = false;
+    with_sessions(|control| unsafe {
+        if session_id(control).as_deref() != Some(id) {
+            return true;
+        }
+        found = match control.cast::<ISimpleAudioVolume>() {
+            Ok(volume) => volume.SetMute(BOOL::from(muted), std::ptr::null()).is_ok(),
+            Err(_) => false,
+        };
+        false
+    });
+    found
+}
+
+unsafe fn describe(control: &IAudioSessionControl2) -> Option<AudioSession> {
+    let pid = control.GetProcessId().ok()?;
+    if pid == 0 {
+        return None;
+    }
+    let muted = control.cast::<ISimpleAudioVolume>().ok()?.GetMute().ok()?.as_bool();
+    Some(AudioSession { id: session_id(control)?, exe_path: process_path_of(pid).ok()?, muted })
+}
+
+unsafe fn session_id(control: &IAudioSessionControl2) -> Option<String> {
+    let id = control.GetSessionInstanceIdentifier().ok()?;
+    let text = id.to_string().ok();
+    CoTaskMemFree(Some(id.0 as *const _));
+    text
+}
+
+// 依次处理每个会话，f 返回 false 时停止
+fn with_sessions(mut f: impl FnMut(&IAudioSessionControl2) -> bool) {
+    unsafe {
+        let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+        if let Err(e) = visit(&mut f) {
+            warn!(target: "focus", "枚举音频会话失败: {}", e);
+        }
+        if initialized {
+            CoUninitialize();
+        }
+    }
+}
+
+unsafe fn visit(f: &mut impl FnMut(&IAudioSessionControl2) -> bool) -> windows::core::Result<()> {
+    let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+    let devices = enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)?;
+    for i in 0..devices.GetCount()? {
+        let Ok(manager) = devices.Item(i).and_then(|d| d.Activate::<IAudioSessionManager2>(CLSCTX_ALL, None)) else {
+            continue;
+        };
+        let sessions = manager.GetSessionEnumerator()?;
+        for j in 0..sessions.GetCount()? {
+            let Ok(control) = sessions.GetSession(j).and_then(|s| s.cast::<IAudioSessionControl2>()) else {
+                continue;
+            };
+            if !f(&control) {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,340 @@
+// macOS 平台实现
+// macOS 不允许把其他进程的窗口设为子窗口，无法像 Windows 那样真正嵌入：
+// - 枚举: CGWindowList (窗口句柄使用 CGWindowID)
+// - 激活/置前/关闭: Accessibility API (需要在"系统设置 > 隐私与安全性 > 辅助功能"中授权)
+// - 标签页内容: 镜像模式，ScreenCaptureKit 捕获窗口画面推送给前端绘制 (见 mirror.rs，需要屏幕录制权限)
+
+use std::ffi::c_void;
+
+use core_foundation::array::CFArray;
+use core_foundation::base::{CFType, CFTypeRef, TCFType};
+use core_foundation::boolean::CFBoolean;
+use core_foundation::dictionary::CFDictionary;
+use core_foundation::number::CFNumber;
+use core_foundation::string::{CFString, CFStringRef};
+use core_graphics::window::{
+    copy_window_info, kCGNullWindowID, kCGWindowBounds, kCGWindowLayer, kCGWindowListExcludeDesktopElements,
+    kCGWindowListOptionIncludingWindow, kCGWindowListOptionOnScreenOnly, kCGWindowName, kCGWindowNumber,
+    kCGWindowOwnerName, kCGWindowOwnerPID,
+};
+
+use tracing::warn;
+
+use super::{mirror, ActivateOptions, EmbedMode, Platform, WindowInfo};
+use crate::window_manager::{EmbedError, EmbeddedWindow, WindowRect};
+
+type AXUIElementRef = CFTypeRef;
+type AXError = i32;
+const AX_ERROR_SUCCESS: AXError = 0;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrusted() -> bool;
+    fn AXUIElementCreateApplication(pid: i32) -> AXUIElementRef;
+    fn AXUIElementCopyAttributeValue(element: AXUIElementRef, attribute: CFStringRef, value: *mut CFTypeRef) -> AXError;
+    fn AXUIElementSetAttributeValue(element: AXUIElementRef, attribute: CFStringRef, value: CFTypeRef) -> AXError;
+    fn AXUIElementPerformAction(element: AXUIElementRef, action: CFStringRef) -> AXError;
+    // 私有 API (窗口管理工具普遍使用)，AX 窗口元素没有公开的方式对应到 CGWindowID
+    fn _AXUIElementGetWindow(element: AXUIElementRef, window_id: *mut u32) -> AXError;
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceButtonState(state_id: i32, button: u32) -> bool;
+    fn CGEventSourceFlagsState(state_id: i32) -> u64;
+}
+
+extern "C" {
+    fn proc_pidpath(pid: i32, buffer: *mut c_void, size: u32) -> i32;
+}
+
+const PROC_PIDPATHINFO_MAXSIZE: usize = 4096;
+const CG_EVENT_SOURCE_STATE_COMBINED: i32 = 0;
+const CG_MOUSE_BUTTON_LEFT: u32 = 0;
+// kCGEventFlagMaskShift | Control | Alternate | Command
+const CG_EVENT_FLAG_MASK_MODIFIERS: u64 = 0x0002_0000 | 0x0004_0000 | 0x0008_0000 | 0x0010_0000;
+
+pub struct MacPlatform;
+
+// CGWindowList 返回的一条窗口信息
+struct MacWindow {
+    id: u32,
+    pid: i32,
+    owner: String,
+    title: String,
+    layer: i32,
+    bounds: WindowRect,
+}
+
+fn window_list(option: u32, relative_to: u32) -> Vec<MacWindow> {
+    let Some(array) = copy_window_info(option, relative_to) else {
+        return Vec::new();
+    };
+    array
+        .iter()
+        .filter_map(|item| {
+            let dict: CFDictionary<CFString, CFType> =
+                unsafe { CFDictionary::wrap_under_get_rule(*item as _) };
+            parse_window(&dict)
+        })
+        .collect()
+}
+
+fn parse_window(dict: &CFDictionary<CFString, CFType>) -> Option<MacWindow> {
+    let string = |key: CFStringRef| {
+        let key = unsafe { CFString::wrap_under_get_rule(key) };
+        dict.find(&key).and_then(|v| v.downcast::<CFString>()).map(|s| s.to_string()).unwrap_or_default()
+    };
+    let number = |key: CFStringRef| {
+        let key = unsafe { CFString::wrap_under_get_rule(key) };
+        dict.find(&key).and_then(|v| v.downcast::<CFNumber>()).and_then(|n| n.to_i64())
+    };
+
+    let bounds_key = unsafe { CFString::wrap_under_get_rule(kCGWindowBounds) };
+    let bounds = dict.find(&bounds_key).and_then(|v| v.downcast::<CFDictionary>()).map(|b| {
+        let b: CFDictionary<CFString, CFType> = unsafe { CFDictionary::wrap_under_get_rule(b.as_concrete_TypeRef()) };
+        let field = |name: &'static str| {
+            b.find(&CFString::from_static_string(name))
+                .and_then(|v| v.downcast::<CFNumber>())
+                .and_then(|n| n.to_f64())
+                .unwrap_or(0.0) as i32
+        };
+        let (x, y) = (field("X"), field("Y"));
+        WindowRect { left: x, top: y, right: x + field("Width"), bottom: y + field("Height") }
+    });
+
+    Some(MacWindow {
+        id: number(unsafe { kCGWindowNumber })? as u32,
+        pid: number(unsafe { kCGWindowOwnerPID })? as i32,
+        owner: string(unsafe { kCGWindowOwnerName }),
+        title: string(unsafe { kCGWindowName }),
+        layer: number(unsafe { kCGWindowLayer }).unwrap_or(0) as i32,
+        bounds: bounds.unwrap_or_default(),
+    })
+}
+
+fn find_window(id: isize) -> Option<MacWindow> {
+    if id <= 0 || id > u32::MAX as isize {
+        return None;
+    }
+    window_list(kCGWindowListOptionIncludingWindow, id as u32).into_iter().find(|w| w.id == id as u32)
+}
+
+// 没有屏幕录制权限时 kCGWindowName 为空，用应用名代替
+fn display_title(window: &MacWindow) -> String {
+    if window.title.is_empty() { window.owner.clone() } else { window.title.clone() }
+}
+
+// ============================================================================
+// Accessibility
+// ============================================================================
+
+fn copy_attribute(element: AXUIElementRef, name: &'static str) -> Option<CFType> {
+    let attribute = CFString::from_static_string(name);
+    let mut value: CFTypeRef = std::ptr::null();
+    let err = unsafe { AXUIElementCopyAttributeValue(element, attribute.as_concrete_TypeRef(), &mut value) };
+    if err != AX_ERROR_SUCCESS || value.is_null() {
+        return None;
+    }
+    Some(unsafe { CFType::wrap_under_create_rule(value) })
+}
+
+fn set_attribute(element: AXUIElementRef, name: &'static str, value: bool) -> bool {
+    let attribute = CFString::from_static_string(name);
+    let value = if value { CFBoolean::true_value() } else { CFBoolean::false_value() };
+    unsafe { AXUIElementSetAttributeValue(element, attribute.as_concrete_TypeRef(), value.as_CFTypeRef()) == AX_ERROR_SUCCESS }
+}
+
+fn perform_action(element: AXUIElementRef, name: &'static str) -> bool {
+    let action = CFString::from_static_string(name);
+    unsafe { AXUIElementPerformAction(element, action.as_concrete_TypeRef()) == AX_ERROR_SUCCESS }
+}
+
+/// 找到 CGWindowID 对应的 AX 元素，返回 (应用元素, 窗口元素)
+fn ax_window(id: isize) -> Option<(CFType, CFType)> {
+    if !unsafe { AXIsProcessTrusted() } {
+        warn!(target: "embed", "未获得辅助功能授权，无法操作窗口");
+        return None;
+    }
+    let window = find_window(id)?;
+    let app = unsafe { CFType::wrap_under_create_rule(AXUIElementCreateApplication(window.pid)) };
+    let windows = copy_attribute(app.as_CFTypeRef(), "AXWindows")?;
+    let windows: CFArray = unsafe { CFArray::wrap_under_get_rule(windows.as_CFTypeRef() as _) };
+    let element = windows.iter().find(|element| {
+        let mut window_id = 0u32;
+        let err = unsafe { _AXUIElementGetWindow(**element as AXUIElementRef, &mut window_id) };
+        err == AX_ERROR_SUCCESS && window_id as isize == id
+    })?;
+    let element = unsafe { CFType::wrap_under_get_rule(*element as CFTypeRef) };
+    Some((app, element))
+}
+
+impl Platform for MacPlatform {
+    fn enumerate(&self) -> Vec<WindowInfo> {
+        let own_pid = std::process::id() as i32;
+        window_list(kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements, kCGNullWindowID)
+            .into_iter()
+            // layer 0 是普通应用窗口，菜单栏、Dock、浮动面板都在更高的层
+            .filter(|w| w.layer == 0 && w.pid != own_pid)
+            .filter(|w| !self.is_dangerous_class(&w.owner))
+            .filter(|w| w.bounds.width() > 100 && w.bounds.height() > 100)
+            .map(|w| WindowInfo {
+                hwnd: w.id as isize,
+                title: display_title(&w),
+                class_name: w.owner.clone(),
+                width: w.bounds.width(),
+                height: w.bounds.height(),
+                hint: None,
+                ordinal: 0,
+                token: String::new(),
+                dpi_awareness: None,
+            })
+            .filter(|w| !w.title.contains("WindowHub"))
+            .collect()
+    }
+
+    fn is_window(&self, hwnd: isize) -> bool {
+        find_window(hwnd).is_some()
+    }
+
+    fn title(&self, hwnd: isize) -> String {
+        find_window(hwnd).map(|w| display_title(&w)).unwrap_or_default()
+    }
+
+    // macOS 没有窗口类名，用所属应用名代替 (禁止嵌入列表、危险窗口判断都按应用名匹配)
+    fn class_name(&self, hwnd: isize) -> String {
+        find_window(hwnd).map(|w| w.owner).unwrap_or_default()
+    }
+
+    fn process_path(&self, hwnd: isize) -> Result<String, String> {
+        let window = find_window(hwnd).ok_or("无效的窗口句柄")?;
+        let mut buffer = [0u8; PROC_PIDPATHINFO_MAXSIZE];
+        let len = unsafe { proc_pidpath(window.pid, buffer.as_mut_ptr() as *mut c_void, buffer.len() as u32) };
+        if len <= 0 {
+            return Err("无法获取进程路径".to_string());
+        }
+        Ok(String::from_utf8_lossy(&buffer[..len as usize]).into_owned())
+    }
+
+    fn is_own_window(&self, hwnd: isize) -> bool {
+        find_window(hwnd).map(|w| w.pid == std::process::id() as i32).unwrap_or(false)
+    }
+
+    fn is_dangerous_class(&self, class_name: &str) -> bool {
+        let dangerous = ["Dock", "Window Server", "SystemUIServer", "Control Center", "Notification Center"];
+        dangerous.contains(&class_name)
+    }
+
+    fn embed_mode(&self) -> EmbedMode {
+        EmbedMode::Mirror
+    }
+
+    // 窗口留在原处，只记录信息并开始捕获画面
+    fn embed(&self, _host: isize, hwnd: isize) -> Result<EmbeddedWindow, EmbedError> {
+        let window = find_window(hwnd).ok_or("无效的窗口句柄")?;
+        mirror::start(hwnd, window.bounds.width(), window.bounds.height())?;
+
+        let embedded_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Ok(EmbeddedWindow {
+            hwnd,
+            tab_id: 0,
+            title: display_title(&window),
+            class_name: window.owner.clone(),
+            pid: window.pid as u32,
+            exe_path: self.process_path(hwnd).ok(),
+            args: Vec::new(),
+            original_style: 0,
+            original_exstyle: 0,
+            original_layered: None,
+            original_rect: window.bounds,
+            original_owner: 0,
+            monitor: 0,
+            embedded_at,
+            label: None,
+            group: None,
+            color: None,
+            hotkey: None,
+            auto_restart: false,
+            pinned: false,
+            input_locked: false,
+            capture_excluded: false,
+            preserve_transparency: false,
+        })
+    }
+
+    fn release(&self, hwnd: isize, _saved: Option<&EmbeddedWindow>) {
+        mirror::stop(hwnd);
+    }
+
+    fn raise(&self, hwnd: isize) {
+        self.activate(hwnd, ActivateOptions::default());
+    }
+
+    // 镜像画面由前端按内容区大小绘制，这里只标记当前显示的标签
+    fn set_rect(&self, hwnd: isize, _x: i32, _y: i32, _width: i32, _height: i32) -> bool {
+        if !self.is_window(hwnd) {
+            return false;
+        }
+        mirror::set_active(hwnd);
+        true
+    }
+
+    fn activate(&self, hwnd: isize, options: ActivateOptions) -> bool {
+        let Some((app, window)) = ax_window(hwnd) else {
+            return false;
+        };
+        // 镜像模式下真实窗口在别处，只调整 Z 序时不需要做什么
+        if options.raise_only {
+            return true;
+        }
+        set_attribute(app.as_CFTypeRef(), "AXFrontmost", true);
+        set_attribute(window.as_CFTypeRef(), "AXMinimized", false);
+        set_attribute(window.as_CFTypeRef(), "AXMain", true);
+        perform_action(window.as_CFTypeRef(), "AXRaise")
+    }
+
+    // 镜像模式下真实窗口不受影响，隐藏时暂停推送画面
+    fn set_visible(&self, hwnd: isize, visible: bool) -> bool {
+        if !self.is_window(hwnd) {
+            return false;
+        }
+        mirror::set_paused(hwnd, !visible);
+        true
+    }
+
+    fn close(&self, hwnd: isize) {
+        let Some((_, window)) = ax_window(hwnd) else { return };
+        if let Some(button) = copy_attribute(window.as_CFTypeRef(), "AXCloseButton") {
+            perform_action(button.as_CFTypeRef(), "AXPress");
+        }
+    }
+
+    fn repaint(&self, hwnd: isize) -> bool {
+        self.is_window(hwnd)
+    }
+
+    // CGWindowList 按从前到后的顺序返回
+    fn foreground_window(&self) -> isize {
+        window_list(kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements, kCGNullWindowID)
+            .into_iter()
+            .find(|w| w.layer == 0)
+            .map(|w| w.id as isize)
+            .unwrap_or(0)
+    }
+
+    fn is_mouse_left_down(&self) -> bool {
+        unsafe { CGEventSourceButtonState(CG_EVENT_SOURCE_STATE_COMBINED, CG_MOUSE_BUTTON_LEFT) }
+    }
+
+    fn is_modifier_down(&self) -> bool {
+        unsafe { CGEventSourceFlagsState(CG_EVENT_SOURCE_STATE_COMBINED) & CG_EVENT_FLAG_MASK_MODIFIERS != 0 }
+    }
+
+    // 镜像模式已有每个标签最近的画面，直接复用
+    fn thumbnail(&self, hwnd: isize, _max_width: i32, _max_height: i32) -> Option<String> {
+        mirror::last_frame(hwnd)
+    }
+}
@@ -0,0 +1,229 @@
+// 镜像模式标签页 (macOS)
+// 每个标签对应一个 ScreenCaptureKit 窗口捕获流，只有当前显示的标签推送画面：
+// 帧编码为 JPEG 后以 mirror-frame 事件发给前端 (载荷为 data URL)，前端绘制到内容区
+// 窗口内容不变时不会产生新帧，所以后台标签也低频缓存最新画面，切换过去时立即推送
+// 捕获需要"屏幕录制"权限，首次 start 时系统会弹出授权提示
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use jpeg_encoder::{ColorType, Encoder};
+use screencapturekit::{
+    cm_sample_buffer::CMSampleBuffer,
+    sc_content_filter::{InitParams, SCContentFilter},
+    sc_error_handler::StreamErrorHandler,
+    sc_output_handler::{SCStreamOutputType, StreamOutput},
+    sc_shareable_content::SCShareableContent,
+    sc_stream::SCStream,
+    sc_stream_configuration::SCStreamConfiguration,
+    sc_types::{base::CMTime, SCFrameStatus},
+};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+#[link(name = "CoreVideo", kind = "framework")]
+extern "C" {
+    fn CVPixelBufferLockBaseAddress(buffer: *const c_void, flags: u64) -> i32;
+    fn CVPixelBufferUnlockBaseAddress(buffer: *const c_void, flags: u64) -> i32;
+    fn CVPixelBufferGetBaseAddress(buffer: *const c_void) -> *const u8;
+    fn CVPixelBufferGetWidth(buffer: *const c_void) -> usize;
+    fn CVPixelBufferGetHeight(buffer: *const c_void) -> usize;
+    fn CVPixelBufferGetBytesPerRow(buffer: *const c_void) -> usize;
+}
+
+const CV_PIXEL_BUFFER_LOCK_READ_ONLY: u64 = 1;
+
+// 画面最大宽度，超过时按比例缩小 (帧通过 IPC 传给前端，太大会卡顿)
+const MAX_WIDTH: i32 = 1600;
+const FRAMES_PER_SECOND: i32 = 10;
+const JPEG_QUALITY: u8 = 70;
+const BACKGROUND_INTERVAL: Duration = Duration::from_secs(1);
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// 当前显示的标签，只有它的画面会推送
+static ACTIVE_TAB: AtomicIsize = AtomicIsize::new(0);
+
+struct Capture {
+    tab: isize,
+    stream: SCStream,
+    paused: bool,
+    last_frame: Option<MirrorFrame>,
+    last_encoded: Option<Instant>,
+}
+
+// SCStream 内部是 Objective-C 对象的引用计数指针，ScreenCaptureKit 允许跨线程调用
+unsafe impl Send for Capture {}
+
+static CAPTURES: Mutex<Vec<Capture>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MirrorFrame {
+    pub hwnd: isize,
+    pub width: usize,
+    pub height: usize,
+    pub data: String, // data:image/jpeg;base64,...
+}
+
+/// 保存 AppHandle 用于推送画面 (setup 时调用一次)
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+struct FrameOutput {
+    tab: isize,
+}
+
+impl StreamOutput for FrameOutput {
+    fn did_output_sample_buffer(&self, sample: CMSampleBuffer, of_type: SCStreamOutputType) {
+        if !matches!(of_type, SCStreamOutputType::Screen) || !matches!(sample.frame_status, SCFrameStatus::Complete) {
+            return;
+        }
+        let active = ACTIVE_TAB.load(Ordering::SeqCst) == self.tab;
+        if !should_encode(self.tab, active) {
+            return;
+        }
+        let Some(buffer) = sample.image_buf_ref.as_ref() else {
+            return;
+        };
+        let buffer = &**buffer as *const _ as *const c_void;
+        let Some(frame) = (unsafe { encode_frame(self.tab, buffer) }) else {
+            return;
+        };
+        if let Some(capture) = CAPTURES.lock().unwrap().iter_mut().find(|c| c.tab == self.tab) {
+            capture.last_frame = Some(frame.clone());
+            capture.last_encoded = Some(Instant::now());
+        }
+        if active {
+            if let Some(app) = APP.get() {
+                let _ = app.emit("mirror-frame", frame);
+            }
+        }
+    }
+}
+
+struct ErrorHandler {
+    tab: isize,
+}
+
+impl StreamErrorHandler for ErrorHandler {
+    fn on_error(&self) {
+        // 窗口关闭后流会出错停止，由前端的存活检测负责移除标签
+        warn!(target: "embed", "窗口画面捕获中断: {}", self.tab);
+    }
+}
+
+// 读取 BGRA 像素并编码为 JPEG，像素缓冲每行末尾可能有对齐填充，需要逐行拷贝
+unsafe fn encode_frame(tab: isize, buffer: *const c_void) -> Option<MirrorFrame> {
+    if CVPixelBufferLockBaseAddress(buffer, CV_PIXEL_BUFFER_LOCK_READ_ONLY) != 0 {
+        return None;
+    }
+    let width = CVPixelBufferGetWidth(buffer);
+    let height = CVPixelBufferGetHeight(buffer);
+    let stride = CVPixelBufferGetBytesPerRow(buffer);
+    let base = CVPixelBufferGetBaseAddress(buffer);
+
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    if !base.is_null() {
+        for row in 0..height {
+            let line = std::slice::from_raw_parts(base.add(row * stride), width * 4);
+            pixels.extend_from_slice(line);
+        }
+    }
+    CVPixelBufferUnlockBaseAddress(buffer, CV_PIXEL_BUFFER_LOCK_READ_ONLY);
+    if pixels.is_empty() || width > u16::MAX as usize || height > u16::MAX as usize {
+        return None;
+    }
+
+    let mut jpeg = Vec::new();
+    Encoder::new(&mut jpeg, JPEG_QUALITY)
+        .encode(&pixels, width as u16, height as u16, ColorType::Bgra)
+        .ok()?;
+    let data = format!("data:image/jpeg;base64,{}", base64::engine::general_purpose::STANDARD.encode(&jpeg));
+    Some(MirrorFrame { hwnd: tab, width, height, data })
+}
+
+// 暂停的标签不处理，后台标签每 BACKGROUND_INTERVAL 最多编码一帧
+fn should_encode(tab: isize, active: bool) -> bool {
+    let captures = CAPTURES.lock().unwrap();
+    let Some(capture) = captures.iter().find(|c| c.tab == tab) else {
+        return false;
+    };
+    if capture.paused {
+        return false;
+    }
+    active || capture.last_encoded.map_or(true, |t| t.elapsed() >= BACKGROUND_INTERVAL)
+}
+
+/// 开始捕获窗口画面 (嵌入时调用)
+pub fn start(tab: isize, width: i32, height: i32) -> Result<(), String> {
+    if CAPTURES.lock().unwrap().iter().any(|c| c.tab == tab) {
+        return Ok(());
+    }
+
+    let content = SCShareableContent::try_current().map_err(|e| format!("无法获取屏幕内容 (需要屏幕录制权限): {}", e))?;
+    let window = content
+        .windows
+        .into_iter()
+        .find(|w| w.window_id as isize == tab)
+        .ok_or("找不到要捕获的窗口")?;
+
+    let scale = if width > MAX_WIDTH { MAX_WIDTH as f64 / width as f64 } else { 1.0 };
+    let config = SCStreamConfiguration {
+        width: ((width as f64 * scale) as u32).max(1),
+        height: ((height as f64 * scale) as u32).max(1),
+        minimum_frame_interval: CMTime { value: 1, timescale: FRAMES_PER_SECOND, flags: 1, epoch: 0 },
+        queue_depth: 3,
+        ..Default::default()
+    };
+
+    let filter = SCContentFilter::new(InitParams::DesktopIndependentWindow(window));
+    let mut stream = SCStream::new(filter, config, ErrorHandler { tab });
+    stream.add_output(FrameOutput { tab }, SCStreamOutputType::Screen);
+    stream.start_capture().map_err(|e| format!("启动画面捕获失败: {}", e))?;
+
+    info!(target: "embed", "开始镜像窗口: {} ({}x{})", tab, width, height);
+    CAPTURES.lock().unwrap().push(Capture { tab, stream, paused: false, last_frame: None, last_encoded: None });
+    Ok(())
+}
+
+/// 停止捕获 (释放标签时调用)
+pub fn stop(tab: isize) {
+    let capture = {
+        let mut captures = CAPTURES.lock().unwrap();
+        let index = captures.iter().position(|c| c.tab == tab);
+        index.map(|i| captures.remove(i))
+    };
+    if let Some(capture) = capture {
+        let _ = capture.stream.stop_capture();
+        info!(target: "embed", "停止镜像窗口: {}", tab);
+    }
+    let _ = ACTIVE_TAB.compare_exchange(tab, 0, Ordering::SeqCst, Ordering::SeqCst);
+}
+
+/// 标记当前显示的标签 (前端切换标签后调整大小时调用)，切换时立即推送缓存的画面
+pub fn set_active(tab: isize) {
+    if ACTIVE_TAB.swap(tab, Ordering::SeqCst) == tab {
+        return;
+    }
+    let frame = CAPTURES.lock().unwrap().iter().find(|c| c.tab == tab).and_then(|c| c.last_frame.clone());
+    if let (Some(app), Some(frame)) = (APP.get(), frame) {
+        let _ = app.emit("mirror-frame", frame);
+    }
+}
+
+/// 标签最近一帧画面 (data URL)，快速切换器用作缩略图
+pub fn last_frame(tab: isize) -> Option<String> {
+    CAPTURES.lock().unwrap().iter().find(|c| c.tab == tab).and_then(|c| c.last_frame.as_ref()).map(|f| f.data.clone())
+}
+
+/// 暂停/恢复推送画面 (弹出全屏面板时前端会隐藏所有标签)
+pub fn set_paused(tab: isize, paused: bool) {
+    if let Some(capture) = CAPTURES.lock().unwrap().iter_mut().find(|c| c.tab == tab) {
+        capture.paused = paused;
+    }
+}
@@ -0,0 +1,317 @@
+// 模拟平台 (cargo test 使用)
+// 在内存中模拟窗口的创建/销毁、样式、父窗口和位置，嵌入/释放按 Win32 实现的语义修改这些状态，
+// 不需要真实桌面就能测试 WindowManager 的嵌入流程
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+
+use super::{ActivateOptions, AudioSession, Platform, WindowInfo};
+use crate::window_manager::{EmbedError, EmbedErrorCode, EmbeddedWindow, WindowRect};
+
+pub const WS_CHILD: i32 = 0x4000_0000;
+pub const WS_VISIBLE: i32 = 0x1000_0000;
+pub const WS_OVERLAPPEDWINDOW: i32 = 0x00CF_0000;
+
+const OWN_PID: u32 = 1;
+const DANGEROUS_CLASSES: [&str; 2] = ["Progman", "Shell_TrayWnd"];
+
+// 没有原始状态时释放到的默认位置 (与 Win32 实现一致)
+const DEFAULT_RECT: WindowRect = WindowRect { left: 100, top: 100, right: 900, bottom: 700 };
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockWindow {
+    pub hwnd: isize,
+    pub title: String,
+    pub class_name: String,
+    pub pid: u32,
+    pub exe_path: Option<String>,
+    pub style: i32,
+    pub exstyle: i32,
+    pub rect: WindowRect,
+    pub parent: isize,
+    pub owner: isize,
+    pub visible: bool,
+    pub on_close: MockClose,
+    pub hung: bool, // 不处理消息，嵌入会超时
+    pub cloaked: bool,
+}
+
+/// 窗口收到关闭请求时的反应
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MockClose {
+    Exit,   // 直接退出
+    Prompt, // 弹出保存提示 (同一进程的新顶层窗口)
+    Ignore, // 不理会
+}
+
+pub struct MockPlatform {
+    windows: Mutex<Vec<MockWindow>>,
+    foreground: AtomicIsize,
+    audio: Mutex<Vec<AudioSession>>,
+}
+
+// 句柄在所有 MockPlatform 间唯一 (与系统一致)，并行的测试共用全局状态 (如 tab_view) 时不会互相影响
+static NEXT_HWND: AtomicIsize = AtomicIsize::new(0x1000);
+
+impl Default for MockPlatform {
+    fn default() -> Self {
+        MockPlatform { windows: Mutex::new(Vec::new()), foreground: AtomicIsize::new(0), audio: Mutex::new(Vec::new()) }
+    }
+}
+
+impl MockPlatform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建一个普通顶层窗口，返回句柄
+    pub fn create_window(&self, title: &str, class_name: &str, exe_path: Option<&str>) -> isize {
+        self.create(title, class_name, exe_path, 1000)
+    }
+
+    /// 创建一个属于 WindowHub 自己的窗口
+    pub fn create_own_window(&self, title: &str) -> isize {
+        self.create(title, "Chrome_WidgetWin_1", None, OWN_PID)
+    }
+
+    fn create(&self, title: &str, class_name: &str, exe_path: Option<&str>, pid: u32) -> isize {
+        let hwnd = NEXT_HWND.fetch_add(0x10, Ordering::SeqCst);
+        self.windows.lock().unwrap().push(MockWindow {
+            hwnd,
+            title: title.to_string(),
+            class_name: class_name.to_string(),
+            pid,
+            exe_path: exe_path.map(str::to_string),
+            style: WS_OVERLAPPEDWINDOW | WS_VISIBLE,
+            exstyle: 0,
+            rect: WindowRect { left: 200, top: 150, right: 1000, bottom: 750 },
+            parent: 0,
+            owner: 0,
+            visible: true,
+            on_close: MockClose::Exit,
+            hung: false,
+            cloaked: false,
+        });
+        hwnd
+    }
+
+    /// 模拟窗口被销毁 (应用退出)
+    pub fn destroy_window(&self, hwnd: isize) {
+        self.windows.lock().unwrap().retain(|w| w.hwnd != hwnd);
+    }
+
+    /// 设置窗口收到关闭请求时的反应
+    pub fn set_on_close(&self, hwnd: isize, on_close: MockClose) {
+        self.with_window(hwnd, |w| w.on_close = on_close);
+    }
+
+    /// 模拟应用未响应
+    pub fn set_hung(&self, hwnd: isize) {
+        self.with_window(hwnd, |w| w.hung = true);
+    }
+
+    /// 添加一个音频会话
+    pub fn add_audio_session(&self, id: &str, exe_path: &str, muted: bool) {
+        self.audio.lock().unwrap().push(AudioSession { id: id.to_string(), exe_path: exe_path.to_string(), muted });
+    }
+
+    /// 窗口当前状态的快照
+    pub fn window(&self, hwnd: isize) -> Option<MockWindow> {
+        self.windows.lock().unwrap().iter().find(|w| w.hwnd == hwnd).cloned()
+    }
+
+    fn with_window<T>(&self, hwnd: isize, f: impl FnOnce(&mut MockWindow) -> T) -> Option<T> {
+        self.windows.lock().unwrap().iter_mut().find(|w| w.hwnd == hwnd).map(f)
+    }
+}
+
+impl Platform for MockPlatform {
+    fn enumerate(&self) -> Vec<WindowInfo> {
+        self.windows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|w| w.visible && w.parent == 0 && w.pid != OWN_PID && !w.title.is_empty())
+            .map(|w| WindowInfo {
+                hwnd: w.hwnd,
+                title: w.title.clone(),
+                class_name: w.class_name.clone(),
+                width: w.rect.width(),
+                height: w.rect.height(),
+                hint: None,
+                ordinal: 0,
+                token: String::new(),
+                dpi_awareness: None,
+            })
+            .collect()
+    }
+
+    fn is_window(&self, hwnd: isize) -> bool {
+        self.window(hwnd).is_some()
+    }
+
+    fn title(&self, hwnd: isize) -> String {
+        self.window(hwnd).map(|w| w.title).unwrap_or_default()
+    }
+
+    fn class_name(&self, hwnd: isize) -> String {
+        self.window(hwnd).map(|w| w.class_name).unwrap_or_default()
+    }
+
+    fn process_path(&self, hwnd: isize) -> Result<String, String> {
+        self.window(hwnd).and_then(|w| w.exe_path).ok_or_else(|| "无法获取进程路径".to_string())
+    }
+
+    fn is_own_window(&self, hwnd: isize) -> bool {
+        self.window(hwnd).is_some_and(|w| w.pid == OWN_PID)
+    }
+
+    fn is_dangerous_class(&self, class_name: &str) -> bool {
+        DANGEROUS_CLASSES.iter().any(|d| class_name.contains(d))
+    }
+
+    fn embed(&self, host: isize, hwnd: isize) -> Result<EmbeddedWindow, EmbedError> {
+        if self.window(hwnd).is_some_and(|w| w.hung) {
+            return Err(EmbedError::new(EmbedErrorCode::Timeout));
+        }
+        let exe_path = self.process_path(hwnd).ok();
+        self.with_window(hwnd, |w| {
+            let saved = EmbeddedWindow {
+                hwnd,
+                tab_id: 0,
+                title: w.title.clone(),
+                class_name: w.class_name.clone(),
+                pid: w.pid,
+                exe_path,
+                args: Vec::new(),
+                original_style: w.style,
+                original_exstyle: w.exstyle,
+                original_layered: None,
+                original_rect: w.rect,
+                original_owner: w.owner,
+                monitor: 0,
+                embedded_at: 0,
+                label: None,
+                group: None,
+                color: None,
+                hotkey: None,
+                auto_restart: false,
+                pinned: false,
+                input_locked: false,
+                capture_excluded: false,
+                preserve_transparency: false,
+            };
+            w.style = WS_CHILD | WS_VISIBLE;
+            w.parent = host;
+            w.visible = true;
+            saved
+        })
+        .ok_or_else(|| "无效的窗口句柄".into())
+    }
+
+    fn release(&self, hwnd: isize, saved: Option<&EmbeddedWindow>) {
+        self.with_window(hwnd, |w| {
+            w.parent = 0;
+            match saved {
+                Some(saved) => {
+                    w.style = saved.original_style;
+                    w.exstyle = saved.original_exstyle;
+                    w.owner = saved.original_owner;
+                    w.rect = saved.original_rect;
+                }
+                None => {
+                    w.style = WS_OVERLAPPEDWINDOW | WS_VISIBLE;
+                    w.rect = DEFAULT_RECT;
+                }
+            }
+            w.visible = true;
+        });
+    }
+
+    fn raise(&self, hwnd: isize) {
+        if self.is_window(hwnd) {
+            self.foreground.store(hwnd, Ordering::SeqCst);
+        }
+    }
+
+    fn set_rect(&self, hwnd: isize, x: i32, y: i32, width: i32, height: i32) -> bool {
+        self.with_window(hwnd, |w| w.rect = WindowRect { left: x, top: y, right: x + width, bottom: y + height })
+            .is_some()
+    }
+
+    fn activate(&self, hwnd: isize, options: ActivateOptions) -> bool {
+        if !self.is_window(hwnd) {
+            return false;
+        }
+        if !options.raise_only {
+            self.foreground.store(hwnd, Ordering::SeqCst);
+        }
+        true
+    }
+
+    fn set_visible(&self, hwnd: isize, visible: bool) -> bool {
+        self.with_window(hwnd, |w| w.visible = visible).is_some()
+    }
+
+    fn set_cloaked(&self, hwnd: isize, cloaked: bool) -> bool {
+        self.with_window(hwnd, |w| w.cloaked = cloaked).is_some()
+    }
+
+    fn close(&self, hwnd: isize) {
+        let Some(window) = self.window(hwnd) else { return };
+        match window.on_close {
+            MockClose::Exit => self.destroy_window(hwnd),
+            MockClose::Prompt => {
+                let dialog = self.create("保存更改?", "#32770", window.exe_path.as_deref(), window.pid);
+                self.with_window(dialog, |w| w.owner = hwnd);
+            }
+            MockClose::Ignore => {}
+        }
+    }
+
+    fn process_id(&self, hwnd: isize) -> u32 {
+        self.window(hwnd).map(|w| w.pid).unwrap_or(0)
+    }
+
+    fn window_size(&self, hwnd: isize) -> Option<(i32, i32)> {
+        self.window(hwnd).map(|w| (w.rect.width(), w.rect.height()))
+    }
+
+    fn kill_process_tree(&self, pid: u32) -> Result<(), String> {
+        let mut windows = self.windows.lock().unwrap();
+        if !windows.iter().any(|w| w.pid == pid) {
+            return Err("进程不存在".to_string());
+        }
+        windows.retain(|w| w.pid != pid);
+        Ok(())
+    }
+
+    fn process_windows(&self, hwnd: isize) -> Vec<isize> {
+        let windows = self.windows.lock().unwrap();
+        let Some(pid) = windows.iter().find(|w| w.hwnd == hwnd).map(|w| w.pid) else { return Vec::new() };
+        windows.iter().filter(|w| w.hwnd != hwnd && w.pid == pid && w.visible && w.parent == 0).map(|w| w.hwnd).collect()
+    }
+
+    fn repaint(&self, hwnd: isize) -> bool {
+        self.is_window(hwnd)
+    }
+
+    fn foreground_window(&self) -> isize {
+        self.foreground.load(Ordering::SeqCst)
+    }
+
+    fn is_mouse_left_down(&self) -> bool {
+        false
+    }
+
+    fn audio_sessions(&self) -> Vec<AudioSession> {
+        self.audio.lock().unwrap().clone()
+    }
+
+    fn set_audio_session_muted(&self, id: &str, muted: bool) -> bool {
+        let mut audio = self.audio.lock().unwrap();
+        let Some(session) = audio.iter_mut().find(|s| s.id == id) else { return false };
+        session.muted = muted;
+        true
+    }
+}
@@ -0,0 +1,293 @@
+// 平台抽象层
+// 把窗口枚举、嵌入/释放、激活、布局等系统调用收拢到 Platform trait 后面，
+// 命令和 WindowManager 只依赖 trait，新平台 (Linux X11 / macOS) 可以逐个实现，
+// 也可以用模拟实现做单元测试
+//
+// 窗口句柄统一用 isize 表示 (Windows 为 HWND，macOS 为 CGWindowID，X11 为 Window ID)
+
+use serde::{Deserialize, Serialize};
+
+use crate::window_manager::{EmbedError, EmbeddedWindow};
+
+#[cfg(windows)]
+pub mod win32;
+#[cfg(windows)]
+mod audio;
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "macos")]
+pub mod mirror;
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod unsupported;
+
+#[cfg(any(windows, test))]
+pub mod style;
+
+#[cfg(test)]
+pub mod mock;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowInfo {
+    pub hwnd: isize,
+    pub title: String,
+    pub class_name: String,
+    pub width: i32,
+    pub height: i32,
+    /// 同一程序有多个窗口时区分它们的文档名或网址 (window_hints.rs)
+    #[serde(default)]
+    pub hint: Option<String>,
+    /// 同一程序的第几个窗口 (从 1 开始，窗口存在期间不变)，程序只有一个窗口时为 0
+    #[serde(default)]
+    pub ordinal: u32,
+    /// 窗口的身份标识，嵌入时随句柄一起传回，检查句柄是否已被其他窗口重新使用 (window_identity.rs)
+    #[serde(default)]
+    pub token: String,
+    /// 窗口的 DPI 感知方式，不支持 DPI 感知的旧程序由系统按位图放大，显示模糊 (见 dpi_compat.rs)
+    #[serde(default)]
+    pub dpi_awareness: Option<DpiAwareness>,
+}
+
+/// 窗口的 DPI 感知方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DpiAwareness {
+    Unaware,          // 按 96 DPI 绘制，系统位图放大 (模糊)
+    UnawareGdiScaled, // 系统增强缩放 (GDI 文字清晰，其他内容仍是位图放大)
+    System,           // 按启动时主显示器的 DPI 绘制，移到其他 DPI 的显示器上会位图缩放
+    PerMonitor,
+    PerMonitorV2,
+}
+
+/// 标签保留透明效果 (set_tab_transparency) 的结果
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransparencyResult {
+    Preserved,   // 重新应用了透明色/不透明度并确认生效
+    PerPixel,    // 保留了 WS_EX_LAYERED，应用用 UpdateLayeredWindow 逐像素绘制，能否透明取决于应用重绘 (需要提示)
+    NotLayered,  // 窗口本来就不透明，不需要处理
+    Opaque,      // 关闭选项：去掉 WS_EX_LAYERED，作为普通子窗口显示
+    Unsupported, // 系统不允许子窗口分层 (Windows 8 之前) 或设置失败，透明效果无法保留 (需要提示)
+}
+
+/// 激活窗口的方式 (activate_window 的 options)，默认为完整激活：提到最前、设为前台并给予输入焦点
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub struct ActivateOptions {
+    #[serde(default)]
+    pub raise_only: bool, // 只调整 Z 序 (悬停预览)，不挂接输入队列、不改变前台和焦点
+    #[serde(default)]
+    pub focus_only: bool, // 只给予输入焦点，不调整 Z 序
+    #[serde(default)]
+    pub no_foreground_steal: bool, // 其他程序在前台时不抢前台，只在主窗口内切换
+    #[serde(default)]
+    pub retry_count: u32, // 焦点没有落到窗口上时重试的次数
+}
+
+/// 一个音频会话 (进程在某个输出设备上的一路声音)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioSession {
+    pub id: String,
+    pub exe_path: String,
+    pub muted: bool,
+}
+
+/// 标签页的显示方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbedMode {
+    /// 窗口成为 host 的子窗口，直接显示在内容区 (Windows)
+    Reparent,
+    /// 窗口留在原处，内容区显示它的实时画面，点击时激活原窗口 (macOS)
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    Mirror,
+}
+
+/// get_platform_info 返回，前端据此决定标签页的显示方式
+#[derive(Debug, Clone, Serialize)]
+pub struct PlatformInfo {
+    pub os: &'static str,
+    pub embed_mode: EmbedMode,
+}
+
+pub trait Platform: Send + Sync {
+    /// 可以嵌入的顶层窗口 (可见、有标题、不属于本进程)
+    fn enumerate(&self) -> Vec<WindowInfo>;
+
+    fn is_window(&self, hwnd: isize) -> bool;
+
+    fn title(&self, hwnd: isize) -> String;
+
+    fn class_name(&self, hwnd: isize) -> String;
+
+    fn process_path(&self, hwnd: isize) -> Result<String, String>;
+
+    /// 窗口所属进程的启动参数 (不含程序路径)，无法获取时为 None
+    fn command_line(&self, _hwnd: isize) -> Option<Vec<String>> {
+        None
+    }
+
+    /// 窗口是否属于 WindowHub 自己
+    fn is_own_window(&self, hwnd: isize) -> bool;
+
+    /// 嵌入后可能导致系统不稳定的窗口类型 (桌面、任务栏等)
+    fn is_dangerous_class(&self, _class_name: &str) -> bool {
+        false
+    }
+
+    fn embed_mode(&self) -> EmbedMode {
+        EmbedMode::Reparent
+    }
+
+    /// 记录原始状态后把窗口嵌入到 host 中
+    fn embed(&self, host: isize, hwnd: isize) -> Result<EmbeddedWindow, EmbedError>;
+
+    /// 已嵌入的窗口被改回了顶层窗口 (如资源管理器重启后) 时重新设置子窗口样式和父窗口，返回是否做了修复
+    fn reassert_embedded(&self, _host: isize, _hwnd: isize) -> bool {
+        false
+    }
+
+    /// 解除嵌入，有原始状态时按原样恢复，否则恢复成普通顶层窗口
+    fn release(&self, hwnd: isize, saved: Option<&EmbeddedWindow>);
+
+    /// 把顶层窗口带到前台 (释放后使用，不做嵌入窗口的焦点修复)
+    fn raise(&self, hwnd: isize);
+
+    /// 设置嵌入窗口在 host 客户区中的位置和大小，窗口无效时返回 false
+    fn set_rect(&self, hwnd: isize, x: i32, y: i32, width: i32, height: i32) -> bool;
+
+    /// 连续调整过程中的中间位置，可以跳过重绘 (调整结束后调用 repaint)
+    fn set_rect_fast(&self, hwnd: isize, x: i32, y: i32, width: i32, height: i32) -> bool {
+        self.set_rect(hwnd, x, y, width, height)
+    }
+
+    /// 按 options 激活窗口 (默认提到最前并给予输入焦点)，窗口无效时返回 false
+    fn activate(&self, hwnd: isize, options: ActivateOptions) -> bool;
+
+    fn set_visible(&self, hwnd: isize, visible: bool) -> bool;
+
+    /// 隐藏窗口画面但保留布局 (DWM cloak)，窗口仍然接收尺寸变化和绘制，不支持时返回 false
+    fn set_cloaked(&self, _hwnd: isize, _cloaked: bool) -> bool {
+        false
+    }
+
+    /// 启用或禁用窗口的鼠标和键盘输入 (禁用后仍然正常绘制)，不支持时返回 false
+    fn set_input_enabled(&self, _hwnd: isize, _enabled: bool) -> bool {
+        false
+    }
+
+    /// 嵌入期间保留 (preserve) 或去掉窗口的分层透明效果，saved 为嵌入时的记录
+    fn set_layered(&self, _hwnd: isize, _saved: &EmbeddedWindow, _preserve: bool) -> TransparencyResult {
+        TransparencyResult::Unsupported
+    }
+
+    /// 请求窗口关闭 (应用可以弹出保存提示)
+    fn close(&self, hwnd: isize);
+
+    /// 窗口所属进程的 ID，无法获取时为 0
+    fn process_id(&self, _hwnd: isize) -> u32 {
+        0
+    }
+
+    /// 进程的创建时间 (与进程 ID 一起唯一标识进程，ID 会被新进程重新使用)，无法获取时为 None
+    fn process_start_time(&self, _pid: u32) -> Option<u64> {
+        None
+    }
+
+    /// 窗口小图标的句柄 (HICON)，没有图标或不支持时为 0
+    fn icon_handle(&self, _hwnd: isize) -> isize {
+        0
+    }
+
+    /// 进程已经运行了多少秒，无法获取时为 None
+    fn process_age_secs(&self, _pid: u32) -> Option<u64> {
+        None
+    }
+
+    /// 窗口外框的宽高，窗口无效时为 None
+    fn window_size(&self, _hwnd: isize) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// 强制结束进程及其子进程 (应用卡死时使用，不会弹出保存提示)
+    fn kill_process_tree(&self, _pid: u32) -> Result<(), String> {
+        Err("当前平台不支持结束进程".to_string())
+    }
+
+    /// 与 hwnd 同一进程的其他可见顶层窗口 (关闭时据此发现应用弹出的保存提示)
+    fn process_windows(&self, _hwnd: isize) -> Vec<isize> {
+        Vec::new()
+    }
+
+    /// 窗口的 DPI 感知方式，无法获取时为 None
+    fn dpi_awareness(&self, _hwnd: isize) -> Option<DpiAwareness> {
+        None
+    }
+
+    /// 强制重绘 (修复嵌入后黑屏)
+    fn repaint(&self, hwnd: isize) -> bool;
+
+    fn foreground_window(&self) -> isize;
+
+    fn is_mouse_left_down(&self) -> bool;
+
+    /// 是否有修饰键 (Ctrl/Alt/Shift/Win) 按着，快速切换器在全部松开时确认选择
+    fn is_modifier_down(&self) -> bool {
+        false
+    }
+
+    /// 当前的音频会话 (免打扰时静音嵌入应用)
+    fn audio_sessions(&self) -> Vec<AudioSession> {
+        Vec::new()
+    }
+
+    /// 静音或取消静音音频会话，会话已不存在时返回 false
+    fn set_audio_session_muted(&self, _id: &str, _muted: bool) -> bool {
+        false
+    }
+
+    /// 是否有程序处于独占全屏 (Direct3D 全屏游戏等)
+    fn exclusive_fullscreen_active(&self) -> bool {
+        false
+    }
+
+    /// 窗口是否盖住了所在的整个显示器
+    fn covers_monitor(&self, _hwnd: isize) -> bool {
+        false
+    }
+
+    /// 窗口缩略图 (JPEG data URL)，按比例缩小到 max_width x max_height 以内，无法获取时为 None
+    fn thumbnail(&self, _hwnd: isize, _max_width: i32, _max_height: i32) -> Option<String> {
+        None
+    }
+
+    /// 窗口原始大小的画面，返回 (宽, 高, 自上而下的 BGRA 像素)，无法获取时为 None
+    fn capture(&self, _hwnd: isize) -> Option<(i32, i32, Vec<u8>)> {
+        None
+    }
+}
+
+/// 当前系统的平台实现
+pub fn current() -> &'static dyn Platform {
+    #[cfg(windows)]
+    {
+        static PLATFORM: win32::Win32Platform = win32::Win32Platform;
+        &PLATFORM
+    }
+    #[cfg(target_os = "macos")]
+    {
+        static PLATFORM: macos::MacPlatform = macos::MacPlatform;
+        &PLATFORM
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        static PLATFORM: unsupported::UnsupportedPlatform = unsupported::UnsupportedPlatform;
+        &PLATFORM
+    }
+}
+
+/// 当前系统名称和标签页显示方式
+pub fn info() -> PlatformInfo {
+    PlatformInfo { os: std::env::consts::OS, embed_mode: current().embed_mode() }
+}
@@ -0,0 +1,114 @@
+// 窗口样式位的比较和描述
+// 嵌入/释放会改写 GWL_STYLE / GWL_EXSTYLE，每次改写记录前后的值，
+// 日志中列出增加和去掉的位，样式被破坏 (如释放后没有标题栏) 时可以据此排查和还原
+
+/// 一次样式修改前后的值
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StyleChange {
+    pub before: u32,
+    pub after: u32,
+}
+
+impl StyleChange {
+    pub fn added(&self) -> u32 {
+        self.after & !self.before
+    }
+
+    pub fn removed(&self) -> u32 {
+        self.before & !self.after
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.before == self.after
+    }
+
+    /// "+WS_CHILD -WS_CAPTION" 形式的描述，没有名称的位以十六进制列出
+    pub fn describe(&self, names: &[(u32, &str)]) -> String {
+        let mut parts = Vec::new();
+        for (sign, bits) in [('+', self.added()), ('-', self.removed())] {
+            let mut rest = bits;
+            for &(mask, name) in names {
+                if rest & mask == mask {
+                    parts.push(format!("{}{}", sign, name));
+                    rest &= !mask;
+                }
+            }
+            if rest != 0 {
+                parts.push(format!("{}0x{:08X}", sign, rest));
+            }
+        }
+        parts.join(" ")
+    }
+}
+
+/// 样式中已设置的位的名称，没有名称的位以十六进制列出
+pub fn set_names(value: u32, names: &[(u32, &str)]) -> Vec<String> {
+    let mut rest = value;
+    let mut parts = Vec::new();
+    for &(mask, name) in names {
+        if rest & mask == mask {
+            parts.push(name.to_string());
+            rest &= !mask;
+        }
+    }
+    if rest != 0 {
+        parts.push(format!("0x{:08X}", rest));
+    }
+    parts
+}
+
+/// GWL_STYLE 的位名称 (组合位 WS_CAPTION 排在其组成部分之前)
+pub const STYLE_NAMES: &[(u32, &str)] = &[
+    (0x8000_0000, "WS_POPUP"),
+    (0x4000_0000, "WS_CHILD"),
+    (0x2000_0000, "WS_MINIMIZE"),
+    (0x1000_0000, "WS_VISIBLE"),
+    (0x0800_0000, "WS_DISABLED"),
+    (0x0400_0000, "WS_CLIPSIBLINGS"),
+    (0x0200_0000, "WS_CLIPCHILDREN"),
+    (0x0100_0000, "WS_MAXIMIZE"),
+    (0x00C0_0000, "WS_CAPTION"),
+    (0x0080_0000, "WS_BORDER"),
+    (0x0040_0000, "WS_DLGFRAME"),
+    (0x0020_0000, "WS_VSCROLL"),
+    (0x0010_0000, "WS_HSCROLL"),
+    (0x0008_0000, "WS_SYSMENU"),
+    (0x0004_0000, "WS_THICKFRAME"),
+    (0x0002_0000, "WS_MINIMIZEBOX"),
+    (0x0001_0000, "WS_MAXIMIZEBOX"),
+];
+
+/// GWL_EXSTYLE 的常见位名称
+pub const EX_STYLE_NAMES: &[(u32, &str)] = &[
+    (0x0000_0001, "WS_EX_DLGMODALFRAME"),
+    (0x0000_0008, "WS_EX_TOPMOST"),
+    (0x0000_0010, "WS_EX_ACCEPTFILES"),
+    (0x0000_0020, "WS_EX_TRANSPARENT"),
+    (0x0000_0040, "WS_EX_MDICHILD"),
+    (0x0000_0080, "WS_EX_TOOLWINDOW"),
+    (0x0000_0100, "WS_EX_WINDOWEDGE"),
+    (0x0000_0200, "WS_EX_CLIENTEDGE"),
+    (0x0004_0000, "WS_EX_APPWINDOW"),
+    (0x0008_0000, "WS_EX_LAYERED"),
+    (0x0010_0000, "WS_EX_NOINHERITLAYOUT"),
+    (0x0200_0000, "WS_EX_COMPOSITED"),
+    (0x0800_0000, "WS_EX_NOACTIVATE"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_added_and_removed_bits() {
+        // WS_OVERLAPPEDWINDOW | WS_VISIBLE -> 嵌入后的子窗口样式
+        let change = StyleChange { before: 0x10CF_0000, after: 0x5400_0000 };
+        assert_eq!(
+            change.describe(STYLE_NAMES),
+            "+WS_CHILD +WS_CLIPSIBLINGS -WS_CAPTION -WS_SYSMENU -WS_THICKFRAME -WS_MINIMIZEBOX -WS_MAXIMIZEBOX"
+        );
+        assert_eq!(StyleChange { before: 0, after: 0x0000_0004 }.describe(STYLE_NAMES), "+0x00000004");
+        assert!(StyleChange { before: 7, after: 7 }.is_empty());
+        assert_eq!(set_names(0x5400_0004, STYLE_NAMES), vec!["WS_CHILD", "WS_VISIBLE", "WS_CLIPSIBLINGS", "0x00000004"]);
+    }
+}
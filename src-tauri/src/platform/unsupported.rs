@@ -0,0 +1,68 @@
+// 尚未实现的平台：所有窗口操作都失败，应用本身可以启动但无法嵌入窗口
+
+use super::{ActivateOptions, Platform, WindowInfo};
+use crate::window_manager::{EmbedError, EmbeddedWindow};
+
+const UNSUPPORTED: &str = "仅支持 Windows 和 macOS";
+
+pub struct UnsupportedPlatform;
+
+impl Platform for UnsupportedPlatform {
+    fn enumerate(&self) -> Vec<WindowInfo> {
+        Vec::new()
+    }
+
+    fn is_window(&self, _hwnd: isize) -> bool {
+        false
+    }
+
+    fn title(&self, _hwnd: isize) -> String {
+        String::new()
+    }
+
+    fn class_name(&self, _hwnd: isize) -> String {
+        String::new()
+    }
+
+    fn process_path(&self, _hwnd: isize) -> Result<String, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    fn is_own_window(&self, _hwnd: isize) -> bool {
+        false
+    }
+
+    fn embed(&self, _host: isize, _hwnd: isize) -> Result<EmbeddedWindow, EmbedError> {
+        Err(UNSUPPORTED.into())
+    }
+
+    fn release(&self, _hwnd: isize, _saved: Option<&EmbeddedWindow>) {}
+
+    fn raise(&self, _hwnd: isize) {}
+
+    fn set_rect(&self, _hwnd: isize, _x: i32, _y: i32, _width: i32, _height: i32) -> bool {
+        false
+    }
+
+    fn activate(&self, _hwnd: isize, _options: ActivateOptions) -> bool {
+        false
+    }
+
+    fn set_visible(&self, _hwnd: isize, _visible: bool) -> bool {
+        false
+    }
+
+    fn close(&self, _hwnd: isize) {}
+
+    fn repaint(&self, _hwnd: isize) -> bool {
+        false
+    }
+
+    fn foreground_window(&self) -> isize {
+        0
+    }
+
+    fn is_mouse_left_down(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,1148 @@
+// Windows 平台实现 (Win32 SetParent 嵌入)
+
+use super::style::{StyleChange, EX_STYLE_NAMES, STYLE_NAMES};
+use tracing::{debug, warn};
+use super::{ActivateOptions, AudioSession, DpiAwareness, Platform, TransparencyResult, WindowInfo};
+use crate::window_manager::{EmbedError, EmbedErrorCode, EmbeddedWindow, LayeredAttributes};
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use jpeg_encoder::{ColorType, Encoder};
+use windows::core::PCWSTR;
+use windows::Wdk::System::Threading::{NtQueryInformationProcess, ProcessCommandLineInformation};
+use windows::Win32::{
+    Foundation::{BOOL, COLORREF, HWND, LPARAM, RECT, TRUE, WPARAM, POINT},
+    Foundation::{CloseHandle, GetLastError, FILETIME, LocalFree, SetLastError, HLOCAL, UNICODE_STRING, WIN32_ERROR},
+    UI::Shell::{CommandLineToArgvW, SHQueryUserNotificationState, QUNS_RUNNING_D3D_FULL_SCREEN},
+    Graphics::Gdi::{InvalidateRect, ScreenToClient, RedrawWindow, RDW_ERASE, RDW_INVALIDATE, RDW_FRAME, RDW_ALLCHILDREN, RDW_UPDATENOW, RDW_INTERNALPAINT, MonitorFromWindow, MONITOR_DEFAULTTONEAREST, GetMonitorInfoW, MONITORINFO},
+    Graphics::Gdi::{
+        CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits, ReleaseDC, SelectObject,
+        SetStretchBltMode, StretchBlt, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HALFTONE, SRCCOPY,
+    },
+    Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_CLOAK},
+    Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS},
+    UI::Input::KeyboardAndMouse::{EnableWindow, GetAsyncKeyState, SetFocus, SetActiveWindow, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT},
+    UI::Input::Ime::{ImmGetDefaultIMEWnd, ISC_SHOWUIALL, IMN_SETCANDIDATEPOS, IMN_SETCOMPOSITIONWINDOW},
+    UI::WindowsAndMessaging::*,
+    System::ProcessStatus::K32GetModuleFileNameExW,
+    System::Threading::{GetCurrentProcessId, GetCurrentThreadId, AttachThreadInput, GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
+};
+
+pub struct Win32Platform;
+
+// 调用 Win32 函数，API 跟踪开启时记录参数、返回值和 GetLastError (见 api_trace.rs)：traced!(hwnd, SetParent(hwnd, parent))
+// 开启时参数会再求值一次用于记录，只用于没有副作用的参数；返回前恢复调用后的 last error
+macro_rules! traced {
+    ($hwnd:expr, $function:ident($($arg:expr),* $(,)?)) => {{
+        let tracing = crate::api_trace::is_enabled();
+        if tracing {
+            SetLastError(WIN32_ERROR(0));
+        }
+        let result = $function($($arg),*);
+        if tracing {
+            let last_error = GetLastError();
+            let args = [$(format!("{:?}", $arg)),*].join(", ");
+            crate::api_trace::record($hwnd.0 as isize, stringify!($function), args, format!("{:?}", result), last_error.0);
+            // 记录时的格式化、加锁和写文件可能改掉 last error，还原后调用方仍能用 GetLastError 判断
+            SetLastError(last_error);
+        }
+        result
+    }};
+}
+
+fn hwnd_of(raw: isize) -> HWND {
+    HWND(raw as *mut _)
+}
+
+pub(crate) fn is_own_window(hwnd: HWND) -> bool {
+    unsafe {
+        let mut pid = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        pid == GetCurrentProcessId()
+    }
+}
+
+/// 进程的 EXE 路径
+pub(crate) fn process_path_of(pid: u32) -> Result<String, String> {
+    unsafe {
+        // 打开进程
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)
+            .map_err(|e| format!("无法打开进程: {}", e))?;
+
+        // 获取路径
+        let mut buffer = [0u16; 1024];
+        let len = K32GetModuleFileNameExW(process, None, &mut buffer);
+        let _ = CloseHandle(process);
+
+        if len == 0 {
+            return Err("无法获取进程路径".to_string());
+        }
+
+        Ok(String::from_utf16_lossy(&buffer[..len as usize]))
+    }
+}
+
+// 辅助：获取类名
+pub(crate) unsafe fn class_name(hwnd: HWND) -> String {
+    let mut class_buf = [0u16; 256];
+    let class_len = GetClassNameW(hwnd, &mut class_buf);
+    String::from_utf16_lossy(&class_buf[..class_len as usize])
+}
+
+pub(crate) unsafe fn window_title(hwnd: HWND) -> String {
+    let len = GetWindowTextLengthW(hwnd);
+    if len == 0 { return String::new(); }
+    let mut buf = vec![0u16; (len + 1) as usize];
+    GetWindowTextW(hwnd, &mut buf);
+    String::from_utf16_lossy(&buf[..len as usize])
+}
+
+unsafe fn dpi_awareness(hwnd: HWND) -> Option<DpiAwareness> {
+    use windows::Win32::UI::HiDpi::{
+        AreDpiAwarenessContextsEqual, GetAwarenessFromDpiAwarenessContext, GetWindowDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+        DPI_AWARENESS_CONTEXT_UNAWARE_GDISCALED, DPI_AWARENESS_PER_MONITOR_AWARE, DPI_AWARENESS_SYSTEM_AWARE, DPI_AWARENESS_UNAWARE,
+    };
+
+    let context = GetWindowDpiAwarenessContext(hwnd);
+    if context.is_invalid() {
+        return None;
+    }
+    // V2 和 GDI 缩放是上下文的变体，GetAwarenessFromDpiAwarenessContext 分别报告为按显示器和不感知
+    if AreDpiAwarenessContextsEqual(context, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2).as_bool() {
+        return Some(DpiAwareness::PerMonitorV2);
+    }
+    if AreDpiAwarenessContextsEqual(context, DPI_AWARENESS_CONTEXT_UNAWARE_GDISCALED).as_bool() {
+        return Some(DpiAwareness::UnawareGdiScaled);
+    }
+    match GetAwarenessFromDpiAwarenessContext(context) {
+        DPI_AWARENESS_UNAWARE => Some(DpiAwareness::Unaware),
+        DPI_AWARENESS_SYSTEM_AWARE => Some(DpiAwareness::System),
+        DPI_AWARENESS_PER_MONITOR_AWARE => Some(DpiAwareness::PerMonitor),
+        _ => None,
+    }
+}
+
+unsafe extern "system" fn enum_window_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let windows = &mut *(lparam.0 as *mut Vec<WindowInfo>);
+    if !IsWindowVisible(hwnd).as_bool() { return TRUE; }
+    if is_own_window(hwnd) { return TRUE; }
+    let title = window_title(hwnd);
+    if title.is_empty() || title.contains("WindowHub") { return TRUE; }
+
+    let class_name = class_name(hwnd);
+    let excluded = ["Progman", "Shell_TrayWnd", "Shell_SecondaryTrayWnd",
+                    "Windows.UI.Core.CoreWindow", "ApplicationFrameWindow",
+                    "WorkerW", "TaskManagerWindow"];
+    if excluded.contains(&class_name.as_str()) { return TRUE; }
+
+    let mut rect = RECT::default();
+    if GetWindowRect(hwnd, &mut rect).is_ok() {
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+        if width > 100 && height > 100 {
+            windows.push(WindowInfo {
+                hwnd: hwnd.0 as isize,
+                title,
+                class_name,
+                width,
+                height,
+                hint: None,
+                ordinal: 0,
+                token: String::new(),
+                dpi_awareness: dpi_awareness(hwnd),
+            });
+        }
+    }
+    TRUE
+}
+
+// process_windows 的枚举参数：目标进程、要排除的窗口、结果
+struct ProcessWindows {
+    pid: u32,
+    exclude: HWND,
+    found: Vec<isize>,
+}
+
+unsafe extern "system" fn process_window_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let query = &mut *(lparam.0 as *mut ProcessWindows);
+    if hwnd == query.exclude || !IsWindowVisible(hwnd).as_bool() { return TRUE; }
+    let mut pid = 0;
+    GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    if pid == query.pid {
+        query.found.push(hwnd.0 as isize);
+    }
+    TRUE
+}
+
+/// 窗口样式 (GWL_STYLE / GWL_EXSTYLE) 的读写，统一使用指针大小的 Get/SetWindowLongPtrW：
+/// 操作前确认窗口仍然存在，检查调用是否失败，写入后读回实际生效的值，返回改动了哪些位
+pub(crate) struct WindowStyle {
+    hwnd: HWND,
+    index: WINDOW_LONG_PTR_INDEX,
+}
+
+impl WindowStyle {
+    pub(crate) fn style(hwnd: HWND) -> Self {
+        WindowStyle { hwnd, index: GWL_STYLE }
+    }
+
+    pub(crate) fn ex_style(hwnd: HWND) -> Self {
+        WindowStyle { hwnd, index: GWL_EXSTYLE }
+    }
+
+    fn names(&self) -> &'static [(u32, &'static str)] {
+        if self.index == GWL_EXSTYLE { EX_STYLE_NAMES } else { STYLE_NAMES }
+    }
+
+    pub(crate) unsafe fn get(&self) -> Result<u32, String> {
+        if !IsWindow(self.hwnd).as_bool() {
+            return Err("无效的窗口句柄".to_string());
+        }
+        // 返回 0 时可能是样式本身为 0，需要结合 GetLastError 判断
+        SetLastError(WIN32_ERROR(0));
+        let value = GetWindowLongPtrW(self.hwnd, self.index);
+        if value == 0 && GetLastError().is_err() {
+            return Err(format!("读取窗口样式失败: {:?}", GetLastError()));
+        }
+        Ok(value as u32)
+    }
+
+    /// 写入样式，返回修改前和读回的值；系统调整了部分位时记录警告
+    pub(crate) unsafe fn set(&self, value: u32) -> Result<StyleChange, String> {
+        let before = self.get()?;
+        if before == value {
+            return Ok(StyleChange { before, after: value });
+        }
+        SetLastError(WIN32_ERROR(0));
+        if traced!(self.hwnd, SetWindowLongPtrW(self.hwnd, self.index, value as i32 as isize)) == 0 && GetLastError().is_err() {
+            return Err(format!("修改窗口样式失败: {:?}", GetLastError()));
+        }
+        let after = self.get()?;
+        let change = StyleChange { before, after };
+        if after != value {
+            let rejected = StyleChange { before: value, after };
+            warn!(target: "embed", "窗口样式未完全生效: hwnd={:?}, 差异 {}", self.hwnd.0, rejected.describe(self.names()));
+        }
+        if !change.is_empty() {
+            debug!(target: "embed", "窗口样式: hwnd={:?}, {}", self.hwnd.0, change.describe(self.names()));
+        }
+        Ok(change)
+    }
+
+    /// 增加 add 中的位、去掉 remove 中的位
+    pub(crate) unsafe fn update(&self, add: u32, remove: u32) -> Result<StyleChange, String> {
+        let current = self.get()?;
+        self.set((current & !remove) | add)
+    }
+}
+
+/// 嵌入过程中对目标窗口做的修改，任何一步失败时按相反顺序撤销，窗口恢复成嵌入前的样子
+/// 全部成功后调用 commit，否则离开作用域时自动回滚
+struct EmbedTransaction {
+    hwnd: HWND,
+    undo: Vec<EmbedUndo>,
+    committed: bool,
+}
+
+enum EmbedUndo {
+    Style(WindowStyle, u32),
+    Parent { owner: HWND, rect: RECT },
+}
+
+impl EmbedTransaction {
+    fn new(hwnd: HWND) -> Self {
+        EmbedTransaction { hwnd, undo: Vec::new(), committed: false }
+    }
+
+    unsafe fn set_style(&mut self, style: WindowStyle, add: u32, remove: u32) -> Result<(), String> {
+        let change = style.update(add, remove)?;
+        self.undo.push(EmbedUndo::Style(style, change.before));
+        Ok(())
+    }
+
+    unsafe fn set_parent(&mut self, parent: HWND) -> Result<(), String> {
+        let owner = GetWindow(self.hwnd, GW_OWNER).unwrap_or_default();
+        let mut rect = RECT::default();
+        let _ = GetWindowRect(self.hwnd, &mut rect);
+        // 顶层窗口的"前一个父窗口"是桌面，返回值不可靠，以实际的父窗口判断是否成功
+        let result = traced!(self.hwnd, SetParent(self.hwnd, parent));
+        if traced!(self.hwnd, GetAncestor(self.hwnd, GA_PARENT)) != parent {
+            return Err(format!("设置父窗口失败: {:?}", result.err()));
+        }
+        self.undo.push(EmbedUndo::Parent { owner, rect });
+        Ok(())
+    }
+
+    fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for EmbedTransaction {
+    fn drop(&mut self) {
+        if self.committed || self.undo.is_empty() {
+            return;
+        }
+        warn!(target: "embed", "嵌入失败，撤销对窗口的 {} 项修改: hwnd={:?}", self.undo.len(), self.hwnd.0);
+        unsafe {
+            while let Some(step) = self.undo.pop() {
+                match step {
+                    EmbedUndo::Style(style, before) => {
+                        let _ = style.set(before);
+                    }
+                    EmbedUndo::Parent { owner, rect } => {
+                        let _ = traced!(self.hwnd, SetParent(self.hwnd, HWND::default()));
+                        if !owner.is_invalid() {
+                            traced!(self.hwnd, SetWindowLongPtrW(self.hwnd, GWLP_HWNDPARENT, owner.0 as isize));
+                        }
+                        let _ = traced!(
+                            self.hwnd,
+                            SetWindowPos(self.hwnd, HWND_TOP, rect.left, rect.top, rect.right - rect.left, rect.bottom - rect.top, SWP_NOACTIVATE)
+                        );
+                    }
+                }
+            }
+            let _ = traced!(
+                self.hwnd,
+                SetWindowPos(self.hwnd, HWND::default(), 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED)
+            );
+        }
+    }
+}
+
+/// 临时把当前线程的输入队列挂接到目标窗口的线程 (跨进程切换前台和焦点需要)，离开作用域时断开
+/// 同一线程不需要挂接；目标线程未响应时不挂接，否则本线程会跟着卡住
+pub(crate) struct ThreadInputAttachment {
+    current: u32,
+    target: u32,
+    attached: bool,
+}
+
+impl ThreadInputAttachment {
+    pub(crate) unsafe fn new(hwnd: HWND) -> Self {
+        let current = GetCurrentThreadId();
+        let target = GetWindowThreadProcessId(hwnd, None);
+        let attached = target != 0
+            && target != current
+            && !IsHungAppWindow(hwnd).as_bool()
+            && AttachThreadInput(current, target, true).as_bool();
+        ThreadInputAttachment { current, target, attached }
+    }
+
+    /// 目标窗口所属的线程
+    pub(crate) fn target_thread(&self) -> u32 {
+        self.target
+    }
+}
+
+impl Drop for ThreadInputAttachment {
+    fn drop(&mut self) {
+        if self.attached {
+            unsafe {
+                let _ = AttachThreadInput(self.current, self.target, false);
+            }
+        }
+    }
+}
+
+/// 恢复嵌入前的样式、所有者和位置
+unsafe fn restore_original_state(hwnd: HWND, saved: &EmbeddedWindow) {
+    for (style, original) in [
+        (WindowStyle::style(hwnd), saved.original_style),
+        (WindowStyle::ex_style(hwnd), saved.original_exstyle),
+    ] {
+        if let Err(e) = style.set(original as u32) {
+            warn!(target: "embed", "恢复窗口样式失败: hwnd={:?}, {}", hwnd.0, e);
+        }
+    }
+    // 重新加上 WS_EX_LAYERED 后要再设置一次透明属性，否则窗口不显示
+    if let Some(layered) = saved.original_layered {
+        apply_layered(hwnd, layered);
+    }
+    if saved.original_owner != 0 {
+        traced!(hwnd, SetWindowLongPtrW(hwnd, GWLP_HWNDPARENT, saved.original_owner));
+    }
+    // WS_EX_TOPMOST 只是样式位，真正置顶要用 HWND_TOPMOST 调整 Z 序
+    let insert_after = if saved.original_exstyle as u32 & WS_EX_TOPMOST.0 != 0 { HWND_TOPMOST } else { HWND_TOP };
+    let rect = saved.original_rect;
+    let _ = traced!(hwnd, SetWindowPos(hwnd, insert_after, rect.left, rect.top, rect.width(), rect.height(), SWP_FRAMECHANGED | SWP_SHOWWINDOW));
+}
+
+unsafe fn apply_layered(hwnd: HWND, layered: LayeredAttributes) {
+    let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS(0);
+    if layered.color_key.is_some() {
+        flags |= LWA_COLORKEY;
+    }
+    if layered.alpha.is_some() {
+        flags |= LWA_ALPHA;
+    }
+    let color_key = COLORREF(layered.color_key.unwrap_or(0));
+    let _ = traced!(hwnd, SetLayeredWindowAttributes(hwnd, color_key, layered.alpha.unwrap_or(255), flags));
+}
+
+/// 分层窗口用 SetLayeredWindowAttributes 设置的透明属性 (用 UpdateLayeredWindow 绘制的窗口取不到，为 None)
+unsafe fn layered_attributes(hwnd: HWND, exstyle: u32) -> Option<LayeredAttributes> {
+    if exstyle & WS_EX_LAYERED.0 == 0 {
+        return None;
+    }
+    let (mut color_key, mut alpha, mut flags) = (COLORREF(0), 0u8, LAYERED_WINDOW_ATTRIBUTES_FLAGS(0));
+    GetLayeredWindowAttributes(hwnd, Some(&mut color_key), Some(&mut alpha), Some(&mut flags)).ok()?;
+    Some(LayeredAttributes {
+        color_key: flags.contains(LWA_COLORKEY).then_some(color_key.0),
+        alpha: flags.contains(LWA_ALPHA).then_some(alpha),
+    })
+}
+
+// 激活时最多重试的次数和间隔
+const MAX_ACTIVATE_RETRIES: u32 = 5;
+const ACTIVATE_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+unsafe fn activate(hwnd: HWND, options: ActivateOptions) -> bool {
+    // 检查窗口是否有效
+    if !IsWindow(hwnd).as_bool() {
+        return false;
+    }
+
+    // 只调整 Z 序：不挂接输入队列、不改变前台和焦点，悬停时频繁调用也不会闪烁；
+    // 也不显示窗口，隐藏着的标签 (其他标签页、切换过渡中) 保持隐藏
+    if options.raise_only {
+        let _ = traced!(hwnd, SetWindowPos(hwnd, HWND_TOP, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE));
+        return true;
+    }
+
+    // 其他程序在前台时不抢前台 (主窗口在前台时照常激活)
+    let steal_foreground = !options.no_foreground_steal || is_own_window(GetForegroundWindow());
+    let retries = options.retry_count.min(MAX_ACTIVATE_RETRIES);
+    for attempt in 0..=retries {
+        if attempt > 0 {
+            std::thread::sleep(ACTIVATE_RETRY_DELAY);
+        }
+        activate_once(hwnd, options.focus_only, steal_foreground);
+        if retries == 0 || has_focus(hwnd) {
+            return true;
+        }
+    }
+    debug!(target: "focus", "激活 {} 次后焦点仍不在窗口上: hwnd={:?}", retries + 1, hwnd.0);
+    false
+}
+
+unsafe fn activate_once(hwnd: HWND, focus_only: bool, steal_foreground: bool) {
+    let attachment = ThreadInputAttachment::new(hwnd);
+    let id_target = attachment.target_thread();
+
+    if !focus_only {
+        // 尝试强制前台
+        if IsIconic(hwnd).as_bool() {
+            let _ = traced!(hwnd, ShowWindow(hwnd, SW_RESTORE));
+        }
+
+        // 关键修复：使用 SetForegroundWindow 确保焦点
+        if steal_foreground {
+            let _ = traced!(hwnd, SetForegroundWindow(hwnd));
+        }
+        let _ = traced!(hwnd, BringWindowToTop(hwnd));
+
+        let _ = traced!(hwnd, SetWindowPos(hwnd, HWND_TOP, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_SHOWWINDOW));
+
+        // 关键修复：发送 WM_NCACTIVATE 欺骗窗口它已被激活 (对 Chrome/VSCode/Electron 应用至关重要)
+        let _ = traced!(hwnd, PostMessageW(hwnd, WM_NCACTIVATE, WPARAM(1), LPARAM(0)));
+
+        // 新增修复：发送 WM_ACTIVATE (WA_ACTIVE=1) 欺骗客户区它已被激活 (针对飞书/Electron/游戏)
+        let _ = traced!(hwnd, PostMessageW(hwnd, WM_ACTIVATE, WPARAM(1), LPARAM(0)));
+    }
+
+    // 关键修复：不仅 SetFocus，还要 SetActiveWindow，确保输入法上下文被激活
+    // 远程桌面窗口的焦点交给内部接收键盘的子窗口
+    if steal_foreground {
+        let _ = traced!(hwnd, SetActiveWindow(hwnd));
+    }
+    let focus = input_target(hwnd);
+    let _ = traced!(hwnd, SetFocus(focus));
+
+    if !focus_only {
+        // 再次强制重绘，确保激活状态正确显示
+        let _ = RedrawWindow(hwnd, None, None, RDW_INVALIDATE | RDW_UPDATENOW | RDW_ALLCHILDREN);
+    }
+
+    // 输入法：让目标线程重新激活输入上下文，并刷新候选框位置
+    refresh_ime_context(hwnd, id_target);
+
+    // 永久挂接会导致所有输入法失效 (死锁或队列冲突)，激活完成后立即断开
+    drop(attachment);
+    // 断开后输入法可能被切回主窗口，再激活一次 (消息在目标线程排队处理，不依赖挂接)
+    refresh_ime_context(hwnd, id_target);
+}
+
+// 窗口所属线程的输入焦点在窗口或其子窗口上
+unsafe fn has_focus(hwnd: HWND) -> bool {
+    let thread = GetWindowThreadProcessId(hwnd, None);
+    let mut gui = GUITHREADINFO { cbSize: std::mem::size_of::<GUITHREADINFO>() as u32, ..Default::default() };
+    if GetGUIThreadInfo(thread, &mut gui).is_err() || gui.hwndFocus.is_invalid() {
+        return false;
+    }
+    gui.hwndFocus == hwnd || IsChild(hwnd, gui.hwndFocus).as_bool()
+}
+
+// 激活时接收输入焦点的窗口：远程桌面窗口 (TscShellContainerClass) 为内部的 IHWindowClass，其他为自身
+unsafe fn input_target(hwnd: HWND) -> HWND {
+    if !crate::rdp::is_rdp_class(&class_name(hwnd)) {
+        return hwnd;
+    }
+    let mut found = HWND::default();
+    let _ = EnumChildWindows(hwnd, Some(find_input_child), LPARAM(&mut found as *mut HWND as isize));
+    if found.is_invalid() { hwnd } else { found }
+}
+
+unsafe extern "system" fn find_input_child(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    if class_name(hwnd) == crate::rdp::INPUT_CLASS {
+        *(lparam.0 as *mut HWND) = hwnd;
+        return BOOL(0);
+    }
+    TRUE
+}
+
+/// 输入焦点所在的标签 (焦点在 tabs 中的某个窗口或其子窗口中)
+pub fn focused_tab(tabs: &[isize]) -> Option<isize> {
+    unsafe {
+        let mut gui = GUITHREADINFO { cbSize: std::mem::size_of::<GUITHREADINFO>() as u32, ..Default::default() };
+        GetGUIThreadInfo(0, &mut gui).ok()?;
+        let mut current = if gui.hwndFocus.is_invalid() { gui.hwndActive } else { gui.hwndFocus };
+        while !current.is_invalid() {
+            if tabs.contains(&(current.0 as isize)) {
+                return Some(current.0 as isize);
+            }
+            current = GetAncestor(current, GA_PARENT);
+        }
+        None
+    }
+}
+
+// 修复嵌入窗口的输入法 (候选框不显示 / 位置错误)
+// Imm* 上下文函数只能在窗口所属线程调用，跨进程无效，所以改为发消息让目标线程自己处理：
+// - WM_IME_SETCONTEXT: 目标的 DefWindowProc 会重新关联并激活输入上下文
+// - IMN_SETCOMPOSITIONWINDOW / IMN_SETCANDIDATEPOS: 通知输入法窗口按嵌入后的新位置重新定位
+unsafe fn refresh_ime_context(hwnd: HWND, id_target: u32) {
+    if !IsWindow(hwnd).as_bool() { return; }
+
+    // 取目标线程真正拥有焦点的子控件 (编辑器、终端的输入区域)
+    let mut info = GUITHREADINFO { cbSize: std::mem::size_of::<GUITHREADINFO>() as u32, ..Default::default() };
+    let focus = if GetGUIThreadInfo(id_target, &mut info).is_ok() && !info.hwndFocus.is_invalid() {
+        info.hwndFocus
+    } else {
+        hwnd
+    };
+
+    let _ = PostMessageW(focus, WM_IME_SETCONTEXT, WPARAM(1), LPARAM(ISC_SHOWUIALL as i32 as isize));
+
+    let ime_wnd = ImmGetDefaultIMEWnd(focus);
+    if !ime_wnd.is_invalid() {
+        let _ = PostMessageW(ime_wnd, WM_IME_NOTIFY, WPARAM(IMN_SETCOMPOSITIONWINDOW as usize), LPARAM(0));
+        let _ = PostMessageW(ime_wnd, WM_IME_NOTIFY, WPARAM(IMN_SETCANDIDATEPOS as usize), LPARAM(1));
+    }
+}
+
+impl Platform for Win32Platform {
+    fn enumerate(&self) -> Vec<WindowInfo> {
+        let mut windows: Vec<WindowInfo> = Vec::new();
+        unsafe {
+            let _ = EnumWindows(
+                Some(enum_window_callback),
+                LPARAM(&mut windows as *mut Vec<WindowInfo> as isize),
+            );
+        }
+        windows
+    }
+
+    fn is_window(&self, hwnd: isize) -> bool {
+        unsafe { IsWindow(hwnd_of(hwnd)).as_bool() }
+    }
+
+    fn title(&self, hwnd: isize) -> String {
+        unsafe { window_title(hwnd_of(hwnd)) }
+    }
+
+    fn class_name(&self, hwnd: isize) -> String {
+        unsafe { class_name(hwnd_of(hwnd)) }
+    }
+
+    fn process_path(&self, hwnd: isize) -> Result<String, String> {
+        unsafe {
+            let hwnd = hwnd_of(hwnd);
+            if !IsWindow(hwnd).as_bool() {
+                return Err("无效的窗口句柄".to_string());
+            }
+
+            let mut pid: u32 = 0;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+
+            if pid == 0 {
+                return Err("无法获取进程 ID".to_string());
+            }
+            process_path_of(pid)
+        }
+    }
+
+    fn audio_sessions(&self) -> Vec<AudioSession> {
+        super::audio::sessions()
+    }
+
+    fn set_audio_session_muted(&self, id: &str, muted: bool) -> bool {
+        super::audio::set_muted(id, muted)
+    }
+
+    fn command_line(&self, hwnd: isize) -> Option<Vec<String>> {
+        unsafe {
+            let mut pid: u32 = 0;
+            GetWindowThreadProcessId(hwnd_of(hwnd), Some(&mut pid));
+            if pid == 0 {
+                return None;
+            }
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+            // 先查询所需大小，结果为 UNICODE_STRING 加上紧随其后的字符串
+            let mut size: u32 = 0;
+            let _ = NtQueryInformationProcess(process, ProcessCommandLineInformation, std::ptr::null_mut(), 0, &mut size);
+            let mut buffer = vec![0u64; (size as usize).div_ceil(8).max(1)];
+            let status = NtQueryInformationProcess(process, ProcessCommandLineInformation, buffer.as_mut_ptr() as *mut _, size, &mut size);
+            let _ = CloseHandle(process);
+            if status.is_err() {
+                return None;
+            }
+
+            let unicode = &*(buffer.as_ptr() as *const UNICODE_STRING);
+            if unicode.Buffer.is_null() {
+                return None;
+            }
+            let mut line = std::slice::from_raw_parts(unicode.Buffer.0, unicode.Length as usize / 2).to_vec();
+            line.push(0);
+
+            // 按系统规则拆分参数，第一个是程序路径
+            let mut argc = 0;
+            let argv = CommandLineToArgvW(PCWSTR(line.as_ptr()), &mut argc);
+            if argv.is_null() {
+                return None;
+            }
+            let args = (1..argc as usize).filter_map(|i| (*argv.add(i)).to_string().ok()).collect();
+            let _ = LocalFree(HLOCAL(argv as *mut _));
+            Some(args)
+        }
+    }
+
+    fn is_own_window(&self, hwnd: isize) -> bool {
+        is_own_window(hwnd_of(hwnd))
+    }
+
+    fn is_dangerous_class(&self, class_name: &str) -> bool {
+        // 这些窗口类型嵌入后可能导致系统不稳定
+        let dangerous = [
+            // "CabinetWClass",        // 文件资源管理器 (已允许)
+            // "ExplorerWClass",       // 文件资源管理器变体 (已允许)
+            "Progman",              // 桌面
+            "WorkerW",              // 桌面工作区
+            "Shell_TrayWnd",        // 任务栏
+            "Shell_SecondaryTrayWnd", // 副屏任务栏
+            "TaskManagerWindow",    // 任务管理器
+            "Windows.UI.Core.CoreWindow", // UWP 应用
+        ];
+        dangerous.iter().any(|d| class_name.contains(d))
+    }
+
+    fn embed(&self, host: isize, target_hwnd: isize) -> Result<EmbeddedWindow, EmbedError> {
+        unsafe {
+            if !IsWindow(hwnd_of(target_hwnd)).as_bool() {
+                return Err("无效的窗口句柄".into());
+            }
+            // host 属于当前线程，在这里修改，不让工作线程反过来等当前线程
+            let _ = WindowStyle::style(hwnd_of(host)).update(WS_CLIPCHILDREN.0, 0);
+            // 双缓冲绘制 host 及其子窗口，嵌入/切换时不会先露出白色背景
+            let _ = WindowStyle::ex_style(hwnd_of(host)).update(WS_EX_COMPOSITED.0, 0);
+        }
+
+        // 修改样式、SetParent 都要等目标线程处理消息，目标卡住时会一直阻塞，
+        // 放到工作线程执行，超过 EMBED_TIMEOUT 就放弃 (工作线程之后完成时自行撤销修改)
+        let state = Arc::new(AtomicU8::new(EMBED_PENDING));
+        let (tx, rx) = mpsc::channel();
+        let worker_state = state.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(unsafe { embed_sequence(host, target_hwnd, &worker_state) });
+        });
+
+        let deadline = Instant::now() + EMBED_TIMEOUT;
+        loop {
+            match rx.recv_timeout(Duration::from_millis(10)) {
+                Ok(result) => return result,
+                Err(RecvTimeoutError::Disconnected) => return Err("嵌入线程异常退出".into()),
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+            // 等待期间处理其他线程发给当前线程窗口的消息 (SetParent 会通知 host)
+            unsafe {
+                let mut msg = MSG::default();
+                let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE | PM_QS_SENDMESSAGE);
+            }
+            if Instant::now() >= deadline
+                && state.compare_exchange(EMBED_PENDING, EMBED_ABANDONED, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+            {
+                warn!(target: "embed", "嵌入超时，已放弃: hwnd={}", target_hwnd);
+                return Err(EmbedError::new(EmbedErrorCode::Timeout));
+            }
+        }
+    }
+
+    fn reassert_embedded(&self, host: isize, target_hwnd: isize) -> bool {
+        unsafe {
+            let hwnd = hwnd_of(target_hwnd);
+            let parent = hwnd_of(host);
+            let style = WindowStyle::style(hwnd);
+            let Ok(current) = style.get() else { return false };
+            let detached = GetAncestor(hwnd, GA_PARENT) != parent;
+            if !detached && current & WS_CHILD.0 != 0 && current & TOP_LEVEL_STYLE == 0 {
+                return false;
+            }
+            // 目标卡住时修改样式、SetParent 会一直等待，不修复
+            if SendMessageTimeoutW(hwnd, WM_NULL, WPARAM(0), LPARAM(0), SMTO_ABORTIFHUNG, PROBE_TIMEOUT_MS, None).0 == 0 {
+                return false;
+            }
+            // 不加 WS_VISIBLE：隐藏的标签由 tab_view 重新隐藏
+            let _ = style.update(WS_CHILD.0 | WS_CLIPSIBLINGS.0, TOP_LEVEL_STYLE);
+            if detached {
+                let _ = SetParent(hwnd, parent);
+            }
+            let _ = SetWindowPos(hwnd, HWND::default(), 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED);
+            GetAncestor(hwnd, GA_PARENT) == parent
+        }
+    }
+
+    fn release(&self, target_hwnd: isize, saved: Option<&EmbeddedWindow>) {
+        unsafe {
+            let hwnd = hwnd_of(target_hwnd);
+
+            // 隐藏的标签 (见 tab_view.rs) 释放后要重新可见
+            self.set_cloaked(target_hwnd, false);
+
+            // 锁定了输入的标签释放后要能重新操作
+            if saved.is_some_and(|s| s.input_locked) {
+                let _ = traced!(hwnd, EnableWindow(hwnd, true));
+            }
+
+            // 移除父窗口关系
+            let _ = traced!(hwnd, SetParent(hwnd, HWND::default()));
+
+            // 恢复原始样式、所有者、位置和大小
+            if let Some(saved) = saved {
+                restore_original_state(hwnd, saved);
+            } else {
+                if let Err(e) = WindowStyle::style(hwnd).set(WS_OVERLAPPEDWINDOW.0 | WS_VISIBLE.0) {
+                    warn!(target: "embed", "恢复窗口样式失败: hwnd={:?}, {}", hwnd.0, e);
+                }
+                let _ = traced!(hwnd, SetWindowPos(hwnd, HWND_TOP, 100, 100, 800, 600, SWP_FRAMECHANGED | SWP_SHOWWINDOW));
+            }
+
+            let _ = traced!(hwnd, ShowWindow(hwnd, SW_RESTORE));
+        }
+    }
+
+    fn raise(&self, hwnd: isize) {
+        let _ = crate::window_ops::call("raise", move || unsafe {
+            let hwnd = hwnd_of(hwnd);
+            let _attachment = ThreadInputAttachment::new(hwnd);
+            let _ = SetForegroundWindow(hwnd);
+        });
+    }
+
+    fn set_rect(&self, target_hwnd: isize, x: i32, y: i32, width: i32, height: i32) -> bool {
+        unsafe {
+            let hwnd = hwnd_of(target_hwnd);
+
+            // 检查窗口是否还有效
+            if !IsWindow(hwnd).as_bool() {
+                return false;
+            }
+
+            let mut rect = RECT::default();
+            if GetWindowRect(hwnd, &mut rect).is_ok() {
+                 if let Ok(parent) = GetParent(hwnd) {
+                      let mut pt_tl = POINT { x: rect.left, y: rect.top };
+                      let _ = ScreenToClient(parent, &mut pt_tl);
+
+                      let current_w = rect.right - rect.left;
+                      let current_h = rect.bottom - rect.top;
+
+                      if (pt_tl.x - x).abs() <= 1 && (pt_tl.y - y).abs() <= 1 &&
+                         (current_w - width).abs() <= 1 && (current_h - height).abs() <= 1 {
+                          return true;
+                      }
+                 }
+            }
+
+            let _ = SetWindowPos(hwnd, HWND::default(), x, y, width, height, SWP_NOZORDER | SWP_NOACTIVATE | SWP_SHOWWINDOW);
+            true
+        }
+    }
+
+    fn set_rect_fast(&self, target_hwnd: isize, x: i32, y: i32, width: i32, height: i32) -> bool {
+        unsafe {
+            let hwnd = hwnd_of(target_hwnd);
+            if !IsWindow(hwnd).as_bool() {
+                return false;
+            }
+            // 不重绘、不复制旧内容，调整结束后统一 repaint
+            let flags = SWP_NOZORDER | SWP_NOACTIVATE | SWP_NOREDRAW | SWP_NOCOPYBITS | SWP_DEFERERASE;
+            let _ = SetWindowPos(hwnd, HWND::default(), x, y, width, height, flags);
+            true
+        }
+    }
+
+    fn activate(&self, target_hwnd: isize, options: ActivateOptions) -> bool {
+        // 在窗口操作线程上执行 (见 window_ops.rs)，挂接输入队列、设置焦点的总是同一个线程
+        crate::window_ops::call("activate", move || unsafe { activate(hwnd_of(target_hwnd), options) }).unwrap_or(false)
+    }
+
+    fn set_visible(&self, hwnd: isize, visible: bool) -> bool {
+        unsafe {
+            let _ = ShowWindow(hwnd_of(hwnd), if visible { SW_SHOW } else { SW_HIDE });
+        }
+        true
+    }
+
+    fn set_input_enabled(&self, target_hwnd: isize, enabled: bool) -> bool {
+        unsafe {
+            let hwnd = hwnd_of(target_hwnd);
+            if !IsWindow(hwnd).as_bool() {
+                return false;
+            }
+            let _ = EnableWindow(hwnd, enabled);
+        }
+        true
+    }
+
+    // Windows 8 起子窗口也可以分层：保留 WS_EX_LAYERED 并重新应用透明属性，读回确认是否生效
+    fn set_layered(&self, target_hwnd: isize, saved: &EmbeddedWindow, preserve: bool) -> TransparencyResult {
+        unsafe {
+            let hwnd = hwnd_of(target_hwnd);
+            if !IsWindow(hwnd).as_bool() {
+                return TransparencyResult::Unsupported;
+            }
+            if saved.original_exstyle as u32 & WS_EX_LAYERED.0 == 0 {
+                return TransparencyResult::NotLayered;
+            }
+            let ex_style = WindowStyle::ex_style(hwnd);
+            let flags = SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED;
+            if !preserve {
+                let _ = ex_style.update(0, WS_EX_LAYERED.0);
+                let _ = traced!(hwnd, SetWindowPos(hwnd, HWND::default(), 0, 0, 0, 0, flags));
+                return TransparencyResult::Opaque;
+            }
+            if !ex_style.update(WS_EX_LAYERED.0, 0).is_ok_and(|change| change.after & WS_EX_LAYERED.0 != 0) {
+                return TransparencyResult::Unsupported;
+            }
+            let _ = traced!(hwnd, SetWindowPos(hwnd, HWND::default(), 0, 0, 0, 0, flags));
+            let Some(layered) = saved.original_layered else { return TransparencyResult::PerPixel };
+            apply_layered(hwnd, layered);
+            if layered_attributes(hwnd, WS_EX_LAYERED.0) == Some(layered) {
+                TransparencyResult::Preserved
+            } else {
+                TransparencyResult::Unsupported
+            }
+        }
+    }
+
+    fn set_cloaked(&self, hwnd: isize, cloaked: bool) -> bool {
+        unsafe {
+            let value = BOOL::from(cloaked);
+            DwmSetWindowAttribute(
+                hwnd_of(hwnd),
+                DWMWA_CLOAK,
+                &value as *const _ as *const _,
+                std::mem::size_of::<BOOL>() as u32,
+            )
+            .is_ok()
+        }
+    }
+
+    fn close(&self, hwnd: isize) {
+        unsafe {
+            let _ = PostMessageW(hwnd_of(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0));
+        }
+    }
+
+    fn process_id(&self, hwnd: isize) -> u32 {
+        let mut pid = 0;
+        unsafe { GetWindowThreadProcessId(hwnd_of(hwnd), Some(&mut pid)) };
+        pid
+    }
+
+    fn process_start_time(&self, pid: u32) -> Option<u64> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut created = FILETIME::default();
+            let (mut exited, mut kernel, mut user) = (FILETIME::default(), FILETIME::default(), FILETIME::default());
+            let result = GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user);
+            let _ = CloseHandle(process);
+            result.ok()?;
+            Some(((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64)
+        }
+    }
+
+    // 先问窗口 (WM_GETICON，未响应的窗口不等待)，没有时用窗口类的图标
+    fn icon_handle(&self, hwnd: isize) -> isize {
+        unsafe {
+            let hwnd = hwnd_of(hwnd);
+            let mut icon = 0usize;
+            let _ = SendMessageTimeoutW(hwnd, WM_GETICON, WPARAM(ICON_SMALL2 as usize), LPARAM(0), SMTO_ABORTIFHUNG, 100, Some(&mut icon));
+            if icon == 0 {
+                icon = GetClassLongPtrW(hwnd, GCLP_HICONSM);
+            }
+            if icon == 0 {
+                icon = GetClassLongPtrW(hwnd, GCLP_HICON);
+            }
+            icon as isize
+        }
+    }
+
+    fn process_age_secs(&self, pid: u32) -> Option<u64> {
+        // FILETIME 是从 1601 年起的 100 纳秒数
+        let created = self.process_start_time(pid)? / 10_000_000;
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() + FILETIME_UNIX_OFFSET_SECS;
+        Some(now.saturating_sub(created))
+    }
+
+    fn window_size(&self, hwnd: isize) -> Option<(i32, i32)> {
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(hwnd_of(hwnd), &mut rect).ok()? };
+        Some((rect.right - rect.left, rect.bottom - rect.top))
+    }
+
+    fn kill_process_tree(&self, pid: u32) -> Result<(), String> {
+        use std::os::windows::process::CommandExt;
+
+        let output = std::process::Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .output()
+            .map_err(|e| format!("无法结束进程: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("无法结束进程: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(())
+    }
+
+    fn process_windows(&self, hwnd: isize) -> Vec<isize> {
+        unsafe {
+            let mut pid = 0;
+            GetWindowThreadProcessId(hwnd_of(hwnd), Some(&mut pid));
+            if pid == 0 {
+                return Vec::new();
+            }
+            let mut query = ProcessWindows { pid, exclude: hwnd_of(hwnd), found: Vec::new() };
+            let _ = EnumWindows(Some(process_window_callback), LPARAM(&mut query as *mut ProcessWindows as isize));
+            query.found
+        }
+    }
+
+    fn dpi_awareness(&self, hwnd: isize) -> Option<DpiAwareness> {
+        unsafe { dpi_awareness(hwnd_of(hwnd)) }
+    }
+
+    fn repaint(&self, hwnd: isize) -> bool {
+        unsafe {
+            let hwnd = hwnd_of(hwnd);
+            if !IsWindow(hwnd).as_bool() { return false; }
+
+            // 方法1: InvalidateRect
+            let _ = InvalidateRect(hwnd, None, true);
+
+            // 方法2: RedrawWindow (更强力)
+            let flags = RDW_ERASE | RDW_FRAME | RDW_INVALIDATE | RDW_ALLCHILDREN | RDW_UPDATENOW | RDW_INTERNALPAINT;
+            let _ = RedrawWindow(hwnd, None, None, flags);
+
+            true
+        }
+    }
+
+    fn foreground_window(&self) -> isize {
+        unsafe { GetForegroundWindow().0 as isize }
+    }
+
+    fn is_mouse_left_down(&self) -> bool {
+        unsafe { (GetAsyncKeyState(0x01) as u16 & 0x8000) != 0 }
+    }
+
+    fn is_modifier_down(&self) -> bool {
+        [VK_CONTROL, VK_MENU, VK_SHIFT, VK_LWIN, VK_RWIN]
+            .iter()
+            .any(|key| unsafe { (GetAsyncKeyState(key.0 as i32) as u16 & 0x8000) != 0 })
+    }
+
+    fn exclusive_fullscreen_active(&self) -> bool {
+        unsafe { SHQueryUserNotificationState().is_ok_and(|state| state == QUNS_RUNNING_D3D_FULL_SCREEN) }
+    }
+
+    fn covers_monitor(&self, hwnd: isize) -> bool {
+        unsafe {
+            let hwnd = hwnd_of(hwnd);
+            let mut rect = RECT::default();
+            if GetWindowRect(hwnd, &mut rect).is_err() {
+                return false;
+            }
+            let mut info = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+            if !GetMonitorInfoW(MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST), &mut info).as_bool() {
+                return false;
+            }
+            let monitor = info.rcMonitor;
+            rect.left <= monitor.left && rect.top <= monitor.top && rect.right >= monitor.right && rect.bottom >= monitor.bottom
+        }
+    }
+
+    // DWM 缩略图只能以顶层窗口为源，嵌入后的子窗口只能用 PrintWindow 截取
+    fn thumbnail(&self, hwnd: isize, max_width: i32, max_height: i32) -> Option<String> {
+        let (width, height, bgra) = unsafe { capture_window(hwnd_of(hwnd), max_width, max_height)? };
+        let mut jpeg = Vec::new();
+        Encoder::new(&mut jpeg, THUMBNAIL_QUALITY)
+            .encode(&bgra, width as u16, height as u16, ColorType::Bgra)
+            .ok()?;
+        Some(format!("data:image/jpeg;base64,{}", base64::engine::general_purpose::STANDARD.encode(&jpeg)))
+    }
+
+    fn capture(&self, hwnd: isize) -> Option<(i32, i32, Vec<u8>)> {
+        unsafe { capture_window(hwnd_of(hwnd), i32::MAX, i32::MAX) }
+    }
+}
+
+// 嵌入总超时，超过后放弃并把窗口类型记为有问题
+const EMBED_TIMEOUT: Duration = Duration::from_secs(3);
+// 嵌入前探测目标线程是否在处理消息，比总超时短，卡死的窗口由工作线程直接报告
+const PROBE_TIMEOUT_MS: u32 = 2000;
+
+// 嵌入时去掉的顶层窗口样式 (标题栏、边框、系统菜单等)
+const TOP_LEVEL_STYLE: u32 =
+    WS_CAPTION.0 | WS_THICKFRAME.0 | WS_MINIMIZEBOX.0 | WS_MAXIMIZEBOX.0 | WS_SYSMENU.0 | WS_POPUP.0 | WS_BORDER.0 | WS_DLGFRAME.0;
+
+// 嵌入工作线程的状态：调用方放弃后，工作线程不再提交修改
+const EMBED_PENDING: u8 = 0;
+const EMBED_DONE: u8 = 1;
+const EMBED_ABANDONED: u8 = 2;
+
+// 嵌入步骤 (在工作线程执行)，调用方已经放弃时撤销修改
+unsafe fn embed_sequence(host: isize, target_hwnd: isize, state: &AtomicU8) -> Result<EmbeddedWindow, EmbedError> {
+    let hwnd = hwnd_of(target_hwnd);
+    let parent = hwnd_of(host);
+
+    if traced!(hwnd, SendMessageTimeoutW(hwnd, WM_NULL, WPARAM(0), LPARAM(0), SMTO_ABORTIFHUNG, PROBE_TIMEOUT_MS, None::<*mut usize>)).0 == 0 {
+        return Err(EmbedError::new(EmbedErrorCode::Timeout));
+    }
+
+    let target_style = WindowStyle::style(hwnd);
+    let original_style = target_style.get()?;
+    let original_exstyle = WindowStyle::ex_style(hwnd).get()?;
+    let mut original_rect = RECT::default();
+    let _ = GetWindowRect(hwnd, &mut original_rect);
+
+    let mut pid = 0;
+    GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    let embedded_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let window = EmbeddedWindow {
+        hwnd: target_hwnd,
+        tab_id: 0,
+        title: window_title(hwnd),
+        class_name: class_name(hwnd),
+        pid,
+        exe_path: Win32Platform.process_path(target_hwnd).ok(),
+        args: Vec::new(),
+        original_style: original_style as i32,
+        original_exstyle: original_exstyle as i32,
+        original_layered: layered_attributes(hwnd, original_exstyle),
+        original_rect: original_rect.into(),
+        original_owner: GetWindow(hwnd, GW_OWNER).map(|h| h.0 as isize).unwrap_or(0),
+        monitor: MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST).0 as isize,
+        embedded_at,
+        label: None,
+        group: None,
+        color: None,
+        hotkey: None,
+        auto_restart: false,
+        pinned: false,
+        input_locked: false,
+        capture_excluded: false,
+        preserve_transparency: false,
+    };
+
+    // 失败时 transaction 离开作用域自动撤销已做的修改
+    let mut transaction = EmbedTransaction::new(hwnd);
+    transaction.set_style(target_style, WS_CHILD.0 | WS_VISIBLE.0 | WS_CLIPSIBLINGS.0, TOP_LEVEL_STYLE)?;
+    transaction.set_parent(parent)?;
+
+    traced!(hwnd, SetWindowPos(hwnd, HWND_TOP, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_FRAMECHANGED | SWP_SHOWWINDOW))
+        .map_err(|e| format!("调整窗口失败: {}", e))?;
+
+    if state.compare_exchange(EMBED_PENDING, EMBED_DONE, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+        warn!(target: "embed", "嵌入完成时调用方已超时，撤销修改: hwnd={:?}", hwnd.0);
+        return Err(EmbedError::new(EmbedErrorCode::Timeout));
+    }
+    transaction.commit();
+    Ok(window)
+}
+
+const THUMBNAIL_QUALITY: u8 = 75;
+
+// 1601-01-01 到 1970-01-01 的秒数
+const FILETIME_UNIX_OFFSET_SECS: u64 = 11_644_473_600;
+
+// 把窗口绘制到内存位图并缩小，返回 (宽, 高, BGRA 像素)
+// PW_RENDERFULLCONTENT 可以截到 DirectComposition/硬件加速的内容 (浏览器、UWP)
+unsafe fn capture_window(hwnd: HWND, max_width: i32, max_height: i32) -> Option<(i32, i32, Vec<u8>)> {
+    let mut rect = RECT::default();
+    GetWindowRect(hwnd, &mut rect).ok()?;
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    if width <= 0 || height <= 0 || max_width <= 0 || max_height <= 0 {
+        return None;
+    }
+    let scale = (max_width as f64 / width as f64).min(max_height as f64 / height as f64).min(1.0);
+    let thumb_width = ((width as f64 * scale) as i32).max(1);
+    let thumb_height = ((height as f64 * scale) as i32).max(1);
+
+    let screen = GetDC(HWND::default());
+    let full_dc = CreateCompatibleDC(screen);
+    let full_bitmap = CreateCompatibleBitmap(screen, width, height);
+    let full_old = SelectObject(full_dc, full_bitmap);
+    let thumb_dc = CreateCompatibleDC(screen);
+    let thumb_bitmap = CreateCompatibleBitmap(screen, thumb_width, thumb_height);
+    let thumb_old = SelectObject(thumb_dc, thumb_bitmap);
+
+    let mut pixels = vec![0u8; (thumb_width * thumb_height * 4) as usize];
+    let printed = PrintWindow(hwnd, full_dc, PRINT_WINDOW_FLAGS(PW_RENDERFULLCONTENT)).as_bool();
+    let copied = printed && {
+        SetStretchBltMode(thumb_dc, HALFTONE);
+        let _ = StretchBlt(thumb_dc, 0, 0, thumb_width, thumb_height, full_dc, 0, 0, width, height, SRCCOPY);
+        // 高度为负表示自上而下的行顺序，与 JPEG 编码一致
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: thumb_width,
+                biHeight: -thumb_height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        GetDIBits(thumb_dc, thumb_bitmap, 0, thumb_height as u32, Some(pixels.as_mut_ptr() as *mut _), &mut info, DIB_RGB_COLORS) > 0
+    };
+
+    SelectObject(thumb_dc, thumb_old);
+    SelectObject(full_dc, full_old);
+    let _ = DeleteObject(thumb_bitmap);
+    let _ = DeleteObject(full_bitmap);
+    let _ = DeleteDC(thumb_dc);
+    let _ = DeleteDC(full_dc);
+    ReleaseDC(HWND::default(), screen);
+
+    copied.then_some((thumb_width, thumb_height, pixels))
+}
@@ -0,0 +1,408 @@
+// 搜索框插件 (第三方的搜索结果和操作)
+// 插件放在 %APPDATA%\WindowHub\plugins\<目录>\，目录中的 plugin.json 描述插件：
+//   { "name": "GitHub", "command": "gh-plugin.exe", "args": [], "prefix": "gh ", "timeout_ms": 1500 }
+// command 相对于插件目录。插件作为子进程运行，通过标准输入输出交换 JSON-RPC 2.0 消息 (每行一条)：
+// - search { query } -> [{ id, title, subtitle? }]，prefix 不为空时只转发以它开头的查询 (去掉前缀)
+// - invoke { id } -> null 或 { open: { path, args? } }，open 由前端启动并嵌入，需要插件有 embed 权限
+//   (插件默认没有任何权限，由用户用 set_capabilities 授予，见 permissions.rs)
+// 插件不可信，后端限制：
+// - 每个请求有超时 (timeout_ms，最长 MAX_TIMEOUT)，超时或输出无效时结束进程，下次使用时重新启动
+// - 每次搜索最多 MAX_RESULTS 条，文本截断到 MAX_TEXT 个字符
+// - 一行输出最长 MAX_LINE 字节，超出时不再读取 (请求失败，结束进程)；最多缓存 MAX_PENDING 行未处理的输出
+// - 启动失败后 RETRY_AFTER 内不再尝试
+// - 名称重复的插件只加载第一个 (按目录名排序)，权限按名称授予，不能借用其他插件的名称
+// 各插件的搜索并行进行，进程在第一次使用时启动，之后一直运行；与插件通信时只锁住该插件，不锁插件列表
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::permissions::{Capability, Grant, GrantKind};
+
+const MANIFEST: &str = "plugin.json";
+
+const DEFAULT_TIMEOUT: u64 = 1000;
+const MAX_TIMEOUT: u64 = 5000;
+const MAX_RESULTS: usize = 20;
+const MAX_TEXT: usize = 200;
+const MAX_LINE: usize = 1024 * 1024;
+const MAX_PENDING: usize = 64;
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct Manifest {
+    name: String,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    prefix: Option<String>,
+    #[serde(default = "default_timeout")]
+    timeout_ms: u64,
+}
+
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT
+}
+
+/// 插件给出的搜索结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PluginItem {
+    pub plugin: String,
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+}
+
+/// 插件操作要求打开的程序或文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginOpen {
+    pub path: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// 已加载的插件
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInfo {
+    pub name: String,
+    pub dir: String,
+    pub prefix: Option<String>,
+    pub running: bool,
+}
+
+// 运行中的插件进程，结束时一并结束进程
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    lines: mpsc::Receiver<String>,
+    next_id: u64,
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+struct Plugin {
+    manifest: Manifest,
+    dir: PathBuf,
+    process: Option<Process>,
+    failed_at: Option<Instant>,
+}
+
+// (名称, 插件)，名称单独保存，查找插件时不用等正在通信的插件
+static PLUGINS: Mutex<Vec<(String, Arc<Mutex<Plugin>>)>> = Mutex::new(Vec::new());
+
+fn plugins_dir() -> Option<PathBuf> {
+    crate::paths::data_dir().map(|dir| dir.join("plugins"))
+}
+
+/// 重新扫描插件目录 (已运行的插件进程结束)，返回加载的插件
+pub fn load() -> Vec<PluginInfo> {
+    let mut plugins: Vec<(String, Arc<Mutex<Plugin>>)> = Vec::new();
+    if let Some(entries) = plugins_dir().and_then(|dir| std::fs::read_dir(dir).ok()) {
+        let mut dirs: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect();
+        dirs.sort();
+        for dir in dirs {
+            match read_manifest(&dir) {
+                Ok(manifest) if plugins.iter().any(|(name, _)| *name == manifest.name) => {
+                    warn!(target: "launcher", "插件名称重复，跳过 {}: {}", dir.display(), manifest.name);
+                }
+                Ok(manifest) => {
+                    info!(target: "launcher", "加载插件: {} ({})", manifest.name, dir.display());
+                    let name = manifest.name.clone();
+                    plugins.push((name, Arc::new(Mutex::new(Plugin { manifest, dir, process: None, failed_at: None }))));
+                }
+                Err(e) => warn!(target: "launcher", "插件无效 {}: {}", dir.display(), e),
+            }
+        }
+    }
+    *PLUGINS.lock().unwrap() = plugins;
+    list()
+}
+
+pub fn list() -> Vec<PluginInfo> {
+    let plugins = PLUGINS.lock().unwrap().clone();
+    plugins
+        .iter()
+        .map(|(_, plugin)| {
+            let plugin = plugin.lock().unwrap();
+            PluginInfo {
+                name: plugin.manifest.name.clone(),
+                dir: plugin.dir.to_string_lossy().to_string(),
+                prefix: plugin.manifest.prefix.clone(),
+                running: plugin.process.is_some(),
+            }
+        })
+        .collect()
+}
+
+/// 向所有插件并行查询，超时的插件没有结果
+pub fn search(query: &str) -> Vec<PluginItem> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let plugins = PLUGINS.lock().unwrap().clone();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = plugins
+            .iter()
+            .map(|(_, plugin)| {
+                scope.spawn(move || {
+                    let mut plugin = plugin.lock().unwrap();
+                    let query = forwarded_query(plugin.manifest.prefix.as_deref(), query)?;
+                    let name = plugin.manifest.name.clone();
+                    match plugin.request("search", json!({ "query": query })) {
+                        Ok(result) => Some(parse_items(&name, &result)),
+                        Err(e) => {
+                            warn!(target: "launcher", "插件 {} 搜索失败: {}", name, e);
+                            None
+                        }
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().ok().flatten()).flatten().collect()
+    })
+}
+
+/// 执行插件的操作，插件要求打开程序或文件时返回它
+pub fn invoke(plugin: &str, id: &str) -> Result<Option<PluginOpen>, String> {
+    let found = PLUGINS.lock().unwrap().iter().find(|(name, _)| name == plugin).map(|(_, p)| p.clone());
+    let plugin = found.ok_or_else(|| format!("插件不存在: {}", plugin))?;
+    let mut plugin = plugin.lock().unwrap();
+    info!(target: "launcher", "执行插件操作: {} {}", plugin.manifest.name, id);
+    let result = plugin.request("invoke", json!({ "id": id }))?;
+    let Some(open) = result.get("open") else { return Ok(None) };
+    Grant::of(GrantKind::Plugin, &plugin.manifest.name).require(Capability::Embed)?;
+    serde_json::from_value(open.clone()).map(Some).map_err(|e| format!("插件返回的 open 无效: {}", e))
+}
+
+impl Plugin {
+    // 发送请求并等待对应的响应，超时或输出无效时结束进程 (插件返回的错误不结束)
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let timeout = Duration::from_millis(self.manifest.timeout_ms.min(MAX_TIMEOUT));
+        let result = self.ensure_running().and_then(|process| process.call(method, params, timeout));
+        if result.is_err() {
+            self.process = None;
+        }
+        result?
+    }
+
+    fn ensure_running(&mut self) -> Result<&mut Process, String> {
+        if self.process.is_none() {
+            if self.failed_at.is_some_and(|at| at.elapsed() < RETRY_AFTER) {
+                return Err("插件启动失败，稍后重试".to_string());
+            }
+            match spawn(&self.manifest, &self.dir) {
+                Ok(process) => {
+                    self.failed_at = None;
+                    self.process = Some(process);
+                }
+                Err(e) => {
+                    self.failed_at = Some(Instant::now());
+                    return Err(e);
+                }
+            }
+        }
+        Ok(self.process.as_mut().unwrap())
+    }
+}
+
+impl Process {
+    // 外层的错误表示插件不可用，内层为插件返回的错误
+    fn call(&mut self, method: &str, params: Value, timeout: Duration) -> Result<Result<Value, String>, String> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.stdin, "{}", message).and_then(|_| self.stdin.flush()).map_err(|e| format!("无法写入插件: {}", e))?;
+
+        // 跳过不属于这个请求的消息
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(mpsc::RecvTimeoutError::Timeout) => return Err(format!("超时 ({} 毫秒)", timeout.as_millis())),
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Err("插件已退出".to_string()),
+            };
+            if let Some(result) = parse_response(&line, id)? {
+                return Ok(result);
+            }
+        }
+    }
+}
+
+fn spawn(manifest: &Manifest, dir: &Path) -> Result<Process, String> {
+    let mut command = Command::new(dir.join(&manifest.command));
+    command.args(&manifest.args).current_dir(dir).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    let mut child = command.spawn().map_err(|e| format!("无法启动插件 {}: {}", manifest.command, e))?;
+    let stdin = child.stdin.take().ok_or("无法连接插件的标准输入")?;
+    let stdout = child.stdout.take().ok_or("无法连接插件的标准输出")?;
+    let (tx, lines) = mpsc::sync_channel(MAX_PENDING);
+    let name = manifest.name.clone();
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stdout);
+        while let Some(line) = read_line(&mut reader) {
+            let Ok(line) = line else {
+                warn!(target: "launcher", "插件 {} 输出的一行超过 {} 字节，不再读取", name, MAX_LINE);
+                break;
+            };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    info!(target: "launcher", "插件已启动: {}", manifest.name);
+    Ok(Process { child, stdin, lines, next_id: 0 })
+}
+
+// 读取一行输出 (不含换行)，输出结束时为 None，超过 MAX_LINE 时为 Some(Err)
+fn read_line(reader: &mut impl BufRead) -> Option<Result<String, ()>> {
+    let mut line = Vec::new();
+    match reader.by_ref().take(MAX_LINE as u64 + 1).read_until(b'\n', &mut line) {
+        Ok(0) | Err(_) => return None,
+        Ok(_) => {}
+    }
+    if line.last() == Some(&b'\n') {
+        line.pop();
+    }
+    if line.len() > MAX_LINE {
+        return Some(Err(()));
+    }
+    let line = String::from_utf8_lossy(&line);
+    Some(Ok(line.strip_suffix('\r').unwrap_or(&line).to_string()))
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest, String> {
+    let content = std::fs::read_to_string(dir.join(MANIFEST)).map_err(|e| format!("无法读取 {}: {}", MANIFEST, e))?;
+    let manifest: Manifest = serde_json::from_str(&content).map_err(|e| format!("{} 格式错误: {}", MANIFEST, e))?;
+    if manifest.name.trim().is_empty() || manifest.command.trim().is_empty() {
+        return Err("name 和 command 不能为空".to_string());
+    }
+    // command 只能指向插件目录中的程序
+    let command = &manifest.command;
+    let escapes = command.split(['\\', '/']).any(|part| part == "..");
+    if Path::new(command).is_absolute() || command.starts_with(['\\', '/']) || command.contains(':') || escapes {
+        return Err("command 必须是插件目录中的程序".to_string());
+    }
+    Ok(manifest)
+}
+
+// 插件收到的查询：有前缀时只转发以前缀开头的查询
+fn forwarded_query<'a>(prefix: Option<&str>, query: &'a str) -> Option<&'a str> {
+    match prefix.filter(|p| !p.is_empty()) {
+        Some(prefix) => {
+            let head = query.get(..prefix.len())?;
+            head.eq_ignore_ascii_case(prefix).then(|| query[prefix.len()..].trim_start())
+        }
+        None => Some(query),
+    }
+}
+
+// 解析一行响应：不是这个请求的响应时为 None，插件返回的错误在内层
+fn parse_response(line: &str, id: u64) -> Result<Option<Result<Value, String>>, String> {
+    let Ok(response) = serde_json::from_str::<Value>(line) else { return Err("插件输出了无效的 JSON".to_string()) };
+    if response.get("id").and_then(Value::as_u64) != Some(id) {
+        return Ok(None);
+    }
+    if let Some(error) = response.get("error") {
+        let message = error.get("message").and_then(Value::as_str).unwrap_or("未知错误");
+        return Ok(Some(Err(truncate(message))));
+    }
+    Ok(Some(Ok(response.get("result").cloned().unwrap_or(Value::Null))))
+}
+
+// 搜索结果：跳过格式不对的条目，限制条数和文本长度
+fn parse_items(plugin: &str, result: &Value) -> Vec<PluginItem> {
+    let Some(items) = result.as_array() else { return Vec::new() };
+    items
+        .iter()
+        .filter_map(|item| {
+            let id = item.get("id").and_then(Value::as_str)?;
+            let title = item.get("title").and_then(Value::as_str)?;
+            Some(PluginItem {
+                plugin: plugin.to_string(),
+                id: truncate(id),
+                title: truncate(title),
+                subtitle: item.get("subtitle").and_then(Value::as_str).map(truncate),
+            })
+        })
+        .take(MAX_RESULTS)
+        .collect()
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_TEXT).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_are_validated_and_limited() {
+        assert_eq!(forwarded_query(Some("gh "), "GH windowhub"), Some("windowhub"));
+        assert_eq!(forwarded_query(Some("gh "), "notepad"), None);
+        assert_eq!(forwarded_query(None, "notepad"), Some("notepad"));
+
+        assert_eq!(parse_response(r#"{"jsonrpc":"2.0","id":3,"result":[]}"#, 3), Ok(Some(Ok(json!([])))));
+        // 其他请求的响应
+        assert_eq!(parse_response(r#"{"jsonrpc":"2.0","id":2,"result":[]}"#, 3), Ok(None));
+        assert_eq!(parse_response(r#"{"id":3,"error":{"code":-32601,"message":"no"}}"#, 3), Ok(Some(Err("no".to_string()))));
+        assert!(parse_response("hello", 3).is_err());
+
+        let many: Vec<Value> = (0..50).map(|i| json!({ "id": i.to_string(), "title": "x".repeat(500) })).collect();
+        let mut result = many.clone();
+        result.insert(0, json!({ "title": "没有 id" }));
+        let items = parse_items("GitHub", &Value::Array(result));
+        assert_eq!(items.len(), MAX_RESULTS);
+        assert_eq!(items[0].id, "0");
+        assert_eq!(items[0].title.chars().count(), MAX_TEXT);
+    }
+
+    #[test]
+    fn long_output_lines_are_rejected() {
+        let output = format!("{{\"id\":1}}\r\n{}\n", "x".repeat(MAX_LINE + 1));
+        let mut reader = std::io::Cursor::new(output);
+        assert_eq!(read_line(&mut reader), Some(Ok(r#"{"id":1}"#.to_string())));
+        assert_eq!(read_line(&mut reader), Some(Err(())));
+
+        let mut reader = std::io::Cursor::new("x".repeat(MAX_LINE) + "\n");
+        assert_eq!(read_line(&mut reader).map(|l| l.map(|l| l.len())), Some(Ok(MAX_LINE)));
+        assert_eq!(read_line(&mut reader), None);
+    }
+
+    #[test]
+    fn manifests_must_stay_in_the_plugin_directory() {
+        let dir = std::env::temp_dir().join(format!("windowhub-plugin-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |content: &str| std::fs::write(dir.join(MANIFEST), content).unwrap();
+
+        write(r#"{ "name": "GitHub", "command": "gh.exe", "prefix": "gh " }"#);
+        let manifest = read_manifest(&dir).unwrap();
+        assert_eq!(manifest.timeout_ms, DEFAULT_TIMEOUT);
+        assert_eq!(manifest.prefix.as_deref(), Some("gh "));
+
+        write(r#"{ "name": "Evil", "command": "..\\..\\cmd.exe" }"#);
+        assert!(read_manifest(&dir).is_err());
+        write(r#"{ "name": "Evil", "command": "C:\\Windows\\System32\\cmd.exe" }"#);
+        assert!(read_manifest(&dir).is_err());
+        write(r#"{ "name": "", "command": "x.exe" }"#);
+        assert!(read_manifest(&dir).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
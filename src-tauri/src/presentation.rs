@@ -0,0 +1,174 @@
+// 演示模式 (enter_presentation_mode / exit_presentation_mode)
+// 一条命令把某个标签放到会议室的电视上：
+// - 主窗口移到指定的显示器 (序号同 enumerate_monitors，不指定时留在当前显示器) 并无边框全屏
+// - 标签临时最大化 (盖住标签栏)，发送 presentation-mode-changed，前端据此隐藏标签栏
+// - 输入只给这个标签：其他标签锁定输入，也不能切换过去
+// - 不发送关注提示和通知角标 (见 win_events::notify_attention、notifications.rs)
+// - 用 SetThreadExecutionState 阻止屏幕保护程序和显示器休眠
+// 退出时恢复主窗口的位置和全屏状态 (展台模式下保持全屏)、标签的最大化，解除这里的锁定 (见 input_lock.rs，专注时段的锁定保持)；
+// 演示的标签被关闭或弹出时自动退出
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition};
+use tracing::info;
+
+use crate::input_lock::{self, LockOwner};
+use crate::window_manager::WindowManager;
+
+#[cfg(windows)]
+use windows::Win32::System::Power::{SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED};
+
+struct Presenting {
+    tab: isize,
+    monitor: Option<usize>,
+    was_fullscreen: bool,
+    position: Option<PhysicalPosition<i32>>, // 进入前主窗口的位置
+    maximized: bool,                         // 标签是由演示模式最大化的
+}
+
+static PRESENTING: Mutex<Option<Presenting>> = Mutex::new(None);
+
+// 每次进入和退出加一，旧的防休眠线程据此退出
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// presentation-mode-changed 事件的载荷和 get_presentation_mode 的返回值
+#[derive(Debug, Clone, Serialize)]
+pub struct PresentationMode {
+    pub active: bool,
+    pub tab: isize,
+    pub monitor: Option<usize>,
+    pub hide_tab_strip: bool,
+}
+
+pub fn is_active() -> bool {
+    PRESENTING.lock().unwrap().is_some()
+}
+
+/// 当前的演示模式，没有进入时为 None
+pub fn current() -> Option<PresentationMode> {
+    let presenting = PRESENTING.lock().unwrap();
+    presenting.as_ref().map(|p| PresentationMode { active: true, tab: p.tab, monitor: p.monitor, hide_tab_strip: true })
+}
+
+/// 演示期间不能切换到其他标签
+pub fn ensure_allows(tab: isize) -> Result<(), String> {
+    match PRESENTING.lock().unwrap().as_ref() {
+        Some(p) if p.tab != tab => Err("演示模式下不能切换标签，请先退出演示模式".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// 进入演示模式，已经在演示其他标签时先退出
+pub fn enter(app: &AppHandle, tab: isize, monitor: Option<usize>) -> Result<PresentationMode, String> {
+    let manager = app.state::<WindowManager>();
+    if manager.get(tab).is_none() {
+        return Err("标签不存在".to_string());
+    }
+    let target = match monitor {
+        Some(index) => Some(crate::monitors::list().into_iter().nth(index).ok_or("显示器不存在")?),
+        None => None,
+    };
+    exit(app);
+    let main = app.get_webview_window("main").ok_or("主窗口不存在")?;
+    let was_fullscreen = main.is_fullscreen().unwrap_or(false);
+    let position = main.outer_position().ok();
+    if let Some(target) = &target {
+        // 全屏的窗口要先退出全屏才能移到其他显示器
+        if was_fullscreen {
+            let _ = main.set_fullscreen(false);
+        }
+        main.set_position(PhysicalPosition::new(target.bounds.left, target.bounds.top)).map_err(|e| format!("移动主窗口失败: {}", e))?;
+    }
+    main.set_fullscreen(true).map_err(|e| format!("切换全屏失败: {}", e))?;
+
+    let maximized = crate::zones::maximized() != Some(tab);
+    if maximized {
+        crate::zones::toggle_maximized(tab);
+    }
+    *PRESENTING.lock().unwrap() = Some(Presenting { tab, monitor, was_fullscreen, position, maximized });
+    if let Err(e) = crate::show_tab(app.clone(), manager.clone(), tab) {
+        exit(app);
+        return Err(e);
+    }
+    crate::relayout(app);
+    lock_others(app, tab);
+    crate::tray::set_attention(app, false);
+    keep_awake(GENERATION.fetch_add(1, Ordering::SeqCst) + 1);
+
+    info!(target: "embed", "进入演示模式: hwnd={}, 显示器={:?}", tab, monitor);
+    let mode = PresentationMode { active: true, tab, monitor, hide_tab_strip: true };
+    if maximized {
+        let _ = app.emit("tab-fullscreen-changed", crate::TabFullscreen { hwnd: tab, fullscreen: true });
+    }
+    let _ = app.emit("presentation-mode-changed", mode.clone());
+    Ok(mode)
+}
+
+/// 退出演示模式，没有进入时返回 false
+pub fn exit(app: &AppHandle) -> bool {
+    let Some(presenting) = PRESENTING.lock().unwrap().take() else { return false };
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    input_lock::unlock_all(app, LockOwner::Presentation);
+    if presenting.maximized && crate::zones::maximized() == Some(presenting.tab) {
+        crate::zones::toggle_maximized(presenting.tab);
+        let _ = app.emit("tab-fullscreen-changed", crate::TabFullscreen { hwnd: presenting.tab, fullscreen: false });
+    }
+    if let Some(main) = app.get_webview_window("main") {
+        if !presenting.was_fullscreen && !crate::kiosk::is_enabled() {
+            let _ = main.set_fullscreen(false);
+            if let Some(position) = presenting.position.filter(|_| presenting.monitor.is_some()) {
+                let _ = main.set_position(position);
+            }
+        }
+    }
+    crate::relayout(app);
+    info!(target: "embed", "退出演示模式: hwnd={}", presenting.tab);
+    let mode = PresentationMode { active: false, tab: presenting.tab, monitor: presenting.monitor, hide_tab_strip: false };
+    let _ = app.emit("presentation-mode-changed", mode);
+    true
+}
+
+/// 标签被关闭或弹出 (演示的标签时退出演示模式)
+pub fn forget(app: &AppHandle, tab: isize) {
+    let presented = PRESENTING.lock().unwrap().as_ref().is_some_and(|p| p.tab == tab);
+    if presented {
+        exit(app);
+    }
+}
+
+/// 演示期间新嵌入的标签同样锁定输入 (embed_tab 调用)
+pub fn on_embedded(app: &AppHandle) {
+    let presented = PRESENTING.lock().unwrap().as_ref().map(|p| p.tab);
+    if let Some(tab) = presented {
+        lock_others(app, tab);
+    }
+}
+
+// 锁定演示标签以外的标签
+fn lock_others(app: &AppHandle, tab: isize) {
+    let others: Vec<isize> = app.state::<WindowManager>().hwnds().into_iter().filter(|&h| h != tab).collect();
+    for other in others {
+        input_lock::lock(app, other, LockOwner::Presentation);
+    }
+    // 锁定期间退出了演示模式
+    if !is_active() {
+        input_lock::unlock_all(app, LockOwner::Presentation);
+    }
+}
+
+// 阻止屏幕保护程序和休眠直到退出演示模式 (执行状态属于调用线程，所以用单独的线程持有)
+#[cfg(windows)]
+fn keep_awake(generation: u64) {
+    std::thread::spawn(move || unsafe {
+        SetThreadExecutionState(ES_CONTINUOUS | ES_DISPLAY_REQUIRED | ES_SYSTEM_REQUIRED);
+        while GENERATION.load(Ordering::SeqCst) == generation {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+        SetThreadExecutionState(ES_CONTINUOUS);
+    });
+}
+
+#[cfg(not(windows))]
+fn keep_awake(_generation: u64) {}
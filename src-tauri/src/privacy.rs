@@ -0,0 +1,135 @@
+// 隐私模式 (共享屏幕开会时使用)
+// 开启后：
+// - 嵌入窗口的标题和自定义名称不再发给前端 (命令返回值和事件中换成统一的 "窗口")，
+//   原生标签栏、托盘菜单和快速切换器也只显示统一的名称
+// - 主窗口 (连同嵌入的标签) 通过 SetWindowDisplayAffinity(WDA_EXCLUDEFROMCAPTURE) 排除在屏幕捕获之外，
+//   共享整个屏幕时对方看到的是主窗口后面的内容 (需要 Windows 10 2004 以上)
+// 只在本次运行中有效，不保存；状态变化时发送 privacy-mode-changed (载荷为是否开启)
+//
+// 也可以只排除某个标签 (set_tab_capture_excluded，如密码管理器、网银)。显示属性只对顶层窗口有效，
+// 嵌入的标签是主窗口的子窗口，所以在排除的标签显示期间把整个主窗口排除在捕获之外，切换到其他标签后恢复；
+// 标签显示或隐藏时 (见 tab_view.rs) 重新计算。设置记录在标签上 (EmbeddedWindow.capture_excluded)，
+// 变化时发送 tab-capture-excluded (载荷为 TabCaptureExcluded)
+
+use serde::Serializer;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::window_manager::{TabCaptureExcluded, WindowManager};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 主窗口当前是否排除在屏幕捕获之外
+static HOST_EXCLUDED: AtomicBool = AtomicBool::new(false);
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// 开启或关闭隐私模式
+pub fn set(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    if ENABLED.swap(enabled, Ordering::SeqCst) == enabled {
+        return Ok(());
+    }
+    info!(target: "focus", "隐私模式: {}", if enabled { "开启" } else { "关闭" });
+    let applied = apply_capture(app);
+    crate::native_tabs::refresh();
+    crate::tray::refresh(app);
+    let _ = app.emit("privacy-mode-changed", enabled);
+    applied
+}
+
+/// 设置标签显示时是否排除在屏幕捕获之外
+pub fn set_tab_excluded(app: &AppHandle, manager: &WindowManager, hwnd: isize, excluded: bool) -> Result<(), String> {
+    manager.set_capture_excluded(hwnd, excluded).ok_or("标签不存在")?;
+    info!(target: "focus", "标签 {} 排除屏幕捕获: {}", hwnd, excluded);
+    let _ = app.emit("tab-capture-excluded", TabCaptureExcluded { hwnd, excluded });
+    apply_capture(app)
+}
+
+/// 标签显示、隐藏、释放或关闭后重新计算主窗口是否排除在屏幕捕获之外
+pub fn refresh_capture() {
+    let Some(app) = APP.get() else { return };
+    if let Err(e) = apply_capture(app) {
+        warn!(target: "focus", "{}", e);
+    }
+}
+
+fn apply_capture(app: &AppHandle) -> Result<(), String> {
+    let tabs = app.state::<WindowManager>().list();
+    let excluded = host_excluded(is_enabled(), tabs.iter().map(|w| (w.capture_excluded, crate::tab_view::is_hidden(w.hwnd))));
+    if HOST_EXCLUDED.swap(excluded, Ordering::SeqCst) == excluded {
+        return Ok(());
+    }
+    exclude_from_capture(crate::get_main_window_hwnd(app.clone()), excluded).inspect_err(|_| {
+        HOST_EXCLUDED.store(!excluded, Ordering::SeqCst);
+    })
+}
+
+// 隐私模式开启，或有排除的标签正在显示 (tabs 为 (是否排除, 是否隐藏))
+fn host_excluded(enabled: bool, mut tabs: impl Iterator<Item = (bool, bool)>) -> bool {
+    enabled || tabs.any(|(excluded, hidden)| excluded && !hidden)
+}
+
+/// 隐私模式下把窗口标题换成统一的名称
+pub fn title(title: String) -> String {
+    if is_enabled() {
+        crate::messages::text("privacy.title", &[])
+    } else {
+        title
+    }
+}
+
+/// 序列化窗口标题 (serialize_with)
+pub fn serialize_title<S: Serializer>(title: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    if is_enabled() {
+        serializer.serialize_str(&crate::messages::text("privacy.title", &[]))
+    } else {
+        serializer.serialize_str(title)
+    }
+}
+
+/// 序列化标签的自定义名称 (serialize_with)，隐私模式下不发送
+pub fn serialize_label<S: Serializer>(label: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match label {
+        Some(label) if !is_enabled() => serializer.serialize_some(label),
+        _ => serializer.serialize_none(),
+    }
+}
+
+#[cfg(windows)]
+fn exclude_from_capture(host: isize, excluded: bool) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_NONE};
+
+    if host == 0 {
+        return Err("无法获取主窗口".to_string());
+    }
+    let affinity = if excluded { WDA_EXCLUDEFROMCAPTURE } else { WDA_NONE };
+    unsafe { SetWindowDisplayAffinity(HWND(host as *mut _), affinity) }.map_err(|e| format!("设置屏幕捕获排除失败: {}", e))
+}
+
+#[cfg(not(windows))]
+fn exclude_from_capture(_host: isize, _excluded: bool) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_is_excluded_while_an_excluded_tab_is_shown() {
+        assert!(!host_excluded(false, [(false, false), (true, true)].into_iter()));
+        assert!(host_excluded(false, [(false, true), (true, false)].into_iter()));
+        assert!(host_excluded(true, std::iter::empty()));
+    }
+}
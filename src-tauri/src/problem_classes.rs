@@ -0,0 +1,58 @@
+// 嵌入时无响应的窗口类型
+// 有些应用在嵌入 (修改样式、SetParent) 过程中卡住，嵌入超时后把它的窗口类名记录下来，
+// 之后不再尝试嵌入同类窗口，避免反复卡住；用户可以在列表中移除后重试
+// 列表保存在数据库中 (storage.rs)
+
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+static CLASSES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// 记录为有问题的窗口类型
+pub fn mark(class_name: &str) {
+    if class_name.is_empty() || contains(class_name) {
+        return;
+    }
+    let classes = {
+        let mut classes = CLASSES.lock().unwrap();
+        classes.push(class_name.to_string());
+        classes.clone()
+    };
+    warn!(target: "embed", "窗口类型嵌入超时，之后不再嵌入: {}", class_name);
+    save(&classes);
+}
+
+pub fn contains(class_name: &str) -> bool {
+    CLASSES.lock().unwrap().iter().any(|c| c == class_name)
+}
+
+pub fn list() -> Vec<String> {
+    CLASSES.lock().unwrap().clone()
+}
+
+/// 从列表中移除 (允许再次尝试嵌入)，返回新的列表
+pub fn forget(class_name: &str) -> Vec<String> {
+    let classes = {
+        let mut classes = CLASSES.lock().unwrap();
+        classes.retain(|c| c != class_name);
+        classes.clone()
+    };
+    info!(target: "embed", "允许再次嵌入窗口类型: {}", class_name);
+    save(&classes);
+    classes
+}
+
+fn save(classes: &[String]) {
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save("problem_classes", classes) {
+            warn!(target: "embed", "{}", e);
+        }
+    }
+}
+
+/// 从数据库加载 (setup 时调用)
+pub fn load() {
+    let Some(loaded) = crate::storage::get().and_then(|s| s.load::<Vec<String>>("problem_classes")) else { return };
+    info!(target: "settings", "已加载 {} 个嵌入无响应的窗口类型", loaded.len());
+    *CLASSES.lock().unwrap() = loaded;
+}
@@ -0,0 +1,118 @@
+// 配置导出/导入
+// 把设置 (含快捷键)、窗口规则、工作区和标签名称打包成一个 JSON 文件，在多台电脑之间同步
+// 导入时先校验整个文件 (包括规则条件的语法)，全部有效才替换当前配置；
+// 替换后发送 config-reloaded (载荷为变化的配置文件名)，与手动编辑配置文件时一致
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+use crate::config_watch::ConfigReloaded;
+use crate::rules::Rule;
+use crate::settings::{self, Settings};
+use crate::tab_labels::{self, TabLabel};
+use crate::Workspace;
+
+// 文件格式的版本，不兼容的修改时增加
+const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: u64, // 导出时间 (Unix 秒)
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub workspaces: Vec<Workspace>,
+    #[serde(default)]
+    pub tab_labels: Vec<TabLabel>,
+}
+
+/// 导出当前配置到 path
+pub fn export(path: &str) -> Result<(), String> {
+    let profile = Profile {
+        version: VERSION,
+        exported_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        settings: settings::get(),
+        rules: crate::rules::list(),
+        workspaces: crate::WORKSPACES.lock().unwrap().clone(),
+        tab_labels: tab_labels::list(),
+    };
+    let json = serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("导出配置失败: {}", e))?;
+    info!(target: "settings", "已导出配置: {}", path);
+    Ok(())
+}
+
+/// 从 path 导入配置并替换当前配置，返回变化的配置文件名
+pub fn import(app: &AppHandle, path: &str) -> Result<Vec<String>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取配置文件失败: {}", e))?;
+    let profile = parse(&content)?;
+
+    let mut changed = Vec::new();
+    if profile.settings != settings::get() {
+        let previous = settings::update(profile.settings)?;
+        crate::on_settings_changed(app, &previous);
+        changed.push("settings.json".to_string());
+    }
+    if crate::rules::replace(profile.rules) {
+        changed.push("rules.json".to_string());
+    }
+    {
+        let mut workspaces = crate::WORKSPACES.lock().unwrap();
+        if *workspaces != profile.workspaces {
+            *workspaces = profile.workspaces;
+            crate::save_workspaces_to_file(&workspaces);
+            changed.push("workspaces.json".to_string());
+        }
+    }
+    if tab_labels::replace(profile.tab_labels) {
+        changed.push("tab_labels.json".to_string());
+    }
+
+    info!(target: "settings", "已导入配置: {} ({:?})", path, changed);
+    if !changed.is_empty() {
+        let _ = app.emit("config-reloaded", ConfigReloaded { files: changed.clone() });
+    }
+    Ok(changed)
+}
+
+// 解析并校验导出的配置文件
+fn parse(content: &str) -> Result<Profile, String> {
+    let profile: Profile = serde_json::from_str(content).map_err(|e| format!("配置文件格式错误: {}", e))?;
+    if profile.version > VERSION {
+        return Err(format!("配置文件来自更新版本的 WindowHub (格式版本 {})，请先升级", profile.version));
+    }
+    settings::validate(&profile.settings)?;
+    crate::rules::validate(&profile.rules)?;
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_exported_profiles() {
+        let profile = Profile {
+            version: VERSION,
+            exported_at: 1_700_000_000,
+            settings: Settings::default(),
+            rules: vec![Rule { name: "日志".to_string(), when: r#"exe == "notepad.exe""#.to_string(), actions: Vec::new() }],
+            workspaces: Vec::new(),
+            tab_labels: Vec::new(),
+        };
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(parse(&json), Ok(profile));
+
+        // 只有部分内容的文件其余使用默认值
+        assert_eq!(parse(r#"{"version":1}"#).unwrap().settings, Settings::default());
+        assert!(parse(r#"{"version":99}"#).is_err());
+        // 规则条件有语法错误时整个文件无效
+        assert!(parse(r#"{"version":1,"rules":[{"name":"坏","when":"exe ==","actions":[]}]}"#).is_err());
+    }
+}
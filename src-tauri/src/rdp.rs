@@ -0,0 +1,142 @@
+// 远程桌面 (mstsc) 窗口的特殊处理
+// RDP 客户端的顶层窗口 (TscShellContainerClass) 本身不处理键盘，输入由内部的 IHWindowClass 子窗口接收：
+// - 激活标签时焦点交给 IHWindowClass (见 Win32Platform::activate)，否则按键不会发到远程会话
+// - Ctrl+Alt+Break 会把 RDP 窗口切换成全屏，脱离主窗口盖住整个屏幕；
+//   焦点在嵌入的 RDP 标签中时由低级键盘钩子拦截这个组合键 (钩子在第一次嵌入 RDP 窗口时安装)
+// 钩子在 mstsc 之后安装，先于 mstsc 自己的键盘钩子收到按键
+
+use std::sync::Mutex;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(windows)]
+use tracing::{debug, info, warn};
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+    System::LibraryLoader::GetModuleHandleW,
+    UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_CONTROL, VK_MENU},
+    UI::WindowsAndMessaging::*,
+};
+
+/// RDP 客户端的顶层窗口类型
+pub const CONTAINER_CLASS: &str = "TscShellContainerClass";
+/// RDP 客户端内部接收键盘输入的子窗口类型
+#[cfg_attr(not(windows), allow(dead_code))]
+pub const INPUT_CLASS: &str = "IHWindowClass";
+
+// Break (Ctrl+Pause 产生 VK_CANCEL) 和 Pause
+const VK_CANCEL: u32 = 0x03;
+const VK_PAUSE: u32 = 0x13;
+
+// 嵌入的 RDP 标签
+static TABS: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+#[cfg(windows)]
+static HOOKED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_rdp_class(class_name: &str) -> bool {
+    class_name == CONTAINER_CLASS
+}
+
+/// 嵌入了新标签：RDP 窗口开始拦截全屏快捷键
+pub fn on_embedded(tab: isize, class_name: &str) {
+    if !is_rdp_class(class_name) {
+        return;
+    }
+    {
+        let mut tabs = TABS.lock().unwrap();
+        if !tabs.contains(&tab) {
+            tabs.push(tab);
+        }
+    }
+    #[cfg(windows)]
+    if !HOOKED.swap(true, Ordering::SeqCst) {
+        info!(target: "embed", "嵌入了远程桌面窗口，拦截 Ctrl+Alt+Break");
+        std::thread::spawn(|| unsafe { hook_thread() });
+    }
+}
+
+/// 标签已释放或关闭
+pub fn forget(tab: isize) {
+    TABS.lock().unwrap().retain(|h| *h != tab);
+}
+
+// 会让 RDP 窗口切换全屏的按键 (Ctrl+Alt+Break)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn is_fullscreen_toggle(vk: u32, ctrl: bool, alt: bool) -> bool {
+    ctrl && alt && (vk == VK_CANCEL || vk == VK_PAUSE)
+}
+
+#[cfg(windows)]
+unsafe fn hook_thread() {
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    match SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), instance, 0) {
+        Ok(hook) => {
+            let resource = crate::resources::register(crate::resources::ResourceKind::LowLevelHook, "rdp", 1, None, None);
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+            let _ = UnhookWindowsHookEx(hook);
+            crate::resources::unregister(resource);
+        }
+        Err(e) => {
+            warn!(target: "embed", "安装键盘钩子失败，无法拦截远程桌面的全屏快捷键: {}", e);
+            HOOKED.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        let ctrl = GetAsyncKeyState(VK_CONTROL.0 as i32) < 0;
+        let alt = GetAsyncKeyState(VK_MENU.0 as i32) < 0;
+        if is_fullscreen_toggle(info.vkCode, ctrl, alt) && focus_in_rdp_tab() {
+            if wparam.0 as u32 == WM_KEYDOWN || wparam.0 as u32 == WM_SYSKEYDOWN {
+                debug!(target: "embed", "已拦截远程桌面的全屏快捷键");
+            }
+            return LRESULT(1);
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+// 输入焦点是否在嵌入的 RDP 标签 (或其子窗口) 中
+#[cfg(windows)]
+unsafe fn focus_in_rdp_tab() -> bool {
+    let mut gui = GUITHREADINFO { cbSize: std::mem::size_of::<GUITHREADINFO>() as u32, ..Default::default() };
+    if GetGUIThreadInfo(0, &mut gui).is_err() {
+        return false;
+    }
+    let tabs = TABS.lock().unwrap().clone();
+    let mut current = if gui.hwndFocus.is_invalid() { gui.hwndActive } else { gui.hwndFocus };
+    while !current.is_invalid() {
+        if tabs.contains(&(current.0 as isize)) {
+            return true;
+        }
+        current = GetAncestor(current, GA_PARENT);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_ctrl_alt_break_is_blocked() {
+        assert!(is_fullscreen_toggle(VK_CANCEL, true, true));
+        assert!(is_fullscreen_toggle(VK_PAUSE, true, true));
+        assert!(!is_fullscreen_toggle(VK_CANCEL, true, false));
+        // Ctrl+Alt+Home (显示连接栏) 等其他组合键照常发给远程会话
+        assert!(!is_fullscreen_toggle(0x24, true, true));
+
+        on_embedded(0x81, "Notepad");
+        assert!(TABS.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+// 应用重启后重新嵌入
+// 嵌入的应用崩溃或自我更新后原窗口句柄失效，标签记录的身份 (EXE 路径、启动参数、标题) 仍然保留：
+// - reattach_window(tab_id, relaunch) 开始等待匹配的新窗口，relaunch 时先用相同参数重新启动应用
+// - 新窗口出现后嵌入到同一个标签位置，继承名称/分组/颜色，发送 window-reattached
+// - 超时仍未出现时发送 reattach-failed，标签保持失效状态，可以再次尝试或关闭
+// restart_tab 是标签的"刷新"：先请求应用关闭 (拒绝时可强制结束)，再按上面的流程重新启动并嵌入；
+// 关闭前读取滚动位置等界面状态，重新嵌入后恢复 (见 ui_state.rs)
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::close_request::CloseOutcome;
+use crate::tab_state::{self, TabState};
+use crate::window_manager::{EmbeddedWindow, WindowManager};
+
+const WATCH_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// 读取界面状态的上限 (跨进程访问辅助功能树，应用无响应时不拖住重启)
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(2);
+
+// 新窗口的内容可能还在加载，恢复界面状态最多尝试的次数和间隔
+const RESTORE_ATTEMPTS: u32 = 10;
+const RESTORE_INTERVAL: Duration = Duration::from_millis(500);
+
+// 正在等待新窗口的标签及其程序路径，避免重复启动等待线程
+static WATCHING: Mutex<Vec<(u64, String)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowReattached {
+    pub tab_id: u64,
+    pub previous: isize, // 失效的窗口句柄
+    pub window: EmbeddedWindow,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReattachFailed {
+    pub tab_id: u64,
+    pub message: String,
+}
+
+// 等待期间出现的新顶层窗口
+struct Candidate {
+    hwnd: isize,
+    exe_path: String,
+    title: String,
+    args: Option<Vec<String>>,
+}
+
+// 同一程序的新窗口中，启动参数相同的优先，其次是标题匹配的，都一样时取最先出现的
+fn best_match(identity: &EmbeddedWindow, candidates: &[Candidate]) -> Option<isize> {
+    let exe_path = identity.exe_path.as_deref()?;
+    candidates
+        .iter()
+        .filter(|c| c.exe_path.eq_ignore_ascii_case(exe_path))
+        .map(|c| {
+            let args_match = c.args.as_ref() == Some(&identity.args);
+            let title_match = crate::tab_labels::matches_pattern(&identity.title, &c.title);
+            (c, (args_match, title_match))
+        })
+        .fold(None, |best: Option<(&Candidate, (bool, bool))>, (c, score)| match best {
+            Some((_, best_score)) if best_score >= score => best,
+            _ => Some((c, score)),
+        })
+        .map(|(c, _)| c.hwnd)
+}
+
+/// 开始为失效的标签等待新窗口
+pub fn start(app: &AppHandle, tab_id: u64, relaunch: bool) -> Result<(), String> {
+    let platform = crate::platform::current();
+    let identity = app.state::<WindowManager>().find_tab(tab_id).ok_or("标签不存在")?;
+    if platform.is_window(identity.hwnd) {
+        return Err("窗口仍然存在，不需要重新嵌入".to_string());
+    }
+    let exe_path = identity.exe_path.clone().ok_or("无法获取原窗口的程序路径")?;
+
+    {
+        let mut watching = WATCHING.lock().unwrap();
+        if watching.iter().any(|(id, _)| *id == tab_id) {
+            // 已经在等待新窗口 (如存活检查先发现了窗口失效)，需要时补上重新启动，新进程的窗口同样会被匹配
+            drop(watching);
+            if relaunch {
+                launch(&exe_path, &identity.args)?;
+                let _ = tab_state::transition(tab_id, identity.hwnd, TabState::Launching);
+            }
+            return Ok(());
+        }
+        watching.push((tab_id, exe_path.clone()));
+    }
+
+    // 之前已存在的窗口不参与匹配，避免抢走用户在外面打开的同一应用的其他窗口
+    let existing: HashSet<isize> = platform.enumerate().iter().map(|w| w.hwnd).collect();
+    if relaunch {
+        if let Err(e) = launch(&exe_path, &identity.args) {
+            WATCHING.lock().unwrap().retain(|(id, _)| *id != tab_id);
+            return Err(e);
+        }
+    }
+    let state = if relaunch { TabState::Launching } else { TabState::Waiting };
+    let _ = tab_state::transition(tab_id, identity.hwnd, state);
+
+    info!(target: "embed", "等待标签 {} 的新窗口: {}", tab_id, exe_path);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = watch(&app, &identity, &existing);
+        WATCHING.lock().unwrap().retain(|(id, _)| *id != tab_id);
+        if let Err(message) = result {
+            warn!(target: "embed", "标签 {} 重新嵌入失败: {}", tab_id, message);
+            let _ = tab_state::transition(tab_id, identity.hwnd, TabState::Waiting);
+            let _ = app.emit("reattach-failed", ReattachFailed { tab_id, message });
+        }
+    });
+    Ok(())
+}
+
+fn launch(exe_path: &str, args: &[String]) -> Result<(), String> {
+    std::process::Command::new(exe_path).args(args).spawn().map_err(|e| format!("重新启动失败: {}", e))?;
+    info!(target: "launcher", "重新启动应用: {} {:?}", exe_path, args);
+    Ok(())
+}
+
+/// 重启标签中的应用：关闭 (或强制结束) 后用相同参数重新启动，新窗口嵌入到同一个标签位置
+/// 返回关闭的结果，只有 Closed 时才开始重启 (force 时应用拒绝关闭会被强制结束，也返回 Closed)；
+/// 应用弹出保存提示时标签保持原样由用户处理
+pub async fn restart(app: &AppHandle, hwnd: isize, force: bool) -> Result<CloseOutcome, String> {
+    let platform = crate::platform::current();
+    let identity = app.state::<WindowManager>().get(hwnd).ok_or("标签不存在")?;
+    if identity.exe_path.is_none() {
+        return Err("无法获取程序路径，不能重启".to_string());
+    }
+    let pid = platform.process_id(hwnd);
+
+    let tab_id = identity.tab_id;
+    let capture = tauri::async_runtime::spawn_blocking(move || crate::ui_state::capture(tab_id, hwnd));
+    if tokio::time::timeout(CAPTURE_TIMEOUT, capture).await.is_err() {
+        warn!(target: "embed", "读取界面状态超时，跳过: hwnd={}", hwnd);
+    }
+
+    match crate::close_request::request(platform, hwnd).await {
+        CloseOutcome::Closed => {}
+        CloseOutcome::Refused if force => {
+            crate::close_request::force_kill(platform, hwnd, pid)?;
+            // 进程结束后窗口随之销毁，稍等片刻
+            for _ in 0..20 {
+                if !platform.is_window(hwnd) {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+        outcome => return Ok(outcome),
+    }
+    info!(target: "launcher", "重启标签 {}: {}", identity.tab_id, identity.title);
+    start(app, identity.tab_id, true)?;
+    Ok(CloseOutcome::Closed)
+}
+
+/// 是否有失效的标签正在等待该程序的新窗口 (自动嵌入时让给原标签)
+pub fn is_waiting_for(exe_path: &str) -> bool {
+    WATCHING.lock().unwrap().iter().any(|(_, path)| path.eq_ignore_ascii_case(exe_path))
+}
+
+fn watch(app: &AppHandle, identity: &EmbeddedWindow, existing: &HashSet<isize>) -> Result<(), String> {
+    let platform = crate::platform::current();
+    let deadline = Instant::now() + WATCH_TIMEOUT;
+    while Instant::now() < deadline {
+        std::thread::sleep(POLL_INTERVAL);
+        // 等待期间标签被关闭
+        if app.state::<WindowManager>().find_tab(identity.tab_id).is_none() {
+            return Ok(());
+        }
+
+        let candidates: Vec<Candidate> = platform
+            .enumerate()
+            .into_iter()
+            .filter(|w| !existing.contains(&w.hwnd))
+            .filter_map(|w| {
+                let exe_path = platform.process_path(w.hwnd).ok()?;
+                Some(Candidate { hwnd: w.hwnd, exe_path, title: w.title, args: platform.command_line(w.hwnd) })
+            })
+            .collect();
+        if let Some(hwnd) = best_match(identity, &candidates) {
+            return attach(app, identity, hwnd);
+        }
+    }
+    Err("等待超时，没有出现匹配的新窗口".to_string())
+}
+
+fn attach(app: &AppHandle, identity: &EmbeddedWindow, hwnd: isize) -> Result<(), String> {
+    let platform = crate::platform::current();
+    let manager = app.state::<WindowManager>();
+    let host = crate::get_main_window_hwnd(app.clone());
+    if let Err(e) = manager.embed(platform, host, hwnd) {
+        crate::lifecycle::embed_failed(hwnd, &e);
+        return Err(e.message);
+    }
+    // 嵌入期间标签被关闭，新窗口恢复为独立窗口
+    let Some(window) = manager.replace_tab(identity.tab_id, hwnd) else {
+        let _ = manager.release(platform, hwnd);
+        return Ok(());
+    };
+    if window.preserve_transparency {
+        let _ = crate::apply_transparency(&manager, hwnd, true);
+    }
+    let _ = platform.repaint(hwnd);
+    crate::crash_watch::watch(&window);
+    let _ = tab_state::transition(window.tab_id, hwnd, TabState::Embedded);
+
+    info!(target: "embed", "标签 {} 已重新嵌入: {} -> {}", identity.tab_id, identity.hwnd, hwnd);
+    crate::tray::refresh(app);
+    let _ = app.emit("window-reattached", WindowReattached { tab_id: identity.tab_id, previous: identity.hwnd, window });
+    restore_ui_state(identity.tab_id, hwnd);
+    Ok(())
+}
+
+// 重启前读取到的界面状态恢复到新窗口 (后台重试，直到恢复或放弃)
+fn restore_ui_state(tab_id: u64, hwnd: isize) {
+    let Some(state) = crate::ui_state::take(tab_id) else { return };
+    std::thread::spawn(move || {
+        for _ in 0..RESTORE_ATTEMPTS {
+            std::thread::sleep(RESTORE_INTERVAL);
+            if !crate::platform::current().is_window(hwnd) || crate::ui_state::restore(hwnd, &state) {
+                return;
+            }
+        }
+        warn!(target: "embed", "未能完全恢复标签 {} 的界面状态", tab_id);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window_manager::WindowRect;
+
+    fn identity(exe_path: &str, title: &str, args: &[&str]) -> EmbeddedWindow {
+        EmbeddedWindow {
+            hwnd: 1,
+            tab_id: 1,
+            title: title.to_string(),
+            class_name: String::new(),
+            pid: 0,
+            exe_path: Some(exe_path.to_string()),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            original_style: 0,
+            original_exstyle: 0,
+            original_layered: None,
+            original_rect: WindowRect::default(),
+            original_owner: 0,
+            monitor: 0,
+            embedded_at: 0,
+            label: None,
+            group: None,
+            color: None,
+            hotkey: None,
+            auto_restart: false,
+            pinned: false,
+            input_locked: false,
+            capture_excluded: false,
+            preserve_transparency: false,
+        }
+    }
+
+    fn candidate(hwnd: isize, exe_path: &str, title: &str, args: Option<&[&str]>) -> Candidate {
+        Candidate {
+            hwnd,
+            exe_path: exe_path.to_string(),
+            title: title.to_string(),
+            args: args.map(|a| a.iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn prefers_same_args_then_same_title() {
+        let tab = identity("/apps/Code.exe", "main.rs - crate", &["/work/crate"]);
+        let candidates = [
+            candidate(10, "/apps/Other.exe", "main.rs - crate", Some(&["/work/crate"])),
+            candidate(11, "/apps/code.exe", "Welcome", Some(&[])),
+            candidate(12, "/apps/Code.exe", "main.rs - crate", None),
+            candidate(13, "/apps/Code.exe", "Welcome", Some(&["/work/crate"])),
+        ];
+        assert_eq!(best_match(&tab, &candidates), Some(13));
+        assert_eq!(best_match(&tab, &candidates[..3]), Some(12));
+        assert_eq!(best_match(&tab, &candidates[..2]), Some(11));
+        assert_eq!(best_match(&tab, &candidates[..1]), None);
+    }
+}
@@ -0,0 +1,660 @@
+// 远程标签 (实验性)
+// 把局域网中另一台电脑上的窗口作为标签显示：对方的 WindowHub 截取窗口画面传过来，本机的点击和按键转发回去
+// 被查看的一方 (服务端)：设置 remote.enabled 开启后在 remote.port 上监听 (默认关闭)，只能查看和操作已嵌入的标签，
+// 连接时服务端发送随机的 nonce (系统的安全随机数)，客户端回复 HMAC-SHA256(密钥, nonce)，与设置中的 remote.key 一致才继续，
+// 密钥不在网络上传输，比较时用固定时间的比较；同一地址认证失败后按次数加倍拒绝连接的时间 (最长 5 分钟)
+// 密钥只说明是可信的机器，每个请求还要有对方用 create_api_client 生成的令牌授予的权限 (见 permissions.rs)：
+// 列出窗口需要 enumerate，查看画面需要 view，转发输入需要 input；令牌同样只发送 HMAC，作废后下一次请求即被拒绝
+// (服务端数据库中的令牌摘要就是 HMAC 的密钥，数据库需要保密，见 permissions.rs)
+// 查看的一方 (客户端)：
+// - list_remote_windows(address, key, token) 列出对方的标签
+// - open_remote_tab(address, key, hwnd, token) 打开远程标签，之后持续收到 remote-tab-frame (JPEG data URL，与缩略图相同)，
+//   画面没有变化时不发送；对方窗口关闭或连接断开时发送 remote-tab-closed
+// - send_remote_input(id, input) 转发点击 (画面中的相对位置) 和按键序列 (格式见 keys.rs)
+// - close_remote_tab(id) 断开
+// 画面只是图片，客户端不依赖本机的窗口系统 (Wayland 等无法嵌入窗口的环境也能使用)；
+// 输入在服务端激活窗口后用 SendInput 注入，只支持 Windows
+// 协议为每行一个 JSON 消息 (Message)；画面和输入不加密，只在可信的局域网中使用
+// 连接数：认证通过后才占用 MAX_CONNECTIONS 的名额，未认证的连接另有上限 (MAX_PENDING)，
+// 握手超时或不回复同样算认证失败；发送有超时，不读取的客户端不会一直卡住画面线程
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicIsize, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, info, warn};
+
+use crate::permissions::{self, Capability};
+use crate::window_manager::{WindowManager, WindowRect};
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, RECT},
+    UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEINPUT,
+        MOUSE_EVENT_FLAGS,
+    },
+    UI::WindowsAndMessaging::{GetWindowRect, SetCursorPos},
+};
+
+pub const DEFAULT_PORT: u16 = 47615;
+// 握手可以被局域网中的其他机器截获后离线猜测密钥，所以要求较长的密钥
+pub const MIN_KEY_LEN: usize = 16;
+
+// 每秒最多 5 帧，截图用 PrintWindow，太快会拖慢被查看的程序
+const FRAME_INTERVAL: Duration = Duration::from_millis(200);
+const FRAME_SIZE: i32 = 1280;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const ACCEPT_POLL: Duration = Duration::from_millis(250);
+const MAX_CONNECTIONS: usize = 4;
+// 同时进行中的握手
+const MAX_PENDING: usize = 8;
+// 发送一条消息的最长时间 (画面约 1 MB)，超时后断开连接
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+// 一行消息的长度上限 (画面的 data URL 通常在 1 MB 以内)
+const MAX_MESSAGE: u64 = 8 * 1024 * 1024;
+// 认证失败后拒绝同一地址连接的最长时间
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// 对方可以查看的窗口
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteWindow {
+    pub hwnd: isize,
+    pub title: String,
+    pub embedded: bool, // 是否是对方已嵌入的标签 (只列出标签，总是 true，保留以兼容旧版本)
+}
+
+/// 转发给远程窗口的输入
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RemoteInput {
+    Click {
+        x: f64, // 画面中的相对位置 (0 ~ 1)
+        y: f64,
+        #[serde(default)]
+        right: bool,
+    },
+    Keys {
+        sequence: String,
+    },
+}
+
+/// 打开的远程标签
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteTab {
+    pub id: u64,
+    pub address: String,
+    pub hwnd: isize, // 对方电脑上的窗口句柄
+    #[serde(serialize_with = "crate::privacy::serialize_title")]
+    pub title: String,
+}
+
+/// remote-tab-frame 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+struct RemoteFrame {
+    id: u64,
+    data_url: String,
+}
+
+/// remote-tab-closed 事件的载荷，reason 为空表示用户自己关闭
+#[derive(Debug, Clone, Serialize)]
+struct RemoteTabClosed {
+    id: u64,
+    reason: Option<String>,
+}
+
+// 连接上的消息，两个方向共用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    Challenge { nonce: String },
+    Auth {
+        proof: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token_proof: Option<String>,
+    },
+    Ready,
+    Error { message: String },
+    List,
+    Windows { windows: Vec<RemoteWindow> },
+    View { hwnd: isize, max_size: i32 },
+    Frame { data_url: String },
+    Closed,
+    Input { input: RemoteInput },
+}
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// 服务端：正在监听的端口，每次重新监听或停止时 GENERATION 加一，旧的监听线程看到后退出
+static LISTENING: Mutex<Option<u16>> = Mutex::new(None);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+// 服务端的连接 (连接 ID, 连接, 是否已认证)，停止时全部断开
+static SESSIONS: Mutex<Vec<(u64, TcpStream, bool)>> = Mutex::new(Vec::new());
+// 客户端打开的远程标签
+static TABS: Mutex<Vec<(RemoteTab, TcpStream)>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+// 服务端：认证失败的地址 (地址, 连续失败次数, 在此之前拒绝连接)
+static FAILURES: Mutex<Vec<(IpAddr, u32, Instant)>> = Mutex::new(Vec::new());
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    apply();
+}
+
+/// 设置变化后开始/停止监听 (密钥在每次连接时读取，修改密钥不需要重新监听)
+pub fn apply() {
+    let settings = crate::settings::get().remote;
+    let wanted = settings.enabled.then_some(settings.port);
+    let mut listening = LISTENING.lock().unwrap();
+    if *listening == wanted {
+        return;
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    for (_, stream, _) in SESSIONS.lock().unwrap().drain(..) {
+        let _ = stream.shutdown(Shutdown::Both);
+    }
+    *listening = wanted;
+    if let Some(port) = wanted {
+        std::thread::spawn(move || serve(port, generation));
+    } else {
+        info!(target: "embed", "远程标签: 停止监听");
+    }
+}
+
+/// 列出对方的窗口
+pub fn list_windows(address: &str, key: &str, token: &str) -> Result<Vec<RemoteWindow>, String> {
+    let (stream, mut reader) = connect(address, key, token)?;
+    let windows = request_windows(&stream, &mut reader);
+    let _ = stream.shutdown(Shutdown::Both);
+    windows
+}
+
+/// 打开对方的窗口作为远程标签
+pub fn open(app: &AppHandle, address: &str, key: &str, hwnd: isize, token: &str) -> Result<RemoteTab, String> {
+    let (stream, mut reader) = connect(address, key, token)?;
+    let title = request_windows(&stream, &mut reader)?
+        .into_iter()
+        .find(|w| w.hwnd == hwnd)
+        .map(|w| w.title)
+        .ok_or("对方没有这个窗口")?;
+    write_message(&stream, &Message::View { hwnd, max_size: FRAME_SIZE })?;
+    let _ = stream.set_read_timeout(None);
+
+    let tab = RemoteTab { id: NEXT_ID.fetch_add(1, Ordering::SeqCst), address: address.trim().to_string(), hwnd, title };
+    TABS.lock().unwrap().push((tab.clone(), stream));
+    info!(target: "embed", "打开远程标签 {}: {} hwnd={}", tab.id, tab.address, hwnd);
+    let (app, id) = (app.clone(), tab.id);
+    std::thread::spawn(move || receive(&app, id, reader));
+    Ok(tab)
+}
+
+/// 向远程标签转发点击或按键
+pub fn send_input(id: u64, input: RemoteInput) -> Result<(), String> {
+    if let RemoteInput::Keys { sequence } = &input {
+        crate::keys::parse_sequence(sequence)?;
+    }
+    // 网络写入可能阻塞，复制连接后在锁外发送
+    let stream = {
+        let tabs = TABS.lock().unwrap();
+        let (_, stream) = tabs.iter().find(|(tab, _)| tab.id == id).ok_or("远程标签不存在")?;
+        stream.try_clone().map_err(|e| e.to_string())?
+    };
+    write_message(&stream, &Message::Input { input })
+}
+
+/// 关闭远程标签
+pub fn close(id: u64) -> Result<(), String> {
+    let (tab, stream) = remove(id).ok_or("远程标签不存在")?;
+    let _ = stream.shutdown(Shutdown::Both);
+    info!(target: "embed", "关闭远程标签 {}: {}", tab.id, tab.address);
+    Ok(())
+}
+
+pub fn list() -> Vec<RemoteTab> {
+    TABS.lock().unwrap().iter().map(|(tab, _)| tab.clone()).collect()
+}
+
+fn remove(id: u64) -> Option<(RemoteTab, TcpStream)> {
+    let mut tabs = TABS.lock().unwrap();
+    let index = tabs.iter().position(|(tab, _)| tab.id == id)?;
+    Some(tabs.remove(index))
+}
+
+// 客户端：连接并认证，同时证明持有令牌
+fn connect(address: &str, key: &str, token: &str) -> Result<(TcpStream, BufReader<TcpStream>), String> {
+    let addr = socket_addr(address)?;
+    let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).map_err(|e| format!("无法连接 {}: {}", address.trim(), e))?;
+    let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(CONNECT_TIMEOUT));
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let Message::Challenge { nonce } = read_message(&mut reader)? else {
+        return Err("对方不是 WindowHub 或不支持远程标签".to_string());
+    };
+    // 服务端只保存令牌的摘要，以摘要为 HMAC 的密钥
+    let token_proof = Some(proof(&nonce, &permissions::token_hash(token)));
+    write_message(&stream, &Message::Auth { proof: proof(&nonce, key), token_proof })?;
+    match read_message(&mut reader)? {
+        Message::Ready => Ok((stream, reader)),
+        Message::Error { message } => Err(message),
+        _ => Err("无效的响应".to_string()),
+    }
+}
+
+fn request_windows(stream: &TcpStream, reader: &mut BufReader<TcpStream>) -> Result<Vec<RemoteWindow>, String> {
+    write_message(stream, &Message::List)?;
+    match read_message(reader)? {
+        Message::Windows { windows } => Ok(windows),
+        Message::Error { message } => Err(message),
+        _ => Err("无效的响应".to_string()),
+    }
+}
+
+// 客户端：接收画面直到连接断开
+fn receive(app: &AppHandle, id: u64, mut reader: BufReader<TcpStream>) {
+    let reason = loop {
+        match read_message(&mut reader) {
+            Ok(Message::Frame { data_url }) => {
+                let _ = app.emit("remote-tab-frame", RemoteFrame { id, data_url });
+            }
+            Ok(Message::Closed) => break "远程窗口已关闭".to_string(),
+            Ok(Message::Error { message }) => warn!(target: "embed", "远程标签 {}: {}", id, message),
+            Ok(_) => {}
+            Err(e) => break e,
+        }
+    };
+    // 用户关闭时已经从列表中移除
+    let reason = remove(id).map(|(tab, _)| {
+        info!(target: "embed", "远程标签 {} 已断开: {} ({})", id, tab.address, reason);
+        reason
+    });
+    let _ = app.emit("remote-tab-closed", RemoteTabClosed { id, reason });
+}
+
+// 地址可以不带端口，使用默认端口
+fn socket_addr(address: &str) -> Result<SocketAddr, String> {
+    let address = address.trim();
+    address
+        .to_socket_addrs()
+        .or_else(|_| (address, DEFAULT_PORT).to_socket_addrs())
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| format!("无效的地址: {}", address))
+}
+
+// 服务端：接受连接直到停止监听
+fn serve(port: u16, generation: u64) {
+    let listener = match TcpListener::bind(("0.0.0.0", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!(target: "embed", "远程标签: 无法监听端口 {}: {}", port, e);
+            let mut listening = LISTENING.lock().unwrap();
+            if GENERATION.load(Ordering::SeqCst) == generation {
+                *listening = None;
+            }
+            return;
+        }
+    };
+    // 非阻塞，定期检查是否已经停止
+    let _ = listener.set_nonblocking(true);
+    info!(target: "embed", "远程标签: 在端口 {} 上监听", port);
+    while GENERATION.load(Ordering::SeqCst) == generation {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                if blocked(peer.ip()) {
+                    debug!(target: "embed", "远程标签: {} 认证失败次数过多，暂时拒绝", peer);
+                    continue;
+                }
+                let _ = stream.set_nonblocking(false);
+                let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+                {
+                    // 认证通过的连接数在认证后检查，这里只限制握手中的
+                    let mut sessions = SESSIONS.lock().unwrap();
+                    if sessions.iter().filter(|(_, _, authenticated)| !authenticated).count() >= MAX_PENDING {
+                        warn!(target: "embed", "远程标签: 等待认证的连接过多，拒绝 {}", peer);
+                        continue;
+                    }
+                    let Ok(clone) = stream.try_clone() else { continue };
+                    sessions.push((id, clone, false));
+                }
+                std::thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, peer.ip(), id) {
+                        debug!(target: "embed", "远程标签: {} 断开: {}", peer, e);
+                    }
+                    SESSIONS.lock().unwrap().retain(|(session, _, _)| *session != id);
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+            Err(e) => {
+                warn!(target: "embed", "远程标签: 接受连接失败: {}", e);
+                std::thread::sleep(ACCEPT_POLL);
+            }
+        }
+    }
+}
+
+// 服务端：认证后处理客户端的请求
+fn handle_connection(stream: TcpStream, peer: IpAddr, id: u64) -> Result<(), String> {
+    let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+    // 客户端不读取时发送会阻塞 (且持有 writer 的锁)，超时后断开
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    let writer = Arc::new(Mutex::new(stream.try_clone().map_err(|e| e.to_string())?));
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let send = |message: &Message| write_message(&writer.lock().unwrap(), message);
+
+    let nonce = nonce()?;
+    send(&Message::Challenge { nonce: nonce.clone() })?;
+    let key = crate::settings::get().remote.key;
+    // 超时不回复和无效的消息同样算认证失败，不能无代价地占着握手名额
+    let token_proof = match read_message(&mut reader) {
+        Ok(Message::Auth { proof: received, token_proof }) if key.chars().count() >= MIN_KEY_LEN && constant_eq(&received, &proof(&nonce, &key)) => {
+            token_proof
+        }
+        _ => {
+            auth_failed(peer);
+            let _ = send(&Message::Error { message: "连接密钥错误".to_string() });
+            return Err("认证失败".to_string());
+        }
+    };
+    let Some(received) = token_proof else {
+        let _ = send(&Message::Error { message: "需要令牌 (在对方用 create_api_client 生成)".to_string() });
+        return Err("没有令牌".to_string());
+    };
+    let Some(client) = permissions::authenticate(|hash| constant_eq(&proof(&nonce, hash), &received)) else {
+        auth_failed(peer);
+        let _ = send(&Message::Error { message: "令牌无效或已作废".to_string() });
+        return Err("令牌无效".to_string());
+    };
+    FAILURES.lock().unwrap().retain(|(ip, _, _)| *ip != peer);
+    if !admit(&mut SESSIONS.lock().unwrap(), id) {
+        let _ = send(&Message::Error { message: "对方的远程连接过多".to_string() });
+        return Err("连接过多".to_string());
+    }
+    let _ = stream.set_read_timeout(None);
+    send(&Message::Ready)?;
+
+    // 正在查看的窗口，0 为没有；连接断开时清零，画面线程随之结束
+    let viewing = Arc::new(AtomicIsize::new(0));
+    let result = serve_requests(&mut reader, &writer, &viewing, client.id);
+    viewing.store(0, Ordering::SeqCst);
+    result
+}
+
+fn serve_requests(reader: &mut BufReader<TcpStream>, writer: &Arc<Mutex<TcpStream>>, viewing: &Arc<AtomicIsize>, client: u64) -> Result<(), String> {
+    let send = |message: &Message| write_message(&writer.lock().unwrap(), message);
+    let mut slot = None;
+    loop {
+        let message = read_message(reader)?;
+        let capability = match &message {
+            Message::List => Capability::Enumerate,
+            Message::View { .. } => Capability::View,
+            Message::Input { .. } => Capability::Input,
+            _ => return Err("无效的请求".to_string()),
+        };
+        // 每次请求重新读取令牌的权限，作废后立即生效
+        if let Err(e) = permissions::check(client, capability) {
+            send(&Message::Error { message: e })?;
+            continue;
+        }
+        match message {
+            Message::List => send(&Message::Windows { windows: windows() })?,
+            Message::View { hwnd, max_size } => {
+                // 每个连接同时只推送一个窗口，第一次查看时占用一个截图流名额，连接断开时归还
+                if slot.is_none() {
+                    match crate::budget::acquire_stream() {
+                        Ok(acquired) => slot = Some(acquired),
+                        Err(e) => {
+                            send(&Message::Error { message: e.into() })?;
+                            continue;
+                        }
+                    }
+                }
+                if !crate::platform::current().is_window(hwnd) || !is_tab(hwnd) {
+                    send(&Message::Error { message: "标签不存在".to_string() })?;
+                } else if viewing.swap(hwnd, Ordering::SeqCst) != hwnd {
+                    let (writer, viewing) = (writer.clone(), viewing.clone());
+                    std::thread::spawn(move || stream_frames(&writer, &viewing, hwnd, max_size.clamp(160, 1920)));
+                }
+            }
+            Message::Input { input } => {
+                let hwnd = viewing.load(Ordering::SeqCst);
+                if hwnd != 0 {
+                    if let Err(e) = forward_input(hwnd, &input) {
+                        send(&Message::Error { message: e })?;
+                    }
+                }
+            }
+            _ => return Err("无效的请求".to_string()),
+        }
+    }
+}
+
+// 服务端：认证通过后占用连接名额，已满时返回 false
+fn admit<T>(sessions: &mut [(u64, T, bool)], id: u64) -> bool {
+    if sessions.iter().filter(|(_, _, authenticated)| *authenticated).count() >= MAX_CONNECTIONS {
+        return false;
+    }
+    if let Some((_, _, authenticated)) = sessions.iter_mut().find(|(session, _, _)| *session == id) {
+        *authenticated = true;
+    }
+    true
+}
+
+// 服务端：这个地址是否还在认证失败后的拒绝期内
+fn blocked(ip: IpAddr) -> bool {
+    let now = Instant::now();
+    FAILURES.lock().unwrap().iter().any(|(failed, _, until)| *failed == ip && *until > now)
+}
+
+// 服务端：记录认证失败，拒绝期按连续失败次数加倍；清掉早已过期的记录
+fn auth_failed(ip: IpAddr) {
+    let now = Instant::now();
+    let mut failures = FAILURES.lock().unwrap();
+    failures.retain(|(_, _, until)| *until + MAX_BACKOFF > now);
+    let count = failures.iter().find(|(failed, _, _)| *failed == ip).map_or(0, |(_, count, _)| *count) + 1;
+    failures.retain(|(failed, _, _)| *failed != ip);
+    failures.push((ip, count, now + backoff(count)));
+    warn!(target: "embed", "远程标签: {} 认证失败 ({} 次)", ip, count);
+}
+
+// 连续失败 n 次后的拒绝时间：1、2、4 ... 秒，最长 MAX_BACKOFF
+fn backoff(failures: u32) -> Duration {
+    Duration::from_secs(1u64 << failures.saturating_sub(1).min(16)).min(MAX_BACKOFF)
+}
+
+// 服务端：定期截取窗口画面发送给客户端，直到改为查看其他窗口或连接断开
+fn stream_frames(writer: &Mutex<TcpStream>, viewing: &AtomicIsize, hwnd: isize, max_size: i32) {
+    let platform = crate::platform::current();
+    let mut last = String::new();
+    while viewing.load(Ordering::SeqCst) == hwnd {
+        // 窗口关闭或被弹出、释放后不再是标签
+        if !platform.is_window(hwnd) || !is_tab(hwnd) {
+            let _ = write_message(&writer.lock().unwrap(), &Message::Closed);
+            let _ = viewing.compare_exchange(hwnd, 0, Ordering::SeqCst, Ordering::SeqCst);
+            return;
+        }
+        // 锁屏期间截取不到画面，暂停发送
+        let frame = if crate::session_watch::is_active() { platform.thumbnail(hwnd, max_size, max_size) } else { None };
+        if let Some(data_url) = frame.filter(|d| *d != last) {
+            if write_message(&writer.lock().unwrap(), &Message::Frame { data_url: data_url.clone() }).is_err() {
+                return;
+            }
+            last = data_url;
+        }
+        std::thread::sleep(FRAME_INTERVAL);
+    }
+}
+
+// 服务端：本机已嵌入的标签 (其他窗口不能查看)
+fn windows() -> Vec<RemoteWindow> {
+    let platform = crate::platform::current();
+    let tabs = APP.get().map(|app| app.state::<WindowManager>().list()).unwrap_or_default();
+    tabs.into_iter().map(|w| RemoteWindow { hwnd: w.hwnd, title: crate::privacy::title(platform.title(w.hwnd)), embedded: true }).collect()
+}
+
+// 服务端：窗口是否是本机已嵌入的标签
+fn is_tab(hwnd: isize) -> bool {
+    APP.get().is_some_and(|app| app.state::<WindowManager>().get(hwnd).is_some())
+}
+
+// 服务端：激活窗口后注入输入
+#[cfg(windows)]
+fn forward_input(hwnd: isize, input: &RemoteInput) -> Result<(), String> {
+    if !crate::activate_window(hwnd, None)? {
+        return Err("窗口不存在".to_string());
+    }
+    // 等待焦点切换完成，否则输入会落到之前的窗口
+    std::thread::sleep(Duration::from_millis(50));
+    match input {
+        RemoteInput::Keys { sequence } => {
+            let steps = crate::keys::parse_sequence(sequence)?;
+            unsafe { crate::keys::send(&steps)? };
+        }
+        RemoteInput::Click { x, y, right } => unsafe {
+            let mut rect = RECT::default();
+            GetWindowRect(HWND(hwnd as *mut _), &mut rect).map_err(|e| format!("获取窗口位置失败: {}", e))?;
+            let (px, py) = point_in(rect.into(), *x, *y);
+            SetCursorPos(px, py).map_err(|e| format!("移动光标失败: {}", e))?;
+            let (down, up) = if *right { (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP) } else { (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP) };
+            let click = |flags: MOUSE_EVENT_FLAGS| INPUT {
+                r#type: INPUT_MOUSE,
+                Anonymous: INPUT_0 { mi: MOUSEINPUT { dwFlags: flags, ..Default::default() } },
+            };
+            if SendInput(&[click(down), click(up)], std::mem::size_of::<INPUT>() as i32) != 2 {
+                return Err("点击被拦截".to_string());
+            }
+        },
+    }
+    debug!(target: "embed", "远程标签: 转发输入到 hwnd={}", hwnd);
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn forward_input(_hwnd: isize, _input: &RemoteInput) -> Result<(), String> {
+    Err("仅支持 Windows".to_string())
+}
+
+// 画面中的相对位置 -> 屏幕坐标
+#[cfg_attr(not(windows), allow(dead_code))]
+fn point_in(rect: WindowRect, x: f64, y: f64) -> (i32, i32) {
+    let offset = |size: i32, ratio: f64| ((size - 1).max(0) as f64 * ratio.clamp(0.0, 1.0)).round() as i32;
+    (rect.left + offset(rect.width(), x), rect.top + offset(rect.height(), y))
+}
+
+fn write_message(mut stream: &TcpStream, message: &Message) -> Result<(), String> {
+    let mut line = serde_json::to_string(message).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).map_err(|e| format!("发送失败: {}", e))
+}
+
+fn read_message(reader: &mut BufReader<TcpStream>) -> Result<Message, String> {
+    let mut line = String::new();
+    let read = reader.by_ref().take(MAX_MESSAGE).read_line(&mut line).map_err(|e| format!("接收失败: {}", e))?;
+    if read == 0 {
+        return Err("连接已断开".to_string());
+    }
+    if !line.ends_with('\n') {
+        return Err("消息过长".to_string());
+    }
+    serde_json::from_str(&line).map_err(|e| format!("无效的消息: {}", e))
+}
+
+// 认证的随机数：32 字节的系统安全随机数
+pub(crate) fn nonce() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("无法获取随机数: {}", e))?;
+    Ok(hex(&bytes))
+}
+
+pub(crate) fn proof(nonce: &str, key: &str) -> String {
+    hex(&hmac_sha256(key.as_bytes(), nonce.as_bytes()))
+}
+
+// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.map(|b| b ^ byte);
+    let inner = Sha256::new().chain_update(pad(0x36)).chain_update(message).finalize();
+    Sha256::new().chain_update(pad(0x5c)).chain_update(inner).finalize().into()
+}
+
+// 比较时间与内容无关，不能从响应时间逐字节猜出 proof
+pub(crate) fn constant_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_tagged_json_lines() {
+        let input = Message::Input { input: RemoteInput::Click { x: 0.5, y: 0.25, right: false } };
+        let json = serde_json::to_string(&input).unwrap();
+        assert_eq!(json, r#"{"type":"input","input":{"kind":"click","x":0.5,"y":0.25,"right":false}}"#);
+        assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), input);
+        assert_eq!(serde_json::from_str::<Message>(r#"{"type":"ready"}"#).unwrap(), Message::Ready);
+
+        let nonce = nonce().unwrap();
+        assert_eq!(nonce.len(), 64);
+        assert_ne!(nonce, super::nonce().unwrap());
+        assert!(constant_eq(&proof(&nonce, "secret-key"), &proof(&nonce, "secret-key")));
+        assert!(!constant_eq(&proof(&nonce, "secret-key"), &proof(&nonce, "secret-kez")));
+    }
+
+    #[test]
+    fn proof_is_hmac_sha256() {
+        // RFC 4231 测试用例 2
+        assert_eq!(proof("what do ya want for nothing?", "Jefe"), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        assert!(!constant_eq("abc", "abd"));
+        assert!(!constant_eq("abc", "abcd"));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_limit() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn old_clients_parse_without_token() {
+        // 旧版本的客户端不发送令牌，能解析出消息才能回复需要令牌的错误
+        let old_client = serde_json::from_str::<Message>(r#"{"type":"auth","proof":"abc"}"#).unwrap();
+        assert_eq!(old_client, Message::Auth { proof: "abc".to_string(), token_proof: None });
+    }
+
+    #[test]
+    fn only_authenticated_connections_take_a_slot() {
+        // 等待认证的连接再多也不影响认证通过的连接
+        let mut sessions: Vec<(u64, (), bool)> = (1..=10).map(|id| (id, (), false)).collect();
+        for id in 1..=MAX_CONNECTIONS as u64 {
+            assert!(admit(&mut sessions, id));
+        }
+        assert!(!admit(&mut sessions, 9));
+        assert!(!sessions[8].2);
+        sessions.retain(|(id, _, _)| *id != 1);
+        assert!(admit(&mut sessions, 9));
+    }
+
+    #[test]
+    fn relative_points_map_into_window() {
+        let rect = WindowRect { left: 100, top: 50, right: 301, bottom: 151 };
+        assert_eq!(point_in(rect, 0.0, 0.0), (100, 50));
+        assert_eq!(point_in(rect, 0.5, 1.0), (200, 150));
+        assert_eq!(point_in(rect, 2.0, -1.0), (300, 50));
+    }
+}
@@ -0,0 +1,119 @@
+// 嵌入窗口的尺寸调整调度
+// 拖动调整主窗口大小时前端会连续调用 update_window_rect，每次都同步 SetWindowPos 会让目标应用忙于重绘、拖慢主线程
+// 命令只把目标位置交给调度线程，调度线程按帧 (约 60Hz) 合并，每帧每个窗口只应用最后一次的位置
+// 开启 settings.resize.fast_path 时调整过程中跳过重绘，SETTLE 内没有新的位置时再统一重绘
+// 应用最终位置后结束窗口的过渡 (见 transition.rs)
+
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::platform::{self, Platform};
+use crate::transition;
+
+const FRAME: Duration = Duration::from_millis(16);
+// 超过这个时间没有新位置视为调整结束
+const SETTLE: Duration = Duration::from_millis(150);
+
+#[derive(Debug, Clone, Copy)]
+struct Target {
+    hwnd: isize,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+static SENDER: OnceLock<Sender<Target>> = OnceLock::new();
+
+// 已发送、调度线程还没取出的位置数
+static PENDING: AtomicUsize = AtomicUsize::new(0);
+
+/// 设置嵌入窗口的位置和大小 (下一帧生效)
+pub fn schedule(hwnd: isize, x: i32, y: i32, width: i32, height: i32) {
+    let sender = SENDER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || run(rx));
+        tx
+    });
+    PENDING.fetch_add(1, Ordering::Relaxed);
+    if sender.send(Target { hwnd, x, y, width, height }).is_err() {
+        // 调度线程已退出，直接应用
+        PENDING.fetch_sub(1, Ordering::Relaxed);
+        warn!(target: "embed", "尺寸调整线程已退出");
+        platform::current().set_rect(hwnd, x, y, width, height);
+    }
+}
+
+/// 积压的位置数
+pub fn pending() -> usize {
+    PENDING.load(Ordering::Relaxed)
+}
+
+fn run(rx: Receiver<Target>) {
+    let platform = platform::current();
+    // 以快速方式应用过、还没有重绘的窗口
+    let mut unsettled: BTreeMap<isize, Target> = BTreeMap::new();
+    loop {
+        let first = if unsettled.is_empty() {
+            match rx.recv() {
+                Ok(target) => {
+                    PENDING.fetch_sub(1, Ordering::Relaxed);
+                    target
+                }
+                Err(_) => return,
+            }
+        } else {
+            match rx.recv_timeout(SETTLE) {
+                Ok(target) => {
+                    PENDING.fetch_sub(1, Ordering::Relaxed);
+                    target
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    settle(platform, std::mem::take(&mut unsettled));
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        };
+
+        // 收集这一帧内的请求，同一窗口只保留最后一次
+        let mut frame = BTreeMap::new();
+        frame.insert(first.hwnd, first);
+        let deadline = Instant::now() + FRAME;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match rx.recv_timeout(remaining) {
+                Ok(target) => {
+                    PENDING.fetch_sub(1, Ordering::Relaxed);
+                    frame.insert(target.hwnd, target);
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        let fast_path = crate::settings::get().resize.fast_path;
+        for target in frame.into_values() {
+            if fast_path {
+                if platform.set_rect_fast(target.hwnd, target.x, target.y, target.width, target.height) {
+                    unsettled.insert(target.hwnd, target);
+                }
+            } else {
+                platform.set_rect(target.hwnd, target.x, target.y, target.width, target.height);
+                transition::end(platform, target.hwnd);
+            }
+        }
+    }
+}
+
+// 调整结束：按完整参数应用最终位置并重绘
+fn settle(platform: &dyn Platform, targets: BTreeMap<isize, Target>) {
+    for target in targets.into_values() {
+        platform.set_rect(target.hwnd, target.x, target.y, target.width, target.height);
+        platform.repaint(target.hwnd);
+        transition::end(platform, target.hwnd);
+    }
+}
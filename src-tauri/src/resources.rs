@@ -0,0 +1,201 @@
+// 钩子和定时器登记
+// 后台创建的每个 WinEvent 钩子、低级鼠标/键盘钩子和定时器都在这里登记 (创建时 register，销毁时 unregister)，
+// list_active_resources 列出当前存在的，用来排查标签反复打开关闭后钩子越积越多的问题。
+// 目前没有 DWM 缩略图注册 (切换器的缩略图是截图)，以后添加时同样在这里登记。
+// 清理：
+// - 标签释放/关闭时 (release_tab)，与该标签关联 (登记时指定 tab) 但仍然登记着的资源视为遗留，
+//   记录警告后注销，关联窗口的发送 WM_CLOSE，属于后台线程的让线程退出消息循环；
+//   各模块仍应在标签释放时自己销毁 (如缩放的覆盖窗口)，这里兜底，忘记清理的模块会在日志中出现
+// - 程序退出时 (release_all) 对所有资源同样处理 (各线程退出消息循环后自行卸载钩子、注销登记)，主线程上的随进程结束
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tracing::warn;
+
+#[cfg(windows)]
+use std::sync::atomic::AtomicU32;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, WPARAM},
+    System::Threading::GetCurrentThreadId,
+    UI::WindowsAndMessaging::{PostMessageW, PostThreadMessageW, WM_CLOSE, WM_QUIT},
+};
+
+static RESOURCES: Mutex<Vec<Resource>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[cfg(windows)]
+static MAIN_THREAD: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum ResourceKind {
+    WinEventHook,
+    LowLevelHook,
+    Timer,
+}
+
+impl ResourceKind {
+    pub fn is_hook(self) -> bool {
+        matches!(self, ResourceKind::WinEventHook | ResourceKind::LowLevelHook)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Resource {
+    pub id: u64,
+    pub kind: ResourceKind,
+    pub owner: &'static str, // 创建它的模块
+    pub count: u32,          // 一次安装的一组钩子登记为一条
+    pub thread: u32,
+    pub tab: Option<isize>,    // 关联的标签
+    pub window: Option<isize>, // 关联的窗口 (窗口定时器)
+    pub created_at: u64,       // Unix 秒
+}
+
+/// 记录主线程 (setup 中调用)
+pub fn init() {
+    #[cfg(windows)]
+    MAIN_THREAD.store(unsafe { GetCurrentThreadId() }, Ordering::SeqCst);
+}
+
+/// 登记资源 (在创建它的线程上调用)，返回登记编号
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn register(kind: ResourceKind, owner: &'static str, count: u32, tab: Option<isize>, window: Option<isize>) -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let resource = Resource { id, kind, owner, count, thread: current_thread(), tab, window, created_at: crate::clock::now_secs() };
+    RESOURCES.lock().unwrap().push(resource);
+    id
+}
+
+/// 注销登记
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn unregister(id: u64) {
+    RESOURCES.lock().unwrap().retain(|r| r.id != id);
+}
+
+/// 窗口销毁时注销与它关联的资源
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn unregister_window(window: isize) {
+    RESOURCES.lock().unwrap().retain(|r| r.window != Some(window));
+}
+
+/// 当前登记的资源 (按创建顺序)
+pub fn list() -> Vec<Resource> {
+    RESOURCES.lock().unwrap().clone()
+}
+
+/// 标签已释放或关闭：注销并销毁仍与它关联的资源
+pub fn release_tab(tab: isize) {
+    let leftover = take_tab(&mut RESOURCES.lock().unwrap(), tab);
+    if leftover.is_empty() {
+        return;
+    }
+    let owners: Vec<&str> = leftover.iter().map(|r| r.owner).collect();
+    warn!(target: "embed", "标签释放后仍有 {} 个资源未清理，强制清理: hwnd={}, {:?}", leftover.len(), tab, owners);
+    cleanup(&leftover);
+}
+
+// 从登记中取出与标签关联的资源
+fn take_tab(resources: &mut Vec<Resource>, tab: isize) -> Vec<Resource> {
+    let (taken, kept) = std::mem::take(resources).into_iter().partition(|r| r.tab == Some(tab));
+    *resources = kept;
+    taken
+}
+
+/// 程序退出：让持有资源的后台线程退出
+pub fn release_all() {
+    let resources = list();
+    cleanup(&resources);
+}
+
+fn cleanup(resources: &[Resource]) {
+    let (windows, threads) = cleanup_targets(resources, main_thread());
+    #[cfg(windows)]
+    unsafe {
+        for window in windows {
+            let _ = PostMessageW(HWND(window as *mut _), WM_CLOSE, WPARAM(0), LPARAM(0));
+        }
+        for thread in threads {
+            let _ = PostThreadMessageW(thread, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = (windows, threads);
+}
+
+// 要关闭的窗口和要退出的后台线程 (每个只处理一次，主线程不处理)
+fn cleanup_targets(resources: &[Resource], main_thread: u32) -> (Vec<isize>, Vec<u32>) {
+    let mut windows = Vec::new();
+    let mut threads = Vec::new();
+    for resource in resources {
+        match resource.window {
+            Some(window) => {
+                if !windows.contains(&window) {
+                    windows.push(window);
+                }
+            }
+            None => {
+                if resource.thread != 0 && resource.thread != main_thread && !threads.contains(&resource.thread) {
+                    threads.push(resource.thread);
+                }
+            }
+        }
+    }
+    (windows, threads)
+}
+
+#[cfg(windows)]
+fn current_thread() -> u32 {
+    unsafe { GetCurrentThreadId() }
+}
+
+#[cfg(not(windows))]
+fn current_thread() -> u32 {
+    0
+}
+
+#[cfg(windows)]
+fn main_thread() -> u32 {
+    MAIN_THREAD.load(Ordering::SeqCst)
+}
+
+#[cfg(not(windows))]
+fn main_thread() -> u32 {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(kind: ResourceKind, thread: u32, window: Option<isize>) -> Resource {
+        Resource { id: 0, kind, owner: "test", count: 1, thread, tab: None, window, created_at: 0 }
+    }
+
+    #[test]
+    fn releasing_a_tab_takes_only_its_resources() {
+        let mut resources = vec![resource(ResourceKind::Timer, 9, Some(100)), resource(ResourceKind::LowLevelHook, 7, None)];
+        resources[0].tab = Some(42);
+        let taken = take_tab(&mut resources, 42);
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].window, Some(100));
+        assert_eq!(resources.len(), 1);
+        assert!(take_tab(&mut resources, 42).is_empty());
+    }
+
+    #[test]
+    fn cleans_up_each_window_and_background_thread_once() {
+        let resources = [
+            resource(ResourceKind::LowLevelHook, 7, None),
+            resource(ResourceKind::Timer, 7, None),
+            resource(ResourceKind::Timer, 1, None),
+            resource(ResourceKind::Timer, 9, Some(100)),
+            resource(ResourceKind::Timer, 9, Some(100)),
+        ];
+        assert_eq!(cleanup_targets(&resources, 1), (vec![100], vec![7]));
+    }
+}
@@ -0,0 +1,469 @@
+// 窗口规则
+// 新出现的顶层窗口按 %APPDATA%\WindowHub\rules.json 中的规则处理，例如：
+//   { "name": "日志", "when": "class == \"Notepad\" and title contains \"*.log\"",
+//     "actions": [{ "type": "embed" }, { "type": "group", "group": "logs" }, { "type": "mute" }] }
+// 条件：字段 class / title / exe (程序文件名) / path (完整路径)，
+// 运算 == / != / contains / matches (* 通配，与标签名称的标题模式相同，contains 匹配任意位置)，不区分大小写，
+// 用 and / or / not 和括号组合
+// 操作：embed 嵌入，group / color / label 设置标签 (只对嵌入的窗口有效)，mute 静音该程序的声音
+// 新窗口由 app_watch 的轮询线程发现，多条规则匹配时按文件中的顺序执行全部操作
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::platform::Platform;
+use crate::tab_labels::TabLabelChanged;
+use crate::window_manager::{TabColor, TabTags, WindowManager};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    pub when: String,
+    #[serde(default)]
+    pub actions: Vec<RuleAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    Embed,
+    Group { group: String },
+    Color { color: TabColor },
+    Label { label: String },
+    Mute,
+}
+
+/// 条件中使用的窗口属性
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowFacts {
+    pub class: String,
+    pub title: String,
+    pub exe: String,
+    pub path: String,
+}
+
+impl WindowFacts {
+    pub fn of(platform: &dyn Platform, hwnd: isize) -> Self {
+        let path = platform.process_path(hwnd).unwrap_or_default();
+        let exe = std::path::Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        WindowFacts { class: platform.class_name(hwnd), title: platform.title(hwnd), exe, path }
+    }
+
+    fn field(&self, field: Field) -> &str {
+        match field {
+            Field::Class => &self.class,
+            Field::Title => &self.title,
+            Field::Exe => &self.exe,
+            Field::Path => &self.path,
+        }
+    }
+}
+
+/// test_rule 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleTest {
+    pub matched: bool,
+    pub window: WindowFacts,
+}
+
+static RULES: Mutex<Vec<Rule>> = Mutex::new(Vec::new());
+
+pub fn has_rules() -> bool {
+    !RULES.lock().unwrap().is_empty()
+}
+
+pub fn list() -> Vec<Rule> {
+    RULES.lock().unwrap().clone()
+}
+
+/// 替换所有规则 (导入配置时)，返回是否有变化
+pub fn replace(rules: Vec<Rule>) -> bool {
+    let mut current = RULES.lock().unwrap();
+    if *current == rules {
+        return false;
+    }
+    *current = rules;
+    save_to_file(&current);
+    true
+}
+
+/// 检查每条规则的条件语法 (导入配置时)
+pub fn validate(rules: &[Rule]) -> Result<(), String> {
+    for rule in rules {
+        Condition::parse(&rule.when).map_err(|e| format!("规则 {} 的条件无效: {}", rule.name, e))?;
+    }
+    Ok(())
+}
+
+/// 用窗口测试一条规则 (条件有语法错误时返回错误)
+pub fn test(platform: &dyn Platform, rule: &Rule, hwnd: isize) -> Result<RuleTest, String> {
+    if !platform.is_window(hwnd) {
+        return Err("无效的窗口句柄".to_string());
+    }
+    let condition = Condition::parse(&rule.when)?;
+    let window = WindowFacts::of(platform, hwnd);
+    Ok(RuleTest { matched: condition.eval(&window), window })
+}
+
+/// 匹配窗口的规则名称 (条件有错误的规则跳过)
+pub fn matched(platform: &dyn Platform, hwnd: isize) -> Vec<String> {
+    let window = WindowFacts::of(platform, hwnd);
+    matching_rules(&window).into_iter().map(|r| r.name).collect()
+}
+
+fn matching_rules(window: &WindowFacts) -> Vec<Rule> {
+    let rules = RULES.lock().unwrap().clone();
+    rules
+        .into_iter()
+        .filter(|rule| match Condition::parse(&rule.when) {
+            Ok(condition) => condition.eval(window),
+            Err(e) => {
+                warn!(target: "embed", "规则 {} 的条件无效: {}", rule.name, e);
+                false
+            }
+        })
+        .collect()
+}
+
+/// 新窗口是否会被规则嵌入 (后台代理据此决定要不要交给主界面)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn wants_embed(platform: &dyn Platform, hwnd: isize) -> bool {
+    let window = WindowFacts::of(platform, hwnd);
+    matching_rules(&window).iter().any(|rule| rule.actions.contains(&RuleAction::Embed))
+}
+
+/// 对新窗口执行匹配的规则，返回窗口是否被规则嵌入
+pub fn apply(app: &AppHandle, platform: &dyn Platform, hwnd: isize) -> bool {
+    let window = WindowFacts::of(platform, hwnd);
+    let rules = matching_rules(&window);
+    if rules.is_empty() {
+        return false;
+    }
+    let names: Vec<&str> = rules.iter().map(|r| r.name.as_str()).collect();
+    info!(target: "embed", "窗口匹配规则: {:?} (hwnd={}, {})", names, hwnd, window.title);
+
+    let actions: Vec<RuleAction> = rules.into_iter().flat_map(|r| r.actions).collect();
+    let manager = app.state::<WindowManager>();
+    let embedded = actions.contains(&RuleAction::Embed) && crate::embed_tab(app, &manager, hwnd).is_ok();
+    for action in actions {
+        match action {
+            RuleAction::Embed => {}
+            RuleAction::Group { group } => {
+                if let Some(window) = manager.set_group(hwnd, Some(group)) {
+                    let _ = app.emit("tab-tags-changed", TabTags::from(&window));
+                }
+            }
+            RuleAction::Color { color } => {
+                if let Some(window) = manager.set_color(hwnd, Some(color)) {
+                    let _ = app.emit("tab-tags-changed", TabTags::from(&window));
+                }
+            }
+            RuleAction::Label { label } => {
+                if manager.set_label(hwnd, Some(label.clone())).is_some() {
+                    let _ = app.emit("tab-label-changed", TabLabelChanged { hwnd, label: Some(label) });
+                }
+            }
+            RuleAction::Mute => mute(platform, &window.path),
+        }
+    }
+    embedded
+}
+
+// 应用第一次发声时才会创建音频会话，窗口刚出现时可能还没有
+fn mute(platform: &dyn Platform, exe_path: &str) {
+    for session in platform.audio_sessions() {
+        if !session.muted && session.exe_path.eq_ignore_ascii_case(exe_path) {
+            platform.set_audio_session_muted(&session.id, true);
+        }
+    }
+}
+
+// ============================================================
+// 条件表达式
+// ============================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Class,
+    Title,
+    Exe,
+    Path,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Contains,
+    Matches,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Compare(Field, Op, String),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Eq,
+    Ne,
+    Open,
+    Close,
+}
+
+impl Condition {
+    fn parse(text: &str) -> Result<Condition, String> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let condition = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(condition),
+            Some(token) => Err(format!("多余的内容: {:?}", token)),
+        }
+    }
+
+    fn eval(&self, window: &WindowFacts) -> bool {
+        match self {
+            Condition::Compare(field, op, value) => {
+                let text = window.field(*field);
+                match op {
+                    Op::Eq => text.to_lowercase() == value.to_lowercase(),
+                    Op::Ne => text.to_lowercase() != value.to_lowercase(),
+                    Op::Contains => glob_match(&format!("*{}*", value), text),
+                    Op::Matches => glob_match(value, text),
+                }
+            }
+            Condition::Not(inner) => !inner.eval(window),
+            Condition::And(a, b) => a.eval(window) && b.eval(window),
+            Condition::Or(a, b) => a.eval(window) || b.eval(window),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '=' | '!' => {
+                chars.next();
+                if chars.next() != Some('=') {
+                    return Err(format!("无效的运算符: {}", c));
+                }
+                tokens.push(if c == '=' { Token::Eq } else { Token::Ne });
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => value.extend(chars.next()),
+                        Some(c) => value.push(c),
+                        None => return Err("字符串缺少结束的引号".to_string()),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word.to_lowercase()));
+            }
+            c => return Err(format!("无效的字符: {}", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.tokens.get(self.pos), Some(Token::Word(w)) if w == keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Condition, String> {
+        let mut left = self.and()?;
+        while self.keyword("or") {
+            left = Condition::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Condition, String> {
+        let mut left = self.unary()?;
+        while self.keyword("and") {
+            left = Condition::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Condition, String> {
+        if self.keyword("not") {
+            return Ok(Condition::Not(Box::new(self.unary()?)));
+        }
+        match self.next() {
+            Some(Token::Open) => {
+                let inner = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("缺少右括号".to_string()),
+                }
+            }
+            Some(Token::Word(word)) => {
+                let field = match word.as_str() {
+                    "class" => Field::Class,
+                    "title" => Field::Title,
+                    "exe" => Field::Exe,
+                    "path" => Field::Path,
+                    _ => return Err(format!("未知的字段: {} (可选 class/title/exe/path)", word)),
+                };
+                let op = match self.next() {
+                    Some(Token::Eq) => Op::Eq,
+                    Some(Token::Ne) => Op::Ne,
+                    Some(Token::Word(w)) if w == "contains" => Op::Contains,
+                    Some(Token::Word(w)) if w == "matches" => Op::Matches,
+                    _ => return Err(format!("{} 后缺少运算符 (==、!=、contains、matches)", word)),
+                };
+                match self.next() {
+                    Some(Token::Str(value)) => Ok(Condition::Compare(field, op, value)),
+                    _ => Err(format!("{} 的比较值需要用引号括起来", word)),
+                }
+            }
+            Some(token) => Err(format!("意外的内容: {:?}", token)),
+            None => Err("条件不完整".to_string()),
+        }
+    }
+}
+
+// 通配符匹配 (* 任意个字符)，不区分大小写
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    crate::tab_labels::matches_pattern(&pattern.to_lowercase(), &text.to_lowercase())
+}
+
+fn save_to_file(rules: &[Rule]) {
+    // 安全模式下没有加载原来的文件，修改只在本次运行中有效
+    if crate::safe_mode::is_enabled() {
+        return;
+    }
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("rules.json");
+
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        if let Ok(json) = serde_json::to_string_pretty(rules) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+/// 从文件加载，返回内容是否有变化
+pub fn load_from_file() -> bool {
+    if let Some(config_dir) = crate::paths::data_dir() {
+        let path = config_dir.join("rules.json");
+
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            match serde_json::from_str::<Vec<Rule>>(&content) {
+                Ok(loaded) => {
+                    let mut rules = RULES.lock().unwrap();
+                    if *rules == loaded {
+                        return false;
+                    }
+                    info!(target: "settings", "已加载 {} 条窗口规则", loaded.len());
+                    *rules = loaded;
+                    return true;
+                }
+                Err(e) => warn!(target: "settings", "规则文件无效，保留当前规则: {}", e),
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notepad(title: &str) -> WindowFacts {
+        WindowFacts {
+            class: "Notepad".to_string(),
+            title: title.to_string(),
+            exe: "notepad.exe".to_string(),
+            path: r"C:\Windows\notepad.exe".to_string(),
+        }
+    }
+
+    #[test]
+    fn evaluates_conditions() {
+        let log = Condition::parse(r#"class == "notepad" and title contains "*.log""#).unwrap();
+        assert!(log.eval(&notepad("server.log - 记事本")));
+        assert!(!log.eval(&notepad("readme.txt - 记事本")));
+
+        let either = Condition::parse(r#"not (exe == "code.exe" or path matches "C:\\Program Files\\*") and title != """#).unwrap();
+        assert!(either.eval(&notepad("a")));
+        assert!(!either.eval(&notepad("")));
+
+        assert!(Condition::parse(r#"class = "Notepad""#).is_err());
+        assert!(Condition::parse(r#"owner == "x""#).is_err());
+        assert!(Condition::parse(r#"(title contains "x""#).is_err());
+        assert!(Condition::parse(r#"title contains x"#).is_err());
+
+        assert!(glob_match("*.LOG", "app.log"));
+        assert!(glob_match("A*C*", "abcdef"));
+        assert!(!glob_match("*.log", "app.log.bak"));
+        assert!(glob_match("**", ""));
+    }
+
+    #[test]
+    fn actions_deserialize_from_tagged_json() {
+        let rule: Rule = serde_json::from_str(
+            r#"{"name":"日志","when":"exe == \"notepad.exe\"","actions":[{"type":"embed"},{"type":"group","group":"logs"},{"type":"color","color":"blue"},{"type":"mute"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            rule.actions,
+            vec![
+                RuleAction::Embed,
+                RuleAction::Group { group: "logs".to_string() },
+                RuleAction::Color { color: TabColor::Blue },
+                RuleAction::Mute,
+            ]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+// 安全模式启动
+// 命令行带 --safe-mode 或启动时按住 Shift 进入，错误的规则或配置导致启动即嵌入、崩溃时用来恢复：
+// - 使用默认设置 (不读取 settings.json，因此也不恢复上次的工作区、不注册自定义快捷键)，不允许修改设置，
+//   避免默认设置覆盖掉原来的配置
+// - 不加载窗口规则 (rules.json) 和自动嵌入的监视列表 (watched_apps.json)，对它们的修改也不保存；
+//   不接管后台代理发现的窗口
+// - 不运行脚本的生命周期钩子，不检查更新，不改动后台代理的开机启动登记
+// 配置文件被修改时也不重新加载上面这些文件；正常启动后一切恢复
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::warn;
+
+#[cfg(windows)]
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_SHIFT};
+
+/// 以安全模式启动的命令行参数
+pub const SAFE_MODE_ARG: &str = "--safe-mode";
+
+/// 安全模式下不加载、也不重新加载的配置文件
+pub const SKIPPED_FILES: [&str; 3] = ["settings.json", "watched_apps.json", "rules.json"];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 启动时判断是否进入安全模式 (在加载任何配置之前调用一次)
+pub fn detect() -> bool {
+    let enabled = requested(std::env::args()) || shift_held();
+    if enabled {
+        warn!(target: "settings", "以安全模式启动：使用默认设置，不加载窗口规则和监视列表");
+    }
+    ENABLED.store(enabled, Ordering::SeqCst);
+    enabled
+}
+
+/// 当前是否处于安全模式
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+fn requested(args: impl IntoIterator<Item = String>) -> bool {
+    args.into_iter().skip(1).any(|arg| arg == SAFE_MODE_ARG)
+}
+
+#[cfg(windows)]
+fn shift_held() -> bool {
+    unsafe { GetAsyncKeyState(VK_SHIFT.0 as i32) < 0 }
+}
+
+#[cfg(not(windows))]
+fn shift_held() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_the_safe_mode_argument() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(requested(args(&["WindowHub.exe", "--safe-mode"])));
+        assert!(!requested(args(&["WindowHub.exe"])));
+        assert!(!requested(args(&["WindowHub.exe", "--agent"])));
+        // 程序路径本身不算参数
+        assert!(!requested(args(&["--safe-mode"])));
+    }
+}
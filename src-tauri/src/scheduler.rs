@@ -0,0 +1,316 @@
+// 定时任务
+// 每个任务在本地时间的某个时刻 (time 为 "HH:MM") 执行一个操作，days 为星期几 (1~7 对应周一到周日，为空表示每天)，如：
+// - 工作日 09:25 恢复工作区 "standup"
+// - 每天 03:00 重启仪表盘标签
+// - 18:30 挂起所有标签 (见 tab_suspend.rs)、执行脚本或某个快捷键操作 (如老板键)
+// 任务保存在数据库的 schedules 文档中，每 CHECK_INTERVAL 检查一次时间，执行上次检查以来经过的每一分钟的任务
+// (检查线程被推迟时不会漏掉)，电脑休眠等超过 MAX_CATCH_UP_MINUTES 的间隔中错过的任务不补执行。
+// 执行时发送 schedule-fired (载荷为 ScheduleFired)；
+// 恢复工作区与 restore_workspace 相同，并在后台依次启动工作区中还没有打开的应用、嵌入为标签
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::window_manager::WindowManager;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+// 最多补执行这么多分钟内错过的任务
+const MAX_CATCH_UP_MINUTES: u64 = 5;
+
+const DOCUMENT: &str = "schedules";
+
+static SCHEDULES: Mutex<Vec<Schedule>> = Mutex::new(Vec::new());
+
+// 上次检查时的分钟 (本地时间自 1970 年起的分钟数)，每一分钟只执行一次
+static LAST_MINUTE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: u64,
+    pub time: String,
+    #[serde(default)]
+    pub days: Vec<u8>,
+    pub action: ScheduledAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledAction {
+    RestoreWorkspace { name: String },
+    RestartTab { app: String }, // 应用的 EXE 路径、文件名或标签的自定义名称，匹配的标签都重启
+    SuspendAllTabs,
+    RunScript { name: String },
+    Shortcut { action: String }, // 快捷键操作的名称 (与脚本中 shortcut(name) 相同)
+}
+
+/// schedule-fired 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleFired {
+    pub id: u64,
+    pub action: ScheduledAction,
+    pub error: Option<String>,
+}
+
+/// 加载任务并开始按时执行
+pub fn start(app: &AppHandle) {
+    if let Some(schedules) = crate::storage::get().and_then(|s| s.load::<Vec<Schedule>>(DOCUMENT)) {
+        info!(target: "workspace", "已加载 {} 个定时任务", schedules.len());
+        *SCHEDULES.lock().unwrap() = schedules;
+    }
+    LAST_MINUTE.store(local_minute(), Ordering::SeqCst);
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        let minute = local_minute();
+        let last = LAST_MINUTE.swap(minute, Ordering::SeqCst);
+        let schedules = due_between(&SCHEDULES.lock().unwrap(), last, minute);
+        for schedule in schedules {
+            fire(&app, schedule);
+        }
+    });
+}
+
+pub fn list() -> Vec<Schedule> {
+    SCHEDULES.lock().unwrap().clone()
+}
+
+/// 添加任务
+pub fn create(time: String, mut days: Vec<u8>, action: ScheduledAction) -> Result<Schedule, String> {
+    parse_time(&time).ok_or_else(|| format!("无效的时间: {} (格式为 HH:MM)", time))?;
+    if days.iter().any(|d| !(1..=7).contains(d)) {
+        return Err("星期应为 1~7 (周一到周日)".to_string());
+    }
+    days.sort_unstable();
+    days.dedup();
+    validate(&action)?;
+    let mut schedules = SCHEDULES.lock().unwrap();
+    let id = schedules.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+    let schedule = Schedule { id, time: time.trim().to_string(), days, action };
+    schedules.push(schedule.clone());
+    save(&schedules);
+    info!(target: "workspace", "添加定时任务 {}: {} {:?}", id, schedule.time, schedule.action);
+    Ok(schedule)
+}
+
+pub fn delete(id: u64) -> Result<(), String> {
+    let mut schedules = SCHEDULES.lock().unwrap();
+    let count = schedules.len();
+    schedules.retain(|s| s.id != id);
+    if schedules.len() == count {
+        return Err("定时任务不存在".to_string());
+    }
+    save(&schedules);
+    Ok(())
+}
+
+fn validate(action: &ScheduledAction) -> Result<(), String> {
+    match action {
+        ScheduledAction::RestoreWorkspace { name } => {
+            if !crate::WORKSPACES.lock().unwrap().iter().any(|w| w.name == *name) {
+                return Err("工作区不存在".to_string());
+            }
+        }
+        ScheduledAction::RestartTab { app } => {
+            if app.trim().is_empty() {
+                return Err("没有指定要重启的应用".to_string());
+            }
+        }
+        ScheduledAction::SuspendAllTabs => {}
+        ScheduledAction::RunScript { name } => {
+            if !crate::scripts::list().contains(name) {
+                return Err(format!("脚本不存在: {}", name));
+            }
+        }
+        ScheduledAction::Shortcut { action } => {
+            crate::scripts::shortcut_action(action).ok_or_else(|| format!("未知的操作: {}", action))?;
+        }
+    }
+    Ok(())
+}
+
+fn save(schedules: &[Schedule]) {
+    let Some(storage) = crate::storage::get() else { return };
+    if let Err(e) = storage.save(DOCUMENT, schedules) {
+        warn!(target: "workspace", "{}", e);
+    }
+}
+
+fn fire(app: &AppHandle, schedule: Schedule) {
+    info!(target: "workspace", "执行定时任务 {}: {:?}", schedule.id, schedule.action);
+    let error = run(app, &schedule.action).err();
+    if let Some(e) = &error {
+        warn!(target: "workspace", "定时任务 {} 执行失败: {}", schedule.id, e);
+    }
+    let _ = app.emit("schedule-fired", ScheduleFired { id: schedule.id, action: schedule.action, error });
+}
+
+fn run(app: &AppHandle, action: &ScheduledAction) -> Result<(), String> {
+    match action {
+        ScheduledAction::RestoreWorkspace { name } => {
+            let apps = crate::restore_workspace(app.clone(), name.clone())?;
+            let open: Vec<String> = app.state::<WindowManager>().list().into_iter().filter_map(|w| w.exe_path).collect();
+            // 和从前端恢复一样排队启动，嵌入时由 apply_workspace_tab 应用工作区中的标签属性
+            for path in apps.into_iter().filter(|path| !open.iter().any(|o| o.eq_ignore_ascii_case(path))) {
+                let handle = app.clone();
+                crate::operations::start(app, "launch_app", true, |progress| async move {
+                    let hwnd = crate::launch_and_wait(&path, &[], &progress).await?;
+                    let manager = handle.state::<WindowManager>();
+                    crate::embed_tab(&handle, &manager, hwnd).map_err(String::from)
+                });
+            }
+            Ok(())
+        }
+        ScheduledAction::RestartTab { app: target } => {
+            let tabs: Vec<isize> = app
+                .state::<WindowManager>()
+                .list()
+                .into_iter()
+                .filter(|w| matches_tab(target, w.exe_path.as_deref(), w.label.as_deref()))
+                .map(|w| w.hwnd)
+                .collect();
+            if tabs.is_empty() {
+                return Err(format!("没有匹配的标签: {}", target));
+            }
+            for hwnd in tabs {
+                crate::restart_tab(app.clone(), hwnd, false);
+            }
+            Ok(())
+        }
+        ScheduledAction::SuspendAllTabs => {
+            crate::tab_suspend::suspend_all(app);
+            Ok(())
+        }
+        ScheduledAction::RunScript { name } => {
+            let (app, name) = (app.clone(), name.clone());
+            std::thread::spawn(move || crate::scripts::run(&app, &name));
+            Ok(())
+        }
+        ScheduledAction::Shortcut { action } => {
+            let action = crate::scripts::shortcut_action(action).ok_or_else(|| format!("未知的操作: {}", action))?;
+            crate::handle_shortcut_action(app, action);
+            Ok(())
+        }
+    }
+}
+
+// 按 EXE 路径、EXE 文件名 (可以不带 .exe) 或自定义名称匹配标签
+fn matches_tab(target: &str, exe_path: Option<&str>, label: Option<&str>) -> bool {
+    let target = target.trim();
+    let file_name = exe_path.and_then(|p| std::path::Path::new(p).file_name()).map(|n| n.to_string_lossy());
+    exe_path.is_some_and(|p| p.eq_ignore_ascii_case(target))
+        || file_name.is_some_and(|n| n.eq_ignore_ascii_case(target) || n.eq_ignore_ascii_case(&format!("{}.exe", target)))
+        || label.is_some_and(|l| l == target)
+}
+
+// 在一天中第 minute 分钟、星期 weekday 应该执行的任务
+fn due(schedules: &[Schedule], minute: u32, weekday: u8) -> Vec<Schedule> {
+    schedules
+        .iter()
+        .filter(|s| parse_time(&s.time) == Some(minute) && (s.days.is_empty() || s.days.contains(&weekday)))
+        .cloned()
+        .collect()
+}
+
+// 上次检查 (last) 之后到 now 的每一分钟应该执行的任务；相隔太久时只看当前这一分钟，时钟回拨时不执行
+fn due_between(schedules: &[Schedule], last: u64, now: u64) -> Vec<Schedule> {
+    if now <= last {
+        return Vec::new();
+    }
+    let first = if now - last > MAX_CATCH_UP_MINUTES { now } else { last + 1 };
+    (first..=now).flat_map(|minute| due(schedules, (minute % 1440) as u32, weekday_of(minute))).collect()
+}
+
+// 本地时间自 1970 年起的分钟数对应的星期几 (1~7，1970-01-01 是星期四)
+fn weekday_of(minute: u64) -> u8 {
+    ((minute / 1440 + 3) % 7 + 1) as u8
+}
+
+// "HH:MM" 换算成一天中的第几分钟
+fn parse_time(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+// 本地时间自 1970 年起的分钟数
+#[cfg(windows)]
+fn local_minute() -> u64 {
+    use windows::Win32::System::SystemInformation::GetLocalTime;
+
+    let now = unsafe { GetLocalTime() };
+    let days = days_from_civil(now.wYear as i64, now.wMonth as u32, now.wDay as u32);
+    days as u64 * 1440 + now.wHour as u64 * 60 + now.wMinute as u64
+}
+
+#[cfg(not(windows))]
+fn local_minute() -> u64 {
+    let now = chrono::Local::now();
+    let local_secs = now.timestamp() + now.offset().local_minus_utc() as i64;
+    (local_secs.max(0) / 60) as u64
+}
+
+// 公历日期到 1970-01-01 的天数
+#[cfg(windows)]
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(id: u64, time: &str, days: &[u8]) -> Schedule {
+        Schedule { id, time: time.to_string(), days: days.to_vec(), action: ScheduledAction::RunScript { name: "x".to_string() } }
+    }
+
+    #[test]
+    fn picks_schedules_for_the_minute_and_weekday() {
+        let schedules = [schedule(1, "09:25", &[1, 2, 3, 4, 5]), schedule(2, "9:25", &[]), schedule(3, "18:30", &[])];
+        let ids = |minute, weekday| due(&schedules, minute, weekday).iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(9 * 60 + 25, 1), vec![1, 2]);
+        assert_eq!(ids(9 * 60 + 25, 6), vec![2]);
+        assert_eq!(ids(18 * 60 + 30, 7), vec![3]);
+        assert_eq!(ids(0, 1), Vec::<u64>::new());
+
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("7:5"), Some(7 * 60 + 5));
+    }
+
+    #[test]
+    fn catches_up_on_recently_missed_minutes() {
+        let schedules = [schedule(1, "09:25", &[]), schedule(2, "09:26", &[]), schedule(3, "09:27", &[4])];
+        // 1970-01-01 (星期四)
+        let at = |time: &str| parse_time(time).unwrap() as u64;
+        let ids = |last, now| due_between(&schedules, last, now).iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(weekday_of(0), 4);
+        assert_eq!(weekday_of(1440 * 4), 1);
+        assert_eq!(ids(at("09:24"), at("09:27")), vec![1, 2, 3]);
+        assert_eq!(ids(at("09:25"), at("09:26")), vec![2]);
+        assert_eq!(ids(at("09:26"), at("09:26")), Vec::<u64>::new());
+        // 休眠了很久只看当前这一分钟
+        assert_eq!(ids(at("08:00"), at("09:26")), vec![2]);
+        // 时钟回拨
+        assert_eq!(ids(at("09:27"), at("09:25")), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn matches_tabs_by_path_file_name_or_label() {
+        let exe = Some("C:/Apps/Dashboard.exe");
+        assert!(matches_tab("c:/apps/dashboard.exe", exe, None));
+        assert!(matches_tab("dashboard", exe, None));
+        assert!(matches_tab("Dashboard.exe", exe, None));
+        assert!(matches_tab("仪表盘", None, Some("仪表盘")));
+        assert!(!matches_tab("dash", exe, Some("仪表盘")));
+    }
+}
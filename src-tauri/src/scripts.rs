@@ -0,0 +1,272 @@
+// 自动化脚本 (Rhai)
+// 脚本保存在 %APPDATA%\WindowHub\scripts\<名称>.rhai，可调用的窗口管理函数：
+// - windows() / tabs(): 顶层窗口列表 / 嵌入的标签列表
+// - find_windows(#{ title_regex, class, exe, pid }): 按条件查找窗口 (见 window_query.rs)
+// - embed(hwnd) / release(hwnd) / show(hwnd): 嵌入、释放、切换到标签
+// - launch(path): 启动应用，返回新窗口的句柄
+// - content_area() / place(hwnd, x, y, w, h): 内容区 (主窗口客户区坐标) 和标签的位置
+// - process_path(hwnd) / shortcut(name) / sleep(ms)，print 输出到日志
+// run_script 执行脚本的顶层语句；脚本中定义的 on_<事件名> 函数 (如 on_window_embedded(event))
+// 在对应的生命周期事件发生时被调用，载荷与发给前端的事件相同
+// 脚本在独立线程中执行，不阻塞调用方
+// 每个函数按脚本的权限检查 (见 permissions.rs)：查询窗口需要 enumerate，embed/release/show/place/launch 需要 embed，
+// shortcut 中关闭、重启标签和退出需要 kill；默认没有 kill
+
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST, INT};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::permissions::{Capability, Grant, GrantKind};
+use crate::window_manager::WindowManager;
+use crate::window_query::{self, WindowQuery};
+use crate::{host_layout, platform, settings::ShortcutAction};
+
+const EXTENSION: &str = "rhai";
+
+fn scripts_dir() -> Option<PathBuf> {
+    crate::paths::data_dir().map(|dir| dir.join("scripts"))
+}
+
+/// 脚本目录中的脚本名称 (不含扩展名)
+pub fn list() -> Vec<String> {
+    let Some(dir) = scripts_dir() else { return Vec::new() };
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION)))
+        .filter_map(|p| Some(p.file_stem()?.to_string_lossy().into_owned()))
+        .collect();
+    names.sort_by_key(|n| n.to_lowercase());
+    names
+}
+
+fn script_path(name: &str) -> Result<PathBuf, String> {
+    if !is_valid_name(name) {
+        return Err(format!("无效的脚本名称: {}", name));
+    }
+    let dir = scripts_dir().ok_or("无法获取配置目录")?;
+    let path = dir.join(format!("{}.{}", name, EXTENSION));
+    if !path.exists() {
+        return Err(format!("脚本不存在: {}", name));
+    }
+    Ok(path)
+}
+
+// 脚本名称只能是脚本目录中的文件名
+fn is_valid_name(name: &str) -> bool {
+    !name.trim().is_empty() && !name.contains(['/', '\\', ':']) && name != "." && name != ".."
+}
+
+/// 执行脚本 (阻塞直到脚本结束)，返回脚本最后一个表达式的值
+pub fn run(app: &AppHandle, name: &str) -> Result<String, String> {
+    let path = script_path(name)?;
+    let engine = engine(app, name);
+    let ast = engine.compile_file(path).map_err(|e| format!("脚本 {} 有错误: {}", name, e))?;
+    info!(target: "scripts", "执行脚本: {}", name);
+    let result = engine.eval_ast::<Dynamic>(&ast).map_err(|e| {
+        warn!(target: "scripts", "脚本 {} 执行失败: {}", name, e);
+        format!("脚本 {} 执行失败: {}", name, e)
+    })?;
+    Ok(if result.is_unit() { String::new() } else { result.to_string() })
+}
+
+/// 生命周期事件 (由 lifecycle 调用)：在后台线程中调用所有脚本里对应的 on_<事件名> 函数 (安全模式下不调用)
+pub fn dispatch<S: Serialize>(app: &AppHandle, event: &str, payload: &S) {
+    if crate::safe_mode::is_enabled() {
+        return;
+    }
+    let Ok(payload) = rhai::serde::to_dynamic(payload) else { return };
+    let app = app.clone();
+    let hook = hook_name(event);
+    std::thread::spawn(move || {
+        let names = list();
+        if names.is_empty() {
+            return;
+        }
+        for name in names {
+            let Ok(path) = script_path(&name) else { continue };
+            let engine = engine(&app, &name);
+            let Ok(ast) = engine.compile_file(path) else { continue };
+            if !has_hook(&ast, &hook) {
+                continue;
+            }
+            let options = CallFnOptions::new().eval_ast(false);
+            let result = engine.call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &ast, &hook, (payload.clone(),));
+            if let Err(e) = result {
+                warn!(target: "scripts", "脚本 {} 的 {} 执行失败: {}", name, hook, e);
+            }
+        }
+    });
+}
+
+// 事件对应的函数名: window-embedded -> on_window_embedded
+fn hook_name(event: &str) -> String {
+    format!("on_{}", event.replace('-', "_"))
+}
+
+fn has_hook(ast: &AST, hook: &str) -> bool {
+    ast.iter_functions().any(|f| f.name == hook && f.params.len() == 1)
+}
+
+// 绑定了窗口管理函数的脚本引擎，函数按脚本 name 的权限检查
+fn engine(app: &AppHandle, name: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine.on_print(|text| info!(target: "scripts", "{}", text));
+    engine.on_debug(|text, source, pos| info!(target: "scripts", "{} {:?} {}", source.unwrap_or(""), pos, text));
+    let grant = Arc::new(Grant::of(GrantKind::Script, name));
+    let require = move |capability: Capability| -> Result<(), Box<EvalAltResult>> { grant.require(capability).map_err(Into::into) };
+
+    let allow = require.clone();
+    engine.register_fn("windows", move || -> Result<Dynamic, Box<EvalAltResult>> {
+        allow(Capability::Enumerate)?;
+        Ok(to_dynamic(&platform::current().enumerate()))
+    });
+    let (handle, allow) = (app.clone(), require.clone());
+    engine.register_fn("tabs", move || -> Result<Dynamic, Box<EvalAltResult>> {
+        allow(Capability::Enumerate)?;
+        Ok(to_dynamic(&handle.state::<WindowManager>().list()))
+    });
+    let (handle, allow) = (app.clone(), require.clone());
+    engine.register_fn("find_windows", move |query: Map| -> Result<Dynamic, Box<EvalAltResult>> {
+        allow(Capability::Enumerate)?;
+        let query: WindowQuery = rhai::serde::from_dynamic(&query.into())?;
+        let tabs = handle.state::<WindowManager>().list();
+        window_query::find(platform::current(), &tabs, &query).map(|found| to_dynamic(&found)).map_err(Into::into)
+    });
+
+    let (handle, allow) = (app.clone(), require.clone());
+    engine.register_fn("embed", move |hwnd: INT| -> Result<INT, Box<EvalAltResult>> {
+        allow(Capability::Embed)?;
+        let manager = handle.state::<WindowManager>();
+        crate::embed_tab(&handle, &manager, hwnd as isize).map(|w| w.hwnd as INT).map_err(|e| e.message.into())
+    });
+    let (handle, allow) = (app.clone(), require.clone());
+    engine.register_fn("release", move |hwnd: INT| -> Result<(), Box<EvalAltResult>> {
+        allow(Capability::Embed)?;
+        let manager = handle.state::<WindowManager>();
+        crate::release_tab(&handle, &manager, hwnd as isize, crate::lifecycle::ReleaseReason::Detach).map_err(|e| e.message.into())
+    });
+    let (handle, allow) = (app.clone(), require.clone());
+    engine.register_fn("show", move |hwnd: INT| -> Result<bool, Box<EvalAltResult>> {
+        allow(Capability::Embed)?;
+        crate::show_tab(handle.clone(), handle.state(), hwnd as isize).map_err(Into::into)
+    });
+    let (handle, allow) = (app.clone(), require.clone());
+    engine.register_fn("launch", move |path: &str| -> Result<INT, Box<EvalAltResult>> {
+        allow(Capability::Embed)?;
+        // 与其他启动类操作排队，等操作结束 (被取消时发送端随任务丢弃)
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let path = path.to_string();
+        crate::operations::start(&handle, "launch_app", true, |progress| async move {
+            let result = crate::launch_and_wait(&path, &[], &progress).await;
+            let _ = sender.send(result.clone());
+            result
+        });
+        let hwnd = receiver.recv().map_err(|_| "操作已取消".to_string())??;
+        Ok(hwnd as INT)
+    });
+
+    let (handle, allow) = (app.clone(), require.clone());
+    engine.register_fn("content_area", move || -> Result<Dynamic, Box<EvalAltResult>> {
+        allow(Capability::Enumerate)?;
+        let Some((x, y, width, height)) = crate::content_area(&handle) else { return Ok(Dynamic::UNIT) };
+        let mut area = Map::new();
+        for (key, value) in [("x", x), ("y", y), ("width", width), ("height", height)] {
+            area.insert(key.into(), (value as INT).into());
+        }
+        Ok(area.into())
+    });
+    let allow = require.clone();
+    engine.register_fn("place", move |hwnd: INT, x: INT, y: INT, width: INT, height: INT| -> Result<(), Box<EvalAltResult>> {
+        allow(Capability::Embed)?;
+        host_layout::place(hwnd as isize, x as i32, y as i32, width as i32, height as i32);
+        Ok(())
+    });
+
+    let allow = require.clone();
+    engine.register_fn("process_path", move |hwnd: INT| -> Result<String, Box<EvalAltResult>> {
+        allow(Capability::Enumerate)?;
+        Ok(platform::current().process_path(hwnd as isize).unwrap_or_default())
+    });
+    let handle = app.clone();
+    engine.register_fn("shortcut", move |name: &str| -> Result<(), Box<EvalAltResult>> {
+        let action = shortcut_action(name).ok_or_else(|| format!("未知的快捷键操作: {}", name))?;
+        if let Some(capability) = shortcut_capability(action) {
+            require(capability)?;
+        }
+        crate::handle_shortcut_action(&handle, action);
+        Ok(())
+    });
+    engine.register_fn("sleep", |ms: INT| std::thread::sleep(Duration::from_millis(ms.max(0) as u64)));
+    engine
+}
+
+// 快捷键操作需要的权限，只切换界面的操作不需要
+fn shortcut_capability(action: ShortcutAction) -> Option<Capability> {
+    match action {
+        ShortcutAction::CloseTab | ShortcutAction::RestartTab | ShortcutAction::Quit => Some(Capability::Kill),
+        ShortcutAction::DetachTab | ShortcutAction::ReopenTab | ShortcutAction::EmbedForeground => Some(Capability::Embed),
+        _ => None,
+    }
+}
+
+fn to_dynamic<T: Serialize>(value: &T) -> Dynamic {
+    rhai::serde::to_dynamic(value).unwrap_or(Dynamic::UNIT)
+}
+
+// 脚本中 shortcut(name) 的操作名称 (与设置中快捷键的字段名一致，切换标签为 switch_tab_1 ~ switch_tab_9)
+pub fn shortcut_action(name: &str) -> Option<ShortcutAction> {
+    if let Some(digit) = name.strip_prefix("switch_tab_") {
+        return digit.parse().ok().filter(|d| (1..=9).contains(d)).map(ShortcutAction::SwitchTab);
+    }
+    Some(match name {
+        "tab_number_chord" => ShortcutAction::TabNumberChord,
+        "close_tab" => ShortcutAction::CloseTab,
+        "next_tab" => ShortcutAction::NextTab,
+        "prev_tab" => ShortcutAction::PrevTab,
+        "search" => ShortcutAction::Search,
+        "detach_tab" => ShortcutAction::DetachTab,
+        "restart_tab" => ShortcutAction::RestartTab,
+        "reopen_tab" => ShortcutAction::ReopenTab,
+        "quit" => ShortcutAction::Quit,
+        "toggle_window" => ShortcutAction::ToggleWindow,
+        "quick_switcher" => ShortcutAction::QuickSwitcher,
+        "embed_foreground" => ShortcutAction::EmbedForeground,
+        "toggle_tab_fullscreen" => ShortcutAction::ToggleTabFullscreen,
+        "toggle_host_fullscreen" => ShortcutAction::ToggleHostFullscreen,
+        "boss_key" => ShortcutAction::BossKey,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_event_hooks_in_scripts() {
+        let engine = Engine::new_raw();
+        let ast = engine
+            .compile(
+                r#"
+                fn on_window_embedded(event) { event.exe_path }
+                fn on_window_released() {}
+                print("top level");
+                "#,
+            )
+            .unwrap();
+        assert!(has_hook(&ast, &hook_name("window-embedded")));
+        // 参数个数不对的函数不作为事件处理
+        assert!(!has_hook(&ast, &hook_name("window-released")));
+        assert!(!has_hook(&ast, &hook_name("window-activated")));
+
+        assert_eq!(shortcut_action("switch_tab_3"), Some(ShortcutAction::SwitchTab(3)));
+        assert_eq!(shortcut_action("switch_tab_0"), None);
+        assert!(!is_valid_name("..\\settings"));
+    }
+}
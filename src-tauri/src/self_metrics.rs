@@ -0,0 +1,174 @@
+// WindowHub 自身的资源占用
+// get_self_metrics 返回本进程的 CPU、内存、句柄数、GDI/USER 对象数，安装中的钩子和内部队列的积压，
+// 并对疑似泄漏给出提示 (长时间运行后 "越来越卡" 时先看这里)。
+// 钩子取自 resources.rs 的登记，一次安装的一组钩子算一套，同一安装方同时持有不止一套说明之前的没有卸载
+
+use serde::Serialize;
+#[cfg(windows)]
+use std::sync::Mutex;
+#[cfg(windows)]
+use std::time::Instant;
+
+use crate::resources;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::FILETIME,
+    System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+    System::Threading::{GetCurrentProcess, GetGuiResources, GetProcessHandleCount, GetProcessTimes, GR_GDIOBJECTS, GR_USEROBJECTS},
+};
+
+// 超过时提示 (系统默认每个进程最多 10000 个 GDI / USER 对象)
+const HANDLE_WARNING: u32 = 10_000;
+const GUI_OBJECT_WARNING: u32 = 5_000;
+
+// 上次查询时的 (时间, CPU 时间 100ns)
+#[cfg(windows)]
+static LAST_CPU: Mutex<Option<(Instant, u64)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HookOwner {
+    pub owner: &'static str,
+    pub hooks: u32,
+    pub sets: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueDepth {
+    pub name: &'static str,
+    pub pending: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SelfMetrics {
+    pub cpu_percent: f64, // 距上次查询 (第一次为启动以来) 的平均占用，100 为占满所有核心
+    pub working_set_bytes: u64,
+    pub private_bytes: u64,
+    pub handles: u32,
+    pub gdi_objects: u32,
+    pub user_objects: u32,
+    pub hooks: Vec<HookOwner>,
+    pub queues: Vec<QueueDepth>,
+    pub warnings: Vec<String>,
+}
+
+pub fn collect() -> SelfMetrics {
+    let mut metrics = process_metrics();
+    metrics.hooks = hook_owners(&resources::list());
+    metrics.queues = vec![
+        QueueDepth { name: "resize", pending: crate::resize::pending() },
+        QueueDepth { name: "directory_changes", pending: crate::file_browser::pending_changes() },
+    ];
+    metrics.warnings = warnings(&metrics);
+    metrics
+}
+
+// 按安装方汇总钩子
+fn hook_owners(resources: &[resources::Resource]) -> Vec<HookOwner> {
+    let mut owners: Vec<HookOwner> = Vec::new();
+    for resource in resources.iter().filter(|r| r.kind.is_hook()) {
+        match owners.iter_mut().find(|o| o.owner == resource.owner) {
+            Some(owner) => {
+                owner.hooks += resource.count;
+                owner.sets += 1;
+            }
+            None => owners.push(HookOwner { owner: resource.owner, hooks: resource.count, sets: 1 }),
+        }
+    }
+    owners
+}
+
+fn warnings(metrics: &SelfMetrics) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for owner in metrics.hooks.iter().filter(|h| h.sets > 1) {
+        warnings.push(format!("{} 同时持有 {} 套钩子 (共 {} 个)，之前安装的没有卸载", owner.owner, owner.sets, owner.hooks));
+    }
+    if metrics.handles > HANDLE_WARNING {
+        warnings.push(format!("句柄数 {} 过多，可能有句柄泄漏", metrics.handles));
+    }
+    if metrics.gdi_objects > GUI_OBJECT_WARNING {
+        warnings.push(format!("GDI 对象 {} 个，接近系统上限", metrics.gdi_objects));
+    }
+    if metrics.user_objects > GUI_OBJECT_WARNING {
+        warnings.push(format!("USER 对象 {} 个，接近系统上限", metrics.user_objects));
+    }
+    warnings
+}
+
+// 一段时间内的 CPU 占用百分比 (时间单位相同)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn cpu_percent(cpu_time: u64, elapsed: u64, cores: u64) -> f64 {
+    if elapsed == 0 || cores == 0 {
+        return 0.0;
+    }
+    (cpu_time as f64 * 100.0 / (elapsed * cores) as f64).min(100.0)
+}
+
+#[cfg(windows)]
+fn process_metrics() -> SelfMetrics {
+    let ticks = |t: FILETIME| ((t.dwHighDateTime as u64) << 32) | t.dwLowDateTime as u64;
+    unsafe {
+        let process = GetCurrentProcess();
+        let mut metrics = SelfMetrics::default();
+
+        let mut counters = PROCESS_MEMORY_COUNTERS::default();
+        if GetProcessMemoryInfo(process, &mut counters, std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32).is_ok() {
+            metrics.working_set_bytes = counters.WorkingSetSize as u64;
+            metrics.private_bytes = counters.PagefileUsage as u64;
+        }
+        let _ = GetProcessHandleCount(process, &mut metrics.handles);
+        metrics.gdi_objects = GetGuiResources(process, GR_GDIOBJECTS);
+        metrics.user_objects = GetGuiResources(process, GR_USEROBJECTS);
+
+        let (mut created, mut exited, mut kernel, mut user) = Default::default();
+        if GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user).is_ok() {
+            let cpu = ticks(kernel) + ticks(user);
+            let now = Instant::now();
+            let cores = std::thread::available_parallelism().map(|n| n.get() as u64).unwrap_or(1);
+            let mut last = LAST_CPU.lock().unwrap();
+            metrics.cpu_percent = match *last {
+                Some((at, previous)) => cpu_percent(cpu.saturating_sub(previous), now.duration_since(at).as_nanos() as u64 / 100, cores),
+                None => {
+                    // FILETIME 从 1601 年起，换算到 Unix 时间
+                    let started = ticks(created).saturating_sub(116_444_736_000_000_000);
+                    let wall = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_nanos() as u64 / 100)
+                        .unwrap_or(0);
+                    cpu_percent(cpu, wall.saturating_sub(started), cores)
+                }
+            };
+            *last = Some((now, cpu));
+        }
+        metrics
+    }
+}
+
+#[cfg(not(windows))]
+fn process_metrics() -> SelfMetrics {
+    SelfMetrics::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warns_about_leaked_hooks_and_handles() {
+        let metrics = SelfMetrics {
+            handles: 12_000,
+            hooks: vec![HookOwner { owner: "idle", hooks: 2, sets: 1 }, HookOwner { owner: "gestures", hooks: 3, sets: 3 }],
+            ..Default::default()
+        };
+        let warnings = warnings(&metrics);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("gestures"));
+        assert!(warnings[1].contains("12000"));
+    }
+
+    #[test]
+    fn cpu_is_averaged_over_all_cores() {
+        assert_eq!(cpu_percent(50, 100, 4), 12.5);
+        assert_eq!(cpu_percent(10, 0, 4), 0.0);
+    }
+}
@@ -0,0 +1,97 @@
+// 锁屏和快速用户切换
+// win_events 的钩子窗口注册了会话通知 (WTSRegisterSessionNotification)，收到 WM_WTSSESSION_CHANGE 后调用 changed：
+// - 锁定、切换到其他用户 (控制台或远程连接断开) -> 暂停：卸载鼠标手势、热角、鼠标侧键的低级钩子，
+//   全屏检测、空闲隐藏、使用记录、程序监视和键盘布局检查跳过这段时间，远程标签和标签录制不再截取画面，焦点保护不处理前台切换
+// - 解锁、切换回来 -> 检查嵌入窗口：已经关闭的清理掉，脱离主窗口的重新设置 (同 shell_restart)，
+//   按主窗口大小重新放置标签，重新安装钩子，并清除焦点保护记录的前台窗口 (锁屏界面)，避免解锁后把焦点抢走
+// 暂停和恢复后都发送 session-changed
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::platform;
+use crate::window_manager::WindowManager;
+
+// 切换回来时先连接、再解锁，两个状态分开记录，都恢复后才算回到前台
+static LOCKED: AtomicBool = AtomicBool::new(false);
+static DISCONNECTED: AtomicBool = AtomicBool::new(false);
+
+/// WM_WTSSESSION_CHANGE 中关心的通知
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionEvent {
+    Lock,
+    Unlock,
+    Disconnect, // 控制台或远程连接断开 (切换到其他用户)
+    Connect,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionChanged {
+    pub active: bool,
+    pub closed: Vec<isize>,   // 恢复时发现已经关闭并清理的标签
+    pub repaired: Vec<isize>, // 恢复时重新设置的标签
+}
+
+/// 会话是否在前台 (没有锁定、没有切换到其他用户)，不在时定时任务应跳过本轮
+pub fn is_active() -> bool {
+    !LOCKED.load(Ordering::SeqCst) && !DISCONNECTED.load(Ordering::SeqCst)
+}
+
+/// 会话状态变化 (钩子线程收到 WM_WTSSESSION_CHANGE 时调用)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn changed(app: &AppHandle, event: SessionEvent) {
+    let was_active = is_active();
+    match event {
+        SessionEvent::Lock => LOCKED.store(true, Ordering::SeqCst),
+        SessionEvent::Unlock => LOCKED.store(false, Ordering::SeqCst),
+        SessionEvent::Disconnect => DISCONNECTED.store(true, Ordering::SeqCst),
+        SessionEvent::Connect => DISCONNECTED.store(false, Ordering::SeqCst),
+    }
+    let active = is_active();
+    if active == was_active {
+        return;
+    }
+    crate::win_events::record_event(if active { "session-resumed" } else { "session-paused" }, 0, None, String::new());
+    if !active {
+        info!(target: "events", "会话已锁定或切换到其他用户，暂停钩子和定时任务");
+        apply_hooks(app);
+        let _ = app.emit("session-changed", SessionChanged { active, closed: Vec::new(), repaired: Vec::new() });
+        return;
+    }
+    // 在单独的线程中检查嵌入窗口，不阻塞钩子线程
+    let app = app.clone();
+    std::thread::spawn(move || {
+        info!(target: "events", "会话已恢复，检查嵌入窗口并恢复钩子和定时任务");
+        let closed = forget_closed(&app);
+        let repaired = crate::shell_restart::reassert_tabs(&app);
+        if !closed.is_empty() || !repaired.is_empty() {
+            warn!(target: "embed", "会话恢复后清理了 {} 个已关闭的标签，修复了 {} 个嵌入窗口", closed.len(), repaired.len());
+        }
+        crate::focus_guard::reset();
+        apply_hooks(&app);
+        crate::relayout(&app);
+        let _ = app.emit("session-changed", SessionChanged { active: true, closed, repaired });
+    });
+}
+
+// 钩子按设置和会话状态开启或关闭
+#[cfg_attr(not(windows), allow(dead_code))]
+fn apply_hooks(app: &AppHandle) {
+    crate::gestures::apply(app);
+    crate::hot_corners::apply(app);
+    crate::mouse_buttons::apply(app);
+}
+
+// 清理锁定期间关闭的标签，返回清理的标签
+#[cfg_attr(not(windows), allow(dead_code))]
+fn forget_closed(app: &AppHandle) -> Vec<isize> {
+    let platform = platform::current();
+    let closed: Vec<isize> = app.state::<WindowManager>().hwnds().into_iter().filter(|&tab| !platform.is_window(tab)).collect();
+    for &tab in &closed {
+        crate::forget_closed_tab(app, tab);
+    }
+    closed
+}
@@ -0,0 +1,789 @@
+// 应用设置
+// 轮询间隔、全局快捷键、禁止嵌入列表、启动行为、剪贴板历史、尺寸调整，保存在 %APPDATA%\WindowHub\settings.json
+// 缺少的字段使用默认值，所以旧版本的设置文件可以直接读取
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri_plugin_global_shortcut::Shortcut;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PollingSettings {
+    pub liveness_ms: u64,    // 存活检查 + 位置锁定
+    pub reconcile_ms: u64,   // 与后端记录对账
+    pub drag_detect_ms: u64, // 拖拽检测
+    pub title_fallback_ms: u64, // 兼容性数据库标记的窗口定时检查标题和图标 (见 title_watch.rs)，0 表示关闭
+}
+
+impl Default for PollingSettings {
+    fn default() -> Self {
+        PollingSettings { liveness_ms: 200, reconcile_ms: 2000, drag_detect_ms: 100, title_fallback_ms: 3000 }
+    }
+}
+
+/// 全局快捷键，空字符串表示不注册
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutSettings {
+    pub switch_tab_modifier: String, // 与数字 1~9 组合切换到指定标签
+    pub last_tab_on_nine: bool,      // 修饰键+9 切换到最后一个标签 (而不是第 9 个)
+    pub tab_number_chord: bool,      // 修饰键+0 开始输入两位以上的标签编号 (见 tab_numbers.rs)
+    pub pinned_slots: bool,          // 修饰键+数字 先切换到固定在该编号的应用，没有打开时启动并嵌入 (见 pinned_apps.rs)
+    pub close_tab: String,
+    pub next_tab: String,
+    pub prev_tab: String,
+    pub search: String,
+    pub detach_tab: String,
+    pub restart_tab: String, // 重启当前标签的应用
+    pub reopen_tab: String,  // 重新打开最近关闭的标签 (见 closed_tabs.rs)
+    pub quit: String,
+    pub toggle_window: String,
+    pub quick_switcher: String, // 按住修饰键重复按下切换，松开后跳转
+    pub embed_foreground: String, // 把当前前台窗口嵌入 WindowHub
+    pub toggle_tab_fullscreen: String, // 当前标签临时占满主窗口 / 恢复布局
+    pub toggle_host_fullscreen: String, // 主窗口无边框全屏 / 恢复
+    pub boss_key: String, // 立即隐藏主窗口和所有标签并静音，再按一次恢复 (见 boss_key.rs)
+    pub mru_cycling: bool,      // next_tab / prev_tab 按最近使用顺序切换 (在最近两个标签间来回)
+    pub passthrough: Vec<PassthroughRule>, // 焦点在这些应用的标签中时交给应用处理的快捷键 (见 passthrough.rs)
+}
+
+impl Default for ShortcutSettings {
+    fn default() -> Self {
+        ShortcutSettings {
+            switch_tab_modifier: "Alt".to_string(),
+            last_tab_on_nine: true,
+            tab_number_chord: true,
+            pinned_slots: false,
+            close_tab: "Ctrl+W".to_string(),
+            next_tab: "Ctrl+Tab".to_string(),
+            prev_tab: "Ctrl+Shift+Tab".to_string(),
+            search: "Ctrl+K".to_string(),
+            detach_tab: "Ctrl+D".to_string(),
+            restart_tab: "Ctrl+Shift+R".to_string(),
+            reopen_tab: "Ctrl+Shift+T".to_string(),
+            quit: "Alt+Q".to_string(),
+            toggle_window: "Alt+Space".to_string(),
+            quick_switcher: "Alt+`".to_string(),
+            embed_foreground: "Ctrl+Alt+E".to_string(),
+            toggle_tab_fullscreen: "Ctrl+Shift+M".to_string(),
+            toggle_host_fullscreen: "Ctrl+Alt+F".to_string(),
+            boss_key: String::new(),
+            mru_cycling: false,
+            passthrough: Vec::new(),
+        }
+    }
+}
+
+/// 快捷键透传规则，如 { "app": "chrome.exe", "shortcuts": ["Ctrl+W", "Ctrl+Tab"] }
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PassthroughRule {
+    pub app: String,            // EXE 文件名 (不区分大小写)
+    pub shortcuts: Vec<String>, // 为空表示所有快捷键
+}
+
+impl PassthroughRule {
+    /// 焦点在 exe_path 的标签中时，按下的 shortcut 是否交给应用处理
+    pub fn passes(&self, exe_path: Option<&str>, shortcut: &Shortcut) -> bool {
+        let exe_name = exe_path.and_then(|p| std::path::Path::new(p).file_name()).and_then(|f| f.to_str());
+        if !exe_name.is_some_and(|name| self.app.eq_ignore_ascii_case(name)) {
+            return false;
+        }
+        self.shortcuts.is_empty()
+            || self.shortcuts.iter().any(|accelerator| crate::keyboard_layout::parse(accelerator).is_ok_and(|s| s == *shortcut))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutAction {
+    SwitchTab(u32),
+    TabNumberChord,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    Search,
+    DetachTab,
+    RestartTab,
+    ReopenTab,
+    Quit,
+    ToggleWindow,
+    QuickSwitcher,
+    EmbedForeground,
+    ToggleTabFullscreen,
+    ToggleHostFullscreen,
+    BossKey,
+}
+
+impl ShortcutSettings {
+    /// 所有需要注册的快捷键及对应操作
+    pub fn bindings(&self) -> Vec<(String, ShortcutAction)> {
+        let mut bindings = Vec::new();
+        if !self.switch_tab_modifier.is_empty() {
+            for i in 1..=9 {
+                bindings.push((format!("{}+{}", self.switch_tab_modifier, i), ShortcutAction::SwitchTab(i)));
+            }
+            if self.tab_number_chord {
+                bindings.push((format!("{}+0", self.switch_tab_modifier), ShortcutAction::TabNumberChord));
+            }
+        }
+        let named = [
+            (&self.close_tab, ShortcutAction::CloseTab),
+            (&self.next_tab, ShortcutAction::NextTab),
+            (&self.prev_tab, ShortcutAction::PrevTab),
+            (&self.search, ShortcutAction::Search),
+            (&self.detach_tab, ShortcutAction::DetachTab),
+            (&self.restart_tab, ShortcutAction::RestartTab),
+            (&self.reopen_tab, ShortcutAction::ReopenTab),
+            (&self.quit, ShortcutAction::Quit),
+            (&self.toggle_window, ShortcutAction::ToggleWindow),
+            (&self.quick_switcher, ShortcutAction::QuickSwitcher),
+            (&self.embed_foreground, ShortcutAction::EmbedForeground),
+            (&self.toggle_tab_fullscreen, ShortcutAction::ToggleTabFullscreen),
+            (&self.toggle_host_fullscreen, ShortcutAction::ToggleHostFullscreen),
+            (&self.boss_key, ShortcutAction::BossKey),
+        ];
+        for (accelerator, action) in named {
+            if !accelerator.is_empty() {
+                bindings.push((accelerator.clone(), action));
+            }
+        }
+        bindings
+    }
+}
+
+/// 禁止嵌入的窗口 (在内置的危险窗口列表之外追加)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockList {
+    pub classes: Vec<String>, // 窗口类名 (包含即匹配)
+    pub apps: Vec<String>,    // EXE 文件名，如 "notepad.exe" (不区分大小写)
+}
+
+impl BlockList {
+    pub fn matches(&self, class_name: &str, exe_path: Option<&str>) -> bool {
+        if self.classes.iter().any(|c| !c.is_empty() && class_name.contains(c.as_str())) {
+            return true;
+        }
+        let exe_name = exe_path.and_then(|p| std::path::Path::new(p).file_name()).and_then(|f| f.to_str());
+        match exe_name {
+            Some(exe_name) => self.apps.iter().any(|a| a.eq_ignore_ascii_case(exe_name)),
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartupSettings {
+    pub start_hidden: bool,                // 启动时只显示托盘图标
+    pub restore_workspace: Option<String>, // 启动时自动恢复的工作区
+    pub background_agent: bool,            // 登录时启动后台代理，主界面未运行时也按规则和监视列表收集窗口 (见 agent.rs)
+}
+
+/// 剪贴板历史 (默认关闭)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardSettings {
+    pub enabled: bool,
+    pub max_entries: usize,
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        ClipboardSettings { enabled: false, max_entries: 50 }
+    }
+}
+
+/// 嵌入窗口随主窗口调整大小的方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResizeSettings {
+    pub fast_path: bool, // 调整过程中不重绘，停止调整后统一重绘一次
+}
+
+impl Default for ResizeSettings {
+    fn default() -> Self {
+        ResizeSettings { fast_path: true }
+    }
+}
+
+/// 主窗口背景材质 (Windows 11，见 theme.rs)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backdrop {
+    #[default]
+    None, // 不透明的网页背景
+    Mica,
+    Acrylic,
+    Tabbed, // 带标签页的窗口使用的 Mica 变体
+}
+
+/// 主窗口圆角 (Windows 11)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Corners {
+    #[default]
+    Default, // 由系统决定
+    Round,
+    RoundSmall,
+    Square,
+}
+
+/// 界面外观
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppearanceSettings {
+    pub native_tab_strip: bool, // 由后端绘制原生标签栏 (见 native_tabs.rs)，网页只用于搜索等面板
+    pub language: String,       // 后端消息的语言 (见 messages.rs)，为空时使用中文
+    pub backdrop: Backdrop,
+    pub corners: Corners,
+    pub custom_chrome: bool, // 去掉系统标题栏，标签栏兼作标题栏 (见 chrome.rs)
+    pub animate_transitions: bool, // 嵌入时窗口从桌面位置移动到标签位置，弹出时移回 (见 animation.rs)，系统关闭动画时不生效
+}
+
+/// 屏幕边缘和角落 (热角，见 hot_corners.rs)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenEdge {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// 热角触发的操作 (与同名快捷键相同)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotCornerAction {
+    ToggleWindow,
+    Search,
+    NextTab,
+    PrevTab,
+    EmbedForeground,
+    ToggleHostFullscreen,
+}
+
+impl HotCornerAction {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn shortcut_action(self) -> ShortcutAction {
+        match self {
+            HotCornerAction::ToggleWindow => ShortcutAction::ToggleWindow,
+            HotCornerAction::Search => ShortcutAction::Search,
+            HotCornerAction::NextTab => ShortcutAction::NextTab,
+            HotCornerAction::PrevTab => ShortcutAction::PrevTab,
+            HotCornerAction::EmbedForeground => ShortcutAction::EmbedForeground,
+            HotCornerAction::ToggleHostFullscreen => ShortcutAction::ToggleHostFullscreen,
+        }
+    }
+}
+
+/// 热角，如 { "edge": "top_right", "action": "toggle_window" }
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HotCorner {
+    pub edge: ScreenEdge,
+    pub action: HotCornerAction,
+}
+
+/// 鼠标手势和鼠标按键触发的操作 (与同名快捷键相同)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseAction {
+    NextTab,
+    PrevTab,
+    CloseTab,
+    RestartTab,
+    DetachTab,
+    Search,
+    ToggleTabFullscreen,
+}
+
+impl MouseAction {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn shortcut_action(self) -> ShortcutAction {
+        match self {
+            MouseAction::NextTab => ShortcutAction::NextTab,
+            MouseAction::PrevTab => ShortcutAction::PrevTab,
+            MouseAction::CloseTab => ShortcutAction::CloseTab,
+            MouseAction::RestartTab => ShortcutAction::RestartTab,
+            MouseAction::DetachTab => ShortcutAction::DetachTab,
+            MouseAction::Search => ShortcutAction::Search,
+            MouseAction::ToggleTabFullscreen => ShortcutAction::ToggleTabFullscreen,
+        }
+    }
+}
+
+/// 鼠标手势，如 { "strokes": "DR", "action": "close_tab" } (先向下再向右)
+/// strokes 由 L/R/U/D (左/右/上/下) 组成，相邻的方向不能相同
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gesture {
+    pub strokes: String,
+    pub action: MouseAction,
+}
+
+impl Gesture {
+    fn new(strokes: &str, action: MouseAction) -> Self {
+        Gesture { strokes: strokes.to_string(), action }
+    }
+}
+
+/// 鼠标中键和侧键 (见 mouse_buttons.rs)，为 null 表示不拦截，交给应用处理 (默认都不拦截)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MouseButtonSettings {
+    pub middle_click_tab: Option<MouseAction>, // 在标签栏上中键单击，关闭/弹出/重启作用于指针下的标签
+    pub back: Option<MouseAction>,             // 侧键 X1 (后退)
+    pub forward: Option<MouseAction>,          // 侧键 X2 (前进)
+}
+
+/// 输入
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputSettings {
+    pub low_latency: bool,           // 内容区的鼠标输入不经过网页，直接进入嵌入窗口 (见 input_bypass.rs)
+    pub hot_corners: Vec<HotCorner>, // 鼠标推到屏幕边缘或角落时触发的操作，为空表示关闭
+    pub hot_corner_delay_ms: u64,    // 鼠标停留多久才触发，避免路过时误触
+    pub mouse_gestures: bool,        // 在主窗口中按住右键划动触发手势 (见 gestures.rs)
+    pub gestures: Vec<Gesture>,
+    pub mouse_buttons: MouseButtonSettings,
+    pub idle_hide_minutes: u64, // 主窗口和标签这么多分钟没有输入时隐藏到托盘 (见 idle.rs)，0 表示关闭
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        InputSettings {
+            low_latency: false,
+            hot_corners: Vec::new(),
+            hot_corner_delay_ms: 300,
+            mouse_gestures: false,
+            gestures: vec![
+                Gesture::new("L", MouseAction::PrevTab),
+                Gesture::new("R", MouseAction::NextTab),
+                Gesture::new("DR", MouseAction::CloseTab),
+                Gesture::new("UD", MouseAction::RestartTab),
+            ],
+            mouse_buttons: MouseButtonSettings::default(),
+            idle_hide_minutes: 0,
+        }
+    }
+}
+
+/// 自动更新 (见 updater.rs)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateSettings {
+    pub channel: String,        // 发布通道，替换更新地址中的 {{channel}}
+    pub endpoint: String,       // 更新清单地址，为空时不检查更新
+    pub check_on_startup: bool, // 启动时在后台检查一次
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        UpdateSettings { channel: "stable".to_string(), endpoint: String::new(), check_on_startup: true }
+    }
+}
+
+/// 文件搜索的提供者 (见 file_search.rs)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileSearchProvider {
+    #[default]
+    Auto, // Everything 可用时使用 Everything，否则使用内置搜索
+    Everything,
+    Builtin, // 遍历桌面和下载目录
+}
+
+/// 搜索框
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchSettings {
+    pub file_provider: FileSearchProvider,
+    pub everything_dll: String, // Everything SDK 的 DLL 路径，为空时在程序目录和 Everything 安装目录中查找
+}
+
+/// 会话快照 (见 snapshots.rs)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotSettings {
+    pub max_snapshots: usize, // 最多保留的快照个数，超出时删除最早的
+    pub on_exit: bool,        // 退出时自动保存一个快照
+}
+
+impl Default for SnapshotSettings {
+    fn default() -> Self {
+        SnapshotSettings { max_snapshots: 30, on_exit: true }
+    }
+}
+
+/// 远程标签 (实验性，见 remote_tabs.rs)：允许局域网中的其他 WindowHub 查看和操作本机的窗口
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteSettings {
+    pub enabled: bool, // 默认关闭，开启后在 port 上监听
+    pub port: u16,
+    pub key: String, // 连接密钥，对方连接时需要输入
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        RemoteSettings { enabled: false, port: crate::remote_tabs::DEFAULT_PORT, key: String::new() }
+    }
+}
+
+/// 资源上限 (见 budget.rs)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LimitSettings {
+    pub max_tabs: usize,            // 同时嵌入的窗口
+    pub max_capture_streams: usize, // 同时进行的标签录制和远程标签画面推送
+    pub max_hooks: usize,           // 登记的钩子 (含常驻的事件钩子)
+}
+
+impl Default for LimitSettings {
+    fn default() -> Self {
+        LimitSettings { max_tabs: 30, max_capture_streams: 4, max_hooks: 32 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub polling: PollingSettings,
+    pub shortcuts: ShortcutSettings,
+    pub block_list: BlockList,
+    pub startup: StartupSettings,
+    pub clipboard: ClipboardSettings,
+    pub resize: ResizeSettings,
+    pub appearance: AppearanceSettings,
+    pub input: InputSettings,
+    pub updates: UpdateSettings,
+    pub search: SearchSettings,
+    pub snapshots: SnapshotSettings,
+    pub remote: RemoteSettings,
+    pub limits: LimitSettings,
+}
+
+static SETTINGS: Mutex<Option<Settings>> = Mutex::new(None);
+
+// 当前工作区自己的快捷键 (Workspace.shortcuts)，为空时使用设置中的快捷键
+static WORKSPACE_SHORTCUTS: Mutex<Option<ShortcutSettings>> = Mutex::new(None);
+
+// 轮询间隔下限，太小会让前端持续占用 CPU
+const MIN_POLL_MS: u64 = 50;
+
+// 标题检查间隔下限，只是补充事件，不需要很及时
+const MIN_TITLE_POLL_MS: u64 = 500;
+
+// 剪贴板历史条数上限 (图片记录会占用较多内存)
+const MAX_CLIPBOARD_ENTRIES: usize = 500;
+
+// 热角停留时间上限，太长时用户会以为没有生效
+const MAX_HOT_CORNER_DELAY_MS: u64 = 5000;
+
+// 空闲隐藏时间上限 (一天)
+const MAX_IDLE_HIDE_MINUTES: u64 = 24 * 60;
+
+// 保留快照个数上限
+const MAX_SNAPSHOTS: usize = 500;
+
+// 资源上限能设置的最大值
+const MAX_TABS_LIMIT: usize = 200;
+const MAX_CAPTURE_STREAMS_LIMIT: usize = 32;
+const MAX_HOOKS_LIMIT: usize = 256;
+
+pub fn get() -> Settings {
+    SETTINGS.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// 当前生效的快捷键 (当前工作区有自己的快捷键时使用工作区的)
+pub fn shortcuts() -> ShortcutSettings {
+    WORKSPACE_SHORTCUTS.lock().unwrap().clone().unwrap_or_else(|| get().shortcuts)
+}
+
+/// 切换工作区的快捷键，返回生效的快捷键是否有变化
+pub fn set_workspace_shortcuts(shortcuts: Option<ShortcutSettings>) -> bool {
+    let before = self::shortcuts();
+    *WORKSPACE_SHORTCUTS.lock().unwrap() = shortcuts;
+    before != self::shortcuts()
+}
+
+/// 检查设置是否有效 (轮询间隔、快捷键格式、快捷键冲突)
+pub fn validate(settings: &Settings) -> Result<(), String> {
+    let polling = &settings.polling;
+    for (name, value) in [
+        ("liveness_ms", polling.liveness_ms),
+        ("reconcile_ms", polling.reconcile_ms),
+        ("drag_detect_ms", polling.drag_detect_ms),
+    ] {
+        if value < MIN_POLL_MS {
+            return Err(format!("轮询间隔 {} 不能小于 {} 毫秒", name, MIN_POLL_MS));
+        }
+    }
+
+    if polling.title_fallback_ms != 0 && polling.title_fallback_ms < MIN_TITLE_POLL_MS {
+        return Err(format!("标题检查间隔不能小于 {} 毫秒 (0 表示关闭)", MIN_TITLE_POLL_MS));
+    }
+
+    let max_entries = settings.clipboard.max_entries;
+    if !(1..=MAX_CLIPBOARD_ENTRIES).contains(&max_entries) {
+        return Err(format!("剪贴板历史条数必须在 1 到 {} 之间", MAX_CLIPBOARD_ENTRIES));
+    }
+
+    let corners = &settings.input.hot_corners;
+    if let Some((i, corner)) = corners.iter().enumerate().find(|(i, c)| corners[..*i].iter().any(|other| other.edge == c.edge)) {
+        return Err(format!("热角冲突: 第 {} 项与之前的项使用了同一个位置 {:?}", i + 1, corner.edge));
+    }
+    if settings.input.hot_corner_delay_ms > MAX_HOT_CORNER_DELAY_MS {
+        return Err(format!("热角停留时间不能超过 {} 毫秒", MAX_HOT_CORNER_DELAY_MS));
+    }
+    if settings.input.idle_hide_minutes > MAX_IDLE_HIDE_MINUTES {
+        return Err(format!("空闲隐藏时间不能超过 {} 分钟", MAX_IDLE_HIDE_MINUTES));
+    }
+    let gestures = &settings.input.gestures;
+    for (i, gesture) in gestures.iter().enumerate() {
+        crate::gestures::validate(&gesture.strokes)?;
+        if gestures[..i].iter().any(|other| other.strokes == gesture.strokes) {
+            return Err(format!("鼠标手势重复: {}", gesture.strokes));
+        }
+    }
+
+    if !(1..=MAX_SNAPSHOTS).contains(&settings.snapshots.max_snapshots) {
+        return Err(format!("保留的快照个数必须在 1 到 {} 之间", MAX_SNAPSHOTS));
+    }
+
+    let limits = &settings.limits;
+    for (name, value, max) in [
+        ("max_tabs", limits.max_tabs, MAX_TABS_LIMIT),
+        ("max_capture_streams", limits.max_capture_streams, MAX_CAPTURE_STREAMS_LIMIT),
+        ("max_hooks", limits.max_hooks, MAX_HOOKS_LIMIT),
+    ] {
+        if !(1..=max).contains(&value) {
+            return Err(format!("资源上限 {} 必须在 1 到 {} 之间", name, max));
+        }
+    }
+
+    if settings.remote.enabled {
+        if settings.remote.port == 0 {
+            return Err("远程标签的端口不能为 0".to_string());
+        }
+        if settings.remote.key.chars().count() < crate::remote_tabs::MIN_KEY_LEN {
+            return Err(format!("远程标签的连接密钥至少需要 {} 个字符", crate::remote_tabs::MIN_KEY_LEN));
+        }
+    }
+
+    let language = &settings.appearance.language;
+    if !language.is_empty() && crate::messages::resolve(language).is_none() {
+        return Err(crate::messages::text("settings.unknown_language", &[language.clone()]));
+    }
+
+    if settings.updates.channel.trim().is_empty() {
+        return Err("发布通道不能为空".to_string());
+    }
+    if !settings.updates.endpoint.trim().is_empty() {
+        crate::updater::endpoint_for(&settings.updates.endpoint, &settings.updates.channel)?;
+    }
+
+    validate_shortcuts(&settings.shortcuts)
+}
+
+/// 检查快捷键格式和冲突 (设置和工作区自己的快捷键)
+pub fn validate_shortcuts(shortcuts: &ShortcutSettings) -> Result<(), String> {
+    for rule in &shortcuts.passthrough {
+        for accelerator in &rule.shortcuts {
+            crate::keyboard_layout::parse(accelerator).map_err(|e| format!("透传规则 {} 中的快捷键无效 {}: {}", rule.app, accelerator, e))?;
+        }
+    }
+
+    let mut parsed: Vec<(String, Shortcut)> = Vec::new();
+    for (accelerator, _) in shortcuts.bindings() {
+        let shortcut = crate::keyboard_layout::parse(&accelerator).map_err(|e| format!("无效的快捷键 {}: {}", accelerator, e))?;
+        if let Some((other, _)) = parsed.iter().find(|(_, s)| *s == shortcut) {
+            return Err(format!("快捷键冲突: {} 与 {}", accelerator, other));
+        }
+        parsed.push((accelerator, shortcut));
+    }
+    Ok(())
+}
+
+/// 替换当前设置并保存，返回之前的设置
+pub fn update(settings: Settings) -> Result<Settings, String> {
+    // 内存中是默认设置，保存会覆盖原来的配置
+    if crate::safe_mode::is_enabled() {
+        return Err(crate::messages::text("settings.safe_mode", &[]));
+    }
+    validate(&settings)?;
+    save_to_file(&settings)?;
+    let previous = SETTINGS.lock().unwrap().replace(settings).unwrap_or_default();
+    info!(target: "settings", "设置已更新");
+    Ok(previous)
+}
+
+/// 按下的快捷键对应的操作
+pub fn action_for(shortcut: &Shortcut) -> Option<ShortcutAction> {
+    shortcuts()
+        .bindings()
+        .into_iter()
+        .find(|(accelerator, _)| crate::keyboard_layout::parse(accelerator).is_ok_and(|s| s == *shortcut))
+        .map(|(_, action)| action)
+}
+
+/// 按下的快捷键在设置中的写法 (如 "Ctrl+W")
+pub fn accelerator_for(shortcut: &Shortcut) -> Option<String> {
+    shortcuts()
+        .bindings()
+        .into_iter()
+        .map(|(accelerator, _)| accelerator)
+        .find(|accelerator| crate::keyboard_layout::parse(accelerator).is_ok_and(|s| s == *shortcut))
+}
+
+/// 窗口是否在用户的禁止嵌入列表中
+pub fn is_blocked(class_name: &str, exe_path: Option<&str>) -> bool {
+    let settings = SETTINGS.lock().unwrap();
+    settings.as_ref().is_some_and(|s| s.block_list.matches(class_name, exe_path))
+}
+
+// 持久化辅助函数
+fn settings_path() -> Option<std::path::PathBuf> {
+    crate::paths::data_dir().map(|dir| dir.join("settings.json"))
+}
+
+fn save_to_file(settings: &Settings) -> Result<(), String> {
+    let path = settings_path().ok_or("无法获取配置目录")?;
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("保存设置失败: {}", e))
+}
+
+// 从文件读取并校验设置，文件不存在时返回 None
+fn read_from_file() -> Option<Result<Settings, String>> {
+    let content = std::fs::read_to_string(settings_path()?).ok()?;
+    let settings = serde_json::from_str::<Settings>(&content)
+        .map_err(|e| format!("设置文件格式错误: {}", e))
+        .map(disable_weak_remote_key)
+        .and_then(|settings| validate(&settings).map(|_| settings));
+    Some(settings)
+}
+
+// 之前的版本允许较短的远程标签密钥，现在不再接受：关闭远程标签而不是丢弃整个设置文件
+fn disable_weak_remote_key(mut settings: Settings) -> Settings {
+    if settings.remote.enabled && settings.remote.key.chars().count() < crate::remote_tabs::MIN_KEY_LEN {
+        warn!(target: "settings", "远程标签的连接密钥少于 {} 个字符，已关闭远程标签", crate::remote_tabs::MIN_KEY_LEN);
+        settings.remote.enabled = false;
+    }
+    settings
+}
+
+pub fn load_from_file() {
+    let settings = match read_from_file() {
+        Some(Ok(settings)) => settings,
+        Some(Err(e)) => {
+            warn!(target: "settings", "{}，使用默认设置", e);
+            Settings::default()
+        }
+        None => Settings::default(),
+    };
+    *SETTINGS.lock().unwrap() = Some(settings);
+}
+
+/// 设置文件被外部修改后重新加载，返回之前的设置 (内容没有变化时返回 None)
+/// 文件无效时保留当前设置
+pub fn reload_from_file() -> Result<Option<Settings>, String> {
+    let settings = read_from_file().unwrap_or_else(|| Ok(Settings::default()))?;
+    let mut current = SETTINGS.lock().unwrap();
+    if current.as_ref() == Some(&settings) {
+        return Ok(None);
+    }
+    info!(target: "settings", "设置文件已变化，重新加载");
+    Ok(Some(current.replace(settings).unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn block_list(classes: &[&str], apps: &[&str]) -> BlockList {
+        BlockList {
+            classes: classes.iter().map(|s| s.to_string()).collect(),
+            apps: apps.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn block_list_matches_class_substring() {
+        let list = block_list(&["Chrome_WidgetWin"], &[]);
+        assert!(list.matches("Chrome_WidgetWin_1", None));
+        assert!(!list.matches("Notepad", None));
+    }
+
+    #[test]
+    fn block_list_matches_exe_name_case_insensitive() {
+        let list = block_list(&[], &["Notepad.exe"]);
+        // 用 / 分隔，测试在非 Windows 主机上也能运行
+        assert!(list.matches("Notepad", Some("C:/Windows/System32/notepad.exe")));
+        assert!(!list.matches("Notepad", Some("C:/Tools/notepad2.exe")));
+        assert!(!list.matches("Notepad", None));
+    }
+
+    #[test]
+    fn block_list_ignores_empty_class() {
+        let list = block_list(&[""], &[]);
+        assert!(!list.matches("AnyClass", None));
+    }
+
+    #[test]
+    fn default_settings_are_valid() {
+        assert_eq!(validate(&Settings::default()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_too_short_polling_interval() {
+        let mut settings = Settings::default();
+        settings.polling.liveness_ms = 10;
+        assert!(validate(&settings).unwrap_err().contains("liveness_ms"));
+    }
+
+    #[test]
+    fn rejects_conflicting_shortcuts() {
+        let mut settings = Settings::default();
+        settings.shortcuts.search = "Shift+Ctrl+Tab".to_string();
+        assert!(validate(&settings).unwrap_err().starts_with("快捷键冲突"));
+    }
+
+    #[test]
+    fn empty_shortcut_disables_binding() {
+        let mut settings = Settings::default();
+        settings.shortcuts.quit = String::new();
+        assert_eq!(validate(&settings), Ok(()));
+        assert!(settings.shortcuts.bindings().iter().all(|(_, action)| *action != ShortcutAction::Quit));
+    }
+
+    #[test]
+    fn passthrough_rule_matches_app_and_shortcut() {
+        let rule = PassthroughRule { app: "Chrome.exe".to_string(), shortcuts: vec!["Ctrl+W".to_string()] };
+        let close = Shortcut::from_str("Ctrl+W").unwrap();
+        let search = Shortcut::from_str("Ctrl+K").unwrap();
+        assert!(rule.passes(Some("C:/Program Files/Google/Chrome/chrome.exe"), &close));
+        assert!(!rule.passes(Some("C:/Program Files/Google/Chrome/chrome.exe"), &search));
+        assert!(!rule.passes(Some("C:/Windows/notepad.exe"), &close));
+        assert!(!rule.passes(None, &close));
+
+        // 没有列出快捷键时全部透传
+        let all = PassthroughRule { app: "code.exe".to_string(), shortcuts: Vec::new() };
+        assert!(all.passes(Some("D:/VSCode/Code.exe"), &search));
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{"polling":{"liveness_ms":500}}"#).unwrap();
+        assert_eq!(settings.polling.liveness_ms, 500);
+        assert_eq!(settings.polling.reconcile_ms, PollingSettings::default().reconcile_ms);
+        assert_eq!(settings.shortcuts, ShortcutSettings::default());
+    }
+}
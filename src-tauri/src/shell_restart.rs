@@ -0,0 +1,50 @@
+// 资源管理器重启
+// explorer.exe 崩溃或重启后任务栏重建，托盘图标随之消失，外壳还会把部分嵌入窗口当作顶层窗口重新显示出来。
+// 任务栏重建时系统向所有顶层窗口广播 "TaskbarCreated"，win_events 的钩子窗口收到后调用 taskbar_created：
+// - 重新添加托盘图标，刷新菜单和角标
+// - 检查每个嵌入窗口是否仍是主窗口的子窗口、是否仍为子窗口样式，不是时重新设置 (Platform::reassert_embedded)
+// - 按记录重新隐藏非当前标签，并按主窗口大小重新放置标签
+// 完成后发送 shell-restarted (载荷为修复的标签句柄)，前端据此对账
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::platform;
+use crate::window_manager::WindowManager;
+
+/// 任务栏已重建 (在单独的线程中处理，不阻塞钩子线程)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn taskbar_created(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        info!(target: "tray", "资源管理器已重启，重新添加托盘图标并检查嵌入窗口");
+        crate::tray::recreate(&app);
+        let repaired = reassert_tabs(&app);
+        if !repaired.is_empty() {
+            warn!(target: "embed", "资源管理器重启后修复了 {} 个嵌入窗口: {:?}", repaired.len(), repaired);
+        }
+        crate::relayout(&app);
+        let _ = app.emit("shell-restarted", repaired);
+    });
+}
+
+// 重新设置脱离了主窗口的标签，返回修复的标签
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn reassert_tabs(app: &AppHandle) -> Vec<isize> {
+    let host = crate::get_main_window_hwnd(app.clone());
+    if host == 0 {
+        return Vec::new();
+    }
+    let platform = platform::current();
+    let repaired: Vec<isize> = app
+        .state::<WindowManager>()
+        .hwnds()
+        .into_iter()
+        .filter(|&tab| platform.is_window(tab) && platform.reassert_embedded(host, tab))
+        .collect();
+    crate::tab_view::reassert(platform);
+    for &tab in &repaired {
+        platform.repaint(tab);
+    }
+    repaired
+}
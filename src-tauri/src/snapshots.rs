@@ -0,0 +1,156 @@
+// 会话快照
+// 把当前所有标签 (程序、启动参数、分组、颜色、快捷键、自动重启) 和分隔比例带时间保存到数据库，
+// 误点 "全部关闭"、规则执行错了之后，可以恢复到之前 (比如昨天下班时) 的布局：
+// - snapshot_session(label) 手动保存，退出时自动保存一个 (设置 snapshots.on_exit)
+// - 最多保留 snapshots.max_snapshots 个，超出时删除最早的
+// - restore_snapshot(id) 和恢复工作区一样：返回需要启动的应用 (快照中有、当前没有嵌入的标签)，
+//   由前端启动，新窗口嵌入时套用快照中的分组、颜色等；恢复前先自动保存当前状态，恢复错了可以再恢复回来
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::window_manager::{EmbeddedWindow, TabColor};
+use crate::zones::SplitRatios;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotTab {
+    pub exe_path: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub color: Option<TabColor>,
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    #[serde(default)]
+    pub auto_restart: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub tabs: Vec<SnapshotTab>,
+    #[serde(default)]
+    pub split: SplitRatios,
+}
+
+/// 快照列表中的一项
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotInfo {
+    pub id: u64,
+    pub label: String,
+    pub created_at: u64, // Unix 秒
+    pub tabs: Vec<String>, // 各标签的标题
+}
+
+/// 当前标签的快照 (无法获取程序路径的标签不能重新启动，不保存)
+pub fn capture(windows: &[EmbeddedWindow], split: SplitRatios) -> SessionSnapshot {
+    let tabs = windows
+        .iter()
+        .filter_map(|w| {
+            Some(SnapshotTab {
+                exe_path: w.exe_path.clone()?,
+                args: w.args.clone(),
+                title: w.label.clone().unwrap_or_else(|| w.title.clone()),
+                group: w.group.clone(),
+                color: w.color,
+                hotkey: w.hotkey.clone(),
+                auto_restart: w.auto_restart,
+            })
+        })
+        .collect();
+    SessionSnapshot { tabs, split }
+}
+
+/// 保存快照并删除超出数量的旧快照
+pub fn save(label: &str, snapshot: &SessionSnapshot) -> Result<SnapshotInfo, String> {
+    let storage = crate::storage::get().ok_or("数据库未打开，无法保存快照")?;
+    let content = serde_json::to_string(snapshot).map_err(|e| e.to_string())?;
+    let created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let id = storage.add_snapshot(label, created_at, &content)?;
+    let pruned = storage.prune_snapshots(crate::settings::get().snapshots.max_snapshots)?;
+    info!(target: "workspace", "已保存快照 {} ({})，{} 个标签，删除 {} 个旧快照", id, label, snapshot.tabs.len(), pruned);
+    Ok(summary(id, label.to_string(), created_at, snapshot))
+}
+
+/// 所有快照，从新到旧
+pub fn list() -> Result<Vec<SnapshotInfo>, String> {
+    let Some(storage) = crate::storage::get() else { return Ok(Vec::new()) };
+    Ok(storage
+        .snapshots()?
+        .into_iter()
+        .map(|(id, label, created_at, content)| {
+            let snapshot = serde_json::from_str(&content).unwrap_or_default();
+            summary(id, label, created_at, &snapshot)
+        })
+        .collect())
+}
+
+pub fn load(id: u64) -> Result<SessionSnapshot, String> {
+    let storage = crate::storage::get().ok_or("数据库未打开")?;
+    let content = storage.snapshot(id)?.ok_or("快照不存在")?;
+    serde_json::from_str(&content).map_err(|e| format!("快照已损坏: {}", e))
+}
+
+/// 快照中每个标签对应的已嵌入标签 (open 中的序号)，没有对应的为 None
+/// (open 为已嵌入标签的程序路径和启动参数，同一程序、同样启动参数的标签一一对应，多出来的算缺少)
+pub fn pair(snapshot: &SessionSnapshot, open: &[(String, Vec<String>)]) -> Vec<Option<usize>> {
+    let mut used = vec![false; open.len()];
+    snapshot
+        .tabs
+        .iter()
+        .map(|tab| {
+            let found = open
+                .iter()
+                .enumerate()
+                .position(|(i, (exe_path, args))| !used[i] && exe_path.eq_ignore_ascii_case(&tab.exe_path) && *args == tab.args);
+            if let Some(i) = found {
+                used[i] = true;
+            }
+            found
+        })
+        .collect()
+}
+
+/// 快照中当前没有嵌入的标签
+pub fn missing<'a>(snapshot: &'a SessionSnapshot, open: &[(String, Vec<String>)]) -> Vec<&'a SnapshotTab> {
+    snapshot.tabs.iter().zip(pair(snapshot, open)).filter(|(_, found)| found.is_none()).map(|(tab, _)| tab).collect()
+}
+
+fn summary(id: u64, label: String, created_at: u64, snapshot: &SessionSnapshot) -> SnapshotInfo {
+    SnapshotInfo { id, label, created_at, tabs: snapshot.tabs.iter().map(|t| t.title.clone()).collect() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(exe_path: &str, args: &[&str]) -> SnapshotTab {
+        SnapshotTab {
+            exe_path: exe_path.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            title: String::new(),
+            group: None,
+            color: None,
+            hotkey: None,
+            auto_restart: false,
+        }
+    }
+
+    #[test]
+    fn only_relaunches_tabs_that_are_not_open() {
+        let snapshot = SessionSnapshot {
+            tabs: vec![tab(r"C:\Apps\Code.exe", &["a"]), tab(r"C:\Apps\Code.exe", &["b"]), tab(r"C:\Apps\wt.exe", &[]), tab(r"C:\Apps\wt.exe", &[])],
+            split: SplitRatios::default(),
+        };
+        let open = |exe_path: &str, args: &[&str]| -> (String, Vec<String>) { (exe_path.to_string(), args.iter().map(|a| a.to_string()).collect()) };
+        let windows = [open(r"c:\apps\code.exe", &["b"]), open(r"C:\Apps\wt.exe", &[])];
+        assert_eq!(missing(&snapshot, &windows), vec![&snapshot.tabs[0], &snapshot.tabs[3]]);
+        assert_eq!(pair(&snapshot, &windows), vec![None, Some(0), Some(1), None]);
+    }
+}
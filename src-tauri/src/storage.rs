@@ -0,0 +1,312 @@
+// 本地数据存储 (SQLite)
+// 程序自己维护的数据 (嵌入无响应的窗口类型、失败统计，以及之后的会话、使用记录等) 统一保存在
+// %APPDATA%\WindowHub\windowhub.db，不再每个功能各写一个 JSON 文件
+// 标签名称 (tab_labels.rs) 在数据库中，旧版的 tab_labels.json 首次打开数据库时导入
+// 以下文件有意仍然是 JSON，不迁移到数据库：
+// - 用户会手动编辑、由 config_watch 监听修改的配置：settings.json、workspaces.json、
+//   watched_apps.json、focus_guard.json (workspaces.json 还由导入布局、配置档案直接读写)
+// - 窗口规则 rules.json 和兼容性数据库的用户条目 compat.json：目前只能手动编辑文件 (没有编辑界面)，
+//   规则还由不打开数据库的后台代理读取；迁移要先有编辑命令和代理的读取方式，另作为后续的改动
+// - 用户可以复制给别人的数据：macros.json
+// - 数据库打不开时也要生效的状态：kiosk.json (展台模式不能因为数据库损坏而解除)
+// - 与后台代理进程交换的数据：agent_handoff.json (代理进程不打开数据库)
+// 程序自己维护的其他数据都保存在数据库中，旧版的 JSON 文件见 LEGACY_FILES
+//
+// 表结构变化通过 MIGRATIONS 追加，数据库的 user_version 记录已执行到第几条，只能在末尾追加，不能修改已发布的条目
+// Storage 作为 Tauri 的 managed state 提供给命令，没有 AppHandle 的模块通过 storage::get() 访问
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const MIGRATIONS: &[&str] = &[
+    // 1: 按名称保存的 JSON 文档
+    "CREATE TABLE documents (
+        name TEXT PRIMARY KEY,
+        content TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );",
+    // 2: 使用记录 (见 activity.rs)，每行为一段连续使用某个应用的时间
+    "CREATE TABLE activity (
+        app TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        seconds INTEGER NOT NULL
+    );
+    CREATE INDEX activity_started_at ON activity (started_at);",
+    // 3: 会话快照 (见 snapshots.rs)，content 为 JSON
+    "CREATE TABLE snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        label TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        content TEXT NOT NULL
+    );",
+];
+
+// 首次打开数据库时导入的旧版 JSON 文件 (文档名, 文件名)，原文件保留不删除
+const LEGACY_FILES: [(&str, &str); 4] = [
+    ("problem_classes", "problem_classes.json"),
+    ("embed_failures", "embed_failures.json"),
+    ("pinned_apps", "pinned_apps.json"),
+    ("tab_labels", "tab_labels.json"),
+];
+
+#[derive(Clone)]
+pub struct Storage {
+    conn: Arc<Mutex<Connection>>,
+}
+
+static STORAGE: OnceLock<Storage> = OnceLock::new();
+
+impl Storage {
+    pub fn open(path: &Path) -> Result<Storage, String> {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let conn = Connection::open(path).map_err(|e| format!("无法打开数据库 {}: {}", path.display(), e))?;
+        Storage::with_connection(conn)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Storage, String> {
+        Storage::with_connection(Connection::open_in_memory().map_err(|e| e.to_string())?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Storage, String> {
+        migrate(&conn)?;
+        Ok(Storage { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// 读取文档，不存在或格式不符时返回 None
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let conn = self.conn.lock().unwrap();
+        let content: Option<String> = conn
+            .query_row("SELECT content FROM documents WHERE name = ?1", params![name], |row| row.get(0))
+            .optional()
+            .unwrap_or_else(|e| {
+                warn!(target: "settings", "读取数据失败 {}: {}", name, e);
+                None
+            });
+        serde_json::from_str(&content?).ok()
+    }
+
+    pub fn save<T: Serialize + ?Sized>(&self, name: &str, value: &T) -> Result<(), String> {
+        let content = serde_json::to_string(value).map_err(|e| e.to_string())?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO documents (name, content, updated_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(name) DO UPDATE SET content = excluded.content, updated_at = excluded.updated_at",
+                params![name, content, now],
+            )
+            .map_err(|e| format!("保存数据失败 {}: {}", name, e))?;
+        Ok(())
+    }
+
+    /// 追加一段使用记录
+    pub fn add_activity(&self, app: &str, started_at: u64, seconds: u64) -> Result<(), String> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO activity (app, started_at, seconds) VALUES (?1, ?2, ?3)",
+                params![app, started_at as i64, seconds as i64],
+            )
+            .map_err(|e| format!("保存使用记录失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 开始时间在 [from, to) 内的使用记录按应用汇总 (应用, 秒数)
+    pub fn activity_totals(&self, from: u64, to: u64) -> Result<Vec<(String, u64)>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT app, SUM(seconds) FROM activity WHERE started_at >= ?1 AND started_at < ?2 GROUP BY app")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![from as i64, to as i64], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64)))
+            .map_err(|e| format!("读取使用记录失败: {}", e))?;
+        rows.collect::<Result<_, _>>().map_err(|e| format!("读取使用记录失败: {}", e))
+    }
+
+    /// 删除开始时间早于 before 的使用记录，返回删除的条数
+    pub fn prune_activity(&self, before: u64) -> Result<usize, String> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM activity WHERE started_at < ?1", params![before as i64])
+            .map_err(|e| format!("清理使用记录失败: {}", e))
+    }
+
+    /// 保存一个快照，返回编号
+    pub fn add_snapshot(&self, label: &str, created_at: u64, content: &str) -> Result<u64, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO snapshots (label, created_at, content) VALUES (?1, ?2, ?3)",
+            params![label, created_at as i64, content],
+        )
+        .map_err(|e| format!("保存快照失败: {}", e))?;
+        Ok(conn.last_insert_rowid() as u64)
+    }
+
+    /// 所有快照 (编号, 名称, 时间, 内容)，从新到旧
+    pub fn snapshots(&self) -> Result<Vec<(u64, String, u64, String)>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id, label, created_at, content FROM snapshots ORDER BY id DESC")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)? as u64, row.get(1)?, row.get::<_, i64>(2)? as u64, row.get(3)?)))
+            .map_err(|e| format!("读取快照失败: {}", e))?;
+        rows.collect::<Result<_, _>>().map_err(|e| format!("读取快照失败: {}", e))
+    }
+
+    /// 快照的内容，不存在时为 None
+    pub fn snapshot(&self, id: u64) -> Result<Option<String>, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT content FROM snapshots WHERE id = ?1", params![id as i64], |row| row.get(0))
+            .optional()
+            .map_err(|e| format!("读取快照失败: {}", e))
+    }
+
+    /// 只保留最新的 keep 个快照，返回删除的个数
+    pub fn prune_snapshots(&self, keep: usize) -> Result<usize, String> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "DELETE FROM snapshots WHERE id NOT IN (SELECT id FROM snapshots ORDER BY id DESC LIMIT ?1)",
+                params![keep as i64],
+            )
+            .map_err(|e| format!("清理快照失败: {}", e))
+    }
+
+    /// 把数据库导出为 path (VACUUM INTO，写入中也能得到一致的副本)，path 不能已经存在
+    pub fn export_to(&self, path: &Path) -> Result<(), String> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("VACUUM INTO ?1", params![path.to_string_lossy()])
+            .map_err(|e| format!("导出数据库失败: {}", e))?;
+        Ok(())
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT 1 FROM documents WHERE name = ?1", params![name], |_| Ok(()))
+            .optional()
+            .is_ok_and(|row| row.is_some())
+    }
+
+    /// 导入旧版的 JSON 文件 (数据库中还没有对应文档时)
+    fn import_legacy(&self, dir: &Path) {
+        for (name, file) in LEGACY_FILES {
+            if self.contains(name) {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(dir.join(file)) else { continue };
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else { continue };
+            if self.save(name, &value).is_ok() {
+                info!(target: "settings", "已导入 {} 到数据库", file);
+            }
+        }
+    }
+}
+
+/// 按 user_version 执行尚未执行的迁移
+fn migrate(conn: &Connection) -> Result<(), String> {
+    let version: usize = conn
+        .query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))
+        .map_err(|e| e.to_string())? as usize;
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        conn.execute_batch(&format!("BEGIN; {} PRAGMA user_version = {}; COMMIT;", sql, i + 1))
+            .map_err(|e| {
+                let _ = conn.execute_batch("ROLLBACK;");
+                format!("数据库迁移 {} 失败: {}", i + 1, e)
+            })?;
+    }
+    if version < MIGRATIONS.len() {
+        info!(target: "settings", "数据库已迁移到版本 {}", MIGRATIONS.len());
+    }
+    Ok(())
+}
+
+/// 打开数据库并注册为 managed state (setup 时在加载其他数据之前调用)
+pub fn init(app: &AppHandle) {
+    let Some(dir) = crate::paths::data_dir() else { return };
+    match Storage::open(&dir.join("windowhub.db")) {
+        Ok(storage) => {
+            storage.import_legacy(&dir);
+            app.manage(storage.clone());
+            let _ = STORAGE.set(storage);
+        }
+        Err(e) => warn!(target: "settings", "{}，本次运行的数据不会保存", e),
+    }
+}
+
+/// 当前程序的数据库结构版本 (已知的迁移条数)
+pub fn schema_version() -> usize {
+    MIGRATIONS.len()
+}
+
+/// 全局的存储 (数据库没有打开时为 None，此时数据只保存在内存中)
+pub fn get() -> Option<&'static Storage> {
+    STORAGE.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_once_and_round_trips_documents() {
+        let storage = Storage::open_in_memory().unwrap();
+        {
+            let conn = storage.conn.lock().unwrap();
+            let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+            assert_eq!(version as usize, MIGRATIONS.len());
+            // 再次执行不会重复建表
+            migrate(&conn).unwrap();
+        }
+
+        assert_eq!(storage.load::<Vec<String>>("problem_classes"), None);
+        storage.save("problem_classes", &["HungWindow"]).unwrap();
+        storage.save("problem_classes", &["HungWindow", "OtherWindow"]).unwrap();
+        assert_eq!(storage.load::<Vec<String>>("problem_classes").unwrap(), vec!["HungWindow", "OtherWindow"]);
+        assert_eq!(storage.load::<u32>("problem_classes"), None);
+    }
+
+    #[test]
+    fn sums_activity_within_range() {
+        let storage = Storage::open_in_memory().unwrap();
+        storage.add_activity("C:/Apps/code.exe", 100, 60).unwrap();
+        storage.add_activity("C:/Apps/code.exe", 200, 30).unwrap();
+        storage.add_activity("C:/Apps/music.exe", 150, 10).unwrap();
+        storage.add_activity("C:/Apps/code.exe", 300, 500).unwrap();
+
+        let mut totals = storage.activity_totals(100, 300).unwrap();
+        totals.sort();
+        assert_eq!(totals, vec![("C:/Apps/code.exe".to_string(), 90), ("C:/Apps/music.exe".to_string(), 10)]);
+
+        assert_eq!(storage.prune_activity(200).unwrap(), 2);
+        assert_eq!(storage.activity_totals(0, u64::MAX / 2).unwrap(), vec![("C:/Apps/code.exe".to_string(), 530)]);
+    }
+
+    #[test]
+    fn keeps_newest_snapshots() {
+        let storage = Storage::open_in_memory().unwrap();
+        for i in 0..5 {
+            storage.add_snapshot(&format!("快照 {}", i), 100 + i, "{}").unwrap();
+        }
+        assert_eq!(storage.prune_snapshots(3).unwrap(), 2);
+        let labels: Vec<String> = storage.snapshots().unwrap().into_iter().map(|(_, label, ..)| label).collect();
+        assert_eq!(labels, vec!["快照 4", "快照 3", "快照 2"]);
+    }
+}
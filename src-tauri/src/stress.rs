@@ -0,0 +1,174 @@
+// 嵌入压力测试 (QA 用的隐藏命令 stress_test，界面中没有入口，只在 debug 构建或启用 stress-test 特性时可用)
+// 创建一个一次性的测试窗口 (test_window.rs，可以指定绘制缓慢、未响应、拒绝关闭等行为)，
+// 按 Platform::embed / release 的完整流程反复嵌入到主窗口再释放，每一轮检查：
+// - 嵌入后父窗口是主窗口，样式是子窗口且没有标题栏
+// - 释放后回到顶层，样式与嵌入前完全一致
+// 结束后比较本进程 GDI / USER 对象数的变化，发布前用来发现泄漏和状态错乱
+
+use serde::Serialize;
+
+#[cfg(windows)]
+use tracing::info;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::HWND,
+    System::Threading::{GetCurrentProcess, GetGuiResources, GR_GDIOBJECTS, GR_USEROBJECTS},
+    UI::WindowsAndMessaging::{GetAncestor, GA_PARENT},
+};
+
+#[cfg(windows)]
+use crate::platform::{self, win32::WindowStyle};
+use crate::test_window::Behavior;
+#[cfg(windows)]
+use crate::test_window::TestWindow;
+
+// 只报告前这么多个问题
+#[cfg(windows)]
+const MAX_FAILURES: usize = 20;
+
+const MAX_ITERATIONS: u32 = 1000;
+
+const WS_CHILD: u32 = 0x4000_0000;
+const WS_CAPTION: u32 = 0x00C0_0000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StressReport {
+    pub iterations: u32,
+    pub completed: u32,
+    pub failures: Vec<String>,
+    pub gdi_objects_delta: i64,
+    pub user_objects_delta: i64,
+    pub elapsed_ms: u64,
+}
+
+// 某一时刻测试窗口的父窗口和样式
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+struct Snapshot {
+    parent: isize,
+    style: u32,
+}
+
+/// 反复嵌入和释放测试窗口 iterations 次
+pub fn run(host: isize, iterations: u32, behavior: Behavior) -> Result<StressReport, String> {
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return Err(format!("次数应在 1~{} 之间", MAX_ITERATIONS));
+    }
+    if host == 0 {
+        return Err("无法获取主窗口".to_string());
+    }
+    run_cycles(host, iterations, behavior)
+}
+
+// 嵌入后的问题
+#[cfg_attr(not(windows), allow(dead_code))]
+fn check_embedded(cycle: u32, host: isize, now: Snapshot) -> Option<String> {
+    if now.parent != host {
+        return Some(format!("第 {} 轮嵌入后父窗口为 {:#x}，应为主窗口 {:#x}", cycle, now.parent, host));
+    }
+    if now.style & WS_CHILD == 0 || now.style & WS_CAPTION != 0 {
+        return Some(format!("第 {} 轮嵌入后样式异常: {:#010x}", cycle, now.style));
+    }
+    None
+}
+
+// 释放后的问题 (before 为嵌入前)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn check_released(cycle: u32, before: Snapshot, now: Snapshot) -> Option<String> {
+    if now.parent != before.parent {
+        return Some(format!("第 {} 轮释放后父窗口为 {:#x}，应为 {:#x}", cycle, now.parent, before.parent));
+    }
+    if now.style != before.style {
+        return Some(format!("第 {} 轮释放后样式为 {:#010x}，嵌入前为 {:#010x}", cycle, now.style, before.style));
+    }
+    None
+}
+
+#[cfg(windows)]
+fn run_cycles(host: isize, iterations: u32, behavior: Behavior) -> Result<StressReport, String> {
+    let started = std::time::Instant::now();
+    let window = TestWindow::create(behavior)?;
+    let (gdi_before, user_before) = gui_objects();
+    let platform = platform::current();
+    let mut report = StressReport { iterations, completed: 0, failures: Vec::new(), gdi_objects_delta: 0, user_objects_delta: 0, elapsed_ms: 0 };
+
+    for cycle in 1..=iterations {
+        let before = snapshot(window.hwnd);
+        let saved = match platform.embed(host, window.hwnd) {
+            Ok(saved) => saved,
+            Err(e) => {
+                fail(&mut report, format!("第 {} 轮嵌入失败: {}", cycle, e.message));
+                continue;
+            }
+        };
+        if let Some(failure) = check_embedded(cycle, host, snapshot(window.hwnd)) {
+            fail(&mut report, failure);
+        }
+        platform.release(window.hwnd, Some(&saved));
+        if let Some(failure) = check_released(cycle, before, snapshot(window.hwnd)) {
+            fail(&mut report, failure);
+        }
+        report.completed = cycle;
+    }
+
+    let (gdi_after, user_after) = gui_objects();
+    drop(window);
+    report.gdi_objects_delta = gdi_after as i64 - gdi_before as i64;
+    report.user_objects_delta = user_after as i64 - user_before as i64;
+    report.elapsed_ms = started.elapsed().as_millis() as u64;
+    info!(
+        target: "embed",
+        "压力测试: {}/{} 轮，{} 个问题，GDI {:+}，USER {:+}，{} ms",
+        report.completed,
+        iterations,
+        report.failures.len(),
+        report.gdi_objects_delta,
+        report.user_objects_delta,
+        report.elapsed_ms
+    );
+    Ok(report)
+}
+
+#[cfg(windows)]
+fn fail(report: &mut StressReport, failure: String) {
+    if report.failures.len() < MAX_FAILURES {
+        report.failures.push(failure);
+    }
+}
+
+#[cfg(windows)]
+fn snapshot(hwnd: isize) -> Snapshot {
+    unsafe {
+        let hwnd = HWND(hwnd as *mut _);
+        Snapshot { parent: GetAncestor(hwnd, GA_PARENT).0 as isize, style: WindowStyle::style(hwnd).get().unwrap_or(0) }
+    }
+}
+
+#[cfg(windows)]
+fn gui_objects() -> (u32, u32) {
+    unsafe {
+        let process = GetCurrentProcess();
+        (GetGuiResources(process, GR_GDIOBJECTS), GetGuiResources(process, GR_USEROBJECTS))
+    }
+}
+
+#[cfg(not(windows))]
+fn run_cycles(_host: isize, _iterations: u32, _behavior: Behavior) -> Result<StressReport, String> {
+    Err("仅支持 Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_broken_embed_and_release_state() {
+        let before = Snapshot { parent: 1, style: 0x14CF_0000 };
+        assert_eq!(check_embedded(1, 100, Snapshot { parent: 100, style: 0x5400_0000 }), None);
+        assert!(check_embedded(1, 100, Snapshot { parent: 1, style: 0x5400_0000 }).is_some());
+        assert!(check_embedded(1, 100, Snapshot { parent: 100, style: 0x54C0_0000 }).is_some());
+        assert_eq!(check_released(1, before, before), None);
+        assert!(check_released(2, before, Snapshot { parent: 1, style: 0x1400_0000 }).unwrap().contains("第 2 轮"));
+    }
+}
@@ -0,0 +1,192 @@
+// 快速切换器
+// 按下快捷键 (默认 Alt+`) 弹出一个置顶的小窗口，按最近使用顺序列出所有标签及缩略图：
+// - 首次按下选中上一个使用的标签，按住修饰键再次按下依次选中下一个
+// - 松开所有修饰键时切换到选中的标签并关闭，也可以直接点击某个标签
+// 切换器窗口只创建一次，之后隐藏/显示复用；列表和选中项由后端维护，通过事件推送给切换器页面：
+// - switcher-open: SwitcherState (缩略图随后单独推送)
+// - switcher-select: 选中项下标
+// - switcher-thumbnail: SwitcherThumbnail
+// 确认后向主窗口发送 switcher-activate-tab (载荷为标签句柄)
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, LogicalSize, Manager, WebviewUrl, WebviewWindowBuilder};
+use tracing::{info, warn};
+
+use crate::window_manager::{TabColor, WindowManager};
+
+pub const WINDOW_LABEL: &str = "switcher";
+
+const THUMBNAIL_WIDTH: i32 = 240;
+const THUMBNAIL_HEIGHT: i32 = 150;
+
+// 每个标签卡片占用的尺寸 (逻辑像素，与 switcher.html 的样式一致)
+const CARD_WIDTH: f64 = 256.0;
+const CARD_HEIGHT: f64 = 196.0;
+const PADDING: f64 = 16.0;
+const MAX_COLUMNS: usize = 5;
+const MAX_ROWS: usize = 3;
+
+// 检测修饰键松开的间隔
+const RELEASE_POLL_INTERVAL: Duration = Duration::from_millis(30);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SwitcherItem {
+    pub hwnd: isize,
+    pub title: String, // 重命名过的标签为自定义名称
+    pub group: Option<String>,
+    pub color: Option<TabColor>,
+    pub thumbnail: Option<String>, // JPEG data URL，尚未截取或无法截取时为 None
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SwitcherState {
+    pub items: Vec<SwitcherItem>,
+    pub selected: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SwitcherThumbnail {
+    pub hwnd: isize,
+    pub thumbnail: String,
+}
+
+// 切换器打开时的状态，关闭时为 None
+static STATE: Mutex<Option<SwitcherState>> = Mutex::new(None);
+
+pub fn state() -> Option<SwitcherState> {
+    STATE.lock().unwrap().clone()
+}
+
+/// 快捷键按下：未打开时打开并选中上一个使用的标签，已打开时选中下一个
+pub fn on_shortcut(app: &AppHandle) {
+    let mut state = STATE.lock().unwrap();
+    if let Some(state) = state.as_mut() {
+        state.selected = (state.selected + 1) % state.items.len();
+        let _ = app.emit("switcher-select", state.selected);
+        return;
+    }
+
+    let windows = app.state::<WindowManager>().list();
+    if windows.is_empty() {
+        return;
+    }
+    let platform = crate::platform::current();
+    let hwnds: Vec<isize> = windows.iter().map(|w| w.hwnd).collect();
+    let ordered = crate::lifecycle::mru(&hwnds);
+    let items: Vec<SwitcherItem> = ordered
+        .iter()
+        .filter_map(|hwnd| windows.iter().find(|w| w.hwnd == *hwnd))
+        .map(|w| {
+            let title = w.label.clone().unwrap_or_else(|| platform.title(w.hwnd));
+            SwitcherItem {
+                hwnd: w.hwnd,
+                title: crate::privacy::title(if title.is_empty() { w.title.clone() } else { title }),
+                group: w.group.clone(),
+                color: w.color,
+                thumbnail: None,
+            }
+        })
+        .collect();
+    let opened = SwitcherState { selected: if items.len() > 1 { 1 } else { 0 }, items };
+    *state = Some(opened.clone());
+    drop(state);
+
+    info!(target: "shortcuts", "打开快速切换器: {} 个标签", opened.items.len());
+    show_window(app, opened.items.len());
+    let _ = app.emit("switcher-open", opened);
+
+    let thumbnail_app = app.clone();
+    std::thread::spawn(move || load_thumbnails(&thumbnail_app, ordered));
+    let release_app = app.clone();
+    std::thread::spawn(move || wait_for_release(&release_app));
+}
+
+/// 切换到标签并关闭切换器，hwnd 为 None 时使用当前选中项
+pub fn commit(app: &AppHandle, hwnd: Option<isize>) {
+    let Some(state) = STATE.lock().unwrap().take() else { return };
+    hide_window(app);
+
+    let Some(target) = hwnd.or_else(|| state.items.get(state.selected).map(|i| i.hwnd)) else { return };
+    info!(target: "shortcuts", "快速切换到标签: {}", target);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    let _ = app.emit("switcher-activate-tab", target);
+}
+
+/// 关闭切换器，不切换标签
+pub fn cancel(app: &AppHandle) {
+    if STATE.lock().unwrap().take().is_some() {
+        hide_window(app);
+    }
+}
+
+// 修饰键全部松开时确认选择；切换器被点击或取消关闭后结束
+fn wait_for_release(app: &AppHandle) {
+    let platform = crate::platform::current();
+    while STATE.lock().unwrap().is_some() {
+        if !platform.is_modifier_down() {
+            commit(app, None);
+            return;
+        }
+        std::thread::sleep(RELEASE_POLL_INTERVAL);
+    }
+}
+
+// 按显示顺序逐个截取缩略图，截取较慢 (需要目标窗口重绘)，所以先打开窗口再推送
+fn load_thumbnails(app: &AppHandle, hwnds: Vec<isize>) {
+    let platform = crate::platform::current();
+    for hwnd in hwnds {
+        if STATE.lock().unwrap().is_none() {
+            return;
+        }
+        let Some(thumbnail) = platform.thumbnail(hwnd, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT) else { continue };
+        if let Some(state) = STATE.lock().unwrap().as_mut() {
+            if let Some(item) = state.items.iter_mut().find(|i| i.hwnd == hwnd) {
+                item.thumbnail = Some(thumbnail.clone());
+            }
+        }
+        let _ = app.emit("switcher-thumbnail", SwitcherThumbnail { hwnd, thumbnail });
+    }
+}
+
+// 首次使用时创建窗口，按标签数量调整大小后居中显示 (不抢焦点)
+fn show_window(app: &AppHandle, count: usize) {
+    let window = match app.get_webview_window(WINDOW_LABEL) {
+        Some(window) => window,
+        None => {
+            let built = WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App("switcher.html".into()))
+                .title("WindowHub 快速切换")
+                .decorations(false)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .resizable(false)
+                .focused(false)
+                .visible(false)
+                .build();
+            match built {
+                Ok(window) => window,
+                Err(e) => {
+                    warn!(target: "shortcuts", "创建快速切换器窗口失败: {}", e);
+                    return;
+                }
+            }
+        }
+    };
+
+    let columns = count.clamp(1, MAX_COLUMNS);
+    let rows = count.div_ceil(MAX_COLUMNS).clamp(1, MAX_ROWS);
+    let size = LogicalSize::new(columns as f64 * CARD_WIDTH + PADDING * 2.0, rows as f64 * CARD_HEIGHT + PADDING * 2.0);
+    let _ = window.set_size(size);
+    let _ = window.center();
+    let _ = window.show();
+}
+
+fn hide_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
@@ -0,0 +1,110 @@
+// 系统辅助功能设置
+// 高对比度、关闭动画 ("在 Windows 中显示动画")、文本大小 ("使文本更大")，
+// 前端据此关闭过渡动画、放大文字，原生标签栏 (native_tabs.rs) 在高对比度下改用系统颜色
+// 系统设置变化时 (WM_SETTINGCHANGE / WM_SYSCOLORCHANGE，由 win_events.rs 的钩子窗口收到) 重新读取，
+// 有变化时发送 system-accessibility-changed 事件 (载荷为 AccessibilityPrefs)
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD},
+        UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW},
+        UI::WindowsAndMessaging::{SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS},
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AccessibilityPrefs {
+    pub high_contrast: bool,
+    pub reduced_motion: bool, // 系统关闭了动画
+    pub text_scale: f32,      // 文本缩放 (1.0 ~ 2.25)
+}
+
+impl Default for AccessibilityPrefs {
+    fn default() -> Self {
+        AccessibilityPrefs { high_contrast: false, reduced_motion: false, text_scale: 1.0 }
+    }
+}
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// 上一次读取的结果，原生标签栏每次重绘都会用到，不重复查询系统
+static CURRENT: Mutex<Option<AccessibilityPrefs>> = Mutex::new(None);
+
+/// 保存 AppHandle (setup 时调用一次)
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+pub fn get() -> AccessibilityPrefs {
+    *CURRENT.lock().unwrap().get_or_insert_with(query)
+}
+
+/// 系统设置可能有变化 (钩子窗口收到 WM_SETTINGCHANGE 等时调用)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn changed() {
+    let prefs = query();
+    let previous = CURRENT.lock().unwrap().replace(prefs);
+    if previous == Some(prefs) {
+        return;
+    }
+    info!(target: "settings", "系统辅助功能设置已变化: {:?}", prefs);
+    crate::native_tabs::refresh();
+    if let Some(app) = APP.get() {
+        let _ = app.emit("system-accessibility-changed", prefs);
+    }
+}
+
+#[cfg(windows)]
+fn query() -> AccessibilityPrefs {
+    unsafe {
+        let mut contrast = HIGHCONTRASTW { cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32, ..Default::default() };
+        let high_contrast = SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            contrast.cbSize,
+            Some(&mut contrast as *mut _ as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+        .is_ok()
+            && (contrast.dwFlags.0 & HCF_HIGHCONTRASTON.0) != 0;
+
+        let mut animation = windows::Win32::Foundation::BOOL(1);
+        let _ = SystemParametersInfoW(SPI_GETCLIENTAREAANIMATION, 0, Some(&mut animation as *mut _ as *mut _), SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0));
+
+        // 设置 > 辅助功能 > 文本大小，没有设置过时没有这个值
+        let mut percent: u32 = 100;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let _ = RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!(r"Software\Microsoft\Accessibility"),
+            w!("TextScaleFactor"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut percent as *mut _ as *mut _),
+            Some(&mut size),
+        );
+
+        AccessibilityPrefs {
+            high_contrast,
+            reduced_motion: !animation.as_bool(),
+            text_scale: text_scale(percent),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn query() -> AccessibilityPrefs {
+    AccessibilityPrefs::default()
+}
+
+// 系统允许的文本缩放是 100% ~ 225%
+#[cfg_attr(not(windows), allow(dead_code))]
+fn text_scale(percent: u32) -> f32 {
+    percent.clamp(100, 225) as f32 / 100.0
+}
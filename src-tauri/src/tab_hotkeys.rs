@@ -0,0 +1,108 @@
+// 标签快捷键
+// 除了 Alt+1~9 按位置切换，还可以给某个标签绑定固定的全局快捷键 (bind_hotkey_to_tab)，
+// 不管标签排在第几个，按下后都切换到它 (如嵌入的密码管理器)
+// 快捷键记录在标签上 (EmbeddedWindow.hotkey)：应用重启后接替标签的新窗口继承，
+// 保存工作区时随标签保存，恢复工作区时绑定到同一应用新嵌入的窗口；标签释放或关闭后注销
+// 绑定变化时发送 tab-hotkey-changed (载荷为 TabHotkey)
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tracing::{info, warn};
+
+use crate::{keyboard_layout, settings};
+use crate::window_manager::{EmbeddedWindow, WindowManager};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TabHotkey {
+    pub hwnd: isize,
+    pub hotkey: Option<String>,
+}
+
+/// 给标签绑定快捷键 (accelerator 为空时解除绑定)，返回修改后的记录
+pub fn bind(app: &AppHandle, manager: &WindowManager, tab_id: u64, accelerator: &str) -> Result<EmbeddedWindow, String> {
+    let window = manager.find_tab(tab_id).ok_or("标签不存在")?;
+    let accelerator = accelerator.trim();
+    if accelerator.is_empty() {
+        unregister(app, window.hotkey.as_deref());
+        let window = manager.set_hotkey(window.hwnd, None).ok_or("标签不存在")?;
+        changed(app, &window);
+        return Ok(window);
+    }
+
+    let shortcut = keyboard_layout::parse(accelerator).map_err(|e| format!("无效的快捷键 {}: {}", accelerator, e))?;
+    if settings::action_for(&shortcut).is_some() {
+        return Err(format!("快捷键冲突: {} 已用于 WindowHub 自己的操作", accelerator));
+    }
+    if let Some(other) = tab_for(&manager.list(), &shortcut).filter(|hwnd| *hwnd != window.hwnd) {
+        let title = manager.get(other).map(|w| w.label.unwrap_or(w.title)).unwrap_or_default();
+        return Err(format!("快捷键冲突: {} 已绑定到标签 {}", accelerator, title));
+    }
+
+    let unchanged = window.hotkey.as_deref().and_then(|h| keyboard_layout::parse(h).ok()) == Some(shortcut);
+    if !unchanged {
+        app.global_shortcut().register(shortcut).map_err(|e| format!("注册快捷键失败: {} - {:?}", accelerator, e))?;
+        unregister(app, window.hotkey.as_deref());
+    }
+    let window = manager.set_hotkey(window.hwnd, Some(accelerator.to_string())).ok_or("标签不存在")?;
+    info!(target: "shortcuts", "标签快捷键: {} -> hwnd={}", accelerator, window.hwnd);
+    changed(app, &window);
+    Ok(window)
+}
+
+/// 按下的快捷键绑定到的标签
+pub fn tab_for(windows: &[EmbeddedWindow], shortcut: &Shortcut) -> Option<isize> {
+    windows
+        .iter()
+        .find(|w| w.hotkey.as_deref().and_then(|h| keyboard_layout::parse(h).ok()) == Some(*shortcut))
+        .map(|w| w.hwnd)
+}
+
+/// 重新注册所有标签的快捷键 (全局快捷键整体重新注册后调用)
+pub fn register_all(app: &AppHandle, manager: &WindowManager) {
+    for hotkey in manager.list().into_iter().filter_map(|w| w.hotkey) {
+        if let Err(e) = keyboard_layout::parse(&hotkey).and_then(|s| app.global_shortcut().register(s).map_err(|e| format!("{:?}", e))) {
+            warn!(target: "shortcuts", "注册标签快捷键失败: {} - {}", hotkey, e);
+        }
+    }
+}
+
+/// 标签已释放或关闭，注销它的快捷键
+pub fn forget(app: &AppHandle, window: Option<&EmbeddedWindow>) {
+    unregister(app, window.and_then(|w| w.hotkey.as_deref()));
+}
+
+fn unregister(app: &AppHandle, hotkey: Option<&str>) {
+    if let Some(hotkey) = hotkey {
+        if let Err(e) = keyboard_layout::parse(hotkey).and_then(|s| app.global_shortcut().unregister(s).map_err(|e| format!("{:?}", e))) {
+            warn!(target: "shortcuts", "注销标签快捷键失败: {} - {}", hotkey, e);
+        }
+    }
+}
+
+fn changed(app: &AppHandle, window: &EmbeddedWindow) {
+    let _ = app.emit("tab-hotkey-changed", TabHotkey { hwnd: window.hwnd, hotkey: window.hotkey.clone() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use crate::platform::mock::MockPlatform;
+
+    #[test]
+    fn hotkey_follows_the_tab_not_its_position() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let notes = platform.create_window("笔记", "Notepad", Some(r"C:\Windows\notepad.exe"));
+        let vault = platform.create_window("密码库", "KeePass", Some(r"C:\Apps\KeePass.exe"));
+        for hwnd in [notes, vault] {
+            manager.embed(&platform, 0x10, hwnd).unwrap();
+        }
+        manager.set_hotkey(vault, Some("Ctrl+Alt+P".to_string()));
+
+        let pressed = Shortcut::from_str("Alt+Ctrl+P").unwrap();
+        assert_eq!(tab_for(&manager.list(), &pressed), Some(vault));
+        assert_eq!(tab_for(&manager.list(), &Shortcut::from_str("Ctrl+Alt+O").unwrap()), None);
+    }
+}
@@ -0,0 +1,159 @@
+// 标签自定义名称
+// 重命名按 EXE 文件名 + 嵌入时的标题保存在数据库中 (storage.rs，旧版的 tab_labels.json 首次打开数据库时导入)，
+// 重启后再次嵌入相同的窗口时自动恢复名称
+// 多个窗口的 EXE 和标题都相同 (如同时嵌入几个终端) 时，按保存顺序依次分配给尚未使用的名称
+// title 支持 * 通配符，标题经常变化的应用可以在配置档案 (profile.rs) 中写通配的标题后导入
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+const STORAGE_KEY: &str = "tab_labels";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TabLabel {
+    pub exe: String,   // EXE 文件名
+    pub title: String, // 窗口标题，支持 * 通配符
+    pub label: String,
+}
+
+/// tab-label-changed 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct TabLabelChanged {
+    pub hwnd: isize,
+    pub label: Option<String>,
+}
+
+static LABELS: Mutex<Vec<TabLabel>> = Mutex::new(Vec::new());
+
+fn exe_name(exe_path: Option<&str>) -> Option<&str> {
+    exe_path.and_then(|p| std::path::Path::new(p).file_name()).and_then(|f| f.to_str())
+}
+
+// 简单通配符匹配，* 匹配任意长度的字符
+pub(crate) fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+impl TabLabel {
+    fn matches(&self, exe: &str, title: &str) -> bool {
+        self.exe.eq_ignore_ascii_case(exe) && matches_pattern(&self.title, title)
+    }
+}
+
+// 第一个匹配且未被其他标签使用的名称
+fn find_in(labels: &[TabLabel], exe: &str, title: &str, taken: &[String]) -> Option<String> {
+    labels
+        .iter()
+        .find(|l| l.matches(exe, title) && !taken.contains(&l.label))
+        .map(|l| l.label.clone())
+}
+
+/// 新嵌入的窗口应使用的名称，taken 为其他标签已使用的名称
+pub fn find(exe_path: Option<&str>, title: &str, taken: &[String]) -> Option<String> {
+    let exe = exe_name(exe_path)?;
+    find_in(&LABELS.lock().unwrap(), exe, title, taken)
+}
+
+/// 保存标签的新名称，previous 为标签原来的名称，label 为 None 时清除
+/// 无法获取 EXE 路径的窗口只在本次运行中生效
+pub fn set(exe_path: Option<&str>, title: &str, previous: Option<&str>, label: Option<&str>) {
+    let Some(exe) = exe_name(exe_path) else { return };
+    let labels = {
+        let mut labels = LABELS.lock().unwrap();
+        let index = previous.and_then(|p| labels.iter().position(|l| l.label == p && l.matches(exe, title)));
+        match (index, label) {
+            (Some(i), Some(label)) => labels[i].label = label.to_string(),
+            (Some(i), None) => {
+                labels.remove(i);
+            }
+            (None, Some(label)) => labels.push(TabLabel { exe: exe.to_string(), title: title.to_string(), label: label.to_string() }),
+            (None, None) => return,
+        }
+        labels.clone()
+    };
+    info!(target: "settings", "标签名称: {} \"{}\" -> {:?}", exe, title, label);
+    save(&labels);
+}
+
+pub fn list() -> Vec<TabLabel> {
+    LABELS.lock().unwrap().clone()
+}
+
+/// 替换所有标签名称 (导入配置时)，返回是否有变化
+pub fn replace(labels: Vec<TabLabel>) -> bool {
+    let mut current = LABELS.lock().unwrap();
+    if *current == labels {
+        return false;
+    }
+    *current = labels;
+    save(&current);
+    true
+}
+
+fn save(labels: &[TabLabel]) {
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save(STORAGE_KEY, labels) {
+            warn!(target: "settings", "保存标签名称失败: {}", e);
+        }
+    }
+}
+
+/// 从数据库加载 (启动时调用)
+pub fn load() {
+    let Some(labels) = crate::storage::get().and_then(|s| s.load::<Vec<TabLabel>>(STORAGE_KEY)) else { return };
+    info!(target: "settings", "已加载 {} 个标签名称", labels.len());
+    *LABELS.lock().unwrap() = labels;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(exe: &str, title: &str, label: &str) -> TabLabel {
+        TabLabel { exe: exe.to_string(), title: title.to_string(), label: label.to_string() }
+    }
+
+    #[test]
+    fn wildcard_title_patterns() {
+        assert!(matches_pattern("Windows PowerShell", "Windows PowerShell"));
+        assert!(!matches_pattern("Windows PowerShell", "Windows PowerShell 2"));
+        assert!(matches_pattern("* - Visual Studio Code", "main.rs - crate - Visual Studio Code"));
+        assert!(matches_pattern("ssh *@*", "ssh root@server"));
+        assert!(!matches_pattern("ssh *@*", "ssh server"));
+        assert!(matches_pattern("*", ""));
+        assert!(!matches_pattern("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn identical_windows_get_labels_in_saved_order() {
+        let labels = vec![
+            label("WindowsTerminal.exe", "Windows PowerShell", "build"),
+            label("WindowsTerminal.exe", "Windows PowerShell", "logs"),
+            label("WindowsTerminal.exe", "Windows PowerShell", "ssh"),
+        ];
+        let mut taken = Vec::new();
+        for expected in ["build", "logs", "ssh"] {
+            let found = find_in(&labels, "windowsterminal.exe", "Windows PowerShell", &taken).unwrap();
+            assert_eq!(found, expected);
+            taken.push(found);
+        }
+        assert_eq!(find_in(&labels, "WindowsTerminal.exe", "Windows PowerShell", &taken), None);
+        assert_eq!(find_in(&labels, "pwsh.exe", "Windows PowerShell", &[]), None);
+    }
+}
@@ -0,0 +1,151 @@
+// 按编号切换标签
+// Alt+1~8 切换到第 1~8 个标签，Alt+9 切换到最后一个标签 (设置 shortcuts.last_tab_on_nine，关闭后为第 9 个)
+// 超过 9 个标签时先按 Alt+0 开始输入编号，再按住 Alt 依次按数字 (如 Alt+0, Alt+1, Alt+5 切换到第 15 个)：
+// 再加一位就超出标签数时立即切换，否则停顿 CHORD_TIMEOUT 后切换；
+// 已经输入了数字时 Alt+0 是数字 0 (如 Alt+0, Alt+1, Alt+0 切换到第 10 个)，还没有输入数字时再按 Alt+0 取消
+// 切换通过 switch-tab 事件 (载荷为从 1 开始的标签序号) 交给前端
+// 开启 shortcuts.pinned_slots 时，直接按下的数字先交给固定的应用 (见 pinned_apps.rs)
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::debug;
+
+use crate::window_manager::WindowManager;
+
+// 输入编号时两次按键的最长间隔，超过后按已输入的编号切换
+const CHORD_TIMEOUT: Duration = Duration::from_millis(1200);
+
+// 正在输入的编号 (尚未输入数字时为 0) 及输入序号，不在输入时为 None
+static PENDING: Mutex<Option<(u32, u64)>> = Mutex::new(None);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 按下 Alt+0：开始输入编号；已经输入了数字时追加 0，还没有输入数字时取消
+pub fn on_chord(app: &AppHandle) {
+    let mut pending = PENDING.lock().unwrap();
+    let current = *pending;
+    match current {
+        Some((number, _)) if number != 0 => {
+            append(app, pending, number, 0);
+            return;
+        }
+        Some(_) => {
+            *pending = None;
+            debug!(target: "shortcuts", "取消输入标签编号");
+            return;
+        }
+        None => {}
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    *pending = Some((0, generation));
+    debug!(target: "shortcuts", "开始输入标签编号");
+    std::thread::spawn(move || {
+        std::thread::sleep(CHORD_TIMEOUT);
+        let mut pending = PENDING.lock().unwrap();
+        // 一直没有输入数字
+        if matches!(*pending, Some((0, g)) if g == generation) {
+            *pending = None;
+        }
+    });
+}
+
+/// 按下 Alt+1~9：正在输入编号时追加一位，否则直接切换
+pub fn on_digit(app: &AppHandle, digit: u32) {
+    let pending = PENDING.lock().unwrap();
+    let Some((number, _)) = *pending else {
+        drop(pending);
+        if crate::settings::shortcuts().pinned_slots && crate::pinned_apps::activate(app, digit) {
+            return;
+        }
+        let count = app.state::<WindowManager>().len() as u32;
+        let last_on_nine = crate::settings::shortcuts().last_tab_on_nine;
+        if let Some(index) = resolve_digit(digit, count, last_on_nine) {
+            switch(app, index);
+        }
+        return;
+    };
+    append(app, pending, number, digit);
+}
+
+// 在正在输入的编号后追加一位
+fn append(app: &AppHandle, mut pending: MutexGuard<'_, Option<(u32, u64)>>, number: u32, digit: u32) {
+    let count = app.state::<WindowManager>().len() as u32;
+    let number = number.saturating_mul(10).saturating_add(digit);
+    if is_complete(number, count) {
+        *pending = None;
+        drop(pending);
+        switch(app, number);
+        return;
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    *pending = Some((number, generation));
+    drop(pending);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(CHORD_TIMEOUT);
+        let mut pending = PENDING.lock().unwrap();
+        // 期间又输入了数字或已取消
+        if !matches!(*pending, Some((_, g)) if g == generation) {
+            return;
+        }
+        *pending = None;
+        drop(pending);
+        switch(&app, number);
+    });
+}
+
+/// 切换到第 index 个标签 (从 1 开始)
+pub fn activate(app: &AppHandle, index: u32) -> Result<(), String> {
+    let count = app.state::<WindowManager>().len() as u32;
+    if index == 0 || index > count {
+        return Err(format!("标签序号超出范围: {} (共 {} 个标签)", index, count));
+    }
+    switch(app, index);
+    Ok(())
+}
+
+fn switch(app: &AppHandle, index: u32) {
+    if index == 0 || index > app.state::<WindowManager>().len() as u32 {
+        debug!(target: "shortcuts", "没有第 {} 个标签", index);
+        return;
+    }
+    debug!(target: "shortcuts", "发送事件: switch-tab({})", index);
+    let _ = app.emit("switch-tab", index);
+}
+
+// 直接按下 Alt+数字 时切换到的标签序号
+fn resolve_digit(digit: u32, count: u32, last_on_nine: bool) -> Option<u32> {
+    let index = if digit == 9 && last_on_nine { count } else { digit };
+    (1..=count).contains(&index).then_some(index)
+}
+
+// 已输入的编号是否已经唯一确定 (再加一位一定超出标签数)
+fn is_complete(number: u32, count: u32) -> bool {
+    number.saturating_mul(10) > count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_digits_and_chords() {
+        assert_eq!(resolve_digit(3, 15, true), Some(3));
+        assert_eq!(resolve_digit(9, 15, true), Some(15));
+        assert_eq!(resolve_digit(9, 4, true), Some(4));
+        assert_eq!(resolve_digit(9, 15, false), Some(9));
+        assert_eq!(resolve_digit(5, 4, true), None);
+        assert_eq!(resolve_digit(9, 0, true), None);
+
+        // 15 个标签：1 之后还可能是 10~15，2 之后不可能再有第二位
+        assert!(!is_complete(1, 15));
+        assert!(is_complete(2, 15));
+        assert!(is_complete(15, 15));
+        assert!(!is_complete(0, 15));
+        // 1 之后的 0 是第 10 个
+        assert!(is_complete(10, 15));
+        assert!(!is_complete(1, 25));
+    }
+}
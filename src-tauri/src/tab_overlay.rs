@@ -0,0 +1,370 @@
+// 标签上的原生状态标记
+// 嵌入窗口是主窗口的子窗口，盖在网页上面，前端画不到标签的画面上，所以由后端为需要标记的标签各创建一个覆盖窗口：
+// - 分屏 (停靠、监控墙) 显示多个标签时，当前标签外圈的彩色边框
+// - 标签未响应时顶部的 "未响应" 横幅
+// - 正在录制宏的标签右上角的红点
+// 覆盖窗口是主窗口拥有的分层弹出窗口 (总在主窗口之上，但不置顶于其他程序)，鼠标穿透，
+// 用窗口区域只保留边框、横幅和圆点，其余部分看不到也点不到
+// 所有覆盖窗口属于一个单独的线程 (第一次需要时创建)，由定时器跟随标签的位置，
+// 标签隐藏 (切换到其他标签) 或主窗口最小化时一起隐藏
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+
+#[cfg(windows)]
+use std::sync::OnceLock;
+#[cfg(windows)]
+use tracing::warn;
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM},
+        Graphics::Gdi::{
+            BeginPaint, CombineRgn, CreateEllipticRgn, CreateRectRgn, CreateSolidBrush, DeleteObject, DrawTextW, EndPaint, FillRect, SetBkMode,
+            SetTextColor, SetWindowRgn, DT_CENTER, DT_SINGLELINE, DT_VCENTER, PAINTSTRUCT, RGN_OR, TRANSPARENT,
+        },
+        System::LibraryLoader::GetModuleHandleW,
+        System::Threading::GetCurrentThreadId,
+        UI::WindowsAndMessaging::*,
+    },
+};
+
+// 要画的部分 (位标志，保存在覆盖窗口的 GWLP_USERDATA 中供绘制时读取)
+const FOCUS: u8 = 1;
+const HUNG: u8 = 2;
+const RECORDING: u8 = 4;
+
+// 尺寸 (物理像素)
+const BORDER: i32 = 3;
+const BANNER_HEIGHT: i32 = 28;
+const DOT_SIZE: i32 = 12;
+const DOT_MARGIN: i32 = 10;
+
+// 颜色 (与前端一致，COLORREF 为 0x00BBGGRR)
+#[cfg(windows)]
+const FOCUS_COLOR: COLORREF = COLORREF(0x00FA_B489);
+#[cfg(windows)]
+const ALERT_COLOR: COLORREF = COLORREF(0x00A8_8BF3);
+#[cfg(windows)]
+const BANNER_TEXT_COLOR: COLORREF = COLORREF(0x002E_1E1E);
+
+// 跟随标签位置的间隔
+#[cfg(windows)]
+const FOLLOW_INTERVAL_MS: u32 = 50;
+
+// 线程消息：立即刷新 (标签切换、状态变化时)
+#[cfg(windows)]
+const WM_REFRESH: u32 = WM_APP + 1;
+
+/// 覆盖窗口中的一块
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    Border(i32, i32, i32, i32), // 左, 上, 右, 下 (覆盖窗口的客户区坐标)
+    Banner(i32, i32, i32, i32),
+    Dot(i32, i32, i32, i32),
+}
+
+// 主窗口 (覆盖窗口的所有者)
+static HOST: AtomicIsize = AtomicIsize::new(0);
+
+// 未响应和正在录制的标签，以及对应的标志
+static MARKS: Mutex<Vec<(isize, u8)>> = Mutex::new(Vec::new());
+
+// 覆盖线程 ID
+#[cfg(windows)]
+static THREAD: OnceLock<u32> = OnceLock::new();
+
+// 已创建的覆盖窗口：(标签, 覆盖窗口, 上次的大小和标志)，只在覆盖线程中修改
+#[cfg(windows)]
+static OVERLAYS: Mutex<Vec<(isize, isize, (i32, i32, u8))>> = Mutex::new(Vec::new());
+
+/// 记录主窗口 (setup 时调用)，覆盖线程在第一次需要时才创建
+pub fn init(host: isize) {
+    HOST.store(host, Ordering::SeqCst);
+}
+
+/// 标签是否未响应 (对账时调用)
+pub fn set_hung(tab: isize, hung: bool) {
+    set_mark(tab, HUNG, hung);
+}
+
+/// 标签是否正在录制宏
+pub fn set_recording(tab: isize, recording: bool) {
+    set_mark(tab, RECORDING, recording);
+}
+
+/// 标签已释放或关闭
+pub fn forget(tab: isize) {
+    let removed = {
+        let mut marks = MARKS.lock().unwrap();
+        let before = marks.len();
+        marks.retain(|(h, _)| *h != tab);
+        marks.len() != before
+    };
+    if removed {
+        refresh();
+    }
+}
+
+/// 按当前标签和状态立即更新覆盖窗口 (切换标签时调用)
+pub fn refresh() {
+    #[cfg(windows)]
+    unsafe {
+        if HOST.load(Ordering::SeqCst) != 0 {
+            let _ = PostThreadMessageW(thread(), WM_REFRESH, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+fn set_mark(tab: isize, flag: u8, on: bool) {
+    let changed = {
+        let mut marks = MARKS.lock().unwrap();
+        let before = marks.iter().find(|(h, _)| *h == tab).map_or(0, |(_, f)| *f);
+        let after = if on { before | flag } else { before & !flag };
+        marks.retain(|(h, _)| *h != tab);
+        if after != 0 {
+            marks.push((tab, after));
+        }
+        before != after
+    };
+    if changed {
+        refresh();
+    }
+}
+
+/// 需要覆盖窗口的标签和要画的部分；split 为当前标签是否和其他标签一起显示 (停靠或监控墙)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn wanted(active: isize, split: bool, marks: &[(isize, u8)]) -> Vec<(isize, u8)> {
+    let mut wanted = marks.to_vec();
+    if split && active != 0 {
+        match wanted.iter_mut().find(|(h, _)| *h == active) {
+            Some((_, flags)) => *flags |= FOCUS,
+            None => wanted.push((active, FOCUS)),
+        }
+    }
+    wanted
+}
+
+/// 覆盖窗口的各块 (width、height 为标签的大小)：边框画在标签外圈，横幅和圆点画在标签内
+/// 覆盖窗口比标签大 BORDER，坐标相对覆盖窗口的左上角
+#[cfg_attr(not(windows), allow(dead_code))]
+fn shapes(width: i32, height: i32, flags: u8) -> Vec<Shape> {
+    let (outer_w, outer_h) = (width + BORDER * 2, height + BORDER * 2);
+    let mut shapes = Vec::new();
+    if flags & FOCUS != 0 {
+        shapes.push(Shape::Border(0, 0, outer_w, BORDER));
+        shapes.push(Shape::Border(0, outer_h - BORDER, outer_w, outer_h));
+        shapes.push(Shape::Border(0, BORDER, BORDER, outer_h - BORDER));
+        shapes.push(Shape::Border(outer_w - BORDER, BORDER, outer_w, outer_h - BORDER));
+    }
+    if flags & HUNG != 0 {
+        shapes.push(Shape::Banner(BORDER, BORDER, BORDER + width, BORDER + BANNER_HEIGHT.min(height)));
+    }
+    if flags & RECORDING != 0 {
+        // 有横幅时圆点放到横幅下面
+        let top = BORDER + DOT_MARGIN + if flags & HUNG != 0 { BANNER_HEIGHT } else { 0 };
+        let right = BORDER + width - DOT_MARGIN;
+        shapes.push(Shape::Dot(right - DOT_SIZE, top, right, top + DOT_SIZE));
+    }
+    shapes
+}
+
+// 覆盖线程 (第一次调用时启动，等线程消息队列建立后返回)
+#[cfg(windows)]
+fn thread() -> u32 {
+    *THREAD.get_or_init(|| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || unsafe {
+            register_class();
+            let mut msg = MSG::default();
+            let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+            let _ = tx.send(GetCurrentThreadId());
+            SetTimer(None, 0, FOLLOW_INTERVAL_MS, None);
+            crate::resources::register(crate::resources::ResourceKind::Timer, "tab_overlay", 1, None, None);
+
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+                if msg.hwnd.is_invalid() && (msg.message == WM_REFRESH || msg.message == WM_TIMER) {
+                    update();
+                    continue;
+                }
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+        rx.recv().unwrap_or(0)
+    })
+}
+
+#[cfg(windows)]
+unsafe fn register_class() {
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(overlay_proc),
+        hInstance: GetModuleHandleW(None).unwrap_or_default().into(),
+        lpszClassName: w!("WindowHubTabOverlay"),
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+}
+
+// 创建、移动或销毁覆盖窗口，使其与标签的状态一致
+#[cfg(windows)]
+unsafe fn update() {
+    let host = HWND(HOST.load(Ordering::SeqCst) as *mut _);
+    let active = crate::host_layout::active();
+    let docked = crate::zones::docked();
+    let split = crate::zones::maximized() != Some(active)
+        && ((docked.len() > 1 && docked.contains(&active)) || (crate::grid::contains(active) && crate::grid::members().len() > 1));
+    let wanted = wanted(active, split, &MARKS.lock().unwrap());
+    let host_shown = IsWindowVisible(host).as_bool() && !IsIconic(host).as_bool();
+
+    let mut overlays = OVERLAYS.lock().unwrap();
+    overlays.retain(|(tab, overlay, _)| {
+        let keep = wanted.iter().any(|(h, _)| h == tab) && IsWindow(HWND(*tab as *mut _)).as_bool();
+        if !keep {
+            let _ = DestroyWindow(HWND(*overlay as *mut _));
+        }
+        keep
+    });
+    for &(tab, flags) in &wanted {
+        let tab_hwnd = HWND(tab as *mut _);
+        let mut rect = RECT::default();
+        let shown = host_shown && !crate::tab_view::is_hidden(tab) && IsWindowVisible(tab_hwnd).as_bool() && GetWindowRect(tab_hwnd, &mut rect).is_ok();
+        let index = match overlays.iter().position(|(h, _, _)| *h == tab) {
+            Some(index) => index,
+            None if !shown => continue,
+            None => match create(host) {
+                Some(overlay) => {
+                    overlays.push((tab, overlay.0 as isize, (0, 0, 0)));
+                    overlays.len() - 1
+                }
+                None => continue,
+            },
+        };
+        let overlay = HWND(overlays[index].1 as *mut _);
+        if !shown {
+            let _ = ShowWindow(overlay, SW_HIDE);
+            continue;
+        }
+        let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+        if overlays[index].2 != (width, height, flags) {
+            overlays[index].2 = (width, height, flags);
+            SetWindowLongPtrW(overlay, GWLP_USERDATA, flags as isize);
+            set_region(overlay, &shapes(width, height, flags));
+        }
+        let _ = SetWindowPos(
+            overlay,
+            HWND::default(),
+            rect.left - BORDER,
+            rect.top - BORDER,
+            width + BORDER * 2,
+            height + BORDER * 2,
+            SWP_NOACTIVATE | SWP_NOZORDER | SWP_SHOWWINDOW,
+        );
+    }
+}
+
+#[cfg(windows)]
+unsafe fn create(host: HWND) -> Option<HWND> {
+    let overlay = CreateWindowExW(
+        WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+        w!("WindowHubTabOverlay"),
+        w!(""),
+        WS_POPUP,
+        0,
+        0,
+        0,
+        0,
+        host,
+        None,
+        GetModuleHandleW(None).unwrap_or_default(),
+        None,
+    );
+    match overlay {
+        Ok(overlay) => {
+            let _ = SetLayeredWindowAttributes(overlay, COLORREF(0), 255, LWA_ALPHA);
+            Some(overlay)
+        }
+        Err(e) => {
+            warn!(target: "embed", "创建标签状态覆盖窗口失败: {:?}", e);
+            None
+        }
+    }
+}
+
+// 窗口区域只保留各块，其余部分透明且不接收鼠标
+#[cfg(windows)]
+unsafe fn set_region(overlay: HWND, shapes: &[Shape]) {
+    let region = CreateRectRgn(0, 0, 0, 0);
+    for shape in shapes {
+        let part = match *shape {
+            Shape::Border(l, t, r, b) | Shape::Banner(l, t, r, b) => CreateRectRgn(l, t, r, b),
+            Shape::Dot(l, t, r, b) => CreateEllipticRgn(l, t, r + 1, b + 1),
+        };
+        CombineRgn(region, region, part, RGN_OR);
+        let _ = DeleteObject(part);
+    }
+    // 区域交给系统管理，不需要删除
+    SetWindowRgn(overlay, region, true);
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn overlay_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg == WM_PAINT {
+        paint(hwnd);
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+#[cfg(windows)]
+unsafe fn paint(overlay: HWND) {
+    let mut ps = PAINTSTRUCT::default();
+    let hdc = BeginPaint(overlay, &mut ps);
+    let mut client = RECT::default();
+    let _ = GetClientRect(overlay, &mut client);
+    let flags = GetWindowLongPtrW(overlay, GWLP_USERDATA) as u8;
+    let (width, height) = (client.right - BORDER * 2, client.bottom - BORDER * 2);
+    for shape in shapes(width, height, flags) {
+        let (rect, color) = match shape {
+            Shape::Border(l, t, r, b) => (RECT { left: l, top: t, right: r, bottom: b }, FOCUS_COLOR),
+            Shape::Banner(l, t, r, b) | Shape::Dot(l, t, r, b) => (RECT { left: l, top: t, right: r, bottom: b }, ALERT_COLOR),
+        };
+        let brush = CreateSolidBrush(color);
+        FillRect(hdc, &rect, brush);
+        let _ = DeleteObject(brush);
+        if let Shape::Banner(..) = shape {
+            let mut text: Vec<u16> = "未响应".encode_utf16().collect();
+            let mut rect = rect;
+            SetBkMode(hdc, TRANSPARENT);
+            SetTextColor(hdc, BANNER_TEXT_COLOR);
+            DrawTextW(hdc, &mut text, &mut rect, DT_CENTER | DT_VCENTER | DT_SINGLELINE);
+        }
+    }
+    let _ = EndPaint(overlay, &ps);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_border_only_when_split() {
+        let marks = [(2, HUNG)];
+        assert_eq!(wanted(1, false, &marks), vec![(2, HUNG)]);
+        assert_eq!(wanted(1, true, &marks), vec![(2, HUNG), (1, FOCUS)]);
+        assert_eq!(wanted(2, true, &marks), vec![(2, HUNG | FOCUS)]);
+    }
+
+    #[test]
+    fn dot_moves_below_banner() {
+        let shapes = shapes(400, 300, HUNG | RECORDING);
+        assert_eq!(shapes[0], Shape::Banner(BORDER, BORDER, BORDER + 400, BORDER + BANNER_HEIGHT));
+        let Shape::Dot(_, top, right, _) = shapes[1] else { panic!("缺少圆点") };
+        assert_eq!(top, BORDER + DOT_MARGIN + BANNER_HEIGHT);
+        assert_eq!(right, BORDER + 400 - DOT_MARGIN);
+        assert_eq!(self::shapes(400, 300, FOCUS).len(), 4);
+    }
+}
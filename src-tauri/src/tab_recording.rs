@@ -0,0 +1,409 @@
+// 录制标签画面为 MP4 (start_tab_recording / stop_tab_recording)
+// 只录制一个标签，不录整个桌面。Windows.Graphics.Capture 不能捕获子窗口，所以捕获主窗口，
+// 每帧按标签在主窗口中的位置裁剪；捕获的是主窗口自己的画面，其他程序的窗口挡在上面也不影响
+// 裁剪出的画面复制到 CPU 可读的纹理后交给 Media Foundation 的 Sink Writer，编码为 H.264 写入 MP4：
+// - 视频大小为开始录制时标签的大小 (H.264 要求宽高为偶数)，录制中标签变大时只录左上部分
+// - 标签被切走 (隐藏) 或主窗口最小化时没有新画面，视频停在之前的画面
+// - 标签被关闭或弹出时自动结束
+// 占用一个截图流名额 (见 budget.rs)，同时只能录制一个标签，开始和结束时发送 tab-recording-changed，录制中的标签显示红点 (tab_overlay.rs)
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use tauri::AppHandle;
+
+use crate::budget::StreamSlot;
+
+#[cfg(windows)]
+use std::time::{Duration, Instant};
+#[cfg(windows)]
+use tauri::Emitter;
+#[cfg(windows)]
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use windows::{
+    core::{Interface, HSTRING, PCWSTR},
+    Graphics::Capture::{Direct3D11CaptureFrame, Direct3D11CaptureFramePool, GraphicsCaptureItem, GraphicsCaptureSession},
+    Graphics::DirectX::{Direct3D11::IDirect3DDevice, DirectXPixelFormat},
+    Graphics::SizeInt32,
+    Win32::{
+        Foundation::{HMODULE, HWND, RECT},
+        Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE,
+        Graphics::Direct3D11::*,
+        Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS},
+        Graphics::Dxgi::Common::{DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_SAMPLE_DESC},
+        Graphics::Dxgi::IDXGIDevice,
+        Media::MediaFoundation::*,
+        System::WinRT::Direct3D11::{CreateDirect3D11DeviceFromDXGIDevice, IDirect3DDxgiInterfaceAccess},
+        System::WinRT::Graphics::Capture::IGraphicsCaptureItemInterop,
+        System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED},
+        UI::WindowsAndMessaging::{GetAncestor, GetWindowRect, IsWindow, GA_ROOT},
+    },
+};
+
+// 帧率
+const FPS: u32 = 30;
+
+// 视频最大的宽高 (H.264 编码器的限制)
+const MAX_SIZE: u32 = 4096;
+
+/// 录制结束后的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingSummary {
+    pub path: String,
+    pub frames: u64,
+    pub duration_ms: u64,
+}
+
+/// tab-recording-changed 事件的载荷
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct TabRecording {
+    pub hwnd: isize,
+    pub recording: bool,
+    pub path: String,
+}
+
+struct Session {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Result<RecordingSummary, String>>,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+/// 开始录制标签 hwnd 到 path (.mp4)，已有录制时返回错误
+pub fn start(app: &AppHandle, hwnd: isize, path: &str) -> Result<(), String> {
+    let path = check_path(path)?;
+    let mut session = SESSION.lock().unwrap();
+    if session.as_ref().is_some_and(|s| !s.thread.is_finished()) {
+        return Err("已经在录制标签".to_string());
+    }
+    let slot = crate::budget::acquire_stream()?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = spawn(app.clone(), hwnd, path, stop.clone(), slot)?;
+    *session = Some(Session { stop, thread });
+    Ok(())
+}
+
+/// 结束录制并等待文件写完 (标签关闭后已自动结束的录制返回其结果)
+pub fn stop() -> Result<RecordingSummary, String> {
+    let session = SESSION.lock().unwrap().take().ok_or("没有正在录制的标签")?;
+    session.stop.store(true, Ordering::SeqCst);
+    session.thread.join().map_err(|_| "录制线程异常退出".to_string())?
+}
+
+fn check_path(path: &str) -> Result<String, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("保存路径不能为空".to_string());
+    }
+    if !path.to_ascii_lowercase().ends_with(".mp4") {
+        return Err("只能保存为 .mp4 文件".to_string());
+    }
+    Ok(path.to_string())
+}
+
+/// 视频大小：宽高取偶数，不超过 MAX_SIZE；太小无法编码时为 None
+#[cfg_attr(not(windows), allow(dead_code))]
+fn video_size(width: i32, height: i32) -> Option<(u32, u32)> {
+    let even = |v: i32| (v.max(0) as u32).min(MAX_SIZE) & !1;
+    let (width, height) = (even(width), even(height));
+    (width >= 16 && height >= 16).then_some((width, height))
+}
+
+/// 码率 (bps)：每像素每帧约 0.1 bit，屏幕内容文字多、变化少，足够清晰
+#[cfg_attr(not(windows), allow(dead_code))]
+fn bitrate(width: u32, height: u32) -> u32 {
+    (width as u64 * height as u64 * FPS as u64 / 10).clamp(1_000_000, 40_000_000) as u32
+}
+
+#[cfg(windows)]
+fn spawn(app: AppHandle, hwnd: isize, path: String, stop: Arc<AtomicBool>, slot: StreamSlot) -> Result<JoinHandle<Result<RecordingSummary, String>>, String> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let thread = std::thread::spawn(move || unsafe {
+        // 录制线程结束时归还截图流名额
+        let _slot = slot;
+        let _ = RoInitialize(RO_INIT_MULTITHREADED);
+        if let Err(e) = MFStartup(MF_VERSION, MFSTARTUP_FULL) {
+            let message = format!("无法初始化 Media Foundation: {}", e);
+            let _ = tx.send(Err(message.clone()));
+            return Err(message);
+        }
+        let result = match Recorder::new(hwnd, &path) {
+            Ok(recorder) => {
+                let _ = tx.send(Ok(()));
+                info!(target: "embed", "开始录制标签: hwnd={}, {}x{} -> {}", hwnd, recorder.width, recorder.height, path);
+                crate::tab_overlay::set_recording(hwnd, true);
+                let _ = app.emit("tab-recording-changed", TabRecording { hwnd, recording: true, path: path.clone() });
+                let result = recorder.run(&stop);
+                crate::tab_overlay::set_recording(hwnd, false);
+                let _ = app.emit("tab-recording-changed", TabRecording { hwnd, recording: false, path: path.clone() });
+                result
+            }
+            Err(e) => {
+                let _ = tx.send(Err(e.clone()));
+                Err(e)
+            }
+        };
+        let _ = MFShutdown();
+        match &result {
+            Ok(summary) => info!(target: "embed", "录制结束: {} ({} 帧, {} 毫秒)", summary.path, summary.frames, summary.duration_ms),
+            Err(e) => warn!(target: "embed", "录制标签失败: {}", e),
+        }
+        result
+    });
+    rx.recv().map_err(|_| "录制线程异常退出".to_string())??;
+    Ok(thread)
+}
+
+#[cfg(not(windows))]
+fn spawn(_app: AppHandle, _hwnd: isize, _path: String, _stop: Arc<AtomicBool>, _slot: StreamSlot) -> Result<JoinHandle<Result<RecordingSummary, String>>, String> {
+    Err("当前平台不支持录制标签".to_string())
+}
+
+// 录制一个标签所需的捕获和编码对象，只在录制线程中使用
+#[cfg(windows)]
+struct Recorder {
+    tab: HWND,
+    host: HWND,
+    path: String,
+    width: u32,
+    height: u32,
+    context: ID3D11DeviceContext,
+    device: IDirect3DDevice,
+    pool: Direct3D11CaptureFramePool,
+    pool_size: SizeInt32,
+    session: GraphicsCaptureSession,
+    staging: ID3D11Texture2D,
+    writer: IMFSinkWriter,
+    stream: u32,
+}
+
+#[cfg(windows)]
+fn failed(context: &'static str) -> impl Fn(windows::core::Error) -> String {
+    move |e| format!("{}: {}", context, e)
+}
+
+#[cfg(windows)]
+impl Recorder {
+    unsafe fn new(hwnd: isize, path: &str) -> Result<Recorder, String> {
+        let tab = HWND(hwnd as *mut _);
+        let host = GetAncestor(tab, GA_ROOT);
+        if !IsWindow(tab).as_bool() || host.is_invalid() || host == tab {
+            return Err("标签不存在".to_string());
+        }
+        let rect = window_rect(tab).ok_or("标签不存在")?;
+        let (width, height) = video_size(rect.right - rect.left, rect.bottom - rect.top).ok_or("标签太小，无法录制")?;
+
+        let mut d3d = None;
+        let mut context = None;
+        D3D11CreateDevice(
+            None,
+            D3D_DRIVER_TYPE_HARDWARE,
+            HMODULE::default(),
+            D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+            None,
+            D3D11_SDK_VERSION,
+            Some(&mut d3d),
+            None,
+            Some(&mut context),
+        )
+        .map_err(failed("无法创建 Direct3D 设备"))?;
+        let (d3d, context) = d3d.zip(context).ok_or("无法创建 Direct3D 设备")?;
+        let dxgi: IDXGIDevice = d3d.cast().map_err(failed("无法创建 Direct3D 设备"))?;
+        let device: IDirect3DDevice = CreateDirect3D11DeviceFromDXGIDevice(&dxgi)
+            .and_then(|d| d.cast())
+            .map_err(failed("无法创建 Direct3D 设备"))?;
+
+        let interop = windows::core::factory::<GraphicsCaptureItem, IGraphicsCaptureItemInterop>().map_err(failed("系统不支持窗口捕获"))?;
+        let item: GraphicsCaptureItem = interop.CreateForWindow(host).map_err(failed("无法捕获主窗口"))?;
+        let size = item.Size().map_err(failed("无法捕获主窗口"))?;
+        let pool = Direct3D11CaptureFramePool::CreateFreeThreaded(&device, DirectXPixelFormat::B8G8R8A8UIntNormalized, 2, size)
+            .map_err(failed("无法捕获主窗口"))?;
+        let session = pool.CreateCaptureSession(&item).map_err(failed("无法捕获主窗口"))?;
+        // 较早的系统不支持以下设置
+        let _ = session.SetIsCursorCaptureEnabled(true);
+        let _ = session.SetIsBorderRequired(false);
+
+        let desc = D3D11_TEXTURE2D_DESC {
+            Width: width,
+            Height: height,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+            Usage: D3D11_USAGE_STAGING,
+            BindFlags: 0,
+            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+            MiscFlags: 0,
+        };
+        let mut staging = None;
+        d3d.CreateTexture2D(&desc, None, Some(&mut staging)).map_err(failed("无法创建纹理"))?;
+        let staging = staging.ok_or("无法创建纹理")?;
+
+        let (writer, stream) = create_writer(path, width, height)?;
+        session.StartCapture().map_err(failed("无法开始捕获"))?;
+        Ok(Recorder { tab, host, path: path.to_string(), width, height, context, device, pool, pool_size: size, session, staging, writer, stream })
+    }
+
+    // 按帧率取最新的画面写入视频，直到 stop 或标签不再嵌入
+    unsafe fn run(&self, stop: &AtomicBool) -> Result<RecordingSummary, String> {
+        let started = Instant::now();
+        let interval = Duration::from_secs(1) / FPS;
+        let mut frames = 0u64;
+        let mut pool_size = self.pool_size;
+        while !stop.load(Ordering::SeqCst) {
+            std::thread::sleep(interval);
+            if !IsWindow(self.tab).as_bool() || GetAncestor(self.tab, GA_ROOT) != self.host {
+                info!(target: "embed", "录制的标签已关闭或弹出，结束录制");
+                break;
+            }
+            let mut latest = None;
+            while let Ok(frame) = self.pool.TryGetNextFrame() {
+                latest = Some(frame);
+            }
+            let Some(frame) = latest else { continue };
+            if crate::tab_view::is_hidden(self.tab.0 as isize) || !crate::session_watch::is_active() {
+                continue;
+            }
+            // 主窗口大小变化后按新大小重建帧缓冲
+            let size = frame.ContentSize().map_err(failed("读取画面失败"))?;
+            if size != pool_size {
+                self.pool
+                    .Recreate(&self.device, DirectXPixelFormat::B8G8R8A8UIntNormalized, 2, size)
+                    .map_err(failed("读取画面失败"))?;
+                pool_size = size;
+            }
+            if self.copy_frame(&frame, size)? {
+                self.write_sample(started.elapsed(), interval)?;
+                frames += 1;
+            }
+        }
+        let _ = self.session.Close();
+        let _ = self.pool.Close();
+        self.writer.Finalize().map_err(failed("写入视频文件失败"))?;
+        Ok(RecordingSummary { path: self.path.clone(), frames, duration_ms: started.elapsed().as_millis() as u64 })
+    }
+
+    // 把标签所在的区域从主窗口的画面复制到暂存纹理，标签不在画面内时返回 false
+    unsafe fn copy_frame(&self, frame: &Direct3D11CaptureFrame, size: SizeInt32) -> Result<bool, String> {
+        let (Some(host), Some(tab)) = (frame_bounds(self.host), window_rect(self.tab)) else { return Ok(false) };
+        let left = tab.left - host.left;
+        let top = tab.top - host.top;
+        let area = D3D11_BOX {
+            left: left.max(0) as u32,
+            top: top.max(0) as u32,
+            front: 0,
+            right: (left + self.width as i32).min(size.Width).max(0) as u32,
+            bottom: (top + self.height as i32).min(size.Height).max(0) as u32,
+            back: 1,
+        };
+        if area.right <= area.left || area.bottom <= area.top {
+            return Ok(false);
+        }
+        let texture: ID3D11Texture2D = frame
+            .Surface()
+            .and_then(|s| s.cast::<IDirect3DDxgiInterfaceAccess>())
+            .and_then(|s| s.GetInterface())
+            .map_err(failed("读取画面失败"))?;
+        self.context.CopySubresourceRegion(&self.staging, 0, 0, 0, 0, &texture, 0, Some(&area));
+        Ok(true)
+    }
+
+    // 暂存纹理的内容作为一帧写入视频
+    unsafe fn write_sample(&self, at: Duration, duration: Duration) -> Result<(), String> {
+        let stride = self.width * 4;
+        let length = stride * self.height;
+        let buffer = MFCreateMemoryBuffer(length).map_err(failed("写入视频失败"))?;
+        let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+        self.context.Map(&self.staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped)).map_err(failed("读取画面失败"))?;
+        let mut dest = std::ptr::null_mut();
+        let locked = buffer.Lock(&mut dest, None, None);
+        if locked.is_ok() {
+            for row in 0..self.height as usize {
+                let src = (mapped.pData as *const u8).add(row * mapped.RowPitch as usize);
+                std::ptr::copy_nonoverlapping(src, dest.add(row * stride as usize), stride as usize);
+            }
+            let _ = buffer.Unlock();
+        }
+        self.context.Unmap(&self.staging, 0);
+        locked.map_err(failed("写入视频失败"))?;
+        buffer.SetCurrentLength(length).map_err(failed("写入视频失败"))?;
+
+        let sample = MFCreateSample().map_err(failed("写入视频失败"))?;
+        sample.AddBuffer(&buffer).map_err(failed("写入视频失败"))?;
+        // Media Foundation 的时间单位为 100 纳秒
+        sample.SetSampleTime((at.as_nanos() / 100) as i64).map_err(failed("写入视频失败"))?;
+        sample.SetSampleDuration((duration.as_nanos() / 100) as i64).map_err(failed("写入视频失败"))?;
+        self.writer.WriteSample(self.stream, &sample).map_err(failed("写入视频失败"))
+    }
+}
+
+// H.264 编码写入 MP4 的 Sink Writer，输入为自上而下的 32 位 BGRA 画面，返回写入器和视频流序号
+#[cfg(windows)]
+unsafe fn create_writer(path: &str, width: u32, height: u32) -> Result<(IMFSinkWriter, u32), String> {
+    let pack = |high: u32, low: u32| ((high as u64) << 32) | low as u64;
+    let set_video_format = |media_type: &IMFMediaType, subtype: &windows::core::GUID| -> windows::core::Result<()> {
+        media_type.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video)?;
+        media_type.SetGUID(&MF_MT_SUBTYPE, subtype)?;
+        media_type.SetUINT32(&MF_MT_INTERLACE_MODE, MFVideoInterlace_Progressive.0 as u32)?;
+        media_type.SetUINT64(&MF_MT_FRAME_SIZE, pack(width, height))?;
+        media_type.SetUINT64(&MF_MT_FRAME_RATE, pack(FPS, 1))?;
+        media_type.SetUINT64(&MF_MT_PIXEL_ASPECT_RATIO, pack(1, 1))
+    };
+
+    let mut attributes = None;
+    MFCreateAttributes(&mut attributes, 1).map_err(failed("无法创建视频文件"))?;
+    let attributes = attributes.ok_or("无法创建视频文件")?;
+    attributes.SetUINT32(&MF_READWRITE_ENABLE_HARDWARE_TRANSFORMS, 1).map_err(failed("无法创建视频文件"))?;
+    let url = HSTRING::from(path);
+    let writer = MFCreateSinkWriterFromURL(PCWSTR(url.as_ptr()), None::<&IMFByteStream>, &attributes).map_err(failed("无法创建视频文件"))?;
+
+    let output = MFCreateMediaType().map_err(failed("无法创建视频文件"))?;
+    set_video_format(&output, &MFVideoFormat_H264)
+        .and_then(|_| output.SetUINT32(&MF_MT_AVG_BITRATE, bitrate(width, height)))
+        .map_err(failed("无法设置视频格式"))?;
+    let stream = writer.AddStream(&output).map_err(failed("无法设置视频格式"))?;
+
+    let input = MFCreateMediaType().map_err(failed("无法创建视频文件"))?;
+    set_video_format(&input, &MFVideoFormat_RGB32)
+        .and_then(|_| input.SetUINT32(&MF_MT_DEFAULT_STRIDE, width * 4))
+        .map_err(failed("无法设置视频格式"))?;
+    writer.SetInputMediaType(stream, &input, None::<&IMFAttributes>).map_err(failed("无法设置视频格式"))?;
+    writer.BeginWriting().map_err(failed("无法开始写入视频"))?;
+    Ok((writer, stream))
+}
+
+#[cfg(windows)]
+unsafe fn window_rect(hwnd: HWND) -> Option<RECT> {
+    let mut rect = RECT::default();
+    GetWindowRect(hwnd, &mut rect).ok()?;
+    Some(rect)
+}
+
+// 捕获画面对应的区域 (主窗口可见的边框，不含 Win10 以上的透明阴影)
+#[cfg(windows)]
+unsafe fn frame_bounds(hwnd: HWND) -> Option<RECT> {
+    let mut rect = RECT::default();
+    let size = std::mem::size_of::<RECT>() as u32;
+    if DwmGetWindowAttribute(hwnd, DWMWA_EXTENDED_FRAME_BOUNDS, &mut rect as *mut _ as *mut _, size).is_ok() {
+        return Some(rect);
+    }
+    window_rect(hwnd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn video_size_is_even_and_bounded() {
+        assert_eq!(video_size(1281, 721), Some((1280, 720)));
+        assert_eq!(video_size(9000, 600), Some((MAX_SIZE, 600)));
+        assert_eq!(video_size(10, 600), None);
+        assert!(check_path(r"C:\demo.MP4").is_ok());
+        assert!(check_path(r"C:\demo.avi").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+// 标签的生命周期状态
+// 标签当前处于哪个阶段原来分散在前端的标志 (lost / reattaching / hung) 和各模块的记录里，
+// 嵌入、重新嵌入、存活检查同时发生时容易不一致。这里按标签 ID 记录唯一的状态，只接受合法的状态转换：
+// - Launching：已重新启动应用，等待它的新窗口
+// - Waiting：窗口已失效，标签等待新窗口 (不重新启动，或等待超时后保持此状态直到再次尝试或关闭)
+// - Embedded / Hidden：已嵌入，当前显示 / 被其他标签挡住隐藏 (tab_view.rs)
+// - Hung：窗口未响应 (存活检查时发现)，恢复响应后回到 Embedded 或 Hidden
+// - Released / Closed：弹出为独立窗口 / 关闭，之后不再记录
+// 状态变化时发送 tab-state-changed (载荷为 TabStateChanged)，前端可用 get_tab_states 重新同步
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TabState {
+    Launching,
+    Waiting,
+    Embedded,
+    Hidden,
+    Hung,
+    Released,
+    Closed,
+}
+
+impl TabState {
+    fn is_final(self) -> bool {
+        matches!(self, TabState::Released | TabState::Closed)
+    }
+}
+
+/// 标签及其状态 (get_tab_states 返回)
+#[derive(Debug, Clone, Serialize)]
+pub struct TabStateEntry {
+    pub tab_id: u64,
+    pub hwnd: isize, // 当前 (或失效前) 的窗口句柄
+    pub state: TabState,
+}
+
+/// tab-state-changed 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct TabStateChanged {
+    pub tab_id: u64,
+    pub hwnd: isize,
+    pub from: Option<TabState>, // 新标签为 None
+    pub to: TabState,
+}
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static STATES: Mutex<Vec<TabStateEntry>> = Mutex::new(Vec::new());
+
+/// 保存 AppHandle (setup 时调用一次)
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// 所有标签的当前状态
+pub fn list() -> Vec<TabStateEntry> {
+    STATES.lock().unwrap().clone()
+}
+
+/// 转换标签的状态，不合法的转换被拒绝并返回错误；状态不变时什么也不做
+pub fn transition(tab_id: u64, hwnd: isize, to: TabState) -> Result<(), String> {
+    let changed = {
+        let mut states = STATES.lock().unwrap();
+        let index = states.iter().position(|e| e.tab_id == tab_id);
+        let from = index.map(|i| states[i].state);
+        if from == Some(to) {
+            if let Some(i) = index {
+                states[i].hwnd = hwnd;
+            }
+            return Ok(());
+        }
+        if !is_allowed(from, to) {
+            let message = format!("标签 {} 不能从 {:?} 转换为 {:?}", tab_id, from, to);
+            warn!(target: "embed", "{}", message);
+            return Err(message);
+        }
+        match index {
+            Some(i) if to.is_final() => {
+                states.remove(i);
+            }
+            Some(i) => states[i] = TabStateEntry { tab_id, hwnd, state: to },
+            None => states.push(TabStateEntry { tab_id, hwnd, state: to }),
+        }
+        TabStateChanged { tab_id, hwnd, from, to }
+    };
+    debug!(target: "embed", "标签 {} 状态: {:?} -> {:?}", tab_id, changed.from, to);
+    if let Some(app) = APP.get() {
+        let _ = app.emit("tab-state-changed", changed);
+    }
+    Ok(())
+}
+
+/// 标签被隐藏或重新显示 (tab_view.rs)，未响应和未嵌入的标签保持原状态
+pub fn set_hidden(hwnd: isize, hidden: bool) {
+    let Some(entry) = find(hwnd) else { return };
+    if !matches!(entry.state, TabState::Embedded | TabState::Hidden) {
+        return;
+    }
+    let to = if hidden { TabState::Hidden } else { TabState::Embedded };
+    let _ = transition(entry.tab_id, hwnd, to);
+}
+
+/// 存活检查的结果：已嵌入的标签未响应时转为 Hung，恢复响应后回到 Embedded 或 Hidden
+pub fn set_hung(hwnd: isize, hung: bool) {
+    let Some(entry) = find(hwnd) else { return };
+    let to = match (entry.state, hung) {
+        (TabState::Embedded | TabState::Hidden, true) => TabState::Hung,
+        (TabState::Hung, false) if crate::tab_view::is_hidden(hwnd) => TabState::Hidden,
+        (TabState::Hung, false) => TabState::Embedded,
+        _ => return,
+    };
+    let _ = transition(entry.tab_id, hwnd, to);
+}
+
+fn find(hwnd: isize) -> Option<TabStateEntry> {
+    STATES.lock().unwrap().iter().find(|e| e.hwnd == hwnd).cloned()
+}
+
+// 合法的状态转换 (from 为 None 表示还没有记录的新标签)
+fn is_allowed(from: Option<TabState>, to: TabState) -> bool {
+    use TabState::*;
+    match from {
+        None => matches!(to, Embedded),
+        Some(Launching) => matches!(to, Waiting | Embedded | Released | Closed),
+        Some(Waiting) => matches!(to, Launching | Embedded | Released | Closed),
+        Some(Embedded) => matches!(to, Hidden | Hung | Launching | Waiting | Released | Closed),
+        Some(Hidden) => matches!(to, Embedded | Hung | Launching | Waiting | Released | Closed),
+        Some(Hung) => matches!(to, Embedded | Hidden | Launching | Waiting | Released | Closed),
+        Some(Released | Closed) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TabState::*;
+
+    #[test]
+    fn only_valid_transitions_are_accepted() {
+        assert!(is_allowed(None, Embedded));
+        assert!(!is_allowed(None, Hidden));
+        assert!(is_allowed(Some(Embedded), Hung));
+        assert!(is_allowed(Some(Hung), Launching));
+        assert!(!is_allowed(Some(Waiting), Hidden));
+        assert!(!is_allowed(Some(Launching), Hung));
+        assert!(!is_allowed(Some(Closed), Embedded));
+
+        // 新窗口接替后记录新的句柄，关闭后不再记录
+        transition(901, -9010, Embedded).unwrap();
+        transition(901, -9010, Hidden).unwrap();
+        assert!(transition(901, -9010, Waiting).is_ok());
+        assert!(transition(901, -9010, Hidden).is_err());
+        transition(901, -9011, Embedded).unwrap();
+        assert_eq!(list().iter().find(|e| e.tab_id == 901).map(|e| e.hwnd), Some(-9011));
+        transition(901, -9011, Closed).unwrap();
+        assert!(list().iter().all(|e| e.tab_id != 901));
+    }
+}
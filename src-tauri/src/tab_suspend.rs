@@ -0,0 +1,123 @@
+// 挂起标签 (效率模式)
+// 把标签的进程降为空闲优先级、开启执行速度节流 (EcoQoS，同任务管理器的"效率模式") 并清空工作集，
+// 不显示的标签几乎不占 CPU 和内存。不用 SuspendThread 暂停线程：主窗口调整布局、切换标签时
+// 要向标签窗口同步发送消息，线程被暂停的窗口会让主窗口一起卡住。
+// 定时任务 suspend_all_tabs 挂起当前显示的标签以外的所有标签；切换到标签 (show_tab)、释放或关闭标签、
+// 关闭主窗口时恢复原来的优先级。同一进程的多个标签都恢复后才恢复进程，WindowHub 自己的进程不挂起
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info};
+
+use crate::window_manager::WindowManager;
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::CloseHandle,
+    System::ProcessStatus::EmptyWorkingSet,
+    System::Threading::*,
+};
+
+// (标签, 进程 ID, 挂起前的优先级)
+static SUSPENDED: Mutex<Vec<(isize, u32, u32)>> = Mutex::new(Vec::new());
+
+/// 挂起当前显示的标签以外的所有标签，返回挂起的个数
+pub fn suspend_all(app: &AppHandle) -> usize {
+    let active = crate::host_layout::active();
+    let own = std::process::id();
+    let mut count = 0;
+    for window in app.state::<WindowManager>().list() {
+        if window.hwnd == active || window.pid == own || is_suspended(window.hwnd) {
+            continue;
+        }
+        let pid = window.pid;
+        let Some(priority) = throttle(pid) else {
+            debug!(target: "embed", "无法挂起标签: hwnd={}, pid={}", window.hwnd, pid);
+            continue;
+        };
+        // 同一进程先挂起的标签记录的才是原来的优先级
+        let mut suspended = SUSPENDED.lock().unwrap();
+        let original = suspended.iter().find(|(_, p, _)| *p == pid).map_or(priority, |(_, _, original)| *original);
+        suspended.push((window.hwnd, pid, original));
+        count += 1;
+    }
+    info!(target: "embed", "挂起了 {} 个标签", count);
+    count
+}
+
+pub fn is_suspended(tab: isize) -> bool {
+    SUSPENDED.lock().unwrap().iter().any(|(h, _, _)| *h == tab)
+}
+
+/// 恢复标签 (没有挂起时不做任何事)
+pub fn resume(tab: isize) {
+    let restore = {
+        let mut suspended = SUSPENDED.lock().unwrap();
+        let Some(index) = suspended.iter().position(|(h, _, _)| *h == tab) else { return };
+        let (_, pid, original) = suspended.remove(index);
+        (!suspended.iter().any(|(_, p, _)| *p == pid)).then_some((pid, original))
+    };
+    if let Some((pid, original)) = restore {
+        unthrottle(pid, original);
+        debug!(target: "embed", "恢复挂起的标签: hwnd={}, pid={}", tab, pid);
+    }
+}
+
+/// 恢复所有挂起的标签 (关闭主窗口时)
+pub fn resume_all() {
+    let tabs: Vec<isize> = SUSPENDED.lock().unwrap().iter().map(|(h, _, _)| *h).collect();
+    for tab in tabs {
+        resume(tab);
+    }
+}
+
+// 降低进程的优先级并开启节流，返回原来的优先级
+#[cfg(windows)]
+fn throttle(pid: u32) -> Option<u32> {
+    unsafe {
+        let process = OpenProcess(PROCESS_SET_INFORMATION | PROCESS_SET_QUOTA | PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let original = GetPriorityClass(process);
+        let ok = original != 0 && SetPriorityClass(process, IDLE_PRIORITY_CLASS).is_ok();
+        if ok {
+            set_power_throttling(process, true);
+            let _ = EmptyWorkingSet(process);
+        }
+        let _ = CloseHandle(process);
+        ok.then_some(original)
+    }
+}
+
+#[cfg(not(windows))]
+fn throttle(_pid: u32) -> Option<u32> {
+    None
+}
+
+#[cfg(windows)]
+fn unthrottle(pid: u32, original: u32) {
+    unsafe {
+        // 进程已经退出时什么也不用做
+        let Ok(process) = OpenProcess(PROCESS_SET_INFORMATION | PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else { return };
+        let _ = SetPriorityClass(process, PROCESS_CREATION_FLAGS(original));
+        set_power_throttling(process, false);
+        let _ = CloseHandle(process);
+    }
+}
+
+#[cfg(not(windows))]
+fn unthrottle(_pid: u32, _original: u32) {}
+
+// 开启执行速度节流，关闭时交还给系统自动决定
+#[cfg(windows)]
+unsafe fn set_power_throttling(process: windows::Win32::Foundation::HANDLE, enabled: bool) {
+    let state = PROCESS_POWER_THROTTLING_STATE {
+        Version: PROCESS_POWER_THROTTLING_CURRENT_VERSION,
+        ControlMask: if enabled { PROCESS_POWER_THROTTLING_EXECUTION_SPEED } else { 0 },
+        StateMask: if enabled { PROCESS_POWER_THROTTLING_EXECUTION_SPEED } else { 0 },
+    };
+    let _ = SetProcessInformation(
+        process,
+        ProcessPowerThrottling,
+        &state as *const _ as *const _,
+        std::mem::size_of::<PROCESS_POWER_THROTTLING_STATE>() as u32,
+    );
+}
@@ -0,0 +1,110 @@
+// 标签的显示和隐藏
+// 所有标签嵌入在同一个内容区。之前全部保持可见，靠 z 序让当前标签在最上面，
+// 打开搜索、工作区等面板时由前端逐个 hide_window，关闭后逐个 show_window 再 activate_window
+// 现在只有当前标签可见，其他标签 cloak (画面隐藏但保持布局并继续绘制，切换回来时不需要等待重绘)；
+// 不支持 cloak 的窗口改为隐藏；当前标签停靠在内容区的某个区域时，其他停靠的标签一起显示 (见 zones.rs)，
+// 当前标签在监控墙网格中时整个网格一起显示 (见 grid.rs)
+
+use std::sync::Mutex;
+
+use crate::platform::Platform;
+use crate::{grid, input_bypass, privacy, tab_overlay, tab_state, zones};
+
+// 隐藏的标签，以及是否通过 cloak 隐藏 (否则为 SW_HIDE)
+static HIDDEN: Mutex<Vec<(isize, bool)>> = Mutex::new(Vec::new());
+
+/// 显示标签 tab (及与它一起停靠或在同一网格中的标签)，隐藏 tabs 中的其他标签
+pub fn show(platform: &dyn Platform, tabs: &[isize], tab: isize) {
+    let shown = if grid::contains(tab) && zones::maximized() != Some(tab) {
+        grid::members().into_iter().filter(|h| tabs.contains(h)).collect()
+    } else {
+        zones::shown_with(tabs, tab)
+    };
+    for &tab in &shown {
+        reveal(platform, tab);
+    }
+    for &other in tabs.iter().filter(|other| !shown.contains(other)) {
+        hide(platform, other);
+    }
+    input_bypass::refresh();
+    tab_overlay::refresh();
+    privacy::refresh_capture();
+}
+
+/// 隐藏标签
+pub fn hide(platform: &dyn Platform, tab: isize) {
+    let mut hidden = HIDDEN.lock().unwrap();
+    if hidden.iter().any(|(h, _)| *h == tab) {
+        return;
+    }
+    let cloaked = platform.set_cloaked(tab, true);
+    if !cloaked {
+        platform.set_visible(tab, false);
+    }
+    hidden.push((tab, cloaked));
+    drop(hidden);
+    tab_state::set_hidden(tab, true);
+    input_bypass::refresh();
+    privacy::refresh_capture();
+}
+
+/// 重新隐藏记录为隐藏的标签 (资源管理器重启后可能重新显示了它们)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn reassert(platform: &dyn Platform) {
+    for &(tab, cloaked) in HIDDEN.lock().unwrap().iter() {
+        if cloaked {
+            platform.set_cloaked(tab, true);
+        } else {
+            platform.set_visible(tab, false);
+        }
+    }
+}
+
+fn reveal(platform: &dyn Platform, tab: isize) {
+    let mut hidden = HIDDEN.lock().unwrap();
+    let Some(index) = hidden.iter().position(|(h, _)| *h == tab) else { return };
+    let (_, cloaked) = hidden.remove(index);
+    drop(hidden);
+    if cloaked {
+        platform.set_cloaked(tab, false);
+    } else {
+        platform.set_visible(tab, true);
+    }
+    tab_state::set_hidden(tab, false);
+}
+
+/// 标签是否被隐藏 (过渡结束时不能把它显示出来)
+pub fn is_hidden(tab: isize) -> bool {
+    HIDDEN.lock().unwrap().iter().any(|(h, _)| *h == tab)
+}
+
+/// 标签已释放或关闭 (释放时平台会恢复窗口的显示)
+pub fn forget(tab: isize) {
+    HIDDEN.lock().unwrap().retain(|(h, _)| *h != tab);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatform;
+
+    #[test]
+    fn only_the_shown_tab_stays_visible() {
+        let platform = MockPlatform::new();
+        let first = platform.create_window("文档", "Notepad", None);
+        let second = platform.create_window("表格", "Notepad", None);
+        let tabs = [first, second];
+
+        show(&platform, &tabs, first);
+        assert!(!platform.window(first).unwrap().cloaked);
+        assert!(platform.window(second).unwrap().cloaked);
+
+        show(&platform, &tabs, second);
+        assert!(platform.window(first).unwrap().cloaked);
+        assert!(!platform.window(second).unwrap().cloaked);
+        assert!(is_hidden(first) && !is_hidden(second));
+
+        forget(first);
+        forget(second);
+    }
+}
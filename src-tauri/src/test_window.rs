@@ -0,0 +1,187 @@
+// 测试用的原生窗口
+// 创建本进程的简单顶层窗口 (独立线程处理消息，放在屏幕外)，可以模拟有问题的应用：
+// - paint_delay_ms：每次 WM_PAINT 先等待这么久 (绘制缓慢)
+// - hang_ms：创建后这么久不处理消息 (未响应，嵌入探测和超时撤销会走到失败分支)
+// - refuse_close：忽略 WM_CLOSE (拒绝关闭，检查关闭请求的超时和强制结束)
+// 压力测试 (stress.rs) 和 Windows 上的测试用它验证看门狗、超时和回滚，不依赖第三方应用。
+// TestWindow 离开作用域时销毁窗口并等待线程退出 (未响应期间会一直等到 hang_ms 结束)
+
+use serde::Deserialize;
+
+#[cfg(windows)]
+use std::sync::mpsc;
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+        Graphics::Gdi::{BeginPaint, EndPaint, PAINTSTRUCT},
+        System::LibraryLoader::GetModuleHandleW,
+        UI::WindowsAndMessaging::*,
+    },
+};
+
+// 绘制等待的上限
+#[cfg_attr(not(windows), allow(dead_code))]
+const MAX_PAINT_DELAY_MS: u32 = 10_000;
+
+// 未响应的上限
+#[cfg_attr(not(windows), allow(dead_code))]
+const MAX_HANG_MS: u32 = 60_000;
+
+// 销毁窗口 (refuse_close 时 WM_CLOSE 不起作用)
+#[cfg(windows)]
+const WM_TEST_DESTROY: u32 = WM_APP + 1;
+
+// GWLP_USERDATA 中 refuse_close 的标志位，低位为 paint_delay_ms
+#[cfg(windows)]
+const REFUSE_CLOSE_FLAG: isize = 1 << 30;
+
+/// 测试窗口的行为
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub struct Behavior {
+    #[serde(default)]
+    pub paint_delay_ms: u32,
+    #[serde(default)]
+    pub hang_ms: u32,
+    #[serde(default)]
+    pub refuse_close: bool,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl Behavior {
+    fn clamped(self) -> Behavior {
+        Behavior {
+            paint_delay_ms: self.paint_delay_ms.min(MAX_PAINT_DELAY_MS),
+            hang_ms: self.hang_ms.min(MAX_HANG_MS),
+            refuse_close: self.refuse_close,
+        }
+    }
+}
+
+#[cfg(windows)]
+pub struct TestWindow {
+    pub hwnd: isize,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(windows)]
+impl TestWindow {
+    pub fn create(behavior: Behavior) -> Result<TestWindow, String> {
+        let (tx, rx) = mpsc::channel();
+        let thread = std::thread::spawn(move || unsafe { window_thread(behavior.clamped(), tx) });
+        let hwnd = rx.recv().map_err(|_| "测试窗口线程异常退出".to_string())??;
+        Ok(TestWindow { hwnd, thread: Some(thread) })
+    }
+}
+
+#[cfg(windows)]
+impl Drop for TestWindow {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostMessageW(HWND(self.hwnd as *mut _), WM_TEST_DESTROY, WPARAM(0), LPARAM(0));
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(windows)]
+unsafe fn window_thread(behavior: Behavior, tx: mpsc::Sender<Result<isize, String>>) {
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let class_name = w!("WindowHubTestWindow");
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(window_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+    let created = CreateWindowExW(
+        WS_EX_TOOLWINDOW,
+        class_name,
+        w!("WindowHub 测试窗口"),
+        // 可见：释放时会按原样恢复成可见窗口，嵌入前后的样式才能逐位比较
+        WS_OVERLAPPEDWINDOW | WS_VISIBLE,
+        -32000,
+        -32000,
+        400,
+        300,
+        HWND::default(),
+        None,
+        instance,
+        None,
+    );
+    let hwnd = match created {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+            let _ = tx.send(Err(format!("创建测试窗口失败: {}", e)));
+            return;
+        }
+    };
+    let flags = behavior.paint_delay_ms as isize | if behavior.refuse_close { REFUSE_CLOSE_FLAG } else { 0 };
+    SetWindowLongPtrW(hwnd, GWLP_USERDATA, flags);
+    let _ = tx.send(Ok(hwnd.0 as isize));
+
+    if behavior.hang_ms > 0 {
+        std::thread::sleep(std::time::Duration::from_millis(behavior.hang_ms as u64));
+    }
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let flags = GetWindowLongPtrW(hwnd, GWLP_USERDATA);
+    match msg {
+        WM_PAINT => {
+            let delay = (flags & !REFUSE_CLOSE_FLAG) as u64;
+            if delay > 0 {
+                std::thread::sleep(std::time::Duration::from_millis(delay));
+            }
+            let mut paint = PAINTSTRUCT::default();
+            BeginPaint(hwnd, &mut paint);
+            let _ = EndPaint(hwnd, &paint);
+            LRESULT(0)
+        }
+        WM_CLOSE if flags & REFUSE_CLOSE_FLAG != 0 => LRESULT(0),
+        WM_TEST_DESTROY => {
+            let _ = DestroyWindow(hwnd);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            PostQuitMessage(0);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_delays() {
+        let behavior = Behavior { paint_delay_ms: u32::MAX, hang_ms: 500, refuse_close: true }.clamped();
+        assert_eq!(behavior, Behavior { paint_delay_ms: MAX_PAINT_DELAY_MS, hang_ms: 500, refuse_close: true });
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn refuses_close_until_dropped() {
+        let window = TestWindow::create(Behavior { refuse_close: true, ..Default::default() }).unwrap();
+        let hwnd = HWND(window.hwnd as *mut _);
+        unsafe {
+            SendMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+            assert!(IsWindow(hwnd).as_bool());
+        }
+        drop(window);
+        assert!(!unsafe { IsWindow(hwnd) }.as_bool());
+    }
+}
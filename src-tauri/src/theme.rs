@@ -0,0 +1,190 @@
+// 系统主题 (深色/浅色) 和强调色
+// 读取 "选择应用模式" 和强调色 (注册表)，主窗口的标题栏随之使用深色模式；
+// 开启了 "在标题栏和窗口边框上显示强调色" 时，标题栏和边框也使用强调色
+// 系统设置变化时 (WM_SETTINGCHANGE，由 win_events.rs 的钩子窗口收到) 重新读取并应用，
+// 有变化时发送 theme-changed 事件 (载荷为 SystemTheme)
+// 主窗口的背景材质 (Mica/Acrylic) 和圆角按设置 appearance.backdrop / corners 应用，
+// 使用背景材质时网页背景改为透明，由前端把自己的背景调成半透明
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+
+use crate::settings::Backdrop;
+
+#[cfg(windows)]
+use tracing::warn;
+
+#[cfg(windows)]
+use crate::settings::Corners;
+
+#[cfg(windows)]
+use windows::{
+    core::PCWSTR,
+    core::w,
+    Win32::{
+        Foundation::{BOOL, HWND},
+        Graphics::Dwm::{
+            DwmSetWindowAttribute, DWMSBT_MAINWINDOW, DWMSBT_NONE, DWMSBT_TABBEDWINDOW, DWMSBT_TRANSIENTWINDOW, DWMWA_BORDER_COLOR, DWMWA_CAPTION_COLOR,
+            DWMWA_COLOR_DEFAULT, DWMWA_SYSTEMBACKDROP_TYPE, DWMWA_USE_IMMERSIVE_DARK_MODE, DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_DEFAULT,
+            DWMWCP_DONOTROUND, DWMWCP_ROUND, DWMWCP_ROUNDSMALL,
+        },
+        System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD},
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SystemTheme {
+    pub mode: ThemeMode,
+    pub accent_color: Option<String>, // "#RRGGBB"
+    pub accent_on_title_bars: bool,   // 系统设置了在标题栏上显示强调色
+}
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// 主窗口，标题栏属性应用到它上面
+static HOST: AtomicIsize = AtomicIsize::new(0);
+
+static CURRENT: Mutex<Option<SystemTheme>> = Mutex::new(None);
+
+/// 保存 AppHandle 并把当前主题应用到主窗口 (setup 时调用一次)
+pub fn init(app: &AppHandle, host: isize) {
+    let _ = APP.set(app.clone());
+    HOST.store(host, Ordering::SeqCst);
+    apply(&get());
+    apply_backdrop(app);
+}
+
+pub fn get() -> SystemTheme {
+    CURRENT.lock().unwrap().get_or_insert_with(query).clone()
+}
+
+/// 系统设置可能有变化 (钩子窗口收到 WM_SETTINGCHANGE 等时调用)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn changed() {
+    let theme = query();
+    let previous = CURRENT.lock().unwrap().replace(theme.clone());
+    if previous.as_ref() == Some(&theme) {
+        return;
+    }
+    info!(target: "settings", "系统主题已变化: {:?}", theme);
+    apply(&theme);
+    if let Some(app) = APP.get() {
+        let _ = app.emit("theme-changed", theme);
+    }
+}
+
+#[cfg(windows)]
+fn read_dword(key: PCWSTR, value: PCWSTR) -> Option<u32> {
+    let mut data: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe { RegGetValueW(HKEY_CURRENT_USER, key, value, RRF_RT_REG_DWORD, None, Some(&mut data as *mut _ as *mut _), Some(&mut size)) };
+    status.is_ok().then_some(data)
+}
+
+#[cfg(windows)]
+fn query() -> SystemTheme {
+    let personalize = w!(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize");
+    let dwm = w!(r"Software\Microsoft\Windows\DWM");
+    // 没有这个值时 (旧版本 Windows) 是浅色
+    let light = read_dword(personalize, w!("AppsUseLightTheme")).is_none_or(|v| v != 0);
+    SystemTheme {
+        mode: if light { ThemeMode::Light } else { ThemeMode::Dark },
+        accent_color: read_dword(dwm, w!("AccentColor")).map(accent_hex),
+        accent_on_title_bars: read_dword(dwm, w!("ColorPrevalence")).is_some_and(|v| v != 0),
+    }
+}
+
+#[cfg(not(windows))]
+fn query() -> SystemTheme {
+    SystemTheme { mode: ThemeMode::Light, accent_color: None, accent_on_title_bars: false }
+}
+
+// 注册表中的强调色是 0xAABBGGRR
+#[cfg_attr(not(windows), allow(dead_code))]
+fn accent_hex(abgr: u32) -> String {
+    format!("#{:02X}{:02X}{:02X}", abgr & 0xFF, (abgr >> 8) & 0xFF, (abgr >> 16) & 0xFF)
+}
+
+/// 主窗口标题栏：深色模式，需要时使用强调色
+fn apply(theme: &SystemTheme) {
+    let host = HOST.load(Ordering::SeqCst);
+    if host == 0 {
+        return;
+    }
+    #[cfg(windows)]
+    unsafe {
+        let hwnd = HWND(host as *mut _);
+        let dark = BOOL::from(theme.mode == ThemeMode::Dark);
+        if let Err(e) = DwmSetWindowAttribute(hwnd, DWMWA_USE_IMMERSIVE_DARK_MODE, &dark as *const _ as *const _, std::mem::size_of::<BOOL>() as u32) {
+            warn!(target: "settings", "设置深色标题栏失败: {}", e);
+        }
+        // COLORREF 是 0x00BBGGRR，与注册表中去掉透明度的值相同；DWMWA_CAPTION_COLOR 等需要 Windows 11
+        let accent = theme
+            .accent_color
+            .as_deref()
+            .filter(|_| theme.accent_on_title_bars)
+            .and_then(|hex| u32::from_str_radix(hex.trim_start_matches('#'), 16).ok())
+            .map(|rgb| ((rgb & 0xFF) << 16) | (rgb & 0xFF00) | ((rgb >> 16) & 0xFF))
+            .unwrap_or(DWMWA_COLOR_DEFAULT);
+        for attribute in [DWMWA_CAPTION_COLOR, DWMWA_BORDER_COLOR] {
+            let _ = DwmSetWindowAttribute(hwnd, attribute, &accent as *const _ as *const _, std::mem::size_of::<u32>() as u32);
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = theme;
+}
+
+/// 按设置应用背景材质和圆角 (启动和外观设置变化时调用)，旧版本 Windows 不支持时忽略
+pub fn apply_backdrop(app: &AppHandle) {
+    let appearance = crate::settings::get().appearance;
+    // 网页背景透明才能看到材质
+    if let Some(main) = app.get_webview_window("main") {
+        let color = (appearance.backdrop != Backdrop::None).then_some(tauri::window::Color(0, 0, 0, 0));
+        let _ = main.set_background_color(color);
+    }
+    let host = HOST.load(Ordering::SeqCst);
+    if host == 0 {
+        return;
+    }
+    #[cfg(windows)]
+    unsafe {
+        let hwnd = HWND(host as *mut _);
+        let backdrop = match appearance.backdrop {
+            Backdrop::None => DWMSBT_NONE,
+            Backdrop::Mica => DWMSBT_MAINWINDOW,
+            Backdrop::Acrylic => DWMSBT_TRANSIENTWINDOW,
+            Backdrop::Tabbed => DWMSBT_TABBEDWINDOW,
+        };
+        if let Err(e) = DwmSetWindowAttribute(hwnd, DWMWA_SYSTEMBACKDROP_TYPE, &backdrop as *const _ as *const _, std::mem::size_of_val(&backdrop) as u32) {
+            warn!(target: "settings", "设置背景材质失败 (需要 Windows 11): {}", e);
+        }
+        let corners = match appearance.corners {
+            Corners::Default => DWMWCP_DEFAULT,
+            Corners::Round => DWMWCP_ROUND,
+            Corners::RoundSmall => DWMWCP_ROUNDSMALL,
+            Corners::Square => DWMWCP_DONOTROUND,
+        };
+        let _ = DwmSetWindowAttribute(hwnd, DWMWA_WINDOW_CORNER_PREFERENCE, &corners as *const _ as *const _, std::mem::size_of_val(&corners) as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accent_color_converts_from_abgr() {
+        assert_eq!(accent_hex(0xFFD77800), "#0078D7");
+    }
+}
@@ -0,0 +1,121 @@
+// 窗口选择器中的静态预览
+// 选择器一次列出几十个窗口，每个都注册实时的 DWM 缩略图开销太大。
+// get_window_thumbnail(hwnd, max_size) 用 PrintWindow 截一张缩小的图 (与快速切换器相同的 JPEG data URL)，
+// 结果按窗口缓存 MAX_AGE，期间再次请求直接返回缓存；过期后下次请求时才重新截取，列表不显示时不做任何事
+// 应用无响应时截图会卡住，超过 CAPTURE_TIMEOUT 放弃；最多缓存 MAX_ENTRIES 个，超出时丢弃最早截取的
+// 每个窗口同时只截取一次：上一次截取还没结束 (包括已经超时、仍卡在后台线程上的) 时直接返回 Err，不再占用新的线程
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+const MAX_AGE: Duration = Duration::from_secs(10);
+const MAX_ENTRIES: usize = 64;
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(2);
+const DEFAULT_SIZE: i32 = 240;
+
+struct Cached {
+    hwnd: isize,
+    max_size: i32,
+    captured_at: Instant,
+    data_url: String,
+}
+
+static CACHE: Mutex<Vec<Cached>> = Mutex::new(Vec::new());
+
+// 正在截取的窗口
+static PENDING: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+/// 窗口的缩略图 (宽高不超过 max_size)，窗口不存在或无法截取时返回 Err
+pub async fn get(hwnd: isize, max_size: Option<i32>) -> Result<String, String> {
+    let max_size = max_size.unwrap_or(DEFAULT_SIZE).clamp(16, 1024);
+    let platform = crate::platform::current();
+    if !platform.is_window(hwnd) {
+        forget(hwnd);
+        return Err("窗口不存在".to_string());
+    }
+    if let Some(data_url) = cached(hwnd, max_size, Instant::now()) {
+        return Ok(data_url);
+    }
+    if !begin(hwnd) {
+        return Err("正在截取窗口画面".to_string());
+    }
+    let capture = tauri::async_runtime::spawn_blocking(move || {
+        let data_url = platform.thumbnail(hwnd, max_size, max_size);
+        finish(hwnd);
+        data_url
+    });
+    let data_url = match tokio::time::timeout(CAPTURE_TIMEOUT, capture).await {
+        Ok(Ok(Some(data_url))) => data_url,
+        Ok(_) => return Err("无法截取窗口画面".to_string()),
+        Err(_) => {
+            debug!(target: "embed", "截取缩略图超时: hwnd={}", hwnd);
+            return Err("截取窗口画面超时".to_string());
+        }
+    };
+    store(Cached { hwnd, max_size, captured_at: Instant::now(), data_url: data_url.clone() });
+    Ok(data_url)
+}
+
+/// 窗口已关闭，丢弃它的缓存
+pub fn forget(hwnd: isize) {
+    CACHE.lock().unwrap().retain(|c| c.hwnd != hwnd);
+}
+
+// 登记窗口正在截取，已经在截取时返回 false
+fn begin(hwnd: isize) -> bool {
+    let mut pending = PENDING.lock().unwrap();
+    if pending.contains(&hwnd) {
+        return false;
+    }
+    pending.push(hwnd);
+    true
+}
+
+fn finish(hwnd: isize) {
+    PENDING.lock().unwrap().retain(|h| *h != hwnd);
+}
+
+fn cached(hwnd: isize, max_size: i32, now: Instant) -> Option<String> {
+    let cache = CACHE.lock().unwrap();
+    cache
+        .iter()
+        .find(|c| c.hwnd == hwnd && c.max_size == max_size && now.duration_since(c.captured_at) < MAX_AGE)
+        .map(|c| c.data_url.clone())
+}
+
+fn store(entry: Cached) {
+    let mut cache = CACHE.lock().unwrap();
+    cache.retain(|c| !(c.hwnd == entry.hwnd && c.max_size == entry.max_size));
+    cache.push(entry);
+    if cache.len() > MAX_ENTRIES {
+        cache.remove(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_thumbnail_expires() {
+        let now = Instant::now();
+        store(Cached { hwnd: 42, max_size: 240, captured_at: now, data_url: "a".to_string() });
+        assert_eq!(cached(42, 240, now + Duration::from_secs(1)).as_deref(), Some("a"));
+        assert_eq!(cached(42, 120, now), None);
+        assert_eq!(cached(42, 240, now + MAX_AGE), None);
+        forget(42);
+        assert_eq!(cached(42, 240, now), None);
+    }
+
+    #[test]
+    fn one_capture_per_window_at_a_time() {
+        assert!(begin(7));
+        assert!(!begin(7));
+        assert!(begin(8));
+        finish(7);
+        assert!(begin(7));
+        finish(7);
+        finish(8);
+    }
+}
@@ -0,0 +1,113 @@
+// 标签标题和图标变化
+// 嵌入窗口的标题或图标变化时发送 title-changed，前端据此更新标签，不必等下一次对账：
+// - 通常由 win_events 的 EVENT_OBJECT_NAMECHANGE 钩子触发 (name_changed)
+// - 少数应用不可靠地发送 NAMECHANGE，兼容性数据库中标记了 poll_title 的窗口由后台线程按
+//   polling.title_fallback_ms 定时检查 (0 表示关闭)，与上次发送的标题和图标比较，变化时发送同样的事件
+// 两条路径共用上次发送的记录，同一次变化只发送一次；锁屏期间不检查
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// 上次发送时的 (标签, 标题, 图标)
+static KNOWN: Mutex<Vec<(isize, String, isize)>> = Mutex::new(Vec::new());
+
+// 定时检查关闭时，多久看一次设置是否重新开启
+const DISABLED_RECHECK: Duration = Duration::from_secs(5);
+
+/// title-changed 事件的载荷
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TitleChanged {
+    pub hwnd: isize,
+    #[serde(serialize_with = "crate::privacy::serialize_title")]
+    pub title: String,
+    pub icon_changed: bool, // 图标变化，前端需要重新获取图标
+}
+
+/// 启动定时检查线程
+pub fn start(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        let interval = crate::settings::get().polling.title_fallback_ms;
+        if interval == 0 {
+            std::thread::sleep(DISABLED_RECHECK);
+            continue;
+        }
+        std::thread::sleep(Duration::from_millis(interval));
+        if crate::session_watch::is_active() {
+            poll(&app);
+        }
+    });
+}
+
+/// 窗口名称变化 (由 win_events 的 EVENT_OBJECT_NAMECHANGE 钩子调用)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn name_changed(hwnd: isize) {
+    let Some(app) = APP.get() else { return };
+    if crate::window_manager::embedded_hwnds(app).contains(&hwnd) {
+        check(app, hwnd);
+    }
+}
+
+// 检查兼容性数据库标记的标签，顺便清掉已关闭标签的记录
+fn poll(app: &AppHandle) {
+    let platform = crate::platform::current();
+    let tabs = crate::window_manager::embedded_hwnds(app);
+    KNOWN.lock().unwrap().retain(|(tab, _, _)| tabs.contains(tab));
+    for tab in tabs {
+        let exe_path = platform.process_path(tab).ok();
+        if crate::compat::needs_title_polling(exe_path.as_deref(), &platform.class_name(tab)) {
+            check(app, tab);
+        }
+    }
+}
+
+fn check(app: &AppHandle, hwnd: isize) {
+    let platform = crate::platform::current();
+    if !platform.is_window(hwnd) {
+        return;
+    }
+    let (title, icon) = (platform.title(hwnd), platform.icon_handle(hwnd));
+    if let Some(changed) = diff(&mut KNOWN.lock().unwrap(), hwnd, title, icon) {
+        let _ = app.emit("title-changed", changed);
+    }
+}
+
+// 与上次的记录比较并更新记录，第一次看到的标签只记录不发送 (嵌入时前端已经有标题)
+fn diff(known: &mut Vec<(isize, String, isize)>, hwnd: isize, title: String, icon: isize) -> Option<TitleChanged> {
+    let Some(entry) = known.iter_mut().find(|(tab, _, _)| *tab == hwnd) else {
+        known.push((hwnd, title, icon));
+        return None;
+    };
+    let icon_changed = entry.2 != icon;
+    if entry.1 == title && !icon_changed {
+        return None;
+    }
+    *entry = (hwnd, title.clone(), icon);
+    Some(TitleChanged { hwnd, title, icon_changed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_only_actual_changes() {
+        let mut known = Vec::new();
+        assert_eq!(diff(&mut known, 1, "a".to_string(), 10), None);
+        assert_eq!(diff(&mut known, 1, "a".to_string(), 10), None);
+        assert_eq!(
+            diff(&mut known, 1, "b".to_string(), 10),
+            Some(TitleChanged { hwnd: 1, title: "b".to_string(), icon_changed: false })
+        );
+        assert_eq!(
+            diff(&mut known, 1, "b".to_string(), 11),
+            Some(TitleChanged { hwnd: 1, title: "b".to_string(), icon_changed: true })
+        );
+        assert_eq!(diff(&mut known, 2, "b".to_string(), 11), None);
+    }
+}
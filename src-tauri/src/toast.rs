@@ -0,0 +1,178 @@
+// WindowHub 自己的系统通知 (Toast)
+// 主窗口隐藏在托盘中时，后台发生的事 (标签崩溃、规则匹配、应用重新嵌入) 也要让用户看到：
+// notify(title, body, actions) 显示一条通知，actions 生成通知上的按钮
+// 点击按钮或通知本身时发送 toast-activated (载荷为 ToastActivated)，点击通知本身时同时显示主窗口
+// 未打包的应用需要注册 AUMID 才能发通知：首次使用时在 HKCU\Software\Classes\AppUserModelId 下写入显示名称
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(windows)]
+use std::sync::{Mutex, Once};
+#[cfg(windows)]
+use tauri::{Emitter, Manager};
+#[cfg(windows)]
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use windows::{
+    core::{IInspectable, Interface, HSTRING},
+    Data::Xml::Dom::XmlDocument,
+    Foundation::TypedEventHandler,
+    UI::Notifications::{ToastActivatedEventArgs, ToastNotification, ToastNotificationManager},
+    Win32::System::Registry::{RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ},
+    Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED},
+};
+
+// 保留最近显示的通知对象，释放后点击通知不再回调
+#[cfg(windows)]
+const MAX_KEPT: usize = 20;
+
+#[cfg(windows)]
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+#[cfg(windows)]
+static SHOWN: Mutex<Vec<ToastNotification>> = Mutex::new(Vec::new());
+#[cfg(windows)]
+static REGISTER: Once = Once::new();
+
+/// 通知上的按钮，点击后 toast-activated 的 action 为 id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToastAction {
+    pub id: String,
+    pub label: String,
+}
+
+/// toast-activated 事件的载荷
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ToastActivated {
+    pub toast: String,          // notify 返回的通知 ID
+    pub action: Option<String>, // 点击通知本身时为 None
+}
+
+/// 显示一条通知，返回通知 ID
+#[cfg(windows)]
+pub fn notify(app: &AppHandle, title: &str, body: &str, actions: &[ToastAction]) -> Result<String, String> {
+    let aumid = app.config().identifier.clone();
+    REGISTER.call_once(|| register_aumid(&aumid));
+
+    let id = format!("toast-{}", NEXT_ID.fetch_add(1, Ordering::SeqCst));
+    let toast = unsafe { show(app, &aumid, &id, &toast_xml(title, body, actions)) }.map_err(|e| format!("显示通知失败: {}", e))?;
+    info!(target: "notify", "显示通知: {} {}", id, title);
+
+    let mut shown = SHOWN.lock().unwrap();
+    shown.push(toast);
+    if shown.len() > MAX_KEPT {
+        shown.remove(0);
+    }
+    Ok(id)
+}
+
+#[cfg(not(windows))]
+pub fn notify(_app: &AppHandle, _title: &str, _body: &str, _actions: &[ToastAction]) -> Result<String, String> {
+    Err("仅支持 Windows".to_string())
+}
+
+#[cfg(windows)]
+unsafe fn show(app: &AppHandle, aumid: &str, id: &str, xml: &str) -> windows::core::Result<ToastNotification> {
+    // 命令可能在未初始化 WinRT 的线程上调用，已初始化时返回错误，忽略即可
+    let _ = RoInitialize(RO_INIT_MULTITHREADED);
+    let document = XmlDocument::new()?;
+    document.LoadXml(&HSTRING::from(xml))?;
+    let toast = ToastNotification::CreateToastNotification(&document)?;
+
+    let app = app.clone();
+    let toast_id = id.to_string();
+    toast.Activated(&TypedEventHandler::new(move |_: &Option<ToastNotification>, args: &Option<IInspectable>| {
+        let action = args
+            .as_ref()
+            .and_then(|args| args.cast::<ToastActivatedEventArgs>().ok())
+            .and_then(|args| args.Arguments().ok())
+            .map(|arguments| arguments.to_string())
+            .filter(|arguments| !arguments.is_empty());
+        if action.is_none() {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        }
+        let _ = app.emit("toast-activated", ToastActivated { toast: toast_id.clone(), action });
+        Ok(())
+    }))?;
+
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(aumid))?.Show(&toast)?;
+    Ok(toast)
+}
+
+// 注册 AUMID 的显示名称 (通知上显示的应用名)
+#[cfg(windows)]
+fn register_aumid(aumid: &str) {
+    let key = HSTRING::from(format!(r"Software\Classes\AppUserModelId\{}", aumid));
+    let name: Vec<u16> = "WindowHub".encode_utf16().chain(Some(0)).collect();
+    let result = unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            &key,
+            windows::core::w!("DisplayName"),
+            REG_SZ.0,
+            Some(name.as_ptr() as *const _),
+            (name.len() * 2) as u32,
+        )
+    };
+    if result.is_err() {
+        warn!(target: "notify", "注册通知应用名称失败: {:?}", result);
+    }
+}
+
+// ToastGeneric 模板：标题、正文和按钮 (按钮的 arguments 为按钮 ID)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn toast_xml(title: &str, body: &str, actions: &[ToastAction]) -> String {
+    let mut xml = format!(
+        r#"<toast><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual>"#,
+        escape(title),
+        escape(body)
+    );
+    if !actions.is_empty() {
+        xml.push_str("<actions>");
+        for action in actions {
+            xml.push_str(&format!(
+                r#"<action content="{}" arguments="{}" activationType="foreground"/>"#,
+                escape(&action.label),
+                escape(&action.id)
+            ));
+        }
+        xml.push_str("</actions>");
+    }
+    xml.push_str("</toast>");
+    xml
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_escaped_toast_xml() {
+        let actions = [ToastAction { id: "restart:42".to_string(), label: "重新启动".to_string() }];
+        assert_eq!(
+            toast_xml("标签已退出", "Notepad <无标题> & 其他", &actions),
+            concat!(
+                r#"<toast><visual><binding template="ToastGeneric"><text>标签已退出</text><text>Notepad &lt;无标题&gt; &amp; 其他</text></binding></visual>"#,
+                r#"<actions><action content="重新启动" arguments="restart:42" activationType="foreground"/></actions></toast>"#
+            )
+        );
+        assert!(!toast_xml("a", "b", &[]).contains("<actions>"));
+    }
+}
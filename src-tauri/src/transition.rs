@@ -0,0 +1,84 @@
+// 嵌入和切换标签时的过渡
+// 修改样式、SetParent、调整大小的过程中窗口会以中间状态绘制 (白色背景、旧尺寸)，看起来在闪
+// 过渡开始时 cloak 窗口 (画面隐藏，但仍接收尺寸变化并绘制)，resize 调度线程应用最终位置后再显示；
+// host 另外使用 WS_EX_COMPOSITED 双缓冲 (见 Win32Platform::embed)
+// 不使用 WM_SETREDRAW / LockWindowUpdate：前者部分应用不响应且忘记恢复时窗口不再绘制，后者全局只能锁一个窗口
+// 最多隐藏 MAX_CLOAK，之后无论布局是否稳定都恢复显示 (被 tab_view 隐藏的标签除外)
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::platform::Platform;
+use crate::tab_view;
+
+const MAX_CLOAK: Duration = Duration::from_millis(300);
+
+// 过渡中的窗口和对应的过渡序号 (同一窗口重新开始过渡时，之前的超时不再生效)
+static CLOAKED: Mutex<Vec<(isize, u64)>> = Mutex::new(Vec::new());
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 开始过渡：隐藏窗口画面直到 end 或超时
+pub fn begin(platform: &'static dyn Platform, hwnd: isize) {
+    if !platform.set_cloaked(hwnd, true) {
+        return;
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    {
+        let mut cloaked = CLOAKED.lock().unwrap();
+        cloaked.retain(|(h, _)| *h != hwnd);
+        cloaked.push((hwnd, generation));
+    }
+    std::thread::spawn(move || {
+        std::thread::sleep(MAX_CLOAK);
+        if take(hwnd, Some(generation)) && !tab_view::is_hidden(hwnd) {
+            debug!(target: "embed", "过渡超时，恢复显示: hwnd={}", hwnd);
+            platform.set_cloaked(hwnd, false);
+        }
+    });
+}
+
+/// 布局已稳定，恢复显示 (不在过渡中的窗口不处理)
+pub fn end(platform: &dyn Platform, hwnd: isize) {
+    if take(hwnd, None) && !tab_view::is_hidden(hwnd) {
+        platform.set_cloaked(hwnd, false);
+    }
+}
+
+// 移除过渡记录，generation 为 Some 时只移除该次过渡
+fn take(hwnd: isize, generation: Option<u64>) -> bool {
+    let mut cloaked = CLOAKED.lock().unwrap();
+    let Some(index) = cloaked
+        .iter()
+        .position(|&(h, g)| h == hwnd && generation.is_none_or(|generation| g == generation))
+    else {
+        return false;
+    };
+    cloaked.remove(index);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatform;
+
+    #[test]
+    fn end_uncloaks_only_windows_in_transition() {
+        let platform: &'static MockPlatform = Box::leak(Box::new(MockPlatform::new()));
+        let hwnd = platform.create_window("文档", "Notepad", None);
+        let other = platform.create_window("其他", "Notepad", None);
+
+        begin(platform, hwnd);
+        assert!(platform.window(hwnd).unwrap().cloaked);
+
+        // 不在过渡中的窗口保持原样
+        platform.set_cloaked(other, true);
+        end(platform, other);
+        assert!(platform.window(other).unwrap().cloaked);
+
+        end(platform, hwnd);
+        assert!(!platform.window(hwnd).unwrap().cloaked);
+    }
+}
@@ -0,0 +1,244 @@
+// 托盘图标与托盘菜单
+// 菜单会列出所有已嵌入的窗口，每个窗口提供 切换 / 弹出 / 关闭 / 焦点保护 子操作
+// 图标右下角显示标签数量角标，有标签请求关注时角标变红
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::warn;
+use tauri::{
+    image::Image,
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    AppHandle, Emitter, Manager,
+};
+
+pub const TRAY_ID: &str = "main";
+
+// 是否有后台标签请求关注 (托盘图标切换为红色角标)
+static ATTENTION: AtomicBool = AtomicBool::new(false);
+
+// 菜单项 ID 前缀，后接 hwnd
+const TAB_ACTIVATE_PREFIX: &str = "tab-activate:";
+const TAB_RELEASE_PREFIX: &str = "tab-release:";
+const TAB_CLOSE_PREFIX: &str = "tab-close:";
+const TAB_FOCUS_GUARD_PREFIX: &str = "tab-focus-guard:";
+
+fn truncate_title(title: &str) -> String {
+    if title.chars().count() > 30 {
+        format!("{}...", title.chars().take(27).collect::<String>())
+    } else {
+        title.to_string()
+    }
+}
+
+/// 根据当前嵌入的窗口构建托盘菜单
+pub fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let menu = Menu::new(app)?;
+
+    let hwnds = crate::window_manager::embedded_hwnds(app);
+    if hwnds.is_empty() {
+        let empty_i = MenuItem::with_id(app, "tabs-empty", "(没有嵌入的窗口)", false, None::<&str>)?;
+        menu.append(&empty_i)?;
+    }
+
+    let manager = app.state::<crate::window_manager::WindowManager>();
+    for hwnd in hwnds {
+        // 重命名过的标签显示自定义名称
+        let mut title = manager.label(hwnd).map(crate::privacy::title).unwrap_or_else(|| crate::get_window_title(hwnd));
+        if title.is_empty() {
+            title = format!("窗口 {}", hwnd);
+        }
+
+        let activate_i = MenuItem::with_id(app, format!("{}{}", TAB_ACTIVATE_PREFIX, hwnd), "切换到此标签", true, None::<&str>)?;
+        // 展台模式下不能弹出或关闭标签
+        let unlocked = !crate::kiosk::is_enabled();
+        let release_i = MenuItem::with_id(app, format!("{}{}", TAB_RELEASE_PREFIX, hwnd), "弹出窗口", unlocked, None::<&str>)?;
+        let close_i = MenuItem::with_id(app, format!("{}{}", TAB_CLOSE_PREFIX, hwnd), "关闭窗口", unlocked, None::<&str>)?;
+        let guard_i = CheckMenuItem::with_id(
+            app,
+            format!("{}{}", TAB_FOCUS_GUARD_PREFIX, hwnd),
+            "阻止抢占焦点",
+            crate::focus_guard::is_enabled(),
+            crate::focus_guard::is_tab_guarded(hwnd),
+            None::<&str>,
+        )?;
+        let submenu = Submenu::with_items(app, truncate_title(&title), true, &[&activate_i, &release_i, &close_i, &guard_i])?;
+        menu.append(&submenu)?;
+    }
+
+    let separator = PredefinedMenuItem::separator(app)?;
+    let focus_guard_i = CheckMenuItem::with_id(app, "focus-guard", "阻止嵌入窗口抢占焦点", true, crate::focus_guard::is_enabled(), None::<&str>)?;
+    let privacy_i = CheckMenuItem::with_id(app, "privacy-mode", "隐私模式 (共享屏幕时)", true, crate::privacy::is_enabled(), None::<&str>)?;
+    let show_i = MenuItem::with_id(app, "show", "显示主窗口", true, None::<&str>)?;
+    let quit_text = if crate::kiosk::is_enabled() { "退出 WindowHub (需要 PIN)..." } else { "退出 WindowHub" };
+    let quit_i = MenuItem::with_id(app, "quit", quit_text, true, None::<&str>)?;
+    menu.append(&separator)?;
+    menu.append(&focus_guard_i)?;
+    menu.append(&privacy_i)?;
+    menu.append(&show_i)?;
+    menu.append(&quit_i)?;
+
+    Ok(menu)
+}
+
+/// 重建托盘菜单和角标 (嵌入/释放窗口后调用)
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => warn!(target: "tray", "重建托盘菜单失败: {:?}", e),
+    }
+    update_icon(app);
+}
+
+/// 重新添加托盘图标 (资源管理器重启后任务栏中的图标会消失)
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn recreate(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    let _ = tray.set_visible(false);
+    let _ = tray.set_visible(true);
+    refresh(app);
+}
+
+/// 设置/清除关注状态，并立即更新托盘图标
+pub fn set_attention(app: &AppHandle, attention: bool) {
+    if ATTENTION.swap(attention, Ordering::SeqCst) != attention {
+        update_icon(app);
+    }
+}
+
+/// 根据标签数量和关注状态重绘托盘图标
+pub fn update_icon(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    let Some(base) = app.default_window_icon() else { return };
+
+    let count = app.state::<crate::window_manager::WindowManager>().len();
+    let attention = ATTENTION.load(Ordering::SeqCst);
+
+    let _ = tray.set_icon(Some(render_badge(base, count, attention)));
+
+    let tooltip = if attention {
+        format!("WindowHub - {} 个标签 (有标签请求关注)", count)
+    } else {
+        format!("WindowHub - {} 个标签", count)
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+// ============================================================
+// 角标绘制：在图标右下角画一个圆形底色 + 3x5 点阵数字
+// ============================================================
+
+// 每个字形 5 行，每行低 3 位为像素
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        _ => [0b010, 0b010, 0b010, 0b000, 0b010], // '!'
+    }
+}
+
+fn render_badge(base: &Image<'_>, count: usize, attention: bool) -> Image<'static> {
+    let width = base.width() as i32;
+    let height = base.height() as i32;
+    let mut rgba = base.rgba().to_vec();
+
+    if count == 0 && !attention {
+        return Image::new_owned(rgba, width as u32, height as u32);
+    }
+
+    let text = match count {
+        0 => "!".to_string(),
+        1..=9 => count.to_string(),
+        _ => "9+".to_string(),
+    };
+    // 关注状态用红色，普通状态用蓝色
+    let color: [u8; 3] = if attention { [0xf3, 0x8b, 0xa8] } else { [0x89, 0xb4, 0xfa] };
+
+    let mut put = |x: i32, y: i32, rgb: [u8; 3]| {
+        if x < 0 || y < 0 || x >= width || y >= height { return; }
+        let i = ((y * width + x) * 4) as usize;
+        rgba[i..i + 4].copy_from_slice(&[rgb[0], rgb[1], rgb[2], 0xff]);
+    };
+
+    // 圆形底色
+    let radius = (width.min(height) as f32 * 0.3) as i32;
+    let cx = width - radius - 1;
+    let cy = height - radius - 1;
+    for y in (cy - radius)..=(cy + radius) {
+        for x in (cx - radius)..=(cx + radius) {
+            if (x - cx).pow(2) + (y - cy).pow(2) <= radius.pow(2) {
+                put(x, y, color);
+            }
+        }
+    }
+
+    // 居中绘制文字
+    let chars: Vec<char> = text.chars().collect();
+    let scale = ((radius * 2) as f32 * 0.6 / 5.0).max(1.0) as i32;
+    let text_w = chars.len() as i32 * 4 * scale - scale;
+    let text_h = 5 * scale;
+    let left = cx - text_w / 2;
+    let top = cy - text_h / 2;
+    for (n, c) in chars.iter().enumerate() {
+        let origin_x = left + n as i32 * 4 * scale;
+        for (row, bits) in glyph(*c).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 { continue; }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        put(origin_x + col * scale + dx, top + row as i32 * scale + dy, [0x11, 0x11, 0x1b]);
+                    }
+                }
+            }
+        }
+    }
+
+    Image::new_owned(rgba, width as u32, height as u32)
+}
+
+/// 处理标签相关的托盘菜单项，返回是否已处理
+pub fn handle_tab_menu_event(app: &AppHandle, id: &str) -> bool {
+    let parse = |prefix: &str| id.strip_prefix(prefix).and_then(|h| h.parse::<isize>().ok());
+
+    if let Some(hwnd) = parse(TAB_ACTIVATE_PREFIX) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        set_attention(app, false);
+        let _ = app.emit("tray-activate-tab", hwnd);
+        return true;
+    }
+
+    if let Some(hwnd) = parse(TAB_RELEASE_PREFIX) {
+        // 前端通过 window-released 事件移除标签；释放不在主线程上执行
+        tauri::async_runtime::spawn(crate::release_window(app.clone(), hwnd));
+        return true;
+    }
+
+    if let Some(hwnd) = parse(TAB_FOCUS_GUARD_PREFIX) {
+        let guarded = crate::focus_guard::is_tab_guarded(hwnd);
+        if let Err(e) = crate::focus_guard::set_tab_guarded(hwnd, !guarded) {
+            warn!(target: "tray", "设置焦点保护失败: {}", e);
+        }
+        refresh(app);
+        return true;
+    }
+
+    if let Some(hwnd) = parse(TAB_CLOSE_PREFIX) {
+        tauri::async_runtime::spawn(crate::close_target_window(app.clone(), hwnd));
+        return true;
+    }
+
+    false
+}
@@ -0,0 +1,192 @@
+// 重启标签前后保留应用的轻量界面状态
+// 重启 (restart_tab) 前用 UI Automation 读取：
+// - 第一个可滚动区域 (ScrollPattern) 的水平/垂直滚动百分比
+// - 缩放比例 (TransformPattern2，支持的应用)
+// - 选中的选项卡名称 (TabItem 的 SelectionItemPattern)
+// 新窗口重新嵌入后按同样的方式找到对应的控件，恢复滚动位置、缩放和选中的选项卡。
+// 文档类应用重新打开后通常回到第一页，这样 "重启标签" 不那么像从头开始；读不到的部分跳过，不影响重启。
+// 状态只保存在内存中 (按标签 ID)，恢复一次后丢弃。目前没有挂起标签的功能，以后添加时同样在挂起前 capture
+
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+#[cfg(windows)]
+use windows::{
+    core::VARIANT,
+    Win32::{
+        Foundation::HWND,
+        System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED},
+        UI::Accessibility::*,
+    },
+};
+
+// 读取到的状态 (标签 ID, 状态)
+static SAVED: Mutex<Vec<(u64, UiState)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UiState {
+    pub scroll: Option<(f64, f64)>, // 水平、垂直滚动百分比 (0~100，不能滚动的方向为 -1)
+    pub zoom: Option<f64>,
+    pub selected_tabs: Vec<String>,
+}
+
+impl UiState {
+    fn is_empty(&self) -> bool {
+        self.scroll.is_none() && self.zoom.is_none() && self.selected_tabs.is_empty()
+    }
+}
+
+/// 读取窗口的界面状态，保存到标签 (读不到任何状态时不保存)
+pub fn capture(tab_id: u64, hwnd: isize) {
+    let state = read(hwnd);
+    debug!(target: "embed", "标签 {} 的界面状态: {:?}", tab_id, state);
+    let mut saved = SAVED.lock().unwrap();
+    saved.retain(|(id, _)| *id != tab_id);
+    if !state.is_empty() {
+        saved.push((tab_id, state));
+    }
+}
+
+/// 取出标签保存的状态
+pub fn take(tab_id: u64) -> Option<UiState> {
+    let mut saved = SAVED.lock().unwrap();
+    let index = saved.iter().position(|(id, _)| *id == tab_id)?;
+    Some(saved.remove(index).1)
+}
+
+/// 恢复到新窗口，返回是否全部恢复 (应用还在加载时可以稍后再试)
+pub fn restore(hwnd: isize, state: &UiState) -> bool {
+    let restored = apply(hwnd, state);
+    if restored {
+        info!(target: "embed", "已恢复界面状态: hwnd={}, {:?}", hwnd, state);
+    }
+    restored
+}
+
+// 只能滚动一个方向时另一个方向保持不变
+#[cfg_attr(not(windows), allow(dead_code))]
+fn scroll_target(saved: f64) -> f64 {
+    if saved < 0.0 {
+        -1.0 // UIA_ScrollPatternNoScroll
+    } else {
+        saved.min(100.0)
+    }
+}
+
+#[cfg(windows)]
+fn automation() -> Option<IUIAutomation> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()
+    }
+}
+
+// 窗口中第一个支持 property 对应模式的元素
+#[cfg(windows)]
+unsafe fn find_first(automation: &IUIAutomation, window: &IUIAutomationElement, property: UIA_PROPERTY_ID) -> Option<IUIAutomationElement> {
+    let condition = automation.CreatePropertyCondition(property, &VARIANT::from(true)).ok()?;
+    window.FindFirst(TreeScope_Subtree, &condition).ok()
+}
+
+// 窗口中所有的选项卡
+#[cfg(windows)]
+unsafe fn tab_items(automation: &IUIAutomation, window: &IUIAutomationElement) -> Vec<(String, IUIAutomationSelectionItemPattern)> {
+    let Ok(condition) = automation.CreatePropertyCondition(UIA_ControlTypePropertyId, &VARIANT::from(UIA_TabItemControlTypeId.0)) else {
+        return Vec::new();
+    };
+    let Ok(items) = window.FindAll(TreeScope_Descendants, &condition) else { return Vec::new() };
+    let mut result = Vec::new();
+    for i in 0..items.Length().unwrap_or(0) {
+        let Ok(item) = items.GetElement(i) else { continue };
+        let Ok(pattern) = item.GetCurrentPatternAs::<IUIAutomationSelectionItemPattern>(UIA_SelectionItemPatternId) else { continue };
+        let name = item.CurrentName().map(|n| n.to_string()).unwrap_or_default();
+        if !name.is_empty() {
+            result.push((name, pattern));
+        }
+    }
+    result
+}
+
+#[cfg(windows)]
+fn read(hwnd: isize) -> UiState {
+    let mut state = UiState::default();
+    let Some(automation) = automation() else { return state };
+    unsafe {
+        let Ok(window) = automation.ElementFromHandle(HWND(hwnd as *mut _)) else { return state };
+        if let Some(element) = find_first(&automation, &window, UIA_IsScrollPatternAvailablePropertyId) {
+            if let Ok(scroll) = element.GetCurrentPatternAs::<IUIAutomationScrollPattern>(UIA_ScrollPatternId) {
+                if let (Ok(h), Ok(v)) = (scroll.CurrentHorizontalScrollPercent(), scroll.CurrentVerticalScrollPercent()) {
+                    state.scroll = Some((h, v));
+                }
+            }
+        }
+        if let Some(element) = find_first(&automation, &window, UIA_IsTransformPattern2AvailablePropertyId) {
+            if let Ok(transform) = element.GetCurrentPatternAs::<IUIAutomationTransformPattern2>(UIA_TransformPattern2Id) {
+                if transform.CurrentCanZoom().is_ok_and(|can| can.as_bool()) {
+                    state.zoom = transform.CurrentZoomLevel().ok();
+                }
+            }
+        }
+        state.selected_tabs = tab_items(&automation, &window)
+            .into_iter()
+            .filter(|(_, pattern)| pattern.CurrentIsSelected().is_ok_and(|selected| selected.as_bool()))
+            .map(|(name, _)| name)
+            .collect();
+    }
+    state
+}
+
+#[cfg(not(windows))]
+fn read(_hwnd: isize) -> UiState {
+    UiState::default()
+}
+
+#[cfg(windows)]
+fn apply(hwnd: isize, state: &UiState) -> bool {
+    let Some(automation) = automation() else { return false };
+    let mut complete = true;
+    unsafe {
+        let Ok(window) = automation.ElementFromHandle(HWND(hwnd as *mut _)) else { return false };
+        // 先切换选项卡，滚动位置和缩放属于选中的页面
+        if !state.selected_tabs.is_empty() {
+            let items = tab_items(&automation, &window);
+            for name in &state.selected_tabs {
+                match items.iter().find(|(n, _)| n == name) {
+                    Some((_, pattern)) => complete &= pattern.Select().is_ok(),
+                    None => complete = false,
+                }
+            }
+        }
+        if let Some(zoom) = state.zoom {
+            let transform = find_first(&automation, &window, UIA_IsTransformPattern2AvailablePropertyId)
+                .and_then(|e| e.GetCurrentPatternAs::<IUIAutomationTransformPattern2>(UIA_TransformPattern2Id).ok());
+            complete &= transform.is_some_and(|t| t.Zoom(zoom).is_ok());
+        }
+        if let Some((h, v)) = state.scroll {
+            let scroll = find_first(&automation, &window, UIA_IsScrollPatternAvailablePropertyId)
+                .and_then(|e| e.GetCurrentPatternAs::<IUIAutomationScrollPattern>(UIA_ScrollPatternId).ok());
+            complete &= scroll.is_some_and(|s| s.SetScrollPercent(scroll_target(h), scroll_target(v)).is_ok());
+        }
+    }
+    complete
+}
+
+#[cfg(not(windows))]
+fn apply(_hwnd: isize, _state: &UiState) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_non_empty_state_until_taken() {
+        SAVED.lock().unwrap().push((7, UiState { scroll: Some((-1.0, 42.0)), ..Default::default() }));
+        assert!(take(8).is_none());
+        assert_eq!(take(7).unwrap().scroll, Some((-1.0, 42.0)));
+        assert!(take(7).is_none());
+        assert_eq!(scroll_target(-1.0), -1.0);
+        assert_eq!(scroll_target(120.0), 100.0);
+    }
+}
@@ -0,0 +1,139 @@
+// 自动更新 (tauri-plugin-updater)
+// 更新清单地址来自设置 updates.endpoint，其中 {{channel}} 替换为发布通道 updates.channel (如 stable / beta)，
+// 插件自己的 {{target}} / {{arch}} / {{current_version}} 照常替换；更新包签名的公钥在 tauri.conf.json 的 plugins.updater.pubkey
+// (发布时填入 tauri signer generate 生成的公钥)，公钥为空的构建停用自动更新：检查和安装都返回 Err，启动时不检查
+// - check_for_updates：有新版本时发送 update-available (载荷为 UpdateInfo)，启动时按 updates.check_on_startup 检查一次
+// - install_update：下载时发送 update-progress (载荷为 UpdateProgress)，下载完成后安装并重启
+// 安装前先释放所有嵌入窗口：Windows 上安装程序启动后进程直接退出，不经过关闭主窗口时的释放流程，
+// 嵌入的窗口会随 WindowHub 一起消失
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_updater::{Updater, UpdaterExt};
+use tracing::{info, warn};
+
+use crate::window_manager::WindowManager;
+
+/// update-available 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: String,
+    pub notes: Option<String>,
+}
+
+/// update-progress 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// 检查更新，有新版本时发送 update-available 并返回版本信息
+pub async fn check(app: &AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let channel = crate::settings::get().updates.channel;
+    let update = updater(app)?.check().await.map_err(|e| format!("检查更新失败: {}", e))?;
+    let Some(update) = update else {
+        info!(target: "settings", "已是最新版本 (通道 {})", channel);
+        return Ok(None);
+    };
+    let info = UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        channel,
+        notes: update.body.clone(),
+    };
+    info!(target: "settings", "发现新版本: {} -> {} (通道 {})", info.current_version, info.version, info.channel);
+    let _ = app.emit("update-available", &info);
+    Ok(Some(info))
+}
+
+/// 下载并安装新版本，成功后重启 (Windows 上由安装程序结束当前进程)
+pub async fn install(app: &AppHandle) -> Result<(), String> {
+    let update = updater(app)?
+        .check()
+        .await
+        .map_err(|e| format!("检查更新失败: {}", e))?
+        .ok_or("已是最新版本")?;
+    info!(target: "settings", "开始下载新版本: {}", update.version);
+
+    let mut downloaded = 0u64;
+    update
+        .download_and_install(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = app.emit("update-progress", UpdateProgress { downloaded, total });
+            },
+            || info!(target: "settings", "新版本下载完成，开始安装"),
+        )
+        .await
+        .map_err(|e| format!("安装更新失败: {}", e))?;
+
+    release_all(app);
+    app.restart();
+}
+
+/// 启动时在后台检查一次更新 (设置 updates.check_on_startup，未配置更新地址时跳过)
+pub fn check_on_startup(app: &AppHandle) {
+    let settings = crate::settings::get().updates;
+    if !settings.check_on_startup || settings.endpoint.trim().is_empty() || !has_pubkey(app) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = check(&app).await {
+            warn!(target: "settings", "{}", e);
+        }
+    });
+}
+
+fn updater(app: &AppHandle) -> Result<Updater, String> {
+    if !has_pubkey(app) {
+        return Err("此版本没有配置更新签名公钥，自动更新已停用".to_string());
+    }
+    let settings = crate::settings::get().updates;
+    let endpoint = endpoint_for(&settings.endpoint, &settings.channel)?;
+    let handle = app.clone();
+    app.updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(|e| format!("更新地址无效: {}", e))?
+        .on_before_exit(move || release_all(&handle))
+        .build()
+        .map_err(|e| format!("无法创建更新器: {}", e))
+}
+
+// tauri.conf.json 中配置了验证更新包签名的公钥
+fn has_pubkey(app: &AppHandle) -> bool {
+    let config = app.config().plugins.0.get("updater");
+    config.and_then(|c| c.get("pubkey")).and_then(|k| k.as_str()).is_some_and(|k| !k.trim().is_empty())
+}
+
+// 进程即将被替换，嵌入的窗口恢复为独立窗口
+fn release_all(app: &AppHandle) {
+    info!(target: "settings", "安装更新前释放所有嵌入窗口");
+    app.state::<WindowManager>().release_all(crate::platform::current());
+}
+
+/// 发布通道对应的更新清单地址
+pub fn endpoint_for(template: &str, channel: &str) -> Result<Url, String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Err("未配置更新地址 (设置 updates.endpoint)".to_string());
+    }
+    let url = template.replace("{{channel}}", channel.trim());
+    Url::parse(&url).map_err(|e| format!("更新地址无效 {}: {}", url, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_includes_the_release_channel() {
+        let url = endpoint_for("https://updates.example.com/{{channel}}/{{target}}/latest.json", "beta").unwrap();
+        assert_eq!(url.as_str(), "https://updates.example.com/beta/%7B%7Btarget%7D%7D/latest.json");
+        assert!(endpoint_for("", "stable").is_err());
+        assert!(endpoint_for("not a url {{channel}}", "stable").is_err());
+    }
+}
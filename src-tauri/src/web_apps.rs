@@ -0,0 +1,159 @@
+// 网页应用 (create_web_app)
+// 把一个网址做成可以启动的应用，"把 Gmail 嵌入成单独的标签" 只需一步：
+// - Chromium 系浏览器 (Chrome / Edge / Brave / Vivaldi) 用 --app=网址 打开没有地址栏的窗口，
+//   --user-data-dir 指向 %APPDATA%\WindowHub\web_apps\<配置名>，每个配置单独登录，也保证启动的是新进程
+// - Firefox 没有应用模式，用 -P 配置名 在新窗口中打开
+// 生成的 AppInfo (浏览器路径和参数) 保存在数据库中 (storage.rs)，出现在应用搜索结果中 (app_index.rs)，
+// 用 launch_app(path, args) 启动并嵌入
+// browser 可以是浏览器名称 (从注册表的 App Paths 查找安装位置) 或浏览器程序的完整路径
+
+use tracing::{info, warn};
+
+use crate::AppInfo;
+
+const STORAGE_KEY: &str = "web_apps";
+
+// 浏览器名称和程序文件名
+const BROWSERS: [(&str, &str); 5] = [
+    ("chrome", "chrome.exe"),
+    ("edge", "msedge.exe"),
+    ("brave", "brave.exe"),
+    ("vivaldi", "vivaldi.exe"),
+    ("firefox", "firefox.exe"),
+];
+
+// 没有指定配置时使用的配置名
+const DEFAULT_PROFILE: &str = "default";
+
+/// 保存的网页应用
+pub fn list() -> Vec<AppInfo> {
+    crate::storage::get().and_then(|s| s.load::<Vec<AppInfo>>(STORAGE_KEY)).unwrap_or_default()
+}
+
+/// 生成网页应用并保存 (同名的替换)，返回生成的 AppInfo
+pub fn create(url: &str, browser: &str, profile: Option<&str>) -> Result<AppInfo, String> {
+    let url = url.trim();
+    let name = app_name(url).ok_or("网址必须以 http:// 或 https:// 开头")?;
+    let browser_path = browser_path(browser.trim()).ok_or_else(|| format!("找不到浏览器: {}", browser))?;
+    let profile = profile.map(str::trim).filter(|p| !p.is_empty());
+
+    let args = if is_firefox(&browser_path) {
+        firefox_args(url, profile)
+    } else {
+        let profile = sanitize_profile(profile.unwrap_or(DEFAULT_PROFILE));
+        let dir = crate::paths::data_dir().ok_or("找不到数据目录")?.join("web_apps").join(profile);
+        chromium_args(url, &dir.to_string_lossy())
+    };
+    let app = AppInfo { name, path: browser_path, args };
+
+    let mut apps = list();
+    apps.retain(|a| a.name != app.name);
+    apps.push(app.clone());
+    save(&apps);
+    info!(target: "launcher", "创建网页应用: {} ({})", app.name, app.path);
+    Ok(app)
+}
+
+/// 删除网页应用，返回新的列表
+pub fn remove(name: &str) -> Vec<AppInfo> {
+    let mut apps = list();
+    apps.retain(|a| a.name != name);
+    save(&apps);
+    info!(target: "launcher", "删除网页应用: {}", name);
+    apps
+}
+
+fn save(apps: &[AppInfo]) {
+    if let Some(storage) = crate::storage::get() {
+        if let Err(e) = storage.save(STORAGE_KEY, apps) {
+            warn!(target: "launcher", "{}", e);
+        }
+    }
+}
+
+// 网页应用的名称：网址的主机名 (去掉 www.)，不是 http/https 网址时为 None
+fn app_name(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))?;
+    let host = rest.split(['/', '?', '#']).next()?.rsplit('@').next()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+fn chromium_args(url: &str, user_data_dir: &str) -> Vec<String> {
+    vec![format!("--app={}", url), format!("--user-data-dir={}", user_data_dir)]
+}
+
+// -P 指定配置时加上 -no-remote，Firefox 已经在运行时也按指定的配置打开
+fn firefox_args(url: &str, profile: Option<&str>) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(profile) = profile {
+        args.extend(["-P".to_string(), profile.to_string(), "-no-remote".to_string()]);
+    }
+    args.extend(["--new-window".to_string(), url.to_string()]);
+    args
+}
+
+// 配置名用作目录名，去掉不能出现在路径中的字符
+fn sanitize_profile(profile: &str) -> String {
+    let name: String = profile.chars().filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' ')).collect();
+    let name = name.trim().trim_matches('.');
+    if name.is_empty() { DEFAULT_PROFILE.to_string() } else { name.to_string() }
+}
+
+fn is_firefox(path: &str) -> bool {
+    path.rsplit(['\\', '/']).next().is_some_and(|name| name.eq_ignore_ascii_case("firefox.exe"))
+}
+
+// 浏览器名称或程序路径对应的程序路径
+fn browser_path(browser: &str) -> Option<String> {
+    if let Some((_, exe)) = BROWSERS.iter().find(|(name, _)| name.eq_ignore_ascii_case(browser)) {
+        return app_path(exe);
+    }
+    let path = std::path::Path::new(browser);
+    (path.is_absolute() && path.is_file()).then(|| browser.to_string())
+}
+
+// 注册表 App Paths 中登记的程序位置 (先当前用户，再本机)
+#[cfg(windows)]
+fn app_path(exe: &str) -> Option<String> {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    let key = HSTRING::from(format!(r"Software\Microsoft\Windows\CurrentVersion\App Paths\{}", exe));
+    let read = |root: HKEY| {
+        let mut buffer = [0u16; 1024];
+        let mut size = std::mem::size_of_val(&buffer) as u32;
+        let status = unsafe {
+            RegGetValueW(root, &key, None, RRF_RT_REG_SZ, None, Some(buffer.as_mut_ptr() as *mut _), Some(&mut size))
+        };
+        let len = (size as usize / 2).saturating_sub(1);
+        status.is_ok().then(|| String::from_utf16_lossy(&buffer[..len]).trim_matches('"').to_string())
+    };
+    read(HKEY_CURRENT_USER).or_else(|| read(HKEY_LOCAL_MACHINE)).filter(|p| std::path::Path::new(p).is_file())
+}
+
+#[cfg(not(windows))]
+fn app_path(_exe: &str) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_browser_command_lines() {
+        assert_eq!(app_name("https://mail.google.com/mail/u/0/"), Some("mail.google.com".to_string()));
+        assert_eq!(app_name("http://www.example.com?x=1"), Some("example.com".to_string()));
+        assert_eq!(app_name("mail.google.com"), None);
+
+        assert_eq!(
+            chromium_args("https://mail.google.com", r"C:\Data\gmail"),
+            vec!["--app=https://mail.google.com", r"--user-data-dir=C:\Data\gmail"]
+        );
+        assert_eq!(firefox_args("https://a.com", None), vec!["--new-window", "https://a.com"]);
+        assert_eq!(firefox_args("https://a.com", Some("work")), vec!["-P", "work", "-no-remote", "--new-window", "https://a.com"]);
+        assert_eq!(sanitize_profile(r"..\工作/邮箱"), "工作邮箱");
+        assert!(is_firefox(r"C:\Program Files\Mozilla Firefox\FIREFOX.EXE"));
+    }
+}
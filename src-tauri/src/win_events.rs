@@ -0,0 +1,412 @@
+// 系统事件监听 (WinEvent 钩子 + Shell 钩子)
+// 在独立线程上运行消息循环，把嵌入窗口相关的系统事件转发给前端
+// - 闪烁任务栏 / 弹出对话框 -> tab-attention
+// - 嵌入应用弹出的对话框、设置窗口 -> 居中到主窗口上方并发送 child-dialog-opened/closed
+// - 嵌入窗口自行最小化 -> 立即还原并发送 tab-minimize-blocked
+// - 前台窗口切换 -> 交给 focus_guard 判断是否是嵌入应用抢占焦点
+// - 系统设置变化 (高对比度、动画、文本大小、深色模式、强调色) -> 交给 system_prefs 和 theme 重新读取
+// - 任务栏重建 (资源管理器重启) -> 交给 shell_restart 恢复托盘图标和嵌入窗口
+// - 锁屏、快速用户切换 (WM_WTSSESSION_CHANGE) -> 交给 session_watch 暂停和恢复钩子、定时任务
+// - 嵌入窗口标题变化 -> 交给 title_watch 发送 title-changed
+// - 嵌入应用调整了主窗口子窗口的 Z 序 -> 交给 child_order 检查是否打乱了指定的顺序
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicIsize, AtomicU32, Ordering};
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM},
+        System::LibraryLoader::GetModuleHandleW,
+        System::RemoteDesktop::{WTSRegisterSessionNotification, WTSUnRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION},
+        UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK},
+        UI::WindowsAndMessaging::*,
+    },
+};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// HSHELL_FLASH = HSHELL_REDRAW | HSHELL_HIGHBIT，windows crate 未导出
+#[cfg(windows)]
+const HSHELL_FLASH: u32 = 0x8006;
+
+// RegisterWindowMessage("SHELLHOOK") 的返回值
+#[cfg(windows)]
+static SHELLHOOK_MSG: AtomicU32 = AtomicU32::new(0);
+
+// RegisterWindowMessage("TaskbarCreated") 的返回值
+#[cfg(windows)]
+static TASKBAR_CREATED_MSG: AtomicU32 = AtomicU32::new(0);
+
+// 主窗口句柄 (子对话框居中/置顶的参照)
+#[cfg(windows)]
+static HOST_HWND: AtomicIsize = AtomicIsize::new(0);
+
+// 当前打开的子对话框: (对话框 hwnd, 所属标签 hwnd)
+static CHILD_DIALOGS: Mutex<Vec<(isize, isize)>> = Mutex::new(Vec::new());
+
+// 最近处理过的事件 (诊断信息导出用)
+const EVENT_HISTORY_LIMIT: usize = 200;
+static EVENT_HISTORY: Mutex<VecDeque<EventRecord>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRecord {
+    pub time: u64, // Unix 毫秒
+    pub kind: &'static str,
+    pub hwnd: isize,
+    pub tab: Option<isize>,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TabAttention {
+    pub hwnd: isize,
+    pub reason: &'static str, // "flash" | "dialog" | "focus"
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChildDialog {
+    pub tab: isize,
+    pub hwnd: isize,
+    pub title: String,
+}
+
+/// 启动事件监听线程 (setup 时调用一次)
+pub fn start(app: &AppHandle) {
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+    #[cfg(windows)]
+    {
+        HOST_HWND.store(crate::get_main_window_hwnd(app.clone()), Ordering::SeqCst);
+        std::thread::spawn(|| unsafe { hook_thread() });
+    }
+}
+
+/// 记录一条事件 (其他模块处理系统事件时也可调用)
+pub(crate) fn record_event(kind: &'static str, hwnd: isize, tab: Option<isize>, detail: String) {
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let mut history = EVENT_HISTORY.lock().unwrap();
+    if history.len() >= EVENT_HISTORY_LIMIT {
+        history.pop_front();
+    }
+    history.push_back(EventRecord { time, kind, hwnd, tab, detail });
+}
+
+/// 最近的事件记录 (从旧到新)
+pub fn recent_events() -> Vec<EventRecord> {
+    EVENT_HISTORY.lock().unwrap().iter().cloned().collect()
+}
+
+// 当前嵌入的窗口句柄 (钩子回调中没有 State，经由全局 AppHandle 获取)
+fn embedded_hwnds() -> Vec<isize> {
+    APP.get().map(crate::window_manager::embedded_hwnds).unwrap_or_default()
+}
+
+/// 主窗口句柄
+#[cfg(windows)]
+pub(crate) fn host_hwnd() -> HWND {
+    HWND(HOST_HWND.load(Ordering::SeqCst) as *mut _)
+}
+
+// 需要监听的事件范围 (min, max, 是否忽略本进程事件)
+#[cfg(windows)]
+const HOOKED_EVENTS: [(u32, u32, bool); 6] = [
+    // 对话框弹出 (模态提示框等)
+    (EVENT_SYSTEM_DIALOGSTART, EVENT_SYSTEM_DIALOGSTART, true),
+    // 嵌入窗口被最小化
+    (EVENT_SYSTEM_MINIMIZESTART, EVENT_SYSTEM_MINIMIZESTART, true),
+    // 窗口销毁/显示/隐藏 (跟踪嵌入应用的子对话框)
+    (EVENT_OBJECT_DESTROY, EVENT_OBJECT_HIDE, true),
+    // 前台切换 (焦点保护)，嵌入窗口抢焦点时前台会变成主窗口，所以不能忽略本进程
+    (EVENT_SYSTEM_FOREGROUND, EVENT_SYSTEM_FOREGROUND, false),
+    // 子窗口 Z 序变化，只关心其他进程 (嵌入应用) 产生的
+    (EVENT_OBJECT_REORDER, EVENT_OBJECT_REORDER, true),
+    // 标题变化 (更新标签标题)
+    (EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_NAMECHANGE, true),
+];
+
+#[cfg(windows)]
+unsafe fn hook_thread() {
+    let hooks: Vec<HWINEVENTHOOK> = HOOKED_EVENTS
+        .iter()
+        .map(|(min, max, skip_own)| {
+            let flags = if *skip_own { WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS } else { WINEVENT_OUTOFCONTEXT };
+            SetWinEventHook(*min, *max, None, Some(win_event_proc), 0, 0, flags)
+        })
+        .collect();
+    let hook_count = hooks.iter().filter(|h| !h.is_invalid()).count() as u32;
+    let resource = crate::resources::register(crate::resources::ResourceKind::WinEventHook, "win_events", hook_count, None, None);
+
+    // Shell 钩子窗口：接收 FlashWindow 产生的 HSHELL_FLASH 通知
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let class_name = w!("WindowHubShellHook");
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(shell_wnd_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+    let shell_window = CreateWindowExW(WINDOW_EX_STYLE(0), class_name, w!(""), WINDOW_STYLE(0), 0, 0, 0, 0, HWND::default(), None, instance, None);
+    match &shell_window {
+        Ok(hwnd) => {
+            let hwnd = *hwnd;
+            SHELLHOOK_MSG.store(RegisterWindowMessageW(w!("SHELLHOOK")), Ordering::SeqCst);
+            TASKBAR_CREATED_MSG.store(RegisterWindowMessageW(w!("TaskbarCreated")), Ordering::SeqCst);
+            if !RegisterShellHookWindow(hwnd).as_bool() {
+                warn!(target: "events", "注册 Shell 钩子失败");
+            }
+            // 锁屏、切换用户的通知也发给这个窗口
+            if let Err(e) = WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) {
+                warn!(target: "events", "注册会话通知失败: {:?}", e);
+            }
+        }
+        Err(e) => warn!(target: "events", "创建 Shell 钩子窗口失败: {:?}", e),
+    }
+
+    info!(target: "events", "系统事件监听已启动");
+
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+    }
+
+    if let Ok(hwnd) = shell_window {
+        let _ = WTSUnRegisterSessionNotification(hwnd);
+    }
+    for hook in hooks {
+        let _ = UnhookWinEvent(hook);
+    }
+    crate::resources::unregister(resource);
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn win_event_proc(
+    _hook: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    _id_child: i32,
+    _event_thread: u32,
+    event_time: u32,
+) {
+    // Z 序变化时 hwnd 为子窗口所在的容器
+    if event == EVENT_OBJECT_REORDER {
+        if hwnd == host_hwnd() {
+            if let Some(app) = APP.get() {
+                crate::child_order::reordered(app, hwnd.0 as isize, &embedded_hwnds());
+            }
+        }
+        return;
+    }
+    if id_object != OBJID_WINDOW.0 || hwnd.is_invalid() {
+        return;
+    }
+    match event {
+        EVENT_SYSTEM_DIALOGSTART => {
+            if let Some(tab) = find_embedded_tab(hwnd) {
+                notify_attention(tab, "dialog");
+            }
+        }
+        EVENT_SYSTEM_MINIMIZESTART => on_minimize(hwnd),
+        EVENT_SYSTEM_FOREGROUND => crate::focus_guard::on_foreground_changed(hwnd, event_time),
+        EVENT_OBJECT_SHOW => on_window_shown(hwnd),
+        EVENT_OBJECT_HIDE | EVENT_OBJECT_DESTROY => on_window_hidden(hwnd),
+        EVENT_OBJECT_NAMECHANGE => crate::title_watch::name_changed(hwnd.0 as isize),
+        _ => {}
+    }
+}
+
+// 嵌入窗口在容器内最小化后会变成空白且无法恢复，直接还原
+#[cfg(windows)]
+unsafe fn on_minimize(hwnd: HWND) {
+    let raw = hwnd.0 as isize;
+    if !embedded_hwnds().contains(&raw) {
+        return;
+    }
+    info!(target: "events", "拦截嵌入窗口最小化: hwnd={}", raw);
+    record_event("minimize-blocked", raw, Some(raw), String::new());
+    // 异步还原，避免目标线程卡住时阻塞钩子线程
+    let _ = ShowWindowAsync(hwnd, SW_RESTORE);
+    let _ = ShowWindowAsync(hwnd, SW_SHOW);
+    if let Some(app) = APP.get() {
+        let _ = app.emit("tab-minimize-blocked", raw);
+    }
+}
+
+// 判断是否是需要托管的对话框/弹出窗口 (排除菜单、提示框等临时窗口)
+#[cfg(windows)]
+unsafe fn is_managed_popup(hwnd: HWND) -> bool {
+    // 只处理顶层窗口，嵌入后的 WS_CHILD 窗口不算
+    if GetAncestor(hwnd, GA_ROOT) != hwnd {
+        return false;
+    }
+    let class_name = crate::platform::win32::class_name(hwnd);
+    if class_name == "#32768" || class_name.contains("tooltips") {
+        return false;
+    }
+    let style = crate::platform::win32::WindowStyle::style(hwnd).get().unwrap_or(0);
+    if class_name != "#32770" && style & WS_CAPTION.0 != WS_CAPTION.0 {
+        return false;
+    }
+    let mut rect = RECT::default();
+    if GetWindowRect(hwnd, &mut rect).is_err() {
+        return false;
+    }
+    rect.right - rect.left > 50 && rect.bottom - rect.top > 50
+}
+
+#[cfg(windows)]
+unsafe fn on_window_shown(hwnd: HWND) {
+    let raw = hwnd.0 as isize;
+    if embedded_hwnds().contains(&raw) || !is_managed_popup(hwnd) {
+        return;
+    }
+    let Some(tab) = find_embedded_tab(hwnd) else { return };
+
+    {
+        let mut dialogs = CHILD_DIALOGS.lock().unwrap();
+        if dialogs.iter().any(|(h, _)| *h == raw) {
+            return;
+        }
+        dialogs.push((raw, tab));
+    }
+
+    let host = host_hwnd();
+    if !host.is_invalid() {
+        // 所有者设为主窗口，保证对话框始终在 WindowHub 之上
+        SetWindowLongPtrW(hwnd, GWLP_HWNDPARENT, host.0 as isize);
+
+        // 居中到主窗口
+        let mut host_rect = RECT::default();
+        let mut rect = RECT::default();
+        if GetWindowRect(host, &mut host_rect).is_ok() && GetWindowRect(hwnd, &mut rect).is_ok() {
+            let width = rect.right - rect.left;
+            let height = rect.bottom - rect.top;
+            let x = host_rect.left + ((host_rect.right - host_rect.left) - width) / 2;
+            let y = host_rect.top + ((host_rect.bottom - host_rect.top) - height) / 2;
+            let _ = SetWindowPos(hwnd, HWND_TOP, x.max(host_rect.left), y.max(host_rect.top), 0, 0, SWP_NOSIZE);
+        }
+    }
+
+    let title = crate::platform::win32::window_title(hwnd);
+    info!(target: "events", "子对话框打开: hwnd={}, tab={}, title={}", raw, tab, title);
+    record_event("dialog-opened", raw, Some(tab), title.clone());
+    if let Some(app) = APP.get() {
+        let _ = app.emit("child-dialog-opened", ChildDialog { tab, hwnd: raw, title });
+    }
+}
+
+#[cfg(windows)]
+unsafe fn on_window_hidden(hwnd: HWND) {
+    let raw = hwnd.0 as isize;
+    let removed = {
+        let mut dialogs = CHILD_DIALOGS.lock().unwrap();
+        dialogs.iter().position(|(h, _)| *h == raw).map(|i| dialogs.remove(i))
+    };
+    if let Some((_, tab)) = removed {
+        info!(target: "events", "子对话框关闭: hwnd={}, tab={}", raw, tab);
+        record_event("dialog-closed", raw, Some(tab), String::new());
+        if let Some(app) = APP.get() {
+            let _ = app.emit("child-dialog-closed", ChildDialog { tab, hwnd: raw, title: String::new() });
+        }
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn shell_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let shellhook_msg = SHELLHOOK_MSG.load(Ordering::SeqCst);
+    if shellhook_msg != 0 && msg == shellhook_msg && wparam.0 as u32 == HSHELL_FLASH {
+        if let Some(tab) = find_embedded_tab(HWND(lparam.0 as *mut _)) {
+            notify_attention(tab, "flash");
+        }
+        return LRESULT(0);
+    }
+    let taskbar_created_msg = TASKBAR_CREATED_MSG.load(Ordering::SeqCst);
+    if taskbar_created_msg != 0 && msg == taskbar_created_msg {
+        if let Some(app) = APP.get() {
+            crate::shell_restart::taskbar_created(app);
+        }
+        return LRESULT(0);
+    }
+    if msg == WM_WTSSESSION_CHANGE {
+        use crate::session_watch::SessionEvent;
+        let event = match wparam.0 as u32 {
+            WTS_SESSION_LOCK => Some(SessionEvent::Lock),
+            WTS_SESSION_UNLOCK => Some(SessionEvent::Unlock),
+            WTS_CONSOLE_DISCONNECT | WTS_REMOTE_DISCONNECT => Some(SessionEvent::Disconnect),
+            WTS_CONSOLE_CONNECT | WTS_REMOTE_CONNECT => Some(SessionEvent::Connect),
+            _ => None,
+        };
+        if let (Some(event), Some(app)) = (event, APP.get()) {
+            crate::session_watch::changed(app, event);
+        }
+        return LRESULT(0);
+    }
+    // 钩子窗口是顶层窗口，也会收到系统设置变化的广播
+    if msg == WM_SETTINGCHANGE || msg == WM_SYSCOLORCHANGE {
+        crate::system_prefs::changed();
+        crate::theme::changed();
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// 找到事件窗口所属的嵌入标签：窗口本身、其所有者链，或同一进程的嵌入窗口
+#[cfg(windows)]
+pub(crate) unsafe fn find_embedded_tab(hwnd: HWND) -> Option<isize> {
+    let embedded = embedded_hwnds();
+    if embedded.is_empty() {
+        return None;
+    }
+
+    let mut current = hwnd;
+    for _ in 0..10 {
+        if embedded.contains(&(current.0 as isize)) {
+            return Some(current.0 as isize);
+        }
+        match GetWindow(current, GW_OWNER) {
+            Ok(owner) if !owner.is_invalid() => current = owner,
+            _ => break,
+        }
+    }
+
+    let mut pid = 0;
+    GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    if pid == 0 {
+        return None;
+    }
+    embedded.into_iter().find(|&h| {
+        let mut embedded_pid = 0;
+        GetWindowThreadProcessId(HWND(h as *mut _), Some(&mut embedded_pid));
+        embedded_pid == pid
+    })
+}
+
+pub(crate) fn notify_attention(tab: isize, reason: &'static str) {
+    record_event("attention", tab, Some(tab), reason.to_string());
+    if crate::dnd::is_enabled() || crate::focus_session::suppresses(tab) || crate::presentation::is_active() {
+        return;
+    }
+    let Some(app) = APP.get() else { return };
+    info!(target: "events", "标签请求关注: hwnd={}, reason={}", tab, reason);
+    let _ = app.emit("tab-attention", TabAttention { hwnd: tab, reason });
+
+    // 主窗口隐藏或不在前台时，托盘图标也显示关注角标
+    if let Some(window) = app.get_webview_window("main") {
+        if !window.is_visible().unwrap_or(false) || !window.is_focused().unwrap_or(false) {
+            crate::tray::set_attention(app, true);
+        }
+    }
+}
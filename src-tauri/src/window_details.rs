@@ -0,0 +1,269 @@
+// 窗口的详细属性 (get_window_details)
+// 排查某个窗口嵌入后表现异常时使用：样式位 (按名称列出)、父窗口/所有者、位置状态、所在显示器和 DPI、
+// DWM cloak 状态、进程是否以管理员身份运行，以及按窗口类名、子窗口类名和加载的模块推断的界面框架
+// (Chromium / Electron / Java / Qt / WPF / WinForms / UWP)，这些框架各有已知的嵌入问题
+
+use serde::Serialize;
+
+use crate::window_manager::WindowRect;
+
+#[cfg(windows)]
+use crate::platform::{style, win32};
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{CloseHandle, HANDLE, HMODULE, HWND, LPARAM, RECT, TRUE},
+    Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED},
+    Graphics::Gdi::{MonitorFromWindow, MONITOR_DEFAULTTONEAREST},
+    Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY},
+    System::ProcessStatus::{K32EnumProcessModulesEx, K32GetModuleBaseNameW, LIST_MODULES_ALL},
+    System::Threading::{OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_VM_READ},
+    UI::HiDpi::GetDpiForWindow,
+    UI::WindowsAndMessaging::{
+        EnumChildWindows, GetParent, GetWindow, GetWindowLongPtrW, GetWindowPlacement, GetWindowRect,
+        GetWindowThreadProcessId, IsWindow, GWL_EXSTYLE, GWL_STYLE, GW_OWNER, WINDOWPLACEMENT,
+    },
+};
+
+// 最多列出的子窗口类名 (去重后)
+#[cfg(windows)]
+const MAX_CHILD_CLASSES: usize = 32;
+
+/// 推断的界面框架
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Framework {
+    Electron,
+    Chromium,
+    Java,
+    Qt,
+    Wpf,
+    WinForms,
+    Uwp,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowDetails {
+    pub hwnd: isize,
+    pub title: String,
+    pub class_name: String,
+    pub pid: u32,
+    pub exe_path: Option<String>,
+    pub style: u32,
+    pub style_names: Vec<String>,
+    pub ex_style: u32,
+    pub ex_style_names: Vec<String>,
+    pub parent: isize,
+    pub owner: isize,
+    pub show_state: &'static str, // normal / minimized / maximized / hidden
+    pub rect: Option<WindowRect>,
+    pub normal_rect: Option<WindowRect>, // 还原后的位置 (最小化、最大化时有意义)
+    pub monitor: isize,
+    pub dpi: u32,
+    pub cloaked: Vec<&'static str>, // app / shell / inherited，未被 cloak 时为空
+    pub elevated: Option<bool>,     // 无法打开进程令牌时为 None (通常说明对方权限更高)
+    pub framework: Option<Framework>,
+    pub child_classes: Vec<String>,
+    pub modules: Vec<String>, // 进程加载的模块 (文件名)，无法读取时为空
+    pub embedded: bool,
+}
+
+/// 读取窗口的详细属性
+#[cfg(windows)]
+pub fn get(hwnd: isize, embedded: bool) -> Result<WindowDetails, String> {
+    let handle = HWND(hwnd as *mut _);
+    unsafe {
+        if !IsWindow(handle).as_bool() {
+            return Err("无效的窗口句柄".to_string());
+        }
+        let mut pid = 0;
+        GetWindowThreadProcessId(handle, Some(&mut pid));
+        let exe_path = win32::process_path_of(pid).ok();
+        let class_name = win32::class_name(handle);
+        let style = GetWindowLongPtrW(handle, GWL_STYLE) as u32;
+        let ex_style = GetWindowLongPtrW(handle, GWL_EXSTYLE) as u32;
+
+        let mut placement = WINDOWPLACEMENT { length: std::mem::size_of::<WINDOWPLACEMENT>() as u32, ..Default::default() };
+        let placed = GetWindowPlacement(handle, &mut placement).is_ok();
+        let mut rect = RECT::default();
+        let rect = GetWindowRect(handle, &mut rect).ok().map(|_| WindowRect::from(rect));
+
+        let mut cloaked = 0u32;
+        let _ = DwmGetWindowAttribute(handle, DWMWA_CLOAKED, &mut cloaked as *mut u32 as *mut _, std::mem::size_of::<u32>() as u32);
+
+        let child_classes = child_classes(handle);
+        let modules = process_modules(pid);
+        let has_asar = exe_path.as_deref().is_some_and(has_electron_resources);
+
+        Ok(WindowDetails {
+            hwnd,
+            title: win32::window_title(handle),
+            pid,
+            exe_path,
+            style,
+            style_names: style::set_names(style, style::STYLE_NAMES),
+            ex_style,
+            ex_style_names: style::set_names(ex_style, style::EX_STYLE_NAMES),
+            parent: GetParent(handle).map(|h| h.0 as isize).unwrap_or(0),
+            owner: GetWindow(handle, GW_OWNER).map(|h| h.0 as isize).unwrap_or(0),
+            show_state: if placed { show_state(placement.showCmd) } else { "normal" },
+            rect,
+            normal_rect: placed.then(|| WindowRect::from(placement.rcNormalPosition)),
+            monitor: MonitorFromWindow(handle, MONITOR_DEFAULTTONEAREST).0 as isize,
+            dpi: GetDpiForWindow(handle),
+            cloaked: cloak_reasons(cloaked),
+            elevated: is_elevated(pid),
+            framework: detect_framework(&class_name, &child_classes, &modules, has_asar),
+            class_name,
+            child_classes,
+            modules,
+            embedded,
+        })
+    }
+}
+
+#[cfg(not(windows))]
+pub fn get(_hwnd: isize, _embedded: bool) -> Result<WindowDetails, String> {
+    Err("仅支持 Windows".to_string())
+}
+
+#[cfg(windows)]
+unsafe fn child_classes(hwnd: HWND) -> Vec<String> {
+    unsafe extern "system" fn callback(child: HWND, lparam: LPARAM) -> windows::Win32::Foundation::BOOL {
+        let classes = &mut *(lparam.0 as *mut Vec<String>);
+        let class_name = win32::class_name(child);
+        if !classes.contains(&class_name) {
+            classes.push(class_name);
+        }
+        (classes.len() < MAX_CHILD_CLASSES).into()
+    }
+    let mut classes: Vec<String> = Vec::new();
+    let _ = EnumChildWindows(hwnd, Some(callback), LPARAM(&mut classes as *mut Vec<String> as isize));
+    classes
+}
+
+#[cfg(windows)]
+unsafe fn process_modules(pid: u32) -> Vec<String> {
+    let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, false, pid) else {
+        return Vec::new();
+    };
+    let mut handles = vec![HMODULE::default(); 1024];
+    let mut needed = 0u32;
+    let size = (handles.len() * std::mem::size_of::<HMODULE>()) as u32;
+    let mut names = Vec::new();
+    if K32EnumProcessModulesEx(process, handles.as_mut_ptr(), size, &mut needed, LIST_MODULES_ALL.0) == TRUE {
+        let count = (needed as usize / std::mem::size_of::<HMODULE>()).min(handles.len());
+        for module in &handles[..count] {
+            let mut buffer = [0u16; 260];
+            let len = K32GetModuleBaseNameW(process, *module, &mut buffer);
+            if len > 0 {
+                names.push(String::from_utf16_lossy(&buffer[..len as usize]));
+            }
+        }
+    }
+    let _ = CloseHandle(process);
+    names
+}
+
+#[cfg(windows)]
+unsafe fn is_elevated(pid: u32) -> Option<bool> {
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+    let mut token = HANDLE::default();
+    let opened = OpenProcessToken(process, TOKEN_QUERY, &mut token).is_ok();
+    let _ = CloseHandle(process);
+    if !opened {
+        return None;
+    }
+    let mut elevation = TOKEN_ELEVATION::default();
+    let mut len = 0u32;
+    let result = GetTokenInformation(
+        token,
+        TokenElevation,
+        Some(&mut elevation as *mut TOKEN_ELEVATION as *mut _),
+        std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+        &mut len,
+    );
+    let _ = CloseHandle(token);
+    result.ok().map(|_| elevation.TokenIsElevated != 0)
+}
+
+// Electron 应用在程序目录的 resources 下带有 app.asar (或解包后的 app 目录)
+#[cfg(windows)]
+fn has_electron_resources(exe_path: &str) -> bool {
+    let Some(dir) = std::path::Path::new(exe_path).parent() else { return false };
+    let resources = dir.join("resources");
+    resources.join("app.asar").exists() || resources.join("electron.asar").exists()
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn show_state(show_cmd: u32) -> &'static str {
+    match show_cmd {
+        0 => "hidden",
+        2 | 6 | 7 => "minimized",
+        3 => "maximized",
+        _ => "normal",
+    }
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn cloak_reasons(cloaked: u32) -> Vec<&'static str> {
+    [(1, "app"), (2, "shell"), (4, "inherited")]
+        .into_iter()
+        .filter(|(bit, _)| cloaked & bit != 0)
+        .map(|(_, name)| name)
+        .collect()
+}
+
+// 按窗口类名、子窗口类名和模块名推断界面框架，Electron 先于 Chromium 判断 (Electron 的窗口类名与 Chrome 相同)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn detect_framework(class_name: &str, child_classes: &[String], modules: &[String], has_asar: bool) -> Option<Framework> {
+    let any_class = |f: &dyn Fn(&str) -> bool| f(class_name) || child_classes.iter().any(|c| f(c));
+    let has_module = |prefix: &str| modules.iter().any(|m| m.to_lowercase().starts_with(prefix));
+
+    let chromium = any_class(&|c| c.starts_with("Chrome_WidgetWin_") || c == "Chrome_RenderWidgetHostHWND");
+    if chromium && (has_asar || has_module("electron")) {
+        return Some(Framework::Electron);
+    }
+    if chromium {
+        return Some(Framework::Chromium);
+    }
+    if any_class(&|c| c.starts_with("SunAwt")) || has_module("jvm.dll") {
+        return Some(Framework::Java);
+    }
+    if any_class(&|c| c.starts_with("Qt") && c.contains("QWindow")) || has_module("qt5core") || has_module("qt6core") {
+        return Some(Framework::Qt);
+    }
+    if any_class(&|c| c.starts_with("HwndWrapper[")) || has_module("presentationframework") {
+        return Some(Framework::Wpf);
+    }
+    if any_class(&|c| c.starts_with("WindowsForms10.")) {
+        return Some(Framework::WinForms);
+    }
+    if any_class(&|c| c == "ApplicationFrameWindow" || c == "Windows.UI.Core.CoreWindow") {
+        return Some(Framework::Uwp);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_ui_frameworks() {
+        let classes = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let none: Vec<String> = Vec::new();
+
+        assert_eq!(detect_framework("Chrome_WidgetWin_1", &classes(&["Chrome_RenderWidgetHostHWND"]), &none, false), Some(Framework::Chromium));
+        assert_eq!(detect_framework("Chrome_WidgetWin_1", &none, &none, true), Some(Framework::Electron));
+        assert_eq!(detect_framework("SunAwtFrame", &none, &none, false), Some(Framework::Java));
+        assert_eq!(detect_framework("Qt5152QWindowIcon", &none, &none, false), Some(Framework::Qt));
+        assert_eq!(detect_framework("MainWindow", &none, &classes(&["Qt6Core.dll"]), false), Some(Framework::Qt));
+        assert_eq!(detect_framework("HwndWrapper[App;;1234]", &none, &none, false), Some(Framework::Wpf));
+        assert_eq!(detect_framework("WindowsForms10.Window.8.app.0.1", &none, &none, false), Some(Framework::WinForms));
+        assert_eq!(detect_framework("Notepad", &classes(&["Edit"]), &none, false), None);
+
+        assert_eq!(show_state(3), "maximized");
+        assert_eq!(cloak_reasons(6), vec!["shell", "inherited"]);
+    }
+}
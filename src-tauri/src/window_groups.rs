@@ -0,0 +1,284 @@
+// 按程序分组的窗口列表 (窗口选择器的 "程序 → 窗口" 树)
+// enumerate_windows_grouped 一次返回所有分组，前端不需要再为每个程序查询名称和图标：
+// - 同一个程序 (EXE 路径相同，不区分大小写) 的窗口归为一组，多个进程的窗口 (如浏览器) 也在同一组；
+//   无法获取程序路径的窗口按进程分组
+// - 名称取程序版本信息中的文件说明 (FileDescription，如 "Notepad")，没有时用 EXE 文件名
+// - 图标为程序的小图标 (ICO data URL，保留透明度)
+// 分组按窗口列表中第一个窗口的顺序排列，组内保持窗口列表的顺序；名称和图标按程序缓存
+
+use serde::Serialize;
+use std::sync::Mutex;
+
+use crate::platform::WindowInfo;
+
+#[cfg(windows)]
+use base64::Engine;
+#[cfg(windows)]
+use windows::{
+    core::{w, HSTRING},
+    Win32::{
+        Foundation::HWND,
+        Graphics::Gdi::{DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HGDIOBJ},
+        Storage::FileSystem::{GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW, FILE_FLAGS_AND_ATTRIBUTES},
+        UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_SMALLICON},
+        UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO},
+    },
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessGroup {
+    pub exe_path: Option<String>,
+    pub name: String,
+    pub icon: Option<String>, // ICO data URL，无法获取时为 None
+    pub window_count: usize,
+    pub windows: Vec<WindowInfo>,
+}
+
+// 程序的名称和图标 (小写的 EXE 路径, 名称, 图标)
+static DESCRIPTIONS: Mutex<Vec<(String, String, Option<String>)>> = Mutex::new(Vec::new());
+
+/// 当前可以嵌入的窗口，按程序分组
+pub async fn enumerate() -> Vec<ProcessGroup> {
+    let windows = crate::window_list::current().await;
+    tauri::async_runtime::spawn_blocking(move || {
+        let platform = crate::platform::current();
+        let windows = windows
+            .into_iter()
+            .map(|w| {
+                let exe_path = platform.process_path(w.hwnd).ok();
+                let pid = platform.process_id(w.hwnd);
+                (w, exe_path, pid)
+            })
+            .collect();
+        let mut groups = group(windows);
+        for group in &mut groups {
+            if let Some(exe_path) = &group.exe_path {
+                let (name, icon) = describe(exe_path);
+                group.name = name;
+                group.icon = icon;
+            }
+        }
+        groups
+    })
+    .await
+    .unwrap_or_default()
+}
+
+// 按程序路径 (没有时按进程) 分组，名称先用 EXE 文件名 (没有路径时用第一个窗口的标题)
+fn group(windows: Vec<(WindowInfo, Option<String>, u32)>) -> Vec<ProcessGroup> {
+    let mut groups: Vec<(String, ProcessGroup)> = Vec::new();
+    for (window, exe_path, pid) in windows {
+        let key = match &exe_path {
+            Some(path) => path.to_lowercase(),
+            None => format!("pid:{}", pid),
+        };
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.windows.push(window),
+            None => {
+                let name = exe_path.as_deref().map_or_else(|| window.title.clone(), |p| exe_name(p).to_string());
+                groups.push((key, ProcessGroup { exe_path, name, icon: None, window_count: 0, windows: vec![window] }));
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, mut group)| {
+            group.window_count = group.windows.len();
+            group
+        })
+        .collect()
+}
+
+fn exe_name(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+// 程序的名称和图标 (缓存)
+fn describe(exe_path: &str) -> (String, Option<String>) {
+    let key = exe_path.to_lowercase();
+    if let Some((_, name, icon)) = DESCRIPTIONS.lock().unwrap().iter().find(|(k, ..)| *k == key) {
+        return (name.clone(), icon.clone());
+    }
+    let name = file_description(exe_path).unwrap_or_else(|| exe_name(exe_path).to_string());
+    let icon = icon(exe_path);
+    DESCRIPTIONS.lock().unwrap().push((key, name.clone(), icon.clone()));
+    (name, icon)
+}
+
+// 版本信息中第一种语言的文件说明
+#[cfg(windows)]
+fn file_description(exe_path: &str) -> Option<String> {
+    let path = HSTRING::from(exe_path);
+    unsafe {
+        let size = GetFileVersionInfoSizeW(&path, None);
+        if size == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; size as usize];
+        GetFileVersionInfoW(&path, 0, size, data.as_mut_ptr() as *mut _).ok()?;
+        let mut buffer = std::ptr::null_mut();
+        let mut len = 0u32;
+        if !VerQueryValueW(data.as_ptr() as *const _, w!("\\VarFileInfo\\Translation"), &mut buffer, &mut len).as_bool() || len < 4 {
+            return None;
+        }
+        let (language, codepage) = (*(buffer as *const u16), *(buffer as *const u16).add(1));
+        let key = HSTRING::from(format!("\\StringFileInfo\\{:04x}{:04x}\\FileDescription", language, codepage));
+        if !VerQueryValueW(data.as_ptr() as *const _, &key, &mut buffer, &mut len).as_bool() || len == 0 {
+            return None;
+        }
+        let text = String::from_utf16_lossy(std::slice::from_raw_parts(buffer as *const u16, len as usize));
+        let text = text.trim_end_matches('\0').trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+#[cfg(not(windows))]
+fn file_description(_exe_path: &str) -> Option<String> {
+    None
+}
+
+// 程序的小图标
+#[cfg(windows)]
+fn icon(exe_path: &str) -> Option<String> {
+    unsafe {
+        let mut info = SHFILEINFOW::default();
+        let found = SHGetFileInfoW(
+            &HSTRING::from(exe_path),
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            Some(&mut info),
+            std::mem::size_of::<SHFILEINFOW>() as u32,
+            SHGFI_ICON | SHGFI_SMALLICON,
+        );
+        if found == 0 || info.hIcon.is_invalid() {
+            return None;
+        }
+        let pixels = icon_pixels(info.hIcon);
+        let _ = DestroyIcon(info.hIcon);
+        let (width, height, bgra) = pixels?;
+        Some(format!("data:image/x-icon;base64,{}", base64::engine::general_purpose::STANDARD.encode(ico(width, height, &bgra))))
+    }
+}
+
+#[cfg(not(windows))]
+fn icon(_exe_path: &str) -> Option<String> {
+    None
+}
+
+// 图标的 (宽, 高, 自上而下的 BGRA 像素)
+#[cfg(windows)]
+unsafe fn icon_pixels(icon: HICON) -> Option<(u32, u32, Vec<u8>)> {
+    let mut info = ICONINFO::default();
+    GetIconInfo(icon, &mut info).ok()?;
+    let mut bitmap = BITMAP::default();
+    let ok = !info.hbmColor.is_invalid()
+        && GetObjectW(HGDIOBJ(info.hbmColor.0), std::mem::size_of::<BITMAP>() as i32, Some(&mut bitmap as *mut _ as *mut _)) > 0;
+    let mut result = None;
+    if ok && bitmap.bmWidth > 0 && bitmap.bmHeight > 0 {
+        let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let mut header = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let screen = GetDC(HWND::default());
+        let copied = GetDIBits(screen, info.hbmColor, 0, height as u32, Some(pixels.as_mut_ptr() as *mut _), &mut header, DIB_RGB_COLORS) > 0;
+        ReleaseDC(HWND::default(), screen);
+        if copied {
+            result = Some((width as u32, height as u32, pixels));
+        }
+    }
+    let _ = DeleteObject(info.hbmColor);
+    let _ = DeleteObject(info.hbmMask);
+    result
+}
+
+// 单个 32 位图像的 ICO 文件 (像素自上而下；没有透明度信息的旧图标按不透明处理)
+#[cfg_attr(not(windows), allow(dead_code))]
+fn ico(width: u32, height: u32, bgra: &[u8]) -> Vec<u8> {
+    let row = width as usize * 4;
+    let mask_row = (width as usize).div_ceil(32) * 4;
+    let opaque = bgra.chunks(4).all(|p| p[3] == 0);
+    let image_size = row * height as usize + mask_row * height as usize;
+
+    let mut out = Vec::with_capacity(22 + 40 + image_size);
+    // ICONDIR
+    out.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    // ICONDIRENTRY (256 记为 0)
+    out.push(width.min(256) as u8);
+    out.push(height.min(256) as u8);
+    out.extend_from_slice(&[0, 0, 1, 0, 32, 0]);
+    out.extend_from_slice(&((40 + image_size) as u32).to_le_bytes());
+    out.extend_from_slice(&22u32.to_le_bytes());
+    // BITMAPINFOHEADER，高度包含 AND 掩码，为图像高度的两倍
+    out.extend_from_slice(&40u32.to_le_bytes());
+    out.extend_from_slice(&(width as i32).to_le_bytes());
+    out.extend_from_slice(&(height as i32 * 2).to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&32u16.to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&(image_size as u32).to_le_bytes());
+    out.extend_from_slice(&[0; 16]);
+    // 像素自下而上
+    for line in bgra.chunks(row).rev() {
+        if opaque {
+            line.chunks(4).for_each(|p| out.extend_from_slice(&[p[0], p[1], p[2], 255]));
+        } else {
+            out.extend_from_slice(line);
+        }
+    }
+    // 透明度由像素的 alpha 决定，AND 掩码全部为 0
+    out.resize(out.len() + mask_row * height as usize, 0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(hwnd: isize, title: &str) -> WindowInfo {
+        WindowInfo {
+            hwnd,
+            title: title.to_string(),
+            class_name: String::new(),
+            width: 0,
+            height: 0,
+            hint: None,
+            ordinal: 0,
+            token: String::new(),
+            dpi_awareness: None,
+        }
+    }
+
+    #[test]
+    fn groups_by_program_then_process() {
+        let groups = group(vec![
+            (window(1, "a"), Some(r"C:\Apps\Code.exe".to_string()), 10),
+            (window(2, "b"), None, 20),
+            (window(3, "c"), Some(r"c:\apps\code.exe".to_string()), 11),
+            (window(4, "d"), None, 21),
+        ]);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].name, "Code.exe");
+        assert_eq!(groups[0].window_count, 2);
+        assert_eq!(groups[0].windows.iter().map(|w| w.hwnd).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!((groups[1].name.as_str(), groups[2].name.as_str()), ("b", "d"));
+    }
+
+    #[test]
+    fn ico_header_and_bottom_up_rows() {
+        let pixels = [1, 2, 3, 0, 4, 5, 6, 0];
+        let ico = ico(1, 2, &pixels);
+        assert_eq!(&ico[..6], &[0, 0, 1, 0, 1, 0]);
+        assert_eq!(ico.len(), 22 + 40 + 8 + 8);
+        // 没有透明度的图标按不透明，最后一行在前
+        assert_eq!(&ico[62..70], &[4, 5, 6, 255, 1, 2, 3, 255]);
+    }
+}
@@ -0,0 +1,158 @@
+// 区分同一程序的多个窗口 (窗口选择器)
+// 三个 Chrome 窗口、四个资源管理器窗口在列表中只有标题可以区分，标题又经常被截断，
+// 所以同一程序有多个窗口时给每个窗口加上：
+// - ordinal: 按出现顺序的序号，窗口存在期间不变，关闭后序号留给之后的新窗口
+// - hint: 区分窗口的文档名或网址。浏览器用 UI Automation 读取地址栏 (ValuePattern)，
+//   其他程序取标题中程序名之前的部分 ("报告.docx - Word" 为 "报告.docx")
+// 读取地址栏要跨进程访问辅助功能树，可能要几百毫秒，不在枚举窗口时等待：没有读取过或标题变化 (切换了网页) 时
+// 在后台线程读取，这一次先用标题中的提示，读到后缓存 (按窗口和标题)，下一次刷新列表时换成网址
+
+use std::sync::Mutex;
+
+use crate::platform::{Platform, WindowInfo};
+
+// 用地址栏区分窗口的浏览器 (EXE 文件名)
+const BROWSERS: [&str; 5] = ["chrome.exe", "msedge.exe", "brave.exe", "vivaldi.exe", "firefox.exe"];
+
+// 标题中文档名和程序名之间的分隔符
+const TITLE_SEPARATORS: [&str; 4] = [" - ", " — ", " – ", " | "];
+
+// 已分配的序号 (窗口, 程序, 序号)
+static ORDINALS: Mutex<Vec<(isize, String, u32)>> = Mutex::new(Vec::new());
+
+// 读取过的地址栏 (窗口, 读取时的标题, 网址)
+static URLS: Mutex<Vec<(isize, String, Option<String>)>> = Mutex::new(Vec::new());
+
+// 正在后台读取地址栏的窗口
+#[cfg(windows)]
+static READING: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+/// 给枚举到的窗口加上序号和提示 (只有一个窗口的程序不加)
+pub fn annotate(platform: &dyn Platform, windows: &mut [WindowInfo]) {
+    let current: Vec<(isize, String)> = windows
+        .iter()
+        .map(|w| {
+            let program = platform.process_path(w.hwnd).map(|p| file_name(&p).to_lowercase());
+            (w.hwnd, program.unwrap_or_else(|_| w.class_name.clone()))
+        })
+        .collect();
+    let ordinals = assign_ordinals(&mut ORDINALS.lock().unwrap(), &current);
+    URLS.lock().unwrap().retain(|(hwnd, ..)| current.iter().any(|(h, _)| h == hwnd));
+
+    for ((window, (_, program)), ordinal) in windows.iter_mut().zip(&current).zip(ordinals) {
+        window.ordinal = ordinal;
+        window.hint = None;
+        if ordinal == 0 {
+            continue;
+        }
+        let url = if BROWSERS.contains(&program.as_str()) { browser_url(window.hwnd, &window.title) } else { None };
+        window.hint = url.or_else(|| title_hint(&window.title));
+    }
+}
+
+// 更新序号表并返回每个窗口的序号：新窗口取同一程序未被占用的最小序号，程序只有一个窗口时为 0
+fn assign_ordinals(table: &mut Vec<(isize, String, u32)>, current: &[(isize, String)]) -> Vec<u32> {
+    table.retain(|(hwnd, program, _)| current.iter().any(|(h, p)| h == hwnd && p == program));
+    for (hwnd, program) in current {
+        if table.iter().any(|(h, ..)| h == hwnd) {
+            continue;
+        }
+        let ordinal = (1..).find(|n| !table.iter().any(|(_, p, o)| p == program && o == n)).unwrap_or(1);
+        table.push((*hwnd, program.clone(), ordinal));
+    }
+    current
+        .iter()
+        .map(|(hwnd, program)| {
+            if current.iter().filter(|(_, p)| p == program).count() < 2 {
+                return 0;
+            }
+            table.iter().find(|(h, ..)| h == hwnd).map_or(0, |(_, _, o)| *o)
+        })
+        .collect()
+}
+
+// 标题中最后一个分隔符之前的部分 (程序名通常在最后)
+fn title_hint(title: &str) -> Option<String> {
+    let end = TITLE_SEPARATORS.iter().filter_map(|sep| title.rfind(sep)).max()?;
+    let hint = title[..end].trim();
+    (!hint.is_empty()).then(|| hint.to_string())
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+// 浏览器窗口地址栏中的网址 (按标题缓存)，还没有读到时在后台读取并返回 None
+#[cfg(windows)]
+fn browser_url(hwnd: isize, title: &str) -> Option<String> {
+    if let Some((_, _, url)) = URLS.lock().unwrap().iter().find(|(h, t, _)| *h == hwnd && t == title) {
+        return url.clone();
+    }
+    {
+        let mut reading = READING.lock().unwrap();
+        if reading.contains(&hwnd) {
+            return None;
+        }
+        reading.push(hwnd);
+    }
+    let title = title.to_string();
+    std::thread::spawn(move || {
+        let url = unsafe { read_address_bar(hwnd) };
+        let mut urls = URLS.lock().unwrap();
+        urls.retain(|(h, ..)| *h != hwnd);
+        urls.push((hwnd, title, url));
+        READING.lock().unwrap().retain(|h| *h != hwnd);
+    });
+    None
+}
+
+#[cfg(not(windows))]
+fn browser_url(_hwnd: isize, _title: &str) -> Option<String> {
+    None
+}
+
+// 窗口中第一个编辑框 (浏览器的地址栏) 的值
+#[cfg(windows)]
+unsafe fn read_address_bar(hwnd: isize) -> Option<String> {
+    use windows::core::VARIANT;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+    use windows::Win32::UI::Accessibility::{
+        CUIAutomation, IUIAutomation, IUIAutomationValuePattern, TreeScope_Descendants, UIA_ControlTypePropertyId,
+        UIA_EditControlTypeId, UIA_ValuePatternId,
+    };
+
+    let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+    let window = automation.ElementFromHandle(HWND(hwnd as *mut _)).ok()?;
+    let condition = automation
+        .CreatePropertyCondition(UIA_ControlTypePropertyId, &VARIANT::from(UIA_EditControlTypeId.0))
+        .ok()?;
+    let edit = window.FindFirst(TreeScope_Descendants, &condition).ok()?;
+    let value: IUIAutomationValuePattern = edit.GetCurrentPatternAs(UIA_ValuePatternId).ok()?;
+    let url = value.CurrentValue().ok()?.to_string();
+    let url = url.trim();
+    (!url.is_empty()).then(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ordinals_are_stable_per_program() {
+        let chrome = |hwnd: isize| (hwnd, "chrome.exe".to_string());
+        let notepad = (9, "notepad.exe".to_string());
+        let mut table = Vec::new();
+
+        assert_eq!(assign_ordinals(&mut table, &[chrome(1), notepad.clone()]), vec![0, 0]);
+        assert_eq!(assign_ordinals(&mut table, &[chrome(1), chrome(2), chrome(3), notepad.clone()]), vec![1, 2, 3, 0]);
+        // 2 关闭后其他窗口的序号不变，新窗口使用空出来的 2
+        assert_eq!(assign_ordinals(&mut table, &[chrome(1), chrome(3)]), vec![1, 3]);
+        assert_eq!(assign_ordinals(&mut table, &[chrome(4), chrome(1), chrome(3)]), vec![2, 1, 3]);
+
+        assert_eq!(title_hint("报告.docx - Word"), Some("报告.docx".to_string()));
+        assert_eq!(title_hint("Inbox - me@example.com - Gmail — Mozilla Firefox"), Some("Inbox - me@example.com - Gmail".to_string()));
+        assert_eq!(title_hint("计算器"), None);
+    }
+}
@@ -0,0 +1,53 @@
+// 窗口的身份标识
+// 窗口关闭后系统会把它的句柄分配给之后创建的窗口，前端拿着旧的窗口列表嵌入时可能嵌入另一个程序的窗口。
+// 枚举窗口时给每个窗口生成 token (句柄 + 进程 ID + 进程创建时间，进程 ID 同样会被重新使用)，
+// 嵌入时 embed_window 带上 token，与窗口当前的身份不一致时返回 StaleHandle，前端刷新列表后重新选择
+
+use crate::platform::Platform;
+use crate::window_manager::{EmbedError, EmbedErrorCode};
+
+/// 窗口当前的身份标识，窗口不存在时为空字符串
+pub fn token(platform: &dyn Platform, hwnd: isize) -> String {
+    if !platform.is_window(hwnd) {
+        return String::new();
+    }
+    let pid = platform.process_id(hwnd);
+    let created = platform.process_start_time(pid).unwrap_or(0);
+    format!("{}:{}:{}", hwnd, pid, created)
+}
+
+/// 检查 token 是否仍然指向窗口 hwnd
+pub fn check(platform: &dyn Platform, hwnd: isize, expected: &str) -> Result<(), EmbedError> {
+    if !platform.is_window(hwnd) {
+        return Err(EmbedError::new(EmbedErrorCode::InvalidWindow));
+    }
+    if token(platform, hwnd) != expected {
+        return Err(EmbedError::new(EmbedErrorCode::StaleHandle));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatform;
+
+    #[test]
+    fn reused_handles_are_rejected() {
+        let platform = MockPlatform::new();
+        let hwnd = platform.create_window("记事本", "Notepad", None);
+        let current = token(&platform, hwnd);
+        assert!(check(&platform, hwnd, &current).is_ok());
+
+        // 同一句柄属于另一个进程
+        let other = format!("{}:{}:0", hwnd, platform.process_id(hwnd) + 4);
+        assert_eq!(check(&platform, hwnd, &other).unwrap_err().code, EmbedErrorCode::StaleHandle);
+        // 另一个窗口的 token
+        let second = platform.create_window("画图", "MSPaintApp", None);
+        assert_eq!(check(&platform, second, &current).unwrap_err().code, EmbedErrorCode::StaleHandle);
+
+        platform.destroy_window(hwnd);
+        assert_eq!(token(&platform, hwnd), "");
+        assert_eq!(check(&platform, hwnd, &current).unwrap_err().code, EmbedErrorCode::InvalidWindow);
+    }
+}
@@ -0,0 +1,182 @@
+// 可嵌入窗口列表 (窗口选择器、launch_app 使用)
+// 枚举顶层窗口要逐个查询标题、类名，窗口多时需要几十毫秒，放到阻塞线程池执行，不占用主线程；
+// 结果缓存 MAX_AGE，短时间内的重复请求直接使用缓存
+// 每次列表变化 token 加一，调用方用上次拿到的 token 取增量，只传输变化的窗口
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::platform::{self, WindowInfo};
+use crate::{ignored_windows, settings, window_hints, window_identity};
+
+const MAX_AGE: Duration = Duration::from_millis(250);
+// 保留的关闭记录数，更早的 token 只能取完整列表
+const MAX_REMOVED: usize = 256;
+
+static CACHE: Mutex<Cache> = Mutex::new(Cache::new());
+
+/// 自 since_token 以来的变化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowDelta {
+    pub token: u64,
+    /// since_token 为 0 或已过期时为 true，changed 是完整列表
+    pub full: bool,
+    /// 新出现或标题、大小有变化的窗口
+    pub changed: Vec<WindowInfo>,
+    pub removed: Vec<isize>,
+}
+
+struct Cache {
+    token: u64,
+    refreshed_at: Option<Instant>,
+    windows: Vec<WindowInfo>,
+    // 窗口最近一次变化时的 token
+    changed_at: BTreeMap<isize, u64>,
+    removed: VecDeque<(isize, u64)>,
+    // 早于它的 token 无法给出增量
+    oldest: u64,
+}
+
+impl Cache {
+    const fn new() -> Self {
+        Cache {
+            token: 0,
+            refreshed_at: None,
+            windows: Vec::new(),
+            changed_at: BTreeMap::new(),
+            removed: VecDeque::new(),
+            oldest: 0,
+        }
+    }
+
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.refreshed_at.is_some_and(|t| now.duration_since(t) < MAX_AGE)
+    }
+
+    // 用新的枚举结果更新缓存，有变化时 token 加一
+    fn apply(&mut self, windows: Vec<WindowInfo>, now: Instant) {
+        self.refreshed_at = Some(now);
+        let changed: Vec<isize> = windows
+            .iter()
+            .filter(|w| !self.windows.contains(w))
+            .map(|w| w.hwnd)
+            .collect();
+        let removed: Vec<isize> = self
+            .windows
+            .iter()
+            .filter(|old| !windows.iter().any(|w| w.hwnd == old.hwnd))
+            .map(|old| old.hwnd)
+            .collect();
+        self.windows = windows;
+        if changed.is_empty() && removed.is_empty() {
+            return;
+        }
+
+        self.token += 1;
+        for hwnd in changed {
+            self.changed_at.insert(hwnd, self.token);
+        }
+        for hwnd in removed {
+            self.changed_at.remove(&hwnd);
+            self.removed.push_back((hwnd, self.token));
+        }
+        while self.removed.len() > MAX_REMOVED {
+            if let Some((_, token)) = self.removed.pop_front() {
+                self.oldest = token;
+            }
+        }
+    }
+
+    fn delta(&self, since: u64) -> WindowDelta {
+        if since == 0 || since < self.oldest || since > self.token {
+            return WindowDelta { token: self.token, full: true, changed: self.windows.clone(), removed: Vec::new() };
+        }
+        WindowDelta {
+            token: self.token,
+            full: false,
+            changed: self
+                .windows
+                .iter()
+                .filter(|w| self.changed_at.get(&w.hwnd).is_some_and(|&t| t > since))
+                .cloned()
+                .collect(),
+            removed: self.removed.iter().filter(|(_, t)| *t > since).map(|(hwnd, _)| *hwnd).collect(),
+        }
+    }
+}
+
+// 在当前线程枚举 (过滤掉不允许嵌入和用户忽略的窗口，同一程序的多个窗口加上序号和提示，每个窗口加上身份标识)
+fn enumerate_now() -> Vec<WindowInfo> {
+    let platform = platform::current();
+    let mut windows: Vec<WindowInfo> = platform
+        .enumerate()
+        .into_iter()
+        .filter(|w| {
+            let exe_path = platform.process_path(w.hwnd).ok();
+            !settings::is_blocked(&w.class_name, exe_path.as_deref()) && !ignored_windows::is_ignored(&w.class_name, exe_path.as_deref())
+        })
+        .collect();
+    window_hints::annotate(platform, &mut windows);
+    for window in &mut windows {
+        window.token = window_identity::token(platform, window.hwnd);
+    }
+    windows
+}
+
+// 缓存过期时在阻塞线程池重新枚举
+async fn refresh() {
+    if CACHE.lock().unwrap().is_fresh(Instant::now()) {
+        return;
+    }
+    match tauri::async_runtime::spawn_blocking(enumerate_now).await {
+        Ok(windows) => CACHE.lock().unwrap().apply(windows, Instant::now()),
+        Err(e) => warn!(target: "embed", "枚举窗口失败: {}", e),
+    }
+}
+
+/// 当前的窗口列表
+pub async fn current() -> Vec<WindowInfo> {
+    refresh().await;
+    CACHE.lock().unwrap().windows.clone()
+}
+
+/// 自 since_token 以来的变化 (since_token 为 0 时返回完整列表)
+pub async fn delta(since_token: u64) -> WindowDelta {
+    refresh().await;
+    CACHE.lock().unwrap().delta(since_token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(hwnd: isize, title: &str) -> WindowInfo {
+        WindowInfo { hwnd, title: title.to_string(), class_name: "Notepad".to_string(), width: 800, height: 600, hint: None, ordinal: 0, token: String::new(), dpi_awareness: None }
+    }
+
+    #[test]
+    fn delta_lists_changed_and_removed_windows() {
+        let mut cache = Cache::new();
+        let now = Instant::now();
+        cache.apply(vec![window(1, "a"), window(2, "b")], now);
+        let first = cache.delta(0);
+        assert!(first.full);
+        assert_eq!(first.changed.len(), 2);
+
+        // 没有变化时 token 不变
+        cache.apply(vec![window(1, "a"), window(2, "b")], now);
+        assert_eq!(cache.delta(first.token), WindowDelta { token: first.token, full: false, changed: vec![], removed: vec![] });
+
+        cache.apply(vec![window(1, "a*"), window(3, "c")], now);
+        let delta = cache.delta(first.token);
+        assert!(!delta.full);
+        assert_eq!(delta.changed, vec![window(1, "a*"), window(3, "c")]);
+        assert_eq!(delta.removed, vec![2]);
+
+        // 未知的 token 返回完整列表
+        assert!(cache.delta(delta.token + 1).full);
+    }
+}
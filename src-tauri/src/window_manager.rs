@@ -0,0 +1,808 @@
+// 嵌入窗口管理器
+// 记录每个嵌入窗口的原始状态 (样式、位置、所有者、显示器) 及所属进程信息，
+// 作为 tauri::State 注册，命令通过 State<WindowManager> 访问，其他模块通过 app.state() 访问
+// 嵌入/释放流程只通过 Platform trait 操作窗口，不直接调用系统 API
+// 同一窗口的嵌入和释放互斥 (lock_hwnd)：重复嵌入已嵌入的窗口什么也不做，释放未嵌入的窗口返回 NotEmbedded，
+// 嵌入过程中的释放等嵌入完成后再进行，不会把修改过的样式当成原始样式记录下来
+// 锁的粒度：操作锁按窗口区分，一个标签卡住时不影响其他标签的嵌入和释放；
+// 记录表用读写锁，只在读写记录时短暂持有，从不在持有期间调用 Platform，查询之间互不阻塞
+// 标签数上限：不同窗口的嵌入可以同时进行，先在 reserved 锁内检查并占用名额，写入记录后归还，
+// 同时嵌入的多个窗口不会都通过检查
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, RwLock};
+use std::thread::ThreadId;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use crate::platform::Platform;
+
+#[cfg(windows)]
+use windows::{
+    Wdk::System::Threading::{NtQueryInformationProcess, ProcessBasicInformation},
+    Win32::{
+        Foundation::{CloseHandle, BOOL, HWND, RECT},
+        Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED},
+        System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
+        UI::WindowsAndMessaging::{GetWindowRect, IsHungAppWindow, IsWindow, IsWindowVisible},
+    },
+};
+
+/// 窗口矩形 (屏幕坐标)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl WindowRect {
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+}
+
+#[cfg(windows)]
+impl From<RECT> for WindowRect {
+    fn from(rect: RECT) -> Self {
+        WindowRect { left: rect.left, top: rect.top, right: rect.right, bottom: rect.bottom }
+    }
+}
+
+/// 标签颜色标记
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TabColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Gray,
+}
+
+/// 分层窗口 (WS_EX_LAYERED) 的透明属性，释放时按原样恢复
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LayeredAttributes {
+    pub color_key: Option<u32>, // 透明色 (COLORREF)，没有使用 LWA_COLORKEY 时为 None
+    pub alpha: Option<u8>,      // 整体不透明度，没有使用 LWA_ALPHA 时为 None
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddedWindow {
+    pub hwnd: isize,
+    #[serde(default)]
+    pub tab_id: u64, // 标签 ID，应用重启后新窗口接替同一标签时保持不变
+    #[serde(serialize_with = "crate::privacy::serialize_title")]
+    pub title: String,
+    pub class_name: String,
+    pub pid: u32,
+    pub exe_path: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>, // 进程启动参数 (不含程序路径)，重新启动应用时使用
+    pub original_style: i32,
+    pub original_exstyle: i32, // 含 WS_EX_TOPMOST，释放时恢复置顶
+    #[serde(default)]
+    pub original_layered: Option<LayeredAttributes>, // 用 SetLayeredWindowAttributes 设置的透明属性
+    pub original_rect: WindowRect,
+    pub original_owner: isize, // 嵌入前的所有者窗口，释放时恢复
+    pub monitor: isize,        // 嵌入前所在的显示器 (HMONITOR)
+    pub embedded_at: u64,      // 嵌入时间 (Unix 秒)
+    #[serde(default, serialize_with = "crate::privacy::serialize_label")]
+    pub label: Option<String>, // 用户自定义的标签名称
+    #[serde(default)]
+    pub group: Option<String>, // 所属分组
+    #[serde(default)]
+    pub color: Option<TabColor>,
+    #[serde(default)]
+    pub hotkey: Option<String>, // 切换到这个标签的全局快捷键 (见 tab_hotkeys.rs)
+    #[serde(default)]
+    pub auto_restart: bool, // 应用崩溃后自动重新启动并嵌入 (见 crash_watch.rs)
+    #[serde(default)]
+    pub pinned: bool, // 固定的标签排在最前面，不能用 Ctrl+W 关闭 (见 pinned_tabs.rs)
+    #[serde(default)]
+    pub input_locked: bool, // 只显示不接收鼠标和键盘输入 (见 lock_tab_input)
+    #[serde(default)]
+    pub capture_excluded: bool, // 显示时不出现在屏幕共享和截图中 (见 privacy.rs)
+    #[serde(default)]
+    pub preserve_transparency: bool, // 嵌入期间保留分层透明效果 (见 set_tab_transparency)
+}
+
+/// tab-input-locked 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct TabInputLocked {
+    pub hwnd: isize,
+    pub locked: bool,
+}
+
+/// tab-capture-excluded 事件的载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct TabCaptureExcluded {
+    pub hwnd: isize,
+    pub excluded: bool,
+}
+
+/// tab-tags-changed 事件的载荷 (分组或颜色变化)
+#[derive(Debug, Clone, Serialize)]
+pub struct TabTags {
+    pub hwnd: isize,
+    pub group: Option<String>,
+    pub color: Option<TabColor>,
+}
+
+impl From<&EmbeddedWindow> for TabTags {
+    fn from(window: &EmbeddedWindow) -> Self {
+        TabTags { hwnd: window.hwnd, group: window.group.clone(), color: window.color }
+    }
+}
+
+/// 嵌入窗口的实时状态 (list_embedded_windows 返回，用于前端对账)
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddedWindowStatus {
+    #[serde(flatten)]
+    pub window: EmbeddedWindow,
+    #[serde(serialize_with = "crate::privacy::serialize_title")]
+    pub current_title: String,
+    pub current_rect: Option<WindowRect>,
+    pub valid: bool,     // 窗口是否仍存在
+    pub visible: bool,
+    pub hung: bool,      // 未响应 (超过 5 秒不处理消息)
+    pub suspended: bool, // 进程被系统挂起 (UWP 应用后台冻结)
+    pub cloaked: bool,   // 被 DWM 隐藏 (其他虚拟桌面、挂起的 UWP 等)
+}
+
+/// 嵌入失败的原因，随 embed-failed 事件发给前端
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbedErrorCode {
+    NoHost,        // 无法获取主窗口
+    InvalidWindow, // 窗口不存在或已关闭
+    OwnWindow,     // WindowHub 自己的窗口
+    Dangerous,     // 桌面、任务栏等危险窗口
+    Blocked,       // 在用户的禁止嵌入列表中
+    Problematic,   // 同类窗口之前嵌入时无响应
+    Timeout,       // 嵌入过程中窗口无响应，已放弃并撤销修改
+    Failed,        // 系统调用失败
+    NotEmbedded,   // 释放的窗口没有被嵌入
+    StaleHandle,   // 窗口列表过期，句柄已属于另一个窗口
+    LimitExceeded, // 超出资源上限 (见 budget.rs)
+}
+
+impl EmbedErrorCode {
+    /// 消息目录中的键 (见 messages.rs)
+    pub fn key(self) -> &'static str {
+        match self {
+            EmbedErrorCode::NoHost => "embed.no_host",
+            EmbedErrorCode::InvalidWindow => "embed.invalid_window",
+            EmbedErrorCode::OwnWindow => "embed.own_window",
+            EmbedErrorCode::Dangerous => "embed.dangerous",
+            EmbedErrorCode::Blocked => "embed.blocked",
+            EmbedErrorCode::Problematic => "embed.problematic",
+            EmbedErrorCode::Timeout => "embed.timeout",
+            EmbedErrorCode::Failed => "embed.failed",
+            EmbedErrorCode::NotEmbedded => "embed.not_embedded",
+            EmbedErrorCode::StaleHandle => "embed.stale_handle",
+            EmbedErrorCode::LimitExceeded => "embed.limit_exceeded",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbedError {
+    pub code: EmbedErrorCode,
+    pub params: Vec<String>, // 消息参数 (类名、系统错误信息等)
+    pub message: String,     // 中文消息，用于日志
+}
+
+impl EmbedError {
+    pub fn new(code: EmbedErrorCode) -> Self {
+        EmbedError { code, params: Vec::new(), message: crate::messages::zh(code.key(), &[]) }
+    }
+
+    pub fn with_param(code: EmbedErrorCode, param: impl Into<String>) -> Self {
+        let params = vec![param.into()];
+        EmbedError { code, message: crate::messages::zh(code.key(), &params), params }
+    }
+}
+
+// 平台实现中系统调用失败的错误信息
+impl From<String> for EmbedError {
+    fn from(message: String) -> Self {
+        EmbedError::with_param(EmbedErrorCode::Failed, message)
+    }
+}
+
+impl From<&str> for EmbedError {
+    fn from(message: &str) -> Self {
+        EmbedError::with_param(EmbedErrorCode::Failed, message)
+    }
+}
+
+// 命令仍以字符串返回错误，按界面语言生成
+impl From<EmbedError> for String {
+    fn from(error: EmbedError) -> String {
+        crate::messages::text(error.code.key(), &error.params)
+    }
+}
+
+#[derive(Default)]
+pub struct WindowManager {
+    windows: RwLock<Vec<EmbeddedWindow>>,
+    last_tab_id: AtomicU64,
+    // 正在嵌入或释放的窗口：(句柄, 持有的线程, 重入次数)
+    busy: Mutex<Vec<(isize, ThreadId, usize)>>,
+    idle: Condvar,
+    // 已通过标签数检查、还没有写入记录的嵌入
+    reserved: Mutex<usize>,
+}
+
+/// 占用的标签名额 (reserve_tab 返回)，离开作用域时归还
+struct TabSlot<'a> {
+    manager: &'a WindowManager,
+}
+
+impl Drop for TabSlot<'_> {
+    fn drop(&mut self) {
+        *self.manager.reserved.lock().unwrap() -= 1;
+    }
+}
+
+/// 窗口操作锁 (lock_hwnd 返回)，离开作用域时释放
+pub struct HwndLock<'a> {
+    manager: &'a WindowManager,
+    hwnd: isize,
+}
+
+impl Drop for HwndLock<'_> {
+    fn drop(&mut self) {
+        let mut busy = self.manager.busy.lock().unwrap();
+        if let Some(index) = busy.iter().position(|(h, _, _)| *h == self.hwnd) {
+            busy[index].2 -= 1;
+            if busy[index].2 == 0 {
+                busy.remove(index);
+                self.manager.idle.notify_all();
+            }
+        }
+    }
+}
+
+impl WindowManager {
+    /// 锁定窗口的嵌入/释放操作，其他线程正在操作同一窗口时等待；同一线程可以重复锁定
+    pub fn lock_hwnd(&self, hwnd: isize) -> HwndLock<'_> {
+        let current = std::thread::current().id();
+        let mut busy = self.busy.lock().unwrap();
+        loop {
+            match busy.iter_mut().find(|(h, _, _)| *h == hwnd) {
+                Some((_, owner, depth)) if *owner == current => {
+                    *depth += 1;
+                    break;
+                }
+                Some(_) => busy = self.idle.wait(busy).unwrap(),
+                None => {
+                    busy.push((hwnd, current, 1));
+                    break;
+                }
+            }
+        }
+        HwndLock { manager: self, hwnd }
+    }
+
+    /// 记录新嵌入的窗口，已存在时保留最初的原始状态并返回 false
+    pub fn insert(&self, window: EmbeddedWindow) -> bool {
+        let mut windows = self.windows.write().unwrap();
+        if windows.iter().any(|w| w.hwnd == window.hwnd) {
+            return false;
+        }
+        windows.push(window);
+        true
+    }
+
+    /// 移除并返回窗口记录
+    pub fn remove(&self, hwnd: isize) -> Option<EmbeddedWindow> {
+        let mut windows = self.windows.write().unwrap();
+        windows.iter().position(|w| w.hwnd == hwnd).map(|i| windows.remove(i))
+    }
+
+    /// 当前已嵌入的窗口句柄 (按嵌入顺序)
+    pub fn hwnds(&self) -> Vec<isize> {
+        self.windows.read().unwrap().iter().map(|w| w.hwnd).collect()
+    }
+
+    pub fn get(&self, hwnd: isize) -> Option<EmbeddedWindow> {
+        self.windows.read().unwrap().iter().find(|w| w.hwnd == hwnd).cloned()
+    }
+
+    pub fn find_tab(&self, tab_id: u64) -> Option<EmbeddedWindow> {
+        self.windows.read().unwrap().iter().find(|w| w.tab_id == tab_id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<EmbeddedWindow> {
+        self.windows.read().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.read().unwrap().len()
+    }
+
+    /// 所有嵌入窗口的实时状态
+    pub fn statuses(&self) -> Vec<EmbeddedWindowStatus> {
+        self.list().into_iter().map(probe).collect()
+    }
+
+    /// 检查后嵌入窗口到 host，并记录原始状态；窗口已嵌入时直接返回已有的记录，不再修改窗口
+    pub fn embed(&self, platform: &dyn Platform, host: isize, hwnd: isize) -> Result<EmbeddedWindow, EmbedError> {
+        let _lock = self.lock_hwnd(hwnd);
+        if let Some(existing) = self.get(hwnd) {
+            return Ok(existing);
+        }
+        if !platform.is_window(hwnd) {
+            return Err(EmbedError::new(EmbedErrorCode::InvalidWindow));
+        }
+        check_embeddable(platform, hwnd)?;
+        let _slot = self.reserve_tab(platform)?;
+        let mut window = platform.embed(host, hwnd).inspect_err(|e| {
+            // 卡住过的窗口类型之后不再嵌入
+            if e.code == EmbedErrorCode::Timeout {
+                crate::problem_classes::mark(&platform.class_name(hwnd));
+            }
+        })?;
+        info!(target: "embed", "嵌入窗口成功: hwnd={}, class={}", hwnd, window.class_name);
+        window.tab_id = self.last_tab_id.fetch_add(1, Ordering::SeqCst) + 1;
+        window.args = platform.command_line(hwnd).unwrap_or_default();
+        let taken: Vec<String> = self.list().into_iter().filter(|w| w.hwnd != hwnd).filter_map(|w| w.label).collect();
+        window.label = crate::tab_labels::find(window.exe_path.as_deref(), &window.title, &taken);
+        self.insert(window.clone());
+        Ok(window)
+    }
+
+    // 占用一个标签名额，检查和占用在同一把锁内完成；名额在记录写入后或嵌入失败时归还
+    fn reserve_tab(&self, platform: &dyn Platform) -> Result<TabSlot<'_>, EmbedError> {
+        let mut reserved = self.reserved.lock().unwrap();
+        // 已关闭等待重新嵌入的标签不占名额
+        let live = self.hwnds().into_iter().filter(|&h| platform.is_window(h)).count();
+        crate::budget::check_tabs(live + *reserved)?;
+        *reserved += 1;
+        Ok(TabSlot { manager: self })
+    }
+
+    /// 标签的自定义名称
+    pub fn label(&self, hwnd: isize) -> Option<String> {
+        self.windows.read().unwrap().iter().find(|w| w.hwnd == hwnd).and_then(|w| w.label.clone())
+    }
+
+    /// 修改标签名称，返回修改前的记录，窗口未嵌入时为 None
+    pub fn set_label(&self, hwnd: isize, label: Option<String>) -> Option<EmbeddedWindow> {
+        let mut windows = self.windows.write().unwrap();
+        let window = windows.iter_mut().find(|w| w.hwnd == hwnd)?;
+        let previous = window.clone();
+        window.label = label;
+        Some(previous)
+    }
+
+    /// 新窗口接替标签 (应用重启后重新嵌入)：移除旧记录，新窗口的记录继承标签 ID、名称、分组、颜色、快捷键、自动重启、固定和屏幕捕获排除，
+    /// 并移到旧记录的位置。返回接替后的记录，标签或新窗口不存在时为 None
+    pub fn replace_tab(&self, tab_id: u64, hwnd: isize) -> Option<EmbeddedWindow> {
+        let mut windows = self.windows.write().unwrap();
+        let old_index = windows.iter().position(|w| w.tab_id == tab_id && w.hwnd != hwnd)?;
+        let new_index = windows.iter().position(|w| w.hwnd == hwnd)?;
+        let mut window = windows.remove(new_index);
+        let old_index = if new_index < old_index { old_index - 1 } else { old_index };
+        let old = windows.remove(old_index);
+        window.tab_id = old.tab_id;
+        window.label = old.label;
+        window.group = old.group;
+        window.color = old.color;
+        window.hotkey = old.hotkey;
+        window.auto_restart = old.auto_restart;
+        window.pinned = old.pinned;
+        window.capture_excluded = old.capture_excluded;
+        window.preserve_transparency = old.preserve_transparency;
+        windows.insert(old_index, window.clone());
+        Some(window)
+    }
+
+    /// 修改标签分组，返回修改后的记录，窗口未嵌入时为 None
+    pub fn set_group(&self, hwnd: isize, group: Option<String>) -> Option<EmbeddedWindow> {
+        self.update(hwnd, |w| w.group = group)
+    }
+
+    /// 修改标签颜色，返回修改后的记录，窗口未嵌入时为 None
+    pub fn set_color(&self, hwnd: isize, color: Option<TabColor>) -> Option<EmbeddedWindow> {
+        self.update(hwnd, |w| w.color = color)
+    }
+
+    /// 修改标签快捷键，返回修改后的记录，窗口未嵌入时为 None
+    pub fn set_hotkey(&self, hwnd: isize, hotkey: Option<String>) -> Option<EmbeddedWindow> {
+        self.update(hwnd, |w| w.hotkey = hotkey)
+    }
+
+    /// 修改标签崩溃后是否自动重启，返回修改后的记录，窗口未嵌入时为 None
+    pub fn set_auto_restart(&self, hwnd: isize, enabled: bool) -> Option<EmbeddedWindow> {
+        self.update(hwnd, |w| w.auto_restart = enabled)
+    }
+
+    /// 修改标签是否锁定输入，返回修改后的记录，窗口未嵌入时为 None
+    pub fn set_input_locked(&self, hwnd: isize, locked: bool) -> Option<EmbeddedWindow> {
+        self.update(hwnd, |w| w.input_locked = locked)
+    }
+
+    /// 修改标签是否排除在屏幕捕获之外，返回修改后的记录，窗口未嵌入时为 None
+    pub fn set_capture_excluded(&self, hwnd: isize, excluded: bool) -> Option<EmbeddedWindow> {
+        self.update(hwnd, |w| w.capture_excluded = excluded)
+    }
+
+    /// 修改标签是否保留透明效果，返回修改后的记录，窗口未嵌入时为 None
+    pub fn set_preserve_transparency(&self, hwnd: isize, preserve: bool) -> Option<EmbeddedWindow> {
+        self.update(hwnd, |w| w.preserve_transparency = preserve)
+    }
+
+    /// 固定或取消固定标签，返回修改后的记录，窗口未嵌入时为 None
+    pub fn set_pinned(&self, hwnd: isize, pinned: bool) -> Option<EmbeddedWindow> {
+        self.update(hwnd, |w| w.pinned = pinned)
+    }
+
+    fn update(&self, hwnd: isize, f: impl FnOnce(&mut EmbeddedWindow)) -> Option<EmbeddedWindow> {
+        let mut windows = self.windows.write().unwrap();
+        let window = windows.iter_mut().find(|w| w.hwnd == hwnd)?;
+        f(window);
+        Some(window.clone())
+    }
+
+    /// 释放窗口并恢复原始状态，释放后移除记录 (托盘菜单和退出清理只处理仍嵌入的窗口)
+    /// 返回移除的记录，窗口没有被嵌入时返回 NotEmbedded，不修改窗口
+    pub fn release(&self, platform: &dyn Platform, hwnd: isize) -> Result<EmbeddedWindow, EmbedError> {
+        let _lock = self.lock_hwnd(hwnd);
+        let saved = self.remove(hwnd).ok_or_else(|| EmbedError::new(EmbedErrorCode::NotEmbedded))?;
+        platform.release(hwnd, Some(&saved));
+        Ok(saved)
+    }
+
+    /// 释放所有嵌入窗口 (用于程序退出时的同步清理)
+    pub fn release_all(&self, platform: &dyn Platform) {
+        let windows = self.list();
+        info!(target: "embed", "释放 {} 个嵌入窗口", windows.len());
+        for saved in windows.iter().filter(|w| platform.is_window(w.hwnd)) {
+            platform.release(saved.hwnd, Some(saved));
+        }
+    }
+}
+
+/// 检查窗口是否可以安全嵌入：不能是自身、危险窗口、用户禁止嵌入或之前嵌入时无响应的窗口
+pub fn check_embeddable(platform: &dyn Platform, hwnd: isize) -> Result<(), EmbedError> {
+    if platform.is_own_window(hwnd) {
+        return Err(EmbedError::new(EmbedErrorCode::OwnWindow));
+    }
+    let class_name = platform.class_name(hwnd);
+    if platform.is_dangerous_class(&class_name) {
+        return Err(EmbedError::with_param(EmbedErrorCode::Dangerous, class_name));
+    }
+    if is_blocked(platform, hwnd, &class_name) {
+        return Err(EmbedError::with_param(EmbedErrorCode::Blocked, class_name));
+    }
+    if crate::problem_classes::contains(&class_name) {
+        return Err(EmbedError::with_param(EmbedErrorCode::Problematic, class_name));
+    }
+    Ok(())
+}
+
+/// 用户在设置中禁止嵌入的窗口 (按类名或 EXE 文件名)
+pub fn is_blocked(platform: &dyn Platform, hwnd: isize, class_name: &str) -> bool {
+    let exe_path = platform.process_path(hwnd).ok();
+    crate::settings::is_blocked(class_name, exe_path.as_deref())
+}
+
+#[cfg(windows)]
+fn probe(window: EmbeddedWindow) -> EmbeddedWindowStatus {
+    unsafe {
+        let hwnd = HWND(window.hwnd as *mut _);
+        let valid = IsWindow(hwnd).as_bool();
+        if !valid {
+            return EmbeddedWindowStatus {
+                window,
+                current_title: String::new(),
+                current_rect: None,
+                valid,
+                visible: false,
+                hung: false,
+                suspended: false,
+                cloaked: false,
+            };
+        }
+
+        let mut rect = RECT::default();
+        let current_rect = GetWindowRect(hwnd, &mut rect).ok().map(|_| rect.into());
+
+        let mut cloaked: u32 = 0;
+        let cloaked = DwmGetWindowAttribute(hwnd, DWMWA_CLOAKED, &mut cloaked as *mut _ as *mut _, std::mem::size_of::<u32>() as u32).is_ok() && cloaked != 0;
+
+        EmbeddedWindowStatus {
+            current_title: crate::platform::win32::window_title(hwnd),
+            current_rect,
+            valid,
+            visible: IsWindowVisible(hwnd).as_bool(),
+            hung: IsHungAppWindow(hwnd).as_bool(),
+            suspended: is_process_frozen(window.pid),
+            cloaked,
+            window,
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn probe(window: EmbeddedWindow) -> EmbeddedWindowStatus {
+    EmbeddedWindowStatus {
+        window,
+        current_title: String::new(),
+        current_rect: None,
+        valid: false,
+        visible: false,
+        hung: false,
+        suspended: false,
+        cloaked: false,
+    }
+}
+
+// PROCESS_EXTENDED_BASIC_INFORMATION (windows crate 需要引入 SystemServices 才有，这里按相同布局声明)
+#[cfg(windows)]
+#[repr(C)]
+#[derive(Default)]
+struct ProcessExtendedBasicInformation {
+    size: usize,
+    basic_info: [usize; 6], // PROCESS_BASIC_INFORMATION
+    flags: u32,
+}
+
+// Flags 中的 IsFrozen 位，任务管理器显示"已挂起"即依据此位
+#[cfg(windows)]
+const PROCESS_FROZEN_FLAG: u32 = 1 << 4;
+
+#[cfg(windows)]
+unsafe fn is_process_frozen(pid: u32) -> bool {
+    if pid == 0 {
+        return false;
+    }
+    let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, BOOL(0), pid) else {
+        return false;
+    };
+    let mut info = ProcessExtendedBasicInformation {
+        size: std::mem::size_of::<ProcessExtendedBasicInformation>(),
+        ..Default::default()
+    };
+    let status = NtQueryInformationProcess(
+        process,
+        ProcessBasicInformation,
+        &mut info as *mut _ as *mut _,
+        std::mem::size_of::<ProcessExtendedBasicInformation>() as u32,
+        std::ptr::null_mut(),
+    );
+    let _ = CloseHandle(process);
+    status.is_ok() && info.flags & PROCESS_FROZEN_FLAG != 0
+}
+
+/// 非命令上下文 (托盘、系统事件线程) 获取已嵌入窗口句柄
+pub fn embedded_hwnds(app: &AppHandle) -> Vec<isize> {
+    app.state::<WindowManager>().hwnds()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::{MockPlatform, WS_CHILD};
+
+    const HOST: isize = 0x10;
+
+    #[test]
+    fn reserved_slots_count_toward_the_tab_limit() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let limit = crate::settings::get().limits.max_tabs;
+        let mut slots: Vec<_> = (0..limit).map(|_| manager.reserve_tab(&platform).unwrap()).collect();
+        // 还没有写入记录的嵌入同样占用名额
+        assert_eq!(manager.reserve_tab(&platform).err().map(|e| e.code), Some(EmbedErrorCode::LimitExceeded));
+        slots.pop();
+        assert!(manager.reserve_tab(&platform).is_ok());
+        drop(slots);
+        assert_eq!(*manager.reserved.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn embed_then_release_restores_original_state() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let hwnd = platform.create_window("记事本", "Notepad", Some(r"C:\Windows\notepad.exe"));
+        let before = platform.window(hwnd).unwrap();
+
+        manager.embed(&platform, HOST, hwnd).unwrap();
+        let embedded = platform.window(hwnd).unwrap();
+        assert_eq!(embedded.parent, HOST);
+        assert_ne!(embedded.style & WS_CHILD, 0);
+        assert_eq!(manager.hwnds(), vec![hwnd]);
+        assert_eq!(manager.list()[0].exe_path.as_deref(), Some(r"C:\Windows\notepad.exe"));
+
+        let saved = manager.release(&platform, hwnd).unwrap();
+        assert_eq!(saved.hwnd, hwnd);
+        assert_eq!(platform.window(hwnd).unwrap(), before);
+        assert_eq!(manager.len(), 0);
+    }
+
+    #[test]
+    fn embed_timeout_marks_class_as_problematic() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let hung = platform.create_window("未响应", "HungTestWindow", None);
+        platform.set_hung(hung);
+
+        let error = manager.embed(&platform, HOST, hung).unwrap_err();
+        assert_eq!(error.code, EmbedErrorCode::Timeout);
+        assert_eq!(manager.len(), 0);
+
+        // 同类的其他窗口直接拒绝，不再尝试
+        let other = platform.create_window("另一个", "HungTestWindow", None);
+        assert_eq!(manager.embed(&platform, HOST, other).unwrap_err().code, EmbedErrorCode::Problematic);
+        crate::problem_classes::forget("HungTestWindow");
+        assert!(manager.embed(&platform, HOST, other).is_ok());
+    }
+
+    #[test]
+    fn embedding_twice_keeps_first_original_state() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let hwnd = platform.create_window("终端", "CASCADIA_HOSTING_WINDOW_CLASS", None);
+        let before = platform.window(hwnd).unwrap();
+
+        manager.embed(&platform, HOST, hwnd).unwrap();
+        manager.embed(&platform, HOST, hwnd).unwrap();
+        assert_eq!(manager.len(), 1);
+
+        manager.release(&platform, hwnd).unwrap();
+        assert_eq!(platform.window(hwnd).unwrap(), before);
+    }
+
+    #[test]
+    fn release_without_record_is_rejected() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let hwnd = platform.create_window("画图", "MSPaintApp", None);
+        let before = platform.window(hwnd).unwrap();
+
+        assert_eq!(manager.release(&platform, hwnd).unwrap_err().code, EmbedErrorCode::NotEmbedded);
+        assert_eq!(platform.window(hwnd).unwrap(), before);
+
+        manager.embed(&platform, HOST, hwnd).unwrap();
+        manager.release(&platform, hwnd).unwrap();
+        assert_eq!(manager.release(&platform, hwnd).unwrap_err().code, EmbedErrorCode::NotEmbedded);
+    }
+
+    #[test]
+    fn release_waits_for_embed_in_flight() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let hwnd = platform.create_window("记事本", "Notepad", None);
+        let before = platform.window(hwnd).unwrap();
+
+        std::thread::scope(|s| {
+            let lock = manager.lock_hwnd(hwnd);
+            // 同一线程可以重复锁定 (嵌入命令持有锁时调用 embed)
+            manager.embed(&platform, HOST, hwnd).unwrap();
+            let release = s.spawn(|| manager.release(&platform, hwnd));
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            assert!(!release.is_finished());
+            drop(lock);
+            assert!(release.join().unwrap().is_ok());
+        });
+        assert_eq!(platform.window(hwnd).unwrap(), before);
+        assert_eq!(manager.len(), 0);
+    }
+
+    #[test]
+    fn busy_window_does_not_block_other_tabs() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let stuck = platform.create_window("未响应", "Stuck", None);
+        let other = platform.create_window("记事本", "Notepad", None);
+        manager.embed(&platform, HOST, stuck).unwrap();
+
+        std::thread::scope(|s| {
+            // 另一个线程一直占着 stuck 的操作锁
+            let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+            let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+            let manager = &manager;
+            s.spawn(move || {
+                let _lock = manager.lock_hwnd(stuck);
+                locked_tx.send(()).unwrap();
+                let _ = done_rx.recv();
+            });
+            locked_rx.recv().unwrap();
+
+            manager.embed(&platform, HOST, other).unwrap();
+            assert_eq!(manager.hwnds(), vec![stuck, other]);
+            manager.release(&platform, other).unwrap();
+            done_tx.send(()).unwrap();
+        });
+        assert_eq!(manager.hwnds(), vec![stuck]);
+    }
+
+    #[test]
+    fn release_all_skips_destroyed_windows() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let alive = platform.create_window("资源管理器", "CabinetWClass", None);
+        let closed = platform.create_window("计算器", "CalcFrame", None);
+        let before = platform.window(alive).unwrap();
+        manager.embed(&platform, HOST, alive).unwrap();
+        manager.embed(&platform, HOST, closed).unwrap();
+
+        platform.destroy_window(closed);
+        manager.release_all(&platform);
+        assert_eq!(platform.window(alive).unwrap(), before);
+        assert!(!platform.is_window(closed));
+    }
+
+    #[test]
+    fn replacement_window_takes_over_tab_slot() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let first = platform.create_window("编辑器", "Editor", Some(r"C:\Apps\editor.exe"));
+        let other = platform.create_window("终端", "Terminal", None);
+        manager.embed(&platform, HOST, first).unwrap();
+        manager.embed(&platform, HOST, other).unwrap();
+        manager.set_label(first, Some("笔记".to_string()));
+        let tab_id = manager.get(first).unwrap().tab_id;
+
+        platform.destroy_window(first);
+        let restarted = platform.create_window("编辑器", "Editor", Some(r"C:\Apps\editor.exe"));
+        manager.embed(&platform, HOST, restarted).unwrap();
+        let window = manager.replace_tab(tab_id, restarted).unwrap();
+        assert_eq!(window.tab_id, tab_id);
+        assert_eq!(window.label.as_deref(), Some("笔记"));
+        assert_eq!(manager.hwnds(), vec![restarted, other]);
+        assert!(manager.replace_tab(tab_id, restarted).is_none());
+    }
+
+    #[test]
+    fn rejects_own_window() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let hwnd = platform.create_own_window("WindowHub");
+
+        let err = manager.embed(&platform, HOST, hwnd).unwrap_err();
+        assert_eq!(err, EmbedError::new(EmbedErrorCode::OwnWindow));
+        assert_eq!(platform.window(hwnd).unwrap().parent, 0);
+        assert_eq!(manager.len(), 0);
+    }
+
+    #[test]
+    fn rejects_dangerous_windows() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let desktop = platform.create_window("Program Manager", "Progman", None);
+        let taskbar = platform.create_window("任务栏", "Shell_TrayWnd", None);
+
+        for hwnd in [desktop, taskbar] {
+            let before = platform.window(hwnd).unwrap();
+            let err = manager.embed(&platform, HOST, hwnd).unwrap_err();
+            assert_eq!(err.code, EmbedErrorCode::Dangerous);
+            assert!(err.message.starts_with("不支持嵌入此类型窗口"), "{}", err.message);
+            assert_eq!(platform.window(hwnd).unwrap(), before);
+        }
+        assert_eq!(manager.len(), 0);
+    }
+
+    #[test]
+    fn embedding_missing_window_fails() {
+        let platform = MockPlatform::new();
+        let manager = WindowManager::default();
+        let hwnd = platform.create_window("临时", "Temp", None);
+        platform.destroy_window(hwnd);
+
+        assert_eq!(manager.embed(&platform, HOST, hwnd).unwrap_err().code, EmbedErrorCode::InvalidWindow);
+        assert_eq!(manager.len(), 0);
+    }
+}
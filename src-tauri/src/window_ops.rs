@@ -0,0 +1,199 @@
+// 窗口操作线程
+// SetForegroundWindow、SetFocus、AttachThreadInput 的效果与调用线程的输入状态有关，
+// 从主线程、命令线程池、钩子线程等不同线程调用时焦点切换时好时坏。
+// 激活标签、把标签提到前台、焦点保护的还原都交给这一个专用线程执行：
+// - 线程有自己的消息循环，任务作为线程消息 (WM_RUN) 送达，依次执行
+// - call 等待结果 (一次性通道)，超时返回错误：还没开始的任务直接取消，已经开始的无法中断，完成后结果丢弃；
+//   等待期间处理其他线程发给调用线程的消息，调用线程 (主线程) 的窗口参与其中时不会互相等待
+// - 某个任务卡住超过 TIMEOUT (目标窗口无响应) 时换一个新线程，不让一个标签拖住所有窗口操作，旧线程做完手上的任务后退出
+// - 在操作线程上再次调用直接执行
+// 嵌入和释放不在这里：SetParent 遇到卡住的目标可能一直阻塞，它们各自用工作线程加超时 (见 platform/win32.rs)
+// 非 Windows 平台直接在调用线程执行
+
+use std::time::Duration;
+
+#[cfg(windows)]
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+#[cfg(windows)]
+use std::sync::mpsc::{self, RecvTimeoutError};
+#[cfg(windows)]
+use std::sync::{Arc, Mutex};
+#[cfg(windows)]
+use std::time::Instant;
+#[cfg(windows)]
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, WPARAM},
+    System::Threading::GetCurrentThreadId,
+    UI::WindowsAndMessaging::*,
+};
+
+/// 等待一次窗口操作的上限
+#[cfg_attr(not(windows), allow(dead_code))]
+pub const TIMEOUT: Duration = Duration::from_secs(3);
+
+// 执行任务 (LPARAM 为 Box<Job> 指针)
+#[cfg(windows)]
+const WM_RUN: u32 = WM_APP + 1;
+
+#[cfg(windows)]
+type Job = Box<dyn FnOnce() + Send>;
+
+// 当前的操作线程 (线程 ID, 代数)
+#[cfg(windows)]
+static CURRENT: Mutex<Option<(u32, u64)>> = Mutex::new(None);
+#[cfg(windows)]
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// 当前线程正在执行的任务开始的时间 (Unix 毫秒)，空闲时为 0
+#[cfg(windows)]
+static BUSY_SINCE: AtomicU64 = AtomicU64::new(0);
+
+/// 在操作线程上执行 f 并等待结果
+#[cfg(windows)]
+pub fn call<T: Send + 'static>(op: &'static str, f: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    let Some(thread) = ops_thread() else {
+        return Ok(f());
+    };
+    if thread == unsafe { GetCurrentThreadId() } {
+        return Ok(f());
+    }
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    let flag = cancelled.clone();
+    post_job(thread, Box::new(move || {
+        if !flag.load(Ordering::SeqCst) {
+            let _ = tx.send(f());
+        }
+    }))?;
+
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        match rx.recv_timeout(Duration::from_millis(10)) {
+            Ok(result) => return Ok(result),
+            Err(RecvTimeoutError::Disconnected) => return Err(format!("窗口操作异常结束: {}", op)),
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+        unsafe {
+            let mut msg = MSG::default();
+            let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE | PM_QS_SENDMESSAGE);
+        }
+        if Instant::now() >= deadline {
+            cancelled.store(true, Ordering::SeqCst);
+            warn!(target: "focus", "窗口操作超时，已放弃: {}", op);
+            return Err(format!("窗口操作超时: {}", op));
+        }
+    }
+}
+
+#[cfg(not(windows))]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn call<T: Send + 'static>(_op: &'static str, f: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    Ok(f())
+}
+
+/// 在操作线程上执行 f，不等待 (钩子回调中使用)
+#[cfg(windows)]
+pub fn post(op: &'static str, f: impl FnOnce() + Send + 'static) {
+    match ops_thread() {
+        Some(thread) if thread != unsafe { GetCurrentThreadId() } => {
+            if let Err(e) = post_job(thread, Box::new(f)) {
+                warn!(target: "focus", "{}: {}", op, e);
+            }
+        }
+        _ => f(),
+    }
+}
+
+#[cfg(windows)]
+fn post_job(thread: u32, job: Job) -> Result<(), String> {
+    let job = Box::into_raw(Box::new(job));
+    unsafe {
+        if PostThreadMessageW(thread, WM_RUN, WPARAM(0), LPARAM(job as isize)).is_err() {
+            drop(Box::from_raw(job));
+            return Err("无法提交窗口操作".to_string());
+        }
+    }
+    Ok(())
+}
+
+// 当前可用的操作线程，还没有或者卡住时启动新的
+#[cfg(windows)]
+fn ops_thread() -> Option<u32> {
+    let mut current = CURRENT.lock().unwrap();
+    if let Some((thread, _)) = *current {
+        let since = BUSY_SINCE.load(Ordering::SeqCst);
+        if since == 0 || now_ms().saturating_sub(since) < TIMEOUT.as_millis() as u64 {
+            return Some(thread);
+        }
+        warn!(target: "focus", "窗口操作线程卡住 {} ms，改用新线程", now_ms().saturating_sub(since));
+    }
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    BUSY_SINCE.store(0, Ordering::SeqCst);
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || unsafe { run(generation, tx) });
+    let thread = rx.recv().ok()?;
+    *current = Some((thread, generation));
+    info!(target: "focus", "窗口操作线程已启动: thread={}", thread);
+    Some(thread)
+}
+
+#[cfg(windows)]
+unsafe fn run(generation: u64, tx: mpsc::Sender<u32>) {
+    let mut msg = MSG::default();
+    // 先建立消息队列，之后 PostThreadMessage 才能送达
+    let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+    let _ = tx.send(GetCurrentThreadId());
+
+    let current = || GENERATION.load(Ordering::SeqCst) == generation;
+    while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+        if msg.hwnd.is_invalid() && msg.message == WM_RUN {
+            let job = Box::from_raw(msg.lParam.0 as *mut Job);
+            if current() {
+                BUSY_SINCE.store(now_ms(), Ordering::SeqCst);
+            }
+            job();
+            if !current() {
+                // 已被新线程取代，剩下的任务的调用方都已放弃
+                break;
+            }
+            BUSY_SINCE.store(0, Ordering::SeqCst);
+            continue;
+        }
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+    }
+    // 丢弃队列中剩下的任务
+    while PeekMessageW(&mut msg, HWND::default(), WM_RUN, WM_RUN, PM_REMOVE).as_bool() {
+        if msg.hwnd.is_invalid() {
+            drop(Box::from_raw(msg.lParam.0 as *mut Job));
+        }
+    }
+}
+
+#[cfg(windows)]
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_every_call_on_the_same_thread() {
+        let first = call("test", || unsafe { GetCurrentThreadId() }).unwrap();
+        let second = call("test", || unsafe { GetCurrentThreadId() }).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, unsafe { GetCurrentThreadId() });
+        // 操作线程上再次调用直接执行
+        assert_eq!(call("test", || call("nested", || 42).unwrap()).unwrap(), 42);
+    }
+}
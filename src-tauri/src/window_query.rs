@@ -0,0 +1,175 @@
+// 按条件查找窗口
+// find_windows(query) 在顶层窗口和已嵌入的标签中查找，脚本和前端不用取回整个列表自己筛选：
+// - title_regex: 标题的正则表达式，不区分大小写 (如 "slack|私信")
+// - class: 窗口类名，exe: 程序文件名或完整路径，支持 * 通配，不区分大小写
+// - pid: 进程 ID
+// 没有给出的条件不限制，结果按窗口列表的顺序，已嵌入的标签排在后面
+// embed_by_reference(reference) 用粘贴的文本指定要嵌入的窗口 (搜索框和自动化接口)，resolve 解析为句柄：
+// - 十六进制句柄 ("0x000A0B2C")
+// - "exe:notepad.exe": 程序文件名，支持通配，前缀不区分大小写
+// - 其他按完整的窗口标题；没有同名窗口时再当作不带前缀的句柄 (Spy++ 显示的 "000A0B2C")，
+//   "Cafe"、"1234" 这样的标题不会被误当作句柄
+// 程序和标题只在没有嵌入的顶层窗口中查找，必须正好匹配一个
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::platform::Platform;
+use crate::window_manager::EmbeddedWindow;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WindowQuery {
+    pub title_regex: Option<String>,
+    pub class: Option<String>,
+    pub exe: Option<String>,
+    pub pid: Option<u32>,
+}
+
+/// 匹配的窗口
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowMatch {
+    pub hwnd: isize,
+    pub title: String,
+    pub class_name: String,
+    pub exe_path: Option<String>,
+    pub pid: u32,
+    pub embedded: bool, // 是否是已嵌入的标签
+}
+
+/// 查找符合条件的顶层窗口和标签，正则表达式有误时返回错误
+pub fn find(platform: &dyn Platform, tabs: &[EmbeddedWindow], query: &WindowQuery) -> Result<Vec<WindowMatch>, String> {
+    let title = query
+        .title_regex
+        .as_deref()
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| {
+            RegexBuilder::new(pattern).case_insensitive(true).build().map_err(|e| format!("标题正则表达式无效: {}", e))
+        })
+        .transpose()?;
+
+    let top_level = platform.enumerate().into_iter().map(|w| WindowMatch {
+        hwnd: w.hwnd,
+        exe_path: platform.process_path(w.hwnd).ok(),
+        pid: platform.process_id(w.hwnd),
+        title: w.title,
+        class_name: w.class_name,
+        embedded: false,
+    });
+    let embedded = tabs.iter().map(|w| WindowMatch {
+        hwnd: w.hwnd,
+        title: crate::privacy::title(platform.title(w.hwnd)),
+        class_name: w.class_name.clone(),
+        exe_path: w.exe_path.clone(),
+        pid: w.pid,
+        embedded: true,
+    });
+    Ok(top_level.chain(embedded).filter(|w| matches(w, query, title.as_ref())).collect())
+}
+
+/// 把粘贴的句柄、标题或 "exe:程序" 解析为要嵌入的窗口句柄，没有或有多个匹配时返回说明
+pub fn resolve(platform: &dyn Platform, reference: &str) -> Result<isize, String> {
+    let reference = reference.trim();
+    if reference.is_empty() {
+        return Err("请输入窗口句柄、标题或 exe:程序名".to_string());
+    }
+    let handle = parse_hwnd(reference).filter(|&hwnd| platform.is_window(hwnd));
+    if reference.starts_with("0x") || reference.starts_with("0X") {
+        if let Some(hwnd) = handle {
+            return Ok(hwnd);
+        }
+    }
+    let (query, described) = match strip_exe_prefix(reference) {
+        Some(exe) => (WindowQuery { exe: Some(exe.trim().to_string()), ..Default::default() }, format!("程序 {}", exe.trim())),
+        None => (WindowQuery::default(), format!("标题 \"{}\"", reference)),
+    };
+    let candidates: Vec<WindowMatch> = find(platform, &[], &query)?
+        .into_iter()
+        .filter(|w| query.exe.is_some() || w.title == reference)
+        .collect();
+    match candidates.as_slice() {
+        [window] => Ok(window.hwnd),
+        [] => handle.ok_or_else(|| format!("没有找到{}的窗口", described)),
+        _ => Err(format!("{}有 {} 个匹配的窗口，请更具体一些", described, candidates.len())),
+    }
+}
+
+// "exe:" 之后的程序名，前缀不区分大小写
+fn strip_exe_prefix(text: &str) -> Option<&str> {
+    text.get(..4).filter(|prefix| prefix.eq_ignore_ascii_case("exe:")).map(|_| &text[4..])
+}
+
+// 十六进制的窗口句柄，可以带 0x 前缀
+fn parse_hwnd(text: &str) -> Option<isize> {
+    let digits = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+    if digits.is_empty() || digits.len() > 16 {
+        return None;
+    }
+    u64::from_str_radix(digits, 16).ok().map(|hwnd| hwnd as isize).filter(|&hwnd| hwnd != 0)
+}
+
+fn matches(window: &WindowMatch, query: &WindowQuery, title: Option<&Regex>) -> bool {
+    if title.is_some_and(|re| !re.is_match(&window.title)) {
+        return false;
+    }
+    if query.class.as_deref().is_some_and(|class| !crate::rules::glob_match(class, &window.class_name)) {
+        return false;
+    }
+    if let Some(exe) = query.exe.as_deref() {
+        let Some(path) = window.exe_path.as_deref() else { return false };
+        let name = path.rsplit(['\\', '/']).next().unwrap_or(path);
+        if !crate::rules::glob_match(exe, name) && !crate::rules::glob_match(exe, path) {
+            return false;
+        }
+    }
+    query.pid.is_none_or(|pid| pid == window.pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::MockPlatform;
+
+    #[test]
+    fn finds_windows_by_title_class_and_exe() {
+        let platform = MockPlatform::new();
+        let slack = platform.create_window("Slack | 通用", "Chrome_WidgetWin_1", Some(r"C:\Users\me\AppData\Local\slack\slack.exe"));
+        let notepad = platform.create_window("无标题 - 记事本", "Notepad", Some(r"C:\Windows\notepad.exe"));
+        let query = |title: Option<&str>, class: Option<&str>, exe: Option<&str>| WindowQuery {
+            title_regex: title.map(String::from),
+            class: class.map(String::from),
+            exe: exe.map(String::from),
+            pid: None,
+        };
+        let hwnds = |query: WindowQuery| find(&platform, &[], &query).unwrap().into_iter().map(|w| w.hwnd).collect::<Vec<_>>();
+
+        assert_eq!(hwnds(query(Some("^slack"), None, None)), vec![slack]);
+        assert_eq!(hwnds(query(None, Some("notepad"), None)), vec![notepad]);
+        assert_eq!(hwnds(query(None, Some("Chrome_*"), Some("slack.exe"))), vec![slack]);
+        assert_eq!(hwnds(query(None, None, Some(r"C:\Windows\*"))), vec![notepad]);
+        assert!(hwnds(query(Some("记事本"), None, Some("slack.exe"))).is_empty());
+        assert!(find(&platform, &[], &query(Some("(未闭合"), None, None)).is_err());
+    }
+
+    #[test]
+    fn resolves_pasted_references() {
+        let platform = MockPlatform::new();
+        let notepad = platform.create_window("无标题 - 记事本", "Notepad", Some(r"C:\Windows\notepad.exe"));
+        let first = platform.create_window("Slack", "Chrome_WidgetWin_1", Some(r"C:\slack\slack.exe"));
+        platform.create_window("Slack", "Chrome_WidgetWin_1", Some(r"C:\slack\slack.exe"));
+
+        assert_eq!(resolve(&platform, &format!("0x{:X}", notepad)), Ok(notepad));
+        assert_eq!(resolve(&platform, &format!("{:08x}", first)), Ok(first));
+        assert_eq!(resolve(&platform, " 无标题 - 记事本 "), Ok(notepad));
+        assert_eq!(resolve(&platform, "exe:NOTEPAD.exe"), Ok(notepad));
+        assert_eq!(resolve(&platform, "EXE: notepad.exe"), Ok(notepad));
+        // 不带前缀时同名的窗口优先于句柄
+        let titled = platform.create_window(&format!("{:x}", notepad), "Notepad", Some(r"C:\Windows\notepad.exe"));
+        assert_eq!(resolve(&platform, &format!("{:x}", notepad)), Ok(titled));
+        assert_eq!(resolve(&platform, &format!("0x{:x}", notepad)), Ok(notepad));
+        assert!(resolve(&platform, "exe:slack.exe").unwrap_err().contains("2 个"));
+        assert!(resolve(&platform, "Slack").unwrap_err().contains("2 个"));
+        assert!(resolve(&platform, "记事本").is_err());
+        assert!(resolve(&platform, "").is_err());
+    }
+}
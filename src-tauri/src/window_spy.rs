@@ -0,0 +1,169 @@
+// 窗口结构查看器 (类似简化的 Spy++)
+// - get_window_tree(hwnd): 窗口及其所有子窗口的层级，每个节点带类名、标题、样式、位置
+// - inspect_point(x, y): 屏幕上某一点最深处的子窗口，以及从顶层窗口到它的路径
+// 拾取窗口时显示光标下的结构；用户反馈某个由多个子窗口组成的程序嵌入后拿不到焦点时，
+// 可以看出焦点停在哪个子窗口 (focus) 以及哪些子窗口被禁用或隐藏
+// 标题用 InternalGetWindowText 读取，不向对方发送消息，目标程序卡死时也不会阻塞
+
+use serde::Serialize;
+
+use crate::window_manager::WindowRect;
+
+#[cfg(windows)]
+use crate::platform::{style, win32};
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, POINT, RECT},
+    Graphics::Gdi::ScreenToClient,
+    UI::Input::KeyboardAndMouse::IsWindowEnabled,
+    UI::WindowsAndMessaging::{
+        ChildWindowFromPointEx, GetAncestor, GetGUIThreadInfo, GetWindow, GetWindowLongPtrW, GetWindowRect,
+        GetWindowThreadProcessId, InternalGetWindowText, IsWindow, IsWindowVisible, WindowFromPoint,
+        CWP_SKIPINVISIBLE, CWP_SKIPTRANSPARENT, GA_PARENT, GA_ROOT, GUITHREADINFO, GWL_EXSTYLE, GWL_STYLE, GW_CHILD,
+        GW_HWNDNEXT,
+    },
+};
+
+// 一棵树最多列出的窗口数 (有的程序有上千个子窗口)
+#[cfg(windows)]
+const MAX_NODES: usize = 2000;
+
+/// 一个窗口，children 为直接子窗口 (按 Z 序从上到下)
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowNode {
+    pub hwnd: isize,
+    pub class_name: String,
+    pub title: String,
+    pub pid: u32,
+    pub thread_id: u32,
+    pub style: u32,
+    pub style_names: Vec<String>,
+    pub ex_style: u32,
+    pub ex_style_names: Vec<String>,
+    pub rect: Option<WindowRect>,
+    pub visible: bool,
+    pub enabled: bool,
+    pub children: Vec<WindowNode>,
+}
+
+/// get_window_tree 的结果
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowTree {
+    pub root: WindowNode,
+    pub focus: isize,    // 窗口所在线程中有输入焦点的窗口，没有时为 0
+    pub truncated: bool, // 超过 MAX_NODES 个窗口，后面的没有列出
+}
+
+/// inspect_point 的结果，path 为从顶层窗口到 hwnd 的每一级 (不含子窗口)
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct PointInfo {
+    pub hwnd: isize,
+    pub root: isize,
+    pub path: Vec<WindowNode>,
+}
+
+/// 窗口及其所有子窗口的层级
+#[cfg(windows)]
+pub fn tree(hwnd: isize) -> Result<WindowTree, String> {
+    let handle = HWND(hwnd as *mut _);
+    unsafe {
+        if !IsWindow(handle).as_bool() {
+            return Err("无效的窗口句柄".to_string());
+        }
+        let mut count = 0;
+        let root = build(handle, &mut count);
+        let mut gui = GUITHREADINFO { cbSize: std::mem::size_of::<GUITHREADINFO>() as u32, ..Default::default() };
+        let focus = if GetGUIThreadInfo(root.thread_id, &mut gui).is_ok() { gui.hwndFocus.0 as isize } else { 0 };
+        Ok(WindowTree { root, focus, truncated: count > MAX_NODES })
+    }
+}
+
+#[cfg(not(windows))]
+pub fn tree(_hwnd: isize) -> Result<WindowTree, String> {
+    Err("仅支持 Windows".to_string())
+}
+
+/// 屏幕坐标处最深的子窗口 (包括被禁用的子窗口)
+#[cfg(windows)]
+pub fn inspect(x: i32, y: i32) -> Result<PointInfo, String> {
+    unsafe {
+        let pt = POINT { x, y };
+        let mut hwnd = WindowFromPoint(pt);
+        if hwnd.is_invalid() {
+            return Err("该位置没有窗口".to_string());
+        }
+        // WindowFromPoint 跳过被禁用的子窗口，继续向下找
+        loop {
+            let mut client = pt;
+            let _ = ScreenToClient(hwnd, &mut client);
+            let child = ChildWindowFromPointEx(hwnd, client, CWP_SKIPINVISIBLE | CWP_SKIPTRANSPARENT);
+            if child.is_invalid() || child == hwnd {
+                break;
+            }
+            hwnd = child;
+        }
+        let root = GetAncestor(hwnd, GA_ROOT);
+        let mut path = Vec::new();
+        let mut current = hwnd;
+        while !current.is_invalid() {
+            path.push(node(current));
+            if current == root {
+                break;
+            }
+            current = GetAncestor(current, GA_PARENT);
+        }
+        path.reverse();
+        Ok(PointInfo { hwnd: hwnd.0 as isize, root: root.0 as isize, path })
+    }
+}
+
+#[cfg(not(windows))]
+pub fn inspect(_x: i32, _y: i32) -> Result<PointInfo, String> {
+    Err("仅支持 Windows".to_string())
+}
+
+// 递归列出子窗口，count 为已经列出的窗口数
+#[cfg(windows)]
+unsafe fn build(hwnd: HWND, count: &mut usize) -> WindowNode {
+    *count += 1;
+    let mut result = node(hwnd);
+    let mut child = GetWindow(hwnd, GW_CHILD).unwrap_or_default();
+    while !child.is_invalid() {
+        if *count >= MAX_NODES {
+            *count += 1;
+            break;
+        }
+        result.children.push(build(child, count));
+        child = GetWindow(child, GW_HWNDNEXT).unwrap_or_default();
+    }
+    result
+}
+
+#[cfg(windows)]
+unsafe fn node(hwnd: HWND) -> WindowNode {
+    let mut pid = 0;
+    let thread_id = GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    let style = GetWindowLongPtrW(hwnd, GWL_STYLE) as u32;
+    let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32;
+    let mut rect = RECT::default();
+    let mut title = [0u16; 256];
+    let len = InternalGetWindowText(hwnd, &mut title).max(0) as usize;
+    WindowNode {
+        hwnd: hwnd.0 as isize,
+        class_name: win32::class_name(hwnd),
+        title: String::from_utf16_lossy(&title[..len]),
+        pid,
+        thread_id,
+        style,
+        style_names: style::set_names(style, style::STYLE_NAMES),
+        ex_style,
+        ex_style_names: style::set_names(ex_style, style::EX_STYLE_NAMES),
+        rect: GetWindowRect(hwnd, &mut rect).ok().map(|_| WindowRect::from(rect)),
+        visible: IsWindowVisible(hwnd).as_bool(),
+        enabled: IsWindowEnabled(hwnd).as_bool(),
+        children: Vec::new(),
+    }
+}
@@ -0,0 +1,321 @@
+// 内容区中的停靠区域
+// 拖动标签或外部窗口到主窗口上时，按光标位置给出停靠区域 (左/右半边、四个角、整个内容区)，
+// 松开后标签停靠到该区域。停靠的标签同时显示，切换到其中任意一个时其他停靠的标签一起显示；
+// 切换到未停靠的标签时它占满内容区，停靠的标签一起隐藏
+// 区域按内容区的 2x2 网格表示，新停靠的标签占用的格子上原来的标签改为不停靠
+// 网格的列宽和行高按分隔比例划分 (可拖动分隔条调整)，比例随工作区保存
+// 标签可以临时最大化 (占满主窗口客户区，其他停靠的标签隐藏)，停靠的区域保持不变，取消最大化或切换到其他标签后恢复
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+
+/// 停靠区域
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Zone {
+    Full,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+// 2x2 网格中的格子
+const TOP_LEFT: u8 = 1;
+const TOP_RIGHT: u8 = 2;
+const BOTTOM_LEFT: u8 = 4;
+const BOTTOM_RIGHT: u8 = 8;
+
+// 靠近内容区边缘多少比例的范围内给出半边或角落，中间为整个内容区
+const EDGE: f32 = 0.25;
+
+// 相邻区域之间留出的空隙 (物理像素)，前端在这里画可拖动的分隔条
+const SPLITTER: i32 = 6;
+
+// 分隔比例的范围，避免把一侧拖到看不见
+const MIN_RATIO: f32 = 0.1;
+const MAX_RATIO: f32 = 0.9;
+
+impl Zone {
+    fn cells(self) -> u8 {
+        match self {
+            Zone::Full => TOP_LEFT | TOP_RIGHT | BOTTOM_LEFT | BOTTOM_RIGHT,
+            Zone::Left => TOP_LEFT | BOTTOM_LEFT,
+            Zone::Right => TOP_RIGHT | BOTTOM_RIGHT,
+            Zone::TopLeft => TOP_LEFT,
+            Zone::TopRight => TOP_RIGHT,
+            Zone::BottomLeft => BOTTOM_LEFT,
+            Zone::BottomRight => BOTTOM_RIGHT,
+        }
+    }
+}
+
+/// 拖动时显示的停靠区域 (主窗口客户区坐标，物理像素)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ZoneHint {
+    pub zone: Zone,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// tab-zone-changed 事件的载荷
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TabZone {
+    pub hwnd: isize,
+    pub zone: Zone,
+}
+
+/// 网格的分隔比例：左列宽度和上行高度占内容区的比例
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SplitRatios {
+    pub columns: f32,
+    pub rows: f32,
+}
+
+impl Default for SplitRatios {
+    fn default() -> Self {
+        SplitRatios { columns: 0.5, rows: 0.5 }
+    }
+}
+
+impl SplitRatios {
+    /// 限制在可以拖动的范围内
+    pub fn clamped(self) -> SplitRatios {
+        SplitRatios { columns: self.columns.clamp(MIN_RATIO, MAX_RATIO), rows: self.rows.clamp(MIN_RATIO, MAX_RATIO) }
+    }
+}
+
+/// 分隔条：columns 为左右两列之间，rows 为上下两行之间
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitGroup {
+    Columns,
+    Rows,
+}
+
+// 停靠的标签 (不含占满内容区的标签)
+static DOCKED: Mutex<Vec<(isize, Zone)>> = Mutex::new(Vec::new());
+
+// 临时最大化的标签 (0 为没有)
+static MAXIMIZED: AtomicIsize = AtomicIsize::new(0);
+
+static SPLIT: Mutex<SplitRatios> = Mutex::new(SplitRatios { columns: 0.5, rows: 0.5 });
+
+pub fn split() -> SplitRatios {
+    *SPLIT.lock().unwrap()
+}
+
+/// 替换分隔比例 (恢复工作区时)
+pub fn set_split(ratios: SplitRatios) {
+    *SPLIT.lock().unwrap() = ratios.clamped();
+}
+
+/// 调整一个分隔条，返回新的分隔比例
+pub fn set_split_ratio(group: SplitGroup, ratio: f32) -> Result<SplitRatios, String> {
+    if !ratio.is_finite() {
+        return Err("无效的分隔比例".to_string());
+    }
+    let mut ratios = split();
+    match group {
+        SplitGroup::Columns => ratios.columns = ratio,
+        SplitGroup::Rows => ratios.rows = ratio,
+    }
+    set_split(ratios);
+    Ok(split())
+}
+
+/// 光标在内容区 area (x, y, 宽, 高) 中 (px, py) 时的停靠区域，不在内容区内时为 None
+pub fn hint_at(area: (i32, i32, i32, i32), ratios: SplitRatios, px: i32, py: i32) -> Option<ZoneHint> {
+    let (x, y, width, height) = area;
+    if px < x || py < y || px >= x + width || py >= y + height {
+        return None;
+    }
+    let fx = (px - x) as f32 / width as f32;
+    let fy = (py - y) as f32 / height as f32;
+    let (left, right) = (fx < EDGE, fx > 1.0 - EDGE);
+    let (top, bottom) = (fy < EDGE, fy > 1.0 - EDGE);
+    let zone = match (left, right, top, bottom) {
+        (true, _, true, _) => Zone::TopLeft,
+        (true, _, _, true) => Zone::BottomLeft,
+        (_, true, true, _) => Zone::TopRight,
+        (_, true, _, true) => Zone::BottomRight,
+        (true, ..) => Zone::Left,
+        (_, true, ..) => Zone::Right,
+        _ => Zone::Full,
+    };
+    let (x, y, width, height) = rect(zone, area, ratios);
+    Some(ZoneHint { zone, x, y, width, height })
+}
+
+/// 区域在内容区 area 中的位置和大小
+pub fn rect(zone: Zone, area: (i32, i32, i32, i32), ratios: SplitRatios) -> (i32, i32, i32, i32) {
+    let (x, y, width, height) = area;
+    let cells = zone.cells();
+    let (x, width) = span(
+        x,
+        width,
+        ratios.columns,
+        cells & (TOP_LEFT | BOTTOM_LEFT) != 0,
+        cells & (TOP_RIGHT | BOTTOM_RIGHT) != 0,
+    );
+    let (y, height) = span(
+        y,
+        height,
+        ratios.rows,
+        cells & (TOP_LEFT | TOP_RIGHT) != 0,
+        cells & (BOTTOM_LEFT | BOTTOM_RIGHT) != 0,
+    );
+    (x, y, width, height)
+}
+
+// 一个方向上的起点和长度：只占前一半或后一半时按比例分开，并留出分隔条的空隙
+fn span(start: i32, len: i32, ratio: f32, first: bool, second: bool) -> (i32, i32) {
+    let at = (len as f32 * ratio).round() as i32;
+    let (before, after) = (SPLITTER / 2, SPLITTER - SPLITTER / 2);
+    match (first, second) {
+        (true, false) => (start, at - before),
+        (false, true) => (start + at + after, len - at - after),
+        _ => (start, len),
+    }
+}
+
+/// 标签所在的区域，未停靠时为 Full
+pub fn zone_of(tab: isize) -> Zone {
+    DOCKED.lock().unwrap().iter().find(|(h, _)| *h == tab).map_or(Zone::Full, |(_, z)| *z)
+}
+
+/// 停靠的标签
+pub fn docked() -> Vec<isize> {
+    DOCKED.lock().unwrap().iter().map(|(h, _)| *h).collect()
+}
+
+/// 临时最大化的标签
+pub fn maximized() -> Option<isize> {
+    Some(MAXIMIZED.load(Ordering::SeqCst)).filter(|&h| h != 0)
+}
+
+/// 切换标签的临时最大化，返回切换后是否最大化 (同时只有一个标签最大化)
+pub fn toggle_maximized(tab: isize) -> bool {
+    let previous = MAXIMIZED.swap(tab, Ordering::SeqCst);
+    if previous == tab {
+        MAXIMIZED.store(0, Ordering::SeqCst);
+        return false;
+    }
+    true
+}
+
+/// 切换到标签 tab 时结束其他标签的最大化，返回被恢复的标签
+pub fn end_maximize_except(tab: isize) -> Option<isize> {
+    let previous = maximized().filter(|&h| h != tab)?;
+    MAXIMIZED.compare_exchange(previous, 0, Ordering::SeqCst, Ordering::SeqCst).ok()
+}
+
+/// 标签 tab 显示时应同时显示的标签 (tabs 为所有标签)
+pub fn shown_with(tabs: &[isize], tab: isize) -> Vec<isize> {
+    if zone_of(tab) == Zone::Full || maximized() == Some(tab) {
+        return vec![tab];
+    }
+    let docked = docked();
+    tabs.iter().copied().filter(|h| *h == tab || docked.contains(h)).collect()
+}
+
+/// 把标签停靠到 zone (Full 为取消停靠)，返回区域变化的标签 (包括被挤出的标签)
+pub fn dock(tab: isize, zone: Zone) -> Vec<TabZone> {
+    let mut docked = DOCKED.lock().unwrap();
+    docked.retain(|(h, _)| *h != tab);
+    let mut changed = vec![TabZone { hwnd: tab, zone }];
+    if zone == Zone::Full {
+        return changed;
+    }
+    docked.retain(|&(hwnd, other)| {
+        let overlaps = other.cells() & zone.cells() != 0;
+        if overlaps {
+            changed.push(TabZone { hwnd, zone: Zone::Full });
+        }
+        !overlaps
+    });
+    docked.push((tab, zone));
+    changed
+}
+
+/// 标签已释放或关闭
+pub fn forget(tab: isize) {
+    DOCKED.lock().unwrap().retain(|(h, _)| *h != tab);
+    let _ = MAXIMIZED.compare_exchange(tab, 0, Ordering::SeqCst, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zones_follow_cursor_and_split_the_content_area() {
+        let area = (0, 80, 1000, 800);
+        let even = SplitRatios::default();
+        assert_eq!(hint_at(area, even, 500, 70), None);
+        assert_eq!(hint_at(area, even, 100, 480).unwrap().zone, Zone::Left);
+        assert_eq!(hint_at(area, even, 900, 850).unwrap().zone, Zone::BottomRight);
+        assert_eq!(hint_at(area, even, 500, 480).unwrap().zone, Zone::Full);
+
+        // 两侧之间留出分隔条的空隙
+        assert_eq!(rect(Zone::Left, area, even), (0, 80, 497, 800));
+        assert_eq!(rect(Zone::Right, area, even), (503, 80, 497, 800));
+        assert_eq!(rect(Zone::BottomLeft, area, even), (0, 483, 497, 397));
+        assert_eq!(rect(Zone::Full, area, even), area);
+
+        // 70/30 分隔
+        let editor = SplitRatios { columns: 0.7, rows: 0.5 };
+        assert_eq!(rect(Zone::Left, area, editor), (0, 80, 697, 800));
+        assert_eq!(rect(Zone::Right, area, editor), (703, 80, 297, 800));
+    }
+
+    #[test]
+    fn docking_pushes_out_overlapping_tabs() {
+        let (left, top_right, bottom_right, right) = (0x51, 0x52, 0x53, 0x54);
+        dock(left, Zone::Left);
+        dock(top_right, Zone::TopRight);
+        dock(bottom_right, Zone::BottomRight);
+        assert_eq!(shown_with(&[left, top_right, bottom_right, right], left), vec![left, top_right, bottom_right]);
+        assert_eq!(shown_with(&[left, right], right), vec![right]);
+
+        let changed = dock(right, Zone::Right);
+        assert_eq!(changed.len(), 3);
+        assert_eq!(zone_of(top_right), Zone::Full);
+        assert_eq!(zone_of(bottom_right), Zone::Full);
+        assert_eq!(zone_of(right), Zone::Right);
+
+        for tab in [left, top_right, bottom_right, right] {
+            forget(tab);
+        }
+    }
+
+    #[test]
+    fn maximizing_hides_docked_siblings_until_restored() {
+        let (left, right) = (0x61, 0x62);
+        dock(left, Zone::Left);
+        dock(right, Zone::Right);
+
+        assert!(toggle_maximized(left));
+        assert_eq!(shown_with(&[left, right], left), vec![left]);
+        // 停靠的区域保持不变
+        assert_eq!(zone_of(left), Zone::Left);
+
+        // 切换到其他标签时恢复
+        assert_eq!(end_maximize_except(left), None);
+        assert_eq!(end_maximize_except(right), Some(left));
+        assert_eq!(shown_with(&[left, right], left), vec![left, right]);
+
+        assert!(toggle_maximized(right));
+        assert!(!toggle_maximized(right));
+        assert_eq!(maximized(), None);
+
+        forget(left);
+        forget(right);
+    }
+}
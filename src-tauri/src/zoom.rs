@@ -0,0 +1,338 @@
+// 标签缩放 (Magnification API)
+// 部分老应用不支持高 DPI，在 4K 屏上界面很小。缩放后：
+// - 嵌入窗口按 1/scale 的尺寸布局在内容区左上角
+// - 在其上方覆盖一个放大镜窗口 (WC_MAGNIFIER)，把这块区域放大到整个内容区
+// - 覆盖窗口收到的鼠标消息按比例换算后转发给嵌入窗口 (键盘输入仍直接进入嵌入窗口)
+// 覆盖窗口必须在有消息循环的线程创建，所以统一通过 run_on_main_thread 操作
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+#[cfg(windows)]
+use std::sync::atomic::AtomicBool;
+
+#[cfg(windows)]
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM},
+        Graphics::Gdi::{InvalidateRect, ScreenToClient},
+        System::LibraryLoader::GetModuleHandleW,
+        UI::Input::KeyboardAndMouse::{ReleaseCapture, SetCapture},
+        UI::Magnification::*,
+        UI::Shell::{DefSubclassProc, SetWindowSubclass},
+        UI::WindowsAndMessaging::*,
+    },
+};
+
+pub const MIN_SCALE: f32 = 1.0;
+pub const MAX_SCALE: f32 = 3.0;
+
+struct ZoomedTab {
+    tab: isize,
+    scale: f32,
+    overlay: isize, // 覆盖窗口，创建前为 0
+    mag: isize,     // 放大镜控件
+}
+
+static ZOOMED: Mutex<Vec<ZoomedTab>> = Mutex::new(Vec::new());
+
+// 当前激活的标签，只有它的覆盖窗口可见
+static ACTIVE_TAB: AtomicIsize = AtomicIsize::new(0);
+
+#[cfg(windows)]
+static MAG_INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+// 刷新间隔 (毫秒)，放大镜控件不会自动跟随源区域内容更新
+#[cfg(windows)]
+const REFRESH_INTERVAL_MS: u32 = 33;
+#[cfg(windows)]
+const REFRESH_TIMER_ID: usize = 1;
+
+/// 标签的缩放比例，未缩放为 1.0
+pub fn scale_of(tab: isize) -> f32 {
+    ZOOMED.lock().unwrap().iter().find(|z| z.tab == tab).map(|z| z.scale).unwrap_or(1.0)
+}
+
+/// 记录当前激活的标签 (activate_window 时调用)
+pub fn set_active(tab: isize) {
+    ACTIVE_TAB.store(tab, Ordering::SeqCst);
+}
+
+/// 设置标签缩放比例，1.0 表示取消缩放
+pub fn set_scale(app: &AppHandle, tab: isize, scale: f32) -> Result<(), String> {
+    if !(MIN_SCALE..=MAX_SCALE).contains(&scale) {
+        return Err(format!("缩放比例超出范围: {} (允许 {}~{})", scale, MIN_SCALE, MAX_SCALE));
+    }
+
+    if (scale - 1.0).abs() < f32::EPSILON {
+        clear(app, tab);
+        return Ok(());
+    }
+
+    {
+        let mut zoomed = ZOOMED.lock().unwrap();
+        match zoomed.iter_mut().find(|z| z.tab == tab) {
+            Some(z) => z.scale = scale,
+            None => zoomed.push(ZoomedTab { tab, scale, overlay: 0, mag: 0 }),
+        }
+    }
+    info!(target: "zoom", "标签缩放: hwnd={}, scale={}", tab, scale);
+
+    #[cfg(windows)]
+    app.run_on_main_thread(move || unsafe { apply_overlay(tab) }).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 取消标签缩放并销毁覆盖窗口 (释放/关闭标签时调用)
+pub fn clear(app: &AppHandle, tab: isize) {
+    let removed = {
+        let mut zoomed = ZOOMED.lock().unwrap();
+        zoomed.iter().position(|z| z.tab == tab).map(|i| zoomed.remove(i))
+    };
+    let Some(z) = removed else { return };
+    info!(target: "zoom", "取消缩放: hwnd={}", tab);
+
+    #[cfg(windows)]
+    if z.overlay != 0 {
+        let overlay = z.overlay;
+        // 覆盖窗口在主线程上异步销毁，先注销它的定时器，释放标签时不算遗留
+        crate::resources::unregister_window(overlay);
+        let _ = app.run_on_main_thread(move || unsafe {
+            let _ = DestroyWindow(HWND(overlay as *mut _));
+        });
+    }
+    #[cfg(not(windows))]
+    let _ = (app, z.overlay, z.mag);
+}
+
+// 创建覆盖窗口 (已存在则只更新缩放矩阵)
+#[cfg(windows)]
+unsafe fn apply_overlay(tab: isize) {
+    let Some((scale, overlay, mag)) = ZOOMED.lock().unwrap().iter().find(|z| z.tab == tab).map(|z| (z.scale, z.overlay, z.mag)) else {
+        return;
+    };
+
+    if overlay != 0 {
+        set_transform(HWND(mag as *mut _), scale);
+        return;
+    }
+
+    if !MAG_INITIALIZED.swap(true, Ordering::SeqCst) && !MagInitialize().as_bool() {
+        MAG_INITIALIZED.store(false, Ordering::SeqCst);
+        warn!(target: "zoom", "MagInitialize 失败");
+        return;
+    }
+
+    let tab_hwnd = HWND(tab as *mut _);
+    let Ok(host) = GetParent(tab_hwnd) else { return };
+
+    let instance = GetModuleHandleW(None).unwrap_or_default();
+    let class_name = w!("WindowHubZoomOverlay");
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(overlay_wnd_proc),
+        hInstance: instance.into(),
+        hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+
+    // 所有者设为主窗口，保证覆盖窗口总在主窗口之上；不抢焦点、不进任务栏
+    let overlay = match CreateWindowExW(
+        WS_EX_LAYERED | WS_EX_NOACTIVATE | WS_EX_TOOLWINDOW,
+        class_name,
+        w!(""),
+        WS_POPUP | WS_CLIPCHILDREN,
+        0, 0, 0, 0,
+        host,
+        None,
+        instance,
+        None,
+    ) {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+            warn!(target: "zoom", "创建覆盖窗口失败: {:?}", e);
+            return;
+        }
+    };
+    let _ = SetLayeredWindowAttributes(overlay, windows::Win32::Foundation::COLORREF(0), 255, LWA_ALPHA);
+
+    let mag = match CreateWindowExW(WINDOW_EX_STYLE(0), WC_MAGNIFIERW, w!(""), WS_CHILD | WS_VISIBLE, 0, 0, 0, 0, overlay, None, instance, None) {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+            warn!(target: "zoom", "创建放大镜控件失败: {:?}", e);
+            let _ = DestroyWindow(overlay);
+            return;
+        }
+    };
+    // 放大镜控件不处理鼠标，交给覆盖窗口转发
+    let _ = SetWindowSubclass(mag, Some(mag_subclass_proc), 0, 0);
+    set_transform(mag, scale);
+
+    // 放大源区域时排除覆盖窗口自身，否则会递归放大
+    let mut excluded = [overlay];
+    let _ = MagSetWindowFilterList(mag, MW_FILTERMODE_EXCLUDE, 1, excluded.as_mut_ptr());
+
+    {
+        let mut zoomed = ZOOMED.lock().unwrap();
+        match zoomed.iter_mut().find(|z| z.tab == tab) {
+            Some(z) => {
+                z.overlay = overlay.0 as isize;
+                z.mag = mag.0 as isize;
+            }
+            None => {
+                // 创建期间已取消缩放
+                drop(zoomed);
+                let _ = DestroyWindow(overlay);
+                return;
+            }
+        }
+    }
+
+    SetTimer(overlay, REFRESH_TIMER_ID, REFRESH_INTERVAL_MS, None);
+    crate::resources::register(crate::resources::ResourceKind::Timer, "zoom", 1, Some(tab), Some(overlay.0 as isize));
+}
+
+#[cfg(windows)]
+unsafe fn set_transform(mag: HWND, scale: f32) {
+    let mut matrix = MAGTRANSFORM { v: [scale, 0.0, 0.0, 0.0, scale, 0.0, 0.0, 0.0, 1.0] };
+    let _ = MagSetWindowTransform(mag, &mut matrix);
+}
+
+#[cfg(windows)]
+fn find_by_overlay(overlay: HWND) -> Option<(isize, f32, isize)> {
+    let raw = overlay.0 as isize;
+    ZOOMED.lock().unwrap().iter().find(|z| z.overlay == raw).map(|z| (z.tab, z.scale, z.mag))
+}
+
+// 定时同步覆盖窗口的位置、可见性和放大内容
+#[cfg(windows)]
+unsafe fn refresh_overlay(overlay: HWND) {
+    let Some((tab, scale, mag)) = find_by_overlay(overlay) else {
+        let _ = DestroyWindow(overlay);
+        return;
+    };
+    let tab_hwnd = HWND(tab as *mut _);
+    let mag = HWND(mag as *mut _);
+
+    if !IsWindow(tab_hwnd).as_bool() {
+        ZOOMED.lock().unwrap().retain(|z| z.tab != tab);
+        let _ = DestroyWindow(overlay);
+        return;
+    }
+
+    // 主窗口隐藏/最小化、标签被隐藏 (搜索面板) 或不是当前标签时隐藏覆盖窗口
+    let host = GetParent(tab_hwnd).unwrap_or_default();
+    let visible = !host.is_invalid()
+        && IsWindowVisible(host).as_bool()
+        && !IsIconic(host).as_bool()
+        && IsWindowVisible(tab_hwnd).as_bool()
+        && ACTIVE_TAB.load(Ordering::SeqCst) == tab;
+    if !visible {
+        if IsWindowVisible(overlay).as_bool() {
+            let _ = ShowWindow(overlay, SW_HIDE);
+        }
+        return;
+    }
+
+    let mut source = RECT::default();
+    if GetWindowRect(tab_hwnd, &mut source).is_err() {
+        return;
+    }
+    let width = ((source.right - source.left) as f32 * scale).round() as i32;
+    let height = ((source.bottom - source.top) as f32 * scale).round() as i32;
+
+    let _ = SetWindowPos(overlay, HWND::default(), source.left, source.top, width, height, SWP_NOZORDER | SWP_NOACTIVATE | SWP_SHOWWINDOW);
+    let _ = SetWindowPos(mag, HWND::default(), 0, 0, width, height, SWP_NOZORDER | SWP_NOACTIVATE);
+    let _ = MagSetWindowSource(mag, source);
+    let _ = InvalidateRect(mag, None, false);
+}
+
+// 把覆盖窗口上的鼠标消息换算到嵌入窗口中对应的子控件
+#[cfg(windows)]
+unsafe fn forward_mouse(overlay: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) {
+    let Some((tab, scale, _)) = find_by_overlay(overlay) else { return };
+    let tab_hwnd = HWND(tab as *mut _);
+
+    let mut source = RECT::default();
+    if GetWindowRect(tab_hwnd, &mut source).is_err() {
+        return;
+    }
+
+    // 滚轮消息的坐标是屏幕坐标，其余为覆盖窗口客户区坐标
+    let is_wheel = msg == WM_MOUSEWHEEL || msg == WM_MOUSEHWHEEL;
+    let (mut x, mut y) = ((lparam.0 & 0xFFFF) as i16 as i32, ((lparam.0 >> 16) & 0xFFFF) as i16 as i32);
+    if is_wheel {
+        let mut overlay_rect = RECT::default();
+        let _ = GetWindowRect(overlay, &mut overlay_rect);
+        x -= overlay_rect.left;
+        y -= overlay_rect.top;
+    }
+    let screen_pt = POINT {
+        x: source.left + (x as f32 / scale) as i32,
+        y: source.top + (y as f32 / scale) as i32,
+    };
+
+    // 逐层找到落点下最深的子控件
+    let mut target = tab_hwnd;
+    loop {
+        let mut pt = screen_pt;
+        let _ = ScreenToClient(target, &mut pt);
+        let child = ChildWindowFromPointEx(target, pt, CWP_SKIPINVISIBLE | CWP_SKIPTRANSPARENT);
+        if child.is_invalid() || child == target {
+            break;
+        }
+        target = child;
+    }
+
+    let forwarded_lparam = if is_wheel {
+        LPARAM(((screen_pt.y as u16 as isize) << 16) | screen_pt.x as u16 as isize)
+    } else {
+        let mut pt = screen_pt;
+        let _ = ScreenToClient(target, &mut pt);
+        LPARAM(((pt.y as u16 as isize) << 16) | pt.x as u16 as isize)
+    };
+
+    if msg == WM_LBUTTONDOWN || msg == WM_RBUTTONDOWN || msg == WM_MBUTTONDOWN {
+        // 拖动时鼠标可能移出覆盖窗口，捕获到松开为止；同时把键盘焦点交给嵌入窗口
+        SetCapture(overlay);
+        let _ = crate::activate_window(tab, None);
+    } else if msg == WM_LBUTTONUP || msg == WM_RBUTTONUP || msg == WM_MBUTTONUP {
+        let _ = ReleaseCapture();
+    }
+
+    let _ = PostMessageW(target, msg, wparam, forwarded_lparam);
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn overlay_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_TIMER if wparam.0 == REFRESH_TIMER_ID => {
+            refresh_overlay(hwnd);
+            LRESULT(0)
+        }
+        WM_MOUSEACTIVATE => LRESULT(MA_NOACTIVATE as isize),
+        WM_MOUSEMOVE..=WM_MBUTTONDBLCLK | WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
+            forward_mouse(hwnd, msg, wparam, lparam);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            let _ = KillTimer(hwnd, REFRESH_TIMER_ID);
+            crate::resources::unregister_window(hwnd.0 as isize);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+// 放大镜控件对鼠标透明，消息落到父级覆盖窗口
+#[cfg(windows)]
+unsafe extern "system" fn mag_subclass_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM, _id: usize, _data: usize) -> LRESULT {
+    if msg == WM_NCHITTEST {
+        return LRESULT(HTTRANSPARENT as isize);
+    }
+    DefSubclassProc(hwnd, msg, wparam, lparam)
+}